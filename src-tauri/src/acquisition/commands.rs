@@ -0,0 +1,1543 @@
+//! 数据采集模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的数据采集相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `acquisition_start` | 启动网关采集 |
+//! | `acquisition_stop` | 停止网关采集 |
+//! | `acquisition_status` | 查询采集状态（读取计划与周期统计） |
+//! | `acquisition_values` | 查询最新点位值 |
+//! | `acquisition_subscribe` | 订阅值变化事件 |
+//! | `acquisition_unsubscribe` | 取消订阅 |
+//! | `acquisition_subscription_list` | 查询操作员的订阅 |
+//! | `acquisition_snapshot` | 查询最新值快照 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入数据采集数据模型
+use crate::acquisition::models::{
+    AcquisitionGatewayPayload, AcquisitionSnapshotPayload, AcquisitionStatusData,
+    AcquisitionStatusPayload, AcquisitionSubscribePayload, AcquisitionSubscriptionData,
+    AcquisitionSubscriptionListPayload, AcquisitionUnsubscribePayload, AcquisitionValueData,
+    AcquisitionValueEvent, AcquisitionValuesPayload,
+};
+// 引入数据采集服务层
+use crate::acquisition::services;
+
+/// 启动网关采集
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+///
+/// # 返回
+/// * 采集状态
+#[tauri::command]
+pub fn acquisition_start(
+    payload: AcquisitionGatewayPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AcquisitionStatusData> {
+    execute_traced_command("acquisition_start", trace, || {
+        Ok(ApiResponse::ok(services::start(&payload, now_millis())?))
+    })
+}
+
+/// 停止网关采集
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+///
+/// # 返回
+/// * 停止时的采集状态
+#[tauri::command]
+pub fn acquisition_stop(
+    payload: AcquisitionGatewayPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AcquisitionStatusData> {
+    execute_traced_command("acquisition_stop", trace, || {
+        Ok(ApiResponse::ok(services::stop(&payload, now_millis())?))
+    })
+}
+
+/// 查询采集状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的网关 ID
+///
+/// # 返回
+/// * 读取计划、周期数、超时周期、启动抖动与周期耗时
+#[tauri::command]
+pub fn acquisition_status(
+    payload: AcquisitionStatusPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<AcquisitionStatusData>> {
+    execute_traced_command("acquisition_status", trace, || {
+        Ok(ApiResponse::ok(services::status(&payload, now_millis())?))
+    })
+}
+
+/// 查询最新点位值
+///
+/// # 参数
+/// * `payload` - 操作员用户名、网关 ID 与可选的从站 ID
+///
+/// # 返回
+/// * 最近一次采集的点位值与错误
+#[tauri::command]
+pub fn acquisition_values(
+    payload: AcquisitionValuesPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<AcquisitionValueData>> {
+    execute_traced_command("acquisition_values", trace, || {
+        Ok(ApiResponse::ok(services::values(&payload, now_millis())?))
+    })
+}
+
+/// 订阅值变化事件
+///
+/// # 参数
+/// * `payload` - 操作员用户名、页面标识、过滤条件与节流参数
+///
+/// # 返回
+/// * 订阅信息（含监听的事件名称）
+#[tauri::command]
+pub fn acquisition_subscribe(
+    payload: AcquisitionSubscribePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AcquisitionSubscriptionData> {
+    execute_traced_command("acquisition_subscribe", trace, || {
+        Ok(ApiResponse::ok(services::subscribe(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 取消订阅
+///
+/// # 参数
+/// * `payload` - 操作员用户名与订阅 ID（或页面标识）
+///
+/// # 返回
+/// * 是否取消成功
+#[tauri::command]
+pub fn acquisition_unsubscribe(
+    payload: AcquisitionUnsubscribePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("acquisition_unsubscribe", trace, || {
+        Ok(ApiResponse::ok(services::unsubscribe(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询操作员的订阅
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+///
+/// # 返回
+/// * 订阅信息与发送、合并、丢弃计数
+#[tauri::command]
+pub fn acquisition_subscription_list(
+    payload: AcquisitionSubscriptionListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<AcquisitionSubscriptionData>> {
+    execute_traced_command("acquisition_subscription_list", trace, || {
+        Ok(ApiResponse::ok(services::subscriptions(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询最新值快照
+///
+/// # 参数
+/// * `payload` - 操作员用户名与订阅 ID（或过滤条件）
+///
+/// # 返回
+/// * 按网关与从站汇总的当前值
+#[tauri::command]
+pub fn acquisition_snapshot(
+    payload: AcquisitionSnapshotPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<AcquisitionValueEvent>> {
+    execute_traced_command("acquisition_snapshot", trace, || {
+        Ok(ApiResponse::ok(services::snapshot(&payload, now_millis())?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, Once};
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::acquisition::models::AcquisitionFilterSpec;
+    use crate::acquisition::telemetry;
+    use crate::auth::admin_commands::user_device_scope_upsert;
+    use crate::auth::models::UserDeviceScopeUpsertPayload;
+    use crate::core::error::AppError;
+    use crate::db;
+    use crate::db::test_support::{ensure_test_db_ready, register_operator, unique_code};
+    use crate::device::commands::device_create;
+    use crate::device::models::DeviceCreatePayload;
+    use crate::device_lifecycle::commands::device_lifecycle_transition;
+    use crate::device_lifecycle::models::DeviceLifecycleTransitionPayload;
+    use crate::device_template::commands::device_template_create;
+    use crate::device_template::models::{
+        DeviceTemplateCreatePayload, DeviceTemplateSpec, TemplatePointSpec,
+    };
+    use crate::gateway::commands::{
+        gateway_create, gateway_point_create, gateway_point_delete, gateway_slave_create,
+    };
+    use crate::gateway::models::{
+        GatewayCreatePayload, GatewayData, GatewayPointCreatePayload, GatewayPointDeletePayload,
+        GatewayPointSpec, GatewaySlaveCreatePayload, GatewaySlaveData, GatewaySlaveSpec,
+        GatewaySpec,
+    };
+    use crate::modbus::commands::{
+        modbus_simulator_set_faults, modbus_simulator_start, modbus_simulator_stop,
+    };
+    use crate::modbus::models::{
+        ModbusSimulatorFaultSpec, ModbusSimulatorFaultsPayload, ModbusSimulatorGeneratorSpec,
+        ModbusSimulatorPayload, ModbusSimulatorStartPayload,
+    };
+    use crate::modbus::simulator::{SlaveMemory, TcpSimulator};
+    use serde_json::{Value, json};
+
+    fn create_gateway(spec: GatewaySpec) -> AppResult<GatewayData> {
+        gateway_create(
+            GatewayCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway: spec,
+            },
+            None,
+        )
+    }
+
+    fn create_point(
+        slave_id: i64,
+        point_key: &str,
+        function_code: u8,
+        address: u16,
+        data_type: &str,
+    ) -> i64 {
+        gateway_point_create(
+            GatewayPointCreatePayload {
+                operator_username: "admin".to_string(),
+                slave_id,
+                point: GatewayPointSpec {
+                    point_key: point_key.to_string(),
+                    name: point_key.to_string(),
+                    function_code,
+                    address,
+                    data_type: data_type.to_string(),
+                    ..GatewayPointSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create point")
+        .data
+        .id
+    }
+
+    fn create_slave(gateway_id: i64, unit_id: u8) -> GatewaySlaveData {
+        gateway_slave_create(
+            GatewaySlaveCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway_id,
+                slave: GatewaySlaveSpec {
+                    unit_id,
+                    name: format!("从站 {unit_id}"),
+                    ..GatewaySlaveSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create slave")
+        .data
+    }
+
+    fn create_virtual(slave_id: i64, point_key: &str, data_type: &str, expression: &str) -> i64 {
+        gateway_point_create(
+            GatewayPointCreatePayload {
+                operator_username: "admin".to_string(),
+                slave_id,
+                point: GatewayPointSpec {
+                    point_key: point_key.to_string(),
+                    name: point_key.to_string(),
+                    data_type: data_type.to_string(),
+                    expression: Some(expression.to_string()),
+                    ..GatewayPointSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create virtual point")
+        .data
+        .id
+    }
+
+    fn create_device(comm_config_ref: Option<String>) -> String {
+        device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: unique_code("dev_acq"),
+                device_name: "水泵".to_string(),
+                device_type: "pump".to_string(),
+                comm_config_ref,
+                ..DeviceCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create device")
+        .data
+        .device_id
+    }
+
+    fn transition(device_id: &str, to_state: &str) {
+        device_lifecycle_transition(
+            DeviceLifecycleTransitionPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.to_string(),
+                to_state: to_state.to_string(),
+                reason: "采集测试".to_string(),
+            },
+            None,
+        )
+        .expect("transition device");
+    }
+
+    // 新建设备为规划状态，经安装、调试后参与采集
+    fn commission(device_id: &str) {
+        transition(device_id, "installed");
+        transition(device_id, "commissioned");
+    }
+
+    fn gateway_payload(operator_username: &str, gateway_id: i64) -> AcquisitionGatewayPayload {
+        AcquisitionGatewayPayload {
+            operator_username: operator_username.to_string(),
+            gateway_id,
+        }
+    }
+
+    fn status(gateway_id: i64) -> AcquisitionStatusData {
+        acquisition_status(
+            AcquisitionStatusPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: Some(gateway_id),
+            },
+            None,
+        )
+        .expect("acquisition status")
+        .data
+        .pop()
+        .expect("gateway status")
+    }
+
+    fn values(gateway_id: i64) -> AppResult<Vec<AcquisitionValueData>> {
+        acquisition_values(
+            AcquisitionValuesPayload {
+                operator_username: "admin".to_string(),
+                gateway_id,
+                slave_id: None,
+            },
+            None,
+        )
+    }
+
+    // 点位标识 → 最新值
+    fn value_of(gateway_id: i64, point_key: &str) -> Option<Value> {
+        values(gateway_id)
+            .expect("acquisition values")
+            .data
+            .into_iter()
+            .find(|value| value.point_key == point_key)
+            .map(|value| value.value)
+    }
+
+    // 轮询等待条件成立（最多 5 秒）
+    fn wait_for(description: &str, mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for {description}"
+            );
+            sleep(Duration::from_millis(50));
+        }
+    }
+
+    // 模拟从站上的采集网关（轮询周期 100 毫秒、合并间隙 2）与一号电表从站（轮询周期 150 毫秒）
+    fn polled_gateway() -> (TcpSimulator, GatewayData, i64, i64) {
+        let mut memory = SlaveMemory::new(20);
+        memory.holding_registers[..3].copy_from_slice(&[100, 200, 300]);
+        memory.holding_registers[5..7].copy_from_slice(&[0x0001, 0x0002]);
+        memory.input_registers[0] = 0xFFFF;
+        memory.coils[0] = true;
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let gateway = create_gateway(polled_spec(simulator.local_addr()))
+            .expect("create gateway")
+            .data;
+        let slave = gateway_slave_create(
+            GatewaySlaveCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway.id,
+                slave: GatewaySlaveSpec {
+                    unit_id: 1,
+                    name: "一号电表".to_string(),
+                    poll_interval_ms: Some(150),
+                    ..GatewaySlaveSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create slave")
+        .data;
+        assert_eq!(slave.poll_interval_ms, Some(150));
+        create_point(slave.id, "ua", 3, 0, "u16");
+        let ub = create_point(slave.id, "ub", 3, 1, "u16");
+        create_point(slave.id, "uc", 3, 2, "u16");
+        create_point(slave.id, "energy", 3, 5, "u32");
+        create_point(slave.id, "relay", 1, 0, "bool");
+        (simulator, gateway, slave.id, ub)
+    }
+
+    fn polled_spec(address: std::net::SocketAddr) -> GatewaySpec {
+        GatewaySpec {
+            code: unique_code("gw_acq"),
+            name: "采集网关".to_string(),
+            host: address.ip().to_string(),
+            port: Some(address.port()),
+            poll_interval_ms: Some(100),
+            poll_max_gap: Some(2),
+            ..GatewaySpec::default()
+        }
+    }
+
+    // 启动采集并等待从站完成两次周期
+    fn start_and_wait(gateway_id: i64) -> AcquisitionStatusData {
+        let started = acquisition_start(gateway_payload("admin", gateway_id), None)
+            .expect("start acquisition")
+            .data;
+        assert!(started.running);
+        wait_for("two polling cycles", || {
+            status(gateway_id)
+                .slaves
+                .first()
+                .is_some_and(|slave| slave.cycles >= 2)
+        });
+        started
+    }
+
+    #[test]
+    fn acquisition_start_validates_gateways_and_permissions() {
+        ensure_test_db_ready();
+        let spec = polled_spec("127.0.0.1:502".parse().expect("address"));
+
+        // 轮询周期与合并间隙的范围校验
+        for (invalid, message) in [
+            (
+                GatewaySpec {
+                    poll_interval_ms: Some(50),
+                    ..spec.clone()
+                },
+                "pollIntervalMs must be between 100 and 3600000",
+            ),
+            (
+                GatewaySpec {
+                    poll_max_gap: Some(126),
+                    ..spec.clone()
+                },
+                "pollMaxGap must be between 0 and 125",
+            ),
+        ] {
+            assert_eq!(
+                create_gateway(invalid).expect_err("invalid polling"),
+                AppError::Validation(message.to_string())
+            );
+        }
+        let gateway = create_gateway(spec.clone()).expect("create gateway").data;
+        assert_eq!((gateway.poll_interval_ms, gateway.poll_max_gap), (100, 2));
+        let disabled = create_gateway(GatewaySpec {
+            code: unique_code("gw_acq_off"),
+            enabled: Some(false),
+            ..spec
+        })
+        .expect("create disabled gateway")
+        .data;
+
+        // 权限、网关状态与未启动采集
+        assert_eq!(
+            acquisition_start(gateway_payload("common", gateway.id), None)
+                .expect_err("common user cannot start"),
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+        assert_eq!(
+            acquisition_start(gateway_payload("admin", disabled.id), None)
+                .expect_err("disabled gateway"),
+            AppError::Validation("gateway is disabled".to_string())
+        );
+        assert_eq!(
+            acquisition_start(gateway_payload("admin", i64::MAX), None)
+                .expect_err("missing gateway"),
+            AppError::Validation("gateway not found".to_string())
+        );
+        assert_eq!(
+            values(gateway.id).expect_err("not running"),
+            AppError::Validation("acquisition is not running".to_string())
+        );
+        assert_eq!(
+            acquisition_status(
+                AcquisitionStatusPayload {
+                    operator_username: "common".to_string(),
+                    gateway_id: None,
+                },
+                None,
+            )
+            .expect_err("common user cannot view"),
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+
+        // 重复启动返回当前状态；重复停止返回未在采集
+        let started = acquisition_start(gateway_payload("admin", gateway.id), None)
+            .expect("start acquisition")
+            .data;
+        let again = acquisition_start(gateway_payload("admin", gateway.id), None)
+            .expect("start again")
+            .data;
+        assert!(again.running && again.started_at == started.started_at);
+        let stopped = acquisition_stop(gateway_payload("admin", gateway.id), None)
+            .expect("stop acquisition")
+            .data;
+        assert!(!stopped.running);
+        assert_eq!(
+            acquisition_stop(gateway_payload("admin", gateway.id), None)
+                .expect_err("already stopped"),
+            AppError::Validation("acquisition is not running".to_string())
+        );
+    }
+
+    #[test]
+    fn acquisition_polls_coalesced_blocks() {
+        ensure_test_db_ready();
+        let (_simulator, gateway, _slave_id, _ub) = polled_gateway();
+        start_and_wait(gateway.id);
+
+        // 地址 0–2 与 5–6 之间空闲 2 个地址，合并为一次 FC03 读取；线圈单独读取
+        let current = status(gateway.id);
+        assert_eq!(current.gateway_code, gateway.code);
+        assert_eq!((current.max_gap, current.plan_loads), (2, 1));
+        let polled = &current.slaves[0];
+        assert_eq!((polled.unit_id, polled.poll_interval_ms), (1, 150));
+        let blocks: Vec<(u8, u16, u16, u16, Vec<String>)> = polled
+            .blocks
+            .iter()
+            .map(|block| {
+                (
+                    block.function_code,
+                    block.address,
+                    block.count,
+                    block.used_count,
+                    block.point_keys.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (1, 0, 1, 1, vec!["relay".to_string()]),
+                (
+                    3,
+                    0,
+                    7,
+                    5,
+                    ["ua", "ub", "uc", "energy"].map(String::from).to_vec()
+                ),
+            ]
+        );
+        assert!(polled.blocks.iter().all(|block| block.requests >= 2));
+        assert!(polled.blocks.iter().all(|block| block.failures == 0));
+        assert!(polled.last_cycle_at.is_some() && polled.last_error.is_none());
+        assert!(polled.max_jitter_ms >= polled.last_jitter_ms);
+        assert!(polled.max_duration_ms >= polled.last_duration_ms);
+        assert_eq!(value_of(gateway.id, "ua"), Some(json!(100)));
+        assert_eq!(value_of(gateway.id, "uc"), Some(json!(300)));
+        assert_eq!(value_of(gateway.id, "energy"), Some(json!(0x0001_0002)));
+        assert_eq!(value_of(gateway.id, "relay"), Some(json!(true)));
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+
+    #[test]
+    fn acquisition_rebuilds_plans_after_point_changes() {
+        ensure_test_db_ready();
+        let (simulator, gateway, slave_id, ub) = polled_gateway();
+        start_and_wait(gateway.id);
+
+        // 点位表变更后自动重新生成读取计划
+        create_point(slave_id, "temperature", 4, 0, "i16");
+        gateway_point_delete(
+            GatewayPointDeletePayload {
+                operator_username: "admin".to_string(),
+                point_id: ub,
+            },
+            None,
+        )
+        .expect("delete point");
+        wait_for("rebuilt plan values", || {
+            value_of(gateway.id, "temperature") == Some(json!(-1))
+                && value_of(gateway.id, "ub").is_none()
+        });
+        let current = status(gateway.id);
+        assert!(current.plan_loads >= 2);
+        assert_eq!(current.slaves[0].blocks.len(), 3);
+        assert!(current.slaves[0].cycles >= 2, "slave counters are kept");
+        simulator.memory().lock().expect("memory").holding_registers[0] = 111;
+        wait_for("updated register value", || {
+            value_of(gateway.id, "ua") == Some(json!(111))
+        });
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+
+    #[test]
+    fn acquisition_keeps_last_values_when_the_slave_goes_offline() {
+        ensure_test_db_ready();
+        let (simulator, gateway, _slave_id, _ub) = polled_gateway();
+        start_and_wait(gateway.id);
+        simulator.memory().lock().expect("memory").holding_registers[0] = 111;
+        wait_for("updated register value", || {
+            value_of(gateway.id, "ua") == Some(json!(111))
+        });
+
+        // 从站离线：读取失败记录在读取块与点位上，保留上一次的值
+        simulator.stop();
+        wait_for("read failures", || {
+            status(gateway.id).slaves[0]
+                .blocks
+                .iter()
+                .all(|block| block.failures > 0)
+        });
+        let offline = values(gateway.id).expect("values").data;
+        let ua = offline
+            .iter()
+            .find(|value| value.point_key == "ua")
+            .expect("ua value");
+        assert_eq!(ua.value, json!(111));
+        assert!(ua.error.is_some() && ua.updated_at.is_some());
+        assert!(status(gateway.id).slaves[0].last_error.is_some());
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+
+    #[test]
+    fn acquisition_skips_slaves_whose_devices_are_not_acquired() {
+        ensure_test_db_ready();
+        let (_simulator, gateway, _slave_id, _ub) = polled_gateway();
+        let pump = create_slave(gateway.id, 2);
+        create_point(pump.id, "speed", 3, 2, "u16");
+        let meter_device = create_device(Some(format!("modbus:{}/1", gateway.code)));
+        commission(&meter_device);
+        let pump_device = create_device(Some(format!("modbus:{}/2", gateway.code)));
+
+        // 规划中的设备不采集，未关联或已调试设备的从站照常采集
+        start_and_wait(gateway.id);
+        let units: Vec<u8> = status(gateway.id)
+            .slaves
+            .iter()
+            .map(|slave| slave.unit_id)
+            .collect();
+        assert_eq!(units, [1]);
+        assert_eq!(value_of(gateway.id, "speed"), None);
+
+        commission(&pump_device);
+        wait_for("commissioned device values", || {
+            value_of(gateway.id, "speed") == Some(json!(300))
+        });
+
+        // 退役后不再调度，最新值移除
+        transition(&pump_device, "decommissioned");
+        wait_for("decommissioned device removed", || {
+            status(gateway.id).slaves.len() == 1 && value_of(gateway.id, "speed").is_none()
+        });
+        assert_eq!(value_of(gateway.id, "ua"), Some(json!(100)));
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+
+    // 记录发送的值变化事件（事件名称，事件内容）
+    fn recorded_events() -> &'static Mutex<Vec<(String, AcquisitionValueEvent)>> {
+        static EVENTS: Mutex<Vec<(String, AcquisitionValueEvent)>> = Mutex::new(Vec::new());
+        static SINK: Once = Once::new();
+        SINK.call_once(|| {
+            telemetry::set_sink(Arc::new(|event, payload| {
+                EVENTS
+                    .lock()
+                    .expect("events")
+                    .push((event.to_string(), payload.clone()));
+            }));
+        });
+        &EVENTS
+    }
+
+    // 指定事件名称收到的事件
+    fn events_of(event: &str) -> Vec<AcquisitionValueEvent> {
+        recorded_events()
+            .lock()
+            .expect("events")
+            .iter()
+            .filter(|(name, _)| name == event)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+
+    // 事件中的点位值（点位标识，值）
+    fn event_values(event: &AcquisitionValueEvent) -> Vec<(String, Value)> {
+        event
+            .values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn subscribe(
+        operator_username: &str,
+        page: Option<&str>,
+        filter: AcquisitionFilterSpec,
+        throttle_ms: u64,
+    ) -> AppResult<AcquisitionSubscriptionData> {
+        acquisition_subscribe(
+            AcquisitionSubscribePayload {
+                operator_username: operator_username.to_string(),
+                page: page.map(String::from),
+                filter,
+                throttle_ms,
+                coalesce: None,
+            },
+            None,
+        )
+    }
+
+    fn snapshot(
+        operator_username: &str,
+        subscription_id: Option<u64>,
+        filter: AcquisitionFilterSpec,
+    ) -> Vec<AcquisitionValueEvent> {
+        acquisition_snapshot(
+            AcquisitionSnapshotPayload {
+                operator_username: operator_username.to_string(),
+                subscription_id,
+                filter,
+            },
+            None,
+        )
+        .expect("acquisition snapshot")
+        .data
+    }
+
+    fn by_devices(device_ids: Vec<String>) -> AcquisitionFilterSpec {
+        AcquisitionFilterSpec {
+            device_ids,
+            ..AcquisitionFilterSpec::default()
+        }
+    }
+
+    fn by_gateway(gateway_id: i64) -> AcquisitionFilterSpec {
+        AcquisitionFilterSpec {
+            gateway_ids: vec![gateway_id],
+            ..AcquisitionFilterSpec::default()
+        }
+    }
+
+    fn unsubscribe(
+        operator_username: &str,
+        subscription_id: Option<u64>,
+        page: Option<&str>,
+    ) -> AppResult<bool> {
+        acquisition_unsubscribe(
+            AcquisitionUnsubscribePayload {
+                operator_username: operator_username.to_string(),
+                subscription_id,
+                page: page.map(String::from),
+            },
+            None,
+        )
+    }
+
+    // 推送测试网关：电表从站（单元 1，点位 ua、ub）与水泵从站（单元 2，点位 speed，绑定已调试的设备）
+    struct PushGateway {
+        simulator: TcpSimulator,
+        gateway: GatewayData,
+        meter: GatewaySlaveData,
+        pump: GatewaySlaveData,
+        ua: i64,
+        pump_device: String,
+    }
+
+    fn push_gateway() -> PushGateway {
+        ensure_test_db_ready();
+        recorded_events();
+        let mut memory = SlaveMemory::new(10);
+        memory.holding_registers[..3].copy_from_slice(&[100, 200, 300]);
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let address = simulator.local_addr();
+        let gateway = create_gateway(GatewaySpec {
+            code: unique_code("gw_push"),
+            name: "推送网关".to_string(),
+            host: address.ip().to_string(),
+            port: Some(address.port()),
+            poll_interval_ms: Some(100),
+            ..GatewaySpec::default()
+        })
+        .expect("create gateway")
+        .data;
+        let meter = create_slave(gateway.id, 1);
+        let pump = create_slave(gateway.id, 2);
+        let ua = create_point(meter.id, "ua", 3, 0, "u16");
+        create_point(meter.id, "ub", 3, 1, "u16");
+        create_point(pump.id, "speed", 3, 2, "u16");
+        let pump_device = create_device(Some(format!("modbus:{}/2", gateway.code)));
+        commission(&pump_device);
+        PushGateway {
+            simulator,
+            gateway,
+            meter,
+            pump,
+            ua,
+            pump_device,
+        }
+    }
+
+    impl PushGateway {
+        fn start(&self) {
+            acquisition_start(gateway_payload("admin", self.gateway.id), None)
+                .expect("start acquisition");
+        }
+
+        fn set_registers(&self, values: &[u16]) {
+            self.simulator
+                .memory()
+                .lock()
+                .expect("memory")
+                .holding_registers[..values.len()]
+                .copy_from_slice(values);
+        }
+
+        // 等待所有从站完成若干个周期（采集计划加载前从站列表为空）
+        fn wait_cycles(&self, cycles: u64) {
+            wait_for("several cycles", || {
+                let slaves = status(self.gateway.id).slaves;
+                !slaves.is_empty() && slaves.iter().all(|slave| slave.cycles >= cycles)
+            });
+        }
+
+        fn stop(self) {
+            acquisition_stop(gateway_payload("admin", self.gateway.id), None)
+                .expect("stop acquisition");
+            self.simulator.stop();
+        }
+    }
+
+    #[test]
+    fn acquisition_subscription_validates_filters_and_permissions() {
+        ensure_test_db_ready();
+        let unlinked_device = create_device(None);
+        for (operator, page, filter, throttle_ms, message) in [
+            (
+                "admin",
+                None,
+                AcquisitionFilterSpec::default(),
+                60_001,
+                "throttleMs must be between 0 and 60000".to_string(),
+            ),
+            (
+                "admin",
+                Some("p".repeat(129)),
+                AcquisitionFilterSpec::default(),
+                0,
+                "page must be at most 128 characters".to_string(),
+            ),
+            (
+                "admin",
+                None,
+                by_devices(vec![unique_code("dev_missing")]),
+                0,
+                "device not found".to_string(),
+            ),
+            (
+                "admin",
+                None,
+                by_devices(vec![unlinked_device.clone()]),
+                0,
+                format!(
+                    "device {unlinked_device} commConfigRef must be modbus:<gatewayCode>/<unitId>"
+                ),
+            ),
+            (
+                "common",
+                None,
+                AcquisitionFilterSpec::default(),
+                0,
+                "forbidden: device view required".to_string(),
+            ),
+        ] {
+            assert_eq!(
+                subscribe(operator, page.as_deref(), filter, throttle_ms)
+                    .expect_err("invalid subscription"),
+                AppError::Validation(message)
+            );
+        }
+    }
+
+    #[test]
+    fn acquisition_pushes_value_events_matching_the_subscription_filter() {
+        let fixture = push_gateway();
+        let (gateway, meter, pump) = (&fixture.gateway, &fixture.meter, &fixture.pump);
+
+        // 页面订阅一个点位，设备订阅对应的整个从站
+        let page = subscribe(
+            "admin",
+            Some(" meter-page "),
+            AcquisitionFilterSpec {
+                point_ids: vec![fixture.ua, fixture.ua],
+                ..AcquisitionFilterSpec::default()
+            },
+            0,
+        )
+        .expect("subscribe page")
+        .data;
+        assert_eq!(page.page.as_deref(), Some("meter-page"));
+        assert_eq!(page.filter.point_ids, vec![fixture.ua]);
+        assert_eq!(
+            page.event,
+            format!("acquisition:values:{}", page.subscription_id)
+        );
+        assert!(page.coalesce);
+        let device = subscribe(
+            "admin",
+            None,
+            by_devices(vec![fixture.pump_device.clone()]),
+            0,
+        )
+        .expect("subscribe device")
+        .data;
+
+        fixture.start();
+        wait_for("initial events", || {
+            !events_of(&page.event).is_empty() && !events_of(&device.event).is_empty()
+        });
+        let first = &events_of(&page.event)[0];
+        assert_eq!((first.gateway_id, first.slave_id), (gateway.id, meter.id));
+        assert_eq!(first.gateway_code, gateway.code);
+        assert_eq!(event_values(first), vec![("ua".to_string(), json!(100))]);
+        let pushed = &events_of(&device.event)[0];
+        assert_eq!((pushed.slave_id, pushed.unit_id), (pump.id, 2));
+        assert_eq!(
+            event_values(pushed),
+            vec![("speed".to_string(), json!(300))]
+        );
+
+        // 值不变时不推送；变化后只推送变化的点位
+        fixture.wait_cycles(4);
+        assert_eq!(events_of(&page.event).len(), 1);
+        fixture.set_registers(&[111, 222]);
+        wait_for("changed value event", || events_of(&page.event).len() == 2);
+        assert_eq!(
+            event_values(&events_of(&page.event)[1]),
+            vec![("ua".to_string(), json!(111))]
+        );
+        assert_eq!(events_of(&device.event).len(), 1);
+
+        // 取消订阅：按页面或订阅 ID，只能取消自己的订阅
+        assert_eq!(
+            unsubscribe("admin", None, None).expect_err("missing target"),
+            AppError::Validation("subscriptionId or page is required".to_string())
+        );
+        assert!(
+            unsubscribe("admin", None, Some("meter-page"))
+                .expect("unsubscribe page")
+                .data
+        );
+        assert_eq!(
+            unsubscribe("admin", Some(page.subscription_id), None).expect_err("already removed"),
+            AppError::Validation("subscription not found".to_string())
+        );
+        assert!(
+            unsubscribe("admin", Some(device.subscription_id), None)
+                .expect("unsubscribe device")
+                .data
+        );
+        fixture.stop();
+    }
+
+    #[test]
+    fn acquisition_throttles_and_coalesces_value_events() {
+        let fixture = push_gateway();
+        let throttled = subscribe(
+            "admin",
+            None,
+            AcquisitionFilterSpec {
+                slave_ids: vec![fixture.meter.id],
+                ..AcquisitionFilterSpec::default()
+            },
+            2_000,
+        )
+        .expect("subscribe throttled")
+        .data;
+        fixture.start();
+        wait_for("initial event", || !events_of(&throttled.event).is_empty());
+
+        // 节流期间的两次变化合并为一个事件，保留每个点位的最新值
+        fixture.set_registers(&[111, 222]);
+        wait_for("first change", || {
+            value_of(fixture.gateway.id, "ua") == Some(json!(111))
+        });
+        fixture.set_registers(&[112]);
+        wait_for("coalesced event", || events_of(&throttled.event).len() == 2);
+        assert_eq!(
+            event_values(&events_of(&throttled.event)[1]),
+            vec![
+                ("ua".to_string(), json!(112)),
+                ("ub".to_string(), json!(222))
+            ]
+        );
+        let listed = acquisition_subscription_list(
+            AcquisitionSubscriptionListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("subscription list")
+        .data;
+        let throttled_now = listed
+            .iter()
+            .find(|subscription| subscription.subscription_id == throttled.subscription_id)
+            .expect("throttled subscription");
+        assert_eq!(
+            (throttled_now.events_sent, throttled_now.changes_coalesced),
+            (2, 2)
+        );
+        assert!(
+            unsubscribe("admin", Some(throttled.subscription_id), None)
+                .expect("unsubscribe")
+                .data
+        );
+        fixture.stop();
+    }
+
+    #[test]
+    fn acquisition_snapshots_current_values() {
+        let fixture = push_gateway();
+        let page = subscribe(
+            "admin",
+            None,
+            AcquisitionFilterSpec {
+                point_ids: vec![fixture.ua],
+                ..AcquisitionFilterSpec::default()
+            },
+            0,
+        )
+        .expect("subscribe page")
+        .data;
+        fixture.start();
+        fixture.wait_cycles(1);
+
+        // 新打开的页面通过快照获取当前值（按订阅或按过滤条件）
+        let current = snapshot(
+            "admin",
+            Some(page.subscription_id),
+            AcquisitionFilterSpec::default(),
+        );
+        assert_eq!(current.len(), 1);
+        assert_eq!(
+            event_values(&current[0]),
+            vec![("ua".to_string(), json!(100))]
+        );
+        let whole = snapshot("admin", None, by_gateway(fixture.gateway.id));
+        assert_eq!(
+            whole
+                .iter()
+                .map(|event| (event.slave_id, event.values.len()))
+                .collect::<Vec<_>>(),
+            vec![(fixture.meter.id, 2), (fixture.pump.id, 1)]
+        );
+        assert!(
+            unsubscribe("admin", Some(page.subscription_id), None)
+                .expect("unsubscribe")
+                .data
+        );
+        fixture.stop();
+    }
+
+    #[test]
+    fn acquisition_limits_delegated_users_to_their_device_scope() {
+        let fixture = push_gateway();
+        let (operator, user_id) = register_operator("acq_scope");
+        user_device_scope_upsert(
+            UserDeviceScopeUpsertPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                devices: vec![fixture.pump_device.clone()],
+                ..UserDeviceScopeUpsertPayload::default()
+            },
+            None,
+        )
+        .expect("upsert scope");
+
+        // 未设置条件与只按网关过滤都只推送范围内设备所在的从站
+        let everything = subscribe(&operator, None, AcquisitionFilterSpec::default(), 0)
+            .expect("subscribe everything")
+            .data;
+        let gateway_only = subscribe(&operator, None, by_gateway(fixture.gateway.id), 0)
+            .expect("subscribe gateway")
+            .data;
+        fixture.start();
+        wait_for("scoped events", || {
+            !events_of(&everything.event).is_empty() && !events_of(&gateway_only.event).is_empty()
+        });
+        fixture.wait_cycles(2);
+        fixture.set_registers(&[111, 222]);
+        wait_for("meter change", || {
+            value_of(fixture.gateway.id, "ua") == Some(json!(111))
+        });
+        fixture.wait_cycles(status(fixture.gateway.id).slaves[0].cycles + 2);
+        for subscription in [&everything, &gateway_only] {
+            assert_eq!(
+                events_of(&subscription.event)
+                    .iter()
+                    .map(|event| (event.slave_id, event_values(event)))
+                    .collect::<Vec<_>>(),
+                vec![(fixture.pump.id, vec![("speed".to_string(), json!(300))])]
+            );
+        }
+
+        // 快照同样限定在范围内
+        assert_eq!(
+            snapshot(&operator, None, AcquisitionFilterSpec::default())
+                .iter()
+                .map(|event| event.slave_id)
+                .collect::<Vec<_>>(),
+            vec![fixture.pump.id]
+        );
+        assert!(
+            snapshot(
+                &operator,
+                None,
+                AcquisitionFilterSpec {
+                    point_ids: vec![fixture.ua],
+                    ..AcquisitionFilterSpec::default()
+                }
+            )
+            .is_empty()
+        );
+        for subscription in [&everything, &gateway_only] {
+            assert!(
+                unsubscribe(&operator, Some(subscription.subscription_id), None)
+                    .expect("unsubscribe")
+                    .data
+            );
+        }
+        fixture.stop();
+    }
+
+    fn set_faults(simulator_id: &str, faults: ModbusSimulatorFaultSpec) {
+        modbus_simulator_set_faults(
+            ModbusSimulatorFaultsPayload {
+                operator_username: "admin".to_string(),
+                simulator_id: simulator_id.to_string(),
+                faults,
+            },
+            None,
+        )
+        .expect("set simulator faults");
+    }
+
+    // 模拟电表模板：电压（输入寄存器）与电能（cdab 字节序的保持寄存器）
+    fn simulator_template() -> i64 {
+        let point =
+            |key: &str, data_type: &str, register_type: &str, address: i64| TemplatePointSpec {
+                key: key.to_string(),
+                name: key.to_string(),
+                data_type: data_type.to_string(),
+                register_type: register_type.to_string(),
+                address,
+                ..TemplatePointSpec::default()
+            };
+        device_template_create(
+            DeviceTemplateCreatePayload {
+                operator_username: "admin".to_string(),
+                template: DeviceTemplateSpec {
+                    code: unique_code("tpl_acq_sim"),
+                    name: "模拟电表".to_string(),
+                    device_type: "meter".to_string(),
+                    points: vec![
+                        point("voltage", "uint16", "input_register", 0),
+                        TemplatePointSpec {
+                            byte_order: Some("cdab".to_string()),
+                            ..point("energy", "uint32", "holding_register", 10)
+                        },
+                    ],
+                    ..DeviceTemplateSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create template")
+        .data
+        .template
+        .id
+    }
+
+    // RTU over TCP 模拟器（电压斜坡、电能累加）与采集它的网关，返回模拟器 ID 与已启动采集的网关
+    fn simulated_gateway() -> (String, GatewayData) {
+        let simulator_id = unique_code("sim_acq");
+        let simulator = modbus_simulator_start(
+            ModbusSimulatorStartPayload {
+                operator_username: "admin".to_string(),
+                simulator_id: simulator_id.clone(),
+                framing: Some("rtu".to_string()),
+                unit_id: Some(5),
+                template_id: Some(simulator_template()),
+                tick_ms: Some(50),
+                seed: Some(11),
+                generators: vec![
+                    ModbusSimulatorGeneratorSpec {
+                        point_key: "voltage".to_string(),
+                        kind: "ramp".to_string(),
+                        start: Some(220.0),
+                        min: Some(220.0),
+                        max: Some(240.0),
+                        ..ModbusSimulatorGeneratorSpec::default()
+                    },
+                    ModbusSimulatorGeneratorSpec {
+                        point_key: "energy".to_string(),
+                        kind: "counter".to_string(),
+                        start: Some(70_000.0),
+                        increment: Some(3.0),
+                        ..ModbusSimulatorGeneratorSpec::default()
+                    },
+                ],
+                ..ModbusSimulatorStartPayload::default()
+            },
+            None,
+        )
+        .expect("start simulator")
+        .data;
+        let gateway = create_gateway(GatewaySpec {
+            code: unique_code("gw_sim"),
+            name: "模拟网关".to_string(),
+            host: simulator.host.clone(),
+            port: Some(simulator.port),
+            framing: Some("rtu".to_string()),
+            request_timeout_ms: Some(300),
+            poll_interval_ms: Some(100),
+            ..GatewaySpec::default()
+        })
+        .expect("create gateway")
+        .data;
+        let slave = create_slave(gateway.id, 5);
+        create_point(slave.id, "voltage", 4, 0, "u16");
+        gateway_point_create(
+            GatewayPointCreatePayload {
+                operator_username: "admin".to_string(),
+                slave_id: slave.id,
+                point: GatewayPointSpec {
+                    point_key: "energy".to_string(),
+                    name: "电能".to_string(),
+                    function_code: 3,
+                    address: 10,
+                    data_type: "u32".to_string(),
+                    byte_order: Some("cdab".to_string()),
+                    ..GatewayPointSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create energy point");
+        acquisition_start(gateway_payload("admin", gateway.id), None).expect("start acquisition");
+        (simulator_id, gateway)
+    }
+
+    #[test]
+    fn acquisition_survives_simulator_faults_and_recovers() {
+        ensure_test_db_ready();
+        let (simulator_id, gateway) = simulated_gateway();
+
+        // 生成器的取值按模板编码，采集按点表解码
+        let energy = || {
+            value_of(gateway.id, "energy")
+                .and_then(|value| value.as_u64())
+                .unwrap_or_default()
+        };
+        wait_for("counter values", || energy() > 70_000);
+        wait_for("ramp values", || {
+            value_of(gateway.id, "voltage")
+                .and_then(|value| value.as_u64())
+                .is_some_and(|voltage| (221..=240).contains(&voltage))
+        });
+        assert_eq!(energy() % 3, 70_000 % 3);
+        let slave_error = || status(gateway.id).slaves[0].last_error.clone();
+
+        // 异常响应、CRC 错误、断开连接与响应超时都记录为读取失败，保留上一次的值
+        for (faults, expected) in [
+            (
+                ModbusSimulatorFaultSpec {
+                    exception_rate: Some(1.0),
+                    ..ModbusSimulatorFaultSpec::default()
+                },
+                "exception 0x04 server device failure",
+            ),
+            (
+                ModbusSimulatorFaultSpec {
+                    corrupt_rate: Some(1.0),
+                    ..ModbusSimulatorFaultSpec::default()
+                },
+                "crc mismatch",
+            ),
+            (
+                ModbusSimulatorFaultSpec {
+                    drop_rate: Some(1.0),
+                    ..ModbusSimulatorFaultSpec::default()
+                },
+                "i/o error",
+            ),
+            (
+                ModbusSimulatorFaultSpec {
+                    latency_ms: Some(600),
+                    ..ModbusSimulatorFaultSpec::default()
+                },
+                "timeout after 300ms",
+            ),
+        ] {
+            set_faults(&simulator_id, faults);
+            wait_for(expected, || {
+                slave_error().is_some_and(|error| error.contains(expected))
+            });
+            let before = energy();
+            assert!(before > 70_000, "last value is kept");
+            sleep(Duration::from_millis(200));
+            assert_eq!(energy(), before, "failed reads keep the last value");
+        }
+        let failures: Vec<u64> = status(gateway.id).slaves[0]
+            .blocks
+            .iter()
+            .map(|block| block.failures)
+            .collect();
+        assert!(
+            failures.iter().all(|failures| *failures >= 4),
+            "{failures:?}"
+        );
+
+        // 清除故障后重连并恢复采集
+        let stalled = energy();
+        set_faults(&simulator_id, ModbusSimulatorFaultSpec::default());
+        wait_for("recovered acquisition", || {
+            slave_error().is_none() && energy() > stalled
+        });
+
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+        modbus_simulator_stop(
+            ModbusSimulatorPayload {
+                operator_username: "admin".to_string(),
+                simulator_id,
+            },
+            None,
+        )
+        .expect("stop simulator");
+    }
+
+    #[test]
+    fn acquisition_applies_point_transforms_and_deadband() {
+        ensure_test_db_ready();
+        let mut memory = SlaveMemory::new(10);
+        // 2120 * 0.1 = 212 °F；状态字 bit 4–5 = 1；液位 500
+        memory.holding_registers[..3].copy_from_slice(&[2120, 0b0001_0000, 500]);
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let address = simulator.local_addr();
+        let gateway = create_gateway(GatewaySpec {
+            code: unique_code("gw_acq_transform"),
+            name: "变换网关".to_string(),
+            host: address.ip().to_string(),
+            port: Some(address.port()),
+            poll_interval_ms: Some(100),
+            ..GatewaySpec::default()
+        })
+        .expect("create gateway")
+        .data;
+        let slave = gateway_slave_create(
+            GatewaySlaveCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway.id,
+                slave: GatewaySlaveSpec {
+                    unit_id: 1,
+                    name: "锅炉".to_string(),
+                    ..GatewaySlaveSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create slave")
+        .data;
+        let create = |point_key: &str, address: u16, unit: Option<&str>, transform: Value| {
+            gateway_point_create(
+                GatewayPointCreatePayload {
+                    operator_username: "admin".to_string(),
+                    slave_id: slave.id,
+                    point: GatewayPointSpec {
+                        point_key: point_key.to_string(),
+                        name: point_key.to_string(),
+                        function_code: 3,
+                        address,
+                        data_type: "u16".to_string(),
+                        unit: unit.map(str::to_string),
+                        transform: Some(serde_json::from_value(transform).expect("transform")),
+                        ..GatewayPointSpec::default()
+                    },
+                },
+                None,
+            )
+            .expect("create point")
+        };
+        create(
+            "temperature",
+            0,
+            Some("°C"),
+            json!({ "scale": 0.1, "sourceUnit": "°F" }),
+        );
+        create(
+            "state",
+            1,
+            None,
+            json!({ "bitOffset": 4, "bitLength": 2, "enumMap": { "0": "stopped", "1": "running" } }),
+        );
+        create("level", 2, Some("mm"), json!({ "deadband": 5 }));
+
+        acquisition_start(gateway_payload("admin", gateway.id), None).expect("start acquisition");
+        wait_for("transformed values", || {
+            value_of(gateway.id, "level") == Some(json!(500))
+        });
+        let temperature = value_of(gateway.id, "temperature")
+            .and_then(|value| value.as_f64())
+            .expect("temperature");
+        assert!((temperature - 100.0).abs() < 1e-6, "{temperature}");
+        assert_eq!(value_of(gateway.id, "state"), Some(json!("running")));
+
+        // 液位变化未超过死区时保持上次上报值，超过后更新
+        let cycles = |gateway_id| status(gateway_id).slaves[0].cycles;
+        simulator.memory().lock().expect("memory").holding_registers[2] = 504;
+        let seen = cycles(gateway.id);
+        wait_for("two more cycles", || cycles(gateway.id) >= seen + 2);
+        assert_eq!(value_of(gateway.id, "level"), Some(json!(500)));
+        simulator.memory().lock().expect("memory").holding_registers[2] = 506;
+        wait_for("level beyond deadband", || {
+            value_of(gateway.id, "level") == Some(json!(506))
+        });
+
+        // 未映射的枚举值记为点位错误并保留上次的值
+        simulator.memory().lock().expect("memory").holding_registers[1] = 0b0011_0000;
+        wait_for("unmapped state", || {
+            values(gateway.id)
+                .expect("acquisition values")
+                .data
+                .iter()
+                .any(|value| {
+                    value.point_key == "state"
+                        && value.error.as_deref() == Some("value 3 is not in enumMap")
+                })
+        });
+        assert_eq!(value_of(gateway.id, "state"), Some(json!("running")));
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+
+    #[test]
+    fn acquisition_evaluates_virtual_points() {
+        ensure_test_db_ready();
+        let mut memory = SlaveMemory::new(10);
+        // 三相功率 100/200/300，电能 5000，冷量 900
+        memory.holding_registers[..5].copy_from_slice(&[100, 200, 300, 5000, 900]);
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let address = simulator.local_addr();
+        let gateway = create_gateway(GatewaySpec {
+            code: unique_code("gw_acq_virtual"),
+            name: "虚拟点位网关".to_string(),
+            host: address.ip().to_string(),
+            port: Some(address.port()),
+            poll_interval_ms: Some(100),
+            ..GatewaySpec::default()
+        })
+        .expect("create gateway")
+        .data;
+        let meter = create_slave(gateway.id, 1);
+        let chiller = create_slave(gateway.id, 2);
+        for (offset, point_key) in ["p_a", "p_b", "p_c", "energy"].into_iter().enumerate() {
+            create_point(
+                meter.id,
+                point_key,
+                3,
+                u16::try_from(offset).expect("offset"),
+                "u16",
+            );
+        }
+        create_point(chiller.id, "cooling", 3, 4, "u16");
+        // 虚拟点位可以引用其他虚拟点位与其他从站的点位
+        create_virtual(meter.id, "total", "", "p_a + p_b + p_c");
+        create_virtual(meter.id, "avg_phase", "", "total / 3");
+        create_virtual(
+            chiller.id,
+            "cop",
+            "",
+            "round(cooling / [1:total] * 100) / 100",
+        );
+        create_virtual(meter.id, "overload", "bool", "max(p_a, p_b, p_c) > 250");
+        create_virtual(meter.id, "ratio", "", "p_b / (p_a - 100)");
+        create_virtual(meter.id, "energy_delta", "", "delta(energy)");
+
+        acquisition_start(gateway_payload("admin", gateway.id), None).expect("start acquisition");
+        // 时间函数在第二次采样后才有值
+        wait_for("virtual values", || {
+            value_of(gateway.id, "energy_delta") == Some(json!(0.0))
+        });
+        assert_eq!(value_of(gateway.id, "avg_phase"), Some(json!(200.0)));
+        assert_eq!(value_of(gateway.id, "total"), Some(json!(600.0)));
+        assert_eq!(value_of(gateway.id, "cop"), Some(json!(1.5)));
+        assert_eq!(value_of(gateway.id, "overload"), Some(json!(true)));
+        let data = values(gateway.id).expect("acquisition values").data;
+        let cop = data
+            .iter()
+            .find(|value| value.point_key == "cop")
+            .expect("cop value");
+        assert_eq!((cop.slave_id, cop.unit_id), (chiller.id, 2));
+        let ratio = data
+            .iter()
+            .find(|value| value.point_key == "ratio")
+            .expect("ratio value");
+        assert_eq!(
+            (&ratio.value, ratio.error.as_deref()),
+            (&Value::Null, Some("division by zero"))
+        );
+
+        // 输入变化后重新计算
+        simulator.memory().lock().expect("memory").holding_registers[0] = 160;
+        wait_for("recalculated values", || {
+            value_of(gateway.id, "total") == Some(json!(660.0))
+        });
+        assert_eq!(value_of(gateway.id, "avg_phase"), Some(json!(220.0)));
+        assert_eq!(
+            value_of(gateway.id, "ratio"),
+            Some(json!(3.333_333_333_333_333_5))
+        );
+        assert_eq!(value_of(gateway.id, "overload"), Some(json!(true)));
+        acquisition_stop(gateway_payload("admin", gateway.id), None).expect("stop acquisition");
+    }
+}
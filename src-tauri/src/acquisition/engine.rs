@@ -0,0 +1,890 @@
+//! 采集引擎
+//!
+//! 在后端按周期轮询网关下的从站：
+//! - 每个启动采集的网关一个后台线程，线程内按从站各自的轮询周期调度（从站未配置时沿用网关的轮询周期）
+//! - 从站按通信配置引用 `modbus:<网关编码>/<单元号>` 关联设备；关联的设备均处于不采集的生命周期状态时不调度该从站
+//! - 从站的点位按读取计划合并为读取块，在网关长连接上依次执行，响应按点位切片解码并经过值变换得到最新值
+//! - 配置了死区的点位，与最近一次上报值之差不超过死区的变化不更新最新值、不发布
+//! - 虚拟点位按依赖顺序排序（循环引用或引用无效的虚拟点位不参与计算）；从站周期结束后，
+//!   输入点位在本周期读取成功的虚拟点位按公式重新计算，结果与物理点位一样保存为最新值并发布
+//! - 网关、从站、点位或设备（通信配置引用、生命周期状态）变更后唤醒采集线程，在下一次调度前重新生成读取计划
+//! - 记录每个从站的周期数、超时周期（周期结束时已错过下一次计划时间）、启动抖动与周期耗时
+//! - 周期结束后将变化的点位值发布给实时推送的订阅
+//!
+//! 采集线程执行通信时不持有状态锁；停止采集时唤醒线程并等待当前周期结束。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::acquisition::formula::{self, Formula, FormulaHistory};
+use crate::acquisition::models::{
+    AcquisitionBlockStatus, AcquisitionSlaveStatus, AcquisitionStatusData, AcquisitionValueData,
+};
+use crate::acquisition::planner::{self, PlanPoint, ReadBlock};
+use crate::acquisition::services as acquisition_services;
+use crate::acquisition::telemetry::{self, ChangedPoint, ValueChange};
+use crate::acquisition::transform::PointTransform;
+use crate::auth::services::now_millis;
+use crate::core::error::AppError;
+use crate::core::sync::lock;
+use crate::db;
+use crate::device::repository as device_repository;
+use crate::device_lifecycle::services as lifecycle_services;
+use crate::gateway::models::GatewayPointRecord;
+use crate::gateway::repository as gateway_repository;
+use crate::gateway::services as gateway_services;
+use crate::modbus::client::ClientConfig;
+use crate::modbus::codec::{ByteOrder, DataType, PointValue};
+use crate::modbus::protocol::{ModbusError, Response};
+use crate::modbus::state::{self as modbus_state, SharedClient};
+use crate::modbus::transport::duration_millis;
+
+// 读取网关配置失败后的重试间隔
+const RELOAD_RETRY: Duration = Duration::from_secs(1);
+
+// 没有可调度从站时的最长等待（等待期间仍可被配置变更或停止唤醒）
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+
+/// 从站读取计划
+#[derive(Debug, Clone)]
+pub struct SlavePlan {
+    pub slave_id: i64,          // 从站 ID
+    pub unit_id: u8,            // 从站单元号
+    pub name: String,           // 从站名称
+    pub interval: Duration,     // 生效的轮询周期
+    pub blocks: Vec<ReadBlock>, // 读取块
+}
+
+/// 虚拟点位（按公式由其他点位计算）
+#[derive(Debug, Clone)]
+pub struct VirtualPoint {
+    pub point_id: i64,             // 点位 ID
+    pub point_key: String,         // 点位标识
+    pub slave_id: i64,             // 所属从站 ID
+    pub unit_id: u8,               // 所属从站单元号
+    pub data_type: DataType,       // 数据类型（f64 / bool）
+    pub expression: String,        // 公式文本（公式未变化时保留时间函数的历史）
+    pub formula: Formula,          // 已解析的公式
+    pub inputs: Vec<i64>,          // 引用点位的 ID（与公式的引用顺序一致）
+    pub transform: PointTransform, // 值变换
+}
+
+/// 网关采集计划
+#[derive(Debug, Clone)]
+pub struct GatewaySchedule {
+    pub gateway_code: String,              // 网关编码（长连接的网关标识）
+    pub config: ClientConfig,              // 长连接配置
+    pub enabled: bool,                     // 网关是否启用（停用时不调度从站）
+    pub max_gap: u16,                      // 合并读取允许跨越的最大空闲地址数
+    pub slaves: Vec<SlavePlan>,            // 启用的从站
+    pub virtual_points: Vec<VirtualPoint>, // 启用从站下的虚拟点位（按计算顺序）
+}
+
+// 采集线程的唤醒信号
+#[derive(Debug, Default)]
+struct Signal {
+    stop: bool,    // 停止采集
+    changed: bool, // 配置已变更，需要重新生成读取计划
+}
+
+// 网关采集线程
+struct Runner {
+    gateway_id: i64,                       // 网关 ID
+    signal: Mutex<Signal>,                 // 唤醒信号
+    wake: Condvar,                         // 唤醒条件
+    state: Mutex<RunnerState>,             // 采集状态与最新值
+    thread: Mutex<Option<JoinHandle<()>>>, // 线程句柄
+}
+
+// 采集状态与最新值
+#[derive(Default)]
+struct RunnerState {
+    status: AcquisitionStatusData,               // 采集状态
+    values: BTreeMap<i64, AcquisitionValueData>, // 点位 ID → 最新值
+}
+
+// 从站调度任务
+struct SlaveTask {
+    plan: SlavePlan,   // 读取计划
+    next_due: Instant, // 下一次计划时间
+}
+
+// 虚拟点位计算任务
+struct VirtualTask {
+    point: VirtualPoint,     // 虚拟点位
+    history: FormulaHistory, // 时间函数的历史
+}
+
+impl Runner {
+    // 等待到超时、停止或配置变更，返回（是否停止，是否变更）并清除变更标记
+    fn wait(&self, timeout: Duration) -> (bool, bool) {
+        let guard = lock(&self.signal);
+        let (mut guard, _) = self
+            .wake
+            .wait_timeout_while(guard, timeout, |signal| !signal.stop && !signal.changed)
+            .unwrap_or_else(PoisonError::into_inner);
+        let result = (guard.stop, guard.changed);
+        guard.changed = false;
+        result
+    }
+
+    // 设置唤醒信号并唤醒线程
+    fn notify(&self, update: impl FnOnce(&mut Signal)) {
+        update(&mut lock(&self.signal));
+        self.wake.notify_all();
+    }
+}
+
+// 网关 ID → 采集线程
+fn runners() -> &'static Mutex<HashMap<i64, Arc<Runner>>> {
+    static RUNNERS: OnceLock<Mutex<HashMap<i64, Arc<Runner>>>> = OnceLock::new();
+    RUNNERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 当前时间戳（毫秒）
+fn timestamp() -> i64 {
+    i64::try_from(now_millis()).unwrap_or_default()
+}
+
+/// 启动网关采集
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 新启动返回 true；已在采集中返回 false
+pub fn start(gateway_id: i64) -> Result<bool, AppError> {
+    let mut runners = lock(runners());
+    if let Some(existing) = runners.get(&gateway_id) {
+        if lock(&existing.state).status.running {
+            return Ok(false);
+        }
+        // 已自行结束的线程（例如网关被删除）直接回收
+        if let Some(thread) = lock(&existing.thread).take() {
+            let _ = thread.join();
+        }
+    }
+    let runner = Arc::new(Runner {
+        gateway_id,
+        signal: Mutex::new(Signal::default()),
+        wake: Condvar::new(),
+        state: Mutex::new(RunnerState {
+            status: AcquisitionStatusData {
+                gateway_id,
+                running: true,
+                started_at: timestamp(),
+                ..AcquisitionStatusData::default()
+            },
+            values: BTreeMap::new(),
+        }),
+        thread: Mutex::new(None),
+    });
+    let thread = std::thread::Builder::new()
+        .name(format!("acquisition-{gateway_id}"))
+        .spawn({
+            let runner = Arc::clone(&runner);
+            move || run(&runner)
+        })
+        .map_err(|err| AppError::Validation(format!("failed to start acquisition: {err}")))?;
+    *lock(&runner.thread) = Some(thread);
+    runners.insert(gateway_id, runner);
+    Ok(true)
+}
+
+/// 停止网关采集（等待当前周期结束）
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 停止时的采集状态；网关未启动采集时返回 None
+pub fn stop(gateway_id: i64) -> Option<AcquisitionStatusData> {
+    let runner = lock(runners()).remove(&gateway_id)?;
+    runner.notify(|signal| signal.stop = true);
+    if let Some(thread) = lock(&runner.thread).take() {
+        let _ = thread.join();
+    }
+    let mut state = lock(&runner.state);
+    state.status.running = false;
+    Some(state.status.clone())
+}
+
+/// 通知全部采集线程重新生成读取计划
+///
+/// 网关、从站或点位增删改后调用；采集线程在下一次调度前重新读取配置
+pub fn invalidate_plans() {
+    for runner in lock(runners()).values() {
+        runner.notify(|signal| signal.changed = true);
+    }
+}
+
+/// 查询采集状态
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID（为空时返回全部）
+///
+/// # 返回
+/// * 按网关 ID 排序的采集状态
+pub fn status(gateway_id: Option<i64>) -> Vec<AcquisitionStatusData> {
+    let mut statuses: Vec<AcquisitionStatusData> = lock(runners())
+        .values()
+        .filter(|runner| gateway_id.is_none_or(|id| runner.gateway_id == id))
+        .map(|runner| lock(&runner.state).status.clone())
+        .collect();
+    statuses.sort_by_key(|status| status.gateway_id);
+    statuses
+}
+
+/// 查询最新点位值
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+/// * `slave_id` - 从站 ID（为空时返回网关下全部点位）
+///
+/// # 返回
+/// * 按点位 ID 排序的最新值；网关未启动采集时返回 None
+pub fn values(gateway_id: i64, slave_id: Option<i64>) -> Option<Vec<AcquisitionValueData>> {
+    let runner = lock(runners()).get(&gateway_id).cloned()?;
+    let state = lock(&runner.state);
+    Some(
+        state
+            .values
+            .values()
+            .filter(|value| slave_id.is_none_or(|id| value.slave_id == id))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// 按从站汇总全部采集中网关的最新值
+///
+/// 只包含读取成功过的点位，时间戳为从站内最近一次成功读取的时间
+///
+/// # 返回
+/// * 按网关 ID 与从站 ID 排序的值变化（供实时推送的快照使用）
+pub fn snapshot() -> Vec<ValueChange> {
+    let mut runners: Vec<Arc<Runner>> = lock(runners()).values().cloned().collect();
+    runners.sort_by_key(|runner| runner.gateway_id);
+    let mut changes = Vec::new();
+    for runner in runners {
+        let state = lock(&runner.state);
+        let mut slaves: BTreeMap<i64, ValueChange> = BTreeMap::new();
+        for value in state.values.values() {
+            let Some(updated_at) = value.updated_at else {
+                continue;
+            };
+            let change = slaves.entry(value.slave_id).or_insert_with(|| ValueChange {
+                timestamp: updated_at,
+                gateway_id: runner.gateway_id,
+                gateway_code: state.status.gateway_code.clone(),
+                slave_id: value.slave_id,
+                unit_id: value.unit_id,
+                points: Vec::new(),
+            });
+            change.timestamp = change.timestamp.max(updated_at);
+            change.points.push(ChangedPoint {
+                point_id: value.point_id,
+                point_key: value.point_key.clone(),
+                value: value.value.clone(),
+            });
+        }
+        changes.extend(slaves.into_values());
+    }
+    changes
+}
+
+/// 读取网关配置并生成采集计划
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 采集计划（停用的从站与关联设备不采集的从站不在计划内）；网关不存在时返回 None
+pub fn load_schedule(gateway_id: i64) -> Result<Option<GatewaySchedule>, AppError> {
+    let Some(gateway) = gateway_repository::find_gateway(gateway_id)? else {
+        return Ok(None);
+    };
+    let config = gateway_services::client_config(&gateway.input.connection)?;
+    let max_gap = gateway.input.poll_max_gap;
+    let mut slaves = Vec::new();
+    let mut catalog = Vec::new();
+    let mut virtual_points = Vec::new();
+    if gateway.input.enabled {
+        let paused = paused_units(&gateway.input.code)?;
+        for slave in gateway_repository::list_slaves(gateway_id)? {
+            if !slave.input.enabled || paused.contains(&slave.input.unit_id) {
+                continue;
+            }
+            let records = gateway_repository::list_points(slave.id)?;
+            for record in &records {
+                catalog.push(gateway_services::catalog_point(
+                    record.id,
+                    &record.input,
+                    slave.input.unit_id,
+                ));
+                virtual_points.extend(virtual_point(record, slave.id, slave.input.unit_id));
+            }
+            let points = records
+                .iter()
+                .filter(|record| record.input.expression.is_none())
+                .filter_map(plan_point)
+                .collect();
+            let interval_ms = slave
+                .input
+                .poll_interval_ms
+                .unwrap_or(gateway.input.poll_interval_ms);
+            slaves.push(SlavePlan {
+                slave_id: slave.id,
+                unit_id: slave.input.unit_id,
+                name: slave.input.name,
+                interval: Duration::from_millis(interval_ms.max(1)),
+                blocks: planner::build_plan(points, max_gap),
+            });
+        }
+    }
+    Ok(Some(GatewaySchedule {
+        gateway_code: gateway.input.code,
+        config,
+        enabled: gateway.input.enabled,
+        max_gap,
+        slaves,
+        virtual_points: order_virtual_points(&catalog, virtual_points),
+    }))
+}
+
+// 关联设备均处于不采集的生命周期状态的单元号（未关联设备的从站照常采集）
+fn paused_units(gateway_code: &str) -> Result<HashSet<u8>, AppError> {
+    let mut acquired: HashMap<u8, bool> = HashMap::new();
+    for device in device_repository::list_devices_by_comm_prefix("modbus:")? {
+        let Some((code, unit_id)) = device
+            .comm_config_ref
+            .as_deref()
+            .and_then(acquisition_services::parse_comm_config_ref)
+        else {
+            continue;
+        };
+        if code == gateway_code {
+            *acquired.entry(unit_id).or_default() |=
+                lifecycle_services::acquisition_enabled(&device.lifecycle_state);
+        }
+    }
+    Ok(acquired
+        .into_iter()
+        .filter_map(|(unit_id, enabled)| (!enabled).then_some(unit_id))
+        .collect())
+}
+
+// 将点位记录转换为虚拟点位（公式与引用在排序时解析，值变换无法识别的点位不参与计算）
+fn virtual_point(point: &GatewayPointRecord, slave_id: i64, unit_id: u8) -> Option<VirtualPoint> {
+    let expression = point.input.expression.clone()?;
+    let data_type = DataType::parse(&point.input.data_type)?;
+    Some(VirtualPoint {
+        point_id: point.id,
+        point_key: point.input.point_key.clone(),
+        slave_id,
+        unit_id,
+        data_type,
+        formula: Formula::parse(&expression).ok()?,
+        expression,
+        inputs: Vec::new(),
+        transform: PointTransform::compile(
+            &point.input.transform,
+            data_type,
+            point.input.unit.as_deref(),
+        )
+        .ok()?,
+    })
+}
+
+// 解析虚拟点位的引用并按依赖排序：引用无效或处于循环引用中的虚拟点位被移除
+fn order_virtual_points(
+    catalog: &[formula::CatalogPoint],
+    points: Vec<VirtualPoint>,
+) -> Vec<VirtualPoint> {
+    let resolved = formula::resolve(catalog);
+    let mut points: HashMap<i64, VirtualPoint> = points
+        .into_iter()
+        .filter_map(|mut point| {
+            let Some(Ok(resolved)) = resolved.get(&point.point_id) else {
+                return None;
+            };
+            point.inputs.clone_from(&resolved.inputs);
+            Some((point.point_id, point))
+        })
+        .collect();
+    loop {
+        let dependencies = points
+            .values()
+            .map(|point| (point.point_id, point.inputs.clone()))
+            .collect();
+        match formula::evaluation_order(&dependencies) {
+            Ok(order) => {
+                return order
+                    .into_iter()
+                    .filter_map(|point_id| points.remove(&point_id))
+                    .collect();
+            }
+            Err(cycle) => {
+                for point_id in cycle {
+                    points.remove(&point_id);
+                }
+            }
+        }
+    }
+}
+
+// 将点位记录转换为计划点位（数据类型、字节序或值变换无法识别的点位不参与采集）
+fn plan_point(point: &GatewayPointRecord) -> Option<PlanPoint> {
+    let data_type = DataType::parse(&point.input.data_type)?;
+    Some(PlanPoint {
+        point_id: point.id,
+        point_key: point.input.point_key.clone(),
+        function_code: point.input.function_code,
+        address: point.input.address,
+        count: point.input.count,
+        data_type,
+        byte_order: ByteOrder::parse(&point.input.byte_order)?,
+        transform: PointTransform::compile(
+            &point.input.transform,
+            data_type,
+            point.input.unit.as_deref(),
+        )
+        .ok()?,
+    })
+}
+
+// 采集线程主循环
+fn run(runner: &Runner) {
+    let mut tasks: Vec<SlaveTask> = Vec::new();
+    let mut virtuals: Vec<VirtualTask> = Vec::new();
+    let mut client: Option<SharedClient> = None;
+    let mut reload = true;
+    loop {
+        if reload {
+            match load_schedule(runner.gateway_id) {
+                Ok(Some(schedule)) => {
+                    client = Some(modbus_state::register(
+                        &schedule.gateway_code,
+                        schedule.config.clone(),
+                    ));
+                    (tasks, virtuals) = apply_schedule(runner, schedule, tasks, virtuals);
+                    reload = false;
+                }
+                Ok(None) => {
+                    let mut state = lock(&runner.state);
+                    state.status.running = false;
+                    state.status.last_error = Some("gateway not found".to_string());
+                    return;
+                }
+                Err(err) => {
+                    lock(&runner.state).status.last_error = Some(err.to_string());
+                    if runner.wait(RELOAD_RETRY).0 {
+                        break;
+                    }
+                    continue;
+                }
+            }
+        }
+        let wait = tasks
+            .iter()
+            .map(|task| task.next_due)
+            .min()
+            .map_or(IDLE_WAIT, |due| {
+                due.saturating_duration_since(Instant::now())
+            });
+        let (stop, changed) = runner.wait(wait);
+        if stop {
+            break;
+        }
+        if changed {
+            reload = true;
+            continue;
+        }
+        let Some(client) = &client else {
+            continue;
+        };
+        let now = Instant::now();
+        for task in tasks.iter_mut().filter(|task| task.next_due <= now) {
+            run_cycle(runner, client, task, &mut virtuals);
+        }
+    }
+    lock(&runner.state).status.running = false;
+}
+
+// 应用新的采集计划：保留已有从站的调度时间与周期统计，重置读取块统计，清理已删除点位的值；
+// 公式未变化的虚拟点位保留时间函数的历史
+fn apply_schedule(
+    runner: &Runner,
+    schedule: GatewaySchedule,
+    previous: Vec<SlaveTask>,
+    previous_virtuals: Vec<VirtualTask>,
+) -> (Vec<SlaveTask>, Vec<VirtualTask>) {
+    let now = Instant::now();
+    let mut previous_due: HashMap<i64, Instant> = previous
+        .into_iter()
+        .map(|task| (task.plan.slave_id, task.next_due))
+        .collect();
+    let mut state = lock(&runner.state);
+    let status = &mut state.status;
+    status.gateway_code.clone_from(&schedule.gateway_code);
+    status.max_gap = schedule.max_gap;
+    status.plan_loaded_at = Some(timestamp());
+    status.plan_loads += 1;
+    status.last_error = (!schedule.enabled).then(|| "gateway is disabled".to_string());
+    let mut previous_slaves: HashMap<i64, AcquisitionSlaveStatus> = status
+        .slaves
+        .drain(..)
+        .map(|slave| (slave.slave_id, slave))
+        .collect();
+    status.slaves = schedule
+        .slaves
+        .iter()
+        .map(|plan| {
+            let mut slave = previous_slaves.remove(&plan.slave_id).unwrap_or_default();
+            slave.slave_id = plan.slave_id;
+            slave.unit_id = plan.unit_id;
+            slave.name.clone_from(&plan.name);
+            slave.poll_interval_ms = duration_millis(plan.interval);
+            slave.blocks = plan.blocks.iter().map(block_status).collect();
+            slave
+        })
+        .collect();
+    let planned: HashMap<i64, i64> = schedule
+        .slaves
+        .iter()
+        .flat_map(|plan| {
+            plan.blocks.iter().flat_map(move |block| {
+                block
+                    .points
+                    .iter()
+                    .map(move |point| (point.point_id, plan.slave_id))
+            })
+        })
+        .chain(
+            schedule
+                .virtual_points
+                .iter()
+                .map(|point| (point.point_id, point.slave_id)),
+        )
+        .collect();
+    state
+        .values
+        .retain(|point_id, value| planned.get(point_id) == Some(&value.slave_id));
+    let mut previous_history: HashMap<i64, (String, FormulaHistory)> = previous_virtuals
+        .into_iter()
+        .map(|task| (task.point.point_id, (task.point.expression, task.history)))
+        .collect();
+    let virtuals = schedule
+        .virtual_points
+        .into_iter()
+        .map(|point| {
+            let history = match previous_history.remove(&point.point_id) {
+                Some((expression, history)) if expression == point.expression => history,
+                _ => point.formula.history(),
+            };
+            VirtualTask { point, history }
+        })
+        .collect();
+    let tasks = schedule
+        .slaves
+        .into_iter()
+        .map(|plan| SlaveTask {
+            next_due: previous_due.remove(&plan.slave_id).unwrap_or(now),
+            plan,
+        })
+        .collect();
+    (tasks, virtuals)
+}
+
+// 生成读取块的初始状态
+fn block_status(block: &ReadBlock) -> AcquisitionBlockStatus {
+    AcquisitionBlockStatus {
+        function_code: block.function_code,
+        address: block.address,
+        count: block.count,
+        used_count: block.used_count(),
+        point_keys: block
+            .points
+            .iter()
+            .map(|point| point.point_key.clone())
+            .collect(),
+        ..AcquisitionBlockStatus::default()
+    }
+}
+
+// 执行一个从站的采集周期并记录统计与最新值，之后重新计算输入已更新的虚拟点位
+fn run_cycle(
+    runner: &Runner,
+    client: &SharedClient,
+    task: &mut SlaveTask,
+    virtuals: &mut [VirtualTask],
+) {
+    let started_at = Instant::now();
+    let jitter_ms = duration_millis(started_at.saturating_duration_since(task.next_due));
+    let unit_id = task.plan.unit_id;
+    let outcomes: Vec<Result<Response, ModbusError>> = task
+        .plan
+        .blocks
+        .iter()
+        .map(|block| {
+            let request = block.request();
+            db::block_on(async { client.lock().await.call(unit_id, &request).await })
+        })
+        .collect();
+    let finished_at = Instant::now();
+    let duration_ms = duration_millis(finished_at.duration_since(started_at));
+    let interval = task.plan.interval;
+    let mut next_due = task.next_due + interval;
+    let mut skipped = 0_u64;
+    while next_due <= finished_at {
+        next_due += interval;
+        skipped += 1;
+    }
+    task.next_due = next_due;
+
+    let now = timestamp();
+    let mut state = lock(&runner.state);
+    let mut cycle_error = None;
+    let mut changed = Vec::new();
+    let mut updated = HashSet::new();
+    for (block_index, outcome) in outcomes.into_iter().enumerate() {
+        let error = record_block(
+            &mut state,
+            &task.plan,
+            block_index,
+            outcome,
+            now,
+            &mut changed,
+            &mut updated,
+        );
+        cycle_error = cycle_error.or(error);
+    }
+    if let Some(slave) = state
+        .status
+        .slaves
+        .iter_mut()
+        .find(|slave| slave.slave_id == task.plan.slave_id)
+    {
+        slave.cycles += 1;
+        if skipped > 0 {
+            slave.overruns += 1;
+            slave.skipped_cycles += skipped;
+        }
+        slave.last_jitter_ms = jitter_ms;
+        slave.max_jitter_ms = slave.max_jitter_ms.max(jitter_ms);
+        slave.last_duration_ms = duration_ms;
+        slave.max_duration_ms = slave.max_duration_ms.max(duration_ms);
+        slave.last_cycle_at = Some(now);
+        slave.last_error = cycle_error;
+    }
+    let mut slave_changes: BTreeMap<i64, (u8, Vec<ChangedPoint>)> = BTreeMap::new();
+    if !changed.is_empty() {
+        slave_changes.insert(task.plan.slave_id, (task.plan.unit_id, changed));
+    }
+    evaluate_virtuals(&mut state, virtuals, &mut updated, now, &mut slave_changes);
+    let gateway_code = state.status.gateway_code.clone();
+    drop(state);
+    for (slave_id, (unit_id, points)) in slave_changes {
+        telemetry::publish(&ValueChange {
+            timestamp: now,
+            gateway_id: runner.gateway_id,
+            gateway_code: gateway_code.clone(),
+            slave_id,
+            unit_id,
+            points,
+        });
+    }
+}
+
+// 按计算顺序重新计算输入点位已更新的虚拟点位，收集值发生变化（超出死区）的点位（按从站分组）
+//
+// 输入点位尚无值或时间函数尚无上一次的值时不更新；计算失败时保留上一次的值并记录错误
+fn evaluate_virtuals(
+    state: &mut RunnerState,
+    virtuals: &mut [VirtualTask],
+    updated: &mut HashSet<i64>,
+    now: i64,
+    changes: &mut BTreeMap<i64, (u8, Vec<ChangedPoint>)>,
+) {
+    for task in virtuals {
+        let point = &task.point;
+        if !point
+            .inputs
+            .iter()
+            .any(|point_id| updated.contains(point_id))
+        {
+            continue;
+        }
+        let inputs = point
+            .inputs
+            .iter()
+            .zip(point.formula.references())
+            .map(|(point_id, reference)| match state.values.get(point_id) {
+                Some(input) if input.updated_at.is_some() => numeric_value(&input.value)
+                    .map(Some)
+                    .ok_or_else(|| format!("input {reference} is not numeric")),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<Option<f64>>, String>>();
+        let result = inputs.and_then(|inputs| {
+            let Some(value) = point
+                .formula
+                .evaluate(&inputs, &mut task.history, now)
+                .map_err(|err| err.to_string())?
+            else {
+                return Ok(None);
+            };
+            let value = if point.data_type == DataType::Bool {
+                PointValue::Bool(value != 0.0)
+            } else {
+                PointValue::Float(value)
+            };
+            point
+                .transform
+                .apply(value)
+                .map(|value| Some(value.to_json()))
+                .map_err(|err| err.to_string())
+        });
+        let entry = state
+            .values
+            .entry(point.point_id)
+            .or_insert_with(|| AcquisitionValueData {
+                point_id: point.point_id,
+                point_key: point.point_key.clone(),
+                slave_id: point.slave_id,
+                unit_id: point.unit_id,
+                value: Value::Null,
+                error: None,
+                updated_at: None,
+            });
+        match result {
+            Ok(Some(value)) => {
+                if entry.updated_at.is_none()
+                    || point.transform.is_significant(&entry.value, &value)
+                {
+                    changes
+                        .entry(point.slave_id)
+                        .or_insert_with(|| (point.unit_id, Vec::new()))
+                        .1
+                        .push(ChangedPoint {
+                            point_id: point.point_id,
+                            point_key: point.point_key.clone(),
+                            value: value.clone(),
+                        });
+                    entry.value = value;
+                }
+                entry.error = None;
+                entry.updated_at = Some(now);
+                updated.insert(point.point_id);
+            }
+            Ok(None) => {}
+            Err(err) => entry.error = Some(err),
+        }
+    }
+}
+
+// 将最新值转换为公式输入（布尔按 1 / 0）
+fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+// 记录一个读取块的结果：更新块内点位的最新值与读取块统计，收集值发生变化（超出死区）的点位
+// 与读取成功的点位，返回第一个点位错误
+fn record_block(
+    state: &mut RunnerState,
+    plan: &SlavePlan,
+    block_index: usize,
+    outcome: Result<Response, ModbusError>,
+    now: i64,
+    changed: &mut Vec<ChangedPoint>,
+    updated: &mut HashSet<i64>,
+) -> Option<String> {
+    let block = &plan.blocks[block_index];
+    let mut block_error = None;
+    let point_results: Vec<(&PlanPoint, Result<Value, String>)> =
+        match outcome.and_then(|response| planner::slice(block, &response)) {
+            Ok(values) => values
+                .into_iter()
+                .map(|(point, value)| {
+                    (
+                        point,
+                        value.map_err(|err| err.to_string()).and_then(|value| {
+                            point
+                                .transform
+                                .apply(value)
+                                .map(|value| value.to_json())
+                                .map_err(|err| err.to_string())
+                        }),
+                    )
+                })
+                .collect(),
+            Err(err) => {
+                block_error = Some(err.to_string());
+                block
+                    .points
+                    .iter()
+                    .map(|point| (point, Err(err.to_string())))
+                    .collect()
+            }
+        };
+    let mut first_error = None;
+    for (point, result) in point_results {
+        let entry = state
+            .values
+            .entry(point.point_id)
+            .or_insert_with(|| AcquisitionValueData {
+                point_id: point.point_id,
+                point_key: point.point_key.clone(),
+                slave_id: plan.slave_id,
+                unit_id: plan.unit_id,
+                value: Value::Null,
+                error: None,
+                updated_at: None,
+            });
+        match result {
+            Ok(value) => {
+                if entry.updated_at.is_none()
+                    || point.transform.is_significant(&entry.value, &value)
+                {
+                    changed.push(ChangedPoint {
+                        point_id: point.point_id,
+                        point_key: point.point_key.clone(),
+                        value: value.clone(),
+                    });
+                    entry.value = value;
+                }
+                entry.error = None;
+                entry.updated_at = Some(now);
+                updated.insert(point.point_id);
+            }
+            Err(err) => {
+                if first_error.is_none() {
+                    first_error = Some(format!("{}: {err}", point.point_key));
+                }
+                entry.error = Some(err);
+            }
+        }
+    }
+    let status = state
+        .status
+        .slaves
+        .iter_mut()
+        .find(|slave| slave.slave_id == plan.slave_id)
+        .and_then(|slave| slave.blocks.get_mut(block_index));
+    if let Some(status) = status {
+        status.requests += 1;
+        if let Some(err) = block_error {
+            status.failures += 1;
+            status.last_error = Some(err);
+        }
+    }
+    first_error
+}
@@ -0,0 +1,1113 @@
+//! 虚拟点位公式
+//!
+//! 虚拟点位的值由同一网关内其他点位的值按公式计算：
+//! - 引用：`[pointKey]` 引用同一从站的点位，`[unitId:pointKey]` 引用同一网关其他从站的点位；
+//!   只含字母、数字与 `_` 且不以数字开头的点位标识可省略方括号
+//! - 运算：`+ - * / % ^`、比较 `< <= > >= == !=` 与逻辑 `&& || !`（比较与逻辑结果为 1 / 0，非 0 为真）
+//! - 函数：`min` / `max` / `avg` / `sum`（至少一个参数）、`abs` / `sqrt` / `round` / `floor` / `ceil`、
+//!   `clamp(x, min, max)`、条件 `if(cond, a, b)`，以及时间函数 `delta(x)`（与上一次计算的差值）与
+//!   `rate(x)`（每秒变化率）
+//!
+//! 公式只能读取引用点位的值，没有变量、循环或其他数据访问；解析时限制长度、项数与嵌套深度，
+//! 计算时限制执行时间。本模块为纯函数（时间函数的历史由调用方保存），不涉及通信与存储。
+
+// 引入有序映射与哈希映射（依赖关系与排序状态）
+use std::collections::{BTreeMap, HashMap};
+// 引入格式化（点位引用的显示形式）
+use std::fmt;
+// 引入计时（计算时间上限）
+use std::time::{Duration, Instant};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+
+// 公式最大长度（字符）
+const MAX_LENGTH: usize = 1024;
+
+// 公式最大项数（数字、引用、运算与函数调用）
+const MAX_NODES: usize = 256;
+
+// 公式最大嵌套深度（括号、函数参数与一元运算）
+const MAX_DEPTH: usize = 32;
+
+/// 单次计算的执行时间上限
+pub const TIME_LIMIT: Duration = Duration::from_millis(5);
+
+/// 公式错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FormulaError {
+    /// 公式为空
+    #[error("expression is required")]
+    Empty,
+    /// 公式过长
+    #[error("expression must be at most {MAX_LENGTH} characters")]
+    TooLong,
+    /// 无法识别的字符
+    #[error("unexpected character '{0}' at column {1}")]
+    UnexpectedChar(char, usize),
+    /// 数字格式错误
+    #[error("invalid number at column {0}")]
+    InvalidNumber(usize),
+    /// 点位引用格式错误
+    #[error("invalid point reference at column {0}")]
+    InvalidReference(usize),
+    /// 语法错误
+    #[error("unexpected '{0}' at column {1}")]
+    UnexpectedToken(String, usize),
+    /// 公式不完整
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    /// 未知函数
+    #[error("unknown function {0}")]
+    UnknownFunction(String),
+    /// 函数参数个数错误
+    #[error("function {0} expects {1}")]
+    Arity(&'static str, &'static str),
+    /// 项数超出上限
+    #[error("expression must have at most {MAX_NODES} terms")]
+    TooComplex,
+    /// 嵌套超出上限
+    #[error("expression must be nested at most {MAX_DEPTH} levels")]
+    TooDeep,
+    /// 没有引用任何点位（不会被触发计算）
+    #[error("expression must reference at least one point")]
+    NoReference,
+    /// 引用的点位不存在
+    #[error("unknown point {0}")]
+    UnknownPoint(PointRef),
+    /// 引用的点位不是数值点位（字符串或枚举映射）
+    #[error("point {0} is not numeric")]
+    NotNumeric(PointRef),
+    /// 循环引用
+    #[error("circular reference {0}")]
+    Cycle(String),
+    /// 除数为 0
+    #[error("division by zero")]
+    DivisionByZero,
+    /// 计算结果不是有限数（例如负数开方）
+    #[error("result is not a finite number")]
+    NotFinite,
+    /// 计算超出执行时间上限
+    #[error("evaluation exceeded {}ms", TIME_LIMIT.as_millis())]
+    Timeout,
+}
+
+/// 转换为应用错误：公式错误均为配置或输入值问题，归为校验错误
+impl From<FormulaError> for AppError {
+    fn from(err: FormulaError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+/// 点位引用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointRef {
+    pub unit_id: Option<u8>, // 从站单元号（为空表示同一从站）
+    pub point_key: String,   // 点位标识
+}
+
+/// 显示为公式中的方括号形式
+impl fmt::Display for PointRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unit_id {
+            Some(unit_id) => write!(f, "[{unit_id}:{}]", self.point_key),
+            None => write!(f, "[{}]", self.point_key),
+        }
+    }
+}
+
+// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),            // 数字
+    Ident(String),          // 标识符（函数名、true / false 或省略方括号的点位标识）
+    Reference(PointRef),    // 方括号点位引用
+    Operator(&'static str), // 运算符
+    Open,                   // (
+    Close,                  // )
+    Comma,                  // ,
+}
+
+impl Token {
+    // 错误信息中的显示形式
+    fn text(&self) -> String {
+        match self {
+            Self::Number(value) => value.to_string(),
+            Self::Ident(name) => name.clone(),
+            Self::Reference(reference) => reference.to_string(),
+            Self::Operator(op) => (*op).to_string(),
+            Self::Open => "(".to_string(),
+            Self::Close => ")".to_string(),
+            Self::Comma => ",".to_string(),
+        }
+    }
+}
+
+// 运算符（两个字符的在前，按最长匹配）
+const OPERATORS: &[&str] = &[
+    "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "!",
+];
+
+// 二元运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+// 内置函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Min,
+    Max,
+    Avg,
+    Sum,
+    Abs,
+    Sqrt,
+    Round,
+    Floor,
+    Ceil,
+    Clamp,
+    If,
+    Delta,
+    Rate,
+}
+
+impl Function {
+    // 按名称查找函数（返回函数、名称与参数个数范围）
+    fn parse(name: &str) -> Option<(Self, &'static str, usize, usize)> {
+        Some(match name {
+            "min" => (Self::Min, "min", 1, usize::MAX),
+            "max" => (Self::Max, "max", 1, usize::MAX),
+            "avg" => (Self::Avg, "avg", 1, usize::MAX),
+            "sum" => (Self::Sum, "sum", 1, usize::MAX),
+            "abs" => (Self::Abs, "abs", 1, 1),
+            "sqrt" => (Self::Sqrt, "sqrt", 1, 1),
+            "round" => (Self::Round, "round", 1, 1),
+            "floor" => (Self::Floor, "floor", 1, 1),
+            "ceil" => (Self::Ceil, "ceil", 1, 1),
+            "clamp" => (Self::Clamp, "clamp", 3, 3),
+            "if" => (Self::If, "if", 3, 3),
+            "delta" => (Self::Delta, "delta", 1, 1),
+            "rate" => (Self::Rate, "rate", 1, 1),
+            _ => return None,
+        })
+    }
+}
+
+// 语法树节点
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),                            // 常数
+    Input(usize),                           // 引用的点位（引用列表下标）
+    Neg(Box<Node>),                         // 取负
+    Not(Box<Node>),                         // 逻辑非
+    Binary(BinaryOp, Box<Node>, Box<Node>), // 二元运算
+    Call(Function, Vec<Node>),              // 函数调用
+    History(Function, usize, Box<Node>),    // 时间函数（delta / rate，历史槽位下标）
+}
+
+/// 已解析的公式
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    root: Node,          // 语法树
+    refs: Vec<PointRef>, // 引用的点位（去重，按首次出现顺序）
+    slots: usize,        // 时间函数个数
+}
+
+/// 时间函数的历史（每个时间函数保存上一次计算的时间戳与参数值）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormulaHistory {
+    samples: Vec<Option<(i64, f64)>>, // 时间函数下标 → （时间戳，参数值）
+}
+
+impl Formula {
+    /// 解析公式
+    ///
+    /// # 参数
+    /// * `source` - 公式文本
+    ///
+    /// # 返回
+    /// * 已解析的公式（至少引用一个点位）
+    pub fn parse(source: &str) -> Result<Self, FormulaError> {
+        let source = source.trim();
+        if source.is_empty() {
+            return Err(FormulaError::Empty);
+        }
+        if source.chars().count() > MAX_LENGTH {
+            return Err(FormulaError::TooLong);
+        }
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            depth: 0,
+            nodes: 0,
+            refs: Vec::new(),
+            slots: 0,
+        };
+        let root = parser.expression()?;
+        if let Some((token, column)) = parser.tokens.get(parser.position) {
+            return Err(FormulaError::UnexpectedToken(token.text(), *column));
+        }
+        if parser.refs.is_empty() {
+            return Err(FormulaError::NoReference);
+        }
+        Ok(Self {
+            root,
+            refs: parser.refs,
+            slots: parser.slots,
+        })
+    }
+
+    /// 引用的点位（计算时的输入按此顺序传入）
+    pub fn references(&self) -> &[PointRef] {
+        &self.refs
+    }
+
+    /// 创建空的时间函数历史
+    pub fn history(&self) -> FormulaHistory {
+        FormulaHistory {
+            samples: vec![None; self.slots],
+        }
+    }
+
+    /// 计算公式
+    ///
+    /// # 参数
+    /// * `inputs` - 引用点位的当前值（按 `references` 顺序，尚无值时为 None）
+    /// * `history` - 时间函数的历史（由 `history` 创建，计算时更新）
+    /// * `now` - 计算时间戳（毫秒，时间函数使用）
+    ///
+    /// # 返回
+    /// * 计算结果；所需输入尚无值或时间函数尚无上一次的值时返回 None
+    pub fn evaluate(
+        &self,
+        inputs: &[Option<f64>],
+        history: &mut FormulaHistory,
+        now: i64,
+    ) -> Result<Option<f64>, FormulaError> {
+        history.samples.resize(self.slots, None);
+        let mut evaluation = Evaluation {
+            inputs,
+            history,
+            results: vec![Ok(None); self.slots],
+            now,
+            deadline: Instant::now() + TIME_LIMIT,
+        };
+        evaluation.record(&self.root)?;
+        match evaluation.eval(&self.root)? {
+            Some(value) if !value.is_finite() => Err(FormulaError::NotFinite),
+            value => Ok(value),
+        }
+    }
+}
+
+// 拆分词法单元（附带 1 起始的列号）
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, FormulaError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let column = index + 1;
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let token = if ch.is_ascii_digit() || ch == '.' {
+            while index < chars.len()
+                && (chars[index].is_ascii_alphanumeric() || chars[index] == '.')
+            {
+                // 指数部分的正负号
+                if matches!(chars[index], 'e' | 'E')
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|next| matches!(next, '+' | '-'))
+                {
+                    index += 1;
+                }
+                index += 1;
+            }
+            let text: String = chars[start..index].iter().collect();
+            Token::Number(
+                text.parse()
+                    .map_err(|_| FormulaError::InvalidNumber(column))?,
+            )
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            while index < chars.len()
+                && (chars[index].is_ascii_alphanumeric() || chars[index] == '_')
+            {
+                index += 1;
+            }
+            Token::Ident(chars[start..index].iter().collect())
+        } else if ch == '[' {
+            let end = chars[start..]
+                .iter()
+                .position(|ch| *ch == ']')
+                .ok_or(FormulaError::InvalidReference(column))?;
+            let text: String = chars[start + 1..start + end].iter().collect();
+            index = start + end + 1;
+            Token::Reference(parse_reference(&text).ok_or(FormulaError::InvalidReference(column))?)
+        } else if ch == '(' || ch == ')' || ch == ',' {
+            index += 1;
+            match ch {
+                '(' => Token::Open,
+                ')' => Token::Close,
+                _ => Token::Comma,
+            }
+        } else {
+            let rest: String = chars[start..chars.len().min(start + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or(FormulaError::UnexpectedChar(ch, column))?;
+            index += op.len();
+            Token::Operator(op)
+        };
+        tokens.push((token, column));
+    }
+    Ok(tokens)
+}
+
+// 解析方括号内的点位引用（`pointKey` 或 `unitId:pointKey`）
+fn parse_reference(text: &str) -> Option<PointRef> {
+    let text = text.trim();
+    let (unit_id, point_key) = match text.split_once(':') {
+        Some((unit_id, point_key)) => {
+            let unit_id = unit_id.trim().parse::<u8>().ok().filter(|id| *id > 0)?;
+            (Some(unit_id), point_key.trim())
+        }
+        None => (None, text),
+    };
+    let valid = !point_key.is_empty()
+        && point_key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+    valid.then(|| PointRef {
+        unit_id,
+        point_key: point_key.to_string(),
+    })
+}
+
+// 递归下降解析器
+struct Parser {
+    tokens: Vec<(Token, usize)>, // 词法单元与列号
+    position: usize,             // 当前位置
+    depth: usize,                // 当前嵌套深度
+    nodes: usize,                // 已生成的节点数
+    refs: Vec<PointRef>,         // 引用的点位
+    slots: usize,                // 时间函数个数
+}
+
+impl Parser {
+    // 当前词法单元
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    // 取出当前词法单元
+    fn next(&mut self) -> Result<(Token, usize), FormulaError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(FormulaError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    // 当前词法单元为指定运算符时取出
+    fn accept(&mut self, operators: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) if operators.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    // 取出指定的词法单元
+    fn expect(&mut self, expected: &Token) -> Result<(), FormulaError> {
+        let (token, column) = self.next()?;
+        if &token == expected {
+            Ok(())
+        } else {
+            Err(FormulaError::UnexpectedToken(token.text(), column))
+        }
+    }
+
+    // 生成节点（检查项数上限）
+    fn node(&mut self, node: Node) -> Result<Node, FormulaError> {
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return Err(FormulaError::TooComplex);
+        }
+        Ok(node)
+    }
+
+    // 生成二元运算节点
+    fn binary(&mut self, op: BinaryOp, left: Node, right: Node) -> Result<Node, FormulaError> {
+        self.node(Node::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    // 进入一层嵌套（检查深度上限）
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, FormulaError>,
+    ) -> Result<T, FormulaError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(FormulaError::TooDeep);
+        }
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    // 表达式：逻辑或
+    fn expression(&mut self) -> Result<Node, FormulaError> {
+        self.nested(|parser| {
+            let mut left = parser.and()?;
+            while parser.accept(&["||"]).is_some() {
+                let right = parser.and()?;
+                left = parser.binary(BinaryOp::Or, left, right)?;
+            }
+            Ok(left)
+        })
+    }
+
+    // 逻辑与
+    fn and(&mut self) -> Result<Node, FormulaError> {
+        let mut left = self.comparison()?;
+        while self.accept(&["&&"]).is_some() {
+            let right = self.comparison()?;
+            left = self.binary(BinaryOp::And, left, right)?;
+        }
+        Ok(left)
+    }
+
+    // 比较（不可连续比较）
+    fn comparison(&mut self) -> Result<Node, FormulaError> {
+        let left = self.additive()?;
+        let Some(op) = self.accept(&["<", "<=", ">", ">=", "==", "!="]) else {
+            return Ok(left);
+        };
+        let op = match op {
+            "<" => BinaryOp::Lt,
+            "<=" => BinaryOp::Le,
+            ">" => BinaryOp::Gt,
+            ">=" => BinaryOp::Ge,
+            "==" => BinaryOp::Eq,
+            _ => BinaryOp::Ne,
+        };
+        let right = self.additive()?;
+        self.binary(op, left, right)
+    }
+
+    // 加减
+    fn additive(&mut self) -> Result<Node, FormulaError> {
+        let mut left = self.multiplicative()?;
+        while let Some(op) = self.accept(&["+", "-"]) {
+            let op = if op == "+" {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            let right = self.multiplicative()?;
+            left = self.binary(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    // 乘除与取余
+    fn multiplicative(&mut self) -> Result<Node, FormulaError> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.accept(&["*", "/", "%"]) {
+            let op = match op {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            let right = self.unary()?;
+            left = self.binary(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    // 一元运算（取负与逻辑非）
+    fn unary(&mut self) -> Result<Node, FormulaError> {
+        match self.accept(&["-", "!"]) {
+            Some(op) => {
+                let operand = self.nested(Self::unary)?;
+                self.node(if op == "-" {
+                    Node::Neg(Box::new(operand))
+                } else {
+                    Node::Not(Box::new(operand))
+                })
+            }
+            None => self.power(),
+        }
+    }
+
+    // 乘方（右结合，指数可带一元运算）
+    fn power(&mut self) -> Result<Node, FormulaError> {
+        let base = self.primary()?;
+        if self.accept(&["^"]).is_none() {
+            return Ok(base);
+        }
+        let exponent = self.nested(Self::unary)?;
+        self.binary(BinaryOp::Pow, base, exponent)
+    }
+
+    // 基本项：数字、点位引用、函数调用或括号
+    fn primary(&mut self) -> Result<Node, FormulaError> {
+        let (token, column) = self.next()?;
+        match token {
+            Token::Number(value) => self.node(Node::Number(value)),
+            Token::Reference(reference) => self.reference(reference),
+            Token::Ident(name) if self.peek() == Some(&Token::Open) => self.call(&name),
+            Token::Ident(name) => match name.as_str() {
+                "true" => self.node(Node::Number(1.0)),
+                "false" => self.node(Node::Number(0.0)),
+                _ => self.reference(PointRef {
+                    unit_id: None,
+                    point_key: name,
+                }),
+            },
+            Token::Open => {
+                let node = self.expression()?;
+                self.expect(&Token::Close)?;
+                Ok(node)
+            }
+            token => Err(FormulaError::UnexpectedToken(token.text(), column)),
+        }
+    }
+
+    // 点位引用（去重后记录下标）
+    fn reference(&mut self, reference: PointRef) -> Result<Node, FormulaError> {
+        let index =
+            if let Some(index) = self.refs.iter().position(|existing| *existing == reference) {
+                index
+            } else {
+                self.refs.push(reference);
+                self.refs.len() - 1
+            };
+        self.node(Node::Input(index))
+    }
+
+    // 函数调用
+    fn call(&mut self, name: &str) -> Result<Node, FormulaError> {
+        let (function, name, min, max) =
+            Function::parse(name).ok_or_else(|| FormulaError::UnknownFunction(name.to_string()))?;
+        self.expect(&Token::Open)?;
+        let mut args = Vec::new();
+        if self.peek() != Some(&Token::Close) {
+            loop {
+                args.push(self.expression()?);
+                if self.peek() != Some(&Token::Comma) {
+                    break;
+                }
+                self.position += 1;
+            }
+        }
+        self.expect(&Token::Close)?;
+        if args.len() < min || args.len() > max {
+            let expected = match (min, max) {
+                (1, 1) => "1 argument",
+                (3, 3) => "3 arguments",
+                _ => "at least 1 argument",
+            };
+            return Err(FormulaError::Arity(name, expected));
+        }
+        if matches!(function, Function::Delta | Function::Rate) {
+            let slot = self.slots;
+            self.slots += 1;
+            let arg = args.remove(0);
+            return self.node(Node::History(function, slot, Box::new(arg)));
+        }
+        self.node(Node::Call(function, args))
+    }
+}
+
+// 计算上下文
+struct Evaluation<'a> {
+    inputs: &'a [Option<f64>],                       // 引用点位的值
+    history: &'a mut FormulaHistory,                 // 时间函数历史
+    results: Vec<Result<Option<f64>, FormulaError>>, // 时间函数下标 → 本次结果
+    now: i64,                                        // 计算时间戳（毫秒）
+    deadline: Instant,                               // 执行时间上限
+}
+
+// 布尔值转换为 1 / 0
+fn truth(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+impl Evaluation<'_> {
+    // 预先计算全部时间函数并更新历史（条件与短路未选中的分支中的时间函数同样记录本次的值）
+    fn record(&mut self, node: &Node) -> Result<(), FormulaError> {
+        match node {
+            Node::Number(_) | Node::Input(_) => Ok(()),
+            Node::Neg(operand) | Node::Not(operand) => self.record(operand),
+            Node::Binary(_, left, right) => {
+                self.record(left)?;
+                self.record(right)
+            }
+            Node::Call(_, args) => args.iter().try_for_each(|arg| self.record(arg)),
+            Node::History(function, slot, arg) => {
+                self.record(arg)?;
+                // 参数计算出错时留到计算该节点时再报告，未选中的分支不影响结果
+                let result = match self.eval(arg) {
+                    Err(FormulaError::Timeout) => return Err(FormulaError::Timeout),
+                    Err(err) => Err(err),
+                    Ok(None) => Ok(None),
+                    Ok(Some(value)) => Ok(self.sample(*function, *slot, value)),
+                };
+                self.results[*slot] = result;
+                Ok(())
+            }
+        }
+    }
+
+    // 以本次参数值更新历史，返回与上一次的差值（或变化率）
+    fn sample(&mut self, function: Function, slot: usize, value: f64) -> Option<f64> {
+        let previous = self.history.samples[slot];
+        // 同一时间戳重复计算时不更新历史
+        if previous.is_some_and(|(at, _)| at >= self.now) {
+            return None;
+        }
+        self.history.samples[slot] = Some((self.now, value));
+        previous.map(|(at, previous)| {
+            let delta = value - previous;
+            if function == Function::Rate {
+                #[allow(clippy::cast_precision_loss)]
+                let seconds = (self.now - at) as f64 / 1000.0;
+                delta / seconds
+            } else {
+                delta
+            }
+        })
+    }
+
+    // 计算节点（None 表示所需的值尚不可用）
+    fn eval(&mut self, node: &Node) -> Result<Option<f64>, FormulaError> {
+        if Instant::now() > self.deadline {
+            return Err(FormulaError::Timeout);
+        }
+        match node {
+            Node::Number(value) => Ok(Some(*value)),
+            Node::Input(index) => Ok(self.inputs.get(*index).copied().flatten()),
+            Node::Neg(operand) => Ok(self.eval(operand)?.map(|value| -value)),
+            Node::Not(operand) => Ok(self.eval(operand)?.map(|value| truth(value == 0.0))),
+            Node::Binary(op @ (BinaryOp::And | BinaryOp::Or), left, right) => {
+                let Some(left) = self.eval(left)? else {
+                    return Ok(None);
+                };
+                // 短路求值：与运算左侧为假、或运算左侧为真时不计算右侧
+                if (*op == BinaryOp::And) == (left == 0.0) {
+                    return Ok(Some(truth(left != 0.0)));
+                }
+                Ok(self.eval(right)?.map(|right| truth(right != 0.0)))
+            }
+            Node::Binary(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                match (left, right) {
+                    (Some(left), Some(right)) => binary(*op, left, right).map(Some),
+                    _ => Ok(None),
+                }
+            }
+            Node::Call(Function::If, args) => {
+                let Some(condition) = self.eval(&args[0])? else {
+                    return Ok(None);
+                };
+                self.eval(&args[if condition == 0.0 { 2 } else { 1 }])
+            }
+            Node::Call(function, args) => {
+                // 计算全部参数，任一参数尚无值时不产生结果
+                let mut values = Vec::with_capacity(args.len());
+                let mut pending = false;
+                for arg in args {
+                    match self.eval(arg)? {
+                        Some(value) => values.push(value),
+                        None => pending = true,
+                    }
+                }
+                Ok((!pending).then(|| call(*function, &values)))
+            }
+            // 时间函数已由 record 预先计算
+            Node::History(_, slot, _) => self.results[*slot].clone(),
+        }
+    }
+}
+
+// 二元运算（与运算、或运算由调用方短路处理）
+fn binary(op: BinaryOp, left: f64, right: f64) -> Result<f64, FormulaError> {
+    Ok(match op {
+        BinaryOp::Add => left + right,
+        BinaryOp::Sub => left - right,
+        BinaryOp::Mul => left * right,
+        BinaryOp::Div | BinaryOp::Rem if right == 0.0 => {
+            return Err(FormulaError::DivisionByZero);
+        }
+        BinaryOp::Div => left / right,
+        BinaryOp::Rem => left % right,
+        BinaryOp::Pow => left.powf(right),
+        BinaryOp::Lt => truth(left < right),
+        BinaryOp::Le => truth(left <= right),
+        BinaryOp::Gt => truth(left > right),
+        BinaryOp::Ge => truth(left >= right),
+        // 公式中的相等比较按精确值比较（常用于状态码、枚举值）
+        #[allow(clippy::float_cmp)]
+        BinaryOp::Eq => truth(left == right),
+        #[allow(clippy::float_cmp)]
+        BinaryOp::Ne => truth(left != right),
+        BinaryOp::And => truth(left != 0.0 && right != 0.0),
+        BinaryOp::Or => truth(left != 0.0 || right != 0.0),
+    })
+}
+
+// 函数调用（参数个数已在解析时校验）
+fn call(function: Function, args: &[f64]) -> f64 {
+    match function {
+        Function::Min => args.iter().copied().fold(f64::INFINITY, f64::min),
+        Function::Max => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        #[allow(clippy::cast_precision_loss)]
+        Function::Avg => args.iter().sum::<f64>() / args.len() as f64,
+        Function::Sum => args.iter().sum(),
+        Function::Abs => args[0].abs(),
+        Function::Sqrt => args[0].sqrt(),
+        Function::Round => args[0].round(),
+        Function::Floor => args[0].floor(),
+        Function::Ceil => args[0].ceil(),
+        Function::Clamp => args[0].max(args[1]).min(args[2]),
+        // 条件与时间函数由调用方处理
+        Function::If | Function::Delta | Function::Rate => f64::NAN,
+    }
+}
+
+/// 网关内的点位（解析公式引用时使用）
+#[derive(Debug, Clone, Default)]
+pub struct CatalogPoint {
+    pub point_id: i64,              // 点位 ID
+    pub point_key: String,          // 点位标识
+    pub unit_id: u8,                // 所属从站单元号
+    pub numeric: bool,              // 是否为数值点位（可被公式引用）
+    pub expression: Option<String>, // 公式（虚拟点位）
+}
+
+/// 已解析引用的虚拟点位公式
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedFormula {
+    pub formula: Formula, // 公式
+    pub inputs: Vec<i64>, // 引用点位的 ID（与公式的引用顺序一致）
+}
+
+/// 解析网关内全部虚拟点位的公式与引用
+///
+/// # 参数
+/// * `points` - 网关内的全部点位
+///
+/// # 返回
+/// * 虚拟点位 ID → 解析结果（公式错误、引用的点位不存在或不是数值点位时为错误）
+pub fn resolve(points: &[CatalogPoint]) -> BTreeMap<i64, Result<ResolvedFormula, FormulaError>> {
+    let catalog: HashMap<(u8, &str), &CatalogPoint> = points
+        .iter()
+        .map(|point| ((point.unit_id, point.point_key.as_str()), point))
+        .collect();
+    points
+        .iter()
+        .filter_map(|point| {
+            let expression = point.expression.as_deref()?;
+            let result = Formula::parse(expression).and_then(|formula| {
+                let inputs = formula
+                    .references()
+                    .iter()
+                    .map(|reference| {
+                        let unit_id = reference.unit_id.unwrap_or(point.unit_id);
+                        let target = catalog
+                            .get(&(unit_id, reference.point_key.as_str()))
+                            .ok_or_else(|| FormulaError::UnknownPoint(reference.clone()))?;
+                        if !target.numeric {
+                            return Err(FormulaError::NotNumeric(reference.clone()));
+                        }
+                        Ok(target.point_id)
+                    })
+                    .collect::<Result<Vec<i64>, FormulaError>>()?;
+                Ok(ResolvedFormula { formula, inputs })
+            });
+            Some((point.point_id, result))
+        })
+        .collect()
+}
+
+// 排序时的访问状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Active, // 正在访问（在当前路径上）
+    Done,   // 已排序
+}
+
+/// 按依赖关系排序虚拟点位（被引用的虚拟点位先计算）
+///
+/// # 参数
+/// * `dependencies` - 虚拟点位 ID → 引用点位的 ID（不在映射中的 ID 为物理点位）
+///
+/// # 返回
+/// * 计算顺序；存在循环引用时返回环上的点位 ID（首尾为同一点位）
+pub fn evaluation_order(dependencies: &BTreeMap<i64, Vec<i64>>) -> Result<Vec<i64>, Vec<i64>> {
+    let mut marks = HashMap::new();
+    let mut path = Vec::new();
+    let mut order = Vec::new();
+    for point_id in dependencies.keys() {
+        visit(*point_id, dependencies, &mut marks, &mut path, &mut order)?;
+    }
+    Ok(order)
+}
+
+// 深度优先访问虚拟点位及其引用
+fn visit(
+    point_id: i64,
+    dependencies: &BTreeMap<i64, Vec<i64>>,
+    marks: &mut HashMap<i64, Mark>,
+    path: &mut Vec<i64>,
+    order: &mut Vec<i64>,
+) -> Result<(), Vec<i64>> {
+    match marks.get(&point_id) {
+        Some(Mark::Done) => return Ok(()),
+        Some(Mark::Active) => {
+            let start = path.iter().position(|id| *id == point_id).unwrap_or(0);
+            let mut cycle = path[start..].to_vec();
+            cycle.push(point_id);
+            return Err(cycle);
+        }
+        None => {}
+    }
+    let Some(inputs) = dependencies.get(&point_id) else {
+        return Ok(());
+    };
+    marks.insert(point_id, Mark::Active);
+    path.push(point_id);
+    for input in inputs {
+        visit(*input, dependencies, marks, path, order)?;
+    }
+    path.pop();
+    marks.insert(point_id, Mark::Done);
+    order.push(point_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 以给定输入计算公式（无时间函数）
+    fn eval(source: &str, inputs: &[Option<f64>]) -> Result<Option<f64>, FormulaError> {
+        let formula = Formula::parse(source)?;
+        formula.evaluate(inputs, &mut formula.history(), 0)
+    }
+
+    #[test]
+    fn parses_and_evaluates_expressions() {
+        let formula = Formula::parse("[p_a] + p_b + [2:p-c] + [p_a]").expect("parse");
+        let keys: Vec<String> = formula
+            .references()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(keys, ["[p_a]", "[p_b]", "[2:p-c]"]);
+
+        let cases = [
+            ("a + b * 2 ^ 2", 9.0),
+            ("-a ^ 2 + 2 ^ -1", -0.5),
+            ("(a + b) % 2", 1.0),
+            (
+                "min(a, b, 0.5) + max(a, b) + avg(a, b) + sum(a, b, 1e1)",
+                17.0,
+            ),
+            (
+                "abs(a - b) + sqrt(b + 2) + round(2.5) + floor(-a / 2) + ceil(0.1)",
+                6.0,
+            ),
+            ("clamp(a * 20, 0, 15) + clamp(b, 3, 2)", 17.0),
+            ("if(a > b, 1, if(a == 1 && b != 1, 2, 3))", 2.0),
+            (
+                "(a < b) + (a <= 1) + (a >= b) + !a + !!b + (a || false)",
+                4.0,
+            ),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(
+                eval(source, &[Some(1.0), Some(2.0)]),
+                Ok(Some(expected)),
+                "{source}"
+            );
+        }
+
+        // 输入尚无值时不产生结果；条件未选中的分支不需要输入
+        assert_eq!(eval("a + b", &[Some(1.0), None]), Ok(None));
+        assert_eq!(eval("if(a, 5, b)", &[Some(1.0), None]), Ok(Some(5.0)));
+        assert_eq!(eval("a || b", &[Some(1.0), None]), Ok(Some(1.0)));
+        assert_eq!(
+            eval("a / b", &[Some(1.0), Some(0.0)]),
+            Err(FormulaError::DivisionByZero)
+        );
+        assert_eq!(eval("sqrt(a)", &[Some(-1.0)]), Err(FormulaError::NotFinite));
+
+        let errors = [
+            ("", FormulaError::Empty),
+            ("a +", FormulaError::UnexpectedEnd),
+            ("a # b", FormulaError::UnexpectedChar('#', 3)),
+            ("a + 1.2.3", FormulaError::InvalidNumber(5)),
+            ("[a", FormulaError::InvalidReference(1)),
+            ("[0:a] + [b c]", FormulaError::InvalidReference(1)),
+            ("(a + b", FormulaError::UnexpectedEnd),
+            ("a b", FormulaError::UnexpectedToken("b".to_string(), 3)),
+            (
+                "a < b < c",
+                FormulaError::UnexpectedToken("<".to_string(), 7),
+            ),
+            (
+                "pow(a, 2)",
+                FormulaError::UnknownFunction("pow".to_string()),
+            ),
+            ("abs(a, b)", FormulaError::Arity("abs", "1 argument")),
+            ("max()", FormulaError::Arity("max", "at least 1 argument")),
+            ("if(a, 1)", FormulaError::Arity("if", "3 arguments")),
+            ("1 + 2", FormulaError::NoReference),
+        ];
+        for (source, expected) in errors {
+            assert_eq!(Formula::parse(source), Err(expected), "{source}");
+        }
+        assert_eq!(
+            Formula::parse(&"a".repeat(MAX_LENGTH + 1)),
+            Err(FormulaError::TooLong)
+        );
+        assert_eq!(
+            Formula::parse(&format!("{}a{}", "(".repeat(40), ")".repeat(40))),
+            Err(FormulaError::TooDeep)
+        );
+        assert_eq!(
+            Formula::parse(&vec!["a"; 200].join(" + ")),
+            Err(FormulaError::TooComplex)
+        );
+    }
+
+    #[test]
+    fn tracks_history_for_time_functions() {
+        let formula = Formula::parse("delta(energy) + rate(energy) * 0").expect("parse");
+        let mut history = formula.history();
+        assert_eq!(
+            formula.evaluate(&[Some(100.0)], &mut history, 1_000),
+            Ok(None)
+        );
+        assert_eq!(
+            formula.evaluate(&[Some(130.0)], &mut history, 3_000),
+            Ok(Some(30.0))
+        );
+        // 同一时间戳重复计算时不更新历史
+        assert_eq!(
+            formula.evaluate(&[Some(150.0)], &mut history, 3_000),
+            Ok(None)
+        );
+        let rate = Formula::parse("rate(energy) * 3600").expect("parse");
+        let mut history = rate.history();
+        assert_eq!(rate.evaluate(&[Some(10.0)], &mut history, 0), Ok(None));
+        assert_eq!(
+            rate.evaluate(&[Some(12.0)], &mut history, 2_000),
+            Ok(Some(3600.0))
+        );
+    }
+
+    #[test]
+    fn records_history_in_untaken_branches() {
+        // 条件切换时，未选中分支中的时间函数仍按每次计算更新历史
+        let formula = Formula::parse("if(mode, delta(energy), 0)").expect("parse");
+        let mut history = formula.history();
+        let mut run = |mode: f64, energy: f64, now| {
+            formula.evaluate(&[Some(mode), Some(energy)], &mut history, now)
+        };
+        assert_eq!(run(1.0, 100.0, 1_000), Ok(None));
+        assert_eq!(run(0.0, 130.0, 2_000), Ok(Some(0.0)));
+        assert_eq!(run(0.0, 150.0, 3_000), Ok(Some(0.0)));
+        assert_eq!(run(1.0, 160.0, 4_000), Ok(Some(10.0)));
+
+        // 短路求值同样不跳过时间函数
+        let formula = Formula::parse("enabled && delta(energy) > 5").expect("parse");
+        let mut history = formula.history();
+        let mut run = |enabled: f64, energy: f64, now| {
+            formula.evaluate(&[Some(enabled), Some(energy)], &mut history, now)
+        };
+        assert_eq!(run(0.0, 100.0, 1_000), Ok(Some(0.0)));
+        assert_eq!(run(0.0, 120.0, 2_000), Ok(Some(0.0)));
+        assert_eq!(run(1.0, 124.0, 3_000), Ok(Some(0.0)));
+        assert_eq!(run(1.0, 134.0, 4_000), Ok(Some(1.0)));
+
+        // 未选中分支中的计算错误不影响结果
+        let formula = Formula::parse("if(b, delta(a / b), a)").expect("parse");
+        let mut history = formula.history();
+        assert_eq!(
+            formula.evaluate(&[Some(0.0), Some(4.0)], &mut history, 1_000),
+            Ok(Some(4.0))
+        );
+        assert_eq!(
+            formula.evaluate(&[Some(2.0), Some(4.0)], &mut history, 2_000),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn resolves_references_and_orders_dependencies() {
+        let point = |point_id, unit_id, point_key: &str, expression: Option<&str>| CatalogPoint {
+            point_id,
+            point_key: point_key.to_string(),
+            unit_id,
+            numeric: point_key != "serial",
+            expression: expression.map(ToString::to_string),
+        };
+        let points = [
+            point(1, 1, "p1", None),
+            point(2, 2, "p1", None),
+            point(3, 1, "serial", None),
+            point(4, 1, "total", Some("p1 + [2:p1] + avg_power")),
+            point(5, 1, "avg_power", Some("[2:p1] / 2")),
+            point(6, 1, "bad", Some("[3:p1] + serial")),
+            point(7, 1, "text", Some("serial * 2")),
+        ];
+        let resolved = resolve(&points);
+        assert_eq!(resolved[&4].clone().map(|r| r.inputs), Ok(vec![1, 2, 5]));
+        assert_eq!(
+            resolved[&6].clone().map(|r| r.inputs),
+            Err(FormulaError::UnknownPoint(PointRef {
+                unit_id: Some(3),
+                point_key: "p1".to_string(),
+            }))
+        );
+        assert_eq!(
+            resolved[&7].as_ref().err().map(ToString::to_string),
+            Some("point [serial] is not numeric".to_string())
+        );
+
+        let dependencies: BTreeMap<i64, Vec<i64>> = resolved
+            .into_iter()
+            .filter_map(|(id, result)| result.ok().map(|result| (id, result.inputs)))
+            .collect();
+        assert_eq!(evaluation_order(&dependencies), Ok(vec![5, 4]));
+        let cyclic = BTreeMap::from([(4, vec![1, 5]), (5, vec![6]), (6, vec![4])]);
+        assert_eq!(evaluation_order(&cyclic), Err(vec![4, 5, 6, 4]));
+    }
+}
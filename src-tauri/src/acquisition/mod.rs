@@ -0,0 +1,26 @@
+//! 数据采集模块入口
+//!
+//! 本模块在后端按周期采集网关点位：
+//! - 读取计划：从站的点位按功能码与地址合并为读取块，可配置间隙容忍度，单块不超过一次请求的上限
+//! - 采集引擎：每个网关一个后台线程，按网关或从站的轮询周期调度，响应切片解码为最新点位值
+//! - 值变换：解码后的原始值经过位域提取、枚举映射、缩放偏移、单位换算与限幅得到工程值，可配置死区
+//! - 虚拟点位：按公式由同一网关内其他点位的值计算，输入点位更新后按依赖顺序重新计算
+//! - 点位表变更后自动重新生成读取计划；周期数、超时周期、启动抖动与周期耗时可通过状态命令查询
+//! - 实时推送：值变化按订阅过滤、节流后以 Tauri 事件推送，新打开的页面可先查询快照
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 采集控制请求与采集状态、最新值、订阅与值变化事件结构
+pub mod models;
+// 公开读取计划模块 - 读取块合并与响应切片
+pub mod planner;
+// 公开值变换模块 - 工程值变换、写入反变换与死区
+pub mod transform;
+// 公开公式模块 - 虚拟点位公式的解析、计算与依赖排序
+pub mod formula;
+// 公开引擎模块 - 采集线程、调度与统计
+pub mod engine;
+// 公开实时推送模块 - 订阅过滤、节流合并与事件发送
+pub mod telemetry;
+// 公开服务模块 - 权限校验与采集控制
+pub mod services;
@@ -0,0 +1,248 @@
+//! 数据采集模块数据模型
+//!
+//! 本模块定义采集控制命令的请求体，采集状态（读取计划、周期统计）与最新点位值的响应结构，
+//! 以及实时推送的订阅与值变化事件
+
+// 引入有序映射（事件中的点位值按点位标识排序）
+use std::collections::BTreeMap;
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+// 启动或停止采集请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionGatewayPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+}
+
+// 采集状态请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionStatusPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID（为空时返回全部采集中的网关）
+    pub gateway_id: Option<i64>,
+}
+
+// 最新点位值请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionValuesPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+    /// 从站 ID（为空时返回网关下全部从站的点位）
+    pub slave_id: Option<i64>,
+}
+
+// 网关采集状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionStatusData {
+    /// 网关 ID
+    pub gateway_id: i64,
+    /// 网关编码（长连接的网关标识）
+    pub gateway_code: String,
+    /// 采集线程是否运行中
+    pub running: bool,
+    /// 启动时间戳（毫秒）
+    pub started_at: i64,
+    /// 最近一次生成读取计划的时间戳（毫秒）
+    pub plan_loaded_at: Option<i64>,
+    /// 读取计划生成次数（点位表变更后自动重新生成）
+    pub plan_loads: u64,
+    /// 合并读取允许跨越的最大空闲地址数
+    pub max_gap: u16,
+    /// 最近一次网关级错误（读取配置失败、网关被删除等）
+    pub last_error: Option<String>,
+    /// 从站采集状态（按单元号排序）
+    pub slaves: Vec<AcquisitionSlaveStatus>,
+}
+
+// 从站采集状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionSlaveStatus {
+    /// 从站 ID
+    pub slave_id: i64,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 从站名称
+    pub name: String,
+    /// 生效的轮询周期（毫秒，从站未配置时为网关的轮询周期）
+    pub poll_interval_ms: u64,
+    /// 读取块
+    pub blocks: Vec<AcquisitionBlockStatus>,
+    /// 已执行的采集周期数
+    pub cycles: u64,
+    /// 超时周期数（周期结束时已错过下一次计划时间）
+    pub overruns: u64,
+    /// 因超时而跳过的计划周期数
+    pub skipped_cycles: u64,
+    /// 最近一次周期的启动抖动（实际启动时间与计划时间之差，毫秒）
+    pub last_jitter_ms: u64,
+    /// 最大启动抖动（毫秒）
+    pub max_jitter_ms: u64,
+    /// 最近一次周期耗时（毫秒）
+    pub last_duration_ms: u64,
+    /// 最大周期耗时（毫秒）
+    pub max_duration_ms: u64,
+    /// 最近一次周期的时间戳（毫秒）
+    pub last_cycle_at: Option<i64>,
+    /// 最近一次周期的错误（周期内全部读取成功时为空）
+    pub last_error: Option<String>,
+}
+
+// 读取块状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionBlockStatus {
+    /// 读取功能码
+    pub function_code: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 读取数量
+    pub count: u16,
+    /// 点位实际占用的地址数（其余为合并时多读的空闲地址）
+    pub used_count: u16,
+    /// 块内点位标识
+    pub point_keys: Vec<String>,
+    /// 请求次数
+    pub requests: u64,
+    /// 失败次数
+    pub failures: u64,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+}
+
+// 最新点位值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionValueData {
+    /// 点位 ID
+    pub point_id: i64,
+    /// 点位标识
+    pub point_key: String,
+    /// 所属从站 ID
+    pub slave_id: i64,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 最近一次上报的工程值（经过值变换；变化未超过死区时保持上一次的值；尚未读取成功时为 null）
+    pub value: serde_json::Value,
+    /// 最近一次读取的错误（读取成功时为空，失败时保留上一次的值）
+    pub error: Option<String>,
+    /// 最近一次成功读取的时间戳（毫秒）
+    pub updated_at: Option<i64>,
+}
+
+// 订阅过滤条件（各条件之间取并集，全部为空时不过滤）
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionFilterSpec {
+    /// 网关 ID
+    pub gateway_ids: Vec<i64>,
+    /// 从站 ID
+    pub slave_ids: Vec<i64>,
+    /// 点位 ID（页面展示的点位）
+    pub point_ids: Vec<i64>,
+    /// 设备标识（按设备的通信配置引用 `modbus:<网关编码>/<单元号>` 匹配从站）
+    pub device_ids: Vec<String>,
+}
+
+// 订阅值变化事件请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionSubscribePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 页面标识（同一操作员以相同页面重新订阅时替换原订阅）
+    pub page: Option<String>,
+    /// 过滤条件
+    pub filter: AcquisitionFilterSpec,
+    /// 节流间隔（毫秒，同一从站两次事件的最小间隔，0 表示不节流）
+    pub throttle_ms: u64,
+    /// 节流期间是否合并变化（默认合并；不合并时丢弃节流期间的变化）
+    pub coalesce: Option<bool>,
+}
+
+// 取消订阅请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionUnsubscribePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 订阅 ID
+    pub subscription_id: Option<u64>,
+    /// 页面标识（未指定订阅 ID 时按页面取消）
+    pub page: Option<String>,
+}
+
+// 订阅列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionSubscriptionListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 最新值快照请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AcquisitionSnapshotPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 订阅 ID（指定时使用订阅的过滤条件）
+    pub subscription_id: Option<u64>,
+    /// 过滤条件（未指定订阅 ID 时使用）
+    pub filter: AcquisitionFilterSpec,
+}
+
+// 订阅信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionSubscriptionData {
+    /// 订阅 ID
+    pub subscription_id: u64,
+    /// 事件名称（前端以此名称监听值变化事件）
+    pub event: String,
+    /// 页面标识
+    pub page: Option<String>,
+    /// 过滤条件
+    pub filter: AcquisitionFilterSpec,
+    /// 节流间隔（毫秒）
+    pub throttle_ms: u64,
+    /// 节流期间是否合并变化
+    pub coalesce: bool,
+    /// 订阅时间戳（毫秒）
+    pub created_at: i64,
+    /// 已发送的事件数
+    pub events_sent: u64,
+    /// 节流期间合并的变化数
+    pub changes_coalesced: u64,
+    /// 节流期间丢弃的变化数（不合并时）
+    pub changes_dropped: u64,
+}
+
+// 值变化事件（同一从站一次采集周期内变化的点位）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquisitionValueEvent {
+    /// 采集时间戳（毫秒）
+    pub timestamp: i64,
+    /// 网关 ID
+    pub gateway_id: i64,
+    /// 网关编码
+    pub gateway_code: String,
+    /// 从站 ID
+    pub slave_id: i64,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 点位标识 → 值
+    pub values: BTreeMap<String, serde_json::Value>,
+}
@@ -0,0 +1,334 @@
+//! 读取计划
+//!
+//! 将从站的点位表合并为尽量少的读取请求：
+//! - 按功能码分组、按起始地址排序，相邻或重叠的点位合并为同一个读取块
+//! - 两个点位之间的空闲地址不超过间隙容忍度时同样合并（多读的地址直接丢弃）
+//! - 单个读取块不超过一次请求的上限（寄存器 125 个，线圈与离散输入 2000 个）
+//!
+//! 响应按点位在读取块内的偏移切片，再按数据类型与字节序解码（值变换由采集引擎在解码后执行）。
+//! 本模块为纯函数，不涉及通信与存储。
+
+// 引入寄存器编解码（数据类型与字节序）
+use crate::modbus::codec::{self, ByteOrder, CodecError, DataType, PointValue};
+// 引入点位值变换
+use crate::acquisition::transform::PointTransform;
+// 引入 Modbus 请求与响应类型以及单次读取上限
+use crate::modbus::protocol::{MAX_READ_BITS, MAX_READ_REGISTERS, ModbusError, Request, Response};
+
+/// 计划中的点位
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanPoint {
+    pub point_id: i64,             // 点位 ID
+    pub point_key: String,         // 点位标识
+    pub function_code: u8,         // 读取功能码（1 / 2 / 3 / 4）
+    pub address: u16,              // 起始地址
+    pub count: u16,                // 寄存器（或线圈）数量
+    pub data_type: DataType,       // 数据类型
+    pub byte_order: ByteOrder,     // 字节序
+    pub transform: PointTransform, // 值变换
+}
+
+/// 点位的切片解码结果
+pub type PointResult<'a> = (&'a PlanPoint, Result<PointValue, CodecError>);
+
+/// 读取块（一次读取请求覆盖的地址区间及其中的点位）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadBlock {
+    pub function_code: u8,      // 读取功能码
+    pub address: u16,           // 起始地址
+    pub count: u16,             // 读取数量
+    pub points: Vec<PlanPoint>, // 块内点位（按地址排序）
+}
+
+impl ReadBlock {
+    /// 读取请求
+    pub fn request(&self) -> Request {
+        let (address, count) = (self.address, self.count);
+        match self.function_code {
+            1 => Request::ReadCoils { address, count },
+            2 => Request::ReadDiscreteInputs { address, count },
+            3 => Request::ReadHoldingRegisters { address, count },
+            _ => Request::ReadInputRegisters { address, count },
+        }
+    }
+
+    /// 块内点位占用的地址数（不含为合并而多读的空闲地址）
+    pub fn used_count(&self) -> u16 {
+        let mut used = 0_u32;
+        let mut covered_to = u32::from(self.address);
+        for point in &self.points {
+            let start = u32::from(point.address).max(covered_to);
+            let end = u32::from(point.address) + u32::from(point.count);
+            if end > start {
+                used += end - start;
+                covered_to = end;
+            }
+        }
+        u16::try_from(used).unwrap_or(u16::MAX)
+    }
+}
+
+/// 功能码的单次读取上限（线圈与离散输入 2000 个，寄存器 125 个）
+pub fn max_block_count(function_code: u8) -> u16 {
+    if function_code <= 2 {
+        MAX_READ_BITS
+    } else {
+        MAX_READ_REGISTERS
+    }
+}
+
+/// 生成读取计划
+///
+/// # 参数
+/// * `points` - 从站的点位
+/// * `max_gap` - 合并时允许跨越的最大空闲地址数（0 表示只合并连续或重叠的地址）
+///
+/// # 返回
+/// * 按功能码与起始地址排序的读取块
+pub fn build_plan(mut points: Vec<PlanPoint>, max_gap: u16) -> Vec<ReadBlock> {
+    points.sort_by_key(|point| {
+        (
+            point.function_code,
+            point.address,
+            point.count,
+            point.point_id,
+        )
+    });
+    let mut blocks: Vec<ReadBlock> = Vec::new();
+    for point in points {
+        let end = u32::from(point.address) + u32::from(point.count);
+        if let Some(block) = blocks
+            .last_mut()
+            .filter(|block| block.function_code == point.function_code)
+        {
+            let block_end = u32::from(block.address) + u32::from(block.count);
+            let merged_end = block_end.max(end);
+            let within_gap = u32::from(point.address) <= block_end + u32::from(max_gap);
+            let within_limit = merged_end - u32::from(block.address)
+                <= u32::from(max_block_count(point.function_code));
+            if within_gap && within_limit {
+                block.count =
+                    u16::try_from(merged_end - u32::from(block.address)).unwrap_or(u16::MAX);
+                block.points.push(point);
+                continue;
+            }
+        }
+        blocks.push(ReadBlock {
+            function_code: point.function_code,
+            address: point.address,
+            count: point.count,
+            points: vec![point],
+        });
+    }
+    blocks
+}
+
+/// 按点位切片读取块的响应并解码
+///
+/// # 参数
+/// * `block` - 读取块
+/// * `response` - 读取块请求的响应
+///
+/// # 返回
+/// * 每个点位的解码结果（单个点位解码失败不影响其他点位）；响应类型或长度不符时返回协议错误
+pub fn slice<'a>(
+    block: &'a ReadBlock,
+    response: &Response,
+) -> Result<Vec<PointResult<'a>>, ModbusError> {
+    let (bits, registers): (&[bool], &[u16]) = match response {
+        Response::Bits(bits) if block.function_code <= 2 => (bits, &[]),
+        Response::Registers(registers) if block.function_code > 2 => (&[], registers),
+        _ => {
+            return Err(ModbusError::Protocol(
+                "unexpected response for read block".to_string(),
+            ));
+        }
+    };
+    let expected = usize::from(block.count);
+    let received = bits.len().max(registers.len());
+    if received < expected {
+        return Err(ModbusError::Protocol(format!(
+            "expected {expected} values, got {received}"
+        )));
+    }
+    Ok(block
+        .points
+        .iter()
+        .map(|point| {
+            let offset = usize::from(point.address - block.address);
+            let value = if block.function_code <= 2 {
+                Ok(PointValue::Bool(bits[offset]))
+            } else {
+                codec::decode(
+                    point.data_type,
+                    point.byte_order,
+                    &registers[offset..offset + usize::from(point.count)],
+                )
+            };
+            (point, value)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(point_id: i64, function_code: u8, address: u16, data_type: DataType) -> PlanPoint {
+        PlanPoint {
+            point_id,
+            point_key: format!("p{point_id}"),
+            function_code,
+            address,
+            count: data_type.register_count().unwrap_or(1),
+            data_type,
+            byte_order: data_type.default_byte_order(),
+            transform: PointTransform::default(),
+        }
+    }
+
+    // 读取块的（功能码、起始地址、数量、点位 ID）
+    fn layout(blocks: &[ReadBlock]) -> Vec<(u8, u16, u16, Vec<i64>)> {
+        blocks
+            .iter()
+            .map(|block| {
+                (
+                    block.function_code,
+                    block.address,
+                    block.count,
+                    block.points.iter().map(|point| point.point_id).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merges_adjacent_points_within_gap_and_limits() {
+        let points = vec![
+            point(1, 3, 0, DataType::F32),
+            point(2, 3, 2, DataType::U16),
+            point(3, 3, 6, DataType::U32),
+            point(4, 4, 3, DataType::I16),
+            point(5, 1, 0, DataType::Bool),
+            point(6, 1, 1, DataType::Bool),
+            point(7, 3, 1, DataType::U16),
+        ];
+
+        // 间隙为 0：只合并连续或重叠的地址，不同功能码分开读取
+        assert_eq!(
+            layout(&build_plan(points.clone(), 0)),
+            vec![
+                (1, 0, 2, vec![5, 6]),
+                (3, 0, 3, vec![1, 7, 2]),
+                (3, 6, 2, vec![3]),
+                (4, 3, 1, vec![4]),
+            ]
+        );
+        // 间隙为 3：地址 3–5 空闲，合并为一次读取
+        let blocks = build_plan(points, 3);
+        assert_eq!(
+            layout(&blocks),
+            vec![
+                (1, 0, 2, vec![5, 6]),
+                (3, 0, 8, vec![1, 7, 2, 3]),
+                (4, 3, 1, vec![4]),
+            ]
+        );
+        assert_eq!(blocks[1].used_count(), 5);
+        assert_eq!(
+            blocks[1].request(),
+            Request::ReadHoldingRegisters {
+                address: 0,
+                count: 8
+            }
+        );
+
+        // 寄存器块不超过 125 个，线圈块不超过 2000 个
+        let blocks = build_plan(
+            vec![
+                point(1, 3, 0, DataType::U16),
+                point(2, 3, 123, DataType::U32),
+                point(3, 3, 124, DataType::U32),
+            ],
+            125,
+        );
+        assert_eq!(
+            layout(&blocks),
+            vec![(3, 0, 125, vec![1, 2]), (3, 124, 2, vec![3])]
+        );
+        let blocks = build_plan(
+            vec![
+                point(4, 2, 0, DataType::Bool),
+                point(5, 2, 1999, DataType::Bool),
+                point(6, 2, 2000, DataType::Bool),
+            ],
+            2000,
+        );
+        assert_eq!(
+            layout(&blocks),
+            vec![(2, 0, 2000, vec![4, 5]), (2, 2000, 1, vec![6])]
+        );
+        assert!(build_plan(Vec::new(), 10).is_empty());
+    }
+
+    #[test]
+    fn slices_responses_into_point_values() {
+        let mut text = point(3, 3, 4, DataType::String);
+        text.count = 2;
+        let mut swapped = point(1, 3, 0, DataType::U32);
+        swapped.byte_order = ByteOrder::CDAB;
+        let blocks = build_plan(vec![swapped, point(2, 3, 2, DataType::I16), text], 1);
+        assert_eq!(blocks.len(), 1);
+        let values: Vec<(i64, Result<PointValue, CodecError>)> = slice(
+            &blocks[0],
+            &Response::Registers(vec![0x5678, 0x1234, 0xFFFE, 0, 0x4142, 0x4300]),
+        )
+        .expect("slice registers")
+        .into_iter()
+        .map(|(point, value)| (point.point_id, value))
+        .collect();
+        assert_eq!(
+            values,
+            vec![
+                (1, Ok(PointValue::Integer(0x1234_5678))),
+                (2, Ok(PointValue::Integer(-2))),
+                (3, Ok(PointValue::Text("ABC".to_string()))),
+            ]
+        );
+
+        // 单个点位解码失败不影响其他点位
+        let values = slice(
+            &blocks[0],
+            &Response::Registers(vec![0, 1, 7, 0, 0xFFFE, 0]),
+        )
+        .expect("slice registers");
+        assert_eq!(values[1].1, Ok(PointValue::Integer(7)));
+        assert_eq!(values[2].1, Err(CodecError::InvalidText));
+
+        let coils = build_plan(
+            vec![
+                point(4, 1, 10, DataType::Bool),
+                point(5, 1, 12, DataType::Bool),
+            ],
+            1,
+        );
+        let values =
+            slice(&coils[0], &Response::Bits(vec![true, true, false, false])).expect("slice bits");
+        assert_eq!(values[0].1, Ok(PointValue::Bool(true)));
+        assert_eq!(values[1].1, Ok(PointValue::Bool(false)));
+
+        // 响应类型或长度不符
+        assert!(matches!(
+            slice(&blocks[0], &Response::Registers(vec![0; 5])),
+            Err(ModbusError::Protocol(_))
+        ));
+        assert!(matches!(
+            slice(&blocks[0], &Response::Bits(vec![false; 6])),
+            Err(ModbusError::Protocol(_))
+        ));
+        assert!(matches!(
+            slice(&coils[0], &Response::Written),
+            Err(ModbusError::Protocol(_))
+        ));
+    }
+}
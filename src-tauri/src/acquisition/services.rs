@@ -0,0 +1,368 @@
+//! 数据采集模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 启动与停止网关采集：校验网关存在且已启用，采集线程以网关编码注册长连接（与点位读写测试共用）
+//! - 查询采集状态：读取计划（读取块与块内点位）、每个从站的周期数、超时周期、启动抖动与周期耗时
+//! - 查询最新点位值：按网关或从站返回最近一次采集的值与错误
+//! - 实时推送订阅：按网关、从站、点位或设备过滤（设备按通信配置引用 `modbus:<网关编码>/<单元号>` 解析，
+//!   须在操作员的设备范围内），可设置节流间隔与是否合并；快照按同样的过滤条件返回当前值。
+//!   受设备范围约束的操作员的过滤条件与其可访问设备所在的从站取交集，未设置条件即范围内的全部从站；
+//!   范围在订阅时解析，之后的范围调整需重新订阅
+//! - 权限校验：`device:view`（状态、最新值、订阅与快照）、`device:manage`（启动与停止）
+//!
+//! 采集停止后长连接保留在全局连接表中，可通过 `modbus_disconnect` 断开。
+
+use std::collections::{BTreeSet, HashSet};
+
+// 引入数据采集模型
+use crate::acquisition::models::{
+    AcquisitionFilterSpec, AcquisitionGatewayPayload, AcquisitionSnapshotPayload,
+    AcquisitionStatusData, AcquisitionStatusPayload, AcquisitionSubscribePayload,
+    AcquisitionSubscriptionData, AcquisitionSubscriptionListPayload, AcquisitionUnsubscribePayload,
+    AcquisitionValueData, AcquisitionValueEvent, AcquisitionValuesPayload,
+};
+// 引入采集引擎
+use crate::acquisition::engine;
+// 引入实时推送
+use crate::acquisition::telemetry::{self, DeviceTarget, Filter, SubscriptionInput};
+// 引入用户设备范围服务
+use crate::auth::device_scope_services::{self, DeviceAccessFilter};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入设备服务（操作员校验）
+use crate::device::services as device_services;
+// 引入设备数据访问层
+use crate::device::repository as device_repository;
+// 引入通信网关仓储模块
+use crate::gateway::repository as gateway_repository;
+
+// 节流间隔上限（毫秒）
+const MAX_THROTTLE_MS: u64 = 60_000;
+
+// 页面标识最大长度
+const MAX_PAGE_LENGTH: usize = 128;
+
+/// 启动网关采集
+///
+/// 已在采集中时不重复启动，直接返回当前状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 采集状态
+pub fn start(
+    payload: &AcquisitionGatewayPayload,
+    now_millis: u64,
+) -> Result<AcquisitionStatusData, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let gateway = gateway_repository::find_gateway(payload.gateway_id)?
+        .ok_or_else(|| AppError::Validation("gateway not found".to_string()))?;
+    if !gateway.input.enabled {
+        return Err(AppError::Validation("gateway is disabled".to_string()));
+    }
+    engine::start(gateway.id)?;
+    find_status(gateway.id)
+}
+
+/// 停止网关采集（等待当前周期结束）
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 停止时的采集状态
+pub fn stop(
+    payload: &AcquisitionGatewayPayload,
+    now_millis: u64,
+) -> Result<AcquisitionStatusData, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    engine::stop(payload.gateway_id).ok_or_else(not_running)
+}
+
+/// 查询采集状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按网关 ID 排序的采集状态（含已自行结束的采集，例如网关被删除）
+pub fn status(
+    payload: &AcquisitionStatusPayload,
+    now_millis: u64,
+) -> Result<Vec<AcquisitionStatusData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    Ok(engine::status(payload.gateway_id))
+}
+
+/// 查询最新点位值
+///
+/// # 参数
+/// * `payload` - 操作员用户名、网关 ID 与可选的从站 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按点位 ID 排序的最新值（尚未采集的点位不在结果中）
+pub fn values(
+    payload: &AcquisitionValuesPayload,
+    now_millis: u64,
+) -> Result<Vec<AcquisitionValueData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    engine::values(payload.gateway_id, payload.slave_id).ok_or_else(not_running)
+}
+
+/// 查询单个网关的采集状态
+fn find_status(gateway_id: i64) -> Result<AcquisitionStatusData, AppError> {
+    engine::status(Some(gateway_id))
+        .pop()
+        .ok_or_else(not_running)
+}
+
+/// 网关未启动采集
+fn not_running() -> AppError {
+    AppError::Validation("acquisition is not running".to_string())
+}
+
+/// 订阅值变化事件
+///
+/// # 参数
+/// * `payload` - 操作员用户名、页面标识、过滤条件与节流参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 订阅信息（前端以其中的 `event` 监听值变化事件）
+pub fn subscribe(
+    payload: &AcquisitionSubscribePayload,
+    now_millis: u64,
+) -> Result<AcquisitionSubscriptionData, AppError> {
+    let (operator_username, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    if payload.throttle_ms > MAX_THROTTLE_MS {
+        return Err(AppError::Validation(format!(
+            "throttleMs must be between 0 and {MAX_THROTTLE_MS}"
+        )));
+    }
+    let page = normalize_page(payload.page.as_deref())?;
+    let spec = normalize_filter(&payload.filter);
+    let filter = resolve_filter(&spec, user_id, now)?;
+    Ok(telemetry::subscribe(SubscriptionInput {
+        operator_username,
+        page,
+        spec,
+        filter,
+        throttle_ms: payload.throttle_ms,
+        coalesce: payload.coalesce.unwrap_or(true),
+        created_at: now,
+    }))
+}
+
+/// 取消订阅
+///
+/// # 参数
+/// * `payload` - 操作员用户名与订阅 ID（或页面标识）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 是否取消成功
+pub fn unsubscribe(
+    payload: &AcquisitionUnsubscribePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let (operator_username, _, _) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let page = normalize_page(payload.page.as_deref())?;
+    if payload.subscription_id.is_none() && page.is_none() {
+        return Err(AppError::Validation(
+            "subscriptionId or page is required".to_string(),
+        ));
+    }
+    if !telemetry::unsubscribe(&operator_username, payload.subscription_id, page.as_deref()) {
+        return Err(subscription_not_found());
+    }
+    Ok(true)
+}
+
+/// 查询操作员的订阅
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按订阅 ID 排序的订阅信息（含发送、合并与丢弃计数）
+pub fn subscriptions(
+    payload: &AcquisitionSubscriptionListPayload,
+    now_millis: u64,
+) -> Result<Vec<AcquisitionSubscriptionData>, AppError> {
+    let (operator_username, _, _) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    Ok(telemetry::subscriptions(&operator_username))
+}
+
+/// 查询最新值快照
+///
+/// # 参数
+/// * `payload` - 操作员用户名与订阅 ID（或过滤条件）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按网关与从站汇总的当前值，结构与值变化事件相同
+pub fn snapshot(
+    payload: &AcquisitionSnapshotPayload,
+    now_millis: u64,
+) -> Result<Vec<AcquisitionValueEvent>, AppError> {
+    let (operator_username, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let filter = match payload.subscription_id {
+        Some(subscription_id) => telemetry::find_filter(&operator_username, subscription_id)
+            .ok_or_else(subscription_not_found)?,
+        None => resolve_filter(&normalize_filter(&payload.filter), user_id, now)?,
+    };
+    Ok(engine::snapshot()
+        .iter()
+        .filter_map(|change| filter.select(change))
+        .collect())
+}
+
+/// 规范化页面标识（去除首尾空白，空字符串视为未指定）
+fn normalize_page(page: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(page) = page.map(str::trim).filter(|page| !page.is_empty()) else {
+        return Ok(None);
+    };
+    if page.chars().count() > MAX_PAGE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "page must be at most {MAX_PAGE_LENGTH} characters"
+        )));
+    }
+    Ok(Some(page.to_string()))
+}
+
+/// 规范化过滤条件（排序去重，设备标识去除首尾空白）
+fn normalize_filter(spec: &AcquisitionFilterSpec) -> AcquisitionFilterSpec {
+    fn sorted<T: Ord + Clone>(items: &[T]) -> Vec<T> {
+        let mut items = items.to_vec();
+        items.sort();
+        items.dedup();
+        items
+    }
+    let device_ids: Vec<String> = spec
+        .device_ids
+        .iter()
+        .map(|device_id| device_id.trim().to_string())
+        .collect();
+    AcquisitionFilterSpec {
+        gateway_ids: sorted(&spec.gateway_ids),
+        slave_ids: sorted(&spec.slave_ids),
+        point_ids: sorted(&spec.point_ids),
+        device_ids: sorted(&device_ids),
+    }
+}
+
+/// 生成过滤器：设备须在操作员的设备范围内，并按通信配置引用解析为网关编码与单元号；
+/// 过滤器限定在操作员可访问的从站内
+fn resolve_filter(
+    spec: &AcquisitionFilterSpec,
+    user_id: i64,
+    now_millis: i64,
+) -> Result<Filter, AppError> {
+    let mut devices = Vec::with_capacity(spec.device_ids.len());
+    for device_id in &spec.device_ids {
+        let record = device_services::ensure_device_accessible(user_id, device_id, now_millis)?;
+        let target = record
+            .comm_config_ref
+            .as_deref()
+            .and_then(parse_comm_config_ref)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "device {device_id} commConfigRef must be modbus:<gatewayCode>/<unitId>"
+                ))
+            })?;
+        devices.push(target);
+    }
+    let filter = Filter::new(spec, devices);
+    Ok(match resolve_scope(user_id, now_millis)? {
+        Some(scope) => filter.within(scope),
+        None => filter,
+    })
+}
+
+/// 解析操作员可访问的从站（None 表示可访问全部设备）
+///
+/// 可访问设备按通信配置引用解析为网关编码与单元号，未绑定从站的设备不对应任何从站
+pub(crate) fn resolve_scope(
+    user_id: i64,
+    now_millis: i64,
+) -> Result<Option<BTreeSet<DeviceTarget>>, AppError> {
+    let DeviceAccessFilter::Devices(device_ids) =
+        device_scope_services::resolve_accessible_devices(user_id, now_millis)?
+    else {
+        return Ok(None);
+    };
+    let device_ids: HashSet<String> = device_ids.into_iter().collect();
+    Ok(Some(
+        device_repository::list_devices_by_comm_prefix("modbus:")?
+            .into_iter()
+            .filter(|record| device_ids.contains(&record.device_id))
+            .filter_map(|record| {
+                record
+                    .comm_config_ref
+                    .as_deref()
+                    .and_then(parse_comm_config_ref)
+            })
+            .collect(),
+    ))
+}
+
+/// 解析设备的通信配置引用 `modbus:<网关编码>/<单元号>`
+pub(crate) fn parse_comm_config_ref(value: &str) -> Option<(String, u8)> {
+    let (gateway_code, unit_id) = value.trim().strip_prefix("modbus:")?.rsplit_once('/')?;
+    let unit_id = unit_id.trim().parse::<u8>().ok()?;
+    let gateway_code = gateway_code.trim();
+    (!gateway_code.is_empty() && unit_id > 0).then(|| (gateway_code.to_string(), unit_id))
+}
+
+/// 订阅不存在（或不属于操作员）
+fn subscription_not_found() -> AppError {
+    AppError::Validation("subscription not found".to_string())
+}
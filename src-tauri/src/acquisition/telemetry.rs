@@ -0,0 +1,672 @@
+//! 实时推送
+//!
+//! 采集引擎每个从站周期结束后发布变化的点位值，本模块按订阅分发为 Tauri 事件：
+//! - 订阅按网关、从站、点位或设备过滤（各条件之间取并集），只推送命中的点位；
+//!   受设备范围约束的操作员只能收到其可访问设备所在从站的值，未设置条件即订阅范围内的全部从站
+//! - 每个订阅使用独立的事件名称（`acquisition:values:<订阅 ID>`），前端只收到自己订阅的数据
+//! - 可按订阅设置节流间隔：同一从站两次事件之间不少于节流间隔，期间的变化按点位合并（保留最新值）
+//!   后在间隔结束时推送，或在不合并时直接丢弃
+//!
+//! 订阅保存在内存中，应用重启后需重新订阅。事件在不持有订阅表锁的情况下发送。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Condvar, Mutex, Once, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::acquisition::models::{
+    AcquisitionFilterSpec, AcquisitionSubscriptionData, AcquisitionValueEvent,
+};
+use crate::core::sync::lock;
+
+// 值变化事件名称前缀
+const EVENT_PREFIX: &str = "acquisition:values:";
+
+/// 变化的点位值
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedPoint {
+    pub point_id: i64,     // 点位 ID
+    pub point_key: String, // 点位标识
+    pub value: Value,      // 新值
+}
+
+/// 一个从站在一次采集周期内的值变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub timestamp: i64,            // 采集时间戳（毫秒）
+    pub gateway_id: i64,           // 网关 ID
+    pub gateway_code: String,      // 网关编码
+    pub slave_id: i64,             // 从站 ID
+    pub unit_id: u8,               // 从站单元号
+    pub points: Vec<ChangedPoint>, // 变化的点位
+}
+
+/// 设备对应的（网关编码，单元号）
+pub type DeviceTarget = (String, u8);
+
+/// 订阅过滤条件（设备已解析为网关编码与单元号）
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    gateway_ids: BTreeSet<i64>,            // 网关 ID
+    slave_ids: BTreeSet<i64>,              // 从站 ID
+    point_ids: BTreeSet<i64>,              // 点位 ID
+    devices: BTreeSet<DeviceTarget>,       // 设备对应的（网关编码，单元号）
+    scope: Option<BTreeSet<DeviceTarget>>, // 操作员可访问的从站（None 表示不受限）
+}
+
+impl Filter {
+    /// 由过滤条件与设备解析结果生成过滤器
+    pub fn new(spec: &AcquisitionFilterSpec, devices: Vec<DeviceTarget>) -> Self {
+        Self {
+            gateway_ids: spec.gateway_ids.iter().copied().collect(),
+            slave_ids: spec.slave_ids.iter().copied().collect(),
+            point_ids: spec.point_ids.iter().copied().collect(),
+            devices: devices.into_iter().collect(),
+            scope: None,
+        }
+    }
+
+    /// 限定在操作员可访问的从站内（与其他条件取交集）
+    #[must_use]
+    pub fn within(mut self, scope: BTreeSet<DeviceTarget>) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    // 从站是否在操作员的设备范围内
+    fn in_scope(&self, change: &ValueChange) -> bool {
+        self.scope
+            .as_ref()
+            .is_none_or(|scope| scope.contains(&(change.gateway_code.clone(), change.unit_id)))
+    }
+
+    // 是否未设置任何条件
+    fn is_empty(&self) -> bool {
+        self.gateway_ids.is_empty()
+            && self.slave_ids.is_empty()
+            && self.point_ids.is_empty()
+            && self.devices.is_empty()
+    }
+
+    // 从站整体命中（网关、从站或设备条件）
+    fn matches_slave(&self, change: &ValueChange) -> bool {
+        self.gateway_ids.contains(&change.gateway_id)
+            || self.slave_ids.contains(&change.slave_id)
+            || self
+                .devices
+                .contains(&(change.gateway_code.clone(), change.unit_id))
+    }
+
+    /// 按过滤条件选取变化中的点位，生成值变化事件（没有命中的点位时返回 None）
+    pub fn select(&self, change: &ValueChange) -> Option<AcquisitionValueEvent> {
+        if !self.in_scope(change) {
+            return None;
+        }
+        let whole = self.is_empty() || self.matches_slave(change);
+        let values: BTreeMap<String, Value> = change
+            .points
+            .iter()
+            .filter(|point| whole || self.point_ids.contains(&point.point_id))
+            .map(|point| (point.point_key.clone(), point.value.clone()))
+            .collect();
+        (!values.is_empty()).then(|| AcquisitionValueEvent {
+            timestamp: change.timestamp,
+            gateway_id: change.gateway_id,
+            gateway_code: change.gateway_code.clone(),
+            slave_id: change.slave_id,
+            unit_id: change.unit_id,
+            values,
+        })
+    }
+}
+
+/// 新订阅的参数
+#[derive(Debug, Clone)]
+pub struct SubscriptionInput {
+    pub operator_username: String,   // 操作员用户名
+    pub page: Option<String>,        // 页面标识
+    pub spec: AcquisitionFilterSpec, // 过滤条件（原样返回给前端）
+    pub filter: Filter,              // 过滤器
+    pub throttle_ms: u64,            // 节流间隔（毫秒）
+    pub coalesce: bool,              // 节流期间是否合并变化
+    pub created_at: i64,             // 订阅时间戳（毫秒）
+}
+
+// 待发送的事件（事件名称，事件内容）
+type Outgoing = (String, AcquisitionValueEvent);
+
+// 事件发送函数
+type Sink = Arc<dyn Fn(&str, &AcquisitionValueEvent) + Send + Sync>;
+
+// 订阅在一个从站上的节流窗口
+struct Window {
+    last_sent: Instant,                     // 最近一次发送时间
+    pending: Option<AcquisitionValueEvent>, // 节流期间合并的待发送事件
+}
+
+// 订阅
+struct Subscription {
+    operator_username: String,            // 操作员用户名
+    data: AcquisitionSubscriptionData,    // 订阅信息与计数
+    filter: Filter,                       // 过滤器
+    throttle: Duration,                   // 节流间隔
+    windows: HashMap<(i64, i64), Window>, // （网关 ID，从站 ID）→ 节流窗口
+}
+
+impl Subscription {
+    // 处理一个命中的事件，需要立即发送时返回事件
+    fn offer(&mut self, event: AcquisitionValueEvent, now: Instant) -> Option<Outgoing> {
+        if self.throttle.is_zero() {
+            return Some(self.sent(event));
+        }
+        let key = (event.gateway_id, event.slave_id);
+        let ready = match self.windows.get_mut(&key) {
+            None => {
+                self.windows.insert(
+                    key,
+                    Window {
+                        last_sent: now,
+                        pending: None,
+                    },
+                );
+                Some(event)
+            }
+            Some(window) if now.saturating_duration_since(window.last_sent) >= self.throttle => {
+                window.last_sent = now;
+                Some(merge(window.pending.take(), event))
+            }
+            Some(window) => {
+                if self.data.coalesce {
+                    window.pending = Some(merge(window.pending.take(), event));
+                    self.data.changes_coalesced += 1;
+                } else {
+                    self.data.changes_dropped += 1;
+                }
+                None
+            }
+        };
+        ready.map(|event| self.sent(event))
+    }
+
+    // 取出节流间隔已结束的待发送事件
+    fn flush(&mut self, now: Instant) -> Vec<Outgoing> {
+        let throttle = self.throttle;
+        let events: Vec<AcquisitionValueEvent> = self
+            .windows
+            .values_mut()
+            .filter(|window| {
+                window.pending.is_some()
+                    && now.saturating_duration_since(window.last_sent) >= throttle
+            })
+            .filter_map(|window| {
+                window.last_sent = now;
+                window.pending.take()
+            })
+            .collect();
+        events.into_iter().map(|event| self.sent(event)).collect()
+    }
+
+    // 最早的待发送时间
+    fn next_due(&self) -> Option<Instant> {
+        self.windows
+            .values()
+            .filter(|window| window.pending.is_some())
+            .map(|window| window.last_sent + self.throttle)
+            .min()
+    }
+
+    // 记录一次发送
+    fn sent(&mut self, event: AcquisitionValueEvent) -> Outgoing {
+        self.data.events_sent += 1;
+        (self.data.event.clone(), event)
+    }
+}
+
+// 合并节流期间的事件：点位取最新值，时间戳取最新一次采集
+fn merge(
+    pending: Option<AcquisitionValueEvent>,
+    event: AcquisitionValueEvent,
+) -> AcquisitionValueEvent {
+    match pending {
+        Some(mut pending) => {
+            pending.timestamp = event.timestamp;
+            pending.values.extend(event.values);
+            pending
+        }
+        None => event,
+    }
+}
+
+// 订阅表
+#[derive(Default)]
+struct Hub {
+    next_id: u64,                               // 上一个订阅 ID
+    subscriptions: BTreeMap<u64, Subscription>, // 订阅 ID → 订阅
+}
+
+impl Hub {
+    // 新增订阅（同一操作员的同一页面只保留最新的订阅）
+    fn subscribe(&mut self, input: SubscriptionInput) -> AcquisitionSubscriptionData {
+        if let Some(page) = &input.page {
+            self.subscriptions.retain(|_, subscription| {
+                subscription.operator_username != input.operator_username
+                    || subscription.data.page.as_ref() != Some(page)
+            });
+        }
+        self.next_id += 1;
+        let subscription_id = self.next_id;
+        let data = AcquisitionSubscriptionData {
+            subscription_id,
+            event: format!("{EVENT_PREFIX}{subscription_id}"),
+            page: input.page,
+            filter: input.spec,
+            throttle_ms: input.throttle_ms,
+            coalesce: input.coalesce,
+            created_at: input.created_at,
+            events_sent: 0,
+            changes_coalesced: 0,
+            changes_dropped: 0,
+        };
+        self.subscriptions.insert(
+            subscription_id,
+            Subscription {
+                operator_username: input.operator_username,
+                data: data.clone(),
+                filter: input.filter,
+                throttle: Duration::from_millis(input.throttle_ms),
+                windows: HashMap::new(),
+            },
+        );
+        data
+    }
+
+    // 取消操作员的订阅（按订阅 ID 或页面）
+    fn unsubscribe(
+        &mut self,
+        operator_username: &str,
+        subscription_id: Option<u64>,
+        page: Option<&str>,
+    ) -> bool {
+        let before = self.subscriptions.len();
+        self.subscriptions.retain(|id, subscription| {
+            let owned = subscription.operator_username == operator_username;
+            let selected = match subscription_id {
+                Some(subscription_id) => *id == subscription_id,
+                None => subscription.data.page.as_deref() == page,
+            };
+            !(owned && selected)
+        });
+        self.subscriptions.len() < before
+    }
+
+    // 分发值变化，返回需要立即发送的事件
+    fn publish(&mut self, change: &ValueChange, now: Instant) -> Vec<Outgoing> {
+        self.subscriptions
+            .values_mut()
+            .filter_map(|subscription| {
+                let event = subscription.filter.select(change)?;
+                subscription.offer(event, now)
+            })
+            .collect()
+    }
+
+    // 取出节流间隔已结束的待发送事件，并返回下一次待发送时间
+    fn flush(&mut self, now: Instant) -> (Vec<Outgoing>, Option<Instant>) {
+        let mut outgoing = Vec::new();
+        for subscription in self.subscriptions.values_mut() {
+            outgoing.extend(subscription.flush(now));
+        }
+        let next_due = self
+            .subscriptions
+            .values()
+            .filter_map(Subscription::next_due)
+            .min();
+        (outgoing, next_due)
+    }
+}
+
+// 订阅表与节流唤醒条件
+fn hub() -> &'static (Mutex<Hub>, Condvar) {
+    static HUB: OnceLock<(Mutex<Hub>, Condvar)> = OnceLock::new();
+    HUB.get_or_init(|| (Mutex::new(Hub::default()), Condvar::new()))
+}
+
+// 事件发送函数（应用启动时设置为 Tauri 事件发送）
+fn sink() -> &'static Mutex<Option<Sink>> {
+    static SINK: OnceLock<Mutex<Option<Sink>>> = OnceLock::new();
+    SINK.get_or_init(|| Mutex::new(None))
+}
+
+// 发送事件（未设置发送函数时丢弃）
+fn emit(outgoing: Vec<Outgoing>) {
+    if outgoing.is_empty() {
+        return;
+    }
+    let Some(sink) = lock(sink()).clone() else {
+        return;
+    };
+    for (event, payload) in outgoing {
+        sink(&event, &payload);
+    }
+}
+
+// 启动节流发送线程（只启动一次）：在节流间隔结束时发送合并的事件
+fn ensure_dispatcher() {
+    static DISPATCHER: Once = Once::new();
+    DISPATCHER.call_once(|| {
+        let spawned = std::thread::Builder::new()
+            .name("acquisition-telemetry".to_string())
+            .spawn(|| {
+                let (mutex, wake) = hub();
+                let mut guard = lock(mutex);
+                loop {
+                    let now = Instant::now();
+                    let (outgoing, next_due) = guard.flush(now);
+                    if !outgoing.is_empty() {
+                        drop(guard);
+                        emit(outgoing);
+                        guard = lock(mutex);
+                        continue;
+                    }
+                    guard = match next_due {
+                        Some(due) => {
+                            wake.wait_timeout(guard, due.saturating_duration_since(now))
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .0
+                        }
+                        None => wake.wait(guard).unwrap_or_else(PoisonError::into_inner),
+                    };
+                }
+            });
+        if let Err(err) = spawned {
+            tracing::warn!(error = %err, "start acquisition telemetry dispatcher failed");
+        }
+    });
+}
+
+/// 设置 Tauri 应用句柄，值变化事件通过 Tauri 事件系统发送给前端
+pub fn set_app_handle(handle: AppHandle) {
+    set_sink(Arc::new(move |event, payload| {
+        if let Err(err) = handle.emit(event, payload) {
+            tracing::warn!(event, error = %err, "emit acquisition event failed");
+        }
+    }));
+}
+
+/// 设置事件发送函数
+pub(crate) fn set_sink(sink_fn: Sink) {
+    *lock(sink()) = Some(sink_fn);
+}
+
+/// 新增订阅
+///
+/// 设置了节流间隔时启动节流发送线程
+pub fn subscribe(input: SubscriptionInput) -> AcquisitionSubscriptionData {
+    if input.throttle_ms > 0 {
+        ensure_dispatcher();
+    }
+    lock(&hub().0).subscribe(input)
+}
+
+/// 取消操作员的订阅
+///
+/// # 参数
+/// * `operator_username` - 操作员用户名（只能取消自己的订阅）
+/// * `subscription_id` - 订阅 ID
+/// * `page` - 页面标识（未指定订阅 ID 时按页面取消）
+///
+/// # 返回
+/// * 是否取消了订阅
+pub fn unsubscribe(
+    operator_username: &str,
+    subscription_id: Option<u64>,
+    page: Option<&str>,
+) -> bool {
+    lock(&hub().0).unsubscribe(operator_username, subscription_id, page)
+}
+
+/// 查询操作员的订阅（按订阅 ID 排序）
+pub fn subscriptions(operator_username: &str) -> Vec<AcquisitionSubscriptionData> {
+    lock(&hub().0)
+        .subscriptions
+        .values()
+        .filter(|subscription| subscription.operator_username == operator_username)
+        .map(|subscription| subscription.data.clone())
+        .collect()
+}
+
+/// 查询操作员订阅的过滤器
+pub fn find_filter(operator_username: &str, subscription_id: u64) -> Option<Filter> {
+    lock(&hub().0)
+        .subscriptions
+        .get(&subscription_id)
+        .filter(|subscription| subscription.operator_username == operator_username)
+        .map(|subscription| subscription.filter.clone())
+}
+
+/// 发布一个从站的值变化
+///
+/// 由采集线程在周期结束后调用；未节流或节流间隔已结束的订阅立即发送，其余合并或丢弃
+pub fn publish(change: &ValueChange) {
+    let (mutex, wake) = hub();
+    let outgoing = lock(mutex).publish(change, Instant::now());
+    wake.notify_all();
+    emit(outgoing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 事件名称、从站 ID 与点位值
+    type Summary = Vec<(String, i64, Vec<(String, Value)>)>;
+
+    fn change(slave_id: i64, unit_id: u8, points: &[(i64, &str, Value)]) -> ValueChange {
+        ValueChange {
+            timestamp: 1_000,
+            gateway_id: 1,
+            gateway_code: "gw-01".to_string(),
+            slave_id,
+            unit_id,
+            points: points
+                .iter()
+                .map(|(point_id, point_key, value)| ChangedPoint {
+                    point_id: *point_id,
+                    point_key: (*point_key).to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn input(
+        page: Option<&str>,
+        filter: Filter,
+        throttle_ms: u64,
+        coalesce: bool,
+    ) -> SubscriptionInput {
+        SubscriptionInput {
+            operator_username: "admin".to_string(),
+            page: page.map(String::from),
+            spec: AcquisitionFilterSpec::default(),
+            filter,
+            throttle_ms,
+            coalesce,
+            created_at: 0,
+        }
+    }
+
+    // 事件名称 → （从站 ID，点位标识 → 值）
+    fn summary(outgoing: &[Outgoing]) -> Summary {
+        outgoing
+            .iter()
+            .map(|(event, payload)| {
+                (
+                    event.clone(),
+                    payload.slave_id,
+                    payload
+                        .values
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filters_by_slave_point_and_device() {
+        let meter = change(10, 1, &[(100, "ua", json!(220)), (101, "ub", json!(221))]);
+        let pump = change(11, 2, &[(110, "run", json!(true))]);
+        let all = Filter::default();
+        assert_eq!(all.select(&meter).expect("all").values.len(), 2);
+
+        // 点位条件只推送命中的点位；设备条件按（网关编码，单元号）命中整个从站
+        let page = Filter::new(
+            &AcquisitionFilterSpec {
+                point_ids: vec![101],
+                ..AcquisitionFilterSpec::default()
+            },
+            vec![("gw-01".to_string(), 2)],
+        );
+        let selected = page.select(&meter).expect("page point");
+        assert_eq!(
+            selected.values.into_iter().collect::<Vec<_>>(),
+            vec![("ub".to_string(), json!(221))]
+        );
+        assert_eq!(
+            page.select(&pump).expect("device").values.get("run"),
+            Some(&json!(true))
+        );
+        let other = Filter::new(
+            &AcquisitionFilterSpec {
+                gateway_ids: vec![2],
+                slave_ids: vec![12],
+                ..AcquisitionFilterSpec::default()
+            },
+            vec![("gw-02".to_string(), 1)],
+        );
+        assert!(other.select(&meter).is_none());
+        let by_slave = Filter::new(
+            &AcquisitionFilterSpec {
+                slave_ids: vec![11],
+                ..AcquisitionFilterSpec::default()
+            },
+            Vec::new(),
+        );
+        assert!(by_slave.select(&meter).is_none());
+        assert!(by_slave.select(&pump).is_some());
+    }
+
+    #[test]
+    fn scope_limits_every_filter() {
+        let meter = change(10, 1, &[(100, "ua", json!(220))]);
+        let pump = change(11, 2, &[(110, "run", json!(true))]);
+        let scope = BTreeSet::from([("gw-01".to_string(), 2)]);
+
+        // 未设置条件即范围内的全部从站；网关、点位条件不能越出范围
+        let all = Filter::default().within(scope.clone());
+        assert!(all.select(&meter).is_none());
+        assert!(all.select(&pump).is_some());
+        let by_gateway = Filter::new(
+            &AcquisitionFilterSpec {
+                gateway_ids: vec![1],
+                point_ids: vec![100],
+                ..AcquisitionFilterSpec::default()
+            },
+            Vec::new(),
+        )
+        .within(scope);
+        assert!(by_gateway.select(&meter).is_none());
+        assert!(by_gateway.select(&pump).is_some());
+        assert!(
+            Filter::default()
+                .within(BTreeSet::new())
+                .select(&pump)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn throttles_with_coalescing_or_dropping_per_subscriber() {
+        let mut hub = Hub::default();
+        let immediate = hub.subscribe(input(None, Filter::default(), 0, true));
+        let merged = hub.subscribe(input(Some("dashboard"), Filter::default(), 100, true));
+        let sampled = hub.subscribe(input(Some("trend"), Filter::default(), 100, false));
+        assert_eq!(
+            (
+                immediate.event.as_str(),
+                merged.subscription_id,
+                sampled.subscription_id
+            ),
+            ("acquisition:values:1", 2, 3)
+        );
+        let start = Instant::now();
+
+        // 首个变化对所有订阅立即发送
+        let first = hub.publish(&change(10, 1, &[(100, "ua", json!(1))]), start);
+        assert_eq!(first.len(), 3);
+
+        // 节流期间：合并的订阅保留每个点位的最新值，不合并的订阅丢弃变化
+        let later = start + Duration::from_millis(40);
+        let second = hub.publish(
+            &change(10, 1, &[(100, "ua", json!(2)), (101, "ub", json!(5))]),
+            later,
+        );
+        let third = hub.publish(&change(10, 1, &[(100, "ua", json!(3))]), later);
+        assert_eq!(summary(&second).len() + summary(&third).len(), 2);
+        assert!(
+            second
+                .iter()
+                .chain(&third)
+                .all(|(event, _)| event == &immediate.event)
+        );
+        // 其他从站有独立的节流窗口
+        assert_eq!(
+            hub.publish(&change(11, 2, &[(110, "run", json!(true))]), later)
+                .len(),
+            3
+        );
+
+        let (flushed, next_due) = hub.flush(later);
+        assert!(flushed.is_empty());
+        assert_eq!(next_due, Some(start + Duration::from_millis(100)));
+        let (flushed, next_due) = hub.flush(start + Duration::from_millis(100));
+        assert_eq!(
+            summary(&flushed),
+            vec![(
+                merged.event.clone(),
+                10,
+                vec![("ua".to_string(), json!(3)), ("ub".to_string(), json!(5))]
+            )]
+        );
+        assert!(next_due.is_none());
+        let counters: Vec<(u64, u64, u64)> = hub
+            .subscriptions
+            .values()
+            .map(|subscription| {
+                (
+                    subscription.data.events_sent,
+                    subscription.data.changes_coalesced,
+                    subscription.data.changes_dropped,
+                )
+            })
+            .collect();
+        assert_eq!(counters, vec![(4, 0, 0), (3, 2, 0), (2, 0, 2)]);
+
+        // 同一页面重新订阅替换原订阅；按页面或订阅 ID 取消，只能取消自己的订阅
+        let replaced = hub.subscribe(input(Some("dashboard"), Filter::default(), 0, true));
+        assert_eq!(
+            hub.subscriptions.keys().copied().collect::<Vec<_>>(),
+            vec![1, 3, replaced.subscription_id]
+        );
+        assert!(!hub.unsubscribe("operator", Some(1), None));
+        assert!(hub.unsubscribe("admin", None, Some("trend")));
+        assert!(hub.unsubscribe("admin", Some(1), None));
+        assert!(!hub.unsubscribe("admin", Some(1), None));
+        assert_eq!(hub.subscriptions.len(), 1);
+    }
+}
@@ -0,0 +1,865 @@
+//! 点位值变换
+//!
+//! 点位按数据类型与字节序解码后，依次经过以下步骤得到工程值：
+//! - 位域提取：从整数寄存器中取出 `bitLength` 位（状态字），结果为非负整数
+//! - 枚举映射：整数（布尔按 0 / 1）映射为文本，映射后不再执行其余步骤
+//! - 缩放与偏移：`原始值 * scale + offset`
+//! - 单位换算：由 `sourceUnit` 换算为点位的工程单位（温度含零点偏移）
+//! - 限幅：截断到 `[clampMin, clampMax]`
+//!
+//! 写入设定值时按相反顺序反变换：枚举文本还原为原始值，超出限幅范围的值拒绝写入，整数类型四舍五入；
+//! 位域点位只替换寄存器中的对应位（由调用方读取寄存器当前值后合并）。
+//! 死区在采集时判断：与最近一次上报值之差不超过死区的变化视为未变化。
+//!
+//! 本模块为纯函数，不涉及通信与存储。
+
+// 引入有序映射（枚举映射按原始值排序）
+use std::collections::BTreeMap;
+
+// 引入 JSON 值类型（采集保存的最新值）
+use serde_json::Value;
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入点位值变换配置
+use crate::gateway::models::PointTransformSpec;
+// 引入点位数据类型与点位值
+use crate::modbus::codec::{DataType, PointValue};
+
+// 死区方式：绝对值
+const DEADBAND_ABSOLUTE: &str = "absolute";
+
+// 死区方式：最近一次上报值的百分比
+const DEADBAND_PERCENT: &str = "percent";
+
+// 整数写入值的最大绝对值（超出后 f64 无法精确表示整数）
+const MAX_EXACT_INTEGER: f64 = 9.0e15;
+
+// 单位换算表：（单位，物理量，系数，偏移），基准单位的值 = 值 * 系数 + 偏移
+const UNITS: &[(&str, &str, f64, f64)] = &[
+    ("K", "temperature", 1.0, 0.0),
+    ("°C", "temperature", 1.0, 273.15),
+    ("℃", "temperature", 1.0, 273.15),
+    ("°F", "temperature", 5.0 / 9.0, 459.67 * 5.0 / 9.0),
+    ("℉", "temperature", 5.0 / 9.0, 459.67 * 5.0 / 9.0),
+    ("Pa", "pressure", 1.0, 0.0),
+    ("kPa", "pressure", 1.0e3, 0.0),
+    ("MPa", "pressure", 1.0e6, 0.0),
+    ("mbar", "pressure", 1.0e2, 0.0),
+    ("bar", "pressure", 1.0e5, 0.0),
+    ("psi", "pressure", 6_894.757_293_168, 0.0),
+    ("W", "power", 1.0, 0.0),
+    ("kW", "power", 1.0e3, 0.0),
+    ("MW", "power", 1.0e6, 0.0),
+    ("Wh", "energy", 1.0, 0.0),
+    ("kWh", "energy", 1.0e3, 0.0),
+    ("MWh", "energy", 1.0e6, 0.0),
+    ("J", "energy", 1.0 / 3600.0, 0.0),
+    ("kJ", "energy", 1.0e3 / 3600.0, 0.0),
+    ("MJ", "energy", 1.0e6 / 3600.0, 0.0),
+    ("mV", "voltage", 1.0e-3, 0.0),
+    ("V", "voltage", 1.0, 0.0),
+    ("kV", "voltage", 1.0e3, 0.0),
+    ("mA", "current", 1.0e-3, 0.0),
+    ("A", "current", 1.0, 0.0),
+    ("kA", "current", 1.0e3, 0.0),
+    ("Hz", "frequency", 1.0, 0.0),
+    ("kHz", "frequency", 1.0e3, 0.0),
+    ("L/s", "flow", 1.0, 0.0),
+    ("L/min", "flow", 1.0 / 60.0, 0.0),
+    ("L/h", "flow", 1.0 / 3600.0, 0.0),
+    ("m³/h", "flow", 1.0e3 / 3600.0, 0.0),
+    ("m3/h", "flow", 1.0e3 / 3600.0, 0.0),
+    ("mm", "length", 1.0e-3, 0.0),
+    ("cm", "length", 1.0e-2, 0.0),
+    ("m", "length", 1.0, 0.0),
+    ("km", "length", 1.0e3, 0.0),
+    ("ms", "time", 1.0e-3, 0.0),
+    ("s", "time", 1.0, 0.0),
+    ("min", "time", 60.0, 0.0),
+    ("h", "time", 3600.0, 0.0),
+];
+
+/// 值变换错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TransformError {
+    /// 原始值不在枚举映射中
+    #[error("value {0} is not in enumMap")]
+    Unmapped(i64),
+    /// 写入的枚举文本或原始值不在枚举映射中
+    #[error("value must be one of {0}")]
+    UnknownLabel(String),
+    /// 写入值不是数字
+    #[error("value must be a number")]
+    NotNumeric,
+    /// 写入值低于限幅下限
+    #[error("value must be at least {0}")]
+    BelowMin(f64),
+    /// 写入值高于限幅上限
+    #[error("value must be at most {0}")]
+    AboveMax(f64),
+    /// 写入值超出位域范围
+    #[error("value must be between 0 and {0} for the bit field")]
+    BitFieldRange(u64),
+    /// 反变换后的值超出数据类型范围
+    #[error("value out of range for data type {}", .0.as_str())]
+    OutOfRange(DataType),
+}
+
+/// 转换为应用错误：变换错误均为配置或写入值问题，归为校验错误
+impl From<TransformError> for AppError {
+    fn from(err: TransformError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+/// 位域（整数寄存器中的连续若干位）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitField {
+    offset: u8,   // 起始位（0 为最低位）
+    length: u8,   // 位数
+    width: u8,    // 数据类型的位宽（16 / 32）
+    signed: bool, // 数据类型是否有符号
+}
+
+impl BitField {
+    // 位域掩码（未移位）
+    fn mask(self) -> u64 {
+        (1_u64 << self.length) - 1
+    }
+
+    /// 从原始整数值中取出位域
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    pub fn extract(self, raw: i64) -> i64 {
+        ((raw as u64 >> self.offset) & self.mask()) as i64
+    }
+
+    /// 将位域值写入原始整数值的对应位（其余位保持不变）
+    ///
+    /// # 参数
+    /// * `current` - 寄存器当前的原始整数值
+    /// * `field` - 位域值
+    ///
+    /// # 返回
+    /// * 合并后的原始整数值（有符号类型按补码还原为负数）
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    pub fn insert(self, current: i64, field: i64) -> i64 {
+        let width_mask = (1_u64 << self.width) - 1;
+        let cleared = current as u64 & width_mask & !(self.mask() << self.offset);
+        let bits = cleared | ((field as u64 & self.mask()) << self.offset);
+        if self.signed && (bits >> (self.width - 1)) & 1 == 1 {
+            bits as i64 - (1_i64 << self.width)
+        } else {
+            bits as i64
+        }
+    }
+}
+
+// 死区
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Deadband {
+    Absolute(f64), // 绝对值
+    Percent(f64),  // 最近一次上报值的百分比
+}
+
+/// 已校验的点位值变换（默认值为不变换）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointTransform {
+    bits: Option<BitField>,            // 位域
+    enum_map: BTreeMap<i64, String>,   // 枚举映射（为空表示不映射）
+    affine: Option<(f64, f64)>,        // 缩放、偏移与单位换算合成的线性变换（系数，偏移）
+    clamp: (Option<f64>, Option<f64>), // 限幅（下限，上限）
+    deadband: Option<Deadband>,        // 死区
+}
+
+impl PointTransform {
+    /// 校验值变换配置
+    ///
+    /// # 参数
+    /// * `spec` - 值变换配置
+    /// * `data_type` - 点位数据类型
+    /// * `unit` - 点位的工程单位（单位换算的目标单位）
+    ///
+    /// # 返回
+    /// * 已校验的值变换
+    pub fn compile(
+        spec: &PointTransformSpec,
+        data_type: DataType,
+        unit: Option<&str>,
+    ) -> Result<Self, AppError> {
+        let spec = normalize(spec);
+        if spec == PointTransformSpec::default() {
+            return Ok(Self::default());
+        }
+        if data_type == DataType::String {
+            return Err(invalid("not supported for dataType string"));
+        }
+        let numeric = spec.scale.is_some()
+            || spec.offset.is_some()
+            || spec.source_unit.is_some()
+            || spec.clamp_min.is_some()
+            || spec.clamp_max.is_some()
+            || spec.deadband.is_some();
+        let has_bits = spec.bit_offset.is_some() || spec.bit_length.is_some();
+        if data_type == DataType::Bool && (numeric || has_bits) {
+            return Err(invalid("only enumMap is supported for dataType bool"));
+        }
+        let bits = if has_bits {
+            Some(bit_field(&spec, data_type)?)
+        } else {
+            None
+        };
+        let enum_map = match &spec.enum_map {
+            Some(_) if numeric => {
+                return Err(invalid(
+                    "enumMap cannot be combined with scale, offset, sourceUnit, clamp or deadband",
+                ));
+            }
+            Some(map) => enum_map(map, data_type)?,
+            None => BTreeMap::new(),
+        };
+        let scale = spec.scale.unwrap_or(1.0);
+        if !scale.is_finite() || scale == 0.0 {
+            return Err(invalid("scale must be a non-zero number"));
+        }
+        let offset = spec.offset.unwrap_or(0.0);
+        if !offset.is_finite() {
+            return Err(invalid("offset must be a finite number"));
+        }
+        let (factor, shift) = match &spec.source_unit {
+            Some(source) => unit_conversion(source, unit)?,
+            None => (1.0, 0.0),
+        };
+        let affine = (spec.scale.is_some() || spec.offset.is_some() || spec.source_unit.is_some())
+            .then_some((scale * factor, offset * factor + shift));
+        let clamp = (spec.clamp_min, spec.clamp_max);
+        if [clamp.0, clamp.1]
+            .into_iter()
+            .flatten()
+            .any(|bound| !bound.is_finite())
+        {
+            return Err(invalid("clampMin and clampMax must be finite numbers"));
+        }
+        if let (Some(min), Some(max)) = clamp {
+            if min > max {
+                return Err(invalid("clampMin must not exceed clampMax"));
+            }
+        }
+        let deadband = match spec.deadband {
+            Some(value) if !value.is_finite() || value < 0.0 => {
+                return Err(invalid("deadband must not be negative"));
+            }
+            Some(value) => match spec.deadband_mode.as_deref() {
+                None | Some(DEADBAND_ABSOLUTE) => Some(Deadband::Absolute(value)),
+                Some(DEADBAND_PERCENT) => Some(Deadband::Percent(value)),
+                Some(_) => {
+                    return Err(invalid("deadbandMode must be one of absolute, percent"));
+                }
+            },
+            None => None,
+        };
+        Ok(Self {
+            bits,
+            enum_map,
+            affine,
+            clamp,
+            deadband,
+        })
+    }
+
+    /// 是否不做任何变换
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 位域（写入时由调用方读取寄存器当前值后合并）
+    pub fn bit_field(&self) -> Option<BitField> {
+        self.bits
+    }
+
+    /// 将解码后的原始值变换为工程值
+    ///
+    /// # 参数
+    /// * `value` - 按数据类型与字节序解码后的原始值
+    ///
+    /// # 返回
+    /// * 工程值：枚举映射为文本；经过缩放或单位换算为浮点；其余保持原类型
+    pub fn apply(&self, value: PointValue) -> Result<PointValue, TransformError> {
+        let mut value = value;
+        if let (Some(bits), PointValue::Integer(raw)) = (self.bits, &value) {
+            value = PointValue::Integer(bits.extract(*raw));
+        }
+        if !self.enum_map.is_empty() {
+            let key = match value {
+                PointValue::Bool(raw) => i64::from(raw),
+                PointValue::Integer(raw) => raw,
+                other => return Ok(other),
+            };
+            return self
+                .enum_map
+                .get(&key)
+                .map(|label| PointValue::Text(label.clone()))
+                .ok_or(TransformError::Unmapped(key));
+        }
+        if let Some((factor, shift)) = self.affine {
+            if let Some(raw) = as_float(&value) {
+                value = PointValue::Float(raw * factor + shift);
+            }
+        }
+        Ok(match value {
+            PointValue::Integer(raw) => PointValue::Integer(self.clamp_integer(raw)),
+            PointValue::Float(raw) => PointValue::Float(self.clamp_float(raw)),
+            other => other,
+        })
+    }
+
+    /// 将写入的工程值反变换为原始值
+    ///
+    /// # 参数
+    /// * `data_type` - 点位数据类型
+    /// * `value` - 工程值（枚举映射可为文本或原始整数值）
+    ///
+    /// # 返回
+    /// * 待编码的原始值；位域点位为位域值（由调用方合并到寄存器当前值）
+    pub fn inverse(
+        &self,
+        data_type: DataType,
+        value: PointValue,
+    ) -> Result<PointValue, TransformError> {
+        if self.is_identity() {
+            return Ok(value);
+        }
+        if !self.enum_map.is_empty() {
+            let key = match &value {
+                PointValue::Text(label) => self
+                    .enum_map
+                    .iter()
+                    .find(|(_, candidate)| candidate.as_str() == label.trim())
+                    .map(|(key, _)| *key),
+                PointValue::Integer(key) => self.enum_map.contains_key(key).then_some(*key),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                TransformError::UnknownLabel(
+                    self.enum_map
+                        .values()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            })?;
+            return if data_type == DataType::Bool {
+                Ok(PointValue::Bool(key != 0))
+            } else {
+                self.raw_integer(key)
+            };
+        }
+        let engineering = as_float(&value).ok_or(TransformError::NotNumeric)?;
+        if let Some(min) = self.clamp.0.filter(|min| engineering < *min) {
+            return Err(TransformError::BelowMin(min));
+        }
+        if let Some(max) = self.clamp.1.filter(|max| engineering > *max) {
+            return Err(TransformError::AboveMax(max));
+        }
+        let raw = self.affine.map_or(engineering, |(factor, shift)| {
+            (engineering - shift) / factor
+        });
+        if self.bits.is_none() && matches!(data_type, DataType::F32 | DataType::F64) {
+            return Ok(PointValue::Float(raw));
+        }
+        let rounded = raw.round();
+        if rounded.abs() > MAX_EXACT_INTEGER {
+            return Err(TransformError::OutOfRange(data_type));
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.raw_integer(rounded as i64)
+    }
+
+    /// 判断新值相对最近一次上报值是否超出死区
+    ///
+    /// # 参数
+    /// * `previous` - 最近一次上报的值
+    /// * `current` - 本次读取的值
+    ///
+    /// # 返回
+    /// * 超出死区（或未配置死区且值不同）时为 true；非数字值按是否相等判断
+    pub fn is_significant(&self, previous: &Value, current: &Value) -> bool {
+        let Some(deadband) = self.deadband else {
+            return previous != current;
+        };
+        let (Some(previous), Some(current)) = (previous.as_f64(), current.as_f64()) else {
+            return previous != current;
+        };
+        let threshold = match deadband {
+            Deadband::Absolute(value) => value,
+            Deadband::Percent(percent) => previous.abs() * percent / 100.0,
+        };
+        (current - previous).abs() > threshold
+    }
+
+    // 校验位域值（未配置位域时直接返回整数原始值）
+    fn raw_integer(&self, raw: i64) -> Result<PointValue, TransformError> {
+        if let Some(bits) = self.bits {
+            if !u64::try_from(raw).is_ok_and(|raw| raw <= bits.mask()) {
+                return Err(TransformError::BitFieldRange(bits.mask()));
+            }
+        }
+        Ok(PointValue::Integer(raw))
+    }
+
+    // 整数限幅（边界向区间内取整）
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn clamp_integer(&self, value: i64) -> i64 {
+        let mut value = value;
+        if let Some(min) = self.clamp.0 {
+            if (value as f64) < min {
+                value = min.ceil() as i64;
+            }
+        }
+        if let Some(max) = self.clamp.1 {
+            if (value as f64) > max {
+                value = max.floor() as i64;
+            }
+        }
+        value
+    }
+
+    // 浮点限幅
+    fn clamp_float(&self, value: f64) -> f64 {
+        let value = self.clamp.0.map_or(value, |min| value.max(min));
+        self.clamp.1.map_or(value, |max| value.min(max))
+    }
+}
+
+/// 规范化值变换配置（去除空白、枚举键规范为整数文本、死区方式小写，未配置死区时忽略死区方式）
+///
+/// # 参数
+/// * `spec` - 值变换配置
+///
+/// # 返回
+/// * 规范化后的配置（保存到点位表）
+pub fn normalize(spec: &PointTransformSpec) -> PointTransformSpec {
+    let text = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    PointTransformSpec {
+        enum_map: spec.enum_map.as_ref().map(|map| {
+            map.iter()
+                .map(|(key, label)| {
+                    let key = key.trim();
+                    let key = key
+                        .parse::<i64>()
+                        .map_or_else(|_| key.to_string(), |key| key.to_string());
+                    (key, label.trim().to_string())
+                })
+                .collect()
+        }),
+        source_unit: text(spec.source_unit.as_deref()),
+        deadband_mode: spec
+            .deadband
+            .and(text(spec.deadband_mode.as_deref()))
+            .map(|mode| mode.to_ascii_lowercase()),
+        ..spec.clone()
+    }
+}
+
+// 构造校验错误
+fn invalid(message: &str) -> AppError {
+    AppError::Validation(message.to_string())
+}
+
+// 按浮点读取数值
+#[allow(clippy::cast_precision_loss)]
+fn as_float(value: &PointValue) -> Option<f64> {
+    match value {
+        PointValue::Integer(value) => Some(*value as f64),
+        PointValue::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+// 校验位域（仅限整数类型，起始位与长度不超过数据类型位宽）
+fn bit_field(spec: &PointTransformSpec, data_type: DataType) -> Result<BitField, AppError> {
+    let (width, signed) = match data_type {
+        DataType::U16 => (16, false),
+        DataType::I16 => (16, true),
+        DataType::U32 => (32, false),
+        DataType::I32 => (32, true),
+        _ => return Err(invalid("bitOffset requires an integer dataType")),
+    };
+    let offset = spec.bit_offset.unwrap_or(0);
+    if offset >= width {
+        return Err(AppError::Validation(format!(
+            "bitOffset must be less than {width}"
+        )));
+    }
+    let length = spec.bit_length.unwrap_or(1);
+    if length == 0 || length > width - offset {
+        return Err(AppError::Validation(format!(
+            "bitLength must be between 1 and {}",
+            width - offset
+        )));
+    }
+    Ok(BitField {
+        offset,
+        length,
+        width,
+        signed,
+    })
+}
+
+// 校验枚举映射（键为整数、文本非空且不重复；布尔类型的键只能为 0 或 1）
+fn enum_map(
+    map: &BTreeMap<String, String>,
+    data_type: DataType,
+) -> Result<BTreeMap<i64, String>, AppError> {
+    if matches!(data_type, DataType::F32 | DataType::F64) {
+        return Err(invalid("enumMap requires an integer or bool dataType"));
+    }
+    if map.is_empty() {
+        return Err(invalid("enumMap must not be empty"));
+    }
+    let mut result = BTreeMap::new();
+    for (key, label) in map {
+        let raw = key
+            .parse::<i64>()
+            .map_err(|_| AppError::Validation(format!("enumMap key {key} must be an integer")))?;
+        if data_type == DataType::Bool && !(0..=1).contains(&raw) {
+            return Err(invalid("enumMap keys must be 0 or 1 for dataType bool"));
+        }
+        if label.is_empty() {
+            return Err(AppError::Validation(format!(
+                "enumMap label for {key} is required"
+            )));
+        }
+        if result.values().any(|existing: &String| existing == label) {
+            return Err(AppError::Validation(format!(
+                "duplicate enumMap label {label}"
+            )));
+        }
+        result.insert(raw, label.clone());
+    }
+    Ok(result)
+}
+
+// 查找单位（物理量，系数，偏移）
+fn lookup_unit(unit: &str) -> Option<(&'static str, f64, f64)> {
+    UNITS
+        .iter()
+        .find(|(symbol, ..)| *symbol == unit)
+        .map(|(_, quantity, factor, offset)| (*quantity, *factor, *offset))
+}
+
+// 计算由源单位换算为工程单位的线性变换（系数，偏移）
+fn unit_conversion(source: &str, unit: Option<&str>) -> Result<(f64, f64), AppError> {
+    let (quantity, factor, offset) = lookup_unit(source)
+        .ok_or_else(|| AppError::Validation(format!("unknown sourceUnit {source}")))?;
+    let unit = unit.ok_or_else(|| invalid("unit is required for sourceUnit"))?;
+    let (target_quantity, target_factor, target_offset) =
+        lookup_unit(unit).ok_or_else(|| AppError::Validation(format!("unknown unit {unit}")))?;
+    if quantity != target_quantity {
+        return Err(AppError::Validation(format!(
+            "cannot convert {source} to {unit}"
+        )));
+    }
+    Ok((
+        factor / target_factor,
+        (offset - target_offset) / target_factor,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec() -> PointTransformSpec {
+        PointTransformSpec::default()
+    }
+
+    fn compile(
+        spec: &PointTransformSpec,
+        data_type: DataType,
+        unit: Option<&str>,
+    ) -> PointTransform {
+        PointTransform::compile(spec, data_type, unit).expect("compile transform")
+    }
+
+    fn float(value: Result<PointValue, TransformError>) -> f64 {
+        match value {
+            Ok(PointValue::Float(value)) => value,
+            other => panic!("expected float, got {other:?}"),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, label)| ((*key).to_string(), (*label).to_string()))
+            .collect()
+    }
+
+    fn status_word() -> PointTransform {
+        // 状态字 bit 4–5 为运行状态
+        compile(
+            &PointTransformSpec {
+                bit_offset: Some(4),
+                bit_length: Some(2),
+                enum_map: Some(labels(&[
+                    ("0", "stopped"),
+                    ("1", "running"),
+                    (" 2 ", " fault "),
+                ])),
+                ..spec()
+            },
+            DataType::I16,
+            None,
+        )
+    }
+
+    #[test]
+    fn extracts_and_inserts_bit_fields() {
+        let transform = compile(
+            &PointTransformSpec {
+                bit_offset: Some(4),
+                bit_length: Some(2),
+                ..spec()
+            },
+            DataType::I16,
+            None,
+        );
+        assert_eq!(
+            transform.apply(PointValue::Integer(0b1001_1111)),
+            Ok(PointValue::Integer(1))
+        );
+        assert_eq!(
+            transform.apply(PointValue::Integer(-1)),
+            Ok(PointValue::Integer(3))
+        );
+        let bits = transform.bit_field().expect("bit field");
+        assert_eq!(bits.insert(0b1001_1111, 2), 0b1010_1111);
+        assert_eq!(bits.insert(-1, 0), -49);
+    }
+
+    #[test]
+    fn maps_raw_values_to_enum_labels() {
+        let transform = status_word();
+        assert_eq!(
+            transform.apply(PointValue::Integer(0b1001_1111)),
+            Ok(PointValue::Text("running".to_string()))
+        );
+        assert_eq!(
+            transform.apply(PointValue::Integer(-1)),
+            Err(TransformError::Unmapped(3))
+        );
+
+        // 布尔点位的枚举映射
+        let transform = compile(
+            &PointTransformSpec {
+                enum_map: Some(labels(&[("0", "closed"), ("1", "open")])),
+                ..spec()
+            },
+            DataType::Bool,
+            None,
+        );
+        assert_eq!(
+            transform.apply(PointValue::Bool(true)),
+            Ok(PointValue::Text("open".to_string()))
+        );
+    }
+
+    #[test]
+    fn applies_scale_offset_and_unit_conversion() {
+        let transform = compile(
+            &PointTransformSpec {
+                scale: Some(0.5),
+                offset: Some(-10.0),
+                ..spec()
+            },
+            DataType::U16,
+            None,
+        );
+        assert_close(float(transform.apply(PointValue::Integer(101))), 40.5);
+
+        // 单位换算：°F → °C，kWh → MWh
+        let transform = compile(
+            &PointTransformSpec {
+                source_unit: Some("°F".to_string()),
+                ..spec()
+            },
+            DataType::F32,
+            Some("°C"),
+        );
+        assert_close(float(transform.apply(PointValue::Float(212.0))), 100.0);
+        let transform = compile(
+            &PointTransformSpec {
+                scale: Some(0.1),
+                source_unit: Some("kWh".to_string()),
+                ..spec()
+            },
+            DataType::U32,
+            Some("MWh"),
+        );
+        assert_close(float(transform.apply(PointValue::Integer(25_000))), 2.5);
+
+        // 未配置时不变换
+        let identity = compile(&spec(), DataType::String, None);
+        assert!(identity.is_identity());
+        assert_eq!(
+            identity.apply(PointValue::Text("AB".to_string())),
+            Ok(PointValue::Text("AB".to_string()))
+        );
+    }
+
+    #[test]
+    fn clamps_reads_to_the_configured_range() {
+        let transform = compile(
+            &PointTransformSpec {
+                scale: Some(0.5),
+                offset: Some(-10.0),
+                clamp_min: Some(0.0),
+                clamp_max: Some(100.0),
+                ..spec()
+            },
+            DataType::U16,
+            None,
+        );
+        assert_close(float(transform.apply(PointValue::Integer(101))), 40.5);
+        assert_close(float(transform.apply(PointValue::Integer(4))), 0.0);
+        assert_close(float(transform.apply(PointValue::Integer(500))), 100.0);
+        let transform = compile(
+            &PointTransformSpec {
+                clamp_max: Some(10.0),
+                ..spec()
+            },
+            DataType::U16,
+            None,
+        );
+        assert_eq!(
+            transform.apply(PointValue::Integer(12)),
+            Ok(PointValue::Integer(10))
+        );
+    }
+
+    #[test]
+    fn inverts_writes_through_the_pipeline() {
+        // 缩放与偏移按原始整数取整，超出限幅拒绝
+        let transform = compile(
+            &PointTransformSpec {
+                scale: Some(0.5),
+                offset: Some(-10.0),
+                clamp_min: Some(0.0),
+                clamp_max: Some(100.0),
+                ..spec()
+            },
+            DataType::U16,
+            None,
+        );
+        assert_eq!(
+            transform.inverse(DataType::U16, PointValue::Float(40.5)),
+            Ok(PointValue::Integer(101))
+        );
+        assert_eq!(
+            transform.inverse(DataType::U16, PointValue::Float(40.7)),
+            Ok(PointValue::Integer(101))
+        );
+        assert_eq!(
+            transform.inverse(DataType::U16, PointValue::Integer(-1)),
+            Err(TransformError::BelowMin(0.0))
+        );
+        assert_eq!(
+            transform.inverse(DataType::U16, PointValue::Text("1".to_string())),
+            Err(TransformError::NotNumeric)
+        );
+
+        // 单位换算：浮点类型写入保持浮点
+        let transform = compile(
+            &PointTransformSpec {
+                source_unit: Some("°F".to_string()),
+                ..spec()
+            },
+            DataType::F32,
+            Some("°C"),
+        );
+        assert_close(
+            float(transform.inverse(DataType::F32, PointValue::Integer(-40))),
+            -40.0,
+        );
+        let transform = compile(
+            &PointTransformSpec {
+                scale: Some(0.1),
+                source_unit: Some("kWh".to_string()),
+                ..spec()
+            },
+            DataType::U32,
+            Some("MWh"),
+        );
+        assert_eq!(
+            transform.inverse(DataType::U32, PointValue::Float(2.5)),
+            Ok(PointValue::Integer(25_000))
+        );
+
+        // 枚举文本还原为原始值
+        let transform = status_word();
+        assert_eq!(
+            transform.inverse(DataType::I16, PointValue::Text("fault".to_string())),
+            Ok(PointValue::Integer(2))
+        );
+        assert_eq!(
+            transform.inverse(DataType::I16, PointValue::Text("idle".to_string())),
+            Err(TransformError::UnknownLabel(
+                "stopped, running, fault".to_string()
+            ))
+        );
+        let transform = compile(
+            &PointTransformSpec {
+                enum_map: Some(labels(&[("0", "closed"), ("1", "open")])),
+                ..spec()
+            },
+            DataType::Bool,
+            None,
+        );
+        assert_eq!(
+            transform.inverse(DataType::Bool, PointValue::Text("closed".to_string())),
+            Ok(PointValue::Bool(false))
+        );
+    }
+
+    #[test]
+    fn suppresses_changes_within_deadband() {
+        let absolute = compile(
+            &PointTransformSpec {
+                deadband: Some(0.5),
+                ..spec()
+            },
+            DataType::F32,
+            None,
+        );
+        assert!(!absolute.is_significant(&json!(10.0), &json!(10.5)));
+        assert!(absolute.is_significant(&json!(10.0), &json!(9.4)));
+        let percent = compile(
+            &PointTransformSpec {
+                deadband: Some(1.0),
+                deadband_mode: Some(" Percent ".to_string()),
+                ..spec()
+            },
+            DataType::U16,
+            None,
+        );
+        assert!(!percent.is_significant(&json!(200), &json!(202)));
+        assert!(percent.is_significant(&json!(200), &json!(203)));
+        assert!(percent.is_significant(&json!(0), &json!(1)));
+        // 未配置死区时按值是否相等判断
+        let identity = PointTransform::default();
+        assert!(!identity.is_significant(&json!(1), &json!(1)));
+        assert!(identity.is_significant(&json!(1), &json!(1.0001)));
+    }
+}
@@ -0,0 +1,238 @@
+//! 审计日志模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的审计相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `audit_query` | 按条件分页查询审计事件 |
+//! | `audit_verify_chain` | 校验审计哈希链完整性 |
+
+// 引入审计数据模型
+use crate::audit::models::{
+    AuditChainVerificationData, AuditQueryData, AuditQueryPayload, AuditVerifyChainPayload,
+};
+// 引入审计服务层函数
+use crate::audit::services::{query_events, verify_chain};
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+
+/// 分页查询审计事件
+///
+/// # 参数
+/// * `payload` - 操作员与过滤条件（操作人、命令、目标、结果、请求 ID、时间范围、分页）
+///
+/// # 返回
+/// * 按 ID 倒序的审计事件分页结果
+#[tauri::command]
+pub fn audit_query(
+    payload: AuditQueryPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AuditQueryData> {
+    execute_traced_command("audit_query", trace, || {
+        Ok(ApiResponse::ok(query_events(payload, now_millis())?))
+    })
+}
+
+/// 校验审计哈希链完整性
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名的请求体
+///
+/// # 返回
+/// * 校验结论、已校验条数及首个断链记录 ID
+#[tauri::command]
+pub fn audit_verify_chain(
+    payload: AuditVerifyChainPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AuditChainVerificationData> {
+    execute_traced_command("audit_verify_chain", trace, || {
+        Ok(ApiResponse::ok(verify_chain(&payload, now_millis())?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::auth::admin_commands::{auth_admin_register_user, auth_admin_update_user};
+    use crate::auth::models::{AdminRegisterUserPayload, AdminUpdateUserPayload};
+    use crate::core::error::AppError;
+    use crate::db;
+
+    fn unique_username(prefix: &str) -> String {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        format!("{prefix}_{counter}_{nanos}")
+    }
+
+    fn ensure_test_db_ready() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            db::set_database_url(db::test_database_url()).expect("configure database url");
+            db::init_database().expect("init database");
+        });
+    }
+
+    fn trace(request_id: &str) -> TraceContext {
+        TraceContext {
+            request_id: Some(request_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn admin_actions_are_recorded_with_diff_and_request_id() {
+        ensure_test_db_ready();
+        let username = unique_username("audited_user");
+        let registered = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: username.clone(),
+                password: "admin123".to_string(),
+                nickname: "审计前".to_string(),
+                phone: None,
+                roles: vec!["operator".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            Some(trace(&format!("{username}_register"))),
+        )
+        .expect("register user")
+        .data;
+
+        let update_request_id = format!("{username}_update");
+        auth_admin_update_user(
+            AdminUpdateUserPayload {
+                operator_username: "admin".to_string(),
+                user_id: registered.user_id,
+                username: username.clone(),
+                nickname: "审计后".to_string(),
+                phone: None,
+                roles: vec!["operator".to_string()],
+                is_active: true,
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+            },
+            Some(trace(&update_request_id)),
+        )
+        .expect("update user");
+
+        let page = audit_query(
+            AuditQueryPayload {
+                operator_username: "admin".to_string(),
+                target_type: Some("user".to_string()),
+                target_id: Some(registered.user_id.to_string()),
+                ..AuditQueryPayload::default()
+            },
+            None,
+        )
+        .expect("query audit events")
+        .data;
+        assert_eq!(page.total, 2);
+
+        let update_event = &page.items[0];
+        assert_eq!(update_event.command, "auth_admin_update_user");
+        assert_eq!(update_event.actor, "admin");
+        assert_eq!(update_event.result, "success");
+        assert_eq!(
+            update_event.request_id.as_deref(),
+            Some(update_request_id.as_str())
+        );
+        let diff = update_event.diff.as_ref().expect("update diff");
+        assert_eq!(diff["nickname"]["before"], "审计前");
+        assert_eq!(diff["nickname"]["after"], "审计后");
+
+        let register_event = &page.items[1];
+        assert_eq!(register_event.command, "auth_admin_register_user");
+        assert!(register_event.before.is_none());
+        assert_eq!(update_event.prev_hash.len(), 64);
+
+        let verification = audit_verify_chain(
+            AuditVerifyChainPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("verify chain")
+        .data;
+        assert!(verification.valid);
+        assert!(verification.checked_count >= 2);
+    }
+
+    #[test]
+    fn failed_admin_action_is_recorded_as_failure() {
+        ensure_test_db_ready();
+        let request_id = unique_username("forbidden_register");
+        let err = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "common".to_string(),
+                username: unique_username("never_created"),
+                password: "admin123".to_string(),
+                nickname: "禁止".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            Some(trace(&request_id)),
+        )
+        .expect_err("expect forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: admin only".to_string())
+        );
+
+        let page = audit_query(
+            AuditQueryPayload {
+                operator_username: "admin".to_string(),
+                request_id: Some(request_id),
+                ..AuditQueryPayload::default()
+            },
+            None,
+        )
+        .expect("query audit events")
+        .data;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].actor, "common");
+        assert_eq!(page.items[0].result, "failure");
+        assert_eq!(
+            page.items[0].error_message.as_deref(),
+            Some("forbidden: admin only")
+        );
+    }
+
+    #[test]
+    fn non_admin_cannot_query_audit_events() {
+        ensure_test_db_ready();
+        let err = audit_query(
+            AuditQueryPayload {
+                operator_username: "common".to_string(),
+                ..AuditQueryPayload::default()
+            },
+            None,
+        )
+        .expect_err("expect forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: audit view required".to_string())
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! 审计日志模块入口
+//!
+//! 本模块记录管理类操作（账号、控制配置等）的审计事件：
+//! - 操作人、命令、目标对象、前后快照及差异、请求 ID 与执行结果
+//! - 每条记录通过 SHA-256 哈希与上一条串联，篡改可被 `audit_verify_chain` 检出
+//! - 数据库触发器拒绝 UPDATE / DELETE / TRUNCATE，记录只追加
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 其他业务模块写入审计事件时使用
+pub mod models;
+// 公开服务模块 - 其他业务模块通过 record_event 写入审计事件
+pub mod services;
+// 私有模块 - 数据仓储层
+mod repository;
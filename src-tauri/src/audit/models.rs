@@ -0,0 +1,160 @@
+//! 审计日志模块数据模型
+//!
+//! 本模块定义审计事件的写入参数、存储记录以及查询接口的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+// 引入 JSON 值类型
+use serde_json::Value;
+
+/// 审计结果：执行成功
+pub const RESULT_SUCCESS: &str = "success";
+
+/// 审计结果：执行失败
+pub const RESULT_FAILURE: &str = "failure";
+
+/// 待写入的审计事件
+///
+/// 由各业务服务构造，`error_message` 为 None 时记为成功
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventInput {
+    pub actor: String,                 // 操作人用户名
+    pub command: String,               // IPC 命令名
+    pub target_type: String,           // 目标对象类型（如 user）
+    pub target_id: Option<String>,     // 目标对象标识
+    pub before: Option<Value>,         // 操作前快照
+    pub after: Option<Value>,          // 操作后快照
+    pub error_message: Option<String>, // 失败原因（成功时为 None）
+}
+
+/// 审计事件存储记录
+///
+/// 与 audit_events 表一一对应，JSON 字段保存写入时的原始文本以保证哈希可复算
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditEventRecord {
+    pub id: i64,                       // 记录 ID（链上顺序）
+    pub occurred_at: i64,              // 发生时间戳（毫秒）
+    pub actor: String,                 // 操作人
+    pub command: String,               // 命令名
+    pub target_type: String,           // 目标类型
+    pub target_id: Option<String>,     // 目标标识
+    pub request_id: Option<String>,    // 请求 ID
+    pub result: String,                // success / failure
+    pub error_message: Option<String>, // 失败原因
+    pub before_json: Option<String>,   // 操作前快照 JSON 文本
+    pub after_json: Option<String>,    // 操作后快照 JSON 文本
+    pub diff_json: Option<String>,     // 差异 JSON 文本
+    pub prev_hash: String,             // 上一条记录哈希
+    pub hash: String,                  // 本条记录哈希
+}
+
+/// 审计事件查询过滤条件（仓储层使用）
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor: Option<String>,       // 操作人
+    pub command: Option<String>,     // 命令名
+    pub target_type: Option<String>, // 目标类型
+    pub target_id: Option<String>,   // 目标标识
+    pub result: Option<String>,      // 执行结果
+    pub request_id: Option<String>,  // 请求 ID
+    pub start_at: Option<i64>,       // 起始时间（含）
+    pub end_at: Option<i64>,         // 结束时间（不含）
+}
+
+// 审计事件查询请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AuditQueryPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 按操作人过滤
+    pub actor: Option<String>,
+    /// 按命令名过滤
+    pub command: Option<String>,
+    /// 按目标类型过滤
+    pub target_type: Option<String>,
+    /// 按目标标识过滤
+    pub target_id: Option<String>,
+    /// 按执行结果过滤：success / failure
+    pub result: Option<String>,
+    /// 按请求 ID 过滤
+    pub request_id: Option<String>,
+    /// 起始时间戳（毫秒，含）
+    pub start_at: Option<i64>,
+    /// 结束时间戳（毫秒，不含）
+    pub end_at: Option<i64>,
+    /// 页码（从 1 开始）
+    pub page: Option<u32>,
+    /// 每页条数（默认 50，最大 500）
+    pub page_size: Option<u32>,
+}
+
+// 审计事件响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEventData {
+    /// 记录 ID
+    pub id: i64,
+    /// 发生时间戳（毫秒）
+    pub occurred_at: i64,
+    /// 操作人
+    pub actor: String,
+    /// 命令名
+    pub command: String,
+    /// 目标类型
+    pub target_type: String,
+    /// 目标标识
+    pub target_id: Option<String>,
+    /// 请求 ID
+    pub request_id: Option<String>,
+    /// 执行结果
+    pub result: String,
+    /// 失败原因
+    pub error_message: Option<String>,
+    /// 操作前快照
+    pub before: Option<Value>,
+    /// 操作后快照
+    pub after: Option<Value>,
+    /// 前后差异
+    pub diff: Option<Value>,
+    /// 上一条记录哈希
+    pub prev_hash: String,
+    /// 本条记录哈希
+    pub hash: String,
+}
+
+// 审计事件分页查询响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQueryData {
+    /// 满足条件的总条数
+    pub total: i64,
+    /// 当前页码
+    pub page: u32,
+    /// 每页条数
+    pub page_size: u32,
+    /// 当前页记录（按 ID 倒序）
+    pub items: Vec<AuditEventData>,
+}
+
+// 审计链校验请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AuditVerifyChainPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 审计链校验响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditChainVerificationData {
+    /// 链是否完整
+    pub valid: bool,
+    /// 已校验的记录数
+    pub checked_count: u64,
+    /// 首个断链记录 ID（完整时为 null）
+    pub broken_event_id: Option<i64>,
+    /// 校验结论说明
+    pub message: String,
+}
@@ -0,0 +1,219 @@
+//! 审计日志模块数据仓储层
+//!
+//! 本模块负责 audit_events 表的读写：
+//! - 追加审计事件（事务内串行化，保证哈希链顺序）
+//! - 按条件分页查询
+//! - 按 ID 顺序分批读取（链校验使用）
+//!
+//! 审计表只追加、查询条件动态组合，使用原生 SQL（SQLx）实现
+
+// 引入 SQLx 查询类型
+use sqlx::postgres::PgRow;
+use sqlx::{Row, query, query_scalar};
+
+// 引入审计模型
+use crate::audit::models::{AuditEventFilter, AuditEventRecord};
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+
+// 审计追加使用的事务级咨询锁键（串行化并发写入，防止链分叉）
+const AUDIT_APPEND_LOCK_KEY: i64 = 2_026_101_802;
+
+// 审计事件查询列（顺序与 map_event_row 对应）
+const AUDIT_EVENT_COLUMNS: &str = "id, occurred_at, actor, command, target_type, target_id, \
+     request_id, result, error_message, before_json, after_json, diff_json, prev_hash, hash";
+
+// 动态过滤条件（$1..$8 依次对应 AuditEventFilter 字段）
+const AUDIT_EVENT_FILTER_SQL: &str = r"
+    WHERE ($1::TEXT IS NULL OR actor = $1)
+      AND ($2::TEXT IS NULL OR command = $2)
+      AND ($3::TEXT IS NULL OR target_type = $3)
+      AND ($4::TEXT IS NULL OR target_id = $4)
+      AND ($5::TEXT IS NULL OR result = $5)
+      AND ($6::TEXT IS NULL OR request_id = $6)
+      AND ($7::BIGINT IS NULL OR occurred_at >= $7)
+      AND ($8::BIGINT IS NULL OR occurred_at < $8)
+";
+
+/// 追加审计事件
+///
+/// 在事务内获取咨询锁后读取链尾哈希，回填 `prev_hash` 并由 `hasher` 计算本条哈希再写入
+///
+/// # 参数
+/// * `record` - 待写入记录（`id`、`prev_hash`、`hash` 由本函数填充）
+/// * `genesis_hash` - 空链时使用的起始哈希
+/// * `hasher` - 哈希计算函数
+///
+/// # 返回
+/// * 写入后的完整记录
+pub fn append_event(
+    mut record: AuditEventRecord,
+    genesis_hash: &str,
+    hasher: fn(&AuditEventRecord) -> String,
+) -> Result<AuditEventRecord, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        // 串行化写入：同一时刻只有一个事务读取链尾并追加
+        query("SELECT pg_advisory_xact_lock($1)")
+            .bind(AUDIT_APPEND_LOCK_KEY)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        // 读取链尾哈希
+        let last_hash: Option<String> =
+            query_scalar("SELECT hash FROM audit_events ORDER BY id DESC LIMIT 1")
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        record.prev_hash = last_hash.unwrap_or_else(|| genesis_hash.to_string());
+        record.hash = hasher(&record);
+
+        // 写入记录
+        let id: i64 = query_scalar(
+            r"
+            INSERT INTO audit_events (
+              occurred_at, actor, command, target_type, target_id, request_id, result,
+              error_message, before_json, after_json, diff_json, prev_hash, hash
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id
+            ",
+        )
+        .bind(record.occurred_at)
+        .bind(&record.actor)
+        .bind(&record.command)
+        .bind(&record.target_type)
+        .bind(&record.target_id)
+        .bind(&record.request_id)
+        .bind(&record.result)
+        .bind(&record.error_message)
+        .bind(&record.before_json)
+        .bind(&record.after_json)
+        .bind(&record.diff_json)
+        .bind(&record.prev_hash)
+        .bind(&record.hash)
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        record.id = id;
+        Ok(record)
+    })
+}
+
+/// 按条件分页查询审计事件
+///
+/// # 参数
+/// * `filter` - 过滤条件
+/// * `limit` - 返回条数
+/// * `offset` - 偏移量
+///
+/// # 返回
+/// * (满足条件的总数, 当前页记录)，记录按 ID 倒序
+pub fn query_events(
+    filter: &AuditEventFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<(i64, Vec<AuditEventRecord>), AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+
+        // 统计总数
+        let count_sql = format!("SELECT COUNT(*) FROM audit_events {AUDIT_EVENT_FILTER_SQL}");
+        let total: i64 = query_scalar(&count_sql)
+            .bind(&filter.actor)
+            .bind(&filter.command)
+            .bind(&filter.target_type)
+            .bind(&filter.target_id)
+            .bind(&filter.result)
+            .bind(&filter.request_id)
+            .bind(filter.start_at)
+            .bind(filter.end_at)
+            .fetch_one(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        // 查询当前页
+        let page_sql = format!(
+            "SELECT {AUDIT_EVENT_COLUMNS} FROM audit_events {AUDIT_EVENT_FILTER_SQL} \
+             ORDER BY id DESC LIMIT $9 OFFSET $10"
+        );
+        let rows = query(&page_sql)
+            .bind(&filter.actor)
+            .bind(&filter.command)
+            .bind(&filter.target_type)
+            .bind(&filter.target_id)
+            .bind(&filter.result)
+            .bind(&filter.request_id)
+            .bind(filter.start_at)
+            .bind(filter.end_at)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        let items = rows
+            .iter()
+            .map(map_event_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((total, items))
+    })
+}
+
+/// 按 ID 升序读取一批审计事件
+///
+/// # 参数
+/// * `after_id` - 从该 ID 之后开始读取
+/// * `limit` - 读取条数
+///
+/// # 返回
+/// * 审计事件列表
+pub fn list_events_after(after_id: i64, limit: i64) -> Result<Vec<AuditEventRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!(
+            "SELECT {AUDIT_EVENT_COLUMNS} FROM audit_events WHERE id > $1 ORDER BY id ASC LIMIT $2"
+        );
+        let rows = query(&sql)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_event_row).collect()
+    })
+}
+
+/// 将数据库行映射为审计事件记录
+fn map_event_row(row: &PgRow) -> Result<AuditEventRecord, AppError> {
+    let map_err = |err: sqlx::Error| AppError::Database(err.to_string());
+    Ok(AuditEventRecord {
+        id: row.try_get(0).map_err(map_err)?,
+        occurred_at: row.try_get(1).map_err(map_err)?,
+        actor: row.try_get(2).map_err(map_err)?,
+        command: row.try_get(3).map_err(map_err)?,
+        target_type: row.try_get(4).map_err(map_err)?,
+        target_id: row.try_get(5).map_err(map_err)?,
+        request_id: row.try_get(6).map_err(map_err)?,
+        result: row.try_get(7).map_err(map_err)?,
+        error_message: row.try_get(8).map_err(map_err)?,
+        before_json: row.try_get(9).map_err(map_err)?,
+        after_json: row.try_get(10).map_err(map_err)?,
+        diff_json: row.try_get(11).map_err(map_err)?,
+        prev_hash: row.try_get(12).map_err(map_err)?,
+        hash: row.try_get(13).map_err(map_err)?,
+    })
+}
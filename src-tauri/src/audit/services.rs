@@ -0,0 +1,471 @@
+//! 审计日志模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 构造审计记录（前后快照差异、请求 ID、执行结果）
+//! - 计算哈希链：`hash = SHA-256(prev_hash | 各字段长度前缀编码)`
+//! - 审计查询与链完整性校验
+//!
+//! 写入失败不会回滚已完成的业务操作，调用方可选择 `record_event_or_log` 仅记录告警
+
+// 引入格式化写入 trait（十六进制编码）
+use std::fmt::Write as _;
+
+// 引入 JSON 值类型
+use serde_json::{Map, Value};
+// 引入 SHA-256 哈希
+use sha2::{Digest, Sha256};
+
+// 引入审计模型
+use crate::audit::models::{
+    AuditChainVerificationData, AuditEventData, AuditEventFilter, AuditEventInput,
+    AuditEventRecord, AuditQueryData, AuditQueryPayload, AuditVerifyChainPayload, RESULT_FAILURE,
+    RESULT_SUCCESS,
+};
+// 引入审计仓储模块
+use crate::audit::repository;
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入当前请求 ID
+use crate::core::tracing::current_request_id;
+
+/// 哈希链起始值（第一条记录的 prev_hash）
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// 查询默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+// 查询最大每页条数
+const MAX_PAGE_SIZE: u32 = 500;
+
+// 链校验每批读取条数
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// 写入审计事件
+///
+/// 自动附带当前命令的请求 ID，并计算前后快照差异
+///
+/// # 参数
+/// * `input` - 审计事件内容
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新记录 ID
+pub fn record_event(input: AuditEventInput, now_millis: u64) -> Result<i64, AppError> {
+    let occurred_at = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    let diff = diff_snapshots(input.before.as_ref(), input.after.as_ref());
+    let record = AuditEventRecord {
+        occurred_at,
+        actor: input.actor,
+        command: input.command,
+        target_type: input.target_type,
+        target_id: input.target_id,
+        request_id: current_request_id(),
+        result: if input.error_message.is_some() {
+            RESULT_FAILURE.to_string()
+        } else {
+            RESULT_SUCCESS.to_string()
+        },
+        error_message: input.error_message,
+        before_json: input.before.map(|value| value.to_string()),
+        after_json: input.after.map(|value| value.to_string()),
+        diff_json: diff.map(|value| value.to_string()),
+        ..AuditEventRecord::default()
+    };
+    let record = repository::append_event(record, GENESIS_HASH, compute_event_hash)?;
+    Ok(record.id)
+}
+
+/// 写入审计事件，失败时仅记录告警日志
+///
+/// 用于业务操作已完成、不应因审计写入失败而向前端报错的场景
+///
+/// # 参数
+/// * `input` - 审计事件内容
+/// * `now_millis` - 当前时间戳（毫秒）
+pub fn record_event_or_log(input: AuditEventInput, now_millis: u64) {
+    let command = input.command.clone();
+    if let Err(err) = record_event(input, now_millis) {
+        tracing::error!(audit_command = %command, error = %err, "audit event write failed");
+    }
+}
+
+/// 业务命令审计事件的命令、操作员与目标
+pub(crate) struct CommandAudit<'a> {
+    pub command: &'a str,           // 命令名称
+    pub operator_username: &'a str, // 操作员用户名（为空时不记录）
+    pub target_type: &'a str,       // 目标类型
+    pub target_id: Option<String>,  // 目标 ID
+}
+
+/// 以业务编码作为审计目标 ID（编码为空时不记录目标）
+pub(crate) fn target_id(id: &str) -> Option<String> {
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// 写入业务命令的审计事件
+///
+/// 各业务模块的增删改命令共用；失败的命令记录错误信息，
+/// 审计写入失败只记录错误日志，不影响业务结果；缺少操作员的请求不记录
+///
+/// # 参数
+/// * `audit` - 命令、操作员与目标
+/// * `(before, after)` - 操作前后的快照
+/// * `result` - 命令结果
+/// * `now_millis` - 当前时间戳（毫秒）
+pub(crate) fn record_command<T>(
+    audit: CommandAudit<'_>,
+    (before, after): (Option<Value>, Option<Value>),
+    result: &Result<T, AppError>,
+    now_millis: u64,
+) {
+    if audit.operator_username.is_empty() {
+        return;
+    }
+    record_event_or_log(
+        AuditEventInput {
+            actor: audit.operator_username.to_string(),
+            command: audit.command.to_string(),
+            target_type: audit.target_type.to_string(),
+            target_id: audit.target_id,
+            before,
+            after,
+            error_message: result.as_ref().err().map(ToString::to_string),
+        },
+        now_millis,
+    );
+}
+
+/// 分页查询审计事件
+///
+/// # 参数
+/// * `payload` - 查询条件
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 分页结果
+pub fn query_events(
+    payload: AuditQueryPayload,
+    now_millis: u64,
+) -> Result<AuditQueryData, AppError> {
+    assert_operator_can_view_audit(&payload.operator_username, now_millis)?;
+
+    // 校验结果过滤值
+    let result = normalize_filter(payload.result);
+    if let Some(result) = result.as_deref()
+        && result != RESULT_SUCCESS
+        && result != RESULT_FAILURE
+    {
+        return Err(AppError::Validation(
+            "result must be 'success' or 'failure'".to_string(),
+        ));
+    }
+
+    // 计算分页参数
+    let page = payload.page.unwrap_or(1).max(1);
+    let page_size = payload
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = i64::from(page - 1) * i64::from(page_size);
+
+    let filter = AuditEventFilter {
+        actor: normalize_filter(payload.actor),
+        command: normalize_filter(payload.command),
+        target_type: normalize_filter(payload.target_type),
+        target_id: normalize_filter(payload.target_id),
+        result,
+        request_id: normalize_filter(payload.request_id),
+        start_at: payload.start_at,
+        end_at: payload.end_at,
+    };
+    let (total, records) = repository::query_events(&filter, i64::from(page_size), offset)?;
+    Ok(AuditQueryData {
+        total,
+        page,
+        page_size,
+        items: records.into_iter().map(map_event_record).collect(),
+    })
+}
+
+/// 校验审计哈希链完整性
+///
+/// 按 ID 顺序逐批复算哈希，任一记录的 prev_hash 与上一条不符或哈希不符即判定断链
+///
+/// # 参数
+/// * `payload` - 校验请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 校验结果
+pub fn verify_chain(
+    payload: &AuditVerifyChainPayload,
+    now_millis: u64,
+) -> Result<AuditChainVerificationData, AppError> {
+    assert_operator_can_view_audit(&payload.operator_username, now_millis)?;
+
+    let mut expected_prev_hash = GENESIS_HASH.to_string();
+    let mut checked_count: u64 = 0;
+    let mut after_id = 0;
+    loop {
+        let records = repository::list_events_after(after_id, VERIFY_BATCH_SIZE)?;
+        let Some(last) = records.last() else {
+            break;
+        };
+        after_id = last.id;
+        match verify_records(&records, &expected_prev_hash) {
+            Ok(last_hash) => {
+                checked_count += records.len() as u64;
+                expected_prev_hash = last_hash;
+            }
+            Err((broken_event_id, checked_in_batch)) => {
+                return Ok(AuditChainVerificationData {
+                    valid: false,
+                    checked_count: checked_count + checked_in_batch,
+                    broken_event_id: Some(broken_event_id),
+                    message: format!("audit chain broken at event {broken_event_id}"),
+                });
+            }
+        }
+    }
+
+    Ok(AuditChainVerificationData {
+        valid: true,
+        checked_count,
+        broken_event_id: None,
+        message: "audit chain intact".to_string(),
+    })
+}
+
+/// 计算审计记录哈希
+///
+/// 各字段按 `长度:内容|` 编码（空值编码为 `-|`），避免字段拼接产生歧义
+///
+/// # 参数
+/// * `record` - 审计记录（`id` 与 `hash` 不参与计算）
+///
+/// # 返回
+/// * 64 位小写十六进制哈希
+pub fn compute_event_hash(record: &AuditEventRecord) -> String {
+    let occurred_at = record.occurred_at.to_string();
+    let fields = [
+        Some(record.prev_hash.as_str()),
+        Some(occurred_at.as_str()),
+        Some(record.actor.as_str()),
+        Some(record.command.as_str()),
+        Some(record.target_type.as_str()),
+        record.target_id.as_deref(),
+        record.request_id.as_deref(),
+        Some(record.result.as_str()),
+        record.error_message.as_deref(),
+        record.before_json.as_deref(),
+        record.after_json.as_deref(),
+        record.diff_json.as_deref(),
+    ];
+
+    let mut hasher = Sha256::new();
+    for field in fields {
+        match field {
+            Some(value) => {
+                hasher.update(value.len().to_string().as_bytes());
+                hasher.update(b":");
+                hasher.update(value.as_bytes());
+            }
+            None => hasher.update(b"-"),
+        }
+        hasher.update(b"|");
+    }
+
+    let mut output = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(output, "{byte:02x}");
+    }
+    output
+}
+
+/// 计算前后快照差异
+///
+/// 两侧均为对象时按顶层字段比较，输出 `{字段: {before, after}}`；
+/// 否则在两者不同的情况下整体输出 `{before, after}`
+///
+/// # 参数
+/// * `before` - 操作前快照
+/// * `after` - 操作后快照
+///
+/// # 返回
+/// * 差异（无变化时为 None）
+pub fn diff_snapshots(before: Option<&Value>, after: Option<&Value>) -> Option<Value> {
+    if before == after {
+        return None;
+    }
+
+    let (Some(Value::Object(before)), Some(Value::Object(after))) = (before, after) else {
+        let mut whole = Map::new();
+        whole.insert("before".to_string(), before.cloned().unwrap_or(Value::Null));
+        whole.insert("after".to_string(), after.cloned().unwrap_or(Value::Null));
+        return Some(Value::Object(whole));
+    };
+
+    let mut diff = Map::new();
+    for key in before.keys().chain(after.keys()) {
+        if diff.contains_key(key) {
+            continue;
+        }
+        let old_value = before.get(key).cloned().unwrap_or(Value::Null);
+        let new_value = after.get(key).cloned().unwrap_or(Value::Null);
+        if old_value != new_value {
+            let mut change = Map::new();
+            change.insert("before".to_string(), old_value);
+            change.insert("after".to_string(), new_value);
+            diff.insert(key.clone(), Value::Object(change));
+        }
+    }
+    Some(Value::Object(diff))
+}
+
+/// 校验一批连续记录
+///
+/// # 返回
+/// * 成功返回最后一条记录的哈希
+/// * 失败返回 (断链记录 ID, 断链前已通过的条数)
+fn verify_records(
+    records: &[AuditEventRecord],
+    expected_prev_hash: &str,
+) -> Result<String, (i64, u64)> {
+    let mut expected_prev_hash = expected_prev_hash.to_string();
+    for (index, record) in records.iter().enumerate() {
+        if record.prev_hash != expected_prev_hash || compute_event_hash(record) != record.hash {
+            return Err((record.id, index as u64));
+        }
+        expected_prev_hash.clone_from(&record.hash);
+    }
+    Ok(expected_prev_hash)
+}
+
+/// 验证操作员是否有审计查看权限
+fn assert_operator_can_view_audit(
+    operator_username: &str,
+    now_millis: u64,
+) -> Result<(), AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        rbac::RESOURCE_AUDIT,
+        rbac::ACTION_VIEW,
+        now_millis,
+        "forbidden: audit view required",
+    )
+}
+
+/// 规范化可选过滤值（去除空白，空串视为未设置）
+fn normalize_filter(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 将审计记录转换为响应格式
+fn map_event_record(record: AuditEventRecord) -> AuditEventData {
+    AuditEventData {
+        id: record.id,
+        occurred_at: record.occurred_at,
+        actor: record.actor,
+        command: record.command,
+        target_type: record.target_type,
+        target_id: record.target_id,
+        request_id: record.request_id,
+        result: record.result,
+        error_message: record.error_message,
+        before: record.before_json.as_deref().map(parse_snapshot),
+        after: record.after_json.as_deref().map(parse_snapshot),
+        diff: record.diff_json.as_deref().map(parse_snapshot),
+        prev_hash: record.prev_hash,
+        hash: record.hash,
+    }
+}
+
+/// 解析快照 JSON 文本（无法解析时按原始字符串返回）
+fn parse_snapshot(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{GENESIS_HASH, compute_event_hash, diff_snapshots, verify_records};
+    use crate::audit::models::AuditEventRecord;
+
+    fn chained_records() -> Vec<AuditEventRecord> {
+        let mut records = Vec::new();
+        let mut prev_hash = GENESIS_HASH.to_string();
+        for id in 1..=3 {
+            let mut record = AuditEventRecord {
+                id,
+                occurred_at: 1_700_000_000_000 + id,
+                actor: "admin".to_string(),
+                command: "auth_admin_update_user".to_string(),
+                target_type: "user".to_string(),
+                target_id: Some(id.to_string()),
+                result: "success".to_string(),
+                prev_hash: prev_hash.clone(),
+                ..AuditEventRecord::default()
+            };
+            record.hash = compute_event_hash(&record);
+            prev_hash.clone_from(&record.hash);
+            records.push(record);
+        }
+        records
+    }
+
+    #[test]
+    fn detects_tampered_and_reordered_records() {
+        let records = chained_records();
+        assert_eq!(
+            verify_records(&records, GENESIS_HASH),
+            Ok(records[2].hash.clone())
+        );
+
+        let mut tampered = records.clone();
+        tampered[1].actor = "intruder".to_string();
+        assert_eq!(verify_records(&tampered, GENESIS_HASH), Err((2, 1)));
+
+        let mut removed = records;
+        removed.remove(1);
+        assert_eq!(verify_records(&removed, GENESIS_HASH), Err((3, 1)));
+    }
+
+    #[test]
+    fn hash_distinguishes_empty_and_missing_fields() {
+        let mut record = chained_records().remove(0);
+        record.error_message = None;
+        let missing = compute_event_hash(&record);
+        record.error_message = Some(String::new());
+        assert_ne!(missing, compute_event_hash(&record));
+        assert_eq!(missing.len(), 64);
+    }
+
+    #[test]
+    fn diffs_only_changed_top_level_fields() {
+        let before = json!({"nickname": "a", "roles": ["operator"], "isActive": true});
+        let after = json!({"nickname": "b", "roles": ["operator"], "isActive": true});
+        assert_eq!(
+            diff_snapshots(Some(&before), Some(&after)),
+            Some(json!({"nickname": {"before": "a", "after": "b"}}))
+        );
+        assert_eq!(diff_snapshots(Some(&before), Some(&before)), None);
+        assert_eq!(
+            diff_snapshots(None, Some(&after)),
+            Some(json!({"before": null, "after": after}))
+        );
+    }
+}
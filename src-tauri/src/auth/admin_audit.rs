@@ -0,0 +1,138 @@
+//! ==========================================================================================
+//! 管理员操作审计记录
+//!
+//! 模块职责：
+//! 为用户管理类操作采集操作前后快照并写入审计日志（`audit_events`）。
+//!
+//! 使用方式：
+//! 1. 操作前调用 `UserAuditScope::begin` 采集目标用户快照（批量操作用 `with_before` 传入事务内快照）
+//! 2. 执行业务操作
+//! 3. 调用 `finish` / `finish_with_after` 按执行结果写入审计事件
+//!
+//! 审计写入失败只记录错误日志，不改变业务操作的返回结果。
+//!
+//! ==========================================================================================
+
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入审计模型
+use crate::audit::models::AuditEventInput;
+// 引入审计服务
+use crate::audit::services;
+// 引入管理员服务（复用用户记录到响应格式的转换作为快照格式）
+use crate::auth::admin_services;
+// 引入核心错误处理模块
+use crate::core::error::AppError;
+// 引入管理员数据访问层
+use crate::db::admin_repository;
+
+// 审计目标类型：用户
+const TARGET_TYPE_USER: &str = "user";
+
+// 用户管理操作的审计范围
+//
+// 保存操作人、命令名、目标用户与操作前快照，操作结束后写入一条审计事件
+pub(crate) struct UserAuditScope {
+    command: &'static str,
+    actor: String,
+    user_id: Option<i64>,
+    before: Option<Value>,
+}
+
+impl UserAuditScope {
+    // 开始审计：采集目标用户操作前快照
+    //
+    // 参数说明：
+    // - command: IPC 命令名
+    // - operator_username: 操作员用户名
+    // - user_id: 目标用户 ID（新建用户时为 None）
+    pub(crate) fn begin(
+        command: &'static str,
+        operator_username: &str,
+        user_id: Option<i64>,
+    ) -> Self {
+        let user_id = user_id.filter(|user_id| *user_id > 0);
+        Self {
+            command,
+            actor: operator_username.trim().to_string(),
+            user_id,
+            before: user_id.and_then(snapshot_user),
+        }
+    }
+
+    // 开始审计：使用调用方已取得的操作前快照（批量操作在事务内读取）
+    pub(crate) fn with_before(
+        command: &'static str,
+        operator_username: &str,
+        user_id: i64,
+        before: Option<Value>,
+    ) -> Self {
+        Self {
+            command,
+            actor: operator_username.trim().to_string(),
+            user_id: Some(user_id),
+            before,
+        }
+    }
+
+    // 设置目标用户（新建用户成功后回填 ID）
+    pub(crate) fn with_target(mut self, user_id: Option<i64>) -> Self {
+        if self.user_id.is_none() {
+            self.user_id = user_id.filter(|user_id| *user_id > 0);
+        }
+        self
+    }
+
+    // 结束审计：成功时采集目标用户操作后快照
+    pub(crate) fn finish<T>(self, result: &Result<T, AppError>, now_millis: u64) {
+        let after = match result {
+            Ok(_) => self.user_id.and_then(snapshot_user),
+            Err(_) => None,
+        };
+        let error_message = result.as_ref().err().map(ToString::to_string);
+        self.finish_with_after(after, error_message, now_millis);
+    }
+
+    // 结束审计：使用调用方提供的操作后内容
+    pub(crate) fn finish_with_after(
+        self,
+        after: Option<Value>,
+        error_message: Option<String>,
+        now_millis: u64,
+    ) {
+        // 操作员缺失的请求在校验阶段即被拒绝，无可归属的操作人，不记录
+        if self.actor.is_empty() {
+            return;
+        }
+        services::record_event_or_log(
+            AuditEventInput {
+                actor: self.actor,
+                command: self.command.to_string(),
+                target_type: TARGET_TYPE_USER.to_string(),
+                target_id: self.user_id.map(|user_id| user_id.to_string()),
+                before: self.before,
+                after,
+                error_message,
+            },
+            now_millis,
+        );
+    }
+}
+
+// 采集用户快照（包含已软删除的用户，不含任何密码信息）
+fn snapshot_user(user_id: i64) -> Option<Value> {
+    let record = match admin_repository::find_managed_user(user_id) {
+        Ok(record) => record?,
+        Err(err) => {
+            tracing::warn!(user_id, error = %err, "audit snapshot load failed");
+            return None;
+        }
+    };
+    snapshot_record(record)
+}
+
+// 将用户记录转换为审计快照
+pub(crate) fn snapshot_record(record: admin_repository::ManagedUserRecord) -> Option<Value> {
+    serde_json::to_value(admin_services::map_managed_user_record(record)).ok()
+}
@@ -0,0 +1,407 @@
+//! ==========================================================================================
+//! 管理员批量账号操作业务逻辑层
+//!
+//! 模块职责：
+//! 对一组用户 ID 执行相同的账号变更（续期、启停用、增删角色、设置到期时间），
+//! 避免年底续约等场景逐个调用单用户命令。
+//!
+//! 执行语义：
+//!
+//! | 模式 | 说明 |
+//! |------|------|
+//! | 默认（`bestEffort = false`） | 单事务执行，任一用户失败则整体回滚并返回错误 |
+//! | 尽力而为（`bestEffort = true`） | 每个用户独立保存点，返回逐个结果 |
+//!
+//! 公共规则：
+//! - 复用 `assert_target_user_editable`：受保护的 admin 账号在两种模式下都记为 `skipped`
+//! - 委派管理员只能操作其管理范围内的用户、增删其可分配的角色（范围外用户按失败处理）
+//! - 用户 ID 按首次出现顺序去重，单批最多 1000 个
+//! - 每个实际变更的用户写入一条审计事件（含事务内读取的前后快照）
+//!
+//! ==========================================================================================
+
+// 引入标准库集合类型，用于去重与按请求顺序汇总结果
+use std::collections::{HashMap, HashSet};
+
+// 引入鉴权模块的模型定义
+use crate::auth::models::{
+    AdminBatchRenewUsersPayload, AdminBatchSetUsersActivePayload, AdminBatchSetUsersExpiryPayload,
+    AdminBatchUserOperationData, AdminBatchUserResultItem, AdminBatchUserRolesPayload,
+};
+// 引入管理员操作审计记录
+use crate::auth::admin_audit::{UserAuditScope, snapshot_record};
+// 引入委派管理员范围解析
+use crate::auth::admin_delegation_services::{OperatorScope, resolve_operator_scope};
+// 引入管理员业务规则（复用校验逻辑）
+use crate::auth::admin_services::{
+    PROTECTED_ADMIN_MESSAGE, assert_target_user_editable, build_renew_term, normalize_roles,
+    sync_account_term_states,
+};
+// 引入核心错误处理模块
+use crate::core::error::AppError;
+// 引入管理员数据访问层
+use crate::db::admin_repository::{self, BatchUserChange};
+
+// ==========================================================================================
+// 常量定义
+// ==========================================================================================
+
+// 单批最多处理的用户数量
+const MAX_BATCH_USER_COUNT: usize = 1000;
+
+// 单个用户结果：成功
+const STATUS_SUCCEEDED: &str = "succeeded";
+
+// 单个用户结果：跳过（受保护账号）
+const STATUS_SKIPPED: &str = "skipped";
+
+// 单个用户结果：失败（仅尽力而为模式）
+const STATUS_FAILED: &str = "failed";
+
+// ==========================================================================================
+// 批量命令入口
+// ==========================================================================================
+
+// 管理员批量续期用户账号
+
+// 功能说明：
+// 与单用户续期规则一致（permanent / days），续期同时激活账号；
+// 未过期的账号逐个从各自的当前到期时间顺延。
+
+// 参数说明：
+// - payload: 包含用户 ID 列表与续期方式的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回逐个用户的执行结果
+// - 失败：返回 AppError 错误（默认模式下任一用户失败即整体失败）
+pub fn batch_renew_users_by_admin(
+    payload: AdminBatchRenewUsersPayload,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    const COMMAND: &str = "auth_admin_batch_renew_users";
+    let operator_username = payload.operator_username.clone();
+    let result = prepare_batch(&payload.operator_username, now_millis).and_then(|(_, scope)| {
+        let (account_is_permanent, account_valid_days) =
+            build_renew_term(&payload.renew_mode, payload.renew_days)?;
+        let change = BatchUserChange::Renew {
+            account_is_permanent,
+            account_valid_days,
+        };
+        run_batch(
+            COMMAND,
+            &payload.operator_username,
+            &scope,
+            payload.user_ids,
+            payload.best_effort,
+            &change,
+            now_millis,
+        )
+    });
+    audit_batch_failure(COMMAND, &operator_username, &result, now_millis);
+    result
+}
+
+// 管理员批量启用/停用用户
+
+// 参数说明：
+// - payload: 包含用户 ID 列表与目标激活状态的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回逐个用户的执行结果
+// - 失败：返回 AppError 错误
+pub fn batch_set_users_active_by_admin(
+    payload: AdminBatchSetUsersActivePayload,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    const COMMAND: &str = "auth_admin_batch_set_users_active";
+    let operator_username = payload.operator_username.clone();
+    let result = prepare_batch(&payload.operator_username, now_millis).and_then(|(_, scope)| {
+        run_batch(
+            COMMAND,
+            &payload.operator_username,
+            &scope,
+            payload.user_ids,
+            payload.best_effort,
+            &BatchUserChange::SetActive(payload.is_active),
+            now_millis,
+        )
+    });
+    audit_batch_failure(COMMAND, &operator_username, &result, now_millis);
+    result
+}
+
+// 管理员批量增加用户角色
+
+// 参数说明：
+// - payload: 包含用户 ID 列表与角色列表的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回逐个用户的执行结果（已拥有的角色忽略）
+// - 失败：返回 AppError 错误
+pub fn batch_add_user_roles_by_admin(
+    payload: AdminBatchUserRolesPayload,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    const COMMAND: &str = "auth_admin_batch_add_user_roles";
+    let operator_username = payload.operator_username.clone();
+    let result = prepare_batch(&payload.operator_username, now_millis).and_then(|(_, scope)| {
+        let roles = normalize_roles(payload.roles)?;
+        scope.ensure_roles_assignable(&roles)?;
+        run_batch(
+            COMMAND,
+            &payload.operator_username,
+            &scope,
+            payload.user_ids,
+            payload.best_effort,
+            &BatchUserChange::AddRoles(roles),
+            now_millis,
+        )
+    });
+    audit_batch_failure(COMMAND, &operator_username, &result, now_millis);
+    result
+}
+
+// 管理员批量移除用户角色
+
+// 功能说明：
+// 移除后用户必须至少保留一个角色，否则该用户执行失败。
+
+// 参数说明：
+// - payload: 包含用户 ID 列表与角色列表的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回逐个用户的执行结果
+// - 失败：返回 AppError 错误
+pub fn batch_remove_user_roles_by_admin(
+    payload: AdminBatchUserRolesPayload,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    const COMMAND: &str = "auth_admin_batch_remove_user_roles";
+    let operator_username = payload.operator_username.clone();
+    let result = prepare_batch(&payload.operator_username, now_millis).and_then(|(_, scope)| {
+        let roles = normalize_roles(payload.roles)?;
+        scope.ensure_roles_assignable(&roles)?;
+        run_batch(
+            COMMAND,
+            &payload.operator_username,
+            &scope,
+            payload.user_ids,
+            payload.best_effort,
+            &BatchUserChange::RemoveRoles(roles),
+            now_millis,
+        )
+    });
+    audit_batch_failure(COMMAND, &operator_username, &result, now_millis);
+    result
+}
+
+// 管理员批量设置账号到期时间
+
+// 功能说明：
+// 将目标用户的到期时间设为指定时间戳；未提供时间戳时改为永久账号。
+// 激活状态保持不变，到期后由过期补偿逻辑停用。
+
+// 参数说明：
+// - payload: 包含用户 ID 列表与到期时间的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回逐个用户的执行结果
+// - 失败：返回 AppError 错误
+pub fn batch_set_users_expiry_by_admin(
+    payload: AdminBatchSetUsersExpiryPayload,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    const COMMAND: &str = "auth_admin_batch_set_users_expiry";
+    let operator_username = payload.operator_username.clone();
+    let result = prepare_batch(&payload.operator_username, now_millis).and_then(|(now, scope)| {
+        if payload
+            .account_expire_at
+            .is_some_and(|expire_at| expire_at <= now)
+        {
+            return Err(AppError::Validation(
+                "accountExpireAt must be in the future".to_string(),
+            ));
+        }
+        run_batch(
+            COMMAND,
+            &payload.operator_username,
+            &scope,
+            payload.user_ids,
+            payload.best_effort,
+            &BatchUserChange::SetExpiry(payload.account_expire_at),
+            now_millis,
+        )
+    });
+    audit_batch_failure(COMMAND, &operator_username, &result, now_millis);
+    result
+}
+
+// ==========================================================================================
+// 内部辅助函数
+// ==========================================================================================
+
+// 校验时间戳、解析操作员的管理范围并同步账号期限状态
+
+// 返回值：
+// - 成功：返回 (i64 类型的当前时间戳, 操作员管理范围)
+// - 失败：返回 AppError 错误
+fn prepare_batch(
+    operator_username: &str,
+    now_millis: u64,
+) -> Result<(i64, OperatorScope), AppError> {
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let scope = resolve_operator_scope(operator_username, now_millis)?;
+    // 按账号期限同步启用状态，批量操作基于与当前时间一致的账号状态执行
+    sync_account_term_states(now_millis)?;
+    Ok((now_millis, scope))
+}
+
+// 执行批量变更并汇总结果
+
+// 执行流程：
+// 1. 规范化用户 ID 列表
+// 2. 逐个校验目标用户是否可编辑且在管理范围内，受保护账号记为跳过
+// 3. 在单个事务中执行变更
+// 4. 按请求顺序汇总结果，并为每个执行过的用户写入审计事件
+fn run_batch(
+    command: &'static str,
+    operator_username: &str,
+    scope: &OperatorScope,
+    user_ids: Vec<i64>,
+    best_effort: bool,
+    change: &BatchUserChange,
+    now_millis: u64,
+) -> Result<AdminBatchUserOperationData, AppError> {
+    let now = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    let user_ids = normalize_user_ids(user_ids)?;
+
+    // 预检目标用户
+    let mut results: Vec<AdminBatchUserResultItem> = Vec::with_capacity(user_ids.len());
+    let mut targets = Vec::with_capacity(user_ids.len());
+    for &user_id in &user_ids {
+        match assert_target_user_editable(user_id)
+            .and_then(|()| scope.ensure_user_manageable(user_id))
+        {
+            Ok(()) => targets.push(user_id),
+            Err(AppError::Validation(message)) if message == PROTECTED_ADMIN_MESSAGE => {
+                results.push(result_item(user_id, STATUS_SKIPPED, Some(message)));
+            }
+            Err(err) if best_effort => {
+                results.push(result_item(user_id, STATUS_FAILED, Some(err.to_string())));
+            }
+            Err(AppError::Validation(message)) => {
+                return Err(AppError::Validation(format!("userId {user_id}: {message}")));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    // 执行变更
+    let outcomes = if targets.is_empty() {
+        Vec::new()
+    } else {
+        admin_repository::apply_batch_user_change(&targets, change, best_effort, now)?
+    };
+
+    // 汇总结果并写入审计
+    for outcome in outcomes {
+        match outcome.result {
+            Ok((before, after)) => {
+                UserAuditScope::with_before(
+                    command,
+                    operator_username,
+                    outcome.user_id,
+                    snapshot_record(before),
+                )
+                .finish_with_after(snapshot_record(after), None, now_millis);
+                results.push(result_item(outcome.user_id, STATUS_SUCCEEDED, None));
+            }
+            Err(err) => {
+                let message = err.to_string();
+                UserAuditScope::with_before(command, operator_username, outcome.user_id, None)
+                    .finish_with_after(None, Some(message.clone()), now_millis);
+                results.push(result_item(outcome.user_id, STATUS_FAILED, Some(message)));
+            }
+        }
+    }
+
+    // 按请求顺序排列
+    let mut by_user_id: HashMap<i64, AdminBatchUserResultItem> = results
+        .into_iter()
+        .map(|item| (item.user_id, item))
+        .collect();
+    let results: Vec<AdminBatchUserResultItem> = user_ids
+        .iter()
+        .filter_map(|user_id| by_user_id.remove(user_id))
+        .collect();
+    let count = |status: &str| results.iter().filter(|item| item.status == status).count();
+    Ok(AdminBatchUserOperationData {
+        best_effort,
+        succeeded_count: count(STATUS_SUCCEEDED),
+        skipped_count: count(STATUS_SKIPPED),
+        failed_count: count(STATUS_FAILED),
+        results,
+    })
+}
+
+// 规范化用户 ID 列表（按首次出现顺序去重）
+fn normalize_user_ids(user_ids: Vec<i64>) -> Result<Vec<i64>, AppError> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::with_capacity(user_ids.len());
+    for user_id in user_ids {
+        if user_id <= 0 {
+            return Err(AppError::Validation(format!("invalid userId: {user_id}")));
+        }
+        if seen.insert(user_id) {
+            normalized.push(user_id);
+        }
+    }
+
+    if normalized.is_empty() {
+        return Err(AppError::Validation("userIds is required".to_string()));
+    }
+    if normalized.len() > MAX_BATCH_USER_COUNT {
+        return Err(AppError::Validation(format!(
+            "userIds must not exceed {MAX_BATCH_USER_COUNT}"
+        )));
+    }
+    Ok(normalized)
+}
+
+// 构造单个用户结果
+fn result_item(user_id: i64, status: &str, message: Option<String>) -> AdminBatchUserResultItem {
+    AdminBatchUserResultItem {
+        user_id,
+        status: status.to_string(),
+        message,
+    }
+}
+
+// 批量操作整体失败时写入一条审计事件（无具体目标用户）
+fn audit_batch_failure(
+    command: &'static str,
+    operator_username: &str,
+    result: &Result<AdminBatchUserOperationData, AppError>,
+    now_millis: u64,
+) {
+    if let Err(err) = result {
+        UserAuditScope::begin(command, operator_username, None).finish_with_after(
+            None,
+            Some(err.to_string()),
+            now_millis,
+        );
+    }
+}
@@ -4,7 +4,8 @@
 //! 模块职责：
 //! 本模块负责接收前端发起的管理员特定 IPC 命令（Tauri Commands）。
 //! 主要是针对用户生命周期的增删改查（CRUD）操作、密码重置及账号续期功能。
-//! 该层作为适配器层，负责数据的解析、验证并转交业务逻辑（`admin_services`）处理。
+//! 该层作为适配器层，负责数据的解析、验证并转交业务逻辑（`admin_services` / `admin_batch_services` /
+//! `admin_delegation_services` / `device_scope_services`）处理。
 //!
 //! 功能清单：
 //!
@@ -14,35 +15,56 @@
 //! | `auth_admin_renew_user_account` | 管理员续期用户账号 |
 //! | `auth_admin_list_users` | 管理员列出所有用户 |
 //! | `auth_admin_update_user` | 管理员更新用户信息 |
-//! | `auth_admin_delete_user` | 管理员删除用户 |
+//! | `auth_admin_delete_user` | 管理员删除用户（软删除） |
+//! | `auth_admin_restore_user` | 管理员恢复已删除用户 |
+//! | `auth_admin_purge_deleted_users` | 管理员清理超过保留期的已删除用户 |
 //! | `auth_admin_change_user_password` | 管理员重置用户密码 |
-//! | `user_device_scope_get` | 获取用户设备范围（预留） |
-//! | `user_device_scope_upsert` | 更新用户设备范围（预留） |
+//! | `auth_admin_batch_renew_users` | 管理员批量续期用户账号 |
+//! | `auth_admin_batch_set_users_active` | 管理员批量启用/停用用户 |
+//! | `auth_admin_batch_add_user_roles` | 管理员批量增加用户角色 |
+//! | `auth_admin_batch_remove_user_roles` | 管理员批量移除用户角色 |
+//! | `auth_admin_batch_set_users_expiry` | 管理员批量设置账号到期时间 |
+//! | `auth_admin_set_user_delegation` | 管理员设置委派管理员（范围与可分配角色） |
+//! | `auth_admin_remove_user_delegation` | 管理员撤销委派管理员 |
+//! | `auth_admin_list_user_delegations` | 管理员查询委派管理员列表 |
+//! | `user_device_scope_get` | 获取用户设备范围 |
+//! | `user_device_scope_upsert` | 整体替换用户设备范围 |
+//! | `user_device_scope_check` | 判定用户能否访问指定设备 |
 //!
 //! 设计原则：
 //! - 薄层适配：本模块仅做参数校验和结果封装，不包含业务逻辑
-//! - 权限校验：验证操作者是否为管理员
+//! - 权限校验：验证操作者是否为管理员，或在委派范围内操作的委派管理员
 //! - 安全防护：防止误删核心 admin 账号
 //!
 //! ==========================================================================================
 
+// 引入管理员批量操作服务模块
+use crate::auth::admin_batch_services;
+// 引入委派管理员服务模块
+use crate::auth::admin_delegation_services;
 // 引入管理员服务模块，用于处理具体的业务逻辑
 use crate::auth::admin_services;
+// 引入用户设备范围服务模块
+use crate::auth::device_scope_services;
 
 // 引入鉴权模块的所有模型定义，这些结构体用于前后端数据交互
 use crate::auth::models::{
-    AdminChangeUserPasswordData, AdminChangeUserPasswordPayload, AdminDeleteUserPayload,
-    AdminListUsersPayload, AdminManagedUserData, AdminRegisterUserPayload, AdminRegisteredUserData,
-    AdminRenewUserAccountData, AdminRenewUserAccountPayload, AdminUpdateUserPayload,
-    UserDeviceScopeGetPayload, UserDeviceScopeReservedData, UserDeviceScopeSnapshot,
-    UserDeviceScopeUpsertPayload,
+    AdminBatchRenewUsersPayload, AdminBatchSetUsersActivePayload, AdminBatchSetUsersExpiryPayload,
+    AdminBatchUserOperationData, AdminBatchUserRolesPayload, AdminChangeUserPasswordData,
+    AdminChangeUserPasswordPayload, AdminDeleteUserPayload, AdminListUserDelegationsPayload,
+    AdminListUsersPayload, AdminManagedUserData, AdminPurgeDeletedUsersData,
+    AdminPurgeDeletedUsersPayload, AdminRegisterUserPayload, AdminRegisteredUserData,
+    AdminRemoveUserDelegationPayload, AdminRenewUserAccountData, AdminRenewUserAccountPayload,
+    AdminRestoreUserPayload, AdminSetUserDelegationPayload, AdminUpdateUserPayload,
+    AdminUserDelegationData, UserDeviceScopeCheckData, UserDeviceScopeCheckPayload,
+    UserDeviceScopeData, UserDeviceScopeGetPayload, UserDeviceScopeUpsertPayload,
 };
 
 // 引入时间工具函数，用于获取当前时间戳
 use crate::auth::services::now_millis;
 
 // 引入核心错误和响应类型，用于统一错误处理和响应格式
-use crate::core::error::{ApiResponse, AppError, AppResult};
+use crate::core::error::{ApiResponse, AppResult};
 use crate::core::tracing::{TraceContext, execute_traced_command};
 
 // ==========================================================================================
@@ -62,8 +84,10 @@ use crate::core::tracing::{TraceContext, execute_traced_command};
 // - nickname: 新用户的昵称
 // - phone: 手机号（可选）
 // - roles: 角色列表
-// - account_term_type: 账号期限类型（permanent/days）
+// - account_term_type: 账号期限类型（permanent/days/range）
 // - account_valid_days: 有效天数（当 term_type 为 days 时必填）
+// - account_start_at: 生效时间（可选；当 term_type 为 range 时必填）
+// - account_expire_at: 到期时间（当 term_type 为 range 时必填）
 //
 // 返回值：
 // 返回新创建用户的 ID、用户名、角色和账号状态
@@ -88,7 +112,7 @@ pub fn auth_admin_register_user(
 // - operator_username: 操作的管理员用户名
 // - user_id: 目标用户 ID
 // - renew_mode: 续期模式（permanent/days）
-// - renew_days: 续期天数（当 renew_mode 为 days 时必填）
+// - renew_days: 续期天数（当 renew_mode 为 days 时必填；账号未过期时从当前到期时间顺延）
 //
 // 返回值：
 // 返回更新后的账号状态信息
@@ -111,6 +135,7 @@ pub fn auth_admin_renew_user_account(
 //
 // 参数说明：
 // - operator_username: 操作的管理员用户名
+// - include_deleted: 是否包含已软删除的用户（默认 false）
 //
 // 返回值：
 // 返回所有用户的详细信息列表
@@ -139,8 +164,10 @@ pub fn auth_admin_list_users(
 // - phone: 手机号（可选）
 // - roles: 角色列表
 // - is_active: 是否激活
-// - account_term_type: 账号期限类型
+// - account_term_type: 账号期限类型（permanent/days/range）
 // - account_valid_days: 有效天数
+// - account_start_at: 生效时间（可选；当 term_type 为 range 时必填）
+// - account_expire_at: 到期时间（当 term_type 为 range 时必填）
 //
 // 返回值：
 // 返回更新后的用户信息
@@ -158,7 +185,7 @@ pub fn auth_admin_update_user(
 // 管理员删除指定用户命令
 //
 // 功能说明：
-// 软删除或硬删除指定用户。
+// 软删除指定用户，记录删除时间与操作人，用户名在清理前保持占用。
 // 内置安全保护机制，防止核心 admin 账号被意外删除。
 //
 // 参数说明：
@@ -178,6 +205,50 @@ pub fn auth_admin_delete_user(
     })
 }
 
+// 管理员恢复已软删除用户命令
+//
+// 功能说明：
+// 清除用户的软删除标记，账号未过期时同时恢复激活状态。
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_id: 目标用户 ID
+//
+// 返回值：
+// 返回恢复后的用户信息
+#[tauri::command]
+pub fn auth_admin_restore_user(
+    payload: AdminRestoreUserPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminManagedUserData> {
+    execute_traced_command("auth_admin_restore_user", trace, || {
+        let data = admin_services::restore_user_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员清理已删除用户命令
+//
+// 功能说明：
+// 物理删除软删除时间超过保留期的用户，释放其用户名。
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - retention_days: 保留天数（可选，默认 30 天）
+//
+// 返回值：
+// 返回清理数量与清理截止时间
+#[tauri::command]
+pub fn auth_admin_purge_deleted_users(
+    payload: AdminPurgeDeletedUsersPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminPurgeDeletedUsersData> {
+    execute_traced_command("auth_admin_purge_deleted_users", trace, || {
+        let data = admin_services::purge_deleted_users_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
 // 管理员强制重置或修改任意用户密码命令
 //
 // 功能说明：
@@ -203,67 +274,252 @@ pub fn auth_admin_change_user_password(
 }
 
 // ==========================================================================================
-// 预留接口
+// 批量账号操作
 // ==========================================================================================
 
-// 预留接口：获取指定用户的设备与区域管理边界配置
+// 管理员批量续期用户账号命令
 //
 // 功能说明：
-// 这是一个预留接口，目前尚未实现。
-// 未来将用于管理用户对设备、区域和楼层的访问权限。
+// 对一组用户按相同的续期方式（permanent / days）续期并激活账号。
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_ids: 目标用户 ID 列表
+// - renew_mode: 续期模式（permanent/days）
+// - renew_days: 续期天数（当 renew_mode 为 days 时必填）
+// - best_effort: 是否尽力而为（默认 false，全部成功或全部回滚）
+//
+// 返回值：
+// 返回成功/跳过/失败数量及按请求顺序排列的逐个结果（受保护的 admin 账号记为跳过）
+#[tauri::command]
+pub fn auth_admin_batch_renew_users(
+    payload: AdminBatchRenewUsersPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminBatchUserOperationData> {
+    execute_traced_command("auth_admin_batch_renew_users", trace, || {
+        let data = admin_batch_services::batch_renew_users_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员批量启用/停用用户命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_ids: 目标用户 ID 列表
+// - is_active: 目标激活状态
+// - best_effort: 是否尽力而为
+//
+// 返回值：
+// 返回成功/跳过/失败数量及按请求顺序排列的逐个结果（受保护的 admin 账号记为跳过）
+#[tauri::command]
+pub fn auth_admin_batch_set_users_active(
+    payload: AdminBatchSetUsersActivePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminBatchUserOperationData> {
+    execute_traced_command("auth_admin_batch_set_users_active", trace, || {
+        let data = admin_batch_services::batch_set_users_active_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员批量增加用户角色命令
 //
 // 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_ids: 目标用户 ID 列表
+// - roles: 要增加的角色列表
+// - best_effort: 是否尽力而为
+//
+// 返回值：
+// 返回成功/跳过/失败数量及按请求顺序排列的逐个结果（受保护的 admin 账号记为跳过）
+#[tauri::command]
+pub fn auth_admin_batch_add_user_roles(
+    payload: AdminBatchUserRolesPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminBatchUserOperationData> {
+    execute_traced_command("auth_admin_batch_add_user_roles", trace, || {
+        let data = admin_batch_services::batch_add_user_roles_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员批量移除用户角色命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_ids: 目标用户 ID 列表
+// - roles: 要移除的角色列表（用户至少保留一个角色）
+// - best_effort: 是否尽力而为
+//
+// 返回值：
+// 返回成功/跳过/失败数量及按请求顺序排列的逐个结果（受保护的 admin 账号记为跳过）
+#[tauri::command]
+pub fn auth_admin_batch_remove_user_roles(
+    payload: AdminBatchUserRolesPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminBatchUserOperationData> {
+    execute_traced_command("auth_admin_batch_remove_user_roles", trace, || {
+        let data = admin_batch_services::batch_remove_user_roles_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员批量设置账号到期时间命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_ids: 目标用户 ID 列表
+// - account_expire_at: 到期时间戳（毫秒），为空表示改为永久账号
+// - best_effort: 是否尽力而为
+//
+// 返回值：
+// 返回成功/跳过/失败数量及按请求顺序排列的逐个结果（受保护的 admin 账号记为跳过）
+#[tauri::command]
+pub fn auth_admin_batch_set_users_expiry(
+    payload: AdminBatchSetUsersExpiryPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminBatchUserOperationData> {
+    execute_traced_command("auth_admin_batch_set_users_expiry", trace, || {
+        let data = admin_batch_services::batch_set_users_expiry_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// ==========================================================================================
+// 委派管理员
+// ==========================================================================================
+
+// 管理员设置委派管理员命令
+//
+// 功能说明：
+// 将有限的账号管理能力委派给非 admin 用户：只能管理授权组织子树内或本人创建的用户，
+// 只能分配授权的角色，且授权角色的权限不能超过委派人自身角色。
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_id: 委派人用户 ID
+// - organization_id: 可管理的组织子树根节点（可选）
+// - manage_created_users: 是否可管理本人创建的用户
+// - assignable_roles: 可分配的角色列表
+//
+// 返回值：
+// 返回保存后的委派配置
+#[tauri::command]
+pub fn auth_admin_set_user_delegation(
+    payload: AdminSetUserDelegationPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<AdminUserDelegationData> {
+    execute_traced_command("auth_admin_set_user_delegation", trace, || {
+        let data = admin_delegation_services::set_user_delegation_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员撤销委派管理员命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_id: 委派人用户 ID
+//
+// 返回值：
+// 撤销成功返回 true
+#[tauri::command]
+pub fn auth_admin_remove_user_delegation(
+    payload: AdminRemoveUserDelegationPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("auth_admin_remove_user_delegation", trace, || {
+        let data =
+            admin_delegation_services::remove_user_delegation_by_admin(&payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 管理员查询委派管理员列表命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+//
+// 返回值：
+// 返回按委派人用户名排序的委派配置
+#[tauri::command]
+pub fn auth_admin_list_user_delegations(
+    payload: AdminListUserDelegationsPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<AdminUserDelegationData>> {
+    execute_traced_command("auth_admin_list_user_delegations", trace, || {
+        let data =
+            admin_delegation_services::list_user_delegations_by_admin(&payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// ==========================================================================================
+// 用户设备范围
+// ==========================================================================================
+
+// 获取指定用户的设备范围命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
 // - user_id: 用户 ID
 //
 // 返回值：
-// 返回预留状态信息，implemented 字段为 false
+// 返回通配标记与区域、楼层、设备列表；未配置时全部为空
 #[tauri::command]
 pub fn user_device_scope_get(
-    _payload: UserDeviceScopeGetPayload,
+    payload: UserDeviceScopeGetPayload,
     trace: Option<TraceContext>,
-) -> AppResult<UserDeviceScopeReservedData> {
+) -> AppResult<UserDeviceScopeData> {
     execute_traced_command("user_device_scope_get", trace, || {
-        Ok(ApiResponse::ok(UserDeviceScopeReservedData {
-            implemented: false,
-            message: admin_services::reserved_device_scope_message().to_string(),
-            scope: UserDeviceScopeSnapshot {
-                all_areas: false,
-                all_floors: false,
-                all_devices: false,
-                areas: vec![],
-                floors: vec![],
-                devices: vec![],
-            },
-        }))
+        let data = device_scope_services::get_user_device_scope_by_admin(&payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
     })
 }
 
-// 预留接口：更新指定用户的设备管理边界
-//
-// 功能说明：
-// 这是一个预留接口，目前尚未实现。
-// 未来将用于更新用户对设备、区域和楼层的访问权限。
+// 整体替换指定用户的设备范围命令
 //
 // 参数说明：
+// - operator_username: 操作的管理员用户名
 // - user_id: 用户 ID
 // - all_areas: 是否可访问所有区域
 // - all_floors: 是否可访问所有楼层
 // - all_devices: 是否可访问所有设备
-// - areas: 可访问的区域列表
-// - floors: 可访问的楼层列表
-// - devices: 可访问的设备列表
+// - areas: 可访问的区域列表（须已存在）
+// - floors: 可访问的楼层列表（须已存在）
+// - devices: 可访问的设备列表（须已存在）
 //
 // 返回值：
-// 返回错误，表示接口尚未实现
+// 返回保存后的设备范围
 #[tauri::command]
 pub fn user_device_scope_upsert(
-    _payload: UserDeviceScopeUpsertPayload,
+    payload: UserDeviceScopeUpsertPayload,
     trace: Option<TraceContext>,
-) -> AppResult<bool> {
+) -> AppResult<UserDeviceScopeData> {
     execute_traced_command("user_device_scope_upsert", trace, || {
-        Err(AppError::Validation(
-            admin_services::reserved_device_scope_message().to_string(),
-        ))
+        let data = device_scope_services::upsert_user_device_scope_by_admin(payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
+// 判定指定用户能否访问设备命令
+//
+// 参数说明：
+// - operator_username: 操作的管理员用户名
+// - user_id: 用户 ID
+// - device_id: 设备标识
+//
+// 返回值：
+// 返回是否可访问及命中的授权来源
+#[tauri::command]
+pub fn user_device_scope_check(
+    payload: UserDeviceScopeCheckPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<UserDeviceScopeCheckData> {
+    execute_traced_command("user_device_scope_check", trace, || {
+        let data = device_scope_services::check_user_device_scope_by_admin(&payload, now_millis())?;
+        Ok(ApiResponse::ok(data))
     })
 }
 
@@ -279,6 +535,8 @@ mod tests {
     use std::sync::atomic::{AtomicU64, Ordering};
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    // 引入应用错误类型
+    use crate::core::error::AppError;
     // 引入数据库模块
     use crate::db;
 
@@ -320,6 +578,9 @@ mod tests {
             roles: vec!["tenant".to_string(), "operator".to_string()],
             account_term_type: "days".to_string(),
             account_valid_days: Some(30),
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
         };
 
         // 执行注册
@@ -351,6 +612,9 @@ mod tests {
             roles: vec!["tenant".to_string()],
             account_term_type: "permanent".to_string(),
             account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
         };
 
         // 执行注册并期望返回错误
@@ -377,6 +641,9 @@ mod tests {
             roles: vec!["tenant".to_string()],
             account_term_type: "days".to_string(),
             account_valid_days: Some(7),
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
         };
         let register_result =
             auth_admin_register_user(register_payload, None).expect("register user for renew");
@@ -393,8 +660,14 @@ mod tests {
         assert_eq!(renewed.data.user_id, register_result.data.user_id);
         // 断言不是永久账号
         assert!(!renewed.data.account_is_permanent);
-        // 断言有过期时间
-        assert!(renewed.data.account_expire_at.is_some());
+        // 断言账号未过期时从原到期时间顺延
+        assert_eq!(
+            renewed.data.account_expire_at,
+            register_result
+                .data
+                .account_expire_at
+                .map(|expire_at| expire_at + 90 * 24 * 60 * 60 * 1000)
+        );
         // 断言账号已激活
         assert!(renewed.data.is_active);
     }
@@ -428,6 +701,8 @@ mod tests {
         // 创建列出用户请求
         let payload = AdminListUsersPayload {
             operator_username: "admin".to_string(),
+            include_deleted: false,
+            organization_id: None,
         };
         let result = auth_admin_list_users(payload, None).expect("list users");
         // 断言用户列表不为空
@@ -452,6 +727,8 @@ mod tests {
             is_active: true,
             account_term_type: "permanent".to_string(),
             account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
         };
         let err = auth_admin_update_user(payload, None).expect_err("expect protected user check");
         // 断言错误消息
@@ -476,6 +753,9 @@ mod tests {
             roles: vec!["tenant".to_string()],
             account_term_type: "days".to_string(),
             account_valid_days: Some(30),
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
         };
         let registered = auth_admin_register_user(register_payload, None).expect("register user");
 
@@ -490,6 +770,8 @@ mod tests {
             is_active: true,
             account_term_type: "permanent".to_string(),
             account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
         };
         let updated = auth_admin_update_user(update_payload, None).expect("update user");
         // 断言用户 ID 匹配
@@ -517,6 +799,443 @@ mod tests {
         assert!(deleted.data);
     }
 
+    // 测试：验证软删除后的用户不可登录、默认列表不可见，且可被恢复
+    #[test]
+    fn admin_can_soft_delete_and_restore_user() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        // 注册新用户
+        let username = unique_username("tenant_for_restore");
+        let register_payload = AdminRegisterUserPayload {
+            operator_username: "admin".to_string(),
+            username: username.clone(),
+            password: "admin123".to_string(),
+            nickname: "restore target".to_string(),
+            phone: None,
+            roles: vec!["tenant".to_string()],
+            account_term_type: "permanent".to_string(),
+            account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
+        };
+        let registered = auth_admin_register_user(register_payload, None).expect("register user");
+
+        // 软删除用户
+        let delete_payload = AdminDeleteUserPayload {
+            operator_username: "admin".to_string(),
+            user_id: registered.data.user_id,
+        };
+        auth_admin_delete_user(delete_payload, None).expect("soft delete user");
+
+        // 已删除用户无法登录
+        let login_err = crate::auth::commands::auth_login(
+            crate::auth::models::LoginPayload {
+                username: username.clone(),
+                password: "admin123".to_string(),
+            },
+            None,
+        )
+        .expect_err("deleted user should not login");
+        assert!(matches!(login_err, AppError::Validation(_)));
+
+        // 默认列表不包含已删除用户
+        let visible = auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: "admin".to_string(),
+                include_deleted: false,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("list users");
+        assert!(!visible.data.iter().any(|item| item.username == username));
+
+        // 回收站视图包含删除标记
+        let with_deleted = auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: "admin".to_string(),
+                include_deleted: true,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("list users with deleted");
+        let deleted_item = with_deleted
+            .data
+            .iter()
+            .find(|item| item.username == username)
+            .expect("deleted user listed");
+        assert!(deleted_item.deleted_at.is_some());
+        assert_eq!(deleted_item.deleted_by.as_deref(), Some("admin"));
+
+        // 用户名在清理前保持占用
+        let reuse_payload = AdminRegisterUserPayload {
+            operator_username: "admin".to_string(),
+            username: username.clone(),
+            password: "admin123".to_string(),
+            nickname: "reuse attempt".to_string(),
+            phone: None,
+            roles: vec!["tenant".to_string()],
+            account_term_type: "permanent".to_string(),
+            account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
+        };
+        let reuse_err =
+            auth_admin_register_user(reuse_payload, None).expect_err("username stays reserved");
+        assert_eq!(
+            reuse_err,
+            AppError::Validation("username already exists".to_string())
+        );
+
+        // 恢复用户
+        let restored = auth_admin_restore_user(
+            AdminRestoreUserPayload {
+                operator_username: "admin".to_string(),
+                user_id: registered.data.user_id,
+            },
+            None,
+        )
+        .expect("restore user");
+        assert!(restored.data.is_active);
+        assert!(restored.data.deleted_at.is_none());
+        assert!(restored.data.deleted_by.is_none());
+    }
+
+    // 测试：验证删除前已停用的用户恢复后仍保持停用
+    #[test]
+    fn restore_keeps_user_disabled_before_delete_inactive() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (username, user_id) =
+            register_active_user("tenant_disabled_restore", &["tenant"], None);
+
+        // 停用用户
+        auth_admin_update_user(
+            AdminUpdateUserPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                username,
+                nickname: "委派测试".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                is_active: false,
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+            },
+            None,
+        )
+        .expect("disable user");
+
+        // 软删除后恢复
+        auth_admin_delete_user(
+            AdminDeleteUserPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+            },
+            None,
+        )
+        .expect("soft delete user");
+        let restored = auth_admin_restore_user(
+            AdminRestoreUserPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+            },
+            None,
+        )
+        .expect("restore user");
+        // 断言恢复后仍为停用状态
+        assert!(!restored.data.is_active);
+        assert!(restored.data.deleted_at.is_none());
+    }
+
+    // 测试：验证恢复未删除的用户返回错误
+    #[test]
+    fn restore_rejects_user_that_is_not_deleted() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let err = auth_admin_restore_user(
+            AdminRestoreUserPayload {
+                operator_username: "admin".to_string(),
+                user_id: 1,
+            },
+            None,
+        )
+        .expect_err("admin is not deleted");
+        assert_eq!(
+            err,
+            AppError::Validation("deleted user not found".to_string())
+        );
+    }
+
+    // 测试：验证清理仅作用于超过保留期的已删除用户
+    #[test]
+    fn purge_removes_users_deleted_before_retention_window() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        // 注册并软删除用户
+        let username = unique_username("tenant_for_purge");
+        let registered = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: username.clone(),
+                password: "admin123".to_string(),
+                nickname: "purge target".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("register user");
+        auth_admin_delete_user(
+            AdminDeleteUserPayload {
+                operator_username: "admin".to_string(),
+                user_id: registered.data.user_id,
+            },
+            None,
+        )
+        .expect("soft delete user");
+
+        // 将删除时间回拨到保留期之外
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query("UPDATE users SET deleted_at = deleted_at - $1 WHERE id = $2")
+                .bind(400_i64 * 24 * 60 * 60 * 1000)
+                .bind(registered.data.user_id)
+                .execute(&mut connection),
+        )
+        .expect("age deleted user");
+
+        // 执行清理
+        let purged = auth_admin_purge_deleted_users(
+            AdminPurgeDeletedUsersPayload {
+                operator_username: "admin".to_string(),
+                retention_days: Some(365),
+            },
+            None,
+        )
+        .expect("purge deleted users");
+        assert!(purged.data.purged_count >= 1);
+
+        // 用户名已释放，可重新注册
+        let reused = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username,
+                password: "admin123".to_string(),
+                nickname: "reused name".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("username released after purge");
+        assert_ne!(reused.data.user_id, registered.data.user_id);
+    }
+
+    // 辅助函数：注册一个生效时间在一天后的起止期限用户
+    fn register_scheduled_user(prefix: &str) -> (String, i64) {
+        let now = i64::try_from(crate::auth::services::now_millis()).expect("now");
+        let username = unique_username(prefix);
+        let registered = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: username.clone(),
+                password: "admin123".to_string(),
+                nickname: "scheduled tenant".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "range".to_string(),
+                account_valid_days: None,
+                account_start_at: Some(now + 24 * 60 * 60 * 1000),
+                account_expire_at: Some(now + 30 * 24 * 60 * 60 * 1000),
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("register scheduled user");
+        // 生效时间未到：账号保持停用
+        assert!(!registered.data.is_active);
+        assert!(registered.data.account_start_at.is_some());
+        // 清除强制改密标记，排除其对角色解析的影响
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query("UPDATE users SET must_change_password = 0 WHERE id = $1")
+                .bind(registered.data.user_id)
+                .execute(&mut connection),
+        )
+        .expect("clear must change password");
+        (username, registered.data.user_id)
+    }
+
+    // 辅助函数：将用户生效时间回拨到过去
+    fn move_account_start_to_past(user_id: i64) {
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query("UPDATE users SET account_start_at = account_start_at - $1 WHERE id = $2")
+                .bind(2_i64 * 24 * 60 * 60 * 1000)
+                .bind(user_id)
+                .execute(&mut connection),
+        )
+        .expect("move account start");
+    }
+
+    // 测试：起止期限账号在生效时间前不可用，到达生效时间后登录检查自动激活
+    #[test]
+    fn scheduled_account_activates_when_start_arrives() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (username, user_id) = register_scheduled_user("tenant_scheduled");
+        let now = crate::auth::services::now_millis();
+        let now_i64 = i64::try_from(now).expect("now");
+
+        // 生效前：不可登录且没有有效角色
+        let err = crate::auth::admin_services::ensure_user_available_with_message(
+            &username, "denied", now,
+        )
+        .expect_err("account not started");
+        assert_eq!(err, AppError::Validation("denied".to_string()));
+        assert!(
+            crate::db::admin_repository::find_effective_roles(&username, now_i64)
+                .expect("roles")
+                .is_empty()
+        );
+
+        // 到达生效时间后：登录检查激活账号
+        move_account_start_to_past(user_id);
+        crate::auth::commands::auth_login(
+            crate::auth::models::LoginPayload {
+                username: username.clone(),
+                password: "admin123".to_string(),
+            },
+            None,
+        )
+        .expect("login after start");
+        assert_eq!(
+            crate::db::admin_repository::find_effective_roles(&username, now_i64).expect("roles"),
+            vec!["tenant".to_string()]
+        );
+        let user = crate::db::admin_repository::find_managed_user(user_id)
+            .expect("find user")
+            .expect("user exists");
+        assert!(user.is_active);
+        assert!(!user.account_activation_pending);
+    }
+
+    // 测试：启动补偿任务激活已到生效时间的待生效账号
+    #[test]
+    fn startup_compensation_activates_started_accounts() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (_, user_id) = register_scheduled_user("tenant_scheduled_sweep");
+        move_account_start_to_past(user_id);
+
+        let changed = crate::auth::admin_services::run_startup_expiration_compensation(
+            crate::auth::services::now_millis(),
+        )
+        .expect("run compensation");
+        assert!(changed >= 1);
+        let user = crate::db::admin_repository::find_managed_user(user_id)
+            .expect("find user")
+            .expect("user exists");
+        assert!(user.is_active);
+        assert!(!user.account_activation_pending);
+    }
+
+    // 测试：管理员列表与批量操作前同步已到生效时间的待生效账号
+    #[test]
+    fn admin_listing_and_batch_ops_see_started_accounts_active() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (username, user_id) = register_scheduled_user("tenant_scheduled_listed");
+        move_account_start_to_past(user_id);
+
+        // 未登录也未重启：列表中已显示为启用
+        let listed = auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: "admin".to_string(),
+                include_deleted: false,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("list users");
+        let item = listed
+            .data
+            .iter()
+            .find(|item| item.username == username)
+            .expect("scheduled user listed");
+        assert!(item.is_active);
+        assert!(!item.account_activation_pending);
+
+        // 批量停用作用于已激活的账号，之后不会因生效时间再次激活
+        let (_, other_user_id) = register_scheduled_user("tenant_scheduled_batch");
+        move_account_start_to_past(other_user_id);
+        let result = auth_admin_batch_set_users_active(
+            AdminBatchSetUsersActivePayload {
+                operator_username: "admin".to_string(),
+                user_ids: vec![other_user_id],
+                is_active: false,
+                best_effort: false,
+            },
+            None,
+        )
+        .expect("batch disable");
+        assert_eq!(result.data.succeeded_count, 1);
+        crate::auth::admin_services::run_startup_expiration_compensation(
+            crate::auth::services::now_millis(),
+        )
+        .expect("run compensation");
+        let user = crate::db::admin_repository::find_managed_user(other_user_id)
+            .expect("find user")
+            .expect("user exists");
+        assert!(!user.is_active);
+        assert!(!user.account_activation_pending);
+    }
+
+    // 测试：起止期限要求到期时间晚于生效时间
+    #[test]
+    fn range_term_rejects_expiry_before_start() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let now = i64::try_from(crate::auth::services::now_millis()).expect("now");
+        let err = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: unique_username("tenant_bad_range"),
+                password: "admin123".to_string(),
+                nickname: "bad range".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "range".to_string(),
+                account_valid_days: None,
+                account_start_at: Some(now + 2 * 24 * 60 * 60 * 1000),
+                account_expire_at: Some(now + 24 * 60 * 60 * 1000),
+                organization_id: None,
+            },
+            None,
+        )
+        .expect_err("expect invalid range");
+        assert_eq!(
+            err,
+            AppError::Validation("accountExpireAt must be later than accountStartAt".to_string())
+        );
+    }
+
     // 测试：验证管理员可以修改受保护的 admin 用户密码
     #[test]
     fn admin_can_change_password_for_protected_admin_user() {
@@ -531,28 +1250,974 @@ mod tests {
         let result = auth_admin_change_user_password(payload, None).expect("change password");
         // 断言用户名正确
         assert_eq!(result.data.username, "admin");
+        // 重置本人密码不会触发强制改密
+        assert!(!result.data.must_change_password);
+    }
+
+    // 辅助函数：以普通期限注册一个测试用户并返回用户名和用户 ID
+    fn register_password_change_user(prefix: &str) -> (String, i64) {
+        let username = unique_username(prefix);
+        let registered = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: username.clone(),
+                password: "init123".to_string(),
+                nickname: "password change".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("register user");
+        (username, registered.data.user_id)
+    }
+
+    // 辅助函数：以指定密码登录
+    fn login(username: &str, password: &str) -> crate::auth::models::LoginData {
+        crate::auth::commands::auth_login(
+            crate::auth::models::LoginPayload {
+                username: username.to_string(),
+                password: password.to_string(),
+            },
+            None,
+        )
+        .expect("login")
+        .data
     }
 
-    // 测试：验证预留的 upsert 接口返回未实现错误
+    // 测试：管理员创建的账号首次登录只获得受限令牌，修改密码后恢复常规令牌；
+    // 管理员重置其密码后再次要求修改
     #[test]
-    fn reserved_upsert_returns_not_implemented() {
+    fn admin_registered_user_must_change_password() {
         // 准备测试数据库
         ensure_test_db_ready();
-        // 尝试调用预留接口
-        let payload = UserDeviceScopeUpsertPayload {
-            user_id: 1,
-            all_areas: false,
-            all_floors: false,
-            all_devices: false,
-            areas: vec![],
-            floors: vec![],
-            devices: vec![],
+        let (username, user_id) = register_password_change_user("tenant_first_login");
+        let now = i64::try_from(crate::auth::services::now_millis()).expect("now");
+
+        // 首次登录：受限令牌，且不解析任何有效角色
+        let first = login(&username, "init123");
+        assert!(first.must_change_password);
+        let identity = crate::auth::services::verify_password_change_token(&first.access_token)
+            .expect("verify password change token");
+        assert_eq!(identity.username, username);
+        assert!(identity.password_change_only);
+        assert!(
+            crate::db::admin_repository::find_effective_roles(&username, now)
+                .expect("roles")
+                .is_empty()
+        );
+
+        // 新密码不能与旧密码相同
+        let err = crate::auth::commands::auth_change_own_password(
+            crate::auth::models::ChangeOwnPasswordPayload {
+                access_token: first.access_token.clone(),
+                old_password: "init123".to_string(),
+                new_password: "init123".to_string(),
+            },
+            None,
+        )
+        .expect_err("expect same password rejected");
+        assert_eq!(
+            err,
+            AppError::Validation("newPassword must differ from oldPassword".to_string())
+        );
+
+        // 使用受限令牌修改密码：返回常规令牌并恢复角色
+        let changed = crate::auth::commands::auth_change_own_password(
+            crate::auth::models::ChangeOwnPasswordPayload {
+                access_token: first.access_token,
+                old_password: "init123".to_string(),
+                new_password: "mine456".to_string(),
+            },
+            None,
+        )
+        .expect("change own password")
+        .data;
+        assert!(!changed.must_change_password);
+        assert!(
+            !crate::auth::services::verify_access_token(&changed.access_token)
+                .expect("verify access token")
+                .password_change_only
+        );
+        assert_eq!(
+            crate::db::admin_repository::find_effective_roles(&username, now).expect("roles"),
+            vec!["tenant".to_string()]
+        );
+        assert!(!login(&username, "mine456").must_change_password);
+
+        // 管理员重置他人密码：再次要求修改，刷新令牌也只能拿到受限令牌
+        let reset = auth_admin_change_user_password(
+            AdminChangeUserPasswordPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                password: "reset789".to_string(),
+            },
+            None,
+        )
+        .expect("reset password");
+        assert!(reset.data.must_change_password);
+        let relogin = login(&username, "reset789");
+        assert!(relogin.must_change_password);
+        let refreshed = crate::auth::commands::auth_refresh_token(
+            crate::auth::models::RefreshTokenPayload {
+                refresh_token: relogin.refresh_token,
+            },
+            None,
+        )
+        .expect("refresh token")
+        .data;
+        assert!(refreshed.must_change_password);
+        assert!(
+            crate::auth::services::verify_password_change_token(&refreshed.access_token)
+                .expect("verify password change token")
+                .password_change_only
+        );
+    }
+
+    // 测试：修改本人密码时旧密码错误被拒绝
+    #[test]
+    fn change_own_password_rejects_wrong_old_password() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (username, _) = register_password_change_user("tenant_wrong_old");
+        let first = login(&username, "init123");
+
+        let err = crate::auth::commands::auth_change_own_password(
+            crate::auth::models::ChangeOwnPasswordPayload {
+                access_token: first.access_token,
+                old_password: "wrong".to_string(),
+                new_password: "mine456".to_string(),
+            },
+            None,
+        )
+        .expect_err("expect wrong old password");
+        assert_eq!(err, AppError::Validation("invalid oldPassword".to_string()));
+        // 标记保持不变
+        assert!(login(&username, "init123").must_change_password);
+    }
+
+    // 测试：受限令牌只能用于修改本人密码；密码首尾空白按原样校验与保存
+    #[test]
+    fn password_change_tokens_are_limited_and_passwords_are_not_trimmed() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (username, _) = register_password_change_user("tenant_scoped_token");
+        let first = login(&username, "init123");
+
+        // 受限令牌在统一的访问令牌校验中被拒绝，改密流程仍可使用
+        assert_eq!(
+            crate::auth::services::verify_access_token(&first.access_token)
+                .expect_err("restricted token rejected"),
+            AppError::Validation("password change required".to_string())
+        );
+        let change = |access_token: &str, old_password: &str, new_password: &str| {
+            crate::auth::commands::auth_change_own_password(
+                crate::auth::models::ChangeOwnPasswordPayload {
+                    access_token: access_token.to_string(),
+                    old_password: old_password.to_string(),
+                    new_password: new_password.to_string(),
+                },
+                None,
+            )
         };
-        let err = user_device_scope_upsert(payload, None).expect_err("expect reserved message");
-        // 断言错误消息
+        assert_eq!(
+            change(&first.access_token, "init123", "   ").expect_err("blank password"),
+            AppError::Validation("newPassword is required".to_string())
+        );
+        let changed = change(&first.access_token, "init123", " mine 456 ")
+            .expect("change own password")
+            .data;
+        let identity = crate::auth::services::verify_access_token(&changed.access_token)
+            .expect("verify access token");
+        assert_eq!(identity.username, username);
+        assert!(!identity.password_change_only);
+
+        // 新密码保留首尾空白：去除空白后登录失败，旧密码同样按原样比较
+        assert!(
+            crate::auth::commands::auth_login(
+                crate::auth::models::LoginPayload {
+                    username: username.clone(),
+                    password: "mine 456".to_string(),
+                },
+                None,
+            )
+            .is_err()
+        );
+        let relogin = login(&username, " mine 456 ");
+        assert_eq!(
+            change(&relogin.access_token, "mine 456", "other789").expect_err("trimmed old"),
+            AppError::Validation("invalid oldPassword".to_string())
+        );
+        change(&relogin.access_token, " mine 456 ", "other789").expect("change again");
+        assert!(!login(&username, "other789").must_change_password);
+    }
+
+    // 辅助函数：注册一个测试用户并返回用户 ID
+    fn register_batch_user(prefix: &str, roles: &[&str]) -> i64 {
+        let payload = AdminRegisterUserPayload {
+            operator_username: "admin".to_string(),
+            username: unique_username(prefix),
+            password: "admin123".to_string(),
+            nickname: "批量用户".to_string(),
+            phone: None,
+            roles: roles.iter().map(ToString::to_string).collect(),
+            account_term_type: "days".to_string(),
+            account_valid_days: Some(1),
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
+        };
+        auth_admin_register_user(payload, None)
+            .expect("register batch user")
+            .data
+            .user_id
+    }
+
+    // 测试：批量续期跳过受保护的 admin 账号，其余用户全部续期
+    #[test]
+    fn batch_renew_skips_protected_admin_and_renews_others() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let first = register_batch_user("batch_renew", &["tenant"]);
+        let second = register_batch_user("batch_renew", &["tenant"]);
+
+        // 批量续期（包含 admin 与重复 ID）
+        let payload = AdminBatchRenewUsersPayload {
+            operator_username: "admin".to_string(),
+            user_ids: vec![first, 1, second, first],
+            renew_mode: "permanent".to_string(),
+            renew_days: None,
+            best_effort: false,
+        };
+        let data = auth_admin_batch_renew_users(payload, None)
+            .expect("batch renew")
+            .data;
+
+        // 断言结果按请求顺序去重，admin 被跳过
+        let statuses: Vec<(i64, &str)> = data
+            .results
+            .iter()
+            .map(|item| (item.user_id, item.status.as_str()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![(first, "succeeded"), (1, "skipped"), (second, "succeeded")]
+        );
+        assert_eq!(data.succeeded_count, 2);
+        assert_eq!(data.skipped_count, 1);
+
+        // 断言用户已变为永久账号
+        let users = auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: "admin".to_string(),
+                include_deleted: false,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("list users")
+        .data;
+        for user_id in [first, second] {
+            let user = users
+                .iter()
+                .find(|user| user.user_id == user_id)
+                .expect("renewed user");
+            assert!(user.account_is_permanent);
+            assert!(user.account_expire_at.is_none());
+
+            // 断言每个受影响用户各有一条审计事件
+            let audit = crate::audit::services::query_events(
+                crate::audit::models::AuditQueryPayload {
+                    operator_username: "admin".to_string(),
+                    command: Some("auth_admin_batch_renew_users".to_string()),
+                    target_id: Some(user_id.to_string()),
+                    ..Default::default()
+                },
+                now_millis(),
+            )
+            .expect("query audit events");
+            assert_eq!(audit.total, 1);
+            assert_eq!(audit.items[0].result, "success");
+        }
+    }
+
+    // 测试：默认模式下任一用户失败则整体回滚，尽力而为模式返回逐个结果
+    #[test]
+    fn batch_remove_roles_is_atomic_unless_best_effort() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let multi_role = register_batch_user("batch_roles", &["tenant", "operator"]);
+        let single_role = register_batch_user("batch_roles", &["tenant"]);
+        let payload = |best_effort| AdminBatchUserRolesPayload {
+            operator_username: "admin".to_string(),
+            user_ids: vec![multi_role, single_role],
+            roles: vec!["tenant".to_string()],
+            best_effort,
+        };
+        let roles_of = |user_id: i64| {
+            auth_admin_list_users(
+                AdminListUsersPayload {
+                    operator_username: "admin".to_string(),
+                    include_deleted: false,
+                    organization_id: None,
+                },
+                None,
+            )
+            .expect("list users")
+            .data
+            .into_iter()
+            .find(|user| user.user_id == user_id)
+            .expect("batch user")
+            .roles
+        };
+
+        // 默认模式：single_role 会失去全部角色，整批回滚
+        let err = auth_admin_batch_remove_user_roles(payload(false), None)
+            .expect_err("expect atomic failure");
         assert_eq!(
             err,
-            AppError::Validation("RESERVED_API_NOT_IMPLEMENTED".to_string())
+            AppError::Validation(format!(
+                "userId {single_role}: user must keep at least one role"
+            ))
+        );
+        assert_eq!(roles_of(multi_role), vec!["operator", "tenant"]);
+
+        // 尽力而为模式：仅 single_role 失败
+        let data = auth_admin_batch_remove_user_roles(payload(true), None)
+            .expect("best effort remove")
+            .data;
+        assert_eq!(data.succeeded_count, 1);
+        assert_eq!(data.failed_count, 1);
+        assert_eq!(data.results[1].status, "failed");
+        assert_eq!(roles_of(multi_role), vec!["operator"]);
+        assert_eq!(roles_of(single_role), vec!["tenant"]);
+    }
+
+    // 测试：批量停用要求管理员权限且校验用户 ID 列表
+    #[test]
+    fn batch_set_active_validates_operator_and_user_ids() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let forbidden = auth_admin_batch_set_users_active(
+            AdminBatchSetUsersActivePayload {
+                operator_username: "common".to_string(),
+                user_ids: vec![2],
+                is_active: false,
+                best_effort: false,
+            },
+            None,
+        )
+        .expect_err("expect forbidden");
+        assert_eq!(
+            forbidden,
+            AppError::Validation("forbidden: admin only".to_string())
+        );
+
+        let empty = auth_admin_batch_set_users_active(
+            AdminBatchSetUsersActivePayload {
+                operator_username: "admin".to_string(),
+                user_ids: vec![],
+                is_active: false,
+                best_effort: false,
+            },
+            None,
+        )
+        .expect_err("expect empty ids rejected");
+        assert_eq!(
+            empty,
+            AppError::Validation("userIds is required".to_string())
+        );
+    }
+
+    // 辅助函数：由 admin 注册一名可立即使用的用户（清除强制改密标记），返回用户名与 ID
+    fn register_active_user(
+        prefix: &str,
+        roles: &[&str],
+        organization_id: Option<i64>,
+    ) -> (String, i64) {
+        let username = unique_username(prefix);
+        let user_id = auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: username.clone(),
+                password: "admin123".to_string(),
+                nickname: "委派测试".to_string(),
+                roles: roles.iter().map(ToString::to_string).collect(),
+                account_term_type: "permanent".to_string(),
+                organization_id,
+                ..AdminRegisterUserPayload::default()
+            },
+            None,
+        )
+        .expect("register active user")
+        .data
+        .user_id;
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query("UPDATE users SET must_change_password = 0 WHERE id = $1")
+                .bind(user_id)
+                .execute(&mut connection),
+        )
+        .expect("clear must change password");
+        (username, user_id)
+    }
+
+    // 辅助函数：由 admin 设置委派配置
+    fn delegate(
+        user_id: i64,
+        organization_id: Option<i64>,
+        manage_created_users: bool,
+        assignable_roles: &[&str],
+    ) -> AppResult<AdminUserDelegationData> {
+        auth_admin_set_user_delegation(
+            AdminSetUserDelegationPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                organization_id,
+                manage_created_users,
+                assignable_roles: assignable_roles.iter().map(ToString::to_string).collect(),
+            },
+            None,
+        )
+    }
+
+    // 辅助函数：以指定操作员注册用户
+    fn register_as(
+        operator_username: &str,
+        roles: &[&str],
+        organization_id: Option<i64>,
+    ) -> AppResult<AdminRegisteredUserData> {
+        auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: operator_username.to_string(),
+                username: unique_username("delegated_tenant"),
+                password: "admin123".to_string(),
+                nickname: "租户".to_string(),
+                roles: roles.iter().map(ToString::to_string).collect(),
+                account_term_type: "days".to_string(),
+                account_valid_days: Some(30),
+                organization_id,
+                ..AdminRegisterUserPayload::default()
+            },
+            None,
+        )
+    }
+
+    // 辅助函数：以指定操作员查询用户 ID 列表
+    fn list_user_ids_as(operator_username: &str) -> Result<Vec<i64>, AppError> {
+        auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: operator_username.to_string(),
+                include_deleted: false,
+                organization_id: None,
+            },
+            None,
+        )
+        .map(|response| response.data.into_iter().map(|user| user.user_id).collect())
+    }
+
+    // 辅助函数：以 admin 创建组织
+    fn create_organization(parent_id: Option<i64>, prefix: &str) -> i64 {
+        crate::organization::commands::organization_create(
+            crate::organization::models::OrganizationCreatePayload {
+                operator_username: "admin".to_string(),
+                parent_id,
+                name: unique_username(prefix),
+                org_type: "site".to_string(),
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("create organization")
+        .data
+        .id
+    }
+
+    // 辅助函数：构造校验错误
+    fn forbidden(message: &str) -> AppError {
+        AppError::Validation(message.to_string())
+    }
+
+    // 辅助函数：创建按创建人授权、只能分配 tenant 角色的委派管理员
+    fn creator_delegate(prefix: &str) -> (String, i64) {
+        let (manager, manager_id) = register_active_user(prefix, &["operator"], None);
+        let delegation = delegate(manager_id, None, true, &["tenant"])
+            .expect("set delegation")
+            .data;
+        assert_eq!(delegation.assignable_roles, vec!["tenant"]);
+        (manager, manager_id)
+    }
+
+    // 测试：按创建人授权的委派管理员只能管理本人创建的用户并分配授权角色
+    #[test]
+    fn delegated_admin_manages_only_created_users_with_assignable_roles() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (manager, manager_id) = creator_delegate("facility_manager");
+        let outsider = register_batch_user("delegation_outsider", &["tenant"]);
+
+        // 范围内：创建并续期租户
+        let tenant = register_as(&manager, &["tenant"], None)
+            .expect("delegate registers tenant")
+            .data
+            .user_id;
+        auth_admin_renew_user_account(
+            AdminRenewUserAccountPayload {
+                operator_username: manager.clone(),
+                user_id: tenant,
+                renew_mode: "days".to_string(),
+                renew_days: Some(30),
+            },
+            None,
+        )
+        .expect("delegate renews own tenant");
+        let visible = list_user_ids_as(&manager).expect("delegate lists users");
+        assert!(visible.contains(&tenant));
+        assert!(!visible.contains(&outsider));
+        assert!(!visible.contains(&manager_id));
+
+        // 提权尝试：分配授权外角色或 admin 角色
+        assert_eq!(
+            register_as(&manager, &["operator"], None).expect_err("operator not assignable"),
+            forbidden("forbidden: role not assignable: operator")
+        );
+        assert_eq!(
+            register_as(&manager, &["admin"], None).expect_err("admin never assignable"),
+            forbidden("invalid role: admin")
+        );
+        let promote = auth_admin_update_user(
+            AdminUpdateUserPayload {
+                operator_username: manager.clone(),
+                user_id: tenant,
+                username: unique_username("promoted"),
+                nickname: "租户".to_string(),
+                roles: vec!["tenant".to_string(), "operator".to_string()],
+                is_active: true,
+                account_term_type: "permanent".to_string(),
+                ..AdminUpdateUserPayload::default()
+            },
+            None,
+        )
+        .expect_err("promotion rejected");
+        assert_eq!(
+            promote,
+            forbidden("forbidden: role not assignable: operator")
+        );
+    }
+
+    // 测试：委派管理员不能管理范围外用户、本人与 admin 账号或执行仅限 admin 的操作，撤销后失去管理权限
+    #[test]
+    fn delegated_admin_is_refused_outside_scope_and_after_revocation() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (manager, manager_id) = creator_delegate("facility_manager_scope");
+        let outsider = register_batch_user("delegation_outsider", &["tenant"]);
+
+        // 越权尝试：范围外用户、本人账号、admin 账号、仅限 admin 的操作
+        let change_password = |user_id: i64| {
+            auth_admin_change_user_password(
+                AdminChangeUserPasswordPayload {
+                    operator_username: manager.clone(),
+                    user_id,
+                    password: "takeover123".to_string(),
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            change_password(outsider).expect_err("outsider"),
+            forbidden("forbidden: user outside delegated scope")
+        );
+        assert_eq!(
+            change_password(manager_id).expect_err("own account"),
+            forbidden("forbidden: delegated admin cannot manage own account")
+        );
+        assert_eq!(
+            change_password(1).expect_err("admin account"),
+            forbidden("forbidden: user outside delegated scope")
+        );
+        let purge = auth_admin_purge_deleted_users(
+            AdminPurgeDeletedUsersPayload {
+                operator_username: manager.clone(),
+                retention_days: Some(0),
+            },
+            None,
+        )
+        .expect_err("purge is admin only");
+        assert_eq!(purge, forbidden("forbidden: admin only"));
+        let self_grant = auth_admin_set_user_delegation(
+            AdminSetUserDelegationPayload {
+                operator_username: manager.clone(),
+                user_id: manager_id,
+                manage_created_users: true,
+                assignable_roles: vec!["operator".to_string()],
+                ..AdminSetUserDelegationPayload::default()
+            },
+            None,
+        )
+        .expect_err("delegates cannot grant delegations");
+        assert_eq!(self_grant, forbidden("forbidden: admin only"));
+
+        // 撤销委派后恢复为普通用户
+        assert!(
+            auth_admin_remove_user_delegation(
+                AdminRemoveUserDelegationPayload {
+                    operator_username: "admin".to_string(),
+                    user_id: manager_id,
+                },
+                None,
+            )
+            .expect("remove delegation")
+            .data
+        );
+        assert_eq!(
+            list_user_ids_as(&manager).expect_err("delegation revoked"),
+            forbidden("forbidden: admin only")
+        );
+    }
+
+    // 测试：按组织授权的委派管理员限定在组织子树内，且不能管理其他委派人
+    #[test]
+    fn organization_delegate_is_limited_to_subtree_and_non_delegates() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let building = create_organization(None, "delegation_building");
+        let floor = create_organization(Some(building), "delegation_floor");
+        let other_building = create_organization(None, "delegation_other_building");
+        let (manager, manager_id) = register_active_user("building_manager", &["operator"], None);
+        delegate(manager_id, Some(building), false, &["tenant"]).expect("set delegation");
+
+        // 未指定组织时放入授权组织；可指定子树内组织，不能指定子树外组织
+        let tenant = register_as(&manager, &["tenant"], None)
+            .expect("register into building")
+            .data
+            .user_id;
+        let floor_tenant = register_as(&manager, &["tenant"], Some(floor))
+            .expect("register into floor")
+            .data
+            .user_id;
+        assert_eq!(
+            register_as(&manager, &["tenant"], Some(other_building)).expect_err("outside subtree"),
+            forbidden("forbidden: organization outside delegated scope")
+        );
+        let visible = list_user_ids_as(&manager).expect("delegate lists users");
+        assert!(visible.contains(&tenant));
+        assert!(visible.contains(&floor_tenant));
+
+        // 其他委派人即使在子树内且角色可分配，也不能被管理
+        let (_, peer_id) = register_active_user("peer_manager", &["tenant"], Some(building));
+        delegate(peer_id, Some(building), false, &["tenant"]).expect("set peer delegation");
+        let (_, other_tenant) =
+            register_active_user("other_tenant", &["tenant"], Some(other_building));
+        for target in [peer_id, other_tenant] {
+            let err = auth_admin_delete_user(
+                AdminDeleteUserPayload {
+                    operator_username: manager.clone(),
+                    user_id: target,
+                },
+                None,
+            )
+            .expect_err("outside scope");
+            assert_eq!(err, forbidden("forbidden: user outside delegated scope"));
+        }
+        assert!(!list_user_ids_as(&manager).expect("list").contains(&peer_id));
+
+        // 批量操作逐个校验范围，并校验角色是否可分配
+        let batch = auth_admin_batch_set_users_active(
+            AdminBatchSetUsersActivePayload {
+                operator_username: manager.clone(),
+                user_ids: vec![tenant, other_tenant],
+                is_active: false,
+                best_effort: true,
+            },
+            None,
+        )
+        .expect("best effort batch")
+        .data;
+        assert_eq!(batch.succeeded_count, 1);
+        assert_eq!(batch.failed_count, 1);
+        let add_roles = auth_admin_batch_add_user_roles(
+            AdminBatchUserRolesPayload {
+                operator_username: manager.clone(),
+                user_ids: vec![floor_tenant],
+                roles: vec!["operator".to_string()],
+                best_effort: false,
+            },
+            None,
+        )
+        .expect_err("operator not assignable");
+        assert_eq!(
+            add_roles,
+            forbidden("forbidden: role not assignable: operator")
+        );
+    }
+
+    // 测试：授权时可分配角色不能超过委派人自身角色的权限
+    #[test]
+    fn delegation_grant_rejects_roles_above_delegate_permissions() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (_, manager_id) = register_active_user("grant_manager", &["operator"], None);
+        assert_eq!(
+            delegate(manager_id, None, true, &["guest"]).expect_err("guest exceeds operator"),
+            forbidden("forbidden: role exceeds delegate permissions: guest")
+        );
+        assert_eq!(
+            delegate(manager_id, None, true, &["admin"]).expect_err("admin not assignable"),
+            forbidden("invalid role: admin")
+        );
+        assert_eq!(
+            delegate(manager_id, None, false, &["tenant"]).expect_err("scope required"),
+            forbidden("delegation scope is required")
+        );
+        assert_eq!(
+            delegate(1, None, true, &["tenant"]).expect_err("protected admin"),
+            forbidden(crate::auth::admin_services::PROTECTED_ADMIN_MESSAGE)
         );
+
+        let delegations = auth_admin_list_user_delegations(
+            AdminListUserDelegationsPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list delegations")
+        .data;
+        assert!(delegations.iter().all(|item| item.user_id != manager_id));
+    }
+
+    // 辅助函数：写入区域 → 楼层位置节点与一台位于该楼层的设备，返回 (区域编码, 楼层编码, 设备标识)
+    fn insert_located_device(prefix: &str) -> (String, String, String) {
+        let area_code = unique_username(&format!("{prefix}_area"));
+        let floor_code = unique_username(&format!("{prefix}_floor"));
+        let device_id = unique_username(&format!("{prefix}_device"));
+        let mut connection = db::connect().expect("open db");
+        db::block_on(async {
+            let area_id: i64 = sqlx::query_scalar(
+                r"
+                INSERT INTO location_nodes (level, code, name, created_at, updated_at, created_by)
+                VALUES ('area', $1, $1, 1, 1, 'admin')
+                RETURNING id
+                ",
+            )
+            .bind(&area_code)
+            .fetch_one(&mut connection)
+            .await?;
+            let floor_id: i64 = sqlx::query_scalar(
+                r"
+                INSERT INTO location_nodes (parent_id, level, code, name, created_at, updated_at, created_by)
+                VALUES ($1, 'floor', $2, $2, 1, 1, 'admin')
+                RETURNING id
+                ",
+            )
+            .bind(area_id)
+            .bind(&floor_code)
+            .fetch_one(&mut connection)
+            .await?;
+            sqlx::query(
+                r"
+                INSERT INTO device_registry (device_id, device_name, owner_username, registered_at, location_id)
+                VALUES ($1, $1, 'admin', 1, $2)
+                ",
+            )
+            .bind(&device_id)
+            .bind(floor_id)
+            .execute(&mut connection)
+            .await
+        })
+        .expect("insert located device");
+        (area_code, floor_code, device_id)
+    }
+
+    // 辅助函数：以 admin 整体替换用户设备范围
+    fn upsert_scope(
+        user_id: i64,
+        (all_areas, all_floors, all_devices): (bool, bool, bool),
+        areas: &[&str],
+        floors: &[&str],
+        devices: &[&str],
+    ) -> AppResult<UserDeviceScopeData> {
+        user_device_scope_upsert(
+            UserDeviceScopeUpsertPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                all_areas,
+                all_floors,
+                all_devices,
+                areas: areas.iter().map(ToString::to_string).collect(),
+                floors: floors.iter().map(ToString::to_string).collect(),
+                devices: devices.iter().map(ToString::to_string).collect(),
+            },
+            None,
+        )
+    }
+
+    // 辅助函数：以 admin 判定用户能否访问设备，返回命中的授权来源
+    fn check_scope(user_id: i64, device_id: &str) -> Option<String> {
+        user_device_scope_check(
+            UserDeviceScopeCheckPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                device_id: device_id.to_string(),
+            },
+            None,
+        )
+        .expect("check device scope")
+        .data
+        .matched_by
+    }
+
+    // 测试：设备范围整体替换，访问判定合并通配标记与显式明细
+    #[test]
+    fn device_scope_upsert_replaces_entries_and_resolves_access() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (_, tenant_id) = register_active_user("scope_tenant", &["tenant"], None);
+        let (area_a, floor_a, device_a) = insert_located_device("scope_a");
+        let (_, floor_b, device_b) = insert_located_device("scope_b");
+        let (_, _, device_c) = insert_located_device("scope_c");
+
+        // 未配置时返回空范围，仅 admin 可访问
+        let empty = user_device_scope_get(
+            UserDeviceScopeGetPayload {
+                operator_username: "admin".to_string(),
+                user_id: tenant_id,
+            },
+            None,
+        )
+        .expect("get empty scope")
+        .data;
+        assert!(empty.scope.areas.is_empty() && empty.updated_by.is_none());
+        assert_eq!(check_scope(tenant_id, &device_a), None);
+        assert_eq!(check_scope(1, &device_a).as_deref(), Some("role"));
+
+        // 区域与显式设备授权（编码去空格、去重）
+        let saved = upsert_scope(
+            tenant_id,
+            (false, false, false),
+            &[&area_a, &format!(" {area_a} ")],
+            &[],
+            &[&device_b],
+        )
+        .expect("save scope")
+        .data;
+        assert_eq!(saved.scope.areas, vec![area_a.clone()]);
+        assert_eq!(saved.scope.devices, vec![device_b.clone()]);
+        assert_eq!(saved.updated_by.as_deref(), Some("admin"));
+        assert_eq!(check_scope(tenant_id, &device_a).as_deref(), Some("area"));
+        assert_eq!(check_scope(tenant_id, &device_b).as_deref(), Some("device"));
+        assert_eq!(check_scope(tenant_id, &device_c), None);
+        assert_eq!(check_scope(tenant_id, "missing-device"), None);
+
+        // 整体替换：旧明细被清除，楼层授权生效
+        let replaced = upsert_scope(tenant_id, (false, false, false), &[], &[&floor_b], &[])
+            .expect("replace scope")
+            .data;
+        assert!(replaced.scope.areas.is_empty() && replaced.scope.devices.is_empty());
+        assert_eq!(check_scope(tenant_id, &device_a), None);
+        assert_eq!(check_scope(tenant_id, &device_b).as_deref(), Some("floor"));
+
+        // 通配标记
+        upsert_scope(tenant_id, (false, true, false), &[], &[], &[]).expect("all floors");
+        assert_eq!(
+            check_scope(tenant_id, &device_c).as_deref(),
+            Some("allFloors")
+        );
+        upsert_scope(tenant_id, (false, false, true), &[], &[&floor_a], &[]).expect("all devices");
+        assert_eq!(
+            check_scope(tenant_id, &device_a).as_deref(),
+            Some("allDevices")
+        );
+
+        // 停用账号后不可访问任何设备
+        auth_admin_batch_set_users_active(
+            AdminBatchSetUsersActivePayload {
+                operator_username: "admin".to_string(),
+                user_ids: vec![tenant_id],
+                is_active: false,
+                best_effort: false,
+            },
+            None,
+        )
+        .expect("deactivate tenant");
+        assert!(
+            !device_scope_services::can_user_access_device(
+                tenant_id,
+                &device_a,
+                i64::try_from(now_millis()).expect("now millis"),
+            )
+            .expect("resolve access")
+        );
+    }
+
+    // 测试：设备范围保存校验引用的编码与操作员权限，失败时不改变已有范围
+    #[test]
+    fn device_scope_upsert_rejects_unknown_codes_and_non_admin() {
+        // 准备测试数据库
+        ensure_test_db_ready();
+        let (tenant, tenant_id) = register_active_user("scope_guard", &["tenant"], None);
+        let (area_code, _, device_id) = insert_located_device("scope_guard");
+        upsert_scope(tenant_id, (false, false, false), &[&area_code], &[], &[])
+            .expect("save scope");
+
+        // 引用不存在的编码时整体回滚
+        let err = upsert_scope(
+            tenant_id,
+            (false, false, false),
+            &[],
+            &[],
+            &[&device_id, "missing-device"],
+        )
+        .expect_err("expect unknown device rejected");
+        assert_eq!(err, forbidden("device not found: missing-device"));
+        let err = upsert_scope(
+            tenant_id,
+            (false, false, false),
+            &["missing-area"],
+            &[],
+            &[],
+        )
+        .expect_err("expect unknown area rejected");
+        assert_eq!(err, forbidden("area not found: missing-area"));
+        let err = upsert_scope(tenant_id, (false, false, false), &[], &[" "], &[])
+            .expect_err("expect empty floor rejected");
+        assert_eq!(err, forbidden("floors contains empty code"));
+        assert_eq!(check_scope(tenant_id, &device_id).as_deref(), Some("area"));
+
+        // 非 admin 操作员（含委派管理员）不能查询或修改设备范围
+        let (manager, manager_id) = register_active_user("scope_manager", &["operator"], None);
+        delegate(manager_id, None, true, &["tenant"]).expect("delegate manager");
+        for operator in [tenant.as_str(), manager.as_str()] {
+            let err = user_device_scope_upsert(
+                UserDeviceScopeUpsertPayload {
+                    operator_username: operator.to_string(),
+                    user_id: tenant_id,
+                    all_devices: true,
+                    ..UserDeviceScopeUpsertPayload::default()
+                },
+                None,
+            )
+            .expect_err("expect non admin rejected");
+            assert_eq!(err, forbidden("forbidden: admin only"));
+            let err = user_device_scope_get(
+                UserDeviceScopeGetPayload {
+                    operator_username: operator.to_string(),
+                    user_id: tenant_id,
+                },
+                None,
+            )
+            .expect_err("expect non admin rejected");
+            assert_eq!(err, forbidden("forbidden: admin only"));
+        }
+
+        // 目标用户不存在
+        let err = upsert_scope(i64::MAX, (true, false, false), &[], &[], &[])
+            .expect_err("expect missing user rejected");
+        assert_eq!(err, forbidden("user not found"));
     }
 }
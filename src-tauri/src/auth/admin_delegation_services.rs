@@ -0,0 +1,463 @@
+//! ==========================================================================================
+//! 委派管理员业务逻辑层
+//!
+//! 模块职责：
+//! admin 可将有限的账号管理能力委派给非 admin 用户（例如物业经理管理本楼宇的租户账号）。
+//! 本模块负责委派配置的维护，并为 `admin_services` / `admin_batch_services` 的每个管理操作
+//! 解析操作员的管理范围（`OperatorScope`）。
+//!
+//! 委派规则：
+//!
+//! | 规则 | 说明 |
+//! |------|------|
+//! | 管理范围 | 授权组织子树内的用户与委派人本人创建（`created_by`）的用户两者之并 |
+//! | 可分配角色 | 仅限委派配置中的角色；授权时校验每个角色的权限不超过委派人自身角色 |
+//! | 目标限制 | 不能管理本人账号、其他委派人以及持有不可分配角色的用户 |
+//! | 仅限 admin | 配置委派、清理已删除用户 |
+//!
+//! 委派人自身角色被收窄后，超出其权限的可分配角色在解析范围时自动失效。
+//! 委派配置的设置与撤销写入审计事件（目标类型 `user_delegation`）。
+//!
+//! ==========================================================================================
+
+// 引入标准库的 HashSet，用于范围判定
+use std::collections::HashSet;
+
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入鉴权模块的模型定义
+use crate::auth::models::{
+    AdminListUserDelegationsPayload, AdminRemoveUserDelegationPayload,
+    AdminSetUserDelegationPayload, AdminUserDelegationData,
+};
+// 引入管理员业务规则（复用校验逻辑）
+use crate::auth::admin_services::{
+    assert_operator_can_manage_users, assert_target_user_editable, normalize_roles,
+};
+use crate::auth::rbac;
+// 引入核心错误处理模块
+use crate::core::error::AppError;
+// 引入管理员数据访问层
+use crate::db::admin_repository::{self, ManagedUserRecord, UserDelegationRecord};
+// 引入组织仓储模块（解析组织子树）
+use crate::organization::repository as organization_repository;
+
+// ==========================================================================================
+// 常量定义
+// ==========================================================================================
+
+// 审计目标类型：委派管理员
+const TARGET_TYPE_USER_DELEGATION: &str = "user_delegation";
+
+// 委派管理员仅限 admin 执行的操作
+const ADMIN_ONLY_MESSAGE: &str = "forbidden: admin only";
+
+// 目标用户不在委派范围内
+const OUTSIDE_SCOPE_MESSAGE: &str = "forbidden: user outside delegated scope";
+
+// 委派管理员尝试管理本人账号
+const OWN_ACCOUNT_MESSAGE: &str = "forbidden: delegated admin cannot manage own account";
+
+// ==========================================================================================
+// 操作员管理范围
+// ==========================================================================================
+
+// 操作员的管理范围
+pub(crate) enum OperatorScope {
+    // 拥有 user:manage 权限（admin），可管理全部用户
+    Full,
+    // 委派管理员，仅可管理范围内的用户
+    Delegated(DelegatedScope),
+}
+
+// 委派管理员的有效范围（组织子树已展开，可分配角色已按自身权限过滤）
+pub(crate) struct DelegatedScope {
+    username: String,                  // 委派人用户名
+    organization_id: Option<i64>,      // 授权组织（新建用户的缺省所属组织）
+    organization_ids: HashSet<i64>,    // 授权组织子树内的全部组织 ID
+    manage_created_users: bool,        // 是否可管理本人创建的用户
+    assignable_roles: HashSet<String>, // 可分配的角色
+}
+
+// 解析操作员的管理范围
+//
+// 功能说明：
+// 拥有 user:manage 权限的操作员返回 Full；否则查找其委派配置，
+// 委派人账号须当前可用（存在有效角色），否则按原有规则拒绝。
+//
+// 参数说明：
+// - operator_username: 操作员用户名（已去除首尾空格）
+// - now_millis: 当前时间戳（毫秒）
+//
+// 返回值：
+// - 成功：返回操作员的管理范围
+// - 失败：返回 "forbidden: admin only" 或数据库错误
+pub(crate) fn resolve_operator_scope(
+    operator_username: &str,
+    now_millis: i64,
+) -> Result<OperatorScope, AppError> {
+    let denied = match assert_operator_can_manage_users(operator_username, now_millis) {
+        Ok(()) => return Ok(OperatorScope::Full),
+        Err(AppError::Validation(message)) => message,
+        Err(err) => return Err(err),
+    };
+    let Some(delegation) = admin_repository::find_user_delegation_by_username(operator_username)?
+    else {
+        return Err(AppError::Validation(denied));
+    };
+    let own_roles = admin_repository::find_effective_roles(operator_username, now_millis)?;
+    if own_roles.is_empty() {
+        return Err(AppError::Validation(denied));
+    }
+
+    // 委派人自身角色收窄后，超出其权限的可分配角色失效
+    let exceeding = rbac::find_roles_exceeding(&delegation.assignable_roles, &own_roles)?;
+    let assignable_roles = delegation
+        .assignable_roles
+        .into_iter()
+        .filter(|role| !exceeding.contains(role))
+        .collect();
+    let organization_ids = match delegation.organization_id {
+        Some(organization_id) => organization_repository::find_subtree_ids(organization_id)?
+            .into_iter()
+            .collect(),
+        None => HashSet::new(),
+    };
+    Ok(OperatorScope::Delegated(DelegatedScope {
+        username: delegation.username,
+        organization_id: delegation.organization_id,
+        organization_ids,
+        manage_created_users: delegation.manage_created_users,
+        assignable_roles,
+    }))
+}
+
+impl OperatorScope {
+    // 要求操作员为 admin（委派管理员无权执行）
+    pub(crate) fn require_full(&self) -> Result<(), AppError> {
+        match self {
+            Self::Full => Ok(()),
+            Self::Delegated(_) => Err(AppError::Validation(ADMIN_ONLY_MESSAGE.to_string())),
+        }
+    }
+
+    // 校验角色均可由操作员分配
+    pub(crate) fn ensure_roles_assignable(&self, roles: &[String]) -> Result<(), AppError> {
+        let Self::Delegated(scope) = self else {
+            return Ok(());
+        };
+        match roles
+            .iter()
+            .find(|role| !scope.assignable_roles.contains(*role))
+        {
+            Some(role) => Err(AppError::Validation(format!(
+                "forbidden: role not assignable: {role}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    // 校验目标用户可由操作员管理（包含已软删除的用户）
+    pub(crate) fn ensure_user_manageable(&self, user_id: i64) -> Result<(), AppError> {
+        let Self::Delegated(scope) = self else {
+            return Ok(());
+        };
+        let record = admin_repository::find_managed_user(user_id)?
+            .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
+        if record.username.eq_ignore_ascii_case(&scope.username) {
+            return Err(AppError::Validation(OWN_ACCOUNT_MESSAGE.to_string()));
+        }
+        // 其他委派人不可被管理，防止通过重置密码接管更大的委派范围
+        let is_delegate = admin_repository::find_user_delegation(user_id)?.is_some();
+        if is_delegate || !scope.covers(&record) {
+            return Err(AppError::Validation(OUTSIDE_SCOPE_MESSAGE.to_string()));
+        }
+        Ok(())
+    }
+
+    // 过滤出操作员可管理的用户（用于用户列表）
+    pub(crate) fn filter_manageable(
+        &self,
+        records: Vec<ManagedUserRecord>,
+    ) -> Result<Vec<ManagedUserRecord>, AppError> {
+        let Self::Delegated(scope) = self else {
+            return Ok(records);
+        };
+        let delegate_ids: HashSet<i64> = admin_repository::list_user_delegations()?
+            .into_iter()
+            .map(|delegation| delegation.user_id)
+            .collect();
+        Ok(records
+            .into_iter()
+            .filter(|record| !delegate_ids.contains(&record.user_id) && scope.covers(record))
+            .collect())
+    }
+
+    // 确定新建用户的所属组织
+    //
+    // admin 原样使用请求值；委派管理员指定的组织须在授权子树内，未指定时缺省为授权组织
+    pub(crate) fn resolve_new_user_organization(
+        &self,
+        requested: Option<i64>,
+    ) -> Result<Option<i64>, AppError> {
+        let Self::Delegated(scope) = self else {
+            return Ok(requested);
+        };
+        match requested {
+            Some(organization_id) if !scope.organization_ids.contains(&organization_id) => Err(
+                AppError::Validation("forbidden: organization outside delegated scope".to_string()),
+            ),
+            Some(organization_id) => Ok(Some(organization_id)),
+            None => Ok(scope.organization_id),
+        }
+    }
+}
+
+impl DelegatedScope {
+    // 判断用户是否在委派范围内且仅持有可分配角色
+    fn covers(&self, record: &ManagedUserRecord) -> bool {
+        if record.username.eq_ignore_ascii_case(&self.username) {
+            return false;
+        }
+        let in_organization = record
+            .organization_id
+            .is_some_and(|organization_id| self.organization_ids.contains(&organization_id));
+        let created_by_operator = self.manage_created_users
+            && record
+                .created_by
+                .as_deref()
+                .is_some_and(|created_by| created_by.eq_ignore_ascii_case(&self.username));
+        (in_organization || created_by_operator)
+            && record
+                .roles
+                .iter()
+                .all(|role| self.assignable_roles.contains(role))
+    }
+}
+
+// ==========================================================================================
+// 委派配置维护（仅限 admin）
+// ==========================================================================================
+
+// 管理员设置委派管理员
+
+// 功能说明：
+// 为指定用户创建或整体替换委派配置。管理范围至少包含授权组织或本人创建的用户之一；
+// 可分配角色须为合法的业务角色，且每个角色的权限不超过委派人当前角色的权限。
+
+// 参数说明：
+// - payload: 包含委派人、授权组织、可分配角色的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回保存后的委派配置
+// - 失败：返回 AppError 错误
+pub fn set_user_delegation_by_admin(
+    payload: AdminSetUserDelegationPayload,
+    now_millis: u64,
+) -> Result<AdminUserDelegationData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let user_id = payload.user_id;
+    let before = find_snapshot(user_id);
+    let result = set_user_delegation(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|data| serde_json::to_value(data).ok());
+    audit_services::record_command(
+        CommandAudit {
+            command: "auth_admin_set_user_delegation",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_USER_DELEGATION,
+            target_id: Some(user_id)
+                .filter(|user_id| *user_id > 0)
+                .map(|user_id| user_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 设置委派管理员（不含审计记录）
+fn set_user_delegation(
+    payload: AdminSetUserDelegationPayload,
+    now_millis: u64,
+) -> Result<AdminUserDelegationData, AppError> {
+    let (operator_username, now_millis) =
+        prepare_admin_operation(&payload.operator_username, now_millis)?;
+    // 校验委派人
+    if payload.user_id <= 0 {
+        return Err(AppError::Validation("userId is required".to_string()));
+    }
+    assert_target_user_editable(payload.user_id)?;
+    let delegate = admin_repository::find_managed_user(payload.user_id)?
+        .filter(|record| record.deleted_at.is_none())
+        .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
+
+    // 校验管理范围
+    if payload.organization_id.is_none() && !payload.manage_created_users {
+        return Err(AppError::Validation(
+            "delegation scope is required".to_string(),
+        ));
+    }
+    if payload
+        .organization_id
+        .is_some_and(|organization_id| organization_id <= 0)
+    {
+        return Err(AppError::Validation("invalid organizationId".to_string()));
+    }
+
+    // 校验可分配角色：不能超过委派人自身角色的权限
+    if payload
+        .assignable_roles
+        .iter()
+        .all(|role| role.trim().is_empty())
+    {
+        return Err(AppError::Validation(
+            "assignableRoles is required".to_string(),
+        ));
+    }
+    let assignable_roles = normalize_roles(payload.assignable_roles)?;
+    let exceeding = rbac::find_roles_exceeding(&assignable_roles, &delegate.roles)?;
+    if !exceeding.is_empty() {
+        return Err(AppError::Validation(format!(
+            "forbidden: role exceeds delegate permissions: {}",
+            exceeding.join(",")
+        )));
+    }
+
+    let record =
+        admin_repository::upsert_user_delegation(&admin_repository::UserDelegationInput {
+            user_id: payload.user_id,
+            organization_id: payload.organization_id,
+            manage_created_users: payload.manage_created_users,
+            assignable_roles,
+            created_by: operator_username,
+            now_millis,
+        })?;
+    Ok(map_delegation_record(record))
+}
+
+// 管理员撤销委派管理员
+
+// 参数说明：
+// - payload: 包含委派人用户 ID 的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回 true
+// - 失败：返回 AppError 错误（未委派时返回 "delegation not found"）
+pub fn remove_user_delegation_by_admin(
+    payload: &AdminRemoveUserDelegationPayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let before = find_snapshot(payload.user_id);
+    let result = remove_user_delegation(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "auth_admin_remove_user_delegation",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_USER_DELEGATION,
+            target_id: Some(payload.user_id)
+                .filter(|user_id| *user_id > 0)
+                .map(|user_id| user_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 撤销委派管理员（不含审计记录）
+fn remove_user_delegation(
+    payload: &AdminRemoveUserDelegationPayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    prepare_admin_operation(&payload.operator_username, now_millis)?;
+    if payload.user_id <= 0 {
+        return Err(AppError::Validation("userId is required".to_string()));
+    }
+    if !admin_repository::delete_user_delegation(payload.user_id)? {
+        return Err(AppError::Validation("delegation not found".to_string()));
+    }
+    Ok(true)
+}
+
+// 管理员查询全部委派管理员
+
+// 参数说明：
+// - payload: 包含操作员用户名的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回按委派人用户名排序的委派配置
+// - 失败：返回 AppError 错误
+pub fn list_user_delegations_by_admin(
+    payload: &AdminListUserDelegationsPayload,
+    now_millis: u64,
+) -> Result<Vec<AdminUserDelegationData>, AppError> {
+    prepare_admin_operation(&payload.operator_username, now_millis)?;
+    Ok(admin_repository::list_user_delegations()?
+        .into_iter()
+        .map(map_delegation_record)
+        .collect())
+}
+
+// ==========================================================================================
+// 内部辅助函数
+// ==========================================================================================
+
+// 校验时间戳与操作员的 admin 权限
+
+// 返回值：
+// - 成功：返回 (操作员用户名, i64 类型的当前时间戳)
+// - 失败：返回 AppError 错误
+fn prepare_admin_operation(
+    operator_username: &str,
+    now_millis: u64,
+) -> Result<(String, i64), AppError> {
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    let operator_username = operator_username.trim().to_string();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    Ok((operator_username, now_millis))
+}
+
+// 将委派记录转换为 API 响应格式
+fn map_delegation_record(record: UserDelegationRecord) -> AdminUserDelegationData {
+    AdminUserDelegationData {
+        user_id: record.user_id,
+        username: record.username,
+        organization_id: record.organization_id,
+        manage_created_users: record.manage_created_users,
+        assignable_roles: record.assignable_roles,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        created_by: record.created_by,
+    }
+}
+
+// 采集委派配置快照（未委派或查询失败时为 None）
+fn find_snapshot(user_id: i64) -> Option<Value> {
+    if user_id <= 0 {
+        return None;
+    }
+    match admin_repository::find_user_delegation(user_id) {
+        Ok(record) => serde_json::to_value(map_delegation_record(record?)).ok(),
+        Err(err) => {
+            tracing::warn!(user_id, error = %err, "audit snapshot load failed");
+            None
+        }
+    }
+}
@@ -10,9 +10,12 @@
 //! - 用户注册与管理
 //! - 用户账号续期
 //! - 用户信息更新
-//! - 用户删除
+//! - 用户软删除、恢复与过期清理
 //! - 密码重置
 //! - 用户状态检查
+//! - 操作审计：每个管理操作写入一条带前后快照的审计事件（见 `admin_audit`）
+//! - 委派管理：非 admin 的委派管理员仅能管理授权范围内的用户、分配授权角色（见 `admin_delegation_services`）
+//! - 设备范围：用户可访问的区域、楼层与设备配置及访问判定（见 `device_scope_services`）
 //!
 //! 设计原则：
 //! - 纯函数：所有业务函数不包含副作用，结果只依赖于输入参数
@@ -34,7 +37,11 @@
 //! | 类型 | 说明 |
 //! |------|------|
 //! | permanent | 永久有效，无过期时间 |
-//! | days | 指定天数后过期 |
+//! | days | 自生效时间（缺省为当前时间）起指定天数后过期 |
+//! | range | 明确的生效时间与到期时间 |
+//!
+//! 三种类型均可指定生效时间 `accountStartAt`：生效时间未到的账号保持停用并标记为待生效，
+//! 到达生效时间后由登录检查或启动补偿任务自动激活。
 //!
 //! ==========================================================================================
 
@@ -44,9 +51,15 @@ use std::collections::HashSet;
 // 引入鉴权模块的所有模型定义
 use crate::auth::models::{
     AdminChangeUserPasswordData, AdminChangeUserPasswordPayload, AdminDeleteUserPayload,
-    AdminListUsersPayload, AdminManagedUserData, AdminRegisterUserPayload, AdminRegisteredUserData,
-    AdminRenewUserAccountData, AdminRenewUserAccountPayload, AdminUpdateUserPayload,
+    AdminListUsersPayload, AdminManagedUserData, AdminPurgeDeletedUsersData,
+    AdminPurgeDeletedUsersPayload, AdminRegisterUserPayload, AdminRegisteredUserData,
+    AdminRenewUserAccountData, AdminRenewUserAccountPayload, AdminRestoreUserPayload,
+    AdminUpdateUserPayload,
 };
+// 引入管理员操作审计记录
+use crate::auth::admin_audit::UserAuditScope;
+// 引入委派管理员范围解析
+use crate::auth::admin_delegation_services::resolve_operator_scope;
 use crate::auth::rbac;
 // 引入核心错误处理模块
 use crate::core::error::AppError;
@@ -57,12 +70,12 @@ use crate::db::admin_repository;
 // 常量定义
 // ==========================================================================================
 
-// 预留设备范围接口的消息
-const RESERVED_DEVICE_SCOPE_MESSAGE: &str = "RESERVED_API_NOT_IMPLEMENTED";
-
 // 受保护的管理员用户名
 const PROTECTED_ADMIN_USERNAME: &str = "admin";
 
+// 尝试修改受保护管理员账号时的错误消息（批量操作据此判定为跳过）
+pub(super) const PROTECTED_ADMIN_MESSAGE: &str = "admin user only supports password change";
+
 // 操作员角色标识
 const ROLE_OPERATOR: &str = "operator";
 
@@ -80,6 +93,15 @@ const TERM_PERMANENT: &str = "permanent";
 // 按天期限类型标识
 const TERM_DAYS: &str = "days";
 
+// 起止时间期限类型标识
+const TERM_RANGE: &str = "range";
+
+// 软删除用户的默认保留天数（超过后可被物理清理）
+const DEFAULT_DELETED_USER_RETENTION_DAYS: i64 = 30;
+
+// 一天对应的毫秒数
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
 // ==========================================================================================
 // 用户注册
 // ==========================================================================================
@@ -99,7 +121,7 @@ const TERM_DAYS: &str = "days";
 // - 失败：返回 AppError 错误
 //
 // 执行流程：
-// 1. 校验操作员是否为管理员
+// 1. 解析操作员的管理范围（admin 或委派管理员）
 // 2. 校验新用户名的有效性
 // 3. 校验密码的有效性
 // 4. 校验昵称的有效性
@@ -110,6 +132,19 @@ const TERM_DAYS: &str = "days";
 pub fn register_user_by_admin(
     payload: AdminRegisterUserPayload,
     now_millis: u64,
+) -> Result<AdminRegisteredUserData, AppError> {
+    let audit = UserAuditScope::begin("auth_admin_register_user", &payload.operator_username, None);
+    let result = register_user(payload, now_millis);
+    audit
+        .with_target(result.as_ref().ok().map(|data| data.user_id))
+        .finish(&result, now_millis);
+    result
+}
+
+// 注册用户（不含审计记录）
+fn register_user(
+    payload: AdminRegisterUserPayload,
+    now_millis: u64,
 ) -> Result<AdminRegisteredUserData, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
@@ -122,8 +157,8 @@ pub fn register_user_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
 
     // 获取并校验新用户名
     let username = payload.username.trim().to_string();
@@ -143,12 +178,17 @@ pub fn register_user_by_admin(
 
     // 校验手机号格式（如果提供）
     validate_phone(payload.phone.as_deref())?;
-    // 规范化角色列表
+    // 规范化角色列表并校验是否可分配
     let roles = normalize_roles(payload.roles)?;
+    scope.ensure_roles_assignable(&roles)?;
+    // 确定所属组织（委派管理员只能放入授权组织子树）
+    let organization_id = scope.resolve_new_user_organization(payload.organization_id)?;
     // 计算账号有效期
-    let (account_is_permanent, account_valid_days, account_expire_at) = build_account_term(
+    let term = build_account_term(
         payload.account_term_type.as_str(),
         payload.account_valid_days,
+        payload.account_start_at,
+        payload.account_expire_at,
         now_millis,
     )?;
 
@@ -159,9 +199,13 @@ pub fn register_user_by_admin(
         nickname,
         phone: payload.phone,
         roles,
-        account_is_permanent,
-        account_valid_days,
-        account_expire_at,
+        account_is_permanent: term.is_permanent,
+        account_valid_days: term.valid_days,
+        account_expire_at: term.expire_at,
+        account_start_at: term.start_at,
+        // 管理员创建的账号首次登录须修改初始密码
+        must_change_password: true,
+        organization_id,
         created_by: operator_username,
         now_millis,
     })?;
@@ -174,6 +218,7 @@ pub fn register_user_by_admin(
         is_active: result.is_active,
         account_is_permanent: result.account_is_permanent,
         account_expire_at: result.account_expire_at,
+        account_start_at: result.account_start_at,
     })
 }
 
@@ -189,6 +234,7 @@ pub fn register_user_by_admin(
 
 // 功能说明：
 // 对指定用户的账户时效进行展期。
+// 账号尚未过期时从当前到期时间顺延，已过期时从当前时间（或更晚的生效时间）起算。
 
 // 失败情形：
 // 1. 尝试给超级管理员（admin）设定过期时间（它是永久的）
@@ -204,6 +250,21 @@ pub fn register_user_by_admin(
 pub fn renew_user_account_by_admin(
     payload: AdminRenewUserAccountPayload,
     now_millis: u64,
+) -> Result<AdminRenewUserAccountData, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_renew_user_account",
+        &payload.operator_username,
+        Some(payload.user_id),
+    );
+    let result = renew_user_account(payload, now_millis);
+    audit.finish(&result, now_millis);
+    result
+}
+
+// 续期用户账号（不含审计记录）
+fn renew_user_account(
+    payload: AdminRenewUserAccountPayload,
+    now_millis: u64,
 ) -> Result<AdminRenewUserAccountData, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
@@ -216,53 +277,26 @@ pub fn renew_user_account_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
 
     // 校验用户 ID
     if payload.user_id <= 0 {
         return Err(AppError::Validation("userId is required".to_string()));
     }
-    // 验证目标用户是否可编辑
+    // 验证目标用户是否可编辑且在管理范围内
     assert_target_user_editable(payload.user_id)?;
+    scope.ensure_user_manageable(payload.user_id)?;
 
-    // 处理续期模式
-    let renew_mode = payload.renew_mode.trim().to_ascii_lowercase();
-    let (account_is_permanent, account_valid_days, account_expire_at) =
-        if renew_mode == TERM_PERMANENT {
-            // 永久模式
-            (true, None, None)
-        } else if renew_mode == TERM_DAYS {
-            // 按天模式
-            let renew_days = payload
-                .renew_days
-                .ok_or_else(|| AppError::Validation("renewDays is required".to_string()))?;
-            if renew_days <= 0 {
-                return Err(AppError::Validation(
-                    "renewDays must be greater than 0".to_string(),
-                ));
-            }
-            // 计算毫秒数
-            let millis = renew_days
-                .checked_mul(24 * 60 * 60 * 1000)
-                .ok_or_else(|| AppError::Validation("renewDays is too large".to_string()))?;
-            // 计算过期时间
-            let expire_at = now_millis
-                .checked_add(millis)
-                .ok_or_else(|| AppError::Validation("renewDays is too large".to_string()))?;
-            (false, Some(renew_days), Some(expire_at))
-        } else {
-            return Err(AppError::Validation(
-                "renewMode must be 'permanent' or 'days'".to_string(),
-            ));
-        };
+    // 处理续期模式（过期时间由数据访问层基于当前到期时间计算）
+    let (account_is_permanent, renew_days) =
+        build_renew_term(&payload.renew_mode, payload.renew_days)?;
 
     // 调用数据访问层续期账号
     let result = admin_repository::renew_user_account(
         payload.user_id,
         account_is_permanent,
-        account_valid_days,
-        account_expire_at,
+        renew_days,
         now_millis,
     )?;
 
@@ -305,10 +339,14 @@ pub fn list_users_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
-    // 获取用户列表
-    let records = admin_repository::list_users()?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
+    // 先按账号期限同步启用状态，列表中的待生效 / 已过期账号与当前时间一致
+    sync_account_term_states(now_millis)?;
+    // 获取用户列表（默认不包含已软删除的用户）
+    let records = admin_repository::list_users(payload.include_deleted, payload.organization_id)?;
+    // 委派管理员仅返回其可管理的用户
+    let records = scope.filter_manageable(records)?;
     // 转换为响应格式并返回
     Ok(records.into_iter().map(map_managed_user_record).collect())
 }
@@ -333,6 +371,21 @@ pub fn list_users_by_admin(
 pub fn update_user_by_admin(
     payload: AdminUpdateUserPayload,
     now_millis: u64,
+) -> Result<AdminManagedUserData, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_update_user",
+        &payload.operator_username,
+        Some(payload.user_id),
+    );
+    let result = update_user(payload, now_millis);
+    audit.finish(&result, now_millis);
+    result
+}
+
+// 更新用户信息（不含审计记录）
+fn update_user(
+    payload: AdminUpdateUserPayload,
+    now_millis: u64,
 ) -> Result<AdminManagedUserData, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
@@ -344,15 +397,16 @@ pub fn update_user_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
 
     // 校验用户 ID
     if payload.user_id <= 0 {
         return Err(AppError::Validation("userId is required".to_string()));
     }
-    // 验证目标用户是否可编辑
+    // 验证目标用户是否可编辑且在管理范围内
     assert_target_user_editable(payload.user_id)?;
+    scope.ensure_user_manageable(payload.user_id)?;
 
     // 获取并校验用户名
     let username = payload.username.trim().to_string();
@@ -367,12 +421,15 @@ pub fn update_user_by_admin(
 
     // 校验手机号格式
     validate_phone(payload.phone.as_deref())?;
-    // 规范化角色列表
+    // 规范化角色列表并校验是否可分配
     let roles = normalize_roles(payload.roles)?;
+    scope.ensure_roles_assignable(&roles)?;
     // 计算账号有效期
-    let (account_is_permanent, account_valid_days, account_expire_at) = build_account_term(
+    let term = build_account_term(
         payload.account_term_type.as_str(),
         payload.account_valid_days,
+        payload.account_start_at,
+        payload.account_expire_at,
         now_millis,
     )?;
 
@@ -384,9 +441,10 @@ pub fn update_user_by_admin(
         phone: payload.phone,
         roles,
         is_active: payload.is_active,
-        account_is_permanent,
-        account_valid_days,
-        account_expire_at,
+        account_is_permanent: term.is_permanent,
+        account_valid_days: term.valid_days,
+        account_expire_at: term.expire_at,
+        account_start_at: term.start_at,
         now_millis,
     })?;
     // 返回更新结果
@@ -400,7 +458,8 @@ pub fn update_user_by_admin(
 // 管理员删除用户
 
 // 功能说明：
-// 物理删除某个用户，同时会自动级联清理角色表及路由表的绑定关系。
+// 软删除某个用户：记录删除时间与操作人并停用账号，角色绑定保留。
+// 已删除用户不再参与登录、鉴权与默认列表查询，用户名在清理前保持占用。
 
 // 参数说明：
 // - payload: 包含删除信息的请求体
@@ -413,6 +472,18 @@ pub fn delete_user_by_admin(
     payload: AdminDeleteUserPayload,
     now_millis: u64,
 ) -> Result<bool, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_delete_user",
+        &payload.operator_username,
+        Some(payload.user_id),
+    );
+    let result = delete_user(payload, now_millis);
+    audit.finish(&result, now_millis);
+    result
+}
+
+// 软删除用户（不含审计记录）
+fn delete_user(payload: AdminDeleteUserPayload, now_millis: u64) -> Result<bool, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
         .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
@@ -423,16 +494,17 @@ pub fn delete_user_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
     // 校验用户 ID
     if payload.user_id <= 0 {
         return Err(AppError::Validation("userId is required".to_string()));
     }
-    // 验证目标用户是否可删除
+    // 验证目标用户是否可删除且在管理范围内
     assert_target_user_editable(payload.user_id)?;
-    // 执行删除
-    let deleted = admin_repository::delete_user(payload.user_id)?;
+    scope.ensure_user_manageable(payload.user_id)?;
+    // 执行软删除
+    let deleted = admin_repository::delete_user(payload.user_id, &operator_username, now_millis)?;
     if !deleted {
         return Err(AppError::Validation("user not found".to_string()));
     }
@@ -440,6 +512,132 @@ pub fn delete_user_by_admin(
     Ok(true)
 }
 
+// 管理员恢复已软删除的用户
+
+// 功能说明：
+// 清除用户的软删除标记；若账号期限尚未到期则同时恢复激活状态，
+// 已过期账号恢复后保持停用，需要再通过续期接口重新启用。
+
+// 参数说明：
+// - payload: 包含恢复信息的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回恢复后的用户信息
+// - 失败：返回 AppError 错误
+pub fn restore_user_by_admin(
+    payload: AdminRestoreUserPayload,
+    now_millis: u64,
+) -> Result<AdminManagedUserData, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_restore_user",
+        &payload.operator_username,
+        Some(payload.user_id),
+    );
+    let result = restore_user(payload, now_millis);
+    audit.finish(&result, now_millis);
+    result
+}
+
+// 恢复用户（不含审计记录）
+fn restore_user(
+    payload: AdminRestoreUserPayload,
+    now_millis: u64,
+) -> Result<AdminManagedUserData, AppError> {
+    // 将时间戳转换为 i64 类型
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    // 获取并校验操作员用户名
+    let operator_username = payload.operator_username.trim().to_string();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
+    // 校验用户 ID
+    if payload.user_id <= 0 {
+        return Err(AppError::Validation("userId is required".to_string()));
+    }
+    // 验证目标用户在管理范围内
+    scope.ensure_user_manageable(payload.user_id)?;
+    // 执行恢复
+    let record = admin_repository::restore_user(payload.user_id, now_millis)?;
+    // 返回恢复结果
+    Ok(map_managed_user_record(record))
+}
+
+// 管理员清理超过保留期的已删除用户
+
+// 功能说明：
+// 物理删除软删除时间早于保留期的用户，级联清理角色绑定并释放用户名。
+
+// 参数说明：
+// - payload: 包含保留天数的请求体（缺省为 30 天）
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回清理数量与清理截止时间
+// - 失败：返回 AppError 错误
+pub fn purge_deleted_users_by_admin(
+    payload: AdminPurgeDeletedUsersPayload,
+    now_millis: u64,
+) -> Result<AdminPurgeDeletedUsersData, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_purge_deleted_users",
+        &payload.operator_username,
+        None,
+    );
+    let result = purge_deleted_users(payload, now_millis);
+    match &result {
+        Ok(data) => audit.finish_with_after(serde_json::to_value(data).ok(), None, now_millis),
+        Err(err) => audit.finish_with_after(None, Some(err.to_string()), now_millis),
+    }
+    result
+}
+
+// 清理已删除用户（不含审计记录）
+fn purge_deleted_users(
+    payload: AdminPurgeDeletedUsersPayload,
+    now_millis: u64,
+) -> Result<AdminPurgeDeletedUsersData, AppError> {
+    // 将时间戳转换为 i64 类型
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    // 获取并校验操作员用户名
+    let operator_username = payload.operator_username.trim().to_string();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    // 清理会物理删除用户，仅限 admin（委派管理员无权执行）
+    resolve_operator_scope(&operator_username, now_millis)?.require_full()?;
+
+    // 校验保留天数
+    let retention_days = payload
+        .retention_days
+        .unwrap_or(DEFAULT_DELETED_USER_RETENTION_DAYS);
+    if retention_days < 0 {
+        return Err(AppError::Validation(
+            "retentionDays must not be negative".to_string(),
+        ));
+    }
+    // 计算清理截止时间
+    let deleted_before = retention_days
+        .checked_mul(MILLIS_PER_DAY)
+        .and_then(|millis| now_millis.checked_sub(millis))
+        .ok_or_else(|| AppError::Validation("retentionDays is too large".to_string()))?;
+
+    // 执行清理
+    let purged_count = admin_repository::purge_deleted_users(deleted_before)?;
+    Ok(AdminPurgeDeletedUsersData {
+        purged_count,
+        deleted_before,
+    })
+}
+
 // ==========================================================================================
 // 密码修改
 // ==========================================================================================
@@ -460,6 +658,21 @@ pub fn delete_user_by_admin(
 pub fn change_user_password_by_admin(
     payload: AdminChangeUserPasswordPayload,
     now_millis: u64,
+) -> Result<AdminChangeUserPasswordData, AppError> {
+    let audit = UserAuditScope::begin(
+        "auth_admin_change_user_password",
+        &payload.operator_username,
+        Some(payload.user_id),
+    );
+    let result = change_user_password(payload, now_millis);
+    audit.finish(&result, now_millis);
+    result
+}
+
+// 修改用户密码（不含审计记录）
+fn change_user_password(
+    payload: AdminChangeUserPasswordPayload,
+    now_millis: u64,
 ) -> Result<AdminChangeUserPasswordData, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
@@ -471,8 +684,8 @@ pub fn change_user_password_by_admin(
             "operatorUsername is required".to_string(),
         ));
     }
-    // 验证操作员是否为管理员
-    assert_operator_can_manage_users(&operator_username, now_millis)?;
+    // 解析操作员的管理范围（admin 或委派管理员）
+    let scope = resolve_operator_scope(&operator_username, now_millis)?;
     // 校验用户 ID
     if payload.user_id <= 0 {
         return Err(AppError::Validation("userId is required".to_string()));
@@ -483,12 +696,26 @@ pub fn change_user_password_by_admin(
         return Err(AppError::Validation("password is required".to_string()));
     }
 
+    // 验证目标用户在管理范围内（委派管理员不能通过此接口重置本人密码）
+    scope.ensure_user_manageable(payload.user_id)?;
+
+    // 重置他人密码后要求对方下次登录修改，重置本人密码不设置该标记
+    let target_username = admin_repository::find_username_by_user_id(payload.user_id)?
+        .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
+    let must_change_password = !target_username.eq_ignore_ascii_case(&operator_username);
+
     // 调用数据访问层修改密码
-    let record = admin_repository::update_user_password(payload.user_id, &password, now_millis)?;
+    let record = admin_repository::update_user_password(
+        payload.user_id,
+        &password,
+        must_change_password,
+        now_millis,
+    )?;
     // 返回修改结果
     Ok(AdminChangeUserPasswordData {
         user_id: record.user_id,
         username: record.username,
+        must_change_password,
     })
 }
 
@@ -499,7 +726,8 @@ pub fn change_user_password_by_admin(
 // 确保用户账号可用
 
 // 功能说明：
-// 检查用户账号是否处于可用状态（未过期且未禁用）
+// 检查用户账号是否处于可用状态（已生效、未过期且未禁用）
+// 生效时间已到达的待生效账号在此处自动激活
 
 // 参数说明：
 // - username: 用户名
@@ -524,8 +752,11 @@ pub fn ensure_user_available_with_message(
         return Err(AppError::Validation(error_message.to_string()));
     };
 
-    // 检查用户是否激活
-    if !status.is_active {
+    // 检查账号是否已到生效时间
+    if status
+        .account_start_at
+        .is_some_and(|start_at| start_at > now_millis)
+    {
         return Err(AppError::Validation(error_message.to_string()));
     }
 
@@ -534,6 +765,16 @@ pub fn ensure_user_available_with_message(
         && status
             .account_expire_at
             .is_some_and(|expire_at| expire_at <= now_millis);
+
+    // 检查用户是否激活（待生效账号到达生效时间后即时激活）
+    let activated = !status.is_active
+        && status.account_activation_pending
+        && !expired
+        && admin_repository::activate_started_user_by_username(username, now_millis)?;
+    if !status.is_active && !activated {
+        return Err(AppError::Validation(error_message.to_string()));
+    }
+
     if expired {
         // 如果账号过期，自动停用用户
         admin_repository::deactivate_user_by_username(username, now_millis)?;
@@ -544,28 +785,51 @@ pub fn ensure_user_available_with_message(
     Ok(())
 }
 
+// 判断用户是否仍需强制修改密码
+//
+// 用于刷新令牌时决定签发常规令牌还是受限令牌；用户不存在时返回 false，
+// 由账号可用性校验负责拒绝
+pub fn password_change_required(username: &str) -> Result<bool, AppError> {
+    Ok(admin_repository::find_user_login_state(username)?
+        .is_some_and(|status| status.must_change_password))
+}
+
 // 运行启动时过期账号补偿
 
 // 功能说明：
-// 在应用启动时自动停用所有已过期的用户账号
+// 在应用启动时自动停用所有已过期的用户账号，并激活生效时间已到达的待生效账号
 
 // 参数说明：
 // - now_millis: 当前时间戳（毫秒）
 
 // 返回值：
-// - 成功：返回停用的用户数量
+// - 成功：返回状态发生变化（停用 + 激活）的用户数量
 // - 失败：返回 AppError 错误
 pub fn run_startup_expiration_compensation(now_millis: u64) -> Result<usize, AppError> {
     // 将时间戳转换为 i64 类型
     let now_millis = i64::try_from(now_millis)
         .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
-    // 执行过期账号停用
-    admin_repository::deactivate_expired_users(now_millis)
+    sync_account_term_states(now_millis)
 }
 
-// 获取预留设备范围接口消息
-pub fn reserved_device_scope_message() -> &'static str {
-    RESERVED_DEVICE_SCOPE_MESSAGE
+// 按账号期限同步启用状态
+
+// 功能说明：
+// 停用所有已过期的账号，并激活生效时间已到达的待生效账号。
+// 除启动补偿外，管理员用户列表与批量操作执行前也会调用，
+// 使待生效账号在生效时间之后无需等到用户登录或应用重启即显示为启用
+
+// 参数说明：
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回状态发生变化（停用 + 激活）的用户数量
+// - 失败：返回 AppError 错误
+pub(super) fn sync_account_term_states(now_millis: i64) -> Result<usize, AppError> {
+    // 先停用过期账号（同时清除其待生效标记），再激活已到生效时间的账号
+    let deactivated = admin_repository::deactivate_expired_users(now_millis)?;
+    let activated = admin_repository::activate_started_users(now_millis)?;
+    Ok(deactivated + activated)
 }
 
 // ==========================================================================================
@@ -581,7 +845,7 @@ pub fn reserved_device_scope_message() -> &'static str {
 // 返回值：
 // - 成功：返回 ()
 // - 失败：返回 AppError 错误
-fn assert_operator_can_manage_users(
+pub(super) fn assert_operator_can_manage_users(
     operator_username: &str,
     now_millis: i64,
 ) -> Result<(), AppError> {
@@ -605,7 +869,7 @@ fn assert_operator_can_manage_users(
 // 返回值：
 // - 成功：返回规范化后的角色列表
 // - 失败：返回 AppError 错误
-fn normalize_roles(raw_roles: Vec<String>) -> Result<Vec<String>, AppError> {
+pub(super) fn normalize_roles(raw_roles: Vec<String>) -> Result<Vec<String>, AppError> {
     // 使用 HashSet 去重
     let mut normalized = HashSet::new();
     for role in raw_roles {
@@ -640,29 +904,76 @@ fn is_allowed_role(role: &str) -> bool {
     )
 }
 
+// 账号期限信息
+struct AccountTerm {
+    is_permanent: bool,      // 是否永久账号
+    valid_days: Option<i64>, // 有效天数
+    start_at: Option<i64>,   // 生效时间戳
+    expire_at: Option<i64>,  // 过期时间戳
+}
+
 // 构建账号期限信息
 
 // 参数说明：
-// - account_term_type: 期限类型
-// - account_valid_days: 有效天数
+// - account_term_type: 期限类型（permanent / days / range）
+// - account_valid_days: 有效天数（days 类型必填）
+// - account_start_at: 生效时间戳（range 类型必填，其余类型可选）
+// - account_expire_at: 到期时间戳（range 类型必填）
 // - now_millis: 当前时间戳（毫秒）
 
 // 返回值：
-// - 成功：返回 (是否永久, 有效天数, 过期时间戳)
+// - 成功：返回账号期限信息
 // - 失败：返回 AppError 错误
 fn build_account_term(
     account_term_type: &str,
     account_valid_days: Option<i64>,
+    account_start_at: Option<i64>,
+    account_expire_at: Option<i64>,
     now_millis: i64,
-) -> Result<(bool, Option<i64>, Option<i64>), AppError> {
+) -> Result<AccountTerm, AppError> {
+    if account_start_at.is_some_and(|start_at| start_at <= 0) {
+        return Err(AppError::Validation(
+            "accountStartAt must be greater than 0".to_string(),
+        ));
+    }
+
     let term = account_term_type.trim().to_ascii_lowercase();
     if term == TERM_PERMANENT {
-        return Ok((true, None, None));
+        return Ok(AccountTerm {
+            is_permanent: true,
+            valid_days: None,
+            start_at: account_start_at,
+            expire_at: None,
+        });
+    }
+
+    if term == TERM_RANGE {
+        // 起止时间均必填，且到期时间需晚于生效时间与当前时间
+        let start_at = account_start_at
+            .ok_or_else(|| AppError::Validation("accountStartAt is required".to_string()))?;
+        let expire_at = account_expire_at
+            .ok_or_else(|| AppError::Validation("accountExpireAt is required".to_string()))?;
+        if expire_at <= start_at {
+            return Err(AppError::Validation(
+                "accountExpireAt must be later than accountStartAt".to_string(),
+            ));
+        }
+        if expire_at <= now_millis {
+            return Err(AppError::Validation(
+                "accountExpireAt must be in the future".to_string(),
+            ));
+        }
+        return Ok(AccountTerm {
+            is_permanent: false,
+            valid_days: None,
+            start_at: Some(start_at),
+            expire_at: Some(expire_at),
+        });
     }
 
     if term != TERM_DAYS {
         return Err(AppError::Validation(
-            "accountTermType must be 'permanent' or 'days'".to_string(),
+            "accountTermType must be 'permanent', 'days' or 'range'".to_string(),
         ));
     }
 
@@ -675,14 +986,59 @@ fn build_account_term(
         ));
     }
 
-    // 计算毫秒和过期时间
+    // 计算毫秒和过期时间（从生效时间与当前时间中较晚者起算）
     let millis = days
-        .checked_mul(24 * 60 * 60 * 1000)
+        .checked_mul(MILLIS_PER_DAY)
         .ok_or_else(|| AppError::Validation("accountValidDays is too large".to_string()))?;
-    let expire_at = now_millis
+    let expire_at = account_start_at
+        .map_or(now_millis, |start_at| start_at.max(now_millis))
         .checked_add(millis)
         .ok_or_else(|| AppError::Validation("accountValidDays is too large".to_string()))?;
-    Ok((false, Some(days), Some(expire_at)))
+    Ok(AccountTerm {
+        is_permanent: false,
+        valid_days: Some(days),
+        start_at: account_start_at,
+        expire_at: Some(expire_at),
+    })
+}
+
+// 校验续期参数
+
+// 参数说明：
+// - renew_mode: 续期模式（permanent / days）
+// - renew_days: 续期天数
+
+// 返回值：
+// - 成功：返回 (是否永久, 续期天数)；过期时间由数据访问层基于当前到期时间计算
+// - 失败：返回 AppError 错误
+pub(super) fn build_renew_term(
+    renew_mode: &str,
+    renew_days: Option<i64>,
+) -> Result<(bool, Option<i64>), AppError> {
+    let renew_mode = renew_mode.trim().to_ascii_lowercase();
+    if renew_mode == TERM_PERMANENT {
+        // 永久模式
+        return Ok((true, None));
+    }
+    if renew_mode != TERM_DAYS {
+        return Err(AppError::Validation(
+            "renewMode must be 'permanent' or 'days'".to_string(),
+        ));
+    }
+
+    // 按天模式
+    let renew_days =
+        renew_days.ok_or_else(|| AppError::Validation("renewDays is required".to_string()))?;
+    if renew_days <= 0 {
+        return Err(AppError::Validation(
+            "renewDays must be greater than 0".to_string(),
+        ));
+    }
+    // 提前拒绝无法换算为毫秒的天数
+    if renew_days.checked_mul(MILLIS_PER_DAY).is_none() {
+        return Err(AppError::Validation("renewDays is too large".to_string()));
+    }
+    Ok((false, Some(renew_days)))
 }
 
 // 校验手机号格式
@@ -710,21 +1066,21 @@ fn validate_phone(phone: Option<&str>) -> Result<(), AppError> {
 }
 
 // 验证目标用户是否可编辑
-fn assert_target_user_editable(user_id: i64) -> Result<(), AppError> {
+pub(super) fn assert_target_user_editable(user_id: i64) -> Result<(), AppError> {
     // 根据用户 ID 查询用户名
     let username = admin_repository::find_username_by_user_id(user_id)?
         .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
     // 检查是否为受保护的管理员用户
     if username.eq_ignore_ascii_case(PROTECTED_ADMIN_USERNAME) {
-        return Err(AppError::Validation(
-            "admin user only supports password change".to_string(),
-        ));
+        return Err(AppError::Validation(PROTECTED_ADMIN_MESSAGE.to_string()));
     }
     Ok(())
 }
 
 // 将数据访问层的记录转换为 API 响应格式
-fn map_managed_user_record(record: admin_repository::ManagedUserRecord) -> AdminManagedUserData {
+pub(super) fn map_managed_user_record(
+    record: admin_repository::ManagedUserRecord,
+) -> AdminManagedUserData {
     AdminManagedUserData {
         user_id: record.user_id,
         username: record.username,
@@ -738,5 +1094,11 @@ fn map_managed_user_record(record: admin_repository::ManagedUserRecord) -> Admin
         created_at: record.created_at,
         updated_at: record.updated_at,
         created_by: record.created_by,
+        deleted_at: record.deleted_at,
+        deleted_by: record.deleted_by,
+        account_start_at: record.account_start_at,
+        account_activation_pending: record.account_activation_pending,
+        must_change_password: record.must_change_password,
+        organization_id: record.organization_id,
     }
 }
@@ -17,6 +17,7 @@
 //! |--------|-------------|------|----------|
 //! | `auth_login` | `invoke("auth_login", { username, password })` | 用户登录验证 | `LoginData` |
 //! | `auth_refresh_token` | `invoke("auth_refresh_token", { refreshToken })` | 刷新访问令牌 | `RefreshTokenData` |
+//! | `auth_change_own_password` | `invoke("auth_change_own_password", { accessToken, oldPassword, newPassword })` | 修改本人密码（受限令牌唯一可用命令） | `LoginData` |
 //! | `auth_get_async_routes` | `invoke("auth_get_async_routes")` | 获取动态路由配置 | `Vec<Value>` |
 //!
 //! ==========================================================================================
@@ -117,10 +118,12 @@
 use serde_json::Value;
 
 use crate::auth::admin_services;
-use crate::auth::models::{LoginData, LoginPayload, RefreshTokenData, RefreshTokenPayload};
+use crate::auth::models::{
+    ChangeOwnPasswordPayload, LoginData, LoginPayload, RefreshTokenData, RefreshTokenPayload,
+};
 use crate::auth::services::{
-    build_async_routes, build_login_data, mint_token_pair, resolve_user_profile,
-    verify_refresh_token,
+    build_async_routes, build_login_data, change_own_password, mint_password_change_token_pair,
+    mint_token_pair, resolve_user_profile, verify_refresh_token,
 };
 use crate::core::error::{ApiResponse, AppError, AppResult};
 use crate::core::tracing::{TraceContext, execute_traced_command};
@@ -157,6 +160,7 @@ use crate::core::tracing::{TraceContext, execute_traced_command};
 /// - `access_token`: JWT 访问令牌（有效期 2 小时）
 /// - `refresh_token`: JWT 刷新令牌（有效期 7 天）
 /// - `expires`: 令牌过期时间（Unix 毫秒时间戳）
+/// - `must_change_password`: 是否需要强制修改密码（为 true 时令牌仅可用于 `auth_change_own_password`）
 ///
 /// 错误处理：
 /// - 参数校验失败：
@@ -205,7 +209,8 @@ pub fn auth_login(payload: LoginPayload, trace: Option<TraceContext>) -> AppResu
 ///    - 校验 JWT 签名是否有效
 ///    - 校验令牌是否过期
 ///    - 校验令牌类型是否为 "refresh"（防止用 access_token 刷新）
-/// 4. 生成新令牌：从验证通过的用户主题（subject）生成新的令牌对
+/// 4. 生成新令牌：从验证通过的用户主题（subject）生成新的令牌对，
+///    用户仍需强制改密时只生成受限令牌
 /// 5. 响应封装：将新的令牌信息封装返回
 ///
 /// 参数校验规则：
@@ -216,6 +221,7 @@ pub fn auth_login(payload: LoginPayload, trace: Option<TraceContext>) -> AppResu
 /// - `access_token`: 新的 JWT 访问令牌（有效期 2 小时）
 /// - `refresh_token`: 新的 JWT 刷新令牌（有效期 7 天）
 /// - `expires`: 新令牌的过期时间（Unix 毫秒时间戳）
+/// - `must_change_password`: 是否需要强制修改密码
 ///
 /// 安全设计：
 /// - 令牌类型校验：仅接受 `token_type` 为 "refresh" 的令牌，防止 access_token 被滥用
@@ -251,15 +257,60 @@ pub fn auth_refresh_token(
             "invalid refreshToken",
             crate::auth::services::now_millis(),
         )?;
-        let refreshed = mint_token_pair(&subject);
+        // 以数据库中的强制改密标记为准，而不是沿用旧令牌的作用域
+        let must_change_password = admin_services::password_change_required(&subject)?;
+        let refreshed = if must_change_password {
+            mint_password_change_token_pair(&subject)
+        } else {
+            mint_token_pair(&subject)
+        };
         Ok(ApiResponse::ok(RefreshTokenData {
             access_token: refreshed.access_token,
             refresh_token: refreshed.refresh_token,
             expires: refreshed.expires,
+            must_change_password,
         }))
     })
 }
 
+// ==========================================================================================
+// 修改本人密码命令 (auth_change_own_password)
+// ==========================================================================================
+
+/// 修改本人密码命令处理器
+///
+/// 功能说明：
+/// 已登录用户凭访问令牌修改自己的密码。被要求强制改密的用户登录后只持有受限令牌，
+/// 本命令是受限令牌唯一可调用的命令；改密成功后清除强制改密标记并返回常规令牌。
+///
+/// 执行流程：
+/// 1. 参数校验：访问令牌不能为空
+/// 2. 业务处理：调用 `change_own_password` 校验令牌与旧密码并写入新密码
+/// 3. 响应封装：返回携带常规令牌的 [`LoginData`]
+///
+/// 错误处理：
+/// - 访问令牌为空 → `AppError::Validation("accessToken is required")`
+/// - 访问令牌无效 → `AppError::Validation("invalid accessToken")`
+/// - 旧密码错误 → `AppError::Validation("invalid oldPassword")`
+/// - 新密码为空或与旧密码相同 → `AppError::Validation(...)`
+///
+/// 测试覆盖：
+/// - `admin_registered_user_must_change_password`：验证新账号登录获得受限令牌并可改密解除
+/// - `change_own_password_rejects_wrong_old_password`：验证旧密码错误被拒绝
+#[tauri::command]
+pub fn auth_change_own_password(
+    payload: ChangeOwnPasswordPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<LoginData> {
+    execute_traced_command("auth_change_own_password", trace, || {
+        if payload.access_token.trim().is_empty() {
+            return Err(AppError::Validation("accessToken is required".to_string()));
+        }
+        let data = change_own_password(payload, crate::auth::services::now_millis())?;
+        Ok(ApiResponse::ok(data))
+    })
+}
+
 // ==========================================================================================
 // 获取动态路由命令 (auth_get_async_routes)
 // ==========================================================================================
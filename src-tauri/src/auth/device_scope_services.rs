@@ -0,0 +1,490 @@
+//! ==========================================================================================
+//! 用户设备范围业务逻辑层
+//!
+//! 模块职责：
+//! 维护每个用户可访问的区域、楼层与设备（`user_device_scope_get` / `user_device_scope_upsert`），
+//! 并提供设备访问判定 API，回答“用户 X 能否查看设备 Y”。
+//! 设备管理模块另外按目标位置判定放置权限（`resolve_placement_access`），
+//! 并按可访问设备集合过滤设备列表（`resolve_accessible_devices`）。
+//!
+//! 判定规则（按顺序，命中任一即可访问）：
+//!
+//! | 来源 | 条件 |
+//! |------|------|
+//! | `role` | 用户角色拥有 `device:manage` 权限（admin） |
+//! | `allDevices` / `device` | 可访问所有设备，或设备在授权设备列表中 |
+//! | `allAreas` / `area` | 设备已设置区域，且可访问所有区域或区域在授权区域列表中 |
+//! | `allFloors` / `floor` | 设备已设置楼层，且可访问所有楼层或楼层在授权楼层列表中 |
+//!
+//! 已删除、已停用或已过期的用户、不存在的设备一律不可访问；未配置设备范围的用户仅 admin 可访问。
+//! 设备范围的配置与查询仅限拥有 `device:manage` 权限的操作员，保存写入审计事件（目标类型 `user_device_scope`）。
+//!
+//! ==========================================================================================
+
+// 引入标准库的 BTreeSet，用于编码去重排序
+use std::collections::BTreeSet;
+
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入鉴权模块的模型定义
+use crate::auth::models::{
+    UserDeviceScopeCheckData, UserDeviceScopeCheckPayload, UserDeviceScopeData,
+    UserDeviceScopeGetPayload, UserDeviceScopeSnapshot, UserDeviceScopeUpsertPayload,
+};
+use crate::auth::rbac;
+// 引入核心错误处理模块
+use crate::core::error::AppError;
+// 引入管理员数据访问层
+use crate::db::admin_repository::{self, DeviceLocationRecord, UserDeviceScopeRecord};
+
+// ==========================================================================================
+// 常量定义
+// ==========================================================================================
+
+// 审计目标类型：用户设备范围
+const TARGET_TYPE_USER_DEVICE_SCOPE: &str = "user_device_scope";
+
+// 设备范围仅限 admin 维护
+const ADMIN_ONLY_MESSAGE: &str = "forbidden: admin only";
+
+// ==========================================================================================
+// 设备访问判定
+// ==========================================================================================
+
+// 设备访问的授权来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceAccessGrant {
+    // 角色拥有 device:manage 权限
+    Role,
+    // 可访问所有设备
+    AllDevices,
+    // 设备在授权设备列表中
+    Device,
+    // 可访问所有区域内的设备
+    AllAreas,
+    // 设备所在区域在授权区域列表中
+    Area,
+    // 可访问所有楼层内的设备
+    AllFloors,
+    // 设备所在楼层在授权楼层列表中
+    Floor,
+}
+
+impl DeviceAccessGrant {
+    // 授权来源的接口名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Role => "role",
+            Self::AllDevices => "allDevices",
+            Self::Device => "device",
+            Self::AllAreas => "allAreas",
+            Self::Area => "area",
+            Self::AllFloors => "allFloors",
+            Self::Floor => "floor",
+        }
+    }
+}
+
+// 判定用户能否访问设备
+//
+// 参数说明：
+// - user_id: 用户 ID
+// - device_id: 设备标识
+// - now_millis: 当前时间戳（毫秒，用于判定账号是否在有效期内）
+//
+// 返回值：
+// - 成功：返回是否可访问
+// - 失败：返回数据库错误
+pub fn can_user_access_device(
+    user_id: i64,
+    device_id: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    Ok(resolve_device_access(user_id, device_id, now_millis)?.is_some())
+}
+
+// 解析用户访问设备的授权来源
+//
+// 参数说明：
+// - user_id: 用户 ID
+// - device_id: 设备标识（去除首尾空格后匹配）
+// - now_millis: 当前时间戳（毫秒）
+//
+// 返回值：
+// - 成功：返回命中的授权来源（不可访问时为 None）
+// - 失败：返回数据库错误
+pub fn resolve_device_access(
+    user_id: i64,
+    device_id: &str,
+    now_millis: i64,
+) -> Result<Option<DeviceAccessGrant>, AppError> {
+    let Some(location) = admin_repository::find_device_location(device_id.trim())? else {
+        return Ok(None);
+    };
+    Ok(load_user_device_access(user_id, now_millis)?.and_then(|access| access.resolve(&location)))
+}
+
+// 解析用户把设备放置到指定位置后能否访问该设备
+//
+// 创建设备或移动设备所在位置前调用：按目标位置所在的区域与楼层判定，
+// 已显式授权的设备标识同样命中
+//
+// 参数说明：
+// - user_id: 用户 ID
+// - device_id: 设备标识（新设备可尚未登记）
+// - location_id: 目标位置 ID（None 表示不设置位置）
+// - now_millis: 当前时间戳（毫秒）
+//
+// 返回值：
+// - 成功：返回命中的授权来源（不可访问时为 None）
+// - 失败：返回数据库错误
+pub fn resolve_placement_access(
+    user_id: i64,
+    device_id: &str,
+    location_id: Option<i64>,
+    now_millis: i64,
+) -> Result<Option<DeviceAccessGrant>, AppError> {
+    let Some(access) = load_user_device_access(user_id, now_millis)? else {
+        return Ok(None);
+    };
+    let location = admin_repository::find_device_placement(device_id.trim(), location_id)?;
+    Ok(access.resolve(&location))
+}
+
+// 用户可访问的设备集合（设备列表按此过滤）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceAccessFilter {
+    // 可访问全部设备
+    All,
+    // 仅可访问列出的设备（按设备标识排序）
+    Devices(Vec<String>),
+}
+
+// 解析用户可访问的设备集合
+//
+// 参数说明：
+// - user_id: 用户 ID
+// - now_millis: 当前时间戳（毫秒）
+//
+// 返回值：
+// - 成功：`device:manage` 角色或 `allDevices` 返回 All，其余返回设备范围覆盖的设备
+// - 失败：返回数据库错误
+pub fn resolve_accessible_devices(
+    user_id: i64,
+    now_millis: i64,
+) -> Result<DeviceAccessFilter, AppError> {
+    match load_user_device_access(user_id, now_millis)? {
+        Some(UserDeviceAccess::Role) => Ok(DeviceAccessFilter::All),
+        Some(UserDeviceAccess::Scope(scope)) if scope.all_devices => Ok(DeviceAccessFilter::All),
+        Some(UserDeviceAccess::Scope(_)) => Ok(DeviceAccessFilter::Devices(
+            admin_repository::find_scope_device_ids(user_id)?,
+        )),
+        None => Ok(DeviceAccessFilter::Devices(Vec::new())),
+    }
+}
+
+// 用户的设备访问能力
+enum UserDeviceAccess {
+    // 角色拥有 device:manage 权限
+    Role,
+    // 按已保存的设备范围判定
+    Scope(UserDeviceScopeRecord),
+}
+
+impl UserDeviceAccess {
+    // 判定能否访问指定位置的设备
+    fn resolve(&self, location: &DeviceLocationRecord) -> Option<DeviceAccessGrant> {
+        match self {
+            Self::Role => Some(DeviceAccessGrant::Role),
+            Self::Scope(scope) => match_device_scope(scope, location),
+        }
+    }
+}
+
+// 加载用户的设备访问能力
+//
+// 已删除、停用、过期或未生效的用户，以及未配置设备范围的非 admin 用户返回 None
+fn load_user_device_access(
+    user_id: i64,
+    now_millis: i64,
+) -> Result<Option<UserDeviceAccess>, AppError> {
+    let Some(user) =
+        admin_repository::find_managed_user(user_id)?.filter(|user| user.deleted_at.is_none())
+    else {
+        return Ok(None);
+    };
+    // 停用、过期或未生效的账号没有有效角色
+    if admin_repository::find_effective_roles(&user.username, now_millis)?.is_empty() {
+        return Ok(None);
+    }
+    match rbac::ensure_user_allowed(
+        &user.username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        now_millis,
+        ADMIN_ONLY_MESSAGE,
+    ) {
+        Ok(()) => return Ok(Some(UserDeviceAccess::Role)),
+        Err(AppError::Validation(_)) => {}
+        Err(err) => return Err(err),
+    }
+    Ok(admin_repository::find_user_device_scope(user_id)?.map(UserDeviceAccess::Scope))
+}
+
+// 按设备范围匹配设备（通配标记与显式明细取并集）
+fn match_device_scope(
+    scope: &UserDeviceScopeRecord,
+    location: &DeviceLocationRecord,
+) -> Option<DeviceAccessGrant> {
+    if scope.all_devices {
+        return Some(DeviceAccessGrant::AllDevices);
+    }
+    if scope.devices.contains(&location.device_id) {
+        return Some(DeviceAccessGrant::Device);
+    }
+    if let Some(area_code) = &location.area_code {
+        if scope.all_areas {
+            return Some(DeviceAccessGrant::AllAreas);
+        }
+        if scope.areas.contains(area_code) {
+            return Some(DeviceAccessGrant::Area);
+        }
+    }
+    if let Some(floor_code) = &location.floor_code {
+        if scope.all_floors {
+            return Some(DeviceAccessGrant::AllFloors);
+        }
+        if scope.floors.contains(floor_code) {
+            return Some(DeviceAccessGrant::Floor);
+        }
+    }
+    None
+}
+
+// ==========================================================================================
+// 设备范围维护（仅限 admin）
+// ==========================================================================================
+
+// 管理员查询用户设备范围
+
+// 参数说明：
+// - payload: 包含操作员与目标用户 ID 的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回设备范围（未配置时通配标记均为 false、列表为空）
+// - 失败：返回 AppError 错误
+pub fn get_user_device_scope_by_admin(
+    payload: &UserDeviceScopeGetPayload,
+    now_millis: u64,
+) -> Result<UserDeviceScopeData, AppError> {
+    prepare_admin_operation(&payload.operator_username, now_millis)?;
+    ensure_target_user(payload.user_id, false)?;
+    load_device_scope(payload.user_id)
+}
+
+// 管理员保存用户设备范围
+
+// 功能说明：
+// 整体替换目标用户的通配标记与区域、楼层、设备列表。编码去除首尾空格并去重，
+// 引用的区域、楼层、设备须已存在，校验与写入在同一事务中完成。
+
+// 参数说明：
+// - payload: 包含操作员、目标用户与设备范围的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回保存后的设备范围
+// - 失败：返回 AppError 错误
+pub fn upsert_user_device_scope_by_admin(
+    payload: UserDeviceScopeUpsertPayload,
+    now_millis: u64,
+) -> Result<UserDeviceScopeData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let user_id = payload.user_id;
+    let before = find_snapshot(user_id);
+    let result = upsert_user_device_scope(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|data| serde_json::to_value(data).ok());
+    audit_services::record_command(
+        CommandAudit {
+            command: "user_device_scope_upsert",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_USER_DEVICE_SCOPE,
+            target_id: Some(user_id)
+                .filter(|user_id| *user_id > 0)
+                .map(|user_id| user_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 保存用户设备范围（不含审计记录）
+fn upsert_user_device_scope(
+    payload: UserDeviceScopeUpsertPayload,
+    now_millis: u64,
+) -> Result<UserDeviceScopeData, AppError> {
+    let (operator_username, now_millis) =
+        prepare_admin_operation(&payload.operator_username, now_millis)?;
+    ensure_target_user(payload.user_id, true)?;
+
+    let record =
+        admin_repository::replace_user_device_scope(&admin_repository::UserDeviceScopeInput {
+            user_id: payload.user_id,
+            all_areas: payload.all_areas,
+            all_floors: payload.all_floors,
+            all_devices: payload.all_devices,
+            areas: normalize_codes("areas", payload.areas)?,
+            floors: normalize_codes("floors", payload.floors)?,
+            devices: normalize_codes("devices", payload.devices)?,
+            updated_by: operator_username,
+            now_millis,
+        })?;
+    Ok(map_device_scope_record(payload.user_id, Some(record)))
+}
+
+// 管理员判定用户能否访问设备
+
+// 参数说明：
+// - payload: 包含操作员、目标用户与设备标识的请求体
+// - now_millis: 当前时间戳（毫秒）
+
+// 返回值：
+// - 成功：返回判定结果与命中的授权来源
+// - 失败：返回 AppError 错误
+pub fn check_user_device_scope_by_admin(
+    payload: &UserDeviceScopeCheckPayload,
+    now_millis: u64,
+) -> Result<UserDeviceScopeCheckData, AppError> {
+    let (_, now_millis) = prepare_admin_operation(&payload.operator_username, now_millis)?;
+    ensure_target_user(payload.user_id, false)?;
+    let device_id = payload.device_id.trim().to_string();
+    if device_id.is_empty() {
+        return Err(AppError::Validation("deviceId is required".to_string()));
+    }
+    let grant = resolve_device_access(payload.user_id, &device_id, now_millis)?;
+    Ok(UserDeviceScopeCheckData {
+        user_id: payload.user_id,
+        device_id,
+        allowed: grant.is_some(),
+        matched_by: grant.map(|grant| grant.as_str().to_string()),
+    })
+}
+
+// ==========================================================================================
+// 内部辅助函数
+// ==========================================================================================
+
+// 校验时间戳与操作员的 device:manage 权限
+
+// 返回值：
+// - 成功：返回 (操作员用户名, i64 类型的当前时间戳)
+// - 失败：返回 AppError 错误
+fn prepare_admin_operation(
+    operator_username: &str,
+    now_millis: u64,
+) -> Result<(String, i64), AppError> {
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    let operator_username = operator_username.trim().to_string();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    rbac::ensure_user_allowed(
+        &operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        now_millis,
+        ADMIN_ONLY_MESSAGE,
+    )?;
+    Ok((operator_username, now_millis))
+}
+
+// 校验目标用户存在（保存时要求未被删除）
+fn ensure_target_user(user_id: i64, require_not_deleted: bool) -> Result<(), AppError> {
+    if user_id <= 0 {
+        return Err(AppError::Validation("userId is required".to_string()));
+    }
+    match admin_repository::find_managed_user(user_id)? {
+        Some(record) if !(require_not_deleted && record.deleted_at.is_some()) => Ok(()),
+        _ => Err(AppError::Validation("user not found".to_string())),
+    }
+}
+
+// 规范化编码列表：去除首尾空格、去重并排序，拒绝空编码
+fn normalize_codes(field: &str, raw_codes: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut codes = BTreeSet::new();
+    for code in raw_codes {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err(AppError::Validation(format!("{field} contains empty code")));
+        }
+        codes.insert(code.to_string());
+    }
+    Ok(codes.into_iter().collect())
+}
+
+// 读取用户设备范围并转换为 API 响应格式
+fn load_device_scope(user_id: i64) -> Result<UserDeviceScopeData, AppError> {
+    let record = admin_repository::find_user_device_scope(user_id)?;
+    Ok(map_device_scope_record(user_id, record))
+}
+
+// 将设备范围记录转换为 API 响应格式（未配置时返回空范围）
+fn map_device_scope_record(
+    user_id: i64,
+    record: Option<UserDeviceScopeRecord>,
+) -> UserDeviceScopeData {
+    match record {
+        Some(record) => UserDeviceScopeData {
+            user_id,
+            scope: UserDeviceScopeSnapshot {
+                all_areas: record.all_areas,
+                all_floors: record.all_floors,
+                all_devices: record.all_devices,
+                areas: record.areas,
+                floors: record.floors,
+                devices: record.devices,
+            },
+            updated_at: Some(record.updated_at),
+            updated_by: Some(record.updated_by),
+        },
+        None => UserDeviceScopeData {
+            user_id,
+            scope: UserDeviceScopeSnapshot {
+                all_areas: false,
+                all_floors: false,
+                all_devices: false,
+                areas: vec![],
+                floors: vec![],
+                devices: vec![],
+            },
+            updated_at: None,
+            updated_by: None,
+        },
+    }
+}
+
+// 采集设备范围快照（未配置或查询失败时为 None）
+fn find_snapshot(user_id: i64) -> Option<Value> {
+    if user_id <= 0 {
+        return None;
+    }
+    match admin_repository::find_user_device_scope(user_id) {
+        Ok(record) => serde_json::to_value(map_device_scope_record(user_id, Some(record?))).ok(),
+        Err(err) => {
+            tracing::warn!(user_id, error = %err, "audit snapshot load failed");
+            None
+        }
+    }
+}
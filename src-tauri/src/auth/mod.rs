@@ -15,6 +15,10 @@
 //! ├── models.rs           # 数据模型层（DTO）- 数据传输对象
 //! ├── admin_commands.rs   # 管理员 IPC 接口层
 //! ├── admin_services.rs   # 管理员业务逻辑层
+//! ├── admin_batch_services.rs # 管理员批量账号操作业务逻辑层
+//! ├── admin_delegation_services.rs # 委派管理员范围解析与委派配置
+//! ├── admin_audit.rs      # 管理员操作审计记录
+//! ├── device_scope_services.rs # 用户设备范围配置与设备访问判定
 //! ├── rbac.rs             # Casbin RBAC 校验与策略装载
 //! └── README.md           # 模块文档
 //! ```
@@ -34,6 +38,10 @@
 //! | `admin_commands.rs` | Adapter Layer | 管理员命令处理 | 薄层适配 |
 //! | `services.rs` | Domain Layer | 业务规则、令牌管理、数据库查询 | 纯函数，无框架依赖 |
 //! | `admin_services.rs` | Domain Layer | 管理员业务规则 | 纯函数 |
+//! | `admin_batch_services.rs` | Domain Layer | 批量账号操作（单事务 / 尽力而为） | 复用管理员业务规则 |
+//! | `admin_delegation_services.rs` | Domain Layer | 委派管理员范围解析与委派配置 | 每个管理操作按操作员范围校验 |
+//! | `device_scope_services.rs` | Domain Layer | 用户设备范围配置与设备访问判定 | 通配标记与显式明细取并集 |
+//! | `admin_audit.rs` | Domain Layer | 管理员操作前后快照与审计写入 | 审计失败不影响业务结果 |
 //! | `rbac.rs` | Domain Layer | RBAC 策略执行（Casbin） | PostgreSQL 持久化策略 |
 //! | `models.rs` | DTO Layer | 数据结构定义、序列化配置 | 仅包含数据字段 |
 //!
@@ -41,22 +49,36 @@
 //!
 //! - 用户登录 (`auth_login`)
 //! - 令牌刷新 (`auth_refresh_token`)
+//! - 修改本人密码 (`auth_change_own_password`，强制改密时受限令牌唯一可用命令)
 //! - 获取动态路由 (`auth_get_async_routes`)
 //! - 管理员注册用户 (`auth_admin_register_user`)
 //! - 管理员续期用户账号 (`auth_admin_renew_user_account`)
 //! - 管理员列出用户 (`auth_admin_list_users`)
 //! - 管理员更新用户 (`auth_admin_update_user`)
-//! - 管理员删除用户 (`auth_admin_delete_user`)
+//! - 管理员删除用户 (`auth_admin_delete_user`，软删除)
+//! - 管理员恢复已删除用户 (`auth_admin_restore_user`)
+//! - 管理员清理已删除用户 (`auth_admin_purge_deleted_users`)
 //! - 管理员修改密码 (`auth_admin_change_user_password`)
+//! - 管理员批量账号操作 (`auth_admin_batch_*`：续期、启停用、增删角色、设置到期时间)
+//! - 委派管理员 (`auth_admin_set_user_delegation` / `auth_admin_remove_user_delegation` / `auth_admin_list_user_delegations`)
+//! - 用户设备范围 (`user_device_scope_get` / `user_device_scope_upsert` / `user_device_scope_check`)
 //!
 //! ==========================================================================================
 
+// 声明管理员操作审计模块
+mod admin_audit;
+// 声明并导出管理员批量操作服务模块
+pub mod admin_batch_services;
+// 声明并导出委派管理员服务模块
+pub mod admin_delegation_services;
 // 声明并导出管理员命令模块
 pub mod admin_commands;
 // 声明并导出管理员服务模块
 pub mod admin_services;
 // 声明并导出命令模块
 pub mod commands;
+// 声明并导出用户设备范围服务模块
+pub mod device_scope_services;
 // 声明并导出模型模块
 pub mod models;
 // 声明并导出 RBAC 模块
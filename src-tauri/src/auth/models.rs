@@ -16,23 +16,31 @@
 //! |------|--------|------|------|
 //! | 内部模型 | `UserProfile` | 业务逻辑内部使用 | 数据库 → services |
 //! | 内部模型 | `TokenPair` | 业务逻辑内部使用 | services → commands |
+//! | 内部模型 | `AccessTokenIdentity` | 访问令牌校验结果 | services 内部 |
 //! | 响应体 | `LoginData` | 登录成功返回 | commands → 前端 |
 //! | 响应体 | `RefreshTokenData` | 令牌刷新返回 | commands → 前端 |
 //! | 响应体 | `AdminRegisteredUserData` | 管理员注册用户返回 | commands → 前端 |
 //! | 响应体 | `AdminRenewUserAccountData` | 管理员续期用户返回 | commands → 前端 |
 //! | 响应体 | `AdminManagedUserData` | 管理员用户列表项 | commands → 前端 |
 //! | 响应体 | `AdminChangeUserPasswordData` | 管理员修改密码返回 | commands → 前端 |
-//! | 响应体 | `UserDeviceScopeReservedData` | 设备范围预留 | commands → 前端 |
+//! | 响应体 | `AdminBatchUserOperationData` | 管理员批量账号操作返回 | commands → 前端 |
+//! | 响应体 | `UserDeviceScopeData` | 用户设备范围返回 | commands → 前端 |
+//! | 响应体 | `UserDeviceScopeCheckData` | 设备访问判定返回 | commands → 前端 |
 //! | 请求体 | `LoginPayload` | 登录请求接收 | 前端 → commands |
 //! | 请求体 | `RefreshTokenPayload` | 令牌刷新请求接收 | 前端 → commands |
+//! | 请求体 | `ChangeOwnPasswordPayload` | 用户修改自己密码请求 | 前端 → commands |
 //! | 请求体 | `AdminRegisterUserPayload` | 管理员注册用户请求 | 前端 → commands |
 //! | 请求体 | `AdminRenewUserAccountPayload` | 管理员续期用户请求 | 前端 → commands |
 //! | 请求体 | `AdminListUsersPayload` | 管理员列出用户请求 | 前端 → commands |
 //! | 请求体 | `AdminUpdateUserPayload` | 管理员更新用户请求 | 前端 → commands |
 //! | 请求体 | `AdminDeleteUserPayload` | 管理员删除用户请求 | 前端 → commands |
+//! | 请求体 | `AdminRestoreUserPayload` | 管理员恢复已删除用户请求 | 前端 → commands |
+//! | 请求体 | `AdminPurgeDeletedUsersPayload` | 管理员清理已删除用户请求 | 前端 → commands |
 //! | 请求体 | `AdminChangeUserPasswordPayload` | 管理员修改密码请求 | 前端 → commands |
+//! | 请求体 | `AdminBatchRenewUsersPayload` 等 | 管理员批量账号操作请求 | 前端 → commands |
 //! | 请求体 | `UserDeviceScopeGetPayload` | 获取设备范围请求 | 前端 → commands |
 //! | 请求体 | `UserDeviceScopeUpsertPayload` | 更新设备范围请求 | 前端 → commands |
+//! | 请求体 | `UserDeviceScopeCheckPayload` | 设备访问判定请求 | 前端 → commands |
 //!
 //! 序列化约定：
 //!
@@ -111,6 +119,10 @@ pub struct UserProfile {
     /// 用于按钮级鉴权，如 `["permission:btn:add", "permission:btn:edit"]`
     /// 前端通过 `v-permission` 指令根据此列表控制按钮显示/隐藏
     pub permissions: Vec<String>,
+
+    /// 是否需要修改密码
+    /// 管理员创建账号或重置密码后为 true，用户修改自己的密码后清除
+    pub must_change_password: bool,
 }
 
 // ==========================================================================================
@@ -155,6 +167,22 @@ pub struct TokenPair {
     pub expires: u64,
 }
 
+// 访问令牌校验结果（内部模型）
+//
+// 说明：
+// 由 [`services::verify_access_token`](crate::auth::services::verify_access_token)
+// 与 [`services::verify_password_change_token`](crate::auth::services::verify_password_change_token) 返回，
+// 标识令牌所属用户以及令牌是否为仅允许修改密码的受限令牌（前者拒绝受限令牌）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessTokenIdentity {
+    /// 令牌主题（用户名）
+    pub username: String,
+
+    /// 是否为受限令牌
+    /// 受限令牌只能用于 `auth_change_own_password`
+    pub password_change_only: bool,
+}
+
 // ==========================================================================================
 // 响应体模型（向前端返回）
 // ==========================================================================================
@@ -177,7 +205,8 @@ pub struct TokenPair {
 //     "permissions": ["*:*:*"],
 //     "accessToken": "eyJ...",
 //     "refreshToken": "eyJ...",
-//     "expires": 1704069600000
+//     "expires": 1704069600000,
+//     "mustChangePassword": false
 //   }
 // }
 // ```
@@ -188,6 +217,7 @@ pub struct TokenPair {
 // 3. 保存 `refreshToken` 到本地存储（用于令牌刷新）
 // 4. 根据 `roles` 动态添加路由
 // 5. 根据 `permissions` 控制按钮显示
+// 6. `mustChangePassword` 为 true 时跳转到修改密码页，此时令牌仅可用于 `auth_change_own_password`
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginData {
@@ -221,6 +251,10 @@ pub struct LoginData {
     /// 令牌过期时间
     /// Unix 毫秒时间戳
     pub expires: u64,
+
+    /// 是否需要修改密码
+    /// 为 true 时签发的是仅允许修改密码的受限令牌
+    pub must_change_password: bool,
 }
 
 // 令牌刷新响应体
@@ -257,6 +291,10 @@ pub struct RefreshTokenData {
     /// 新令牌过期时间
     /// Unix 毫秒时间戳
     pub expires: u64,
+
+    /// 是否需要修改密码
+    /// 未完成改密前刷新得到的仍是受限令牌
+    pub must_change_password: bool,
 }
 
 // ==========================================================================================
@@ -302,6 +340,25 @@ pub struct RefreshTokenPayload {
     pub refresh_token: String,
 }
 
+// 用户修改自己密码请求体
+//
+// 说明：
+// 前端通过 `invoke("auth_change_own_password", { payload: { accessToken, oldPassword, newPassword } })` 传入。
+// 受限令牌（强制改密状态）与普通访问令牌均可调用。
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChangeOwnPasswordPayload {
+    /// 当前持有的访问令牌
+    pub access_token: String,
+
+    /// 当前密码
+    pub old_password: String,
+
+    /// 新密码
+    /// 不能为空，且不能与当前密码相同
+    pub new_password: String,
+}
+
 // ==========================================================================================
 // 管理员相关模型
 // ==========================================================================================
@@ -322,10 +379,16 @@ pub struct AdminRegisterUserPayload {
     pub phone: Option<String>,
     /// 角色列表
     pub roles: Vec<String>,
-    /// 账号期限类型：permanent 或 days
+    /// 账号期限类型：permanent、days 或 range
     pub account_term_type: String,
     /// 账号有效天数（当 account_term_type 为 days 时必填）
     pub account_valid_days: Option<i64>,
+    /// 账号生效时间戳（毫秒，可选；当 account_term_type 为 range 时必填）
+    pub account_start_at: Option<i64>,
+    /// 账号到期时间戳（毫秒，当 account_term_type 为 range 时必填）
+    pub account_expire_at: Option<i64>,
+    /// 所属组织 ID（可选；按组织授权的委派管理员缺省为其授权组织）
+    pub organization_id: Option<i64>,
 }
 
 // 管理员注册用户响应体
@@ -344,6 +407,8 @@ pub struct AdminRegisteredUserData {
     pub account_is_permanent: bool,
     /// 过期时间戳（毫秒）
     pub account_expire_at: Option<i64>,
+    /// 生效时间戳（毫秒），为空表示立即生效
+    pub account_start_at: Option<i64>,
 }
 
 // 管理员续期用户账号请求体
@@ -356,7 +421,7 @@ pub struct AdminRenewUserAccountPayload {
     pub user_id: i64,
     /// 续期模式：permanent 或 days
     pub renew_mode: String,
-    /// 续期天数（当 renew_mode 为 days 时必填）
+    /// 续期天数（当 renew_mode 为 days 时必填；账号未过期时从当前到期时间顺延）
     pub renew_days: Option<i64>,
 }
 
@@ -380,9 +445,14 @@ pub struct AdminRenewUserAccountData {
 pub struct AdminListUsersPayload {
     /// 操作员用户名
     pub operator_username: String,
+    /// 是否包含已软删除的用户（默认不包含）
+    pub include_deleted: bool,
+    /// 仅列出该组织及其下级组织的成员（可选）
+    pub organization_id: Option<i64>,
 }
 
 // 管理员管理的用户数据
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminManagedUserData {
@@ -410,6 +480,18 @@ pub struct AdminManagedUserData {
     pub updated_at: Option<i64>,
     /// 创建者
     pub created_by: Option<String>,
+    /// 软删除时间戳（毫秒），未删除为 null
+    pub deleted_at: Option<i64>,
+    /// 软删除操作人
+    pub deleted_by: Option<String>,
+    /// 生效时间戳（毫秒），为空表示立即生效
+    pub account_start_at: Option<i64>,
+    /// 是否待生效（生效时间到达后自动激活）
+    pub account_activation_pending: bool,
+    /// 是否要求下次登录修改密码
+    pub must_change_password: bool,
+    /// 所属组织 ID，未归属为 null
+    pub organization_id: Option<i64>,
 }
 
 // 管理员更新用户请求体
@@ -430,10 +512,14 @@ pub struct AdminUpdateUserPayload {
     pub roles: Vec<String>,
     /// 是否激活
     pub is_active: bool,
-    /// 账号期限类型
+    /// 账号期限类型：permanent、days 或 range
     pub account_term_type: String,
     /// 账号有效天数
     pub account_valid_days: Option<i64>,
+    /// 账号生效时间戳（毫秒，可选；当 account_term_type 为 range 时必填）
+    pub account_start_at: Option<i64>,
+    /// 账号到期时间戳（毫秒，当 account_term_type 为 range 时必填）
+    pub account_expire_at: Option<i64>,
 }
 
 // 管理员删除用户请求体
@@ -446,6 +532,36 @@ pub struct AdminDeleteUserPayload {
     pub user_id: i64,
 }
 
+// 管理员恢复已删除用户请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminRestoreUserPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 用户 ID
+    pub user_id: i64,
+}
+
+// 管理员清理已删除用户请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminPurgeDeletedUsersPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 保留天数（删除时间超过该天数的用户将被物理清理，缺省使用系统默认值）
+    pub retention_days: Option<i64>,
+}
+
+// 管理员清理已删除用户响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminPurgeDeletedUsersData {
+    /// 本次清理的用户数量
+    pub purged_count: usize,
+    /// 清理截止时间戳（毫秒），删除时间早于等于该值的用户被清理
+    pub deleted_before: i64,
+}
+
 // 管理员修改用户密码请求体
 #[derive(Debug, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -466,24 +582,180 @@ pub struct AdminChangeUserPasswordData {
     pub user_id: i64,
     /// 用户名
     pub username: String,
+    /// 是否要求该用户下次登录修改密码（重置本人密码时为 false）
+    pub must_change_password: bool,
+}
+
+// ==========================================================================================
+// 批量账号操作相关模型
+// ==========================================================================================
+
+// 管理员批量续期用户账号请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminBatchRenewUsersPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 目标用户 ID 列表
+    pub user_ids: Vec<i64>,
+    /// 续期模式：permanent 或 days
+    pub renew_mode: String,
+    /// 续期天数（当 renew_mode 为 days 时必填；账号未过期时从当前到期时间顺延）
+    pub renew_days: Option<i64>,
+    /// 是否尽力而为模式（默认 false：全部成功或全部回滚）
+    pub best_effort: bool,
+}
+
+// 管理员批量启用/停用用户请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminBatchSetUsersActivePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 目标用户 ID 列表
+    pub user_ids: Vec<i64>,
+    /// 目标激活状态
+    pub is_active: bool,
+    /// 是否尽力而为模式
+    pub best_effort: bool,
+}
+
+// 管理员批量增加/移除用户角色请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminBatchUserRolesPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 目标用户 ID 列表
+    pub user_ids: Vec<i64>,
+    /// 要增加或移除的角色列表
+    pub roles: Vec<String>,
+    /// 是否尽力而为模式
+    pub best_effort: bool,
+}
+
+// 管理员批量设置账号到期时间请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminBatchSetUsersExpiryPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 目标用户 ID 列表
+    pub user_ids: Vec<i64>,
+    /// 到期时间戳（毫秒），为空表示改为永久账号
+    pub account_expire_at: Option<i64>,
+    /// 是否尽力而为模式
+    pub best_effort: bool,
+}
+
+// 批量操作中单个用户的执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminBatchUserResultItem {
+    /// 用户 ID
+    pub user_id: i64,
+    /// 执行状态：succeeded / skipped / failed
+    pub status: String,
+    /// 跳过或失败原因
+    pub message: Option<String>,
+}
+
+// 管理员批量账号操作响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminBatchUserOperationData {
+    /// 是否为尽力而为模式
+    pub best_effort: bool,
+    /// 成功数量
+    pub succeeded_count: usize,
+    /// 跳过数量（受保护的管理员账号）
+    pub skipped_count: usize,
+    /// 失败数量（仅尽力而为模式下可能大于 0）
+    pub failed_count: usize,
+    /// 按请求顺序排列的逐个结果
+    pub results: Vec<AdminBatchUserResultItem>,
+}
+
+// ==========================================================================================
+// 委派管理员相关模型
+// ==========================================================================================
+
+// 设置委派管理员请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminSetUserDelegationPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 委派人用户 ID
+    pub user_id: i64,
+    /// 可管理的组织子树根节点（为空表示不按组织授权）
+    pub organization_id: Option<i64>,
+    /// 是否可管理本人创建的用户
+    pub manage_created_users: bool,
+    /// 可分配的角色列表
+    pub assignable_roles: Vec<String>,
+}
+
+// 撤销委派管理员请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminRemoveUserDelegationPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 委派人用户 ID
+    pub user_id: i64,
+}
+
+// 查询委派管理员请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdminListUserDelegationsPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 委派管理员响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminUserDelegationData {
+    /// 委派人用户 ID
+    pub user_id: i64,
+    /// 委派人用户名
+    pub username: String,
+    /// 可管理的组织子树根节点
+    pub organization_id: Option<i64>,
+    /// 是否可管理本人创建的用户
+    pub manage_created_users: bool,
+    /// 可分配的角色列表
+    pub assignable_roles: Vec<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+    /// 授权人用户名
+    pub created_by: String,
 }
 
 // ==========================================================================================
-// 设备范围相关模型（预留接口）
+// 设备范围相关模型
 // ==========================================================================================
 
 // 获取用户设备范围请求体
 #[derive(Debug, Deserialize, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub struct UserDeviceScopeGetPayload {
+    /// 操作员用户名
+    pub operator_username: String,
     /// 用户 ID
     pub user_id: i64,
 }
 
-// 更新用户设备范围请求体
+// 更新用户设备范围请求体（整体替换）
 #[derive(Debug, Deserialize, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub struct UserDeviceScopeUpsertPayload {
+    /// 操作员用户名
+    pub operator_username: String,
     /// 用户 ID
     pub user_id: i64,
     /// 是否可访问所有区域
@@ -500,6 +772,18 @@ pub struct UserDeviceScopeUpsertPayload {
     pub devices: Vec<String>,
 }
 
+// 设备访问判定请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserDeviceScopeCheckPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 用户 ID
+    pub user_id: i64,
+    /// 设备标识
+    pub device_id: String,
+}
+
 // 用户设备范围快照
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -518,14 +802,30 @@ pub struct UserDeviceScopeSnapshot {
     pub devices: Vec<String>,
 }
 
-// 用户设备范围预留数据
+// 用户设备范围数据
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct UserDeviceScopeReservedData {
-    /// 是否已实现
-    pub implemented: bool,
-    /// 消息
-    pub message: String,
-    /// 设备范围快照
+pub struct UserDeviceScopeData {
+    /// 用户 ID
+    pub user_id: i64,
+    /// 设备范围快照（未配置时全部为空）
     pub scope: UserDeviceScopeSnapshot,
+    /// 更新时间戳（毫秒，未配置时为空）
+    pub updated_at: Option<i64>,
+    /// 最后修改人用户名（未配置时为空）
+    pub updated_by: Option<String>,
+}
+
+// 设备访问判定结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDeviceScopeCheckData {
+    /// 用户 ID
+    pub user_id: i64,
+    /// 设备标识
+    pub device_id: String,
+    /// 是否可访问
+    pub allowed: bool,
+    /// 授权来源（role / allDevices / device / allAreas / area / allFloors / floor，不可访问时为空）
+    pub matched_by: Option<String>,
 }
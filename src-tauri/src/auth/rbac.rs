@@ -1,4 +1,6 @@
-use casbin::{CoreApi, DefaultModel, Enforcer};
+use std::collections::HashSet;
+
+use casbin::{CoreApi, DefaultModel, Enforcer, MgmtApi};
 use sqlx_adapter::SqlxAdapter;
 
 use crate::core::error::AppError;
@@ -9,6 +11,9 @@ pub const RESOURCE_USER: &str = "user";
 pub const RESOURCE_DEVICE: &str = "device";
 pub const RESOURCE_CONTROL: &str = "control";
 pub const RESOURCE_DASHBOARD: &str = "dashboard";
+pub const RESOURCE_AUDIT: &str = "audit";
+pub const RESOURCE_ORGANIZATION: &str = "organization";
+pub const RESOURCE_LOCATION: &str = "location";
 
 pub const ACTION_MANAGE: &str = "manage";
 pub const ACTION_CREATE: &str = "create";
@@ -63,6 +68,26 @@ pub fn ensure_user_allowed(
     }
 }
 
+pub fn find_roles_exceeding(
+    candidate_roles: &[String],
+    granted_roles: &[String],
+) -> Result<Vec<String>, AppError> {
+    let policies = db::block_on(load_policies())?;
+    let permissions_of = |roles: &[String]| -> HashSet<(String, String)> {
+        policies
+            .iter()
+            .filter(|rule| rule.len() >= 3 && roles.contains(&rule[0]))
+            .map(|rule| (rule[1].clone(), rule[2].clone()))
+            .collect()
+    };
+    let granted = permissions_of(granted_roles);
+    Ok(candidate_roles
+        .iter()
+        .filter(|role| !permissions_of(std::slice::from_ref(role)).is_subset(&granted))
+        .cloned()
+        .collect())
+}
+
 async fn build_enforcer() -> Result<Enforcer, AppError> {
     let model = DefaultModel::from_str(RBAC_MODEL_CONF)
         .await
@@ -75,6 +100,10 @@ async fn build_enforcer() -> Result<Enforcer, AppError> {
         .map_err(|err| AppError::Database(format!("initialize rbac enforcer failed: {err}")))
 }
 
+async fn load_policies() -> Result<Vec<Vec<String>>, AppError> {
+    Ok(build_enforcer().await?.get_policy())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Once;
@@ -25,11 +25,18 @@
 //! | access_token | 2 小时 | API 请求认证 |
 //! | refresh_token | 7 天 | 令牌刷新 |
 //!
+//! 令牌作用域：
+//! - 常规令牌不携带 `scope` 字段，可访问全部业务命令
+//! - 需要强制改密的账号登录后签发 `scope = "password_change"` 的受限令牌，
+//!   仅允许调用 `auth_change_own_password`；`verify_access_token` 统一拒绝受限令牌，
+//!   只有改密流程通过 `verify_password_change_token` 接受
+//!
 //! JWT 载荷结构：
 //! ```json
 //! {
 //!   "sub": "admin",
 //!   "token_type": "access",
+//!   "scope": "password_change",
 //!   "iat": 1704067200,
 //!   "exp": 1704074400
 //! }
@@ -71,11 +78,13 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 // 引入管理员服务模块，用于验证用户账号状态
 use crate::auth::admin_services;
 // 引入鉴权模块的数据模型
-use crate::auth::models::{LoginData, TokenPair, UserProfile};
+use crate::auth::models::{
+    AccessTokenIdentity, ChangeOwnPasswordPayload, LoginData, TokenPair, UserProfile,
+};
 // 引入核心错误处理模块
 use crate::core::error::AppError;
-// 引入鉴权数据访问层
-use crate::db::auth_repository;
+// 引入鉴权数据访问层与账号管理数据访问层
+use crate::db::{admin_repository, auth_repository};
 
 // ==========================================================================================
 // JWT 配置常量
@@ -95,6 +104,12 @@ const ACCESS_TOKEN_TYPE: &str = "access";
 // 刷新令牌的类型标识
 const REFRESH_TOKEN_TYPE: &str = "refresh";
 
+// 常规令牌的作用域标识（空字符串，序列化时省略）
+const FULL_TOKEN_SCOPE: &str = "";
+
+// 受限令牌的作用域标识：仅允许修改本人密码
+const PASSWORD_CHANGE_TOKEN_SCOPE: &str = "password_change";
+
 // ==========================================================================================
 // JWT 声明结构体
 // ==========================================================================================
@@ -107,6 +122,9 @@ struct JwtClaims {
     sub: String,
     // token_type: 令牌类型，"access" 或 "refresh"
     token_type: String,
+    // scope: 令牌作用域，常规令牌为空；旧令牌缺省该字段时按常规令牌处理
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    scope: String,
     // iat: 签发时间（Unix 时间戳，秒）
     iat: u64,
     // exp: 过期时间（Unix 时间戳，秒）
@@ -210,6 +228,7 @@ fn default_validation() -> Validation {
 // 参数：
 // - subject: 令牌主题，通常为用户名
 // - token_type: 令牌类型，"access" 或 "refresh"
+// - scope: 令牌作用域，常规令牌为空字符串
 // - issued_at: 签发时间（Unix 时间戳，秒）
 // - ttl: 有效期时长（秒）
 //
@@ -219,12 +238,20 @@ fn default_validation() -> Validation {
 // 注意：
 // 使用 saturating_add 防止整数溢出
 #[must_use]
-fn build_claims(subject: &str, token_type: &'static str, issued_at: u64, ttl: u64) -> JwtClaims {
+fn build_claims(
+    subject: &str,
+    token_type: &'static str,
+    scope: &'static str,
+    issued_at: u64,
+    ttl: u64,
+) -> JwtClaims {
     JwtClaims {
         // 将主题转换为字符串
         sub: subject.to_string(),
         // 将令牌类型转换为字符串
         token_type: token_type.to_string(),
+        // 将作用域转换为字符串
+        scope: scope.to_string(),
         // 设置签发时间
         iat: issued_at,
         // 计算过期时间，使用 saturating_add 防止溢出
@@ -280,6 +307,27 @@ fn encode_claims(claims: &JwtClaims) -> String {
 // - refresh_token: 7 天
 #[must_use]
 pub fn mint_token_pair(subject: &str) -> TokenPair {
+    mint_scoped_token_pair(subject, FULL_TOKEN_SCOPE)
+}
+
+// 生成仅限修改密码的受限令牌对
+//
+// 功能：
+// 为被要求强制改密的用户生成令牌对，令牌作用域为 "password_change"
+//
+// 参数：
+// - subject: 用户主题（用户名）
+//
+// 返回值：
+// 返回受限的 TokenPair，有效期与常规令牌一致
+#[must_use]
+pub fn mint_password_change_token_pair(subject: &str) -> TokenPair {
+    mint_scoped_token_pair(subject, PASSWORD_CHANGE_TOKEN_SCOPE)
+}
+
+// 按指定作用域生成令牌对
+#[must_use]
+fn mint_scoped_token_pair(subject: &str, scope: &'static str) -> TokenPair {
     // 获取当前时间（秒）
     let issued_at = now_secs();
 
@@ -287,6 +335,7 @@ pub fn mint_token_pair(subject: &str) -> TokenPair {
     let access_claims = build_claims(
         subject,
         ACCESS_TOKEN_TYPE,
+        scope,
         issued_at,
         ACCESS_TOKEN_LIFETIME_SECONDS,
     );
@@ -295,6 +344,7 @@ pub fn mint_token_pair(subject: &str) -> TokenPair {
     let refresh_claims = build_claims(
         subject,
         REFRESH_TOKEN_TYPE,
+        scope,
         issued_at,
         REFRESH_TOKEN_LIFETIME_SECONDS,
     );
@@ -359,6 +409,67 @@ pub fn verify_refresh_token(refresh_token: &str) -> Result<String, AppError> {
     Ok(claims.sub)
 }
 
+// 验证访问令牌
+//
+// 功能：
+// 验证访问令牌的签名、过期时间和令牌类型，并拒绝仅限改密的受限令牌；
+// 除修改本人密码外的所有命令都应通过此函数校验令牌
+//
+// 参数：
+// - access_token: 待验证的访问令牌字符串
+//
+// 返回值：
+// - 成功：返回令牌中的用户名（password_change_only 恒为 false）
+// - 失败：返回 AppError 验证错误
+//
+// 错误情况：
+// - 令牌格式非法、签名无效或已过期
+// - 令牌类型不是 "access"
+// - 主题为空
+// - 令牌为受限令牌 → "password change required"
+pub fn verify_access_token(access_token: &str) -> Result<AccessTokenIdentity, AppError> {
+    let identity = decode_access_token(access_token)?;
+    if identity.password_change_only {
+        return Err(AppError::Validation("password change required".to_string()));
+    }
+    Ok(identity)
+}
+
+// 验证修改本人密码使用的访问令牌
+//
+// 功能：
+// 与 verify_access_token 相同的校验，但同时接受常规令牌与受限令牌，仅供改密流程使用
+//
+// 返回值：
+// - 成功：返回令牌中的用户名及是否为仅限改密的受限令牌
+// - 失败：返回 AppError 验证错误
+pub fn verify_password_change_token(access_token: &str) -> Result<AccessTokenIdentity, AppError> {
+    decode_access_token(access_token)
+}
+
+// 解码访问令牌：校验签名、过期时间、令牌类型与主题，并解析作用域
+fn decode_access_token(access_token: &str) -> Result<AccessTokenIdentity, AppError> {
+    // 使用密钥解码令牌并验证
+    let decoded = decode::<JwtClaims>(
+        access_token,
+        &DecodingKey::from_secret(jwt_secret().as_bytes()),
+        &default_validation(),
+    )
+    .map_err(|_| AppError::Validation("invalid accessToken".to_string()))?;
+
+    let claims = decoded.claims;
+
+    // 验证令牌类型和主题有效性
+    if claims.token_type != ACCESS_TOKEN_TYPE || claims.sub.trim().is_empty() {
+        return Err(AppError::Validation("invalid accessToken".to_string()));
+    }
+
+    Ok(AccessTokenIdentity {
+        username: claims.sub,
+        password_change_only: claims.scope == PASSWORD_CHANGE_TOKEN_SCOPE,
+    })
+}
+
 // ==========================================================================================
 // 用户档案解析
 // ==========================================================================================
@@ -419,15 +530,19 @@ pub fn resolve_user_profile(username: &str, password: &str) -> Result<UserProfil
 // 返回包含用户信息和 JWT 令牌对的 LoginData
 //
 // 执行流程：
-// 1. 为用户生成令牌对
+// 1. 为用户生成令牌对（需强制改密时生成受限令牌）
 // 2. 合并用户档案和令牌信息
 //
 // 注意：
 // 此函数会生成新的令牌，因此每次调用都会产生新的令牌
 #[must_use]
 pub fn build_login_data(profile: UserProfile) -> LoginData {
-    // 为用户生成令牌对
-    let token = mint_token_pair(&profile.username);
+    // 为用户生成令牌对，需强制改密的账号只获得受限令牌
+    let token = if profile.must_change_password {
+        mint_password_change_token_pair(&profile.username)
+    } else {
+        mint_token_pair(&profile.username)
+    };
 
     // 构建并返回登录数据
     LoginData {
@@ -447,7 +562,71 @@ pub fn build_login_data(profile: UserProfile) -> LoginData {
         refresh_token: token.refresh_token,
         // 过期时间（毫秒时间戳）
         expires: token.expires,
+        // 是否需要强制修改密码
+        must_change_password: profile.must_change_password,
+    }
+}
+
+// ==========================================================================================
+// 修改本人密码
+// ==========================================================================================
+
+// 修改当前登录用户的密码
+//
+// 功能：
+// 凭访问令牌（常规或受限令牌均可）校验旧密码后写入新密码，
+// 同时清除强制改密标记，并返回携带常规令牌的新登录数据
+//
+// 参数：
+// - payload: 改密请求，包含访问令牌、旧密码和新密码
+// - now_millis: 当前毫秒时间戳
+//
+// 返回值：
+// - 成功：返回新的 LoginData（must_change_password 为 false）
+// - 失败：返回 AppError 错误
+//
+// 错误情况：
+// - 访问令牌无效
+// - 新旧密码为空，或新密码与旧密码相同
+// - 旧密码错误
+// - 账号不可用（禁用、过期或已删除）
+pub fn change_own_password(
+    payload: ChangeOwnPasswordPayload,
+    now_millis: u64,
+) -> Result<LoginData, AppError> {
+    let identity = verify_password_change_token(&payload.access_token)?;
+
+    // 校验新旧密码（密码按原样比较与保存，不去除首尾空白）
+    let new_password = payload.new_password;
+    if payload.old_password.trim().is_empty() {
+        return Err(AppError::Validation("oldPassword is required".to_string()));
+    }
+    if new_password.trim().is_empty() {
+        return Err(AppError::Validation("newPassword is required".to_string()));
+    }
+    if new_password == payload.old_password {
+        return Err(AppError::Validation(
+            "newPassword must differ from oldPassword".to_string(),
+        ));
     }
+
+    // 校验旧密码，再确认账号仍然可用
+    auth_repository::find_user_profile(&identity.username, &payload.old_password)?
+        .ok_or_else(|| AppError::Validation("invalid oldPassword".to_string()))?;
+    admin_services::ensure_user_available_with_message(
+        &identity.username,
+        "invalid accessToken",
+        now_millis,
+    )?;
+
+    let now = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    if !admin_repository::update_own_password(&identity.username, &new_password, now)? {
+        return Err(AppError::Validation("invalid accessToken".to_string()));
+    }
+
+    // 以新密码重新解析档案，签发常规令牌
+    resolve_user_profile(&identity.username, &new_password).map(build_login_data)
 }
 
 // ==========================================================================================
@@ -23,6 +23,9 @@ pub enum AppError {
     /// 数据库访问或执行 SQL 失败。
     #[error("database error: {0}")]
     Database(String),
+    /// Modbus 通信失败（连接、超时、异常响应等）。
+    #[error("modbus error: {0}")]
+    Modbus(String),
 }
 
 /// 为 `AppError` 手动实现 `Serialize`。
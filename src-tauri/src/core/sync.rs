@@ -0,0 +1,11 @@
+//! # 同步原语工具模块
+//!
+//! 提供各领域共用的互斥锁辅助函数。后台线程（采集引擎、遥测推送、模拟器等）持锁期间 panic 时
+//! 锁会被标记为中毒，这里统一选择继续使用内部数据，避免一次 panic 让整个运行时状态不可用。
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// 获取锁（锁中毒时继续使用内部数据）
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -15,12 +16,35 @@ use crate::core::error::{AppError, AppResult};
 static TRACING_GUARDS: OnceLock<TracingGuards> = OnceLock::new();
 static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+thread_local! {
+    static CURRENT_REQUEST_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub struct TraceContext {
     pub request_id: Option<String>,
 }
 
+struct RequestIdScope {
+    previous: Option<String>,
+}
+
+impl RequestIdScope {
+    fn enter(request_id: &str) -> Self {
+        let previous =
+            CURRENT_REQUEST_ID.with(|current| current.borrow_mut().replace(request_id.to_string()));
+        Self { previous }
+    }
+}
+
+impl Drop for RequestIdScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_REQUEST_ID.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
 struct TracingGuards {
     _console: WorkerGuard,
     _file: WorkerGuard,
@@ -97,12 +121,13 @@ pub fn execute_traced_command<T>(
     let request_id = resolve_request_id(trace.as_ref());
     let span = tracing::info_span!("tauri_request", command, request_id = %request_id);
     let _span_guard = span.enter();
+    let _request_scope = RequestIdScope::enter(&request_id);
 
     tracing::info!("request started");
     let result = handler();
     match &result {
         Ok(_) => tracing::info!("request completed"),
-        Err(AppError::Validation(message)) => {
+        Err(AppError::Validation(message) | AppError::Modbus(message)) => {
             tracing::warn!(error = %message, "request failed");
         }
         Err(AppError::Database(message)) => {
@@ -113,6 +138,14 @@ pub fn execute_traced_command<T>(
     result
 }
 
+/// Returns the request id of the traced command running on the current thread.
+///
+/// Domain services use it to correlate persisted records (e.g. audit events)
+/// with the frontend `TraceContext` without threading it through every signature.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.with(|current| current.borrow().clone())
+}
+
 fn resolve_request_id(trace: Option<&TraceContext>) -> String {
     if let Some(request_id) = trace
         .and_then(|context| context.request_id.as_ref())
@@ -143,7 +176,11 @@ fn resolve_log_directory(directory: &str) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::{TraceContext, parse_env_filter, resolve_request_id};
+    use super::{
+        TraceContext, current_request_id, execute_traced_command, parse_env_filter,
+        resolve_request_id,
+    };
+    use crate::core::error::ApiResponse;
 
     #[test]
     fn accepts_valid_env_filter_level() {
@@ -170,4 +207,18 @@ mod tests {
         let request_id = resolve_request_id(None);
         assert!(!request_id.is_empty());
     }
+
+    #[test]
+    fn exposes_request_id_only_while_command_runs() {
+        let trace = TraceContext {
+            request_id: Some("fe-scope-001".to_string()),
+        };
+        let seen = execute_traced_command("scope_test", Some(trace), || {
+            Ok(ApiResponse::ok(current_request_id()))
+        })
+        .expect("command succeeds");
+
+        assert_eq!(seen.data.as_deref(), Some("fe-scope-001"));
+        assert!(current_request_id().is_none());
+    }
 }
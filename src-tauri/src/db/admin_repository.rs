@@ -1,8 +1,8 @@
 //! 管理员数据仓储模块
 //! 
 //! 本模块提供管理员功能相关的数据访问接口：
-//! - 用户创建、更新、删除
-//! - 用户账号续期
+//! - 用户创建、更新、软删除、恢复与清理
+//! - 用户账号续期与生效时间
 //! - 用户列表查询
 //! - 管理员权限验证
 //! 
@@ -14,6 +14,9 @@ use std::collections::HashSet;
 // 引入应用错误类型
 use crate::core::error::AppError;
 
+// 一天对应的毫秒数
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
 /// SeaORM 用户管理模块（使用 SeaORM ORM 框架）
 #[path = "admin_repository/seaorm_users.rs"]
 mod seaorm_users;
@@ -22,6 +25,14 @@ mod seaorm_users;
 #[path = "admin_repository/sqlx_reports.rs"]
 mod sqlx_reports;
 
+/// SQLx 委派管理员模块（委派范围与可分配角色）
+#[path = "admin_repository/sqlx_delegations.rs"]
+mod sqlx_delegations;
+
+/// SQLx 用户设备范围模块（通配标记与区域、楼层、设备明细）
+#[path = "admin_repository/sqlx_device_scopes.rs"]
+mod sqlx_device_scopes;
+
 /// 新用户输入数据结构
 /// 
 /// 用于创建新用户时的输入参数
@@ -34,6 +45,9 @@ pub struct NewUserInput {
     pub account_is_permanent: bool, // 是否永久账号
     pub account_valid_days: Option<i64>, // 有效天数（非永久账号）
     pub account_expire_at: Option<i64>,  // 过期时间戳（毫秒）
+    pub account_start_at: Option<i64>,   // 生效时间戳（毫秒，None 表示立即生效）
+    pub must_change_password: bool, // 是否要求首次登录修改密码
+    pub organization_id: Option<i64>, // 所属组织 ID（None 表示未归属）
     pub created_by: String,        // 创建者用户名
     pub now_millis: i64,          // 当前时间戳（毫秒）
 }
@@ -49,21 +63,27 @@ pub struct RegisteredUserRecord {
     pub is_active: bool,           // 是否激活
     pub account_is_permanent: bool, // 是否永久账号
     pub account_expire_at: Option<i64>, // 过期时间戳
+    pub account_start_at: Option<i64>, // 生效时间戳
 }
 
 /// 用户登录状态数据结构
 /// 
 /// 用于验证用户登录资格
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct UserLoginState {
     pub is_active: bool,           // 是否激活
     pub account_is_permanent: bool, // 是否永久账号
     pub account_expire_at: Option<i64>, // 过期时间戳
+    pub account_start_at: Option<i64>, // 生效时间戳
+    pub account_activation_pending: bool, // 是否待生效激活
+    pub must_change_password: bool, // 是否需要修改密码
 }
 
 /// 可管理的用户记录数据结构
 /// 
 /// 包含完整的用户信息，用于列表展示和详情查看
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct ManagedUserRecord {
     pub user_id: i64,              // 用户 ID
@@ -78,6 +98,12 @@ pub struct ManagedUserRecord {
     pub created_at: Option<i64>,   // 创建时间戳
     pub updated_at: Option<i64>,   // 更新时间戳
     pub created_by: Option<String>, // 创建者
+    pub deleted_at: Option<i64>,   // 软删除时间戳（None 表示未删除）
+    pub deleted_by: Option<String>, // 软删除操作人
+    pub account_start_at: Option<i64>, // 生效时间戳
+    pub account_activation_pending: bool, // 是否待生效激活（生效时间到达后自动激活）
+    pub must_change_password: bool, // 是否需要修改密码
+    pub organization_id: Option<i64>, // 所属组织 ID
 }
 
 /// 用户更新输入数据结构
@@ -93,9 +119,97 @@ pub struct UpdateUserInput {
     pub account_is_permanent: bool, // 是否永久账号
     pub account_valid_days: Option<i64>, // 有效天数
     pub account_expire_at: Option<i64>, // 过期时间戳
+    pub account_start_at: Option<i64>, // 生效时间戳
+    pub now_millis: i64,          // 当前时间戳
+}
+
+/// 批量用户变更类型
+/// 
+/// 同一批次内对每个目标用户执行相同的变更
+pub enum BatchUserChange {
+    /// 续期（同时激活账号，过期时间按用户逐个计算）
+    Renew {
+        account_is_permanent: bool,        // 是否永久账号
+        account_valid_days: Option<i64>,   // 续期天数（永久账号为 None）
+    },
+    /// 启用或停用账号
+    SetActive(bool),
+    /// 增加角色（已拥有的角色忽略）
+    AddRoles(Vec<String>),
+    /// 移除角色（用户至少保留一个角色）
+    RemoveRoles(Vec<String>),
+    /// 设置到期时间（None 表示改为永久账号）
+    SetExpiry(Option<i64>),
+}
+
+/// 委派管理员记录数据结构
+/// 
+/// 委派人可管理的范围为组织子树与本人创建的用户两者之并
+#[derive(Debug, Clone)]
+pub struct UserDelegationRecord {
+    pub user_id: i64,              // 委派人用户 ID
+    pub username: String,           // 委派人用户名
+    pub organization_id: Option<i64>, // 可管理的组织子树根节点
+    pub manage_created_users: bool, // 是否可管理本人创建的用户
+    pub assignable_roles: Vec<String>, // 可分配的角色（排序）
+    pub created_at: i64,           // 创建时间戳
+    pub updated_at: i64,           // 更新时间戳
+    pub created_by: String,        // 授权人用户名
+}
+
+/// 委派管理员写入数据结构
+pub struct UserDelegationInput {
+    pub user_id: i64,              // 委派人用户 ID
+    pub organization_id: Option<i64>, // 可管理的组织子树根节点
+    pub manage_created_users: bool, // 是否可管理本人创建的用户
+    pub assignable_roles: Vec<String>, // 可分配的角色（已规范化）
+    pub created_by: String,        // 授权人用户名
     pub now_millis: i64,          // 当前时间戳
 }
 
+/// 用户设备范围记录数据结构
+/// 
+/// 通配标记与显式明细共同决定用户可访问的设备
+#[derive(Debug, Clone)]
+pub struct UserDeviceScopeRecord {
+    pub user_id: i64,              // 用户 ID
+    pub all_areas: bool,           // 是否可访问所有区域内的设备
+    pub all_floors: bool,          // 是否可访问所有楼层内的设备
+    pub all_devices: bool,         // 是否可访问所有设备
+    pub areas: Vec<String>,        // 授权的区域编码（排序）
+    pub floors: Vec<String>,       // 授权的楼层编码（排序）
+    pub devices: Vec<String>,      // 授权的设备标识（排序）
+    pub updated_at: i64,           // 更新时间戳
+    pub updated_by: String,        // 最后修改人用户名
+}
+
+/// 用户设备范围写入数据结构
+pub struct UserDeviceScopeInput {
+    pub user_id: i64,              // 用户 ID
+    pub all_areas: bool,           // 是否可访问所有区域内的设备
+    pub all_floors: bool,          // 是否可访问所有楼层内的设备
+    pub all_devices: bool,         // 是否可访问所有设备
+    pub areas: Vec<String>,        // 授权的区域编码（已去重）
+    pub floors: Vec<String>,       // 授权的楼层编码（已去重）
+    pub devices: Vec<String>,      // 授权的设备标识（已去重）
+    pub updated_by: String,        // 修改人用户名
+    pub now_millis: i64,          // 当前时间戳
+}
+
+/// 设备位置记录数据结构
+#[derive(Debug, Clone)]
+pub struct DeviceLocationRecord {
+    pub device_id: String,          // 设备标识
+    pub area_code: Option<String>,  // 所在区域编码（位置路径上的 area 节点）
+    pub floor_code: Option<String>, // 所在楼层编码（位置路径上的 floor 节点）
+}
+
+/// 批量变更中单个用户的执行结果
+pub struct BatchUserChangeOutcome {
+    pub user_id: i64, // 用户 ID
+    pub result: Result<(ManagedUserRecord, ManagedUserRecord), AppError>, // 成功时为 (变更前, 变更后)
+}
+
 /// 创建新用户
 /// 
 /// # 参数
@@ -110,11 +224,12 @@ pub fn create_user(input: NewUserInput) -> Result<RegisteredUserRecord, AppError
 
 /// 续期用户账号
 /// 
+/// 账号尚未过期时从当前过期时间顺延，否则从当前时间（或更晚的生效时间）起算
+/// 
 /// # 参数
 /// * `user_id` - 用户 ID
 /// * `account_is_permanent` - 是否永久账号
-/// * `account_valid_days` - 有效天数
-/// * `account_expire_at` - 过期时间戳
+/// * `account_valid_days` - 续期天数（永久账号为 None）
 /// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
@@ -124,16 +239,9 @@ pub fn renew_user_account(
     user_id: i64,
     account_is_permanent: bool,
     account_valid_days: Option<i64>,
-    account_expire_at: Option<i64>,
     now_millis: i64,
 ) -> Result<RegisteredUserRecord, AppError> {
-    seaorm_users::renew_user_account(
-        user_id,
-        account_is_permanent,
-        account_valid_days,
-        account_expire_at,
-        now_millis,
-    )
+    seaorm_users::renew_user_account(user_id, account_is_permanent, account_valid_days, now_millis)
 }
 
 /// 检查用户是否为管理员
@@ -191,12 +299,45 @@ pub fn deactivate_expired_users(now_millis: i64) -> Result<usize, AppError> {
     seaorm_users::deactivate_expired_users(now_millis)
 }
 
+/// 激活生效时间已到达的待生效用户
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 实际被激活返回 true（用户不处于待生效状态返回 false）
+pub fn activate_started_user_by_username(
+    username: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    seaorm_users::activate_started_user_by_username(username, now_millis)
+}
+
+/// 激活所有生效时间已到达且未过期的待生效用户
+/// 
+/// # 参数
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 成功激活的用户数量
+pub fn activate_started_users(now_millis: i64) -> Result<usize, AppError> {
+    seaorm_users::activate_started_users(now_millis)
+}
+
 /// 获取所有用户列表
 /// 
+/// # 参数
+/// * `include_deleted` - 是否包含已软删除的用户
+/// * `organization_id` - 仅返回该组织及其所有下级组织的成员（None 表示不过滤）
+/// 
 /// # 返回
 /// * 所有可管理的用户记录列表
-pub fn list_users() -> Result<Vec<ManagedUserRecord>, AppError> {
-    sqlx_reports::list_users()
+pub fn list_users(
+    include_deleted: bool,
+    organization_id: Option<i64>,
+) -> Result<Vec<ManagedUserRecord>, AppError> {
+    sqlx_reports::list_users(include_deleted, organization_id)
 }
 
 /// 更新用户信息
@@ -210,15 +351,40 @@ pub fn update_user(input: UpdateUserInput) -> Result<ManagedUserRecord, AppError
     seaorm_users::update_user(input)
 }
 
-/// 删除用户
+/// 软删除用户
 /// 
 /// # 参数
 /// * `user_id` - 用户 ID
+/// * `deleted_by` - 操作人用户名
+/// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
 /// * 删除成功返回 true
-pub fn delete_user(user_id: i64) -> Result<bool, AppError> {
-    seaorm_users::delete_user(user_id)
+pub fn delete_user(user_id: i64, deleted_by: &str, now_millis: i64) -> Result<bool, AppError> {
+    seaorm_users::soft_delete_user(user_id, deleted_by, now_millis)
+}
+
+/// 恢复已软删除的用户
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 恢复后的用户记录
+pub fn restore_user(user_id: i64, now_millis: i64) -> Result<ManagedUserRecord, AppError> {
+    seaorm_users::restore_user(user_id, now_millis)
+}
+
+/// 物理清理超过保留期的软删除用户
+/// 
+/// # 参数
+/// * `deleted_before` - 删除时间早于等于该时间戳的用户将被清理
+/// 
+/// # 返回
+/// * 清理的用户数量
+pub fn purge_deleted_users(deleted_before: i64) -> Result<usize, AppError> {
+    seaorm_users::purge_deleted_users(deleted_before)
 }
 
 /// 更新用户密码
@@ -226,6 +392,7 @@ pub fn delete_user(user_id: i64) -> Result<bool, AppError> {
 /// # 参数
 /// * `user_id` - 用户 ID
 /// * `password` - 新密码
+/// * `must_change_password` - 是否要求用户下次登录修改密码
 /// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
@@ -233,9 +400,27 @@ pub fn delete_user(user_id: i64) -> Result<bool, AppError> {
 pub fn update_user_password(
     user_id: i64,
     password: &str,
+    must_change_password: bool,
     now_millis: i64,
 ) -> Result<ManagedUserRecord, AppError> {
-    seaorm_users::update_user_password(user_id, password, now_millis)
+    seaorm_users::update_user_password(user_id, password, must_change_password, now_millis)
+}
+
+/// 用户修改自己的密码并清除强制改密标记
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// * `password` - 新密码
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 更新成功返回 true（用户不存在或已删除返回 false）
+pub fn update_own_password(
+    username: &str,
+    password: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    seaorm_users::update_own_password(username, password, now_millis)
 }
 
 /// 根据用户 ID 查询用户名
@@ -249,6 +434,216 @@ pub fn find_username_by_user_id(user_id: i64) -> Result<Option<String>, AppError
     seaorm_users::find_username_by_user_id(user_id)
 }
 
+/// 根据用户名查询用户 ID
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// 
+/// # 返回
+/// * 用户 ID（用户不存在或已软删除时为 None）
+pub fn find_user_id_by_username(username: &str) -> Result<Option<i64>, AppError> {
+    seaorm_users::find_user_id_by_username(username)
+}
+
+/// 在单个事务中对多个用户执行批量变更
+/// 
+/// # 参数
+/// * `user_ids` - 目标用户 ID 列表（调用方已去重并排除受保护账号）
+/// * `change` - 变更内容
+/// * `best_effort` - false 时任一用户失败即整体回滚；true 时逐个使用保存点，失败的用户单独回滚
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 按输入顺序排列的逐个结果（非尽力而为模式下全部成功）
+pub fn apply_batch_user_change(
+    user_ids: &[i64],
+    change: &BatchUserChange,
+    best_effort: bool,
+    now_millis: i64,
+) -> Result<Vec<BatchUserChangeOutcome>, AppError> {
+    seaorm_users::apply_batch_user_change(user_ids, change, best_effort, now_millis)
+}
+
+/// 按 ID 查询可管理的用户记录（包含已软删除的用户）
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// 
+/// # 返回
+/// * 用户记录（如果存在）
+pub fn find_managed_user(user_id: i64) -> Result<Option<ManagedUserRecord>, AppError> {
+    seaorm_users::find_managed_user(user_id)
+}
+
+/// 查询全部委派管理员
+/// 
+/// # 返回
+/// * 按委派人用户名排序的委派记录
+pub fn list_user_delegations() -> Result<Vec<UserDelegationRecord>, AppError> {
+    sqlx_delegations::list_user_delegations()
+}
+
+/// 按委派人用户 ID 查询委派记录
+/// 
+/// # 参数
+/// * `user_id` - 委派人用户 ID
+/// 
+/// # 返回
+/// * 委派记录（未委派时为 None）
+pub fn find_user_delegation(user_id: i64) -> Result<Option<UserDelegationRecord>, AppError> {
+    sqlx_delegations::find_user_delegation(user_id)
+}
+
+/// 按委派人用户名查询委派记录
+/// 
+/// # 参数
+/// * `username` - 委派人用户名
+/// 
+/// # 返回
+/// * 委派记录（未委派时为 None）
+pub fn find_user_delegation_by_username(
+    username: &str,
+) -> Result<Option<UserDelegationRecord>, AppError> {
+    sqlx_delegations::find_user_delegation_by_username(username)
+}
+
+/// 创建或替换委派记录
+/// 
+/// # 参数
+/// * `input` - 委派写入参数
+/// 
+/// # 返回
+/// * 写入后的委派记录
+pub fn upsert_user_delegation(
+    input: &UserDelegationInput,
+) -> Result<UserDelegationRecord, AppError> {
+    sqlx_delegations::upsert_user_delegation(input)
+}
+
+/// 删除委派记录
+/// 
+/// # 参数
+/// * `user_id` - 委派人用户 ID
+/// 
+/// # 返回
+/// * 是否删除成功（未委派时为 false）
+pub fn delete_user_delegation(user_id: i64) -> Result<bool, AppError> {
+    sqlx_delegations::delete_user_delegation(user_id)
+}
+
+/// 按用户 ID 查询设备范围
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// 
+/// # 返回
+/// * 设备范围记录（未配置时为 None）
+pub fn find_user_device_scope(user_id: i64) -> Result<Option<UserDeviceScopeRecord>, AppError> {
+    sqlx_device_scopes::find_user_device_scope(user_id)
+}
+
+/// 创建或整体替换用户设备范围
+/// 
+/// # 参数
+/// * `input` - 设备范围写入参数
+/// 
+/// # 返回
+/// * 写入后的设备范围记录
+/// * 引用的区域、楼层或设备不存在时返回 AppError::Validation
+pub fn replace_user_device_scope(
+    input: &UserDeviceScopeInput,
+) -> Result<UserDeviceScopeRecord, AppError> {
+    sqlx_device_scopes::replace_user_device_scope(input)
+}
+
+/// 查询设备所在的区域与楼层
+/// 
+/// # 参数
+/// * `device_id` - 设备标识
+/// 
+/// # 返回
+/// * 设备位置记录（设备不存在时为 None）
+pub fn find_device_location(device_id: &str) -> Result<Option<DeviceLocationRecord>, AppError> {
+    sqlx_device_scopes::find_device_location(device_id)
+}
+
+/// 查询设备放置到指定位置时所在的区域与楼层
+/// 
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `location_id` - 目标位置 ID（None 表示不设置位置）
+/// 
+/// # 返回
+/// * 以目标位置解析区域与楼层的设备位置记录
+pub fn find_device_placement(
+    device_id: &str,
+    location_id: Option<i64>,
+) -> Result<DeviceLocationRecord, AppError> {
+    sqlx_device_scopes::find_device_placement(device_id, location_id)
+}
+
+/// 查询用户设备范围明细覆盖的全部设备标识
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// 
+/// # 返回
+/// * 按设备标识排序的设备列表（不含通配 `all_devices` 的情况）
+pub fn find_scope_device_ids(user_id: i64) -> Result<Vec<String>, AppError> {
+    sqlx_device_scopes::find_scope_device_ids(user_id)
+}
+
+/// 根据生效时间计算账号激活状态
+/// 
+/// 生效时间晚于当前时间时，请求激活的账号先保持停用并标记为待生效
+/// 
+/// # 参数
+/// * `requested_active` - 期望的激活状态
+/// * `account_start_at` - 生效时间戳
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * (is_active, account_activation_pending) 列值
+pub(super) fn resolve_activation(
+    requested_active: bool,
+    account_start_at: Option<i64>,
+    now_millis: i64,
+) -> (i32, i32) {
+    if requested_active && account_start_at.is_some_and(|start_at| start_at > now_millis) {
+        return (0, 1);
+    }
+    (i32::from(requested_active), 0)
+}
+
+/// 计算续期后的过期时间
+/// 
+/// 起算点取当前时间、生效时间与未过期的当前过期时间三者中的最大值，
+/// 避免提前续期时损失剩余有效期
+/// 
+/// # 参数
+/// * `current_expire_at` - 当前过期时间戳（永久账号传 None）
+/// * `account_start_at` - 生效时间戳
+/// * `renew_days` - 续期天数
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 续期后的过期时间戳
+pub(super) fn extend_account_expiry(
+    current_expire_at: Option<i64>,
+    account_start_at: Option<i64>,
+    renew_days: i64,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    let base = [current_expire_at, account_start_at]
+        .into_iter()
+        .flatten()
+        .fold(now_millis, i64::max);
+    renew_days
+        .checked_mul(MILLIS_PER_DAY)
+        .and_then(|millis| base.checked_add(millis))
+        .ok_or_else(|| AppError::Validation("renewDays is too large".to_string()))
+}
+
 /// 规范化角色列表（去重、排序）
 /// 
 /// # 参数
@@ -322,6 +717,10 @@ pub(super) fn map_user_mutation_error(message: String) -> AppError {
     {
         return AppError::Validation("username already exists".to_string());
     }
+    // 检查是否为所属组织不存在错误
+    if lowered.contains("users_organization_id_fkey") {
+        return AppError::Validation("organization not found".to_string());
+    }
 
     AppError::Database(message)
 }
@@ -7,8 +7,8 @@
 use sea_orm::sea_query::Expr;
 // 引入 SeaORM 核心 trait
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
-    QueryFilter, QueryOrder, TransactionTrait,
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
 };
 
 // 引入应用错误类型
@@ -20,8 +20,9 @@ use crate::db::entities::{user_roles, users};
 
 // 引入父模块的数据结构
 use super::{
-    ManagedUserRecord, NewUserInput, RegisteredUserRecord, UpdateUserInput, UserLoginState,
-    map_user_mutation_error, normalize_unique_roles, trim_optional_phone,
+    BatchUserChange, BatchUserChangeOutcome, ManagedUserRecord, NewUserInput,
+    RegisteredUserRecord, UpdateUserInput, UserLoginState, extend_account_expiry,
+    map_user_mutation_error, normalize_unique_roles, resolve_activation, trim_optional_phone,
 };
 
 /// 创建新用户
@@ -49,17 +50,25 @@ pub(super) fn create_user(input: NewUserInput) -> Result<RegisteredUserRecord, A
         // 规范化角色列表（去重排序）
         let unique_roles = normalize_unique_roles(input.roles);
 
+        // 默认激活；生效时间未到时保持停用并标记待生效
+        let (is_active, activation_pending) =
+            resolve_activation(true, input.account_start_at, input.now_millis);
+
         // 构建用户 ActiveModel
         let user = users::ActiveModel {
             username: Set(input.username),
             password: Set(input.password),
             nickname: Set(input.nickname),
             avatar: Set(String::new()), // 默认空头像
-            is_active: Set(1),          // 默认激活
+            is_active: Set(is_active),
             phone: Set(normalized_phone),
             account_is_permanent: Set(i32::from(input.account_is_permanent)),
             account_valid_days: Set(input.account_valid_days),
             account_expire_at: Set(input.account_expire_at),
+            account_start_at: Set(input.account_start_at),
+            account_activation_pending: Set(activation_pending),
+            must_change_password: Set(i32::from(input.must_change_password)),
+            organization_id: Set(input.organization_id),
             created_at: Set(Some(input.now_millis)),
             updated_at: Set(Some(input.now_millis)),
             created_by: Set(Some(input.created_by)),
@@ -91,6 +100,7 @@ pub(super) fn create_user(input: NewUserInput) -> Result<RegisteredUserRecord, A
             is_active: inserted.is_active == 1,
             account_is_permanent: inserted.account_is_permanent == 1,
             account_expire_at: inserted.account_expire_at,
+            account_start_at: inserted.account_start_at,
         })
     })
 }
@@ -100,8 +110,7 @@ pub(super) fn create_user(input: NewUserInput) -> Result<RegisteredUserRecord, A
 /// # 参数
 /// * `user_id` - 用户 ID
 /// * `account_is_permanent` - 是否永久账号
-/// * `account_valid_days` - 有效天数
-/// * `account_expire_at` - 过期时间戳
+/// * `account_valid_days` - 续期天数
 /// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
@@ -110,25 +119,28 @@ pub(super) fn renew_user_account(
     user_id: i64,
     account_is_permanent: bool,
     account_valid_days: Option<i64>,
-    account_expire_at: Option<i64>,
     now_millis: i64,
 ) -> Result<RegisteredUserRecord, AppError> {
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
 
-        // 查询现有用户
+        // 查询现有用户（已软删除的用户视为不存在）
         let existing = users::Entity::find_by_id(user_id)
+            .filter(users::Column::DeletedAt.is_null())
             .one(&connection)
             .await
             .map_err(map_db_error)?
             .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
 
         // 构建更新模型
-        let mut active: users::ActiveModel = existing.into();
-        active.account_is_permanent = Set(i32::from(account_is_permanent));
-        active.account_valid_days = Set(account_valid_days);
-        active.account_expire_at = Set(account_expire_at);
-        active.is_active = Set(1); // 续期时激活账号
+        let mut active: users::ActiveModel = existing.clone().into();
+        apply_renewal(
+            &mut active,
+            &existing,
+            account_is_permanent,
+            account_valid_days,
+            now_millis,
+        )?;
         active.updated_at = Set(Some(now_millis));
         
         // 执行更新
@@ -153,9 +165,10 @@ pub(super) fn find_user_login_state(username: &str) -> Result<Option<UserLoginSt
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
         
-        // 按用户名查询用户
+        // 按用户名查询用户（已软删除的用户视为不存在）
         let user = users::Entity::find()
             .filter(users::Column::Username.eq(username))
+            .filter(users::Column::DeletedAt.is_null())
             .one(&connection)
             .await
             .map_err(map_db_error)?;
@@ -165,6 +178,9 @@ pub(super) fn find_user_login_state(username: &str) -> Result<Option<UserLoginSt
             is_active: record.is_active == 1,
             account_is_permanent: record.account_is_permanent == 1,
             account_expire_at: record.account_expire_at,
+            account_start_at: record.account_start_at,
+            account_activation_pending: record.account_activation_pending == 1,
+            must_change_password: record.must_change_password == 1,
         }))
     })
 }
@@ -178,9 +194,10 @@ pub(super) fn deactivate_user_by_username(username: &str, now_millis: i64) -> Re
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
         
-        // 批量更新：设置 is_active = 0, updated_at = now
+        // 批量更新：设置 is_active = 0, updated_at = now，并清除待生效标记
         users::Entity::update_many()
             .col_expr(users::Column::IsActive, Expr::value(0))
+            .col_expr(users::Column::AccountActivationPending, Expr::value(0))
             .col_expr(users::Column::UpdatedAt, Expr::value(now_millis))
             .filter(users::Column::Username.eq(username))
             .exec(&connection)
@@ -201,18 +218,65 @@ pub(super) fn deactivate_expired_users(now_millis: i64) -> Result<usize, AppErro
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
         
-        // 查询并更新：只更新非永久账号且已过期的用户
+        // 查询并更新：只更新非永久账号且已过期的用户（待生效账号过期后不再激活）
         let result = users::Entity::update_many()
             .col_expr(users::Column::IsActive, Expr::value(0))
+            .col_expr(users::Column::AccountActivationPending, Expr::value(0))
             .col_expr(users::Column::UpdatedAt, Expr::value(now_millis))
-            .filter(users::Column::IsActive.eq(1))
+            .filter(
+                Condition::any()
+                    .add(users::Column::IsActive.eq(1))
+                    .add(users::Column::AccountActivationPending.eq(1)),
+            )
             .filter(users::Column::AccountIsPermanent.eq(0))
             .filter(users::Column::AccountExpireAt.is_not_null())
             .filter(users::Column::AccountExpireAt.lte(now_millis))
+            .filter(users::Column::DeletedAt.is_null())
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(usize::try_from(result.rows_affected).unwrap_or(usize::MAX))
+    })
+}
+
+/// 激活生效时间已到达的待生效用户
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 实际被激活返回 true
+pub(super) fn activate_started_user_by_username(
+    username: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = started_pending_users(users::Entity::update_many(), now_millis)
+            .filter(users::Column::Username.eq(username))
             .exec(&connection)
             .await
             .map_err(map_db_error)?;
+        Ok(result.rows_affected > 0)
+    })
+}
 
+/// 激活所有生效时间已到达且未过期的待生效用户
+/// 
+/// # 参数
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 成功激活的用户数量
+pub(super) fn activate_started_users(now_millis: i64) -> Result<usize, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = started_pending_users(users::Entity::update_many(), now_millis)
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
         Ok(usize::try_from(result.rows_affected).unwrap_or(usize::MAX))
     })
 }
@@ -231,8 +295,9 @@ pub(super) fn update_user(input: UpdateUserInput) -> Result<ManagedUserRecord, A
         // 开启事务
         let transaction = connection.begin().await.map_err(map_db_error)?;
 
-        // 查询现有用户
+        // 查询现有用户（已软删除的用户视为不存在）
         let existing = users::Entity::find_by_id(input.user_id)
+            .filter(users::Column::DeletedAt.is_null())
             .one(&transaction)
             .await
             .map_err(map_db_error)?
@@ -243,10 +308,14 @@ pub(super) fn update_user(input: UpdateUserInput) -> Result<ManagedUserRecord, A
         active.username = Set(input.username);
         active.nickname = Set(input.nickname);
         active.phone = Set(trim_optional_phone(input.phone));
-        active.is_active = Set(i32::from(input.is_active));
+        let (is_active, activation_pending) =
+            resolve_activation(input.is_active, input.account_start_at, input.now_millis);
+        active.is_active = Set(is_active);
+        active.account_activation_pending = Set(activation_pending);
         active.account_is_permanent = Set(i32::from(input.account_is_permanent));
         active.account_valid_days = Set(input.account_valid_days);
         active.account_expire_at = Set(input.account_expire_at);
+        active.account_start_at = Set(input.account_start_at);
         active.updated_at = Set(Some(input.now_millis));
         
         // 执行更新
@@ -284,19 +353,42 @@ pub(super) fn update_user(input: UpdateUserInput) -> Result<ManagedUserRecord, A
     })
 }
 
-/// 删除用户
+/// 软删除用户
+/// 
+/// 仅标记 deleted_at / deleted_by 并停用账号，同时记录删除前是否已停用（恢复时还原），
+/// 用户记录与角色关联保留，用户名在清理前保持占用
 /// 
 /// # 参数
 /// * `user_id` - 用户 ID
+/// * `deleted_by` - 操作人用户名
+/// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
-/// * 删除成功返回 true
-pub(super) fn delete_user(user_id: i64) -> Result<bool, AppError> {
+/// * 删除成功返回 true（用户不存在或已删除返回 false）
+pub(super) fn soft_delete_user(
+    user_id: i64,
+    deleted_by: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
-        
-        // 根据 ID 删除用户（关联的角色会自动因 CASCADE 而删除）
-        let result = users::Entity::delete_by_id(user_id)
+
+        // 批量更新：仅作用于未删除的记录，保证重复删除不会覆盖原删除信息
+        // 同一条 UPDATE 中的表达式读取更新前的值：既未启用也不待生效即为删除前已停用
+        let result = users::Entity::update_many()
+            .col_expr(
+                users::Column::DisabledBeforeDelete,
+                Expr::cust(
+                    "CASE WHEN is_active = 0 AND account_activation_pending = 0 THEN 1 ELSE 0 END",
+                ),
+            )
+            .col_expr(users::Column::IsActive, Expr::value(0))
+            .col_expr(users::Column::AccountActivationPending, Expr::value(0))
+            .col_expr(users::Column::DeletedAt, Expr::value(now_millis))
+            .col_expr(users::Column::DeletedBy, Expr::value(deleted_by))
+            .col_expr(users::Column::UpdatedAt, Expr::value(now_millis))
+            .filter(users::Column::Id.eq(user_id))
+            .filter(users::Column::DeletedAt.is_null())
             .exec(&connection)
             .await
             .map_err(map_db_error)?;
@@ -305,11 +397,89 @@ pub(super) fn delete_user(user_id: i64) -> Result<bool, AppError> {
     })
 }
 
+/// 恢复已软删除的用户
+/// 
+/// 清除删除标记；删除前未停用且未过期的账号同时恢复为激活状态，删除前已停用的账号保持停用
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 恢复后的用户记录
+pub(super) fn restore_user(user_id: i64, now_millis: i64) -> Result<ManagedUserRecord, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+
+        // 仅查询已软删除的用户
+        let existing = users::Entity::find_by_id(user_id)
+            .filter(users::Column::DeletedAt.is_not_null())
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+            .ok_or_else(|| AppError::Validation("deleted user not found".to_string()))?;
+
+        // 已过期的限期账号恢复后保持停用，需管理员续期
+        let expired = existing.account_is_permanent == 0
+            && existing
+                .account_expire_at
+                .is_some_and(|expire_at| expire_at <= now_millis);
+
+        // 删除前已停用的账号保持停用，需管理员显式启用
+        let enabled = !expired && existing.disabled_before_delete == 0;
+
+        // 生效时间未到的账号恢复为待生效状态
+        let (is_active, activation_pending) =
+            resolve_activation(enabled, existing.account_start_at, now_millis);
+
+        // 构建更新模型
+        let mut active: users::ActiveModel = existing.into();
+        active.deleted_at = Set(None);
+        active.deleted_by = Set(None);
+        active.disabled_before_delete = Set(0);
+        active.is_active = Set(is_active);
+        active.account_activation_pending = Set(activation_pending);
+        active.updated_at = Set(Some(now_millis));
+
+        // 执行更新
+        active
+            .update(&connection)
+            .await
+            .map_err(map_user_db_error)?;
+
+        // 加载更新后的用户记录
+        load_managed_user_record(&connection, user_id).await
+    })
+}
+
+/// 物理清理超过保留期的软删除用户
+/// 
+/// # 参数
+/// * `deleted_before` - 删除时间早于等于该时间戳的用户将被清理
+/// 
+/// # 返回
+/// * 清理的用户数量（关联的角色会因 CASCADE 一并删除）
+pub(super) fn purge_deleted_users(deleted_before: i64) -> Result<usize, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+
+        let result = users::Entity::delete_many()
+            .filter(users::Column::DeletedAt.is_not_null())
+            .filter(users::Column::DeletedAt.lte(deleted_before))
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(usize::try_from(result.rows_affected).unwrap_or(usize::MAX))
+    })
+}
+
 /// 更新用户密码
 /// 
 /// # 参数
 /// * `user_id` - 用户 ID
 /// * `password` - 新密码
+/// * `must_change_password` - 是否要求用户下次登录修改密码
 /// * `now_millis` - 当前时间戳
 /// 
 /// # 返回
@@ -317,13 +487,15 @@ pub(super) fn delete_user(user_id: i64) -> Result<bool, AppError> {
 pub(super) fn update_user_password(
     user_id: i64,
     password: &str,
+    must_change_password: bool,
     now_millis: i64,
 ) -> Result<ManagedUserRecord, AppError> {
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
 
-        // 查询现有用户
+        // 查询现有用户（已软删除的用户视为不存在）
         let existing = users::Entity::find_by_id(user_id)
+            .filter(users::Column::DeletedAt.is_null())
             .one(&connection)
             .await
             .map_err(map_db_error)?
@@ -332,6 +504,7 @@ pub(super) fn update_user_password(
         // 构建更新模型
         let mut active: users::ActiveModel = existing.into();
         active.password = Set(password.to_string());
+        active.must_change_password = Set(i32::from(must_change_password));
         active.updated_at = Set(Some(now_millis));
         
         // 执行更新
@@ -345,6 +518,37 @@ pub(super) fn update_user_password(
     })
 }
 
+/// 用户修改自己的密码并清除强制改密标记
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// * `password` - 新密码
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 更新成功返回 true
+pub(super) fn update_own_password(
+    username: &str,
+    password: &str,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+
+        let result = users::Entity::update_many()
+            .col_expr(users::Column::Password, Expr::value(password))
+            .col_expr(users::Column::MustChangePassword, Expr::value(0))
+            .col_expr(users::Column::UpdatedAt, Expr::value(now_millis))
+            .filter(users::Column::Username.eq(username))
+            .filter(users::Column::DeletedAt.is_null())
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(result.rows_affected > 0)
+    })
+}
+
 /// 根据用户 ID 查询用户名
 /// 
 /// # 参数
@@ -356,8 +560,9 @@ pub(super) fn find_username_by_user_id(user_id: i64) -> Result<Option<String>, A
     db::block_on(async move {
         let connection = db::connect_orm_async().await?;
         
-        // 根据 ID 查询用户
+        // 根据 ID 查询用户（已软删除的用户视为不存在）
         let record = users::Entity::find_by_id(user_id)
+            .filter(users::Column::DeletedAt.is_null())
             .one(&connection)
             .await
             .map_err(map_db_error)?;
@@ -367,6 +572,111 @@ pub(super) fn find_username_by_user_id(user_id: i64) -> Result<Option<String>, A
     })
 }
 
+/// 根据用户名查询用户 ID
+/// 
+/// # 参数
+/// * `username` - 用户名
+/// 
+/// # 返回
+/// * 用户 ID（如果存在）
+pub(super) fn find_user_id_by_username(username: &str) -> Result<Option<i64>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        
+        // 根据用户名查询用户（已软删除的用户视为不存在）
+        let record = users::Entity::find()
+            .filter(users::Column::Username.eq(username))
+            .filter(users::Column::DeletedAt.is_null())
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        
+        // 返回用户 ID
+        Ok(record.map(|model| model.id))
+    })
+}
+
+/// 在单个事务中对多个用户执行批量变更
+/// 
+/// 尽力而为模式下每个用户在独立的保存点内执行，失败时仅回滚该用户
+/// 
+/// # 参数
+/// * `user_ids` - 目标用户 ID 列表
+/// * `change` - 变更内容
+/// * `best_effort` - 是否尽力而为模式
+/// * `now_millis` - 当前时间戳
+/// 
+/// # 返回
+/// * 逐个用户的执行结果
+pub(super) fn apply_batch_user_change(
+    user_ids: &[i64],
+    change: &BatchUserChange,
+    best_effort: bool,
+    now_millis: i64,
+) -> Result<Vec<BatchUserChangeOutcome>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+
+        let mut outcomes = Vec::with_capacity(user_ids.len());
+        for &user_id in user_ids {
+            if !best_effort {
+                // 全有或全无：任一失败直接返回，事务随 drop 回滚
+                let result = apply_user_change(&transaction, user_id, change, now_millis)
+                    .await
+                    .map_err(|err| prefix_user_error(user_id, err))?;
+                outcomes.push(BatchUserChangeOutcome {
+                    user_id,
+                    result: Ok(result),
+                });
+                continue;
+            }
+
+            // 尽力而为：嵌套事务即保存点
+            let savepoint = transaction.begin().await.map_err(map_db_error)?;
+            let result = apply_user_change(&savepoint, user_id, change, now_millis).await;
+            if result.is_ok() {
+                savepoint.commit().await.map_err(map_db_error)?;
+            } else {
+                savepoint.rollback().await.map_err(map_db_error)?;
+            }
+            outcomes.push(BatchUserChangeOutcome { user_id, result });
+        }
+
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(outcomes)
+    })
+}
+
+/// 按 ID 查询可管理的用户记录（包含已软删除的用户）
+/// 
+/// 供审计快照使用，用户不存在时返回 None
+/// 
+/// # 参数
+/// * `user_id` - 用户 ID
+/// 
+/// # 返回
+/// * 用户记录（如果存在）
+pub(super) fn find_managed_user(user_id: i64) -> Result<Option<ManagedUserRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+
+        // 先确认用户存在，避免把“不存在”当作错误返回
+        let exists = users::Entity::find_by_id(user_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+            .is_some();
+        if !exists {
+            return Ok(None);
+        }
+
+        load_managed_user_record(&connection, user_id)
+            .await
+            .map(Some)
+    })
+}
+
 /// 加载已注册用户记录
 /// 
 /// # 参数
@@ -399,6 +709,7 @@ where
         is_active: user.is_active == 1,
         account_is_permanent: user.account_is_permanent == 1,
         account_expire_at: user.account_expire_at,
+        account_start_at: user.account_start_at,
     })
 }
 
@@ -440,9 +751,174 @@ where
         created_at: user.created_at,
         updated_at: user.updated_at,
         created_by: user.created_by,
+        deleted_at: user.deleted_at,
+        deleted_by: user.deleted_by,
+        account_start_at: user.account_start_at,
+        account_activation_pending: user.account_activation_pending == 1,
+        must_change_password: user.must_change_password == 1,
+        organization_id: user.organization_id,
     })
 }
 
+/// 对单个用户执行批量变更
+/// 
+/// # 返回
+/// * (变更前记录, 变更后记录)
+async fn apply_user_change<C>(
+    connection: &C,
+    user_id: i64,
+    change: &BatchUserChange,
+    now_millis: i64,
+) -> Result<(ManagedUserRecord, ManagedUserRecord), AppError>
+where
+    C: ConnectionTrait,
+{
+    // 查询现有用户（已软删除的用户视为不存在）
+    let existing = users::Entity::find_by_id(user_id)
+        .filter(users::Column::DeletedAt.is_null())
+        .one(connection)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| AppError::Validation("user not found".to_string()))?;
+    let before = load_managed_user_record(connection, user_id).await?;
+
+    let mut active: users::ActiveModel = existing.clone().into();
+    active.updated_at = Set(Some(now_millis));
+    match change {
+        BatchUserChange::Renew {
+            account_is_permanent,
+            account_valid_days,
+        } => {
+            apply_renewal(
+                &mut active,
+                &existing,
+                *account_is_permanent,
+                *account_valid_days,
+                now_millis,
+            )?;
+        }
+        BatchUserChange::SetActive(is_active) => {
+            let (is_active, activation_pending) =
+                resolve_activation(*is_active, existing.account_start_at, now_millis);
+            active.is_active = Set(is_active);
+            active.account_activation_pending = Set(activation_pending);
+        }
+        BatchUserChange::SetExpiry(account_expire_at) => {
+            active.account_is_permanent = Set(i32::from(account_expire_at.is_none()));
+            active.account_valid_days = Set(None);
+            active.account_expire_at = Set(*account_expire_at);
+        }
+        BatchUserChange::AddRoles(roles) => {
+            for role in roles.iter().filter(|role| !before.roles.contains(role)) {
+                user_roles::ActiveModel {
+                    user_id: Set(user_id),
+                    role: Set(role.clone()),
+                }
+                .insert(connection)
+                .await
+                .map_err(map_db_error)?;
+            }
+        }
+        BatchUserChange::RemoveRoles(roles) => {
+            if before.roles.iter().all(|role| roles.contains(role)) {
+                return Err(AppError::Validation(
+                    "user must keep at least one role".to_string(),
+                ));
+            }
+            user_roles::Entity::delete_many()
+                .filter(user_roles::Column::UserId.eq(user_id))
+                .filter(user_roles::Column::Role.is_in(roles.iter().cloned()))
+                .exec(connection)
+                .await
+                .map_err(map_db_error)?;
+        }
+    }
+
+    // 执行更新
+    active
+        .update(connection)
+        .await
+        .map_err(map_user_db_error)?;
+
+    let after = load_managed_user_record(connection, user_id).await?;
+    Ok((before, after))
+}
+
+/// 将续期结果写入更新模型（同时激活账号，生效时间未到时标记待生效）
+/// 
+/// # 参数
+/// * `active` - 用户 ActiveModel
+/// * `existing` - 续期前的用户记录
+/// * `account_is_permanent` - 是否续期为永久账号
+/// * `account_valid_days` - 续期天数
+/// * `now_millis` - 当前时间戳
+fn apply_renewal(
+    active: &mut users::ActiveModel,
+    existing: &users::Model,
+    account_is_permanent: bool,
+    account_valid_days: Option<i64>,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    let account_start_at = existing.account_start_at;
+    let account_expire_at = if account_is_permanent {
+        None
+    } else {
+        // 原为永久账号时没有可顺延的过期时间
+        let current_expire_at = if existing.account_is_permanent == 1 {
+            None
+        } else {
+            existing.account_expire_at
+        };
+        let renew_days = account_valid_days
+            .ok_or_else(|| AppError::Validation("renewDays is required".to_string()))?;
+        Some(extend_account_expiry(
+            current_expire_at,
+            account_start_at,
+            renew_days,
+            now_millis,
+        )?)
+    };
+
+    let (is_active, activation_pending) = resolve_activation(true, account_start_at, now_millis);
+    active.account_is_permanent = Set(i32::from(account_is_permanent));
+    active.account_valid_days = Set(account_valid_days);
+    active.account_expire_at = Set(account_expire_at);
+    active.is_active = Set(is_active);
+    active.account_activation_pending = Set(activation_pending);
+    Ok(())
+}
+
+/// 限定为生效时间已到达、未过期且未删除的待生效用户，并设置激活列
+fn started_pending_users(
+    update: sea_orm::UpdateMany<users::Entity>,
+    now_millis: i64,
+) -> sea_orm::UpdateMany<users::Entity> {
+    update
+        .col_expr(users::Column::IsActive, Expr::value(1))
+        .col_expr(users::Column::AccountActivationPending, Expr::value(0))
+        .col_expr(users::Column::UpdatedAt, Expr::value(now_millis))
+        .filter(users::Column::AccountActivationPending.eq(1))
+        .filter(users::Column::AccountStartAt.lte(now_millis))
+        .filter(users::Column::DeletedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(users::Column::AccountIsPermanent.eq(1))
+                .add(users::Column::AccountExpireAt.is_null())
+                .add(users::Column::AccountExpireAt.gt(now_millis)),
+        )
+}
+
+/// 为批量操作中的错误附加用户 ID
+fn prefix_user_error(user_id: i64, err: AppError) -> AppError {
+    match err {
+        AppError::Validation(message) => {
+            AppError::Validation(format!("userId {user_id}: {message}"))
+        }
+        AppError::Database(message) => AppError::Database(format!("userId {user_id}: {message}")),
+        other @ AppError::Modbus(_) => other,
+    }
+}
+
 /// 加载用户角色列表
 /// 
 /// # 参数
@@ -0,0 +1,222 @@
+//! SQLx 委派管理员模块
+//!
+//! 本模块使用原生 SQL（通过 SQLx）读写委派管理员的范围与可分配角色
+//! 委派记录与可分配角色在同一事务中整体替换
+
+// 引入 SQLx 查询相关类型
+use sqlx::postgres::PgRow;
+use sqlx::{Row, query};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+
+// 引入数据库模块
+use crate::db;
+
+// 引入父模块的数据结构
+use super::{UserDelegationInput, UserDelegationRecord, split_csv_sorted};
+
+// 委派查询语句（条件由调用方拼接，列顺序与 map_delegation_row 对应）
+const DELEGATION_SELECT: &str = r"
+    SELECT
+      d.user_id,
+      u.username,
+      d.organization_id,
+      d.manage_created_users,
+      COALESCE(STRING_AGG(r.role, ','), '') AS assignable_roles,
+      d.created_at,
+      d.updated_at,
+      d.created_by
+    FROM user_admin_delegations d
+    JOIN users u ON u.id = d.user_id
+    LEFT JOIN user_admin_delegation_roles r ON r.user_id = d.user_id
+";
+
+// 委派查询的分组语句
+const DELEGATION_GROUP_BY: &str = "GROUP BY d.user_id, u.username";
+
+/// 查询全部委派记录
+///
+/// # 返回
+/// * 按委派人用户名排序的委派记录
+pub(super) fn list_user_delegations() -> Result<Vec<UserDelegationRecord>, AppError> {
+    db::block_on(async {
+        let mut connection = db::connect_async().await?;
+        let sql = format!("{DELEGATION_SELECT} {DELEGATION_GROUP_BY} ORDER BY u.username");
+        let rows = query(&sql)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_delegation_row).collect()
+    })
+}
+
+/// 按委派人用户 ID 查询委派记录
+///
+/// # 参数
+/// * `user_id` - 委派人用户 ID
+///
+/// # 返回
+/// * 委派记录（未委派时为 None）
+pub(super) fn find_user_delegation(user_id: i64) -> Result<Option<UserDelegationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!("{DELEGATION_SELECT} WHERE d.user_id = $1 {DELEGATION_GROUP_BY}");
+        let row = query(&sql)
+            .bind(user_id)
+            .fetch_optional(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        row.as_ref().map(map_delegation_row).transpose()
+    })
+}
+
+/// 按委派人用户名查询委派记录
+///
+/// # 参数
+/// * `username` - 委派人用户名
+///
+/// # 返回
+/// * 委派记录（未委派时为 None）
+pub(super) fn find_user_delegation_by_username(
+    username: &str,
+) -> Result<Option<UserDelegationRecord>, AppError> {
+    db::block_on(async {
+        let mut connection = db::connect_async().await?;
+        let sql = format!("{DELEGATION_SELECT} WHERE u.username = $1 {DELEGATION_GROUP_BY}");
+        let row = query(&sql)
+            .bind(username)
+            .fetch_optional(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        row.as_ref().map(map_delegation_row).transpose()
+    })
+}
+
+/// 创建或替换委派记录
+///
+/// 单事务执行：写入委派范围后整体替换可分配角色
+///
+/// # 参数
+/// * `input` - 委派写入参数（角色已规范化）
+///
+/// # 返回
+/// * 写入后的委派记录
+pub(super) fn upsert_user_delegation(
+    input: &UserDelegationInput,
+) -> Result<UserDelegationRecord, AppError> {
+    db::block_on(async {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        query(
+            r"
+            INSERT INTO user_admin_delegations (
+              user_id, organization_id, manage_created_users, created_at, updated_at, created_by
+            )
+            VALUES ($1, $2, $3, $4, $4, $5)
+            ON CONFLICT (user_id) DO UPDATE
+            SET organization_id = EXCLUDED.organization_id,
+                manage_created_users = EXCLUDED.manage_created_users,
+                updated_at = EXCLUDED.updated_at
+            ",
+        )
+        .bind(input.user_id)
+        .bind(input.organization_id)
+        .bind(i32::from(input.manage_created_users))
+        .bind(input.now_millis)
+        .bind(&input.created_by)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| map_delegation_mutation_error(&err))?;
+
+        query("DELETE FROM user_admin_delegation_roles WHERE user_id = $1")
+            .bind(input.user_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        query(
+            r"
+            INSERT INTO user_admin_delegation_roles (user_id, role)
+            SELECT $1, role FROM UNNEST($2::TEXT[]) AS role
+            ",
+        )
+        .bind(input.user_id)
+        .bind(&input.assignable_roles)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))
+    })?;
+    find_user_delegation(input.user_id)?
+        .ok_or_else(|| AppError::Database("saved delegation not found".to_string()))
+}
+
+/// 删除委派记录（可分配角色级联删除）
+///
+/// # 参数
+/// * `user_id` - 委派人用户 ID
+///
+/// # 返回
+/// * 是否删除成功（未委派时为 false）
+pub(super) fn delete_user_delegation(user_id: i64) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query("DELETE FROM user_admin_delegations WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut connection)
+            .await
+            .map(|result| result.rows_affected() > 0)
+            .map_err(|err| AppError::Database(err.to_string()))
+    })
+}
+
+/// 将委派查询的一行转换为委派记录
+fn map_delegation_row(row: &PgRow) -> Result<UserDelegationRecord, AppError> {
+    let manage_created_users: i32 = row
+        .try_get(3)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let assignable_roles: String = row
+        .try_get(4)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(UserDelegationRecord {
+        user_id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        username: row
+            .try_get(1)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        organization_id: row
+            .try_get(2)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        manage_created_users: manage_created_users == 1,
+        assignable_roles: split_csv_sorted(&assignable_roles),
+        created_at: row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_at: row
+            .try_get(6)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_by: row
+            .try_get(7)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    })
+}
+
+/// 将委派写入错误转换为业务错误（委派人或组织不存在）
+fn map_delegation_mutation_error(err: &sqlx::Error) -> AppError {
+    let message = err.to_string();
+    if message.contains("user_admin_delegations_organization_id_fkey") {
+        return AppError::Validation("organization not found".to_string());
+    }
+    if message.contains("user_admin_delegations_user_id_fkey") {
+        return AppError::Validation("user not found".to_string());
+    }
+    AppError::Database(message)
+}
@@ -0,0 +1,414 @@
+//! SQLx 用户设备范围模块
+//!
+//! 本模块使用原生 SQL（通过 SQLx）读写用户设备范围（通配标记与区域、楼层、设备明细）
+//! 保存时在同一事务中校验引用的编码并整体替换明细；明细聚合为数组需要 ARRAY 子查询
+//! 区域与楼层均为 location_nodes 中对应层级的位置编码，设备所在区域/楼层沿位置树向上递归解析
+
+// 引入 SQLx 查询相关类型
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, Row, query, query_scalar};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+
+// 引入数据库模块
+use crate::db;
+
+// 引入父模块的数据结构
+use super::{DeviceLocationRecord, UserDeviceScopeInput, UserDeviceScopeRecord};
+
+/// 按用户 ID 查询设备范围
+///
+/// # 参数
+/// * `user_id` - 用户 ID
+///
+/// # 返回
+/// * 设备范围记录（未配置时为 None），明细按编码排序
+pub(super) fn find_user_device_scope(
+    user_id: i64,
+) -> Result<Option<UserDeviceScopeRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let row = query(
+            r"
+            SELECT
+              s.user_id,
+              s.all_areas,
+              s.all_floors,
+              s.all_devices,
+              ARRAY(
+                SELECT a.area_code FROM user_device_scope_areas a
+                WHERE a.user_id = s.user_id ORDER BY a.area_code
+              ) AS areas,
+              ARRAY(
+                SELECT f.floor_code FROM user_device_scope_floors f
+                WHERE f.user_id = s.user_id ORDER BY f.floor_code
+              ) AS floors,
+              ARRAY(
+                SELECT d.device_id FROM user_device_scope_devices d
+                WHERE d.user_id = s.user_id ORDER BY d.device_id
+              ) AS devices,
+              s.updated_at,
+              s.updated_by
+            FROM user_device_scopes s
+            WHERE s.user_id = $1
+            ",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+        row.as_ref().map(map_device_scope_row).transpose()
+    })
+}
+
+/// 创建或整体替换用户设备范围
+///
+/// 单事务执行：校验引用的区域、楼层、设备均存在 → 写入通配标记 → 替换三类明细
+///
+/// # 参数
+/// * `input` - 设备范围写入参数（编码已去重）
+///
+/// # 返回
+/// * 写入后的设备范围记录
+pub(super) fn replace_user_device_scope(
+    input: &UserDeviceScopeInput,
+) -> Result<UserDeviceScopeRecord, AppError> {
+    db::block_on(async {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        ensure_codes_exist(
+            &mut transaction,
+            "area",
+            "SELECT code FROM location_nodes WHERE level = 'area'",
+            &input.areas,
+        )
+        .await?;
+        ensure_codes_exist(
+            &mut transaction,
+            "floor",
+            "SELECT code FROM location_nodes WHERE level = 'floor'",
+            &input.floors,
+        )
+        .await?;
+        ensure_codes_exist(
+            &mut transaction,
+            "device",
+            "SELECT device_id FROM device_registry",
+            &input.devices,
+        )
+        .await?;
+
+        query(
+            r"
+            INSERT INTO user_device_scopes (
+              user_id, all_areas, all_floors, all_devices, updated_at, updated_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE
+            SET all_areas = EXCLUDED.all_areas,
+                all_floors = EXCLUDED.all_floors,
+                all_devices = EXCLUDED.all_devices,
+                updated_at = EXCLUDED.updated_at,
+                updated_by = EXCLUDED.updated_by
+            ",
+        )
+        .bind(input.user_id)
+        .bind(i32::from(input.all_areas))
+        .bind(i32::from(input.all_floors))
+        .bind(i32::from(input.all_devices))
+        .bind(input.now_millis)
+        .bind(&input.updated_by)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+        replace_entries(
+            &mut transaction,
+            "user_device_scope_areas",
+            "area_code",
+            input.user_id,
+            &input.areas,
+        )
+        .await?;
+        replace_entries(
+            &mut transaction,
+            "user_device_scope_floors",
+            "floor_code",
+            input.user_id,
+            &input.floors,
+        )
+        .await?;
+        replace_entries(
+            &mut transaction,
+            "user_device_scope_devices",
+            "device_id",
+            input.user_id,
+            &input.devices,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))
+    })?;
+    find_user_device_scope(input.user_id)?
+        .ok_or_else(|| AppError::Database("saved device scope not found".to_string()))
+}
+
+/// 查询设备所在的区域与楼层
+///
+/// 从设备所在位置节点沿 parent_id 向上递归，取路径上（含自身）area 与 floor 层级的编码
+///
+/// # 参数
+/// * `device_id` - 设备标识
+///
+/// # 返回
+/// * 设备位置记录（设备不存在时为 None）
+pub(super) fn find_device_location(
+    device_id: &str,
+) -> Result<Option<DeviceLocationRecord>, AppError> {
+    db::block_on(async {
+        let mut connection = db::connect_async().await?;
+        let row = query(
+            r"
+            WITH RECURSIVE ancestors(id, parent_id, level, code) AS (
+              SELECT n.id, n.parent_id, n.level, n.code
+              FROM location_nodes n
+              JOIN device_registry d ON d.location_id = n.id
+              WHERE d.device_id = $1
+              UNION ALL
+              SELECT p.id, p.parent_id, p.level, p.code
+              FROM location_nodes p
+              JOIN ancestors a ON p.id = a.parent_id
+            )
+            SELECT
+              d.device_id,
+              (SELECT a.code FROM ancestors a WHERE a.level = 'area' LIMIT 1) AS area_code,
+              (SELECT a.code FROM ancestors a WHERE a.level = 'floor' LIMIT 1) AS floor_code
+            FROM device_registry d
+            WHERE d.device_id = $1
+            ",
+        )
+        .bind(device_id)
+        .fetch_optional(&mut connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+        row.map(|row| {
+            Ok(DeviceLocationRecord {
+                device_id: row
+                    .try_get(0)
+                    .map_err(|err| AppError::Database(err.to_string()))?,
+                area_code: row
+                    .try_get(1)
+                    .map_err(|err| AppError::Database(err.to_string()))?,
+                floor_code: row
+                    .try_get(2)
+                    .map_err(|err| AppError::Database(err.to_string()))?,
+            })
+        })
+        .transpose()
+    })
+}
+
+/// 查询设备放置到指定位置时所在的区域与楼层
+///
+/// 用于创建设备或移动设备前判定目标位置是否在用户设备范围内
+///
+/// # 参数
+/// * `device_id` - 设备标识（新设备可尚未登记）
+/// * `location_id` - 目标位置 ID（None 表示不设置位置）
+///
+/// # 返回
+/// * 以目标位置解析区域与楼层的设备位置记录
+pub(super) fn find_device_placement(
+    device_id: &str,
+    location_id: Option<i64>,
+) -> Result<DeviceLocationRecord, AppError> {
+    let (area_code, floor_code) = match location_id {
+        Some(location_id) => db::block_on(async move {
+            let mut connection = db::connect_async().await?;
+            let row = query(
+                r"
+                WITH RECURSIVE ancestors(id, parent_id, level, code) AS (
+                  SELECT n.id, n.parent_id, n.level, n.code FROM location_nodes n WHERE n.id = $1
+                  UNION ALL
+                  SELECT p.id, p.parent_id, p.level, p.code
+                  FROM location_nodes p
+                  JOIN ancestors a ON p.id = a.parent_id
+                )
+                SELECT
+                  (SELECT a.code FROM ancestors a WHERE a.level = 'area' LIMIT 1) AS area_code,
+                  (SELECT a.code FROM ancestors a WHERE a.level = 'floor' LIMIT 1) AS floor_code
+                ",
+            )
+            .bind(location_id)
+            .fetch_one(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+            Ok::<_, AppError>((
+                row.try_get(0)
+                    .map_err(|err| AppError::Database(err.to_string()))?,
+                row.try_get(1)
+                    .map_err(|err| AppError::Database(err.to_string()))?,
+            ))
+        })?,
+        None => (None, None),
+    };
+    Ok(DeviceLocationRecord {
+        device_id: device_id.to_string(),
+        area_code,
+        floor_code,
+    })
+}
+
+/// 查询用户设备范围明细覆盖的全部设备标识
+///
+/// 显式授权的设备，加上位于授权区域/楼层（或 `all_areas` / `all_floors` 时任一区域/楼层）
+/// 子树内的设备；子树沿位置树向下递归展开
+///
+/// # 参数
+/// * `user_id` - 用户 ID
+///
+/// # 返回
+/// * 去重并按设备标识排序的设备列表
+pub(super) fn find_scope_device_ids(user_id: i64) -> Result<Vec<String>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query_scalar(
+            r"
+            WITH RECURSIVE granted(id) AS (
+              SELECT n.id
+              FROM location_nodes n
+              JOIN user_device_scopes s ON s.user_id = $1
+              WHERE (
+                n.level = 'area' AND (
+                  s.all_areas = 1 OR EXISTS (
+                    SELECT 1 FROM user_device_scope_areas a
+                    WHERE a.user_id = s.user_id AND a.area_code = n.code
+                  )
+                )
+              ) OR (
+                n.level = 'floor' AND (
+                  s.all_floors = 1 OR EXISTS (
+                    SELECT 1 FROM user_device_scope_floors f
+                    WHERE f.user_id = s.user_id AND f.floor_code = n.code
+                  )
+                )
+              )
+              UNION
+              SELECT c.id FROM location_nodes c JOIN granted g ON c.parent_id = g.id
+            )
+            SELECT d.device_id FROM device_registry d WHERE d.location_id IN (SELECT id FROM granted)
+            UNION
+            SELECT e.device_id FROM user_device_scope_devices e WHERE e.user_id = $1
+            ORDER BY 1
+            ",
+        )
+        .bind(user_id)
+        .fetch_all(&mut connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+    })
+}
+
+/// 校验编码均存在于候选编码集合中
+///
+/// 候选编码查询来自本模块的固定常量，编码通过参数绑定传入
+///
+/// # 返回
+/// * 存在缺失编码时返回 "{kind} not found: {codes}"
+async fn ensure_codes_exist(
+    connection: &mut PgConnection,
+    kind: &str,
+    existing_codes_sql: &str,
+    codes: &[String],
+) -> Result<(), AppError> {
+    if codes.is_empty() {
+        return Ok(());
+    }
+    let sql = format!(
+        "SELECT t.code FROM UNNEST($1::TEXT[]) AS t(code) \
+         WHERE NOT EXISTS (SELECT 1 FROM ({existing_codes_sql}) x(code) WHERE x.code = t.code) \
+         ORDER BY t.code"
+    );
+    let missing: Vec<String> = query_scalar(&sql)
+        .bind(codes)
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "{kind} not found: {}",
+            missing.join(",")
+        )))
+    }
+}
+
+/// 整体替换一类设备范围明细
+///
+/// 表名与列名来自本模块的固定常量，用户 ID 与编码通过参数绑定传入
+async fn replace_entries(
+    connection: &mut PgConnection,
+    table: &str,
+    column: &str,
+    user_id: i64,
+    codes: &[String],
+) -> Result<(), AppError> {
+    query(&format!("DELETE FROM {table} WHERE user_id = $1"))
+        .bind(user_id)
+        .execute(&mut *connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    query(&format!(
+        "INSERT INTO {table} (user_id, {column}) SELECT $1, code FROM UNNEST($2::TEXT[]) AS code"
+    ))
+    .bind(user_id)
+    .bind(codes)
+    .execute(&mut *connection)
+    .await
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 将设备范围查询的一行转换为设备范围记录
+fn map_device_scope_row(row: &PgRow) -> Result<UserDeviceScopeRecord, AppError> {
+    let all_areas: i32 = row
+        .try_get(1)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let all_floors: i32 = row
+        .try_get(2)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let all_devices: i32 = row
+        .try_get(3)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(UserDeviceScopeRecord {
+        user_id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        all_areas: all_areas == 1,
+        all_floors: all_floors == 1,
+        all_devices: all_devices == 1,
+        areas: row
+            .try_get(4)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        floors: row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        devices: row
+            .try_get(6)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_at: row
+            .try_get(7)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_by: row
+            .try_get(8)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    })
+}
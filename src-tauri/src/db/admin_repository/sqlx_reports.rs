@@ -4,6 +4,7 @@
 //! 适用于复杂的聚合查询和报表数据提取
 
 // 引入 SQLx 查询相关类型
+use sqlx::postgres::PgRow;
 use sqlx::{Row, query, query_scalar};
 
 // 引入应用错误类型
@@ -17,7 +18,7 @@ use super::{ManagedUserRecord, split_csv_sorted};
 
 /// 查询用户的有效角色
 /// 
-/// 有效角色是指用户当前可用的角色（考虑账号有效期）
+/// 有效角色是指用户当前可用的角色（考虑账号生效时间与有效期）
 /// 
 /// # 参数
 /// * `username` - 用户名
@@ -33,7 +34,7 @@ pub(super) fn find_effective_roles(
         let mut connection = db::connect_async().await?;
 
         // 使用 SQLx 执行复杂查询
-        // 查询条件包括：账号激活状态 + 账号有效期判断
+        // 查询条件包括：账号激活状态 + 未软删除 + 已到生效时间 + 已完成强制改密 + 账号有效期判断
         let row = query_scalar::<_, String>(
             r"
             SELECT COALESCE(STRING_AGG(DISTINCT ur.role, ','), '') AS roles
@@ -41,6 +42,9 @@ pub(super) fn find_effective_roles(
             LEFT JOIN user_roles ur ON ur.user_id = u.id
             WHERE u.username = $1
               AND u.is_active = 1
+              AND u.deleted_at IS NULL
+              AND (u.account_start_at IS NULL OR u.account_start_at <= $2)
+              AND u.must_change_password = 0
               AND (
                 COALESCE(u.account_is_permanent, 1) = 1
                 OR u.account_expire_at IS NULL
@@ -78,10 +82,16 @@ pub(super) fn is_admin_user(username: &str, now_millis: i64) -> Result<bool, App
 /// 
 /// 查询所有用户及其关联的角色信息
 /// 
+/// # 参数
+/// * `include_deleted` - 是否包含已软删除的用户（用于回收站视图）
+/// 
 /// # 返回
 /// * 所有可管理的用户记录列表
-pub(super) fn list_users() -> Result<Vec<ManagedUserRecord>, AppError> {
-    db::block_on(async {
+pub(super) fn list_users(
+    include_deleted: bool,
+    organization_id: Option<i64>,
+) -> Result<Vec<ManagedUserRecord>, AppError> {
+    db::block_on(async move {
         let mut connection = db::connect_async().await?;
         
         // 查询用户及其角色（使用 LEFT JOIN 保留没有角色的用户）
@@ -99,9 +109,27 @@ pub(super) fn list_users() -> Result<Vec<ManagedUserRecord>, AppError> {
               u.created_at,
               u.updated_at,
               u.created_by,
-              COALESCE(STRING_AGG(DISTINCT ur.role, ','), '') AS roles
+              COALESCE(STRING_AGG(DISTINCT ur.role, ','), '') AS roles,
+              u.deleted_at,
+              u.deleted_by,
+              u.account_start_at,
+              u.account_activation_pending,
+              u.must_change_password,
+              u.organization_id
             FROM users u
             LEFT JOIN user_roles ur ON ur.user_id = u.id
+            WHERE ($1 OR u.deleted_at IS NULL)
+              AND (
+                $2::BIGINT IS NULL
+                OR u.organization_id IN (
+                  WITH RECURSIVE subtree(id) AS (
+                    SELECT id FROM organizations WHERE id = $2
+                    UNION ALL
+                    SELECT o.id FROM organizations o JOIN subtree s ON o.parent_id = s.id
+                  )
+                  SELECT id FROM subtree
+                )
+              )
             GROUP BY
               u.id,
               u.username,
@@ -113,63 +141,97 @@ pub(super) fn list_users() -> Result<Vec<ManagedUserRecord>, AppError> {
               u.account_expire_at,
               u.created_at,
               u.updated_at,
-              u.created_by
+              u.created_by,
+              u.deleted_at,
+              u.deleted_by,
+              u.account_start_at,
+              u.account_activation_pending,
+              u.must_change_password,
+              u.organization_id
             ORDER BY u.id ASC
             ",
         )
+        .bind(include_deleted)
+        .bind(organization_id)
         .fetch_all(&mut connection)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
         // 将查询结果转换为 ManagedUserRecord 列表
-        let mut records = Vec::with_capacity(rows.len());
-        for row in rows {
-            // 提取字段值
-            let is_active: i32 = row
-                .try_get(4)
-                .map_err(|err| AppError::Database(err.to_string()))?;
-            let account_is_permanent: i32 = row
-                .try_get(5)
-                .map_err(|err| AppError::Database(err.to_string()))?;
-            let roles_csv: String = row
-                .try_get(11)
-                .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_managed_user_row).collect()
+    })
+}
 
-            // 构建用户记录
-            records.push(ManagedUserRecord {
-                user_id: row
-                    .try_get(0)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                username: row
-                    .try_get(1)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                nickname: row
-                    .try_get(2)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                phone: row
-                    .try_get(3)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                roles: split_csv_sorted(&roles_csv),
-                is_active: is_active == 1,
-                account_is_permanent: account_is_permanent == 1,
-                account_valid_days: row
-                    .try_get(6)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                account_expire_at: row
-                    .try_get(7)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                created_at: row
-                    .try_get(8)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                updated_at: row
-                    .try_get(9)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-                created_by: row
-                    .try_get(10)
-                    .map_err(|err| AppError::Database(err.to_string()))?,
-            });
-        }
+/// 将用户列表查询的一行转换为用户记录
+/// 
+/// # 参数
+/// * `row` - 按 list_users 查询列顺序排列的结果行
+/// 
+/// # 返回
+/// * 用户记录
+fn map_managed_user_row(row: &PgRow) -> Result<ManagedUserRecord, AppError> {
+    // 提取字段值
+    let is_active: i32 = row
+        .try_get(4)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let account_is_permanent: i32 = row
+        .try_get(5)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let roles_csv: String = row
+        .try_get(11)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let account_activation_pending: i32 = row
+        .try_get(15)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let must_change_password: i32 = row
+        .try_get(16)
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-        Ok(records)
+    // 构建用户记录
+    Ok(ManagedUserRecord {
+        user_id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        username: row
+            .try_get(1)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        nickname: row
+            .try_get(2)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        phone: row
+            .try_get(3)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        roles: split_csv_sorted(&roles_csv),
+        is_active: is_active == 1,
+        account_is_permanent: account_is_permanent == 1,
+        account_valid_days: row
+            .try_get(6)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        account_expire_at: row
+            .try_get(7)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_at: row
+            .try_get(8)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_at: row
+            .try_get(9)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_by: row
+            .try_get(10)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        deleted_at: row
+            .try_get(12)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        deleted_by: row
+            .try_get(13)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        account_start_at: row
+            .try_get(14)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        account_activation_pending: account_activation_pending == 1,
+        must_change_password: must_change_password == 1,
+        organization_id: row
+            .try_get(17)
+            .map_err(|err| AppError::Database(err.to_string()))?,
     })
 }
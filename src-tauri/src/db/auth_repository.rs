@@ -77,6 +77,7 @@ pub fn find_user_profile(username: &str, password: &str) -> Result<Option<UserPr
 
         // 使用 sqlx 执行复杂的多表关联查询
         // 查询用户基本信息、聚合角色和权限
+        // 待生效账号也会返回，由 ensure_user_available_with_message 判断生效时间并激活
         let row = query(
             r"
             SELECT
@@ -84,13 +85,17 @@ pub fn find_user_profile(username: &str, password: &str) -> Result<Option<UserPr
               u.username,
               u.nickname,
               COALESCE(STRING_AGG(DISTINCT ur.role, ','), '') AS roles,
-              COALESCE(STRING_AGG(DISTINCT p.code, ','), '') AS permissions
+              COALESCE(STRING_AGG(DISTINCT p.code, ','), '') AS permissions,
+              u.must_change_password
             FROM users u
             LEFT JOIN user_roles ur ON ur.user_id = u.id
             LEFT JOIN user_permissions up ON up.user_id = u.id
             LEFT JOIN permissions p ON p.id = up.permission_id
-            WHERE u.username = $1 AND u.password = $2 AND u.is_active = 1
-            GROUP BY u.id, u.avatar, u.username, u.nickname
+            WHERE u.username = $1
+              AND u.password = $2
+              AND (u.is_active = 1 OR u.account_activation_pending = 1)
+              AND u.deleted_at IS NULL
+            GROUP BY u.id, u.avatar, u.username, u.nickname, u.must_change_password
             LIMIT 1
             ",
         )
@@ -121,6 +126,9 @@ pub fn find_user_profile(username: &str, password: &str) -> Result<Option<UserPr
         let permissions: String = row
             .try_get(4)
             .map_err(|err| AppError::Database(err.to_string()))?;
+        let must_change_password: i32 = row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?;
 
         // 构建用户档案并返回
         Ok(Some(UserProfile {
@@ -129,6 +137,7 @@ pub fn find_user_profile(username: &str, password: &str) -> Result<Option<UserPr
             nickname,
             roles: split_csv(&roles),
             permissions: split_csv(&permissions),
+            must_change_password: must_change_password == 1,
         }))
     })
 }
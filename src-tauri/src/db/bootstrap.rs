@@ -53,6 +53,60 @@ pub(crate) async fn init_database() -> Result<(), AppError> {
         // 3.6 执行隐藏按钮权限路由迁移
         migrations::apply_hide_button_permission_route(&mut connection).await?;
 
+        // 3.7 执行用户软删除迁移（添加 deleted_at / deleted_by 字段）
+        migrations::apply_user_soft_delete(&mut connection).await?;
+
+        // 3.8 执行审计事件表迁移（只追加的哈希链审计日志）
+        migrations::apply_audit_events(&mut connection).await?;
+
+        // 3.9 执行账号生效时间迁移（添加 account_start_at 等字段）
+        migrations::apply_user_account_start(&mut connection).await?;
+
+        // 3.10 执行强制改密标记迁移（添加 must_change_password 字段）
+        migrations::apply_user_must_change_password(&mut connection).await?;
+
+        // 3.11 执行组织架构迁移（organizations 表与 users.organization_id）
+        migrations::apply_organizations(&mut connection).await?;
+
+        // 3.12 执行委派管理员迁移（委派范围与可分配角色）
+        migrations::apply_user_admin_delegations(&mut connection).await?;
+
+        // 3.13 执行用户设备范围迁移（区域/楼层字典、设备位置与用户设备范围表）
+        migrations::apply_user_device_scopes(&mut connection).await?;
+
+        // 3.14 执行空间位置树迁移（location_nodes 表与设备所在位置）
+        migrations::apply_location_nodes(&mut connection).await?;
+
+        // 3.15 执行设备注册表扩展迁移（设备元数据字段与 device:view 权限）
+        migrations::apply_device_registry_management(&mut connection).await?;
+
+        // 3.16 执行设备模板迁移（模板、模板点位与设备点位表）
+        migrations::apply_device_templates(&mut connection).await?;
+
+        // 3.17 执行设备生命周期迁移（生命周期状态与流转历史表）
+        migrations::apply_device_lifecycle(&mut connection).await?;
+
+        // 3.18 执行设备标签迁移（设备与点位标签表及标签键值索引）
+        migrations::apply_device_tags(&mut connection).await?;
+
+        // 3.19 执行通信网关迁移（网关连接配置表）
+        migrations::apply_gateways(&mut connection).await?;
+
+        // 3.20 执行网关从站与点位迁移（从站表与寄存器点位表）
+        migrations::apply_gateway_points(&mut connection).await?;
+
+        // 3.21 执行采集参数迁移（网关与从站轮询周期、合并间隙）
+        migrations::apply_acquisition_settings(&mut connection).await?;
+
+        // 3.22 执行点位值变换迁移（网关点位的值变换配置）
+        migrations::apply_point_transforms(&mut connection).await?;
+
+        // 3.23 执行虚拟点位迁移（网关点位的虚拟点位公式）
+        migrations::apply_virtual_points(&mut connection).await?;
+
+        // 3.24 执行删除前停用标记迁移（恢复软删除账号时还原启用状态）
+        migrations::apply_user_disabled_before_delete(&mut connection).await?;
+
         Ok::<(), AppError>(())
     }
     .await;
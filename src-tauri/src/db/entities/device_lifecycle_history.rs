@@ -0,0 +1,29 @@
+//! 设备生命周期流转历史实体定义模块
+//!
+//! 本模块定义 device_lifecycle_history 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备生命周期流转历史实体模型
+///
+/// 对应数据库中的 device_lifecycle_history 表
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_lifecycle_history")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 历史记录 ID
+    pub device_id: String,  // 设备标识
+    pub from_state: String, // 流转前状态
+    pub to_state: String,   // 流转后状态
+    pub reason: String,     // 流转原因
+    pub actor: String,      // 操作人用户名
+    pub changed_at: i64,    // 流转时间戳（毫秒）
+}
+
+/// 设备生命周期流转历史实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,38 @@
+//! 设备点位实体定义模块
+//!
+//! 本模块定义 device_points 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备点位实体模型
+///
+/// 对应数据库中的 device_points 表（模板点位与设备级覆盖合成后的生效点位）
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_points")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 点位 ID
+    pub device_id: String,     // 设备标识
+    pub point_key: String,     // 点位标识（设备内唯一）
+    pub name: String,          // 点位名称
+    pub unit: Option<String>,  // 工程单位
+    pub data_type: String,     // 数据类型
+    pub register_type: String, // 寄存器类型
+    pub address: i32,          // 起始寄存器地址
+    pub byte_order: String,    // 多寄存器字节序
+    pub scale: f64,            // 缩放系数
+    pub value_offset: f64,     // 偏移量
+    pub access: String,        // 访问方式
+    pub sort_order: i32,       // 排序号
+    #[sea_orm(column_type = "JsonBinary")] // JSONB 列
+    pub overrides: Json, // 设备级覆盖字段（JSON 对象）
+    pub updated_at: i64,       // 更新时间戳（毫秒）
+}
+
+/// 设备点位实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,42 @@
+//! 设备注册实体定义模块
+//!
+//! 本模块定义 device_registry 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备注册实体模型
+///
+/// 对应数据库中的 device_registry 表
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_registry")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 自增主键 ID
+    #[sea_orm(unique)] // 唯一约束
+    pub device_id: String, // 设备标识（唯一）
+    pub device_name: String,             // 设备名称
+    pub owner_username: String,          // 设备归属用户名
+    pub registered_at: i64,              // 注册时间戳（毫秒）
+    pub location_id: Option<i64>,        // 所在位置 ID（NULL=未设置）
+    pub device_type: String,             // 设备类型
+    pub manufacturer: Option<String>,    // 厂商
+    pub model: Option<String>,           // 型号
+    pub serial_number: Option<String>,   // 序列号
+    pub comm_config_ref: Option<String>, // 通信配置引用
+    pub enabled: i32,                    // 是否启用（1=启用，0=停用）
+    #[sea_orm(column_type = "JsonBinary")] // JSONB 列
+    pub attributes: Json, // 自由属性（JSON 对象）
+    pub updated_at: i64,                 // 更新时间戳（毫秒）
+    pub template_id: Option<i64>,        // 关联模板 ID（NULL=未关联）
+    pub template_version: Option<i32>,   // 已同步的模板版本
+    pub lifecycle_state: String,         // 生命周期状态
+    pub lifecycle_changed_at: i64,       // 进入当前状态的时间戳（毫秒）
+}
+
+/// 设备注册实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,29 @@
+//! 设备标签实体定义模块
+//!
+//! 本模块定义 device_tags 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备标签实体模型
+///
+/// 对应数据库中的 device_tags 表（`point_key` 为空字符串表示设备级标签）
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_tags")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)] // 复合主键：非自增
+    pub device_id: String, // 设备标识
+    #[sea_orm(primary_key, auto_increment = false)] // 复合主键：非自增
+    pub point_key: String, // 点位标识（空字符串表示设备级标签）
+    #[sea_orm(primary_key, auto_increment = false)] // 复合主键：非自增
+    pub tag_key: String, // 标签键
+    pub tag_value: String, // 标签值（空字符串表示仅标记）
+    pub updated_at: i64,   // 更新时间戳（毫秒）
+}
+
+/// 设备标签实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,50 @@
+//! 设备模板点位实体定义模块
+//!
+//! 本模块定义 device_template_points 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备模板点位实体模型
+///
+/// 对应数据库中的 device_template_points 表
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_template_points")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 点位 ID
+    pub template_id: i64,      // 所属模板 ID
+    pub point_key: String,     // 点位标识（模板内唯一）
+    pub name: String,          // 点位名称
+    pub unit: Option<String>,  // 工程单位
+    pub data_type: String,     // 数据类型
+    pub register_type: String, // 寄存器类型
+    pub address: i32,          // 起始寄存器地址
+    pub byte_order: String,    // 多寄存器字节序
+    pub scale: f64,            // 缩放系数
+    pub value_offset: f64,     // 偏移量
+    pub access: String,        // 访问方式
+    pub sort_order: i32,       // 排序号
+}
+
+/// 设备模板点位实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::device_templates::Entity",
+        from = "Column::TemplateId",
+        to = "super::device_templates::Column::Id",
+        on_delete = "Cascade"
+    )]
+    DeviceTemplates, // 多对一：点位属于一个模板
+}
+
+/// 实现与设备模板实体的关联
+impl Related<super::device_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeviceTemplates.def()
+    }
+}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,47 @@
+//! 设备模板实体定义模块
+//!
+//! 本模块定义 device_templates 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 设备模板实体模型
+///
+/// 对应数据库中的 device_templates 表
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "device_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 模板 ID
+    #[sea_orm(unique)] // 唯一约束
+    pub code: String, // 模板编码（唯一）
+    pub name: String,                 // 模板名称
+    pub manufacturer: Option<String>, // 厂商
+    pub model: Option<String>,        // 型号
+    pub device_type: String,          // 设备类型
+    pub description: Option<String>,  // 说明
+    pub poll_interval_ms: i32,        // 默认轮询周期（毫秒）
+    pub poll_timeout_ms: i32,         // 默认请求超时（毫秒）
+    pub poll_retries: i32,            // 默认失败重试次数
+    pub version: i32,                 // 模板版本（每次修改加 1）
+    pub created_at: i64,              // 创建时间戳（毫秒）
+    pub updated_at: i64,              // 更新时间戳（毫秒）
+    pub created_by: String,           // 创建人用户名
+}
+
+/// 设备模板实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::device_template_points::Entity")]
+    DeviceTemplatePoints, // 一对多：模板包含多个点位
+}
+
+/// 实现与模板点位实体的关联
+impl Related<super::device_template_points::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeviceTemplatePoints.def()
+    }
+}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+//! 网关点位实体定义模块
+//!
+//! 本模块定义 gateway_points 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 网关点位实体模型
+///
+/// 对应数据库中的 gateway_points 表（同一从站下点位标识唯一）
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "gateway_points")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 点位 ID
+    pub slave_id: i64,        // 所属从站 ID
+    pub point_key: String,    // 点位标识（从站内唯一）
+    pub name: String,         // 点位名称
+    pub function_code: i32,   // 读取功能码（1 / 2 / 3 / 4，0 为虚拟点位）
+    pub address: i32,         // 起始地址
+    pub count: i32,           // 寄存器（或线圈）数量
+    pub data_type: String,    // 数据类型
+    pub byte_order: String,   // 字节序
+    pub access: String,       // 访问方式（read / read_write）
+    pub unit: Option<String>, // 工程单位
+    pub sort_order: i32,      // 排序号
+    #[sea_orm(column_type = "JsonBinary")] // JSONB 列
+    pub transform: Json, // 值变换配置（JSON 对象）
+    pub expression: Option<String>, // 虚拟点位公式（物理点位为空）
+    pub created_at: i64,      // 创建时间戳（毫秒）
+    pub updated_at: i64,      // 更新时间戳（毫秒）
+}
+
+/// 网关点位实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::gateway_slaves::Entity",
+        from = "Column::SlaveId",
+        to = "super::gateway_slaves::Column::Id",
+        on_delete = "Cascade"
+    )]
+    GatewaySlaves, // 多对一：点位属于一个从站
+}
+
+/// 实现与网关从站实体的关联
+impl Related<super::gateway_slaves::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GatewaySlaves.def()
+    }
+}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
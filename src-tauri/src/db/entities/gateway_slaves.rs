@@ -0,0 +1,48 @@
+//! 网关从站实体定义模块
+//!
+//! 本模块定义 gateway_slaves 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 网关从站实体模型
+///
+/// 对应数据库中的 gateway_slaves 表（同一网关下单元号唯一）
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "gateway_slaves")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 从站 ID
+    pub gateway_id: i64,               // 所属网关 ID
+    pub unit_id: i32,                  // 从站单元号（1–247）
+    pub name: String,                  // 从站名称
+    pub address_min: Option<i32>,      // 点位地址下限（含）
+    pub address_max: Option<i32>,      // 点位地址上限（含）
+    pub poll_interval_ms: Option<i32>, // 从站轮询周期（毫秒，为空沿用网关）
+    pub enabled: bool,                 // 是否启用
+    pub remark: Option<String>,        // 备注
+    pub created_at: i64,               // 创建时间戳（毫秒）
+    pub updated_at: i64,               // 更新时间戳（毫秒）
+}
+
+/// 网关从站实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::gateways::Entity",
+        from = "Column::GatewayId",
+        to = "super::gateways::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Gateways, // 多对一：从站属于一个网关
+}
+
+/// 实现与通信网关实体的关联
+impl Related<super::gateways::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Gateways.def()
+    }
+}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
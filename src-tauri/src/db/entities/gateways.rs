@@ -0,0 +1,44 @@
+//! 通信网关实体定义模块
+//!
+//! 本模块定义 gateways 表的 SeaORM 实体模型
+
+// 引入 SeaORM 实体 prelude
+use sea_orm::entity::prelude::*;
+
+/// 通信网关实体模型
+///
+/// 对应数据库中的 gateways 表（TCP 网关使用 host/port，串口网关使用 serial_port 及串口参数）
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "gateways")]
+pub struct Model {
+    #[sea_orm(primary_key)] // 主键
+    pub id: i64, // 网关 ID
+    #[sea_orm(unique)] // 唯一约束
+    pub code: String, // 网关编码（唯一，作为长连接的网关标识）
+    pub name: String,                // 网关名称
+    pub transport: String,           // 传输方式（tcp / serial）
+    pub host: Option<String>,        // IP 地址或主机名（TCP 网关）
+    pub port: Option<i32>,           // TCP 端口（TCP 网关）
+    pub serial_port: Option<String>, // 串口路径（串口网关）
+    pub baud_rate: Option<i32>,      // 波特率（串口网关）
+    pub data_bits: Option<i32>,      // 数据位（串口网关）
+    pub parity: Option<String>,      // 校验位（串口网关）
+    pub stop_bits: Option<i32>,      // 停止位（串口网关）
+    pub framing: String,             // 帧格式（mbap / rtu / ascii）
+    pub connect_timeout_ms: i32,     // 建连超时（毫秒）
+    pub request_timeout_ms: i32,     // 请求超时（毫秒）
+    pub poll_interval_ms: i32,       // 轮询周期（毫秒）
+    pub poll_max_gap: i32,           // 合并读取允许跨越的最大空闲地址数
+    pub enabled: bool,               // 是否启用
+    pub remark: Option<String>,      // 备注
+    pub created_at: i64,             // 创建时间戳（毫秒）
+    pub updated_at: i64,             // 更新时间戳（毫秒）
+    pub created_by: String,          // 创建人用户名
+}
+
+/// 通信网关实体关系定义
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// ActiveModel 行为实现
+impl ActiveModelBehavior for ActiveModel {}
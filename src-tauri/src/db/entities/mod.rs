@@ -3,6 +3,24 @@
 //! 本模块包含数据库表的实体定义
 //! 使用 SeaORM 框架的代码生成器从数据库schema自动生成
 
+// 导出设备生命周期流转历史实体
+pub mod device_lifecycle_history;
+// 导出设备点位实体
+pub mod device_points;
+// 导出设备注册实体
+pub mod device_registry;
+// 导出设备标签实体
+pub mod device_tags;
+// 导出设备模板点位实体
+pub mod device_template_points;
+// 导出设备模板实体
+pub mod device_templates;
+// 导出网关点位实体
+pub mod gateway_points;
+// 导出网关从站实体
+pub mod gateway_slaves;
+// 导出通信网关实体
+pub mod gateways;
 // 导出 prelude 模块
 pub mod prelude;
 // 导出用户角色关联实体
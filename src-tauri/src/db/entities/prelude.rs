@@ -2,6 +2,24 @@
 //!
 //! 本模块重新导出常用的实体类型，方便其他模块使用
 
+// 导出 device_lifecycle_history 实体为 DeviceLifecycleHistory
+pub use super::device_lifecycle_history::Entity as DeviceLifecycleHistory;
+// 导出 device_points 实体为 DevicePoints
+pub use super::device_points::Entity as DevicePoints;
+// 导出 device_registry 实体为 DeviceRegistry
+pub use super::device_registry::Entity as DeviceRegistry;
+// 导出 device_tags 实体为 DeviceTags
+pub use super::device_tags::Entity as DeviceTags;
+// 导出 device_template_points 实体为 DeviceTemplatePoints
+pub use super::device_template_points::Entity as DeviceTemplatePoints;
+// 导出 device_templates 实体为 DeviceTemplates
+pub use super::device_templates::Entity as DeviceTemplates;
+// 导出 gateway_points 实体为 GatewayPoints
+pub use super::gateway_points::Entity as GatewayPoints;
+// 导出 gateway_slaves 实体为 GatewaySlaves
+pub use super::gateway_slaves::Entity as GatewaySlaves;
+// 导出 gateways 实体为 Gateways
+pub use super::gateways::Entity as Gateways;
 // 导出 user_roles 实体为 UserRoles
 pub use super::user_roles::Entity as UserRoles;
 // 导出 users 实体为 Users
@@ -25,6 +25,13 @@ pub struct Model {
     pub created_at: Option<i64>,         // 创建时间戳
     pub updated_at: Option<i64>,         // 更新时间戳
     pub created_by: Option<String>,      // 创建者
+    pub deleted_at: Option<i64>,         // 软删除时间戳（NULL=未删除）
+    pub deleted_by: Option<String>,      // 软删除操作人
+    pub account_start_at: Option<i64>,   // 账号生效时间戳（NULL=立即生效）
+    pub account_activation_pending: i32, // 是否待生效激活（1=到达生效时间后自动激活）
+    pub must_change_password: i32,       // 是否需要修改密码（1=下次登录必须改密）
+    pub organization_id: Option<i64>,    // 所属组织 ID（NULL=未归属）
+    pub disabled_before_delete: i32,     // 删除前是否已停用（1=恢复后保持停用）
 }
 
 /// 用户实体关系定义
@@ -30,6 +30,79 @@ pub(crate) const PERMISSION_ROUTE_RENAME_MIGRATION_ID: &str =
 pub(crate) const HIDE_BUTTON_PERMISSION_ROUTE_MIGRATION_ID: &str =
     "0006_hide_button_permission_route";
 
+/// 用户软删除迁移的唯一标识符
+/// 对应 migrations/0007_user_soft_delete.sql
+pub(crate) const USER_SOFT_DELETE_MIGRATION_ID: &str = "0007_user_soft_delete";
+
+/// 审计事件表迁移的唯一标识符
+/// 对应 migrations/0008_audit_events.sql
+pub(crate) const AUDIT_EVENTS_MIGRATION_ID: &str = "0008_audit_events";
+
+/// 账号生效时间迁移的唯一标识符
+/// 对应 migrations/0009_user_account_start.sql
+pub(crate) const USER_ACCOUNT_START_MIGRATION_ID: &str = "0009_user_account_start";
+
+/// 强制改密标记迁移的唯一标识符
+/// 对应 migrations/0010_user_must_change_password.sql
+pub(crate) const USER_MUST_CHANGE_PASSWORD_MIGRATION_ID: &str = "0010_user_must_change_password";
+
+/// 组织架构迁移的唯一标识符
+/// 对应 migrations/0011_organizations.sql
+pub(crate) const ORGANIZATIONS_MIGRATION_ID: &str = "0011_organizations";
+
+/// 委派管理员迁移的唯一标识符
+/// 对应 migrations/0012_user_admin_delegations.sql
+pub(crate) const USER_ADMIN_DELEGATIONS_MIGRATION_ID: &str = "0012_user_admin_delegations";
+
+/// 用户设备范围迁移的唯一标识符
+/// 对应 migrations/0013_user_device_scopes.sql
+pub(crate) const USER_DEVICE_SCOPES_MIGRATION_ID: &str = "0013_user_device_scopes";
+
+/// 空间位置树迁移的唯一标识符
+/// 对应 migrations/0014_location_nodes.sql
+pub(crate) const LOCATION_NODES_MIGRATION_ID: &str = "0014_location_nodes";
+
+/// 设备注册表扩展迁移的唯一标识符
+/// 对应 migrations/0015_device_registry_management.sql
+pub(crate) const DEVICE_REGISTRY_MANAGEMENT_MIGRATION_ID: &str =
+    "0015_device_registry_management";
+
+/// 设备模板迁移的唯一标识符
+/// 对应 migrations/0016_device_templates.sql
+pub(crate) const DEVICE_TEMPLATES_MIGRATION_ID: &str = "0016_device_templates";
+
+/// 设备生命周期迁移的唯一标识符
+/// 对应 migrations/0017_device_lifecycle.sql
+pub(crate) const DEVICE_LIFECYCLE_MIGRATION_ID: &str = "0017_device_lifecycle";
+
+/// 设备标签迁移的唯一标识符
+/// 对应 migrations/0018_device_tags.sql
+pub(crate) const DEVICE_TAGS_MIGRATION_ID: &str = "0018_device_tags";
+
+/// 通信网关迁移的唯一标识符
+/// 对应 migrations/0019_gateways.sql
+pub(crate) const GATEWAYS_MIGRATION_ID: &str = "0019_gateways";
+
+/// 网关从站与点位迁移的唯一标识符
+/// 对应 migrations/0020_gateway_points.sql
+pub(crate) const GATEWAY_POINTS_MIGRATION_ID: &str = "0020_gateway_points";
+
+/// 采集参数迁移的唯一标识符
+/// 对应 migrations/0021_acquisition_settings.sql
+pub(crate) const ACQUISITION_SETTINGS_MIGRATION_ID: &str = "0021_acquisition_settings";
+
+/// 点位值变换迁移的唯一标识符
+/// 对应 migrations/0022_point_transforms.sql
+pub(crate) const POINT_TRANSFORMS_MIGRATION_ID: &str = "0022_point_transforms";
+
+/// 虚拟点位迁移的唯一标识符
+/// 对应 migrations/0023_virtual_points.sql
+pub(crate) const VIRTUAL_POINTS_MIGRATION_ID: &str = "0023_virtual_points";
+
+/// 删除前停用标记迁移的唯一标识符
+/// 对应 migrations/0024_user_disabled_before_delete.sql
+pub(crate) const USER_DISABLED_BEFORE_DELETE_MIGRATION_ID: &str = "0024_user_disabled_before_delete";
+
 /// 初始化数据库表结构
 /// 
 /// 执行 migrations/0001_schema.sql 中的所有 CREATE TABLE 语句
@@ -239,6 +312,383 @@ pub(crate) async fn apply_hide_button_permission_route(
     Ok(())
 }
 
+/// 应用用户软删除迁移
+/// 
+/// 为用户表添加 deleted_at / deleted_by 字段
+/// 删除操作改为标记删除，保留历史数据直至清理
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_soft_delete(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_SOFT_DELETE_MIGRATION_ID,
+        user_soft_delete_sql(),
+    )
+    .await
+}
+
+/// 应用审计事件表迁移
+/// 
+/// 创建只追加的 audit_events 哈希链表，并写入审计查看权限策略
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_audit_events(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, AUDIT_EVENTS_MIGRATION_ID, audit_events_sql()).await
+}
+
+/// 应用账号生效时间迁移
+/// 
+/// 为用户表添加 account_start_at / account_activation_pending 字段
+/// 支持到达生效时间后才可登录的账号
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_account_start(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_ACCOUNT_START_MIGRATION_ID,
+        user_account_start_sql(),
+    )
+    .await
+}
+
+/// 应用强制改密标记迁移
+/// 
+/// 为用户表添加 must_change_password 字段
+/// 管理员创建账号或重置密码后，用户需先修改密码才能正常使用系统
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_must_change_password(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_MUST_CHANGE_PASSWORD_MIGRATION_ID,
+        user_must_change_password_sql(),
+    )
+    .await
+}
+
+/// 应用组织架构迁移
+/// 
+/// 创建 organizations 组织树表，为用户表添加 organization_id 归属字段，
+/// 并写入 admin 角色的组织查看与管理策略
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_organizations(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, ORGANIZATIONS_MIGRATION_ID, organizations_sql()).await
+}
+
+/// 应用委派管理员迁移
+/// 
+/// 创建 user_admin_delegations 委派范围表与 user_admin_delegation_roles 可分配角色表
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_admin_delegations(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_ADMIN_DELEGATIONS_MIGRATION_ID,
+        user_admin_delegations_sql(),
+    )
+    .await
+}
+
+/// 应用用户设备范围迁移
+/// 
+/// 创建 device_areas / device_floors 字典表、device_registry 的区域与楼层字段（已由 0014 迁入位置树），
+/// 以及 user_device_scopes 通配标记表与区域、楼层、设备三张明细表
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_device_scopes(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_DEVICE_SCOPES_MIGRATION_ID,
+        user_device_scopes_sql(),
+    )
+    .await
+}
+
+/// 应用空间位置树迁移
+/// 
+/// 创建 location_nodes 位置树表与 device_registry.location_id，
+/// 将 0013 的区域/楼层字典迁入位置树后移除字典表与设备区域/楼层字段
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_location_nodes(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        LOCATION_NODES_MIGRATION_ID,
+        location_nodes_sql(),
+    )
+    .await
+}
+
+/// 应用设备注册表扩展迁移
+/// 
+/// 为 device_registry 添加设备类型、厂商型号、序列号、通信配置引用、启用标记、
+/// JSONB 自由属性与更新时间，并授予 device:view 权限
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_device_registry_management(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        DEVICE_REGISTRY_MANAGEMENT_MIGRATION_ID,
+        device_registry_management_sql(),
+    )
+    .await
+}
+
+/// 应用设备模板迁移
+/// 
+/// 创建设备模板表、模板点位表与设备点位表，并为 device_registry
+/// 添加关联模板与已同步的模板版本
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_device_templates(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        DEVICE_TEMPLATES_MIGRATION_ID,
+        device_templates_sql(),
+    )
+    .await
+}
+
+/// 应用设备生命周期迁移
+/// 
+/// 为 device_registry 添加生命周期状态与进入当前状态的时间，
+/// 并创建生命周期流转历史表
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_device_lifecycle(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        DEVICE_LIFECYCLE_MIGRATION_ID,
+        device_lifecycle_sql(),
+    )
+    .await
+}
+
+/// 应用设备标签迁移
+/// 
+/// 创建设备与设备点位的键值标签表，并为选择器查询建立标签键值索引
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_device_tags(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, DEVICE_TAGS_MIGRATION_ID, device_tags_sql()).await
+}
+
+/// 应用通信网关迁移
+/// 
+/// 创建 Modbus 网关连接配置表
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_gateways(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, GATEWAYS_MIGRATION_ID, gateways_sql()).await
+}
+
+/// 应用网关从站与点位迁移
+/// 
+/// 创建网关从站表与从站寄存器点位表
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_gateway_points(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, GATEWAY_POINTS_MIGRATION_ID, gateway_points_sql()).await
+}
+
+/// 应用采集参数迁移
+/// 
+/// 为网关添加轮询周期与合并间隙，为从站添加可选的从站级轮询周期
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_acquisition_settings(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, ACQUISITION_SETTINGS_MIGRATION_ID, acquisition_settings_sql()).await
+}
+
+/// 应用点位值变换迁移
+/// 
+/// 为网关点位添加值变换配置（JSON 对象，默认为空对象）
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_point_transforms(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, POINT_TRANSFORMS_MIGRATION_ID, point_transforms_sql()).await
+}
+
+/// 应用虚拟点位迁移
+/// 
+/// 为网关点位添加虚拟点位公式，功能码放宽为 0–4（0 为虚拟点位）
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_virtual_points(connection: &mut PgConnection) -> Result<(), AppError> {
+    apply_versioned_migration(connection, VIRTUAL_POINTS_MIGRATION_ID, virtual_points_sql()).await
+}
+
+/// 应用删除前停用标记迁移
+/// 
+/// 为用户表添加 disabled_before_delete 字段，恢复软删除账号时还原删除前的启用状态
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+pub(crate) async fn apply_user_disabled_before_delete(
+    connection: &mut PgConnection,
+) -> Result<(), AppError> {
+    apply_versioned_migration(
+        connection,
+        USER_DISABLED_BEFORE_DELETE_MIGRATION_ID,
+        user_disabled_before_delete_sql(),
+    )
+    .await
+}
+
+/// 按迁移标识执行一次性 SQL 脚本
+/// 
+/// 0007 及之后的迁移统一走此入口：
+/// 检查 app_migrations 日志 → 执行脚本 → 写入执行记录
+/// 
+/// # 参数
+/// * `connection` - 数据库连接
+/// * `migration_id` - 迁移唯一标识符
+/// * `sql` - 迁移脚本内容
+/// 
+/// # 返回
+/// * 成功返回 `Ok(())`
+/// * 失败返回 `AppError`
+async fn apply_versioned_migration(
+    connection: &mut PgConnection,
+    migration_id: &str,
+    sql: &'static str,
+) -> Result<(), AppError> {
+    // 确保迁移日志表存在
+    ensure_migration_log_table(connection).await?;
+
+    // 检查该迁移是否已执行过
+    let applied = query_scalar::<_, i32>("SELECT 1 FROM app_migrations WHERE id = $1 LIMIT 1")
+        .bind(migration_id)
+        .fetch_optional(&mut *connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if applied.is_some() {
+        return Ok(());
+    }
+
+    // 执行迁移 SQL
+    raw_sql(sql)
+        .execute(&mut *connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    // 记录迁移执行状态
+    query(
+        r"
+        INSERT INTO app_migrations (id, applied_at)
+        VALUES ($1, EXTRACT(EPOCH FROM NOW())::BIGINT)
+        ",
+    )
+    .bind(migration_id)
+    .execute(&mut *connection)
+    .await
+    .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(())
+}
+
 /// 确保迁移日志表存在
 /// 
 /// 创建 app_migrations 表用于记录已执行的迁移
@@ -382,3 +832,147 @@ pub(crate) fn permission_route_rename_sql() -> &'static str {
 pub(crate) fn hide_button_permission_route_sql() -> &'static str {
     include_str!("migrations/0006_hide_button_permission_route.sql")
 }
+
+/// 获取用户软删除 SQL 脚本
+/// 
+/// # 返回
+/// * 0007_user_soft_delete.sql 文件内容的静态引用
+pub(crate) fn user_soft_delete_sql() -> &'static str {
+    include_str!("migrations/0007_user_soft_delete.sql")
+}
+
+/// 获取审计事件表 SQL 脚本
+/// 
+/// # 返回
+/// * 0008_audit_events.sql 文件内容的静态引用
+pub(crate) fn audit_events_sql() -> &'static str {
+    include_str!("migrations/0008_audit_events.sql")
+}
+
+/// 获取账号生效时间 SQL 脚本
+/// 
+/// # 返回
+/// * 0009_user_account_start.sql 文件内容的静态引用
+pub(crate) fn user_account_start_sql() -> &'static str {
+    include_str!("migrations/0009_user_account_start.sql")
+}
+
+/// 获取强制改密标记 SQL 脚本
+/// 
+/// # 返回
+/// * 0010_user_must_change_password.sql 文件内容的静态引用
+pub(crate) fn user_must_change_password_sql() -> &'static str {
+    include_str!("migrations/0010_user_must_change_password.sql")
+}
+
+/// 获取组织架构 SQL 脚本
+/// 
+/// # 返回
+/// * 0011_organizations.sql 文件内容的静态引用
+pub(crate) fn organizations_sql() -> &'static str {
+    include_str!("migrations/0011_organizations.sql")
+}
+
+/// 获取委派管理员 SQL 脚本
+/// 
+/// # 返回
+/// * 0012_user_admin_delegations.sql 文件内容的静态引用
+pub(crate) fn user_admin_delegations_sql() -> &'static str {
+    include_str!("migrations/0012_user_admin_delegations.sql")
+}
+
+/// 获取用户设备范围 SQL 脚本
+/// 
+/// # 返回
+/// * 0013_user_device_scopes.sql 文件内容的静态引用
+pub(crate) fn user_device_scopes_sql() -> &'static str {
+    include_str!("migrations/0013_user_device_scopes.sql")
+}
+
+/// 获取空间位置树 SQL 脚本
+/// 
+/// # 返回
+/// * 0014_location_nodes.sql 文件内容的静态引用
+pub(crate) fn location_nodes_sql() -> &'static str {
+    include_str!("migrations/0014_location_nodes.sql")
+}
+
+/// 获取设备注册表扩展 SQL 脚本
+/// 
+/// # 返回
+/// * 0015_device_registry_management.sql 文件内容的静态引用
+pub(crate) fn device_registry_management_sql() -> &'static str {
+    include_str!("migrations/0015_device_registry_management.sql")
+}
+
+/// 获取设备模板 SQL 脚本
+/// 
+/// # 返回
+/// * 0016_device_templates.sql 文件内容的静态引用
+pub(crate) fn device_templates_sql() -> &'static str {
+    include_str!("migrations/0016_device_templates.sql")
+}
+
+/// 获取设备生命周期 SQL 脚本
+/// 
+/// # 返回
+/// * 0017_device_lifecycle.sql 文件内容的静态引用
+pub(crate) fn device_lifecycle_sql() -> &'static str {
+    include_str!("migrations/0017_device_lifecycle.sql")
+}
+
+/// 获取设备标签 SQL 脚本
+/// 
+/// # 返回
+/// * 0018_device_tags.sql 文件内容的静态引用
+pub(crate) fn device_tags_sql() -> &'static str {
+    include_str!("migrations/0018_device_tags.sql")
+}
+
+/// 获取通信网关 SQL 脚本
+/// 
+/// # 返回
+/// * 0019_gateways.sql 文件内容的静态引用
+pub(crate) fn gateways_sql() -> &'static str {
+    include_str!("migrations/0019_gateways.sql")
+}
+
+/// 获取网关从站与点位 SQL 脚本
+/// 
+/// # 返回
+/// * 0020_gateway_points.sql 文件内容的静态引用
+pub(crate) fn gateway_points_sql() -> &'static str {
+    include_str!("migrations/0020_gateway_points.sql")
+}
+
+/// 获取采集参数 SQL 脚本
+/// 
+/// # 返回
+/// * 0021_acquisition_settings.sql 文件内容的静态引用
+pub(crate) fn acquisition_settings_sql() -> &'static str {
+    include_str!("migrations/0021_acquisition_settings.sql")
+}
+
+/// 获取点位值变换 SQL 脚本
+/// 
+/// # 返回
+/// * 0022_point_transforms.sql 文件内容的静态引用
+pub(crate) fn point_transforms_sql() -> &'static str {
+    include_str!("migrations/0022_point_transforms.sql")
+}
+
+/// 获取虚拟点位 SQL 脚本
+/// 
+/// # 返回
+/// * 0023_virtual_points.sql 文件内容的静态引用
+pub(crate) fn virtual_points_sql() -> &'static str {
+    include_str!("migrations/0023_virtual_points.sql")
+}
+
+/// 获取删除前停用标记 SQL 脚本
+/// 
+/// # 返回
+/// * 0024_user_disabled_before_delete.sql 文件内容的静态引用
+pub(crate) fn user_disabled_before_delete_sql() -> &'static str {
+    include_str!("migrations/0024_user_disabled_before_delete.sql")
+}
@@ -0,0 +1,7 @@
+-- 为 users (用户表) 添加软删除标记列：删除操作不再物理移除记录，而是打上删除时间与操作人
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at BIGINT;                 -- 软删除时间戳 (毫秒)，NULL 表示未删除
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_by TEXT;                   -- 执行软删除的操作人员用户名
+
+-- 软删除账号保留原用户名（users.username 唯一约束继续生效），直至被清理任务物理删除
+-- 为鉴权、列表查询中的 deleted_at IS NULL 过滤及按保留期清理提供索引
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);
@@ -0,0 +1,48 @@
+-- 创建 audit_events (审计事件表)：记录所有管理类操作的操作人、命令、目标、前后快照与执行结果
+-- 每条记录通过 prev_hash → hash 串联成哈希链，任何篡改、插入或删除中间记录都会导致链校验失败
+CREATE TABLE IF NOT EXISTS audit_events (
+  id BIGSERIAL PRIMARY KEY,                    -- 自增主键，同时代表链上顺序
+  occurred_at BIGINT NOT NULL,                 -- 事件发生时间戳 (毫秒)
+  actor TEXT NOT NULL,                         -- 操作人用户名
+  command TEXT NOT NULL,                       -- 触发事件的 IPC 命令名 (例如 auth_admin_update_user)
+  target_type TEXT NOT NULL,                   -- 目标对象类型 (例如 user)
+  target_id TEXT,                              -- 目标对象标识 (例如用户 ID)，批量或全局操作可为空
+  request_id TEXT,                             -- 前端 TraceContext 透传的请求 ID，用于与日志关联
+  result TEXT NOT NULL,                        -- 执行结果：success / failure
+  error_message TEXT,                          -- 失败时的错误信息
+  before_json TEXT,                            -- 操作前快照 (JSON 文本，按写入原文参与哈希)
+  after_json TEXT,                             -- 操作后快照 (JSON 文本)
+  diff_json TEXT,                              -- 前后差异 (JSON 文本，仅包含变化字段)
+  prev_hash TEXT NOT NULL,                     -- 上一条记录的哈希值，首条记录为 64 个 0
+  hash TEXT NOT NULL UNIQUE,                   -- 本条记录的 SHA-256 哈希 (十六进制)
+  CONSTRAINT audit_events_result_check CHECK (result IN ('success', 'failure'))
+);
+
+-- 查询索引：按操作人、命令、目标、时间与请求 ID 检索
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor ON audit_events(actor);
+CREATE INDEX IF NOT EXISTS idx_audit_events_command ON audit_events(command);
+CREATE INDEX IF NOT EXISTS idx_audit_events_target ON audit_events(target_type, target_id);
+CREATE INDEX IF NOT EXISTS idx_audit_events_occurred_at ON audit_events(occurred_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_request_id ON audit_events(request_id);
+
+-- 数据库层面的只追加约束：拒绝对审计记录执行 UPDATE / DELETE / TRUNCATE
+CREATE OR REPLACE FUNCTION audit_events_reject_mutation() RETURNS trigger AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_audit_events_append_only ON audit_events;
+CREATE TRIGGER trg_audit_events_append_only
+  BEFORE UPDATE OR DELETE ON audit_events
+  FOR EACH ROW EXECUTE FUNCTION audit_events_reject_mutation();
+
+DROP TRIGGER IF EXISTS trg_audit_events_no_truncate ON audit_events;
+CREATE TRIGGER trg_audit_events_no_truncate
+  BEFORE TRUNCATE ON audit_events
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_events_reject_mutation();
+
+-- 审计查询与链校验权限：仅 admin 角色可访问
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+  ('p', 'admin', 'audit', 'view', '', '', '')            -- 策略: admin 角色具有 audit(审计日志) 的 view(查看) 权限
+ON CONFLICT (ptype, v0, v1, v2, v3, v4, v5) DO NOTHING;
@@ -0,0 +1,6 @@
+-- 为 users (用户表) 添加账号生效时间：支持提前登记、到指定日期才生效的账号（如下月起租的租户）
+ALTER TABLE users ADD COLUMN IF NOT EXISTS account_start_at BIGINT;                               -- 账号生效时间戳 (毫秒)，NULL 表示立即生效
+ALTER TABLE users ADD COLUMN IF NOT EXISTS account_activation_pending INTEGER NOT NULL DEFAULT 0; -- 是否待生效激活 (1=到达生效时间后自动激活, 0=否)
+
+-- 为启动补偿任务按生效时间批量激活待生效账号提供索引
+CREATE INDEX IF NOT EXISTS idx_users_account_start_at ON users(account_start_at);
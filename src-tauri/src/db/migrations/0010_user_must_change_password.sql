@@ -0,0 +1,2 @@
+-- 为 users (用户表) 添加强制改密标记：管理员创建账号或重置密码后，用户下次登录必须先修改密码
+ALTER TABLE users ADD COLUMN IF NOT EXISTS must_change_password INTEGER NOT NULL DEFAULT 0; -- 是否需要修改密码 (1=是, 0=否)
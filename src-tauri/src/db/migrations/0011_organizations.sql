@@ -0,0 +1,29 @@
+-- 创建 organizations (组织架构表)：公司 → 场所 → 部门等多级组织树，用户通过 users.organization_id 归属到组织
+CREATE TABLE IF NOT EXISTS organizations (
+  id BIGSERIAL PRIMARY KEY,                          -- 自增主键
+  parent_id BIGINT REFERENCES organizations(id),     -- 上级组织 ID，NULL 表示根节点
+  name TEXT NOT NULL,                                -- 组织名称 (同一上级下唯一)
+  org_type TEXT NOT NULL,                            -- 组织类型 (例如 company / site / department / shop / contractor)
+  contact_name TEXT,                                 -- 联系人
+  contact_phone TEXT,                                -- 联系电话
+  contract_number TEXT,                              -- 合同编号
+  remark TEXT,                                       -- 备注
+  created_at BIGINT NOT NULL,                        -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                        -- 更新时间戳 (毫秒)
+  created_by TEXT NOT NULL                           -- 创建人用户名
+);
+
+-- 同一上级下组织名称唯一 (根节点的 parent_id 视为 0)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_organizations_parent_name
+  ON organizations ((COALESCE(parent_id, 0)), name);
+CREATE INDEX IF NOT EXISTS idx_organizations_parent_id ON organizations(parent_id);
+
+-- 为 users (用户表) 添加所属组织
+ALTER TABLE users ADD COLUMN IF NOT EXISTS organization_id BIGINT REFERENCES organizations(id); -- 所属组织 ID，NULL 表示未归属
+CREATE INDEX IF NOT EXISTS idx_users_organization_id ON users(organization_id);
+
+-- 组织管理权限：仅 admin 角色可查看与维护组织架构
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+  ('p', 'admin', 'organization', 'view', '', '', ''),    -- 策略: admin 角色具有 organization(组织架构) 的 view(查看) 权限
+  ('p', 'admin', 'organization', 'manage', '', '', '')   -- 策略: admin 角色具有 organization(组织架构) 的 manage(管理) 权限
+ON CONFLICT (ptype, v0, v1, v2, v3, v4, v5) DO NOTHING;
@@ -0,0 +1,20 @@
+-- 创建 user_admin_delegations (委派管理员表)：非 admin 用户在限定范围内管理账号 (例如物业经理管理本楼宇的租户)
+-- 管理范围为组织子树与本人创建的用户两者之并；委派人不能管理本人账号及其他委派人
+CREATE TABLE IF NOT EXISTS user_admin_delegations (
+  user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,  -- 委派人用户 ID (物理清理用户时级联删除)
+  organization_id BIGINT REFERENCES organizations(id) ON DELETE SET NULL, -- 可管理的组织子树根节点，NULL 表示不按组织授权
+  manage_created_users INTEGER NOT NULL DEFAULT 0,                   -- 是否可管理本人创建的用户 (1=是, 0=否)
+  created_at BIGINT NOT NULL,                                        -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                                        -- 更新时间戳 (毫秒)
+  created_by TEXT NOT NULL                                           -- 授权人用户名
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_admin_delegations_organization_id
+  ON user_admin_delegations(organization_id);
+
+-- 创建 user_admin_delegation_roles (委派可分配角色表)：委派人只能为范围内用户分配其中的角色
+CREATE TABLE IF NOT EXISTS user_admin_delegation_roles (
+  user_id BIGINT NOT NULL REFERENCES user_admin_delegations(user_id) ON DELETE CASCADE, -- 委派人用户 ID
+  role TEXT NOT NULL,                                                                   -- 可分配的角色
+  PRIMARY KEY (user_id, role)
+);
@@ -0,0 +1,56 @@
+-- 创建 device_areas (设备区域字典表)：用户设备范围中引用的区域编码 (例如 A01)
+CREATE TABLE IF NOT EXISTS device_areas (
+  area_code TEXT PRIMARY KEY,                        -- 区域编码
+  area_name TEXT NOT NULL,                           -- 区域名称
+  created_at BIGINT NOT NULL                         -- 创建时间戳 (毫秒)
+);
+
+-- 创建 device_floors (设备楼层字典表)：用户设备范围中引用的楼层编码 (例如 F03)
+CREATE TABLE IF NOT EXISTS device_floors (
+  floor_code TEXT PRIMARY KEY,                       -- 楼层编码
+  floor_name TEXT NOT NULL,                          -- 楼层名称
+  created_at BIGINT NOT NULL                         -- 创建时间戳 (毫秒)
+);
+
+-- 为 device_registry (设备注册表) 添加所在区域与楼层，设备范围按此判定区域/楼层授权
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS area_code TEXT
+  REFERENCES device_areas(area_code) ON UPDATE CASCADE ON DELETE SET NULL;  -- 所在区域编码，NULL 表示未设置
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS floor_code TEXT
+  REFERENCES device_floors(floor_code) ON UPDATE CASCADE ON DELETE SET NULL; -- 所在楼层编码，NULL 表示未设置
+CREATE INDEX IF NOT EXISTS idx_device_registry_area_code ON device_registry(area_code);
+CREATE INDEX IF NOT EXISTS idx_device_registry_floor_code ON device_registry(floor_code);
+
+-- 创建 user_device_scopes (用户设备范围表)：每个用户一行，保存通配授权标记
+CREATE TABLE IF NOT EXISTS user_device_scopes (
+  user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE, -- 用户 ID (物理清理用户时级联删除)
+  all_areas INTEGER NOT NULL DEFAULT 0,                              -- 是否可访问所有区域内的设备 (1=是, 0=否)
+  all_floors INTEGER NOT NULL DEFAULT 0,                             -- 是否可访问所有楼层内的设备 (1=是, 0=否)
+  all_devices INTEGER NOT NULL DEFAULT 0,                            -- 是否可访问所有设备 (1=是, 0=否)
+  updated_at BIGINT NOT NULL,                                        -- 更新时间戳 (毫秒)
+  updated_by TEXT NOT NULL                                           -- 最后修改人用户名
+);
+
+-- 创建 user_device_scope_areas / floors / devices (用户设备范围明细表)：显式授权的区域、楼层与设备
+-- 被引用的区域、楼层或设备删除时明细级联删除，编码变更时级联更新
+CREATE TABLE IF NOT EXISTS user_device_scope_areas (
+  user_id BIGINT NOT NULL REFERENCES user_device_scopes(user_id) ON DELETE CASCADE,                  -- 用户 ID
+  area_code TEXT NOT NULL REFERENCES device_areas(area_code) ON UPDATE CASCADE ON DELETE CASCADE,    -- 区域编码
+  PRIMARY KEY (user_id, area_code)
+);
+
+CREATE TABLE IF NOT EXISTS user_device_scope_floors (
+  user_id BIGINT NOT NULL REFERENCES user_device_scopes(user_id) ON DELETE CASCADE,                  -- 用户 ID
+  floor_code TEXT NOT NULL REFERENCES device_floors(floor_code) ON UPDATE CASCADE ON DELETE CASCADE, -- 楼层编码
+  PRIMARY KEY (user_id, floor_code)
+);
+
+CREATE TABLE IF NOT EXISTS user_device_scope_devices (
+  user_id BIGINT NOT NULL REFERENCES user_device_scopes(user_id) ON DELETE CASCADE,                  -- 用户 ID
+  device_id TEXT NOT NULL REFERENCES device_registry(device_id) ON UPDATE CASCADE ON DELETE CASCADE, -- 设备标识
+  PRIMARY KEY (user_id, device_id)
+);
+
+-- 设备管理权限：admin 角色可查看全部设备，并维护用户设备范围
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+  ('p', 'admin', 'device', 'manage', '', '', '')         -- 策略: admin 角色具有 device(设备资源) 的 manage(管理) 权限
+ON CONFLICT (ptype, v0, v1, v2, v3, v4, v5) DO NOTHING;
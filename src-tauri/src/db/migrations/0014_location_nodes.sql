@@ -0,0 +1,59 @@
+-- 创建 location_nodes (空间位置树表)：场所 → 楼栋 → 区域 → 楼层 → 房间，设备挂载到位置节点
+CREATE TABLE IF NOT EXISTS location_nodes (
+  id BIGSERIAL PRIMARY KEY,                          -- 自增主键
+  parent_id BIGINT REFERENCES location_nodes(id),    -- 上级位置 ID，NULL 表示根节点
+  level TEXT NOT NULL
+    CHECK (level IN ('site', 'building', 'area', 'floor', 'room')), -- 位置层级 (下级层级必须比上级更深)
+  code TEXT NOT NULL UNIQUE,                         -- 位置编码 (全局唯一，例如 A01 / F03)
+  name TEXT NOT NULL,                                -- 位置名称
+  sort_order INTEGER NOT NULL DEFAULT 0,             -- 同级排序号 (升序)
+  remark TEXT,                                       -- 备注
+  created_at BIGINT NOT NULL,                        -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                        -- 更新时间戳 (毫秒)
+  created_by TEXT NOT NULL                           -- 创建人用户名
+);
+CREATE INDEX IF NOT EXISTS idx_location_nodes_parent_id ON location_nodes(parent_id);
+CREATE INDEX IF NOT EXISTS idx_location_nodes_level ON location_nodes(level);
+
+-- 为 device_registry (设备注册表) 添加所在位置节点
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS location_id BIGINT
+  REFERENCES location_nodes(id);                     -- 所在位置 ID，NULL 表示未设置
+CREATE INDEX IF NOT EXISTS idx_device_registry_location_id ON device_registry(location_id);
+
+-- 将 0013 的区域/楼层字典迁入位置树 (作为根节点，后续可移动到楼栋之下)
+INSERT INTO location_nodes (level, code, name, created_at, updated_at, created_by)
+SELECT 'area', area_code, area_name, created_at, created_at, 'migration' FROM device_areas
+ON CONFLICT (code) DO NOTHING;
+INSERT INTO location_nodes (level, code, name, created_at, updated_at, created_by)
+SELECT 'floor', floor_code, floor_name, created_at, created_at, 'migration' FROM device_floors
+ON CONFLICT (code) DO NOTHING;
+
+-- 设备位置优先取楼层，其次取区域
+UPDATE device_registry d
+SET location_id = n.id
+FROM location_nodes n
+WHERE d.location_id IS NULL AND n.code = COALESCE(d.floor_code, d.area_code);
+
+-- 用户设备范围中的区域/楼层明细改为引用位置编码，清理无法对应的明细
+DELETE FROM user_device_scope_areas s
+WHERE NOT EXISTS (SELECT 1 FROM location_nodes n WHERE n.code = s.area_code AND n.level = 'area');
+DELETE FROM user_device_scope_floors s
+WHERE NOT EXISTS (SELECT 1 FROM location_nodes n WHERE n.code = s.floor_code AND n.level = 'floor');
+ALTER TABLE user_device_scope_areas DROP CONSTRAINT IF EXISTS user_device_scope_areas_area_code_fkey;
+ALTER TABLE user_device_scope_areas ADD CONSTRAINT user_device_scope_areas_area_code_fkey
+  FOREIGN KEY (area_code) REFERENCES location_nodes(code) ON UPDATE CASCADE ON DELETE CASCADE;
+ALTER TABLE user_device_scope_floors DROP CONSTRAINT IF EXISTS user_device_scope_floors_floor_code_fkey;
+ALTER TABLE user_device_scope_floors ADD CONSTRAINT user_device_scope_floors_floor_code_fkey
+  FOREIGN KEY (floor_code) REFERENCES location_nodes(code) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- 移除被位置树取代的区域/楼层字段与字典表
+ALTER TABLE device_registry DROP COLUMN IF EXISTS area_code;
+ALTER TABLE device_registry DROP COLUMN IF EXISTS floor_code;
+DROP TABLE IF EXISTS device_areas;
+DROP TABLE IF EXISTS device_floors;
+
+-- 位置管理权限：仅 admin 角色可查看与维护空间位置树
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+  ('p', 'admin', 'location', 'view', '', '', ''),        -- 策略: admin 角色具有 location(空间位置) 的 view(查看) 权限
+  ('p', 'admin', 'location', 'manage', '', '', '')       -- 策略: admin 角色具有 location(空间位置) 的 manage(管理) 权限
+ON CONFLICT (ptype, v0, v1, v2, v3, v4, v5) DO NOTHING;
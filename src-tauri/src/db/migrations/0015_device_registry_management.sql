@@ -0,0 +1,25 @@
+-- 扩展 device_registry (设备注册表)：设备类型、厂商型号、序列号、通信配置引用、启用标记与自由属性
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS device_type TEXT NOT NULL DEFAULT 'generic'; -- 设备类型 (例如 meter / chiller / gateway)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS manufacturer TEXT;                          -- 厂商
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS model TEXT;                                 -- 型号
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS serial_number TEXT;                         -- 序列号 (同一厂商下唯一)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS comm_config_ref TEXT;                       -- 通信配置引用 (例如 modbus:gateway-01/3)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS enabled INTEGER NOT NULL DEFAULT 1;         -- 是否启用 (1=启用, 0=停用)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS attributes JSONB NOT NULL DEFAULT '{}'::JSONB; -- 自由属性 (JSON 对象)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS updated_at BIGINT NOT NULL DEFAULT 0;      -- 更新时间戳 (毫秒)
+
+-- 存量设备的更新时间取注册时间
+UPDATE device_registry SET updated_at = registered_at WHERE updated_at = 0;
+
+-- 同一厂商下序列号唯一 (未填写序列号的设备不受约束)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_device_registry_serial_number
+  ON device_registry ((COALESCE(manufacturer, '')), serial_number)
+  WHERE serial_number IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_device_registry_device_type ON device_registry(device_type);
+CREATE INDEX IF NOT EXISTS idx_device_registry_enabled ON device_registry(enabled);
+
+-- 设备查看权限：admin 与 operator 角色可查看设备 (operator 仍受用户设备范围限制)
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+  ('p', 'admin', 'device', 'view', '', '', ''),          -- 策略: admin 角色具有 device(设备资源) 的 view(查看) 权限
+  ('p', 'operator', 'device', 'view', '', '', '')        -- 策略: operator 角色具有 device(设备资源) 的 view(查看) 权限
+ON CONFLICT (ptype, v0, v1, v2, v3, v4, v5) DO NOTHING;
@@ -0,0 +1,61 @@
+-- 创建 device_templates (设备模板表)：同一厂商型号的设备共享的点位表与默认轮询参数
+CREATE TABLE IF NOT EXISTS device_templates (
+  id BIGSERIAL PRIMARY KEY,                            -- 自增主键 ID
+  code TEXT NOT NULL UNIQUE,                           -- 模板编码 (全局唯一，导入导出时作为模板标识)
+  name TEXT NOT NULL,                                  -- 模板名称
+  manufacturer TEXT,                                   -- 厂商
+  model TEXT,                                          -- 型号
+  device_type TEXT NOT NULL DEFAULT 'generic',         -- 设备类型 (由模板创建设备时的默认类型)
+  description TEXT,                                    -- 说明
+  poll_interval_ms INTEGER NOT NULL DEFAULT 1000,      -- 默认轮询周期 (毫秒)
+  poll_timeout_ms INTEGER NOT NULL DEFAULT 1000,       -- 默认请求超时 (毫秒)
+  poll_retries INTEGER NOT NULL DEFAULT 3,             -- 默认失败重试次数
+  version INTEGER NOT NULL DEFAULT 1,                  -- 模板版本 (每次修改加 1，设备记录已同步的版本)
+  created_at BIGINT NOT NULL,                          -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                          -- 更新时间戳 (毫秒)
+  created_by TEXT NOT NULL                             -- 创建人用户名
+);
+
+-- 创建 device_template_points (模板点位表)：点位名称、单位、数据类型、寄存器映射与缩放
+CREATE TABLE IF NOT EXISTS device_template_points (
+  id BIGSERIAL PRIMARY KEY,                                                          -- 自增主键 ID
+  template_id BIGINT NOT NULL REFERENCES device_templates(id) ON DELETE CASCADE,     -- 所属模板 ID
+  point_key TEXT NOT NULL,                                                           -- 点位标识 (模板内唯一)
+  name TEXT NOT NULL,                                                                -- 点位名称
+  unit TEXT,                                                                         -- 工程单位 (例如 kW、℃)
+  data_type TEXT NOT NULL,                                                           -- 数据类型 (bool / int16 / uint16 / int32 / uint32 / int64 / uint64 / float32 / float64)
+  register_type TEXT NOT NULL,                                                       -- 寄存器类型 (coil / discrete_input / holding_register / input_register)
+  address INTEGER NOT NULL CHECK (address BETWEEN 0 AND 65535),                      -- 起始寄存器地址 (0 起始)
+  byte_order TEXT NOT NULL DEFAULT 'abcd',                                           -- 多寄存器字节序 (abcd / badc / cdab / dcba)
+  scale DOUBLE PRECISION NOT NULL DEFAULT 1,                                         -- 缩放系数 (工程值 = 原始值 * scale + offset)
+  value_offset DOUBLE PRECISION NOT NULL DEFAULT 0,                                  -- 偏移量
+  access TEXT NOT NULL DEFAULT 'read',                                               -- 访问方式 (read / read_write)
+  sort_order INTEGER NOT NULL DEFAULT 0,                                             -- 排序号 (点位表中的顺序)
+  UNIQUE (template_id, point_key)
+);
+
+-- 为 device_registry (设备注册表) 添加关联模板与已同步的模板版本
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS template_id BIGINT
+  REFERENCES device_templates(id);                   -- 关联模板 ID，NULL 表示未关联模板 (仍有关联设备的模板不能删除)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS template_version INTEGER; -- 已同步的模板版本
+CREATE INDEX IF NOT EXISTS idx_device_registry_template_id ON device_registry(template_id);
+
+-- 创建 device_points (设备点位表)：由模板点位与设备级覆盖合成的生效点位
+CREATE TABLE IF NOT EXISTS device_points (
+  id BIGSERIAL PRIMARY KEY,                                                                            -- 自增主键 ID
+  device_id TEXT NOT NULL REFERENCES device_registry(device_id) ON UPDATE CASCADE ON DELETE CASCADE,   -- 设备标识
+  point_key TEXT NOT NULL,                                                                             -- 点位标识 (与模板点位对应)
+  name TEXT NOT NULL,                                                                                  -- 点位名称
+  unit TEXT,                                                                                           -- 工程单位
+  data_type TEXT NOT NULL,                                                                             -- 数据类型
+  register_type TEXT NOT NULL,                                                                         -- 寄存器类型
+  address INTEGER NOT NULL CHECK (address BETWEEN 0 AND 65535),                                        -- 起始寄存器地址
+  byte_order TEXT NOT NULL DEFAULT 'abcd',                                                             -- 多寄存器字节序
+  scale DOUBLE PRECISION NOT NULL DEFAULT 1,                                                           -- 缩放系数
+  value_offset DOUBLE PRECISION NOT NULL DEFAULT 0,                                                    -- 偏移量
+  access TEXT NOT NULL DEFAULT 'read',                                                                 -- 访问方式
+  sort_order INTEGER NOT NULL DEFAULT 0,                                                               -- 排序号
+  overrides JSONB NOT NULL DEFAULT '{}'::JSONB,                                                        -- 设备级覆盖字段 (JSON 对象，同步模板时保留)
+  updated_at BIGINT NOT NULL,                                                                          -- 更新时间戳 (毫秒)
+  UNIQUE (device_id, point_key)
+);
@@ -0,0 +1,22 @@
+-- 为 device_registry (设备注册表) 添加生命周期状态
+-- 存量设备视为已投运 (in_service)，之后新建的设备默认处于规划状态 (planned)
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS lifecycle_state TEXT NOT NULL DEFAULT 'in_service'
+  CHECK (lifecycle_state IN ('planned', 'installed', 'commissioned', 'in_service', 'maintenance', 'decommissioned')); -- 生命周期状态
+ALTER TABLE device_registry ALTER COLUMN lifecycle_state SET DEFAULT 'planned';
+ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS lifecycle_changed_at BIGINT; -- 进入当前状态的时间戳 (毫秒)
+UPDATE device_registry SET lifecycle_changed_at = registered_at WHERE lifecycle_changed_at IS NULL; -- 存量设备取注册时间
+ALTER TABLE device_registry ALTER COLUMN lifecycle_changed_at SET DEFAULT EXTRACT(EPOCH FROM NOW())::BIGINT * 1000; -- 未指定时取写入时刻 (种子数据)
+ALTER TABLE device_registry ALTER COLUMN lifecycle_changed_at SET NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_device_registry_lifecycle_state ON device_registry(lifecycle_state);
+
+-- 创建 device_lifecycle_history (设备生命周期流转历史表)：记录每次状态流转的操作人与原因
+CREATE TABLE IF NOT EXISTS device_lifecycle_history (
+  id BIGSERIAL PRIMARY KEY,                                                                            -- 自增主键 ID
+  device_id TEXT NOT NULL REFERENCES device_registry(device_id) ON UPDATE CASCADE ON DELETE CASCADE,   -- 设备标识
+  from_state TEXT NOT NULL,                                                                            -- 流转前状态
+  to_state TEXT NOT NULL,                                                                              -- 流转后状态
+  reason TEXT NOT NULL,                                                                                -- 流转原因
+  actor TEXT NOT NULL,                                                                                 -- 操作人用户名
+  changed_at BIGINT NOT NULL                                                                           -- 流转时间戳 (毫秒)
+);
+CREATE INDEX IF NOT EXISTS idx_device_lifecycle_history_device ON device_lifecycle_history(device_id, changed_at DESC, id DESC);
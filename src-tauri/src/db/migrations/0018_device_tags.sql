@@ -0,0 +1,17 @@
+-- 创建 device_tags (设备标签表)：设备与设备点位上的键值标签，用于不依赖位置树的临时分组与选择器查询
+-- point_key 为空字符串表示设备级标签，否则为该设备点位上的标签 (点位随模板同步重建时保留仍存在点位的标签)
+CREATE TABLE IF NOT EXISTS device_tags (
+  device_id TEXT NOT NULL REFERENCES device_registry(device_id) ON UPDATE CASCADE ON DELETE CASCADE,   -- 设备标识
+  point_key TEXT NOT NULL DEFAULT '',                                                                  -- 点位标识 (空字符串表示设备级标签)
+  tag_key TEXT NOT NULL,                                                                               -- 标签键 (小写)
+  tag_value TEXT NOT NULL DEFAULT '',                                                                  -- 标签值 (空字符串表示仅标记)
+  updated_at BIGINT NOT NULL,                                                                          -- 更新时间戳 (毫秒)
+  PRIMARY KEY (device_id, point_key, tag_key)
+);
+
+-- 选择器按标签键值反查设备：设备级标签与点位标签分别建立部分索引
+CREATE INDEX IF NOT EXISTS idx_device_tags_device_key_value ON device_tags(tag_key, tag_value, device_id)
+  WHERE point_key = '';
+CREATE INDEX IF NOT EXISTS idx_device_tags_point_key_value ON device_tags(tag_key, tag_value, device_id)
+  WHERE point_key <> '';
+
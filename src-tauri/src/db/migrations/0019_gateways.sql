@@ -0,0 +1,23 @@
+-- 创建 gateways (通信网关表)：Modbus 网关的连接配置 (TCP 网关的地址端口、串口网关的串口参数、帧格式与超时)
+-- code 作为 Modbus 长连接的网关标识；TCP 网关使用 host/port，串口网关使用 serial_port 及串口参数
+CREATE TABLE IF NOT EXISTS gateways (
+  id BIGSERIAL PRIMARY KEY,                                                  -- 自增主键 ID
+  code TEXT NOT NULL UNIQUE,                                                 -- 网关编码 (全局唯一，作为长连接的网关标识)
+  name TEXT NOT NULL,                                                        -- 网关名称
+  transport TEXT NOT NULL DEFAULT 'tcp' CHECK (transport IN ('tcp', 'serial')), -- 传输方式 (tcp / serial)
+  host TEXT,                                                                 -- IP 地址或主机名 (TCP 网关)
+  port INTEGER CHECK (port BETWEEN 1 AND 65535),                             -- TCP 端口 (TCP 网关)
+  serial_port TEXT,                                                          -- 串口路径 (串口网关，例如 /dev/ttyUSB0、COM3)
+  baud_rate INTEGER,                                                         -- 波特率 (串口网关)
+  data_bits INTEGER,                                                         -- 数据位 (串口网关)
+  parity TEXT,                                                               -- 校验位 (串口网关，none / even / odd)
+  stop_bits INTEGER,                                                         -- 停止位 (串口网关)
+  framing TEXT NOT NULL DEFAULT 'mbap',                                      -- 帧格式 (TCP：mbap / rtu / ascii；串口：rtu / ascii)
+  connect_timeout_ms INTEGER NOT NULL DEFAULT 3000,                          -- 建连超时 (毫秒)
+  request_timeout_ms INTEGER NOT NULL DEFAULT 1000,                          -- 请求超时 (毫秒)
+  enabled BOOLEAN NOT NULL DEFAULT TRUE,                                     -- 是否启用
+  remark TEXT,                                                               -- 备注
+  created_at BIGINT NOT NULL,                                                -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                                                -- 更新时间戳 (毫秒)
+  created_by TEXT NOT NULL                                                   -- 创建人用户名
+);
@@ -0,0 +1,40 @@
+-- 创建 gateway_slaves (网关从站表)：网关下的 Modbus 从站 (单元号与可选的地址范围)
+-- 同一网关下单元号唯一；address_min/address_max 为空表示不限制点位地址
+CREATE TABLE IF NOT EXISTS gateway_slaves (
+  id BIGSERIAL PRIMARY KEY,                                                  -- 自增主键 ID
+  gateway_id BIGINT NOT NULL REFERENCES gateways(id) ON DELETE CASCADE,      -- 所属网关 ID (网关删除时级联删除)
+  unit_id INTEGER NOT NULL CHECK (unit_id BETWEEN 1 AND 247),                -- 从站单元号 (1–247)
+  name TEXT NOT NULL,                                                        -- 从站名称
+  address_min INTEGER CHECK (address_min BETWEEN 0 AND 65535),               -- 点位地址下限 (含，为空表示不限制)
+  address_max INTEGER CHECK (address_max BETWEEN 0 AND 65535),               -- 点位地址上限 (含，为空表示不限制)
+  enabled BOOLEAN NOT NULL DEFAULT TRUE,                                     -- 是否启用
+  remark TEXT,                                                               -- 备注
+  created_at BIGINT NOT NULL,                                                -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                                                -- 更新时间戳 (毫秒)
+  CONSTRAINT gateway_slaves_unit_key UNIQUE (gateway_id, unit_id),           -- 同一网关下单元号唯一
+  CONSTRAINT gateway_slaves_address_range CHECK (address_min IS NULL OR address_max IS NULL OR address_min <= address_max)
+);
+
+-- 创建 gateway_points (网关点位表)：从站的寄存器点位 (功能码、起始地址、数量、数据类型与字节序)
+-- 数据类型与字节序以小写存储；数量须与数据类型匹配 (由服务层校验)
+CREATE TABLE IF NOT EXISTS gateway_points (
+  id BIGSERIAL PRIMARY KEY,                                                  -- 自增主键 ID
+  slave_id BIGINT NOT NULL REFERENCES gateway_slaves(id) ON DELETE CASCADE,  -- 所属从站 ID (从站删除时级联删除)
+  point_key TEXT NOT NULL,                                                   -- 点位标识 (从站内唯一)
+  name TEXT NOT NULL,                                                        -- 点位名称
+  function_code INTEGER NOT NULL CHECK (function_code IN (1, 2, 3, 4)),      -- 读取功能码 (1 线圈 / 2 离散输入 / 3 保持寄存器 / 4 输入寄存器)
+  address INTEGER NOT NULL CHECK (address BETWEEN 0 AND 65535),              -- 起始地址
+  count INTEGER NOT NULL CHECK (count BETWEEN 1 AND 125),                    -- 寄存器 (或线圈) 数量
+  data_type TEXT NOT NULL CHECK (data_type IN ('bool', 'u16', 'i16', 'u32', 'i32', 'f32', 'f64', 'string')), -- 数据类型
+  byte_order TEXT NOT NULL CHECK (byte_order IN ('ab', 'ba', 'abcd', 'cdab', 'badc', 'dcba')), -- 字节序
+  access TEXT NOT NULL DEFAULT 'read' CHECK (access IN ('read', 'read_write')), -- 访问方式 (只读 / 读写)
+  unit TEXT,                                                                 -- 工程单位
+  sort_order INTEGER NOT NULL DEFAULT 0,                                     -- 排序号
+  created_at BIGINT NOT NULL,                                                -- 创建时间戳 (毫秒)
+  updated_at BIGINT NOT NULL,                                                -- 更新时间戳 (毫秒)
+  CONSTRAINT gateway_points_key UNIQUE (slave_id, point_key),                -- 同一从站下点位标识唯一
+  CONSTRAINT gateway_points_address_range CHECK (address + count <= 65536)   -- 地址范围不超过 65535
+);
+
+-- 点位按从站与排序号查询
+CREATE INDEX IF NOT EXISTS idx_gateway_points_slave ON gateway_points (slave_id, sort_order);
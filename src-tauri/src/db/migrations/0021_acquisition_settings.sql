@@ -0,0 +1,6 @@
+-- 为 gateways (通信网关表) 添加采集参数：默认轮询周期与相邻地址合并的间隙容忍度
+ALTER TABLE gateways ADD COLUMN IF NOT EXISTS poll_interval_ms INTEGER NOT NULL DEFAULT 1000 CHECK (poll_interval_ms BETWEEN 100 AND 3600000); -- 轮询周期 (毫秒，从站未单独配置时使用)
+ALTER TABLE gateways ADD COLUMN IF NOT EXISTS poll_max_gap INTEGER NOT NULL DEFAULT 0 CHECK (poll_max_gap BETWEEN 0 AND 125); -- 合并读取时允许跨越的最大空闲地址数
+
+-- 为 gateway_slaves (网关从站表) 添加从站级轮询周期：为空时沿用网关的轮询周期
+ALTER TABLE gateway_slaves ADD COLUMN IF NOT EXISTS poll_interval_ms INTEGER CHECK (poll_interval_ms BETWEEN 100 AND 3600000); -- 从站轮询周期 (毫秒，为空表示沿用网关)
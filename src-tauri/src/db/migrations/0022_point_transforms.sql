@@ -0,0 +1,3 @@
+-- 为 gateway_points (网关点位表) 添加值变换配置：缩放与偏移、位域提取、枚举映射、单位换算、限幅与死区
+-- 配置以 JSON 对象保存 (字段由服务层校验)，空对象表示不做变换
+ALTER TABLE gateway_points ADD COLUMN IF NOT EXISTS transform JSONB NOT NULL DEFAULT '{}'::JSONB; -- 值变换配置 (JSON 对象)
@@ -0,0 +1,11 @@
+-- 为 gateway_points (网关点位表) 添加虚拟点位：功能码 0 表示按公式由同一网关内其他点位计算的虚拟点位
+-- 虚拟点位的公式保存在 expression 中 (语法由服务层校验)，起始地址、数量与字节序为固定值，不参与读取计划
+ALTER TABLE gateway_points ADD COLUMN IF NOT EXISTS expression TEXT; -- 虚拟点位公式 (物理点位为空)
+
+-- 功能码放宽为 0–4 (0 为虚拟点位)
+ALTER TABLE gateway_points DROP CONSTRAINT IF EXISTS gateway_points_function_code_check;
+ALTER TABLE gateway_points ADD CONSTRAINT gateway_points_function_code_check CHECK (function_code IN (0, 1, 2, 3, 4));
+
+-- 虚拟点位必须有公式，物理点位不能有公式
+ALTER TABLE gateway_points DROP CONSTRAINT IF EXISTS gateway_points_expression_check;
+ALTER TABLE gateway_points ADD CONSTRAINT gateway_points_expression_check CHECK ((function_code = 0) = (expression IS NOT NULL));
@@ -0,0 +1,5 @@
+-- 为 users (用户表) 添加删除前停用标记：软删除会停用账号，恢复时据此还原删除前的启用状态
+ALTER TABLE users ADD COLUMN IF NOT EXISTS disabled_before_delete INTEGER NOT NULL DEFAULT 0; -- 删除前是否已被停用 (1=是, 0=否)，仅对已软删除的账号有意义
+
+-- 存量的已删除账号无法得知删除前的状态，按已停用处理，恢复后需管理员显式启用
+UPDATE users SET disabled_before_delete = 1 WHERE deleted_at IS NOT NULL;
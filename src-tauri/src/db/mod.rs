@@ -173,6 +173,23 @@ pub async fn connect_orm_async() -> Result<DatabaseConnection, AppError> {
 /// 4. 执行用户注册扩展迁移
 /// 5. 执行权限路由重命名迁移
 /// 6. 执行隐藏按钮权限路由迁移
+/// 7. 执行用户软删除迁移
+/// 8. 执行审计事件表迁移
+/// 9. 执行账号生效时间迁移
+/// 10. 执行强制改密标记迁移
+/// 11. 执行组织架构迁移
+/// 12. 执行委派管理员迁移
+/// 13. 执行用户设备范围迁移
+/// 14. 执行空间位置树迁移
+/// 15. 执行设备注册表扩展迁移
+/// 16. 执行设备模板迁移
+/// 17. 执行设备生命周期迁移
+/// 18. 执行设备标签迁移
+/// 19. 执行通信网关迁移
+/// 20. 执行网关从站与点位迁移
+/// 21. 执行采集参数迁移
+/// 22. 执行点位值变换迁移
+/// 23. 执行虚拟点位迁移
 ///
 /// # 返回
 /// * 成功返回 `Ok(())`
@@ -193,3 +210,7 @@ pub async fn init_database_async() -> Result<(), AppError> {
 /// 数据库模块测试模块
 #[cfg(test)]
 mod tests;
+
+/// 命令层测试共用的数据库初始化与唯一编码
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,64 @@
+//! 命令层测试共用的辅助函数
+//!
+//! 各业务模块的命令测试共用同一个测试数据库：
+//! 在进程内完成一次初始化，生成互不冲突的编码作为测试数据的唯一键，并注册受设备范围约束的操作员
+
+use std::sync::Once;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::auth::admin_commands::auth_admin_register_user;
+use crate::auth::models::AdminRegisterUserPayload;
+use crate::db;
+
+/// 初始化测试数据库（进程内只执行一次）
+pub fn ensure_test_db_ready() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        db::set_database_url(db::test_database_url()).expect("configure database url");
+        db::init_database().expect("init database");
+    });
+}
+
+/// 生成带前缀的唯一编码（进程内计数器与纳秒时间戳）
+pub fn unique_code(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    format!("{prefix}_{counter}_{nanos}")
+}
+
+/// 注册一个可立即使用的 operator 账号，返回用户名与用户 ID
+pub fn register_operator(prefix: &str) -> (String, i64) {
+    let username = unique_code(prefix);
+    let data = auth_admin_register_user(
+        AdminRegisterUserPayload {
+            operator_username: "admin".to_string(),
+            username: username.clone(),
+            password: "admin123".to_string(),
+            nickname: "设备操作员".to_string(),
+            phone: None,
+            roles: vec!["operator".to_string()],
+            account_term_type: "permanent".to_string(),
+            account_valid_days: None,
+            account_start_at: None,
+            account_expire_at: None,
+            organization_id: None,
+        },
+        None,
+    )
+    .expect("register operator")
+    .data;
+    // 清除强制改密标记，使 operator 角色立即生效
+    let mut connection = db::connect().expect("open db");
+    db::block_on(
+        sqlx::query("UPDATE users SET must_change_password = 0 WHERE id = $1")
+            .bind(data.user_id)
+            .execute(&mut connection),
+    )
+    .expect("clear must change password");
+    (username, data.user_id)
+}
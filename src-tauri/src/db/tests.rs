@@ -12,12 +12,28 @@ use sqlx::{query, query_scalar, Connection as _, PgConnection};
 
 // 引入迁移模块
 use super::migrations::{
-    apply_hide_button_permission_route, apply_one_time_data_fix, apply_permission_route_rename,
-    apply_user_registration_extension, data_fix_sql, hide_button_permission_route_sql, init_schema,
-    init_seed_data, permission_route_rename_sql, schema_sql, seed_sql,
-    user_registration_extension_sql, DATA_FIX_MIGRATION_ID,
-    HIDE_BUTTON_PERMISSION_ROUTE_MIGRATION_ID, PERMISSION_ROUTE_RENAME_MIGRATION_ID,
-    USER_REGISTRATION_MIGRATION_ID,
+    apply_acquisition_settings, apply_audit_events, apply_device_lifecycle, apply_device_registry_management, apply_device_tags,
+    apply_device_templates, apply_gateway_points, apply_gateways, apply_hide_button_permission_route, apply_location_nodes,
+    apply_one_time_data_fix, apply_organizations, apply_permission_route_rename, apply_point_transforms,
+    apply_virtual_points,
+    apply_user_account_start, apply_user_admin_delegations, apply_user_device_scopes,
+    apply_user_disabled_before_delete, apply_user_must_change_password,
+    apply_user_registration_extension, apply_user_soft_delete,
+    acquisition_settings_sql, audit_events_sql, data_fix_sql, device_lifecycle_sql, device_registry_management_sql,
+    device_tags_sql, device_templates_sql, gateway_points_sql, gateways_sql, hide_button_permission_route_sql, init_schema,
+    init_seed_data, location_nodes_sql, organizations_sql, permission_route_rename_sql, point_transforms_sql, schema_sql, virtual_points_sql,
+    seed_sql, user_account_start_sql, user_admin_delegations_sql, user_device_scopes_sql,
+    user_disabled_before_delete_sql, user_must_change_password_sql, user_registration_extension_sql,
+    user_soft_delete_sql,
+    ACQUISITION_SETTINGS_MIGRATION_ID, AUDIT_EVENTS_MIGRATION_ID, DATA_FIX_MIGRATION_ID, DEVICE_LIFECYCLE_MIGRATION_ID,
+    DEVICE_REGISTRY_MANAGEMENT_MIGRATION_ID, DEVICE_TAGS_MIGRATION_ID,
+    DEVICE_TEMPLATES_MIGRATION_ID, GATEWAYS_MIGRATION_ID, GATEWAY_POINTS_MIGRATION_ID, HIDE_BUTTON_PERMISSION_ROUTE_MIGRATION_ID,
+    LOCATION_NODES_MIGRATION_ID, ORGANIZATIONS_MIGRATION_ID, PERMISSION_ROUTE_RENAME_MIGRATION_ID, POINT_TRANSFORMS_MIGRATION_ID,
+    VIRTUAL_POINTS_MIGRATION_ID,
+    USER_ACCOUNT_START_MIGRATION_ID, USER_ADMIN_DELEGATIONS_MIGRATION_ID,
+    USER_DEVICE_SCOPES_MIGRATION_ID, USER_DISABLED_BEFORE_DELETE_MIGRATION_ID,
+    USER_MUST_CHANGE_PASSWORD_MIGRATION_ID,
+    USER_REGISTRATION_MIGRATION_ID, USER_SOFT_DELETE_MIGRATION_ID,
 };
 
 // 引入数据库模块
@@ -104,6 +120,24 @@ fn loads_sql_scripts_from_files() {
     let user_registration_extension = user_registration_extension_sql();
     let permission_route_rename = permission_route_rename_sql();
     let hide_button_permission_route = hide_button_permission_route_sql();
+    let user_soft_delete = user_soft_delete_sql();
+    let audit_events = audit_events_sql();
+    let user_account_start = user_account_start_sql();
+    let user_must_change_password = user_must_change_password_sql();
+    let organizations = organizations_sql();
+    let user_admin_delegations = user_admin_delegations_sql();
+    let user_device_scopes = user_device_scopes_sql();
+    let location_nodes = location_nodes_sql();
+    let device_registry_management = device_registry_management_sql();
+    let device_templates = device_templates_sql();
+    let device_lifecycle = device_lifecycle_sql();
+    let device_tags = device_tags_sql();
+    let gateways = gateways_sql();
+    let gateway_points = gateway_points_sql();
+    let acquisition_settings = acquisition_settings_sql();
+    let point_transforms = point_transforms_sql();
+    let virtual_points = virtual_points_sql();
+    let user_disabled_before_delete = user_disabled_before_delete_sql();
 
     assert!(schema.contains("CREATE TABLE IF NOT EXISTS users"));
     assert!(schema.contains("CREATE TABLE IF NOT EXISTS casbin_rule"));
@@ -115,6 +149,42 @@ fn loads_sql_scripts_from_files() {
     );
     assert!(permission_route_rename.contains("UPDATE routes"));
     assert!(hide_button_permission_route.contains("DELETE FROM routes"));
+    assert!(user_soft_delete.contains("ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at"));
+    assert!(audit_events.contains("CREATE TABLE IF NOT EXISTS audit_events"));
+    assert!(
+        user_account_start.contains("ALTER TABLE users ADD COLUMN IF NOT EXISTS account_start_at")
+    );
+    assert!(
+        user_must_change_password
+            .contains("ALTER TABLE users ADD COLUMN IF NOT EXISTS must_change_password")
+    );
+    assert!(organizations.contains("CREATE TABLE IF NOT EXISTS organizations"));
+    assert!(user_admin_delegations.contains("CREATE TABLE IF NOT EXISTS user_admin_delegations"));
+    assert!(user_device_scopes.contains("CREATE TABLE IF NOT EXISTS user_device_scopes"));
+    assert!(location_nodes.contains("CREATE TABLE IF NOT EXISTS location_nodes"));
+    assert!(device_registry_management
+        .contains("ALTER TABLE device_registry ADD COLUMN IF NOT EXISTS attributes JSONB"));
+    assert!(device_templates.contains("CREATE TABLE IF NOT EXISTS device_templates"));
+    assert!(device_templates.contains("CREATE TABLE IF NOT EXISTS device_points"));
+    assert!(device_lifecycle.contains("CREATE TABLE IF NOT EXISTS device_lifecycle_history"));
+    assert!(device_tags.contains("CREATE TABLE IF NOT EXISTS device_tags"));
+    assert!(gateways.contains("CREATE TABLE IF NOT EXISTS gateways"));
+    assert!(gateway_points.contains("CREATE TABLE IF NOT EXISTS gateway_slaves"));
+    assert!(gateway_points.contains("CREATE TABLE IF NOT EXISTS gateway_points"));
+    assert!(
+        acquisition_settings
+            .contains("ALTER TABLE gateways ADD COLUMN IF NOT EXISTS poll_interval_ms")
+    );
+    assert!(
+        acquisition_settings
+            .contains("ALTER TABLE gateway_slaves ADD COLUMN IF NOT EXISTS poll_interval_ms")
+    );
+    assert!(point_transforms.contains("ALTER TABLE gateway_points ADD COLUMN IF NOT EXISTS transform"));
+    assert!(virtual_points.contains("ALTER TABLE gateway_points ADD COLUMN IF NOT EXISTS expression"));
+    assert!(
+        user_disabled_before_delete
+            .contains("ALTER TABLE users ADD COLUMN IF NOT EXISTS disabled_before_delete")
+    );
 }
 
 #[test]
@@ -303,6 +373,95 @@ fn hides_button_permission_routes() {
     assert_eq!(migration_count, 1);
 }
 
+#[test]
+fn applies_user_soft_delete_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_registration_extension(&mut *conn))
+        .expect("apply user registration extension");
+    super::block_on(apply_user_soft_delete(&mut *conn)).expect("apply user soft delete");
+
+    let soft_delete_column_count: i64 = super::block_on(
+        query_scalar(
+            r"
+            SELECT COUNT(1)
+            FROM information_schema.columns
+            WHERE table_schema = current_schema()
+              AND table_name = 'users'
+              AND column_name IN ('deleted_at', 'deleted_by')
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query users table info");
+    let deleted_user_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM users WHERE deleted_at IS NOT NULL")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query deleted users");
+
+    assert_eq!(soft_delete_column_count, 2);
+    assert_eq!(deleted_user_count, 0);
+
+    super::block_on(apply_user_soft_delete(&mut *conn)).expect("skip second run");
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_SOFT_DELETE_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_audit_events_only_once_and_rejects_mutation() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_audit_events(&mut *conn)).expect("apply audit events");
+    super::block_on(apply_audit_events(&mut *conn)).expect("skip second run");
+
+    super::block_on(
+        query(
+            r"
+            INSERT INTO audit_events (occurred_at, actor, command, target_type, result, prev_hash, hash)
+            VALUES (1, 'admin', 'auth_admin_update_user', 'user', 'success', 'p', 'h')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert audit event");
+    let update_error =
+        super::block_on(query("UPDATE audit_events SET actor = 'intruder'").execute(&mut *conn))
+            .expect_err("update must be rejected");
+    let delete_error = super::block_on(query("DELETE FROM audit_events").execute(&mut *conn))
+        .expect_err("delete must be rejected");
+    assert!(update_error.to_string().contains("append-only"));
+    assert!(delete_error.to_string().contains("append-only"));
+
+    let audit_policy_count: i64 = super::block_on(
+        query_scalar(
+            "SELECT COUNT(1) FROM casbin_rule WHERE ptype = 'p' AND v0 = 'admin' AND v1 = 'audit'",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query audit policy");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(AUDIT_EVENTS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(audit_policy_count, 1);
+    assert_eq!(migration_count, 1);
+}
+
 #[test]
 fn opens_seaorm_connection_for_postgres() {
     ensure_db_ready();
@@ -319,3 +478,893 @@ fn opens_seaorm_connection_for_postgres() {
     let current_database: String = row.try_get_by_index(0).expect("decode database name");
     assert!(!current_database.trim().is_empty());
 }
+
+#[test]
+fn applies_user_account_start_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_account_start(&mut *conn)).expect("apply account start");
+    super::block_on(apply_user_account_start(&mut *conn)).expect("skip second run");
+
+    let column_count: i64 = super::block_on(
+        query_scalar(
+            r"
+            SELECT COUNT(1)
+            FROM information_schema.columns
+            WHERE table_schema = current_schema()
+              AND table_name = 'users'
+              AND column_name IN ('account_start_at', 'account_activation_pending')
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query account start columns");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_ACCOUNT_START_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(column_count, 2);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_user_must_change_password_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_must_change_password(&mut *conn)).expect("apply must change");
+    super::block_on(apply_user_must_change_password(&mut *conn)).expect("skip second run");
+
+    // 既有用户默认不需要修改密码
+    let flagged_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM users WHERE must_change_password <> 0")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query flagged users");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_MUST_CHANGE_PASSWORD_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(flagged_count, 0);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_user_disabled_before_delete_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_soft_delete(&mut *conn)).expect("apply user soft delete");
+    super::block_on(
+        query("UPDATE users SET is_active = 0, deleted_at = 1, deleted_by = 'admin' WHERE username = 'common'")
+            .execute(&mut *conn),
+    )
+    .expect("soft delete user");
+    super::block_on(apply_user_disabled_before_delete(&mut *conn)).expect("apply disabled before delete");
+    super::block_on(apply_user_disabled_before_delete(&mut *conn)).expect("skip second run");
+
+    // 存量的已删除账号按删除前已停用处理，未删除的账号不受影响
+    let flagged: Vec<String> = super::block_on(
+        query_scalar("SELECT username FROM users WHERE disabled_before_delete <> 0 ORDER BY username")
+            .fetch_all(&mut *conn),
+    )
+    .expect("query flagged users");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_DISABLED_BEFORE_DELETE_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(flagged, vec!["common".to_string()]);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_organizations_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_organizations(&mut *conn)).expect("apply organizations");
+    super::block_on(apply_organizations(&mut *conn)).expect("skip second run");
+
+    // 同一上级下组织名称唯一
+    super::block_on(
+        query(
+            r"
+            INSERT INTO organizations (name, org_type, created_at, updated_at, created_by)
+            VALUES ('总部', 'company', 1, 1, 'admin')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert organization");
+    let duplicate_error = super::block_on(
+        query(
+            r"
+            INSERT INTO organizations (name, org_type, created_at, updated_at, created_by)
+            VALUES ('总部', 'company', 1, 1, 'admin')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect_err("duplicate root name must be rejected");
+    assert!(
+        duplicate_error
+            .to_string()
+            .contains("idx_organizations_parent_name")
+    );
+
+    let organization_policy_count: i64 = super::block_on(
+        query_scalar(
+            "SELECT COUNT(1) FROM casbin_rule WHERE ptype = 'p' AND v0 = 'admin' AND v1 = 'organization'",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query organization policy");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(ORGANIZATIONS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(organization_policy_count, 2);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_user_admin_delegations_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_organizations(&mut *conn)).expect("apply organizations");
+    super::block_on(apply_user_admin_delegations(&mut *conn)).expect("apply delegations");
+    super::block_on(apply_user_admin_delegations(&mut *conn)).expect("skip second run");
+
+    // 删除委派记录时级联删除可分配角色
+    super::block_on(
+        query(
+            r"
+            INSERT INTO user_admin_delegations (user_id, manage_created_users, created_at, updated_at, created_by)
+            VALUES (2, 1, 1, 1, 'admin')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert delegation");
+    super::block_on(
+        query("INSERT INTO user_admin_delegation_roles (user_id, role) VALUES (2, 'tenant')")
+            .execute(&mut *conn),
+    )
+    .expect("insert delegation role");
+    super::block_on(
+        query("DELETE FROM user_admin_delegations WHERE user_id = 2").execute(&mut *conn),
+    )
+    .expect("delete delegation");
+
+    let role_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM user_admin_delegation_roles WHERE user_id = 2")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query delegation roles");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_ADMIN_DELEGATIONS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(role_count, 0);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_user_device_scopes_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_device_scopes(&mut *conn)).expect("apply device scopes");
+    super::block_on(apply_user_device_scopes(&mut *conn)).expect("skip second run");
+
+    // 删除设备时级联删除用户设备范围中的设备明细
+    super::block_on(
+        query(
+            r"
+            INSERT INTO user_device_scopes (user_id, updated_at, updated_by)
+            VALUES (2, 1, 'admin')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert device scope");
+    super::block_on(
+        query(
+            r"
+            INSERT INTO user_device_scope_devices (user_id, device_id)
+            VALUES (2, 'device-localhost-001')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert device scope entry");
+    super::block_on(
+        query("DELETE FROM device_registry WHERE device_id = 'device-localhost-001'")
+            .execute(&mut *conn),
+    )
+    .expect("delete device");
+
+    let entry_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM user_device_scope_devices WHERE user_id = 2")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query device scope entries");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(USER_DEVICE_SCOPES_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(entry_count, 0);
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_location_nodes_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_user_device_scopes(&mut *conn)).expect("apply device scopes");
+
+    // 准备 0013 的区域/楼层字典、设备位置与用户设备范围明细
+    for sql in [
+        "INSERT INTO device_areas (area_code, area_name, created_at) VALUES ('A01', 'A 区', 1)",
+        "INSERT INTO device_floors (floor_code, floor_name, created_at) VALUES ('F03', '3 层', 1)",
+        "UPDATE device_registry SET area_code = 'A01', floor_code = 'F03' \
+         WHERE device_id = 'device-localhost-001'",
+        "INSERT INTO user_device_scopes (user_id, updated_at, updated_by) VALUES (2, 1, 'admin')",
+        "INSERT INTO user_device_scope_areas (user_id, area_code) VALUES (2, 'A01')",
+    ] {
+        super::block_on(query(sql).execute(&mut *conn)).expect("prepare legacy device scope");
+    }
+
+    super::block_on(apply_location_nodes(&mut *conn)).expect("apply location nodes");
+    super::block_on(apply_location_nodes(&mut *conn)).expect("skip second run");
+
+    // 设备位置迁移为楼层节点，区域明细改为引用位置编码
+    let device_location: Option<String> = super::block_on(
+        query_scalar(
+            r"
+            SELECT n.code FROM device_registry d
+            JOIN location_nodes n ON n.id = d.location_id
+            WHERE d.device_id = 'device-localhost-001'
+            ",
+        )
+        .fetch_optional(&mut *conn),
+    )
+    .expect("query device location");
+    assert_eq!(device_location.as_deref(), Some("F03"));
+
+    // 删除位置节点时级联删除用户设备范围中的区域明细
+    super::block_on(query("DELETE FROM location_nodes WHERE code = 'A01'").execute(&mut *conn))
+        .expect("delete area node");
+    let entry_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM user_device_scope_areas WHERE user_id = 2")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query device scope entries");
+    let legacy_table: Option<String> = super::block_on(
+        query_scalar("SELECT to_regclass('device_areas')::TEXT").fetch_one(&mut *conn),
+    )
+    .expect("query legacy table");
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(LOCATION_NODES_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(entry_count, 0);
+    assert!(legacy_table.is_none());
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_device_registry_management_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_device_registry_management(&mut *conn)).expect("apply device registry");
+    super::block_on(apply_device_registry_management(&mut *conn)).expect("skip second run");
+
+    // 存量设备取默认类型、启用状态与空属性，更新时间回填为注册时间
+    let (device_type, enabled, attributes, backfilled): (String, i32, String, bool) =
+        super::block_on(
+            sqlx::query_as(
+                r"
+                SELECT device_type, enabled, attributes::TEXT, updated_at = registered_at
+                FROM device_registry
+                WHERE device_id = 'device-localhost-001'
+                ",
+            )
+            .fetch_one(&mut *conn),
+        )
+        .expect("query seeded device");
+    assert_eq!(device_type, "generic");
+    assert_eq!(enabled, 1);
+    assert_eq!(attributes, "{}");
+    assert!(backfilled);
+
+    // 同一厂商下序列号唯一
+    let insert_serial = |device_id: &'static str| {
+        query(
+            r"
+            INSERT INTO device_registry (
+              device_id, device_name, owner_username, registered_at, manufacturer, serial_number
+            )
+            VALUES ($1, $1, 'admin', 1, 'ACME', 'SN-001')
+            ",
+        )
+        .bind(device_id)
+    };
+    super::block_on(insert_serial("serial-a").execute(&mut *conn)).expect("insert first serial");
+    assert!(super::block_on(insert_serial("serial-b").execute(&mut *conn)).is_err());
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(DEVICE_REGISTRY_MANAGEMENT_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_device_templates_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_device_templates(&mut *conn)).expect("apply device templates");
+    super::block_on(apply_device_templates(&mut *conn)).expect("skip second run");
+
+    let template_id: i64 = super::block_on(
+        query_scalar(
+            r"
+            INSERT INTO device_templates (code, name, created_at, updated_at, created_by)
+            VALUES ('meter-v1', '电表', 1, 1, 'admin')
+            RETURNING id
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert template");
+    super::block_on(
+        query(
+            r"
+            UPDATE device_registry SET template_id = $1, template_version = 1
+            WHERE device_id = 'device-localhost-001'
+            ",
+        )
+        .bind(template_id)
+        .execute(&mut *conn),
+    )
+    .expect("link seeded device");
+
+    // 仍有关联设备的模板不能删除
+    assert!(super::block_on(
+        query("DELETE FROM device_templates WHERE id = $1")
+            .bind(template_id)
+            .execute(&mut *conn)
+    )
+    .is_err());
+
+    // 设备点位随设备删除
+    super::block_on(
+        query(
+            r"
+            INSERT INTO device_points (
+              device_id, point_key, name, data_type, register_type, address, updated_at
+            )
+            VALUES ('device-localhost-001', 'power', '功率', 'float32', 'holding_register', 0, 1)
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert device point");
+    super::block_on(
+        query("DELETE FROM device_registry WHERE device_id = 'device-localhost-001'")
+            .execute(&mut *conn),
+    )
+    .expect("delete device");
+    let point_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM device_points").fetch_one(&mut *conn),
+    )
+    .expect("count device points");
+    assert_eq!(point_count, 0);
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(DEVICE_TEMPLATES_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_device_lifecycle_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_device_lifecycle(&mut *conn)).expect("apply device lifecycle");
+    super::block_on(apply_device_lifecycle(&mut *conn)).expect("skip second run");
+
+    // 存量设备视为已投运，进入状态的时间取注册时间
+    let (state, changed_at, registered_at): (String, i64, i64) = super::block_on(
+        sqlx::query_as(
+            r"
+            SELECT lifecycle_state, lifecycle_changed_at, registered_at
+            FROM device_registry
+            WHERE device_id = 'device-localhost-001'
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query seeded device");
+    assert_eq!(state, "in_service");
+    assert_eq!(changed_at, registered_at);
+
+    // 新设备默认处于规划状态，非法状态被拒绝
+    super::block_on(
+        query(
+            r"
+            INSERT INTO device_registry (
+              device_id, device_name, owner_username, registered_at, lifecycle_changed_at
+            )
+            VALUES ('device-lifecycle-001', '新设备', 'admin', 1, 1)
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert device");
+    let state: String = super::block_on(
+        query_scalar(
+            "SELECT lifecycle_state FROM device_registry WHERE device_id = 'device-lifecycle-001'",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query new device");
+    assert_eq!(state, "planned");
+    assert!(super::block_on(
+        query(
+            "UPDATE device_registry SET lifecycle_state = 'retired' WHERE device_id = 'device-lifecycle-001'",
+        )
+        .execute(&mut *conn)
+    )
+    .is_err());
+
+    // 流转历史随设备删除
+    super::block_on(
+        query(
+            r"
+            INSERT INTO device_lifecycle_history (
+              device_id, from_state, to_state, reason, actor, changed_at
+            )
+            VALUES ('device-lifecycle-001', 'planned', 'installed', '现场安装', 'admin', 2)
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert history");
+    super::block_on(
+        query("DELETE FROM device_registry WHERE device_id = 'device-lifecycle-001'")
+            .execute(&mut *conn),
+    )
+    .expect("delete device");
+    let history_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM device_lifecycle_history").fetch_one(&mut *conn),
+    )
+    .expect("count history");
+    assert_eq!(history_count, 0);
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(DEVICE_LIFECYCLE_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_device_tags_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_device_tags(&mut *conn)).expect("apply device tags");
+    super::block_on(apply_device_tags(&mut *conn)).expect("skip second run");
+
+    // 设备级标签与点位标签共用一张表，同一目标上的标签键唯一
+    super::block_on(
+        query(
+            r"
+            INSERT INTO device_tags (device_id, point_key, tag_key, tag_value, updated_at)
+            VALUES
+              ('device-localhost-001', '', 'critical', '', 1),
+              ('device-localhost-001', 'voltage', 'critical', '', 1)
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert tags");
+    assert!(super::block_on(
+        query(
+            r"
+            INSERT INTO device_tags (device_id, tag_key, tag_value, updated_at)
+            VALUES ('device-localhost-001', 'critical', 'yes', 2)
+            ",
+        )
+        .execute(&mut *conn)
+    )
+    .is_err());
+
+    // 标签随设备删除
+    super::block_on(
+        query("DELETE FROM device_registry WHERE device_id = 'device-localhost-001'")
+            .execute(&mut *conn),
+    )
+    .expect("delete device");
+    let tag_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM device_tags").fetch_one(&mut *conn),
+    )
+    .expect("count tags");
+    assert_eq!(tag_count, 0);
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(DEVICE_TAGS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_gateways_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_gateways(&mut *conn)).expect("apply gateways");
+    super::block_on(apply_gateways(&mut *conn)).expect("skip second run");
+
+    // 未指定的帧格式、超时与启用状态取默认值
+    super::block_on(
+        query(
+            r"
+            INSERT INTO gateways (code, name, host, port, created_at, updated_at, created_by)
+            VALUES ('gw-01', '一号网关', '192.168.1.100', 502, 1, 1, 'admin')
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert gateway");
+    let (framing, connect_timeout_ms, enabled): (String, i32, bool) = super::block_on(
+        sqlx::query_as(
+            "SELECT framing, connect_timeout_ms, enabled FROM gateways WHERE code = 'gw-01'",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("query gateway");
+    assert_eq!(
+        (framing.as_str(), connect_timeout_ms, enabled),
+        ("mbap", 3000, true)
+    );
+
+    // 网关编码唯一，传输方式与端口受约束
+    for sql in [
+        "INSERT INTO gateways (code, name, created_at, updated_at, created_by) \
+         VALUES ('gw-01', '重复编码', 1, 1, 'admin')",
+        "INSERT INTO gateways (code, name, transport, created_at, updated_at, created_by) \
+         VALUES ('gw-02', '未知传输', 'udp', 1, 1, 'admin')",
+        "INSERT INTO gateways (code, name, port, created_at, updated_at, created_by) \
+         VALUES ('gw-03', '端口越界', 70000, 1, 1, 'admin')",
+    ] {
+        assert!(super::block_on(query(sql).execute(&mut *conn)).is_err());
+    }
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(GATEWAYS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_gateway_points_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_gateways(&mut *conn)).expect("apply gateways");
+    super::block_on(apply_gateway_points(&mut *conn)).expect("apply gateway points");
+    super::block_on(apply_gateway_points(&mut *conn)).expect("skip second run");
+
+    let gateway_id: i64 = super::block_on(
+        query_scalar(
+            r"
+            INSERT INTO gateways (code, name, host, port, created_at, updated_at, created_by)
+            VALUES ('gw-01', '一号网关', '192.168.1.100', 502, 1, 1, 'admin')
+            RETURNING id
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert gateway");
+    let slave_id: i64 = super::block_on(
+        query_scalar(
+            r"
+            INSERT INTO gateway_slaves (gateway_id, unit_id, name, address_min, address_max, created_at, updated_at)
+            VALUES ($1, 1, '一号电表', 0, 99, 1, 1)
+            RETURNING id
+            ",
+        )
+        .bind(gateway_id)
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert slave");
+    super::block_on(
+        query(
+            r"
+            INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at)
+            VALUES ($1, 'voltage', '电压', 3, 0, 2, 'f32', 'abcd', 1, 1)
+            ",
+        )
+        .bind(slave_id)
+        .execute(&mut *conn),
+    )
+    .expect("insert point");
+    let access: String = super::block_on(
+        query_scalar("SELECT access FROM gateway_points WHERE point_key = 'voltage'")
+            .fetch_one(&mut *conn),
+    )
+    .expect("query point");
+    assert_eq!(access, "read");
+
+    // 单元号、地址范围、功能码与数据类型受约束，单元号与点位标识唯一
+    for sql in [
+        "INSERT INTO gateway_slaves (gateway_id, unit_id, name, created_at, updated_at) \
+         SELECT id, 1, '重复单元号', 1, 1 FROM gateways",
+        "INSERT INTO gateway_slaves (gateway_id, unit_id, name, created_at, updated_at) \
+         SELECT id, 248, '单元号越界', 1, 1 FROM gateways",
+        "INSERT INTO gateway_slaves (gateway_id, unit_id, name, address_min, address_max, created_at, updated_at) \
+         SELECT id, 2, '范围颠倒', 10, 5, 1, 1 FROM gateways",
+        "INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at) \
+         SELECT id, 'voltage', '重复标识', 3, 2, 1, 'u16', 'ab', 1, 1 FROM gateway_slaves",
+        "INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at) \
+         SELECT id, 'fc5', '写功能码', 5, 2, 1, 'u16', 'ab', 1, 1 FROM gateway_slaves",
+        "INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at) \
+         SELECT id, 'int16', '未知类型', 3, 2, 1, 'int16', 'ab', 1, 1 FROM gateway_slaves",
+        "INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at) \
+         SELECT id, 'tail', '地址越界', 3, 65535, 2, 'u32', 'abcd', 1, 1 FROM gateway_slaves",
+    ] {
+        assert!(super::block_on(query(sql).execute(&mut *conn)).is_err());
+    }
+
+    // 删除网关级联删除从站与点位
+    super::block_on(
+        query("DELETE FROM gateways WHERE id = $1")
+            .bind(gateway_id)
+            .execute(&mut *conn),
+    )
+    .expect("delete gateway");
+    let point_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM gateway_points").fetch_one(&mut *conn),
+    )
+    .expect("query point count");
+    assert_eq!(point_count, 0);
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(GATEWAY_POINTS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_acquisition_settings_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_gateways(&mut *conn)).expect("apply gateways");
+    super::block_on(apply_gateway_points(&mut *conn)).expect("apply gateway points");
+    super::block_on(apply_acquisition_settings(&mut *conn)).expect("apply acquisition settings");
+    super::block_on(apply_acquisition_settings(&mut *conn)).expect("skip second run");
+
+    let gateway_id: i64 = super::block_on(
+        query_scalar(
+            r"
+            INSERT INTO gateways (code, name, host, port, created_at, updated_at, created_by)
+            VALUES ('gw-01', '一号网关', '192.168.1.100', 502, 1, 1, 'admin')
+            RETURNING id
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert gateway");
+    let (poll_interval_ms, poll_max_gap): (i32, i32) = super::block_on(
+        sqlx::query_as("SELECT poll_interval_ms, poll_max_gap FROM gateways WHERE id = $1")
+            .bind(gateway_id)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query gateway");
+    assert_eq!((poll_interval_ms, poll_max_gap), (1000, 0));
+    let slave_interval: Option<i32> = super::block_on(
+        query_scalar(
+            r"
+            INSERT INTO gateway_slaves (gateway_id, unit_id, name, created_at, updated_at)
+            VALUES ($1, 1, '一号电表', 1, 1)
+            RETURNING poll_interval_ms
+            ",
+        )
+        .bind(gateway_id)
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert slave");
+    assert_eq!(slave_interval, None);
+
+    // 轮询周期与合并间隙受范围约束
+    for sql in [
+        "UPDATE gateways SET poll_interval_ms = 99",
+        "UPDATE gateways SET poll_interval_ms = 3600001",
+        "UPDATE gateways SET poll_max_gap = 126",
+        "UPDATE gateway_slaves SET poll_interval_ms = 50",
+    ] {
+        assert!(super::block_on(query(sql).execute(&mut *conn)).is_err());
+    }
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(ACQUISITION_SETTINGS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_point_transforms_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_gateways(&mut *conn)).expect("apply gateways");
+    super::block_on(apply_gateway_points(&mut *conn)).expect("apply gateway points");
+    super::block_on(apply_acquisition_settings(&mut *conn)).expect("apply acquisition settings");
+    super::block_on(apply_point_transforms(&mut *conn)).expect("apply point transforms");
+    super::block_on(apply_point_transforms(&mut *conn)).expect("skip second run");
+
+    // 已有点位与未指定变换的新点位默认为空对象
+    let transform: String = super::block_on(
+        query_scalar(
+            r"
+            WITH gateway AS (
+              INSERT INTO gateways (code, name, host, port, created_at, updated_at, created_by)
+              VALUES ('gw-01', '一号网关', '192.168.1.100', 502, 1, 1, 'admin')
+              RETURNING id
+            ), slave AS (
+              INSERT INTO gateway_slaves (gateway_id, unit_id, name, created_at, updated_at)
+              SELECT id, 1, '一号电表', 1, 1 FROM gateway
+              RETURNING id
+            )
+            INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, created_at, updated_at)
+            SELECT id, 'voltage', '电压', 3, 0, 1, 'u16', 'ab', 1, 1 FROM slave
+            RETURNING transform::TEXT
+            ",
+        )
+        .fetch_one(&mut *conn),
+    )
+    .expect("insert point");
+    assert_eq!(transform, "{}");
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(POINT_TRANSFORMS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
+
+#[test]
+fn applies_virtual_points_only_once() {
+    let mut isolated = IsolatedDb::new();
+    let conn = isolated.conn();
+
+    super::block_on(init_schema(&mut *conn)).expect("init schema");
+    super::block_on(init_seed_data(&mut *conn)).expect("init seed");
+    super::block_on(apply_gateways(&mut *conn)).expect("apply gateways");
+    super::block_on(apply_gateway_points(&mut *conn)).expect("apply gateway points");
+    super::block_on(apply_acquisition_settings(&mut *conn)).expect("apply acquisition settings");
+    super::block_on(apply_point_transforms(&mut *conn)).expect("apply point transforms");
+    super::block_on(apply_virtual_points(&mut *conn)).expect("apply virtual points");
+    super::block_on(apply_virtual_points(&mut *conn)).expect("skip second run");
+
+    super::block_on(
+        query(
+            r"
+            WITH gateway AS (
+              INSERT INTO gateways (code, name, host, port, created_at, updated_at, created_by)
+              VALUES ('gw-01', '一号网关', '192.168.1.100', 502, 1, 1, 'admin')
+              RETURNING id
+            )
+            INSERT INTO gateway_slaves (gateway_id, unit_id, name, created_at, updated_at)
+            SELECT id, 1, '一号电表', 1, 1 FROM gateway
+            ",
+        )
+        .execute(&mut *conn),
+    )
+    .expect("insert slave");
+    let insert_point = |point_key: &str, function_code: i32, expression: Option<&str>| {
+        format!(
+            "INSERT INTO gateway_points (slave_id, point_key, name, function_code, address, count, data_type, byte_order, expression, created_at, updated_at) \
+             SELECT id, '{point_key}', '{point_key}', {function_code}, 0, 1, 'f64', 'ab', {}, 1, 1 FROM gateway_slaves",
+            expression.map_or_else(|| "NULL".to_string(), |value| format!("'{value}'"))
+        )
+    };
+
+    // 功能码 0 的虚拟点位必须有公式，物理点位不能有公式
+    super::block_on(query(&insert_point("voltage", 3, None)).execute(&mut *conn)).expect("insert physical point");
+    super::block_on(query(&insert_point("power", 0, Some("voltage * 2"))).execute(&mut *conn))
+        .expect("insert virtual point");
+    for (point_key, function_code, expression) in [
+        ("missing", 0, None),
+        ("physical", 3, Some("voltage")),
+        ("fc5", 5, Some("voltage")),
+    ] {
+        assert!(
+            super::block_on(query(&insert_point(point_key, function_code, expression)).execute(&mut *conn)).is_err(),
+            "{point_key} should violate constraints"
+        );
+    }
+
+    let migration_count: i64 = super::block_on(
+        query_scalar("SELECT COUNT(1) FROM app_migrations WHERE id = $1")
+            .bind(VIRTUAL_POINTS_MIGRATION_ID)
+            .fetch_one(&mut *conn),
+    )
+    .expect("query migration count");
+    assert_eq!(migration_count, 1);
+}
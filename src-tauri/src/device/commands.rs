@@ -0,0 +1,477 @@
+//! 设备管理模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的设备管理相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `device_list` | 分页查询操作员设备范围内的设备 |
+//! | `device_get` | 查询单个设备 |
+//! | `device_create` | 创建设备 |
+//! | `device_update` | 修改设备元数据、位置与启用状态 |
+//! | `device_delete` | 删除设备 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入设备数据模型
+use crate::device::models::{
+    DeviceCreatePayload, DeviceData, DeviceDeletePayload, DeviceGetPayload, DeviceListData,
+    DeviceListPayload, DeviceUpdatePayload,
+};
+// 引入设备服务层
+use crate::device::services;
+
+/// 分页查询设备
+///
+/// # 参数
+/// * `payload` - 关键字、设备类型、启用状态、位置等过滤条件与分页参数
+///
+/// # 返回
+/// * 操作员设备范围内满足条件的设备分页结果
+#[tauri::command]
+pub fn device_list(
+    payload: DeviceListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceListData> {
+    execute_traced_command("device_list", trace, || {
+        Ok(ApiResponse::ok(services::list_devices(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询单个设备
+///
+/// # 参数
+/// * `payload` - 操作员用户名与设备标识
+///
+/// # 返回
+/// * 设备详情
+#[tauri::command]
+pub fn device_get(payload: DeviceGetPayload, trace: Option<TraceContext>) -> AppResult<DeviceData> {
+    execute_traced_command("device_get", trace, || {
+        Ok(ApiResponse::ok(services::get_device(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建设备
+///
+/// # 参数
+/// * `payload` - 设备标识、名称、类型、厂商型号、序列号、位置、通信配置引用、启用状态与自由属性
+///
+/// # 返回
+/// * 新建的设备
+#[tauri::command]
+pub fn device_create(
+    payload: DeviceCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceData> {
+    execute_traced_command("device_create", trace, || {
+        Ok(ApiResponse::ok(services::create_device(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 修改设备
+///
+/// # 参数
+/// * `payload` - 设备标识及修改后的设备字段
+///
+/// # 返回
+/// * 修改后的设备
+#[tauri::command]
+pub fn device_update(
+    payload: DeviceUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceData> {
+    execute_traced_command("device_update", trace, || {
+        Ok(ApiResponse::ok(services::update_device(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除设备
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名与设备标识的请求体
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn device_delete(payload: DeviceDeletePayload, trace: Option<TraceContext>) -> AppResult<bool> {
+    execute_traced_command("device_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_device(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::auth::admin_commands::user_device_scope_upsert;
+    use crate::auth::models::UserDeviceScopeUpsertPayload;
+    use crate::core::error::AppError;
+    use crate::db::test_support::{ensure_test_db_ready, register_operator, unique_code};
+    use crate::location::commands::location_create;
+    use crate::location::models::{LocationCreatePayload, LocationData};
+
+    fn create_location(parent_id: Option<i64>, prefix: &str, level: &str) -> LocationData {
+        location_create(
+            LocationCreatePayload {
+                operator_username: "admin".to_string(),
+                parent_id,
+                level: level.to_string(),
+                code: unique_code(prefix),
+                name: prefix.to_string(),
+                ..LocationCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create location")
+        .data
+    }
+
+    fn create_payload(
+        operator_username: &str,
+        device_id: &str,
+        device_type: &str,
+        location_id: Option<i64>,
+    ) -> DeviceCreatePayload {
+        DeviceCreatePayload {
+            operator_username: operator_username.to_string(),
+            device_id: device_id.to_string(),
+            device_name: format!("{device_id} 名称"),
+            device_type: device_type.to_string(),
+            location_id,
+            ..DeviceCreatePayload::default()
+        }
+    }
+
+    fn list(operator_username: &str, payload: DeviceListPayload) -> AppResult<DeviceListData> {
+        device_list(
+            DeviceListPayload {
+                operator_username: operator_username.to_string(),
+                ..payload
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn create_update_get_delete_round_trip() {
+        ensure_test_db_ready();
+        let location = create_location(None, "device_crud_site", "site");
+        let device_id = unique_code("device_crud");
+        let created = device_create(
+            DeviceCreatePayload {
+                manufacturer: Some(" ACME ".to_string()),
+                model: Some("M-100".to_string()),
+                serial_number: Some(unique_code("sn")),
+                comm_config_ref: Some("modbus:gw-01/3".to_string()),
+                attributes: Some(json!({ "ratedPower": 15 })),
+                ..create_payload(
+                    "admin",
+                    &format!(" {device_id} "),
+                    "Meter",
+                    Some(location.id),
+                )
+            },
+            None,
+        )
+        .expect("create device")
+        .data;
+        assert_eq!(created.device_id, device_id);
+        assert_eq!(created.device_type, "meter");
+        assert_eq!(created.manufacturer.as_deref(), Some("ACME"));
+        assert_eq!(created.owner_username, "admin");
+        assert!(created.enabled);
+        assert_eq!(created.attributes, json!({ "ratedPower": 15 }));
+
+        let updated = device_update(
+            DeviceUpdatePayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+                device_name: "改名后".to_string(),
+                device_type: "meter".to_string(),
+                enabled: Some(false),
+                ..DeviceUpdatePayload::default()
+            },
+            None,
+        )
+        .expect("update device")
+        .data;
+        assert_eq!(updated.device_name, "改名后");
+        assert!(!updated.enabled);
+        assert!(updated.manufacturer.is_none());
+        assert!(updated.location_id.is_none());
+        // 未提交的启用状态与自由属性保持不变
+        assert_eq!(updated.attributes, json!({ "ratedPower": 15 }));
+        assert_eq!(updated.registered_at, created.registered_at);
+
+        let fetched = device_get(
+            DeviceGetPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+            },
+            None,
+        )
+        .expect("get device")
+        .data;
+        assert_eq!(fetched.device_name, "改名后");
+
+        let delete = || {
+            device_delete(
+                DeviceDeletePayload {
+                    operator_username: "admin".to_string(),
+                    device_id: device_id.clone(),
+                },
+                None,
+            )
+        };
+        assert!(delete().expect("delete device").data);
+        assert_eq!(
+            delete().expect_err("already deleted"),
+            AppError::Validation("device not found".to_string())
+        );
+    }
+
+    #[test]
+    fn list_paginates_and_filters() {
+        ensure_test_db_ready();
+        let device_type = unique_code("type").to_lowercase();
+        let building = create_location(None, "device_list_building", "building");
+        let floor = create_location(Some(building.id), "device_list_floor", "floor");
+        let prefix = unique_code("device_list");
+        for index in 0..5 {
+            let location_id = match index {
+                0 | 1 => Some(floor.id),
+                2 => Some(building.id),
+                _ => None,
+            };
+            device_create(
+                DeviceCreatePayload {
+                    enabled: Some(index != 4),
+                    ..create_payload(
+                        "admin",
+                        &format!("{prefix}_{index}"),
+                        &device_type,
+                        location_id,
+                    )
+                },
+                None,
+            )
+            .expect("create device");
+        }
+
+        let by_type = |payload: DeviceListPayload| {
+            list(
+                "admin",
+                DeviceListPayload {
+                    device_type: Some(device_type.to_uppercase()),
+                    ..payload
+                },
+            )
+            .expect("list devices")
+            .data
+        };
+        let page = by_type(DeviceListPayload {
+            page: Some(2),
+            page_size: Some(2),
+            ..DeviceListPayload::default()
+        });
+        assert_eq!(page.total, 5);
+        assert_eq!(page.page, 2);
+        let ids: Vec<String> = page.items.into_iter().map(|item| item.device_id).collect();
+        assert_eq!(ids, vec![format!("{prefix}_2"), format!("{prefix}_3")]);
+
+        let in_building = by_type(DeviceListPayload {
+            location_id: Some(building.id),
+            ..DeviceListPayload::default()
+        });
+        assert_eq!(in_building.total, 3);
+        let disabled = by_type(DeviceListPayload {
+            enabled: Some(false),
+            ..DeviceListPayload::default()
+        });
+        assert_eq!(disabled.items[0].device_id, format!("{prefix}_4"));
+        assert_eq!(disabled.total, 1);
+
+        let by_keyword = list(
+            "admin",
+            DeviceListPayload {
+                keyword: Some(format!("{}_1", prefix.to_uppercase())),
+                ..DeviceListPayload::default()
+            },
+        )
+        .expect("keyword")
+        .data;
+        assert_eq!(by_keyword.total, 1);
+        // LIKE 通配符按字面匹配
+        let wildcard = list(
+            "admin",
+            DeviceListPayload {
+                keyword: Some("%".to_string()),
+                device_type: Some(device_type.clone()),
+                ..DeviceListPayload::default()
+            },
+        )
+        .expect("wildcard")
+        .data;
+        assert_eq!(wildcard.total, 0);
+    }
+
+    #[test]
+    fn create_rejects_duplicates_and_invalid_input() {
+        ensure_test_db_ready();
+        let device_id = unique_code("device_dup");
+        let serial_number = unique_code("sn_dup");
+        let payload = |device_id: &str, serial_number: &str| DeviceCreatePayload {
+            manufacturer: Some("ACME".to_string()),
+            serial_number: Some(serial_number.to_string()),
+            ..create_payload("admin", device_id, "meter", None)
+        };
+        let create = |payload: DeviceCreatePayload| device_create(payload, None);
+        create(payload(&device_id, &serial_number)).expect("create device");
+
+        assert_eq!(
+            create(payload(&device_id, &unique_code("sn"))).expect_err("duplicate id"),
+            AppError::Validation("device already exists".to_string())
+        );
+        assert_eq!(
+            create(payload(&unique_code("device_sn"), &serial_number))
+                .expect_err("duplicate serial"),
+            AppError::Validation("serial number already exists".to_string())
+        );
+        assert_eq!(
+            create(DeviceCreatePayload {
+                attributes: Some(json!([1, 2])),
+                ..payload(&unique_code("device_attr"), &unique_code("sn"))
+            })
+            .expect_err("attributes must be object"),
+            AppError::Validation("attributes must be a JSON object".to_string())
+        );
+        assert_eq!(
+            create(payload("bad device", &unique_code("sn"))).expect_err("invalid id"),
+            AppError::Validation(
+                "deviceId must be at most 64 letters, digits, '_', '-', '.' or ':'".to_string()
+            )
+        );
+        assert_eq!(
+            create(DeviceCreatePayload {
+                location_id: Some(i64::MAX),
+                ..payload(&unique_code("device_loc"), &unique_code("sn"))
+            })
+            .expect_err("unknown location"),
+            AppError::Validation("location not found".to_string())
+        );
+    }
+
+    #[test]
+    fn operator_only_sees_devices_in_scope() {
+        ensure_test_db_ready();
+        let area = create_location(None, "device_scope_area", "area");
+        let floor = create_location(Some(area.id), "device_scope_floor", "floor");
+        let other = create_location(None, "device_scope_other", "area");
+        let device_type = unique_code("scope").to_lowercase();
+        let prefix = unique_code("device_scope");
+        for (suffix, location_id) in [
+            ("in", Some(floor.id)),
+            ("out", Some(other.id)),
+            ("none", None),
+        ] {
+            device_create(
+                create_payload(
+                    "admin",
+                    &format!("{prefix}_{suffix}"),
+                    &device_type,
+                    location_id,
+                ),
+                None,
+            )
+            .expect("create device");
+        }
+
+        let (operator, user_id) = register_operator("device_operator");
+        let scoped = |operator: &str| {
+            list(
+                operator,
+                DeviceListPayload {
+                    device_type: Some(device_type.clone()),
+                    ..DeviceListPayload::default()
+                },
+            )
+            .expect("list scoped")
+            .data
+        };
+        // 未配置设备范围时看不到任何设备
+        assert_eq!(scoped(&operator).total, 0);
+
+        user_device_scope_upsert(
+            UserDeviceScopeUpsertPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                areas: vec![area.code.clone()],
+                devices: vec![format!("{prefix}_none")],
+                ..UserDeviceScopeUpsertPayload::default()
+            },
+            None,
+        )
+        .expect("upsert scope");
+        let ids: Vec<String> = scoped(&operator)
+            .items
+            .into_iter()
+            .map(|item| item.device_id)
+            .collect();
+        assert_eq!(ids, vec![format!("{prefix}_in"), format!("{prefix}_none")]);
+        assert_eq!(scoped("admin").total, 3);
+
+        let get = |device_id: String| {
+            device_get(
+                DeviceGetPayload {
+                    operator_username: operator.clone(),
+                    device_id,
+                },
+                None,
+            )
+        };
+        get(format!("{prefix}_in")).expect("in scope");
+        assert_eq!(
+            get(format!("{prefix}_out")).expect_err("out of scope"),
+            AppError::Validation("forbidden: device out of scope".to_string())
+        );
+        // operator 角色只有查看权限
+        assert_eq!(
+            device_create(
+                create_payload(&operator, &unique_code("device_denied"), &device_type, None),
+                None,
+            )
+            .expect_err("create forbidden"),
+            AppError::Validation("forbidden: device create required".to_string())
+        );
+    }
+
+    #[test]
+    fn non_permitted_user_cannot_list_devices() {
+        ensure_test_db_ready();
+        let err = list("common", DeviceListPayload::default()).expect_err("expect forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! 设备管理模块入口
+//!
+//! 本模块维护设备注册表（device_registry）中的设备元数据：
+//! - 设备类型、厂商型号、序列号、所在位置、通信配置引用、启用标记与 JSONB 自由属性
+//! - 列表分页查询支持关键字、类型、启用状态与位置子树过滤
+//! - 所有命令按 RBAC 权限与操作员的用户设备范围授权，范围外的设备不可见、不可修改
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 设备请求/响应结构
+pub mod models;
+// 公开服务模块 - 设备管理业务逻辑
+pub mod services;
+// 公开仓储模块 - 设备注册表的 SeaORM 读写
+pub mod repository;
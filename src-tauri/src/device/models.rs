@@ -0,0 +1,222 @@
+//! 设备管理模块数据模型
+//!
+//! 本模块定义设备注册表的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入标签选择器表达式
+use crate::device_tag::selector::SelectorExpr;
+
+/// 设备存储记录
+///
+/// 与 device_registry 表对应
+#[derive(Debug, Clone, Default)]
+pub struct DeviceRecord {
+    pub device_id: String,               // 设备标识
+    pub device_name: String,             // 设备名称
+    pub owner_username: String,          // 设备归属用户名
+    pub device_type: String,             // 设备类型
+    pub manufacturer: Option<String>,    // 厂商
+    pub model: Option<String>,           // 型号
+    pub serial_number: Option<String>,   // 序列号
+    pub location_id: Option<i64>,        // 所在位置 ID
+    pub comm_config_ref: Option<String>, // 通信配置引用
+    pub enabled: bool,                   // 是否启用
+    pub attributes: Value,               // 自由属性（JSON 对象）
+    pub template_id: Option<i64>,        // 关联模板 ID
+    pub template_version: Option<i32>,   // 已同步的模板版本
+    pub lifecycle_state: String,         // 生命周期状态
+    pub lifecycle_changed_at: i64,       // 进入当前状态的时间戳（毫秒）
+    pub registered_at: i64,              // 注册时间戳（毫秒）
+    pub updated_at: i64,                 // 更新时间戳（毫秒）
+}
+
+/// 设备写入参数（创建与更新共用，已完成规范化）
+#[derive(Debug, Clone, Default)]
+pub struct DeviceInput {
+    pub device_name: String,             // 设备名称
+    pub owner_username: String,          // 设备归属用户名
+    pub device_type: String,             // 设备类型
+    pub manufacturer: Option<String>,    // 厂商
+    pub model: Option<String>,           // 型号
+    pub serial_number: Option<String>,   // 序列号
+    pub location_id: Option<i64>,        // 所在位置 ID
+    pub comm_config_ref: Option<String>, // 通信配置引用
+    pub enabled: bool,                   // 是否启用
+    pub attributes: Value,               // 自由属性（JSON 对象）
+}
+
+/// 设备列表查询条件（已完成规范化）
+#[derive(Debug, Clone, Default)]
+pub struct DeviceFilter {
+    pub keyword: Option<String>, // 关键字（小写，匹配设备标识、名称、厂商、型号、序列号）
+    pub device_type: Option<String>, // 设备类型
+    pub enabled: Option<bool>,   // 启用状态
+    pub lifecycle_state: Option<String>, // 生命周期状态
+    pub location_ids: Option<Vec<i64>>, // 所在位置范围（位置子树）
+    pub device_ids: Option<Vec<String>>, // 可访问设备范围（None 表示不限）
+    pub selector: Option<SelectorExpr>, // 标签选择器
+}
+
+// 设备列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 关键字（匹配设备标识、名称、厂商、型号、序列号，不区分大小写）
+    pub keyword: Option<String>,
+    /// 按设备类型过滤
+    pub device_type: Option<String>,
+    /// 按启用状态过滤
+    pub enabled: Option<bool>,
+    /// 按生命周期状态过滤
+    pub lifecycle_state: Option<String>,
+    /// 按所在位置过滤（含全部下级位置）
+    pub location_id: Option<i64>,
+    /// 按标签选择器过滤（例如 `type=meter AND critical`）
+    pub selector: Option<String>,
+    /// 页码（从 1 开始）
+    pub page: Option<u32>,
+    /// 每页条数（默认 20，最大 200）
+    pub page_size: Option<u32>,
+}
+
+// 查询单个设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceGetPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+}
+
+// 创建设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识（全局唯一）
+    pub device_id: String,
+    /// 设备名称
+    pub device_name: String,
+    /// 设备归属用户名（为空时取操作员）
+    pub owner_username: Option<String>,
+    /// 设备类型（如 meter / chiller / gateway）
+    pub device_type: String,
+    /// 厂商
+    pub manufacturer: Option<String>,
+    /// 型号
+    pub model: Option<String>,
+    /// 序列号（同一厂商下唯一）
+    pub serial_number: Option<String>,
+    /// 所在位置 ID
+    pub location_id: Option<i64>,
+    /// 通信配置引用
+    pub comm_config_ref: Option<String>,
+    /// 是否启用（默认启用）
+    pub enabled: Option<bool>,
+    /// 自由属性（JSON 对象）
+    pub attributes: Option<Value>,
+    /// 设备模板 ID（继承模板点位；类型、厂商、型号为空时取模板的值）
+    pub template_id: Option<i64>,
+}
+
+// 更新设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 设备名称
+    pub device_name: String,
+    /// 设备归属用户名（为空时保持不变）
+    pub owner_username: Option<String>,
+    /// 设备类型
+    pub device_type: String,
+    /// 厂商
+    pub manufacturer: Option<String>,
+    /// 型号
+    pub model: Option<String>,
+    /// 序列号
+    pub serial_number: Option<String>,
+    /// 所在位置 ID（为空表示清除位置）
+    pub location_id: Option<i64>,
+    /// 通信配置引用
+    pub comm_config_ref: Option<String>,
+    /// 是否启用（为空时保持不变）
+    pub enabled: Option<bool>,
+    /// 自由属性（JSON 对象，为空时保持不变）
+    pub attributes: Option<Value>,
+}
+
+// 删除设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+}
+
+// 设备响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceData {
+    /// 设备标识
+    pub device_id: String,
+    /// 设备名称
+    pub device_name: String,
+    /// 设备归属用户名
+    pub owner_username: String,
+    /// 设备类型
+    pub device_type: String,
+    /// 厂商
+    pub manufacturer: Option<String>,
+    /// 型号
+    pub model: Option<String>,
+    /// 序列号
+    pub serial_number: Option<String>,
+    /// 所在位置 ID
+    pub location_id: Option<i64>,
+    /// 通信配置引用
+    pub comm_config_ref: Option<String>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 自由属性
+    pub attributes: Value,
+    /// 关联模板 ID
+    pub template_id: Option<i64>,
+    /// 已同步的模板版本
+    pub template_version: Option<i32>,
+    /// 生命周期状态
+    pub lifecycle_state: String,
+    /// 进入当前状态的时间戳（毫秒）
+    pub lifecycle_changed_at: i64,
+    /// 注册时间戳（毫秒）
+    pub registered_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+}
+
+// 设备分页查询响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceListData {
+    /// 满足条件的总条数
+    pub total: i64,
+    /// 当前页码
+    pub page: u32,
+    /// 每页条数
+    pub page_size: u32,
+    /// 当前页记录（按设备标识排序）
+    pub items: Vec<DeviceData>,
+}
@@ -0,0 +1,320 @@
+//! 设备管理模块数据仓储层
+//!
+//! 本模块负责 device_registry 表的读写：
+//! - 按关键字、类型、启用状态、生命周期状态、位置范围、标签选择器与可访问设备范围分页查询设备
+//! - 按通信配置引用前缀查询设备（采集模块据此解析从站绑定的设备）
+//! - 设备的新增、修改与删除
+//!
+//! 设备元数据属于简单 CRUD，按 `docs/database-access-policy.md` 规则 1 使用 SeaORM 实现
+
+// 引入 SeaORM 查询表达式
+use sea_orm::sea_query::{Expr, Func, LikeExpr};
+// 引入 SeaORM 核心 trait
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入设备注册实体
+use crate::db::entities::device_registry;
+// 引入设备模型
+use crate::device::models::{DeviceFilter, DeviceInput, DeviceRecord};
+// 引入设备标签仓储（按选择器过滤）
+use crate::device_tag::repository as tag_repository;
+// 引入设备模板模型与仓储（创建设备时写入继承的点位）
+use crate::device_template::models::TemplateBinding;
+use crate::device_template::repository as template_repository;
+
+/// 分页查询设备
+///
+/// # 参数
+/// * `filter` - 查询条件
+/// * `limit` - 每页条数
+/// * `offset` - 偏移量
+///
+/// # 返回
+/// * (满足条件的总条数, 当前页按设备标识排序的记录)
+pub fn query_devices(
+    filter: &DeviceFilter,
+    limit: u64,
+    offset: u64,
+) -> Result<(i64, Vec<DeviceRecord>), AppError> {
+    let condition = build_condition(filter);
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let total = device_registry::Entity::find()
+            .filter(condition.clone())
+            .count(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let models = device_registry::Entity::find()
+            .filter(condition)
+            .order_by_asc(device_registry::Column::DeviceId)
+            .limit(limit)
+            .offset(offset)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let total = i64::try_from(total)
+            .map_err(|_| AppError::Database("device count out of range".to_string()))?;
+        Ok((total, models.into_iter().map(map_model).collect()))
+    })
+}
+
+/// 按设备标识查询设备
+///
+/// # 参数
+/// * `device_id` - 设备标识
+///
+/// # 返回
+/// * 设备记录（不存在时为 None）
+pub fn find_device(device_id: &str) -> Result<Option<DeviceRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let model = device_registry::Entity::find()
+            .filter(device_registry::Column::DeviceId.eq(device_id))
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(model.map(map_model))
+    })
+}
+
+/// 按通信配置引用前缀查询设备
+///
+/// # 参数
+/// * `prefix` - 通信配置引用前缀（如 `modbus:`）
+///
+/// # 返回
+/// * 通信配置引用以该前缀开头的设备记录
+pub fn list_devices_by_comm_prefix(prefix: &str) -> Result<Vec<DeviceRecord>, AppError> {
+    let pattern = format!("{}%", escape_like(prefix));
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = device_registry::Entity::find()
+            .filter(
+                Expr::col(device_registry::Column::CommConfigRef)
+                    .like(LikeExpr::new(pattern).escape('\\')),
+            )
+            .order_by_asc(device_registry::Column::DeviceId)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_model).collect())
+    })
+}
+
+/// 新增设备
+///
+/// 指定模板时在同一事务中写入模板关联与继承的设备点位
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `input` - 设备写入参数
+/// * `binding` - 模板关联（可选）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的设备记录
+pub fn insert_device(
+    device_id: &str,
+    input: DeviceInput,
+    binding: Option<TemplateBinding>,
+    now_millis: i64,
+) -> Result<DeviceRecord, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let mut model = device_registry::ActiveModel {
+            device_id: Set(device_id.to_string()),
+            device_name: Set(input.device_name),
+            owner_username: Set(input.owner_username),
+            registered_at: Set(now_millis),
+            location_id: Set(input.location_id),
+            device_type: Set(input.device_type),
+            manufacturer: Set(input.manufacturer),
+            model: Set(input.model),
+            serial_number: Set(input.serial_number),
+            comm_config_ref: Set(input.comm_config_ref),
+            enabled: Set(i32::from(input.enabled)),
+            attributes: Set(input.attributes),
+            updated_at: Set(now_millis),
+            lifecycle_changed_at: Set(now_millis),
+            ..Default::default()
+        }
+        .insert(&transaction)
+        .await
+        .map_err(map_device_mutation_error)?;
+        if let Some(binding) = binding {
+            model.template_id = Some(binding.template_id);
+            model.template_version = Some(binding.version);
+            template_repository::write_device_binding(&transaction, device_id, binding, now_millis)
+                .await?;
+        }
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(map_model(model))
+    })
+}
+
+/// 修改设备
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `input` - 设备写入参数（整体覆盖）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的设备记录（设备不存在时为 None）
+pub fn update_device(
+    device_id: &str,
+    input: DeviceInput,
+    now_millis: i64,
+) -> Result<Option<DeviceRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let Some(current) = device_registry::Entity::find()
+            .filter(device_registry::Column::DeviceId.eq(device_id))
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(None);
+        };
+        let mut model: device_registry::ActiveModel = current.into();
+        model.device_name = Set(input.device_name);
+        model.owner_username = Set(input.owner_username);
+        model.location_id = Set(input.location_id);
+        model.device_type = Set(input.device_type);
+        model.manufacturer = Set(input.manufacturer);
+        model.model = Set(input.model);
+        model.serial_number = Set(input.serial_number);
+        model.comm_config_ref = Set(input.comm_config_ref);
+        model.enabled = Set(i32::from(input.enabled));
+        model.attributes = Set(input.attributes);
+        model.updated_at = Set(now_millis);
+        let model = model
+            .update(&connection)
+            .await
+            .map_err(map_device_mutation_error)?;
+        Ok(Some(map_model(model)))
+    })
+}
+
+/// 删除设备（用户设备范围中的设备授权与设备点位随之级联删除）
+///
+/// # 参数
+/// * `device_id` - 设备标识
+///
+/// # 返回
+/// * 删除的记录数（设备不存在时为 0）
+pub fn delete_device(device_id: &str) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = device_registry::Entity::delete_many()
+            .filter(device_registry::Column::DeviceId.eq(device_id))
+            .exec(&connection)
+            .await
+            .map_err(map_device_mutation_error)?;
+        Ok(result.rows_affected)
+    })
+}
+
+/// 根据查询条件构造过滤表达式
+fn build_condition(filter: &DeviceFilter) -> Condition {
+    let mut condition = Condition::all();
+    if let Some(keyword) = filter.keyword.as_deref() {
+        let pattern = format!("%{}%", escape_like(keyword));
+        let mut keyword_condition = Condition::any();
+        for column in [
+            device_registry::Column::DeviceId,
+            device_registry::Column::DeviceName,
+            device_registry::Column::Manufacturer,
+            device_registry::Column::Model,
+            device_registry::Column::SerialNumber,
+        ] {
+            keyword_condition = keyword_condition.add(
+                Expr::expr(Func::lower(Expr::col(column)))
+                    .like(LikeExpr::new(pattern.clone()).escape('\\')),
+            );
+        }
+        condition = condition.add(keyword_condition);
+    }
+    if let Some(device_type) = filter.device_type.as_deref() {
+        condition = condition.add(device_registry::Column::DeviceType.eq(device_type));
+    }
+    if let Some(enabled) = filter.enabled {
+        condition = condition.add(device_registry::Column::Enabled.eq(i32::from(enabled)));
+    }
+    if let Some(lifecycle_state) = filter.lifecycle_state.as_deref() {
+        condition = condition.add(device_registry::Column::LifecycleState.eq(lifecycle_state));
+    }
+    if let Some(location_ids) = filter.location_ids.as_ref() {
+        condition = condition.add(device_registry::Column::LocationId.is_in(location_ids.clone()));
+    }
+    if let Some(device_ids) = filter.device_ids.as_ref() {
+        condition = condition.add(device_registry::Column::DeviceId.is_in(device_ids.clone()));
+    }
+    if let Some(selector) = filter.selector.as_ref() {
+        condition = condition.add(tag_repository::selector_condition(selector));
+    }
+    condition
+}
+
+/// 转义 LIKE 通配符（`%`、`_` 与转义符自身）
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// 将实体模型转换为设备记录
+fn map_model(model: device_registry::Model) -> DeviceRecord {
+    DeviceRecord {
+        device_id: model.device_id,
+        device_name: model.device_name,
+        owner_username: model.owner_username,
+        device_type: model.device_type,
+        manufacturer: model.manufacturer,
+        model: model.model,
+        serial_number: model.serial_number,
+        location_id: model.location_id,
+        comm_config_ref: model.comm_config_ref,
+        enabled: model.enabled == 1,
+        attributes: model.attributes,
+        template_id: model.template_id,
+        template_version: model.template_version,
+        lifecycle_state: model.lifecycle_state,
+        lifecycle_changed_at: model.lifecycle_changed_at,
+        registered_at: model.registered_at,
+        updated_at: model.updated_at,
+    }
+}
+
+/// 将数据库错误映射为应用错误
+fn map_db_error(err: DbErr) -> AppError {
+    AppError::Database(err.to_string())
+}
+
+/// 将设备写入的数据库错误映射为应用错误（唯一约束与外键冲突转为校验错误）
+fn map_device_mutation_error(err: DbErr) -> AppError {
+    let message = err.to_string();
+    if message.contains("device_registry_device_id_key") {
+        return AppError::Validation("device already exists".to_string());
+    }
+    if message.contains("idx_device_registry_serial_number") {
+        return AppError::Validation("serial number already exists".to_string());
+    }
+    if message.contains("device_registry_location_id_fkey") {
+        return AppError::Validation("location not found".to_string());
+    }
+    AppError::Database(message)
+}
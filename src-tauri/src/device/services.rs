@@ -0,0 +1,558 @@
+//! 设备管理模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 设备元数据的分页查询与增删改（类型、厂商型号、序列号、位置、通信配置引用、启用标记、自由属性）
+//! - 设备操作的权限校验：`device:view`（查询）、`device:create`（创建与修改）、`device:manage`（删除）
+//! - 按操作员的用户设备范围过滤列表，并拒绝访问范围外的设备
+//! - 设备增删改的审计记录（`targetType = "device"`）
+
+// 引入 JSON 值类型
+use serde_json::{Map, Value};
+
+// 引入采集引擎（通信配置引用变更后重新生成读取计划）
+use crate::acquisition::engine as acquisition_engine;
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入设备范围判定
+use crate::auth::device_scope_services::{self, DeviceAccessFilter};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入管理员数据访问层（按用户名查询用户 ID）
+use crate::db::admin_repository;
+// 引入设备模型
+use crate::device::models::{
+    DeviceCreatePayload, DeviceData, DeviceDeletePayload, DeviceFilter, DeviceGetPayload,
+    DeviceInput, DeviceListData, DeviceListPayload, DeviceRecord, DeviceUpdatePayload,
+};
+// 引入设备仓储模块
+use crate::device::repository;
+// 引入标签选择器解析（设备列表按选择器过滤）
+use crate::device_tag::selector;
+// 引入设备模板服务（由模板创建设备）
+use crate::device_template::services as template_services;
+// 引入位置仓储模块（校验位置存在并展开位置子树）
+use crate::location::repository as location_repository;
+
+// 审计目标类型：设备
+const TARGET_TYPE_DEVICE: &str = "device";
+
+// 访问范围外设备的拒绝消息
+const OUT_OF_SCOPE_MESSAGE: &str = "forbidden: device out of scope";
+
+// 默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+// 每页条数上限
+const MAX_PAGE_SIZE: u32 = 200;
+
+// 设备标识最大长度
+const MAX_DEVICE_ID_LENGTH: usize = 64;
+
+// 设备类型最大长度
+const MAX_DEVICE_TYPE_LENGTH: usize = 32;
+
+/// 分页查询设备
+///
+/// 结果只包含操作员设备范围内的设备
+///
+/// # 参数
+/// * `payload` - 查询条件与分页参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 分页结果
+pub fn list_devices(
+    payload: DeviceListPayload,
+    now_millis: u64,
+) -> Result<DeviceListData, AppError> {
+    let (_, user_id, now) = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+
+    // 计算分页参数
+    let page = payload.page.unwrap_or(1).max(1);
+    let page_size = payload
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = u64::from(page - 1) * u64::from(page_size);
+
+    let location_ids = match payload.location_id {
+        Some(location_id) => {
+            ensure_location_exists(location_id)?;
+            Some(
+                location_repository::list_subtree(location_id)?
+                    .into_iter()
+                    .map(|record| record.id)
+                    .collect(),
+            )
+        }
+        None => None,
+    };
+    let selector = trim_optional(payload.selector)
+        .map(|selector| selector::parse_selector(&selector))
+        .transpose()?;
+    let device_ids = match device_scope_services::resolve_accessible_devices(user_id, now)? {
+        DeviceAccessFilter::All => None,
+        DeviceAccessFilter::Devices(device_ids) => Some(device_ids),
+    };
+    let filter = DeviceFilter {
+        keyword: trim_optional(payload.keyword).map(|keyword| keyword.to_lowercase()),
+        device_type: trim_optional(payload.device_type).map(|value| value.to_lowercase()),
+        enabled: payload.enabled,
+        lifecycle_state: trim_optional(payload.lifecycle_state).map(|value| value.to_lowercase()),
+        location_ids,
+        device_ids,
+        selector,
+    };
+    let (total, records) = repository::query_devices(&filter, u64::from(page_size), offset)?;
+    Ok(DeviceListData {
+        total,
+        page,
+        page_size,
+        items: records.into_iter().map(map_device_record).collect(),
+    })
+}
+
+/// 查询单个设备
+///
+/// # 参数
+/// * `payload` - 操作员用户名与设备标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 设备详情（范围外的设备返回 forbidden）
+pub fn get_device(payload: &DeviceGetPayload, now_millis: u64) -> Result<DeviceData, AppError> {
+    let (_, user_id, now) = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let record = ensure_device_accessible(user_id, &payload.device_id, now)?;
+    Ok(map_device_record(record))
+}
+
+/// 创建设备
+///
+/// # 参数
+/// * `payload` - 创建请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的设备
+pub fn create_device(
+    payload: DeviceCreatePayload,
+    now_millis: u64,
+) -> Result<DeviceData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let device_id = payload.device_id.trim().to_string();
+    let result = create_device_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_create",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(&device_id),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 修改设备
+///
+/// # 参数
+/// * `payload` - 修改请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的设备
+pub fn update_device(
+    payload: DeviceUpdatePayload,
+    now_millis: u64,
+) -> Result<DeviceData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let device_id = payload.device_id.trim().to_string();
+    let before = find_snapshot(&device_id);
+    let result = update_device_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_update",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(&device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除设备
+///
+/// 用户设备范围中对该设备的授权随之删除
+///
+/// # 参数
+/// * `payload` - 删除请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_device(payload: &DeviceDeletePayload, now_millis: u64) -> Result<bool, AppError> {
+    let device_id = payload.device_id.trim();
+    let before = find_snapshot(device_id);
+    let result = delete_device_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(device_id),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 创建设备（不含审计记录）
+fn create_device_unaudited(
+    payload: DeviceCreatePayload,
+    now_millis: u64,
+) -> Result<DeviceData, AppError> {
+    let (operator_username, user_id, now) = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let device_id = normalize_device_id(&payload.device_id)?;
+    let owner_username = trim_optional(payload.owner_username).unwrap_or(operator_username);
+    // 由模板创建时，未填写的类型、厂商与型号取模板的值
+    let (template, binding) = match payload.template_id {
+        Some(template_id) => {
+            let (template, binding) = template_services::resolve_template_binding(template_id)?;
+            (Some(template), Some(binding))
+        }
+        None => (None, None),
+    };
+    let device_type = match template.as_ref() {
+        Some(template) if payload.device_type.trim().is_empty() => template.device_type.clone(),
+        _ => payload.device_type,
+    };
+    let manufacturer = trim_optional(payload.manufacturer).or_else(|| {
+        template
+            .as_ref()
+            .and_then(|template| template.manufacturer.clone())
+    });
+    let model = trim_optional(payload.model).or_else(|| {
+        template
+            .as_ref()
+            .and_then(|template| template.model.clone())
+    });
+    let input = normalize_input(
+        owner_username,
+        DeviceFields {
+            device_name: payload.device_name,
+            device_type,
+            manufacturer,
+            model,
+            serial_number: payload.serial_number,
+            location_id: payload.location_id,
+            comm_config_ref: payload.comm_config_ref,
+            enabled: payload.enabled.unwrap_or(true),
+            attributes: payload
+                .attributes
+                .unwrap_or_else(|| Value::Object(Map::new())),
+        },
+    )?;
+    if repository::find_device(&device_id)?.is_some() {
+        return Err(AppError::Validation("device already exists".to_string()));
+    }
+    ensure_placement_accessible(user_id, &device_id, input.location_id, now)?;
+    let record = repository::insert_device(&device_id, input, binding, now)?;
+    acquisition_engine::invalidate_plans();
+    Ok(map_device_record(record))
+}
+
+// 修改设备（不含审计记录）
+fn update_device_unaudited(
+    payload: DeviceUpdatePayload,
+    now_millis: u64,
+) -> Result<DeviceData, AppError> {
+    let (_, user_id, now) = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let current = ensure_device_accessible(user_id, &payload.device_id, now)?;
+    let owner_username =
+        trim_optional(payload.owner_username).unwrap_or_else(|| current.owner_username.clone());
+    let input = normalize_input(
+        owner_username,
+        DeviceFields {
+            device_name: payload.device_name,
+            device_type: payload.device_type,
+            manufacturer: payload.manufacturer,
+            model: payload.model,
+            serial_number: payload.serial_number,
+            location_id: payload.location_id,
+            comm_config_ref: payload.comm_config_ref,
+            enabled: payload.enabled.unwrap_or(current.enabled),
+            attributes: payload
+                .attributes
+                .unwrap_or_else(|| current.attributes.clone()),
+        },
+    )?;
+    // 移动到其他位置时，目标位置同样必须在操作员的设备范围内
+    if input.location_id != current.location_id {
+        ensure_placement_accessible(user_id, &current.device_id, input.location_id, now)?;
+    }
+    let record = repository::update_device(&current.device_id, input, now)?
+        .ok_or_else(|| AppError::Validation("device not found".to_string()))?;
+    acquisition_engine::invalidate_plans();
+    Ok(map_device_record(record))
+}
+
+// 删除设备（不含审计记录）
+fn delete_device_unaudited(
+    payload: &DeviceDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let (_, user_id, now) = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let current = ensure_device_accessible(user_id, &payload.device_id, now)?;
+    if repository::delete_device(&current.device_id)? == 0 {
+        return Err(AppError::Validation("device not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    Ok(true)
+}
+
+/// 验证操作员是否具有设备权限
+///
+/// 设备模板模块复用同一校验
+///
+/// # 返回
+/// * (去除空白的操作员用户名, 操作员用户 ID, 转换为 i64 的当前时间戳)
+pub(crate) fn assert_operator_allowed(
+    operator_username: &str,
+    action: &str,
+    forbidden_message: &str,
+    now_millis: u64,
+) -> Result<(String, i64, i64), AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        rbac::RESOURCE_DEVICE,
+        action,
+        now_millis,
+        forbidden_message,
+    )?;
+    let user_id = admin_repository::find_user_id_by_username(operator_username)?
+        .ok_or_else(|| AppError::Validation(forbidden_message.to_string()))?;
+    Ok((operator_username.to_string(), user_id, now_millis))
+}
+
+/// 查询设备并校验其在操作员的设备范围内
+pub(crate) fn ensure_device_accessible(
+    user_id: i64,
+    device_id: &str,
+    now_millis: i64,
+) -> Result<DeviceRecord, AppError> {
+    let device_id = device_id.trim();
+    if device_id.is_empty() {
+        return Err(AppError::Validation("deviceId is required".to_string()));
+    }
+    let record = repository::find_device(device_id)?
+        .ok_or_else(|| AppError::Validation("device not found".to_string()))?;
+    if device_scope_services::resolve_device_access(user_id, device_id, now_millis)?.is_none() {
+        return Err(AppError::Validation(OUT_OF_SCOPE_MESSAGE.to_string()));
+    }
+    Ok(record)
+}
+
+/// 校验设备放置到目标位置后仍在操作员的设备范围内
+fn ensure_placement_accessible(
+    user_id: i64,
+    device_id: &str,
+    location_id: Option<i64>,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    if let Some(location_id) = location_id {
+        ensure_location_exists(location_id)?;
+    }
+    if device_scope_services::resolve_placement_access(user_id, device_id, location_id, now_millis)?
+        .is_none()
+    {
+        return Err(AppError::Validation(OUT_OF_SCOPE_MESSAGE.to_string()));
+    }
+    Ok(())
+}
+
+/// 校验位置存在
+fn ensure_location_exists(location_id: i64) -> Result<(), AppError> {
+    if location_id <= 0 {
+        return Err(AppError::Validation("invalid locationId".to_string()));
+    }
+    location_repository::find_location(location_id)?
+        .map(|_| ())
+        .ok_or_else(|| AppError::Validation("location not found".to_string()))
+}
+
+// 创建与修改共用的设备字段（尚未规范化）
+struct DeviceFields {
+    device_name: String,             // 设备名称
+    device_type: String,             // 设备类型
+    manufacturer: Option<String>,    // 厂商
+    model: Option<String>,           // 型号
+    serial_number: Option<String>,   // 序列号
+    location_id: Option<i64>,        // 所在位置 ID
+    comm_config_ref: Option<String>, // 通信配置引用
+    enabled: bool,                   // 是否启用
+    attributes: Value,               // 自由属性
+}
+
+/// 校验并规范化设备标识
+///
+/// 设备标识只允许字母、数字、`_`、`-`、`.`、`:`，最长 64 个字符
+fn normalize_device_id(device_id: &str) -> Result<String, AppError> {
+    let device_id = device_id.trim();
+    if device_id.is_empty() {
+        return Err(AppError::Validation("deviceId is required".to_string()));
+    }
+    if device_id.len() > MAX_DEVICE_ID_LENGTH
+        || !device_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.' | ':'))
+    {
+        return Err(AppError::Validation(
+            "deviceId must be at most 64 letters, digits, '_', '-', '.' or ':'".to_string(),
+        ));
+    }
+    Ok(device_id.to_string())
+}
+
+/// 校验并规范化设备写入参数
+///
+/// 可选文本去除空白后空串视为未设置；
+/// 自由属性必须是 JSON 对象；归属用户必须存在
+fn normalize_input(owner_username: String, fields: DeviceFields) -> Result<DeviceInput, AppError> {
+    let device_name = fields.device_name.trim().to_string();
+    if device_name.is_empty() {
+        return Err(AppError::Validation("deviceName is required".to_string()));
+    }
+    let device_type = normalize_device_type(&fields.device_type)?;
+    if !fields.attributes.is_object() {
+        return Err(AppError::Validation(
+            "attributes must be a JSON object".to_string(),
+        ));
+    }
+    if admin_repository::find_user_id_by_username(&owner_username)?.is_none() {
+        return Err(AppError::Validation("owner user not found".to_string()));
+    }
+    Ok(DeviceInput {
+        device_name,
+        owner_username,
+        device_type,
+        manufacturer: trim_optional(fields.manufacturer),
+        model: trim_optional(fields.model),
+        serial_number: trim_optional(fields.serial_number),
+        location_id: fields.location_id,
+        comm_config_ref: trim_optional(fields.comm_config_ref),
+        enabled: fields.enabled,
+        attributes: fields.attributes,
+    })
+}
+
+/// 校验并规范化设备类型
+///
+/// 设备类型统一转为小写，只允许小写字母、数字、`_`、`-`，最长 32 个字符；设备模板复用同一规则
+pub(crate) fn normalize_device_type(device_type: &str) -> Result<String, AppError> {
+    let device_type = device_type.trim().to_lowercase();
+    if device_type.is_empty() {
+        return Err(AppError::Validation("deviceType is required".to_string()));
+    }
+    if device_type.len() > MAX_DEVICE_TYPE_LENGTH
+        || !device_type
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '_' | '-'))
+    {
+        return Err(AppError::Validation(
+            "deviceType must be at most 32 letters, digits, '_' or '-'".to_string(),
+        ));
+    }
+    Ok(device_type)
+}
+
+/// 去除可选文本首尾空白，空串视为未设置
+pub(crate) fn trim_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 将设备记录转换为响应格式
+fn map_device_record(record: DeviceRecord) -> DeviceData {
+    DeviceData {
+        device_id: record.device_id,
+        device_name: record.device_name,
+        owner_username: record.owner_username,
+        device_type: record.device_type,
+        manufacturer: record.manufacturer,
+        model: record.model,
+        serial_number: record.serial_number,
+        location_id: record.location_id,
+        comm_config_ref: record.comm_config_ref,
+        enabled: record.enabled,
+        attributes: record.attributes,
+        template_id: record.template_id,
+        template_version: record.template_version,
+        lifecycle_state: record.lifecycle_state,
+        lifecycle_changed_at: record.lifecycle_changed_at,
+        registered_at: record.registered_at,
+        updated_at: record.updated_at,
+    }
+}
+
+/// 查询设备当前快照（审计操作前内容，查询失败时不记录快照）
+fn find_snapshot(device_id: &str) -> Option<Value> {
+    if device_id.is_empty() {
+        return None;
+    }
+    repository::find_device(device_id)
+        .ok()
+        .flatten()
+        .map(map_device_record)
+        .as_ref()
+        .and_then(snapshot)
+}
+
+/// 将设备响应数据序列化为审计快照
+fn snapshot(data: &DeviceData) -> Option<Value> {
+    serde_json::to_value(data).ok()
+}
@@ -0,0 +1,321 @@
+//! 设备生命周期模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的设备生命周期相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `device_lifecycle_states` | 查询生命周期状态、允许的流转与采集告警策略 |
+//! | `device_lifecycle_transition` | 流转单个设备的生命周期状态 |
+//! | `device_lifecycle_bulk_transition` | 批量流转设备的生命周期状态 |
+//! | `device_lifecycle_history` | 分页查询设备的流转历史 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入设备生命周期数据模型
+use crate::device_lifecycle::models::{
+    DeviceLifecycleBulkData, DeviceLifecycleBulkTransitionPayload, DeviceLifecycleData,
+    DeviceLifecycleHistoryData, DeviceLifecycleHistoryPayload, DeviceLifecycleStateData,
+    DeviceLifecycleStatesPayload, DeviceLifecycleTransitionPayload,
+};
+// 引入设备生命周期服务层
+use crate::device_lifecycle::services;
+
+/// 查询生命周期状态定义
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+///
+/// # 返回
+/// * 各状态允许的流转与采集、告警策略
+#[tauri::command]
+pub fn device_lifecycle_states(
+    payload: DeviceLifecycleStatesPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<DeviceLifecycleStateData>> {
+    execute_traced_command("device_lifecycle_states", trace, || {
+        Ok(ApiResponse::ok(services::list_states(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 流转单个设备的生命周期状态
+///
+/// # 参数
+/// * `payload` - 设备标识、目标状态与流转原因
+///
+/// # 返回
+/// * 流转后的设备生命周期
+#[tauri::command]
+pub fn device_lifecycle_transition(
+    payload: DeviceLifecycleTransitionPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceLifecycleData> {
+    execute_traced_command("device_lifecycle_transition", trace, || {
+        Ok(ApiResponse::ok(services::transition_device(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 批量流转设备的生命周期状态
+///
+/// # 参数
+/// * `payload` - 设备标识列表、目标状态与流转原因
+///
+/// # 返回
+/// * 流转成功与失败的设备
+#[tauri::command]
+pub fn device_lifecycle_bulk_transition(
+    payload: DeviceLifecycleBulkTransitionPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceLifecycleBulkData> {
+    execute_traced_command("device_lifecycle_bulk_transition", trace, || {
+        Ok(ApiResponse::ok(services::bulk_transition(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 分页查询设备的流转历史
+///
+/// # 参数
+/// * `payload` - 设备标识与分页参数
+///
+/// # 返回
+/// * 按流转时间倒序的历史分页结果
+#[tauri::command]
+pub fn device_lifecycle_history(
+    payload: DeviceLifecycleHistoryPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceLifecycleHistoryData> {
+    execute_traced_command("device_lifecycle_history", trace, || {
+        Ok(ApiResponse::ok(services::list_history(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use crate::db::test_support::{ensure_test_db_ready, unique_code};
+    use crate::device::commands::{device_create, device_get, device_list};
+    use crate::device::models::{DeviceCreatePayload, DeviceGetPayload, DeviceListPayload};
+
+    fn create_device(prefix: &str) -> String {
+        let device_id = unique_code(prefix);
+        device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+                device_name: format!("{device_id} 名称"),
+                device_type: "meter".to_string(),
+                ..DeviceCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create device");
+        device_id
+    }
+
+    fn transition(device_id: &str, to_state: &str) -> AppResult<DeviceLifecycleData> {
+        device_lifecycle_transition(
+            DeviceLifecycleTransitionPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.to_string(),
+                to_state: to_state.to_string(),
+                reason: format!("切换到 {to_state}"),
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn transitions_follow_state_machine_and_record_history() {
+        ensure_test_db_ready();
+        let device_id = create_device("lifecycle_single");
+        let device = device_get(
+            DeviceGetPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+            },
+            None,
+        )
+        .expect("get device")
+        .data;
+        assert_eq!(device.lifecycle_state, "planned");
+
+        assert_eq!(
+            transition(&device_id, "in_service").expect_err("skip states"),
+            AppError::Validation("transition not allowed: planned -> in_service".to_string())
+        );
+        for state in ["installed", "commissioned", "in_service"] {
+            transition(&device_id, state).expect("transition");
+        }
+        let maintenance = transition(&device_id, "maintenance")
+            .expect("enter maintenance")
+            .data;
+        assert!(maintenance.acquisition_enabled);
+        assert!(!maintenance.alarms_enabled);
+        let in_service = transition(&device_id, "in_service")
+            .expect("leave maintenance")
+            .data;
+        assert!(in_service.alarms_enabled);
+        assert_eq!(
+            transition(&device_id, "in_service").expect_err("same state"),
+            AppError::Validation("device already in state in_service".to_string())
+        );
+
+        let err = device_lifecycle_transition(
+            DeviceLifecycleTransitionPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+                to_state: "decommissioned".to_string(),
+                reason: "  ".to_string(),
+            },
+            None,
+        )
+        .expect_err("reason required");
+        assert_eq!(err, AppError::Validation("reason is required".to_string()));
+
+        let history = device_lifecycle_history(
+            DeviceLifecycleHistoryPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+                page: Some(1),
+                page_size: Some(2),
+            },
+            None,
+        )
+        .expect("list history")
+        .data;
+        assert_eq!(history.total, 5);
+        assert_eq!(history.items.len(), 2);
+        assert_eq!(history.items[0].from_state, "maintenance");
+        assert_eq!(history.items[0].to_state, "in_service");
+        assert_eq!(history.items[0].actor, "admin");
+        assert_eq!(history.items[0].reason, "切换到 in_service");
+
+        transition(&device_id, "decommissioned").expect("decommission");
+        assert_eq!(
+            transition(&device_id, "planned").expect_err("terminal state"),
+            AppError::Validation("transition not allowed: decommissioned -> planned".to_string())
+        );
+        let listed = device_list(
+            DeviceListPayload {
+                operator_username: "admin".to_string(),
+                keyword: Some(device_id.clone()),
+                lifecycle_state: Some("decommissioned".to_string()),
+                ..DeviceListPayload::default()
+            },
+            None,
+        )
+        .expect("list decommissioned")
+        .data;
+        assert_eq!(listed.total, 1);
+    }
+
+    #[test]
+    fn bulk_transition_reports_per_device_results() {
+        ensure_test_db_ready();
+        let first = create_device("lifecycle_bulk_a");
+        let second = create_device("lifecycle_bulk_b");
+        transition(&second, "installed").expect("install second");
+
+        let data = device_lifecycle_bulk_transition(
+            DeviceLifecycleBulkTransitionPayload {
+                operator_username: "admin".to_string(),
+                device_ids: vec![
+                    first.clone(),
+                    second.clone(),
+                    first.clone(),
+                    "missing".to_string(),
+                ],
+                to_state: "installed".to_string(),
+                reason: "批量安装".to_string(),
+            },
+            None,
+        )
+        .expect("bulk transition")
+        .data;
+        assert_eq!(data.succeeded.len(), 1);
+        assert_eq!(data.succeeded[0].device_id, first);
+        assert_eq!(data.succeeded[0].lifecycle_state, "installed");
+        assert_eq!(data.failed.len(), 2);
+        assert_eq!(data.failed[0].device_id, second);
+        assert_eq!(data.failed[0].message, "device already in state installed");
+        assert_eq!(data.failed[1].message, "device not found");
+
+        let err = device_lifecycle_bulk_transition(
+            DeviceLifecycleBulkTransitionPayload {
+                operator_username: "admin".to_string(),
+                device_ids: vec![first],
+                to_state: "retired".to_string(),
+                reason: "无效状态".to_string(),
+            },
+            None,
+        )
+        .expect_err("invalid state");
+        assert_eq!(
+            err,
+            AppError::Validation(
+                "toState must be one of planned, installed, commissioned, in_service, maintenance, decommissioned"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn states_describe_policies_and_transitions_require_permission() {
+        ensure_test_db_ready();
+        let states = device_lifecycle_states(
+            DeviceLifecycleStatesPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list states")
+        .data;
+        assert_eq!(states.len(), 6);
+        let maintenance = states
+            .iter()
+            .find(|state| state.state == "maintenance")
+            .expect("maintenance state");
+        assert!(maintenance.acquisition_enabled);
+        assert!(!maintenance.alarms_enabled);
+        assert!(
+            states
+                .iter()
+                .find(|state| state.state == "decommissioned")
+                .expect("decommissioned state")
+                .transitions
+                .is_empty()
+        );
+
+        let device_id = create_device("lifecycle_forbidden");
+        let err = device_lifecycle_transition(
+            DeviceLifecycleTransitionPayload {
+                operator_username: "common".to_string(),
+                device_id,
+                to_state: "installed".to_string(),
+                reason: "无权限".to_string(),
+            },
+            None,
+        )
+        .expect_err("forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device create required".to_string())
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! 设备生命周期模块入口
+//!
+//! 本模块维护设备的生命周期状态：
+//! - 规划、已安装、已调试、运行中、维护中、已退役六个状态及允许的流转，由服务层强制校验
+//! - 各状态下的采集与告警策略（例如维护中的设备不产生告警）
+//! - 每次流转记录操作人与原因，支持单个与批量流转
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 生命周期请求/响应结构
+pub mod models;
+// 公开服务模块 - 状态机、采集告警策略与流转业务逻辑
+pub mod services;
+// 公开仓储模块 - 状态变更与流转历史的 SeaORM 读写
+pub mod repository;
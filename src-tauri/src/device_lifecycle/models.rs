@@ -0,0 +1,154 @@
+//! 设备生命周期模块数据模型
+//!
+//! 本模块定义生命周期流转历史的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+/// 生命周期流转历史存储记录
+///
+/// 与 device_lifecycle_history 表对应
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleHistoryRecord {
+    pub id: i64,            // 历史记录 ID
+    pub device_id: String,  // 设备标识
+    pub from_state: String, // 流转前状态
+    pub to_state: String,   // 流转后状态
+    pub reason: String,     // 流转原因
+    pub actor: String,      // 操作人用户名
+    pub changed_at: i64,    // 流转时间戳（毫秒）
+}
+
+// 生命周期状态定义请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceLifecycleStatesPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 单个设备状态流转请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceLifecycleTransitionPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 目标状态
+    pub to_state: String,
+    /// 流转原因
+    pub reason: String,
+}
+
+// 批量状态流转请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceLifecycleBulkTransitionPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识列表（最多 500 个）
+    pub device_ids: Vec<String>,
+    /// 目标状态
+    pub to_state: String,
+    /// 流转原因
+    pub reason: String,
+}
+
+// 流转历史查询请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceLifecycleHistoryPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 页码（从 1 开始）
+    pub page: Option<u32>,
+    /// 每页条数（默认 20，最大 200）
+    pub page_size: Option<u32>,
+}
+
+// 生命周期状态定义响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleStateData {
+    /// 状态
+    pub state: String,
+    /// 允许流转到的状态
+    pub transitions: Vec<String>,
+    /// 是否采集数据
+    pub acquisition_enabled: bool,
+    /// 是否产生告警
+    pub alarms_enabled: bool,
+}
+
+// 设备生命周期响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleData {
+    /// 设备标识
+    pub device_id: String,
+    /// 当前状态
+    pub lifecycle_state: String,
+    /// 进入当前状态的时间戳（毫秒）
+    pub lifecycle_changed_at: i64,
+    /// 是否采集数据
+    pub acquisition_enabled: bool,
+    /// 是否产生告警
+    pub alarms_enabled: bool,
+}
+
+// 批量流转中失败的设备
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleFailure {
+    /// 设备标识
+    pub device_id: String,
+    /// 失败原因
+    pub message: String,
+}
+
+// 批量流转结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleBulkData {
+    /// 流转成功的设备
+    pub succeeded: Vec<DeviceLifecycleData>,
+    /// 流转失败的设备
+    pub failed: Vec<DeviceLifecycleFailure>,
+}
+
+// 流转历史条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleHistoryItem {
+    /// 历史记录 ID
+    pub id: i64,
+    /// 设备标识
+    pub device_id: String,
+    /// 流转前状态
+    pub from_state: String,
+    /// 流转后状态
+    pub to_state: String,
+    /// 流转原因
+    pub reason: String,
+    /// 操作人用户名
+    pub actor: String,
+    /// 流转时间戳（毫秒）
+    pub changed_at: i64,
+}
+
+// 流转历史分页响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLifecycleHistoryData {
+    /// 总条数
+    pub total: i64,
+    /// 当前页码
+    pub page: u32,
+    /// 每页条数
+    pub page_size: u32,
+    /// 当前页数据（按流转时间倒序）
+    pub items: Vec<DeviceLifecycleHistoryItem>,
+}
@@ -0,0 +1,129 @@
+//! 设备生命周期模块数据仓储层
+//!
+//! 本模块负责设备生命周期状态的变更与 device_lifecycle_history 表的读写：
+//! - 按流转前状态做条件更新，并在同一事务中写入流转历史
+//! - 按设备分页查询流转历史
+//!
+//! 均为简单 CRUD，按 `docs/database-access-policy.md` 规则 1 使用 SeaORM 实现
+
+// 引入 SeaORM 查询表达式
+use sea_orm::sea_query::Expr;
+// 引入 SeaORM 核心 trait
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入实体模型
+use crate::db::entities::{device_lifecycle_history, device_registry};
+// 引入设备生命周期模型
+use crate::device_lifecycle::models::LifecycleHistoryRecord;
+
+/// 变更设备生命周期状态并写入流转历史（单事务）
+///
+/// 仅当设备当前状态仍为 `record.from_state` 时更新，避免并发流转覆盖
+///
+/// # 参数
+/// * `record` - 流转记录（`id` 忽略）
+///
+/// # 返回
+/// * 更新成功返回 true；设备不存在或状态已被修改返回 false
+pub fn transition_device(record: LifecycleHistoryRecord) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let result = device_registry::Entity::update_many()
+            .col_expr(
+                device_registry::Column::LifecycleState,
+                Expr::value(record.to_state.as_str()),
+            )
+            .col_expr(
+                device_registry::Column::LifecycleChangedAt,
+                Expr::value(record.changed_at),
+            )
+            .col_expr(
+                device_registry::Column::UpdatedAt,
+                Expr::value(record.changed_at),
+            )
+            .filter(device_registry::Column::DeviceId.eq(record.device_id.as_str()))
+            .filter(device_registry::Column::LifecycleState.eq(record.from_state.as_str()))
+            .exec(&transaction)
+            .await
+            .map_err(map_db_error)?;
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+        device_lifecycle_history::ActiveModel {
+            device_id: Set(record.device_id),
+            from_state: Set(record.from_state),
+            to_state: Set(record.to_state),
+            reason: Set(record.reason),
+            actor: Set(record.actor),
+            changed_at: Set(record.changed_at),
+            ..Default::default()
+        }
+        .insert(&transaction)
+        .await
+        .map_err(map_db_error)?;
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(true)
+    })
+}
+
+/// 分页查询设备的流转历史
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `limit` - 每页条数
+/// * `offset` - 偏移量
+///
+/// # 返回
+/// * (总条数, 当前页按流转时间倒序的记录)
+pub fn query_history(
+    device_id: &str,
+    limit: u64,
+    offset: u64,
+) -> Result<(i64, Vec<LifecycleHistoryRecord>), AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let total = device_lifecycle_history::Entity::find()
+            .filter(device_lifecycle_history::Column::DeviceId.eq(device_id))
+            .count(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let models = device_lifecycle_history::Entity::find()
+            .filter(device_lifecycle_history::Column::DeviceId.eq(device_id))
+            .order_by_desc(device_lifecycle_history::Column::ChangedAt)
+            .order_by_desc(device_lifecycle_history::Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let total = i64::try_from(total)
+            .map_err(|_| AppError::Database("history count out of range".to_string()))?;
+        Ok((total, models.into_iter().map(map_model).collect()))
+    })
+}
+
+/// 将实体模型转换为流转历史记录
+fn map_model(model: device_lifecycle_history::Model) -> LifecycleHistoryRecord {
+    LifecycleHistoryRecord {
+        id: model.id,
+        device_id: model.device_id,
+        from_state: model.from_state,
+        to_state: model.to_state,
+        reason: model.reason,
+        actor: model.actor,
+        changed_at: model.changed_at,
+    }
+}
+
+/// 将数据库错误映射为应用错误
+fn map_db_error(err: DbErr) -> AppError {
+    AppError::Database(err.to_string())
+}
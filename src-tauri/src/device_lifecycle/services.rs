@@ -0,0 +1,389 @@
+//! 设备生命周期模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 生命周期状态机：规划、已安装、已调试、运行中、维护中、已退役，以及允许的流转
+//! - 各状态下的采集与告警策略：采集引擎按采集策略决定是否调度设备关联的从站，流转后唤醒采集线程重新生成读取计划；
+//!   告警策略目前只在状态定义中返回，尚无模块据此判定
+//! - 单个与批量状态流转（流转原因必填），以及流转历史查询
+//! - 权限校验：`device:view`（状态定义与历史）、`device:create`（状态流转），并校验用户设备范围
+//! - 状态流转的审计记录（`targetType = "device"`）
+
+// 引入 JSON 构造宏与值类型
+use serde_json::{Value, json};
+
+// 引入采集引擎（状态流转后重新生成读取计划）
+use crate::acquisition::engine as acquisition_engine;
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入设备仓储（审计快照）
+use crate::device::repository as device_repository;
+// 引入设备服务（操作员校验与设备范围校验）
+use crate::device::services as device_services;
+// 引入设备生命周期模型
+use crate::device_lifecycle::models::{
+    DeviceLifecycleBulkData, DeviceLifecycleBulkTransitionPayload, DeviceLifecycleData,
+    DeviceLifecycleFailure, DeviceLifecycleHistoryData, DeviceLifecycleHistoryItem,
+    DeviceLifecycleHistoryPayload, DeviceLifecycleStateData, DeviceLifecycleStatesPayload,
+    DeviceLifecycleTransitionPayload, LifecycleHistoryRecord,
+};
+// 引入设备生命周期仓储模块
+use crate::device_lifecycle::repository;
+
+// 审计目标类型：设备
+const TARGET_TYPE_DEVICE: &str = "device";
+
+/// 生命周期状态（按典型流转顺序排列）
+pub const LIFECYCLE_STATES: [&str; 6] = [
+    "planned",
+    "installed",
+    "commissioned",
+    "in_service",
+    "maintenance",
+    "decommissioned",
+];
+
+// 默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+// 每页条数上限
+const MAX_PAGE_SIZE: u32 = 200;
+
+// 批量流转的设备数上限
+const MAX_BULK_DEVICES: usize = 500;
+
+// 流转原因最大长度（字符）
+const MAX_REASON_LENGTH: usize = 500;
+
+/// 状态允许流转到的目标状态
+///
+/// 已退役为终态；维护结束可回到运行中，或回到已调试重新验收
+///
+/// # 参数
+/// * `state` - 当前状态
+///
+/// # 返回
+/// * 允许的目标状态（未知状态为空）
+pub fn allowed_transitions(state: &str) -> &'static [&'static str] {
+    match state {
+        "planned" => &["installed", "decommissioned"],
+        "installed" => &["commissioned", "maintenance", "decommissioned"],
+        "commissioned" => &["in_service", "maintenance", "decommissioned"],
+        "in_service" => &["maintenance", "decommissioned"],
+        "maintenance" => &["in_service", "commissioned", "decommissioned"],
+        _ => &[],
+    }
+}
+
+/// 该状态下是否采集数据
+///
+/// 已调试、运行中与维护中的设备参与采集；规划、已安装与已退役的设备不采集（采集引擎据此跳过设备关联的从站）
+pub fn acquisition_enabled(state: &str) -> bool {
+    matches!(state, "commissioned" | "in_service" | "maintenance")
+}
+
+/// 该状态下是否产生告警
+///
+/// 只有运行中的设备产生告警，调试与维护期间的异常数据不告警；目前只在状态定义中返回
+pub fn alarms_enabled(state: &str) -> bool {
+    state == "in_service"
+}
+
+/// 查询生命周期状态定义
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 各状态允许的流转与采集、告警策略
+pub fn list_states(
+    payload: &DeviceLifecycleStatesPayload,
+    now_millis: u64,
+) -> Result<Vec<DeviceLifecycleStateData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    Ok(LIFECYCLE_STATES
+        .iter()
+        .map(|state| DeviceLifecycleStateData {
+            state: (*state).to_string(),
+            transitions: allowed_transitions(state)
+                .iter()
+                .map(|target| (*target).to_string())
+                .collect(),
+            acquisition_enabled: acquisition_enabled(state),
+            alarms_enabled: alarms_enabled(state),
+        })
+        .collect())
+}
+
+/// 流转单个设备的生命周期状态
+///
+/// # 参数
+/// * `payload` - 设备标识、目标状态与流转原因
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 流转后的设备生命周期
+pub fn transition_device(
+    payload: &DeviceLifecycleTransitionPayload,
+    now_millis: u64,
+) -> Result<DeviceLifecycleData, AppError> {
+    let operator_username = payload.operator_username.trim();
+    let device_id = payload.device_id.trim();
+    let before = find_snapshot(device_id);
+    let result = device_services::assert_operator_allowed(
+        operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )
+    .and_then(|(operator_username, user_id, now)| {
+        let transition = normalize_transition(&payload.to_state, &payload.reason)?;
+        transition_one(&operator_username, user_id, device_id, &transition, now)
+    });
+    let after = result.as_ref().ok().map(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_lifecycle_transition",
+            operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 批量流转设备的生命周期状态
+///
+/// 各设备独立流转并分别记录审计，单个设备失败不影响其他设备
+///
+/// # 参数
+/// * `payload` - 设备标识列表、目标状态与流转原因
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 流转成功与失败的设备
+pub fn bulk_transition(
+    payload: DeviceLifecycleBulkTransitionPayload,
+    now_millis: u64,
+) -> Result<DeviceLifecycleBulkData, AppError> {
+    let (operator_username, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let transition = normalize_transition(&payload.to_state, &payload.reason)?;
+    let mut device_ids: Vec<String> = Vec::with_capacity(payload.device_ids.len());
+    for device_id in payload.device_ids {
+        let device_id = device_id.trim().to_string();
+        if !device_id.is_empty() && !device_ids.contains(&device_id) {
+            device_ids.push(device_id);
+        }
+    }
+    if device_ids.is_empty() {
+        return Err(AppError::Validation("deviceIds is required".to_string()));
+    }
+    if device_ids.len() > MAX_BULK_DEVICES {
+        return Err(AppError::Validation(
+            "deviceIds must contain at most 500 devices".to_string(),
+        ));
+    }
+
+    let mut data = DeviceLifecycleBulkData::default();
+    for device_id in device_ids {
+        let before = find_snapshot(&device_id);
+        let result = transition_one(&operator_username, user_id, &device_id, &transition, now);
+        let after = result.as_ref().ok().map(snapshot);
+        audit_services::record_command(
+            CommandAudit {
+                command: "device_lifecycle_bulk_transition",
+                operator_username: &operator_username,
+                target_type: TARGET_TYPE_DEVICE,
+                target_id: audit_services::target_id(&device_id),
+            },
+            (before, after),
+            &result,
+            now_millis,
+        );
+        match result {
+            Ok(item) => data.succeeded.push(item),
+            Err(err) => data.failed.push(DeviceLifecycleFailure {
+                device_id,
+                message: err.to_string(),
+            }),
+        }
+    }
+    Ok(data)
+}
+
+/// 分页查询设备的流转历史
+///
+/// # 参数
+/// * `payload` - 设备标识与分页参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按流转时间倒序的历史分页结果
+pub fn list_history(
+    payload: &DeviceLifecycleHistoryPayload,
+    now_millis: u64,
+) -> Result<DeviceLifecycleHistoryData, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let device = device_services::ensure_device_accessible(user_id, &payload.device_id, now)?;
+
+    // 计算分页参数
+    let page = payload.page.unwrap_or(1).max(1);
+    let page_size = payload
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = u64::from(page - 1) * u64::from(page_size);
+
+    let (total, records) =
+        repository::query_history(&device.device_id, u64::from(page_size), offset)?;
+    Ok(DeviceLifecycleHistoryData {
+        total,
+        page,
+        page_size,
+        items: records.into_iter().map(map_history_record).collect(),
+    })
+}
+
+// 已校验的流转目标
+struct Transition {
+    to_state: String, // 目标状态
+    reason: String,   // 流转原因
+}
+
+/// 校验目标状态与流转原因
+fn normalize_transition(to_state: &str, reason: &str) -> Result<Transition, AppError> {
+    let to_state = to_state.trim().to_lowercase();
+    if !LIFECYCLE_STATES.contains(&to_state.as_str()) {
+        return Err(AppError::Validation(format!(
+            "toState must be one of {}",
+            LIFECYCLE_STATES.join(", ")
+        )));
+    }
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(AppError::Validation("reason is required".to_string()));
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(AppError::Validation(
+            "reason must be at most 500 characters".to_string(),
+        ));
+    }
+    Ok(Transition { to_state, reason })
+}
+
+/// 校验设备范围与状态机后流转单个设备
+fn transition_one(
+    operator_username: &str,
+    user_id: i64,
+    device_id: &str,
+    transition: &Transition,
+    now_millis: i64,
+) -> Result<DeviceLifecycleData, AppError> {
+    let device = device_services::ensure_device_accessible(user_id, device_id, now_millis)?;
+    let from_state = device.lifecycle_state;
+    if from_state == transition.to_state {
+        return Err(AppError::Validation(format!(
+            "device already in state {from_state}"
+        )));
+    }
+    if !allowed_transitions(&from_state).contains(&transition.to_state.as_str()) {
+        return Err(AppError::Validation(format!(
+            "transition not allowed: {from_state} -> {}",
+            transition.to_state
+        )));
+    }
+    let updated = repository::transition_device(LifecycleHistoryRecord {
+        id: 0,
+        device_id: device.device_id.clone(),
+        from_state,
+        to_state: transition.to_state.clone(),
+        reason: transition.reason.clone(),
+        actor: operator_username.to_string(),
+        changed_at: now_millis,
+    })?;
+    if !updated {
+        return Err(AppError::Validation(
+            "device lifecycle state changed concurrently, please retry".to_string(),
+        ));
+    }
+    acquisition_engine::invalidate_plans();
+    Ok(lifecycle_data(
+        device.device_id,
+        transition.to_state.clone(),
+        now_millis,
+    ))
+}
+
+/// 组装设备生命周期响应数据
+fn lifecycle_data(
+    device_id: String,
+    lifecycle_state: String,
+    lifecycle_changed_at: i64,
+) -> DeviceLifecycleData {
+    DeviceLifecycleData {
+        acquisition_enabled: acquisition_enabled(&lifecycle_state),
+        alarms_enabled: alarms_enabled(&lifecycle_state),
+        device_id,
+        lifecycle_state,
+        lifecycle_changed_at,
+    }
+}
+
+/// 将流转历史记录转换为响应格式
+fn map_history_record(record: LifecycleHistoryRecord) -> DeviceLifecycleHistoryItem {
+    DeviceLifecycleHistoryItem {
+        id: record.id,
+        device_id: record.device_id,
+        from_state: record.from_state,
+        to_state: record.to_state,
+        reason: record.reason,
+        actor: record.actor,
+        changed_at: record.changed_at,
+    }
+}
+
+/// 查询设备当前生命周期快照（审计操作前内容，查询失败时不记录快照）
+fn find_snapshot(device_id: &str) -> Option<Value> {
+    if device_id.is_empty() {
+        return None;
+    }
+    device_repository::find_device(device_id)
+        .ok()
+        .flatten()
+        .map(|record| {
+            json!({
+                "lifecycleState": record.lifecycle_state,
+                "lifecycleChangedAt": record.lifecycle_changed_at,
+            })
+        })
+}
+
+/// 流转结果的审计快照（状态与流转时间）
+fn snapshot(data: &DeviceLifecycleData) -> Value {
+    json!({
+        "lifecycleState": data.lifecycle_state,
+        "lifecycleChangedAt": data.lifecycle_changed_at,
+    })
+}
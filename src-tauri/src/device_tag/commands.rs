@@ -0,0 +1,535 @@
+//! 设备标签模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的设备标签相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `device_tag_list` | 查询设备级标签与点位标签 |
+//! | `device_tag_set` | 设置设备或点位上的单个标签 |
+//! | `device_tag_delete` | 删除设备或点位上的单个标签 |
+//! | `device_tag_bulk_update` | 批量为设备或同名点位打标签 |
+//! | `device_tag_select` | 按标签选择器分页查询设备 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入设备标签数据模型
+use crate::device_tag::models::{
+    DeviceTagBulkData, DeviceTagBulkPayload, DeviceTagDeletePayload, DeviceTagListPayload,
+    DeviceTagSelectData, DeviceTagSelectPayload, DeviceTagSetPayload, DeviceTagsData, TagData,
+};
+// 引入设备标签服务层
+use crate::device_tag::services;
+
+/// 查询设备标签
+///
+/// # 参数
+/// * `payload` - 设备标识
+///
+/// # 返回
+/// * 设备级标签与点位标签
+#[tauri::command]
+pub fn device_tag_list(
+    payload: DeviceTagListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTagsData> {
+    execute_traced_command("device_tag_list", trace, || {
+        Ok(ApiResponse::ok(services::list_tags(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 设置设备或点位上的单个标签
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识（可选）与标签键值
+///
+/// # 返回
+/// * 该设备或点位上的全部标签
+#[tauri::command]
+pub fn device_tag_set(
+    payload: DeviceTagSetPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<TagData>> {
+    execute_traced_command("device_tag_set", trace, || {
+        Ok(ApiResponse::ok(services::set_tag(&payload, now_millis())?))
+    })
+}
+
+/// 删除设备或点位上的单个标签
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识（可选）与标签键
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn device_tag_delete(
+    payload: DeviceTagDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("device_tag_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_tag(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 批量打标签
+///
+/// # 参数
+/// * `payload` - 设备标识列表、点位标识（可选）、新增覆盖的标签与删除的标签键
+///
+/// # 返回
+/// * 更新成功与失败的设备
+#[tauri::command]
+pub fn device_tag_bulk_update(
+    payload: DeviceTagBulkPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTagBulkData> {
+    execute_traced_command("device_tag_bulk_update", trace, || {
+        Ok(ApiResponse::ok(services::bulk_update(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 按标签选择器查询设备
+///
+/// # 参数
+/// * `payload` - 选择器文本与分页参数
+///
+/// # 返回
+/// * 规范化的选择器与匹配设备的分页结果
+#[tauri::command]
+pub fn device_tag_select(
+    payload: DeviceTagSelectPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTagSelectData> {
+    execute_traced_command("device_tag_select", trace, || {
+        Ok(ApiResponse::ok(services::select_devices(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use crate::db::test_support::{ensure_test_db_ready, unique_code};
+    use crate::device::commands::{device_create, device_list};
+    use crate::device::models::{DeviceCreatePayload, DeviceListPayload};
+    use crate::device_template::commands::{
+        device_template_create, device_template_propagate, device_template_update,
+    };
+    use crate::device_template::models::{
+        DeviceTemplateCreatePayload, DeviceTemplatePropagatePayload, DeviceTemplateSpec,
+        DeviceTemplateUpdatePayload, TemplatePointSpec,
+    };
+    use crate::location::commands::location_create;
+    use crate::location::models::{LocationCreatePayload, LocationData};
+
+    fn create_device(prefix: &str, template_id: Option<i64>) -> String {
+        create_device_with(
+            prefix,
+            DeviceCreatePayload {
+                template_id,
+                ..DeviceCreatePayload::default()
+            },
+        )
+    }
+
+    fn create_device_with(prefix: &str, payload: DeviceCreatePayload) -> String {
+        let device_id = unique_code(prefix);
+        device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+                device_name: format!("{device_id} 名称"),
+                device_type: if payload.template_id.is_some() {
+                    ""
+                } else {
+                    "meter"
+                }
+                .to_string(),
+                ..payload
+            },
+            None,
+        )
+        .expect("create device");
+        device_id
+    }
+
+    fn create_location(parent_id: Option<i64>, level: &str) -> LocationData {
+        location_create(
+            LocationCreatePayload {
+                operator_username: "admin".to_string(),
+                parent_id,
+                level: level.to_string(),
+                code: unique_code(&format!("TAG_{level}")),
+                name: format!("{level} 名称"),
+                ..LocationCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create location")
+        .data
+    }
+
+    // 辅助函数：只含给定点位（输入寄存器）的模板定义
+    fn template_spec(code: &str, keys: &[&str]) -> DeviceTemplateSpec {
+        DeviceTemplateSpec {
+            code: code.to_string(),
+            name: "标签测试模板".to_string(),
+            device_type: "meter".to_string(),
+            points: keys
+                .iter()
+                .zip(0_i64..)
+                .map(|(key, address)| TemplatePointSpec {
+                    key: (*key).to_string(),
+                    name: format!("{key} 点位"),
+                    data_type: "uint16".to_string(),
+                    register_type: "input_register".to_string(),
+                    address,
+                    ..TemplatePointSpec::default()
+                })
+                .collect(),
+            ..DeviceTemplateSpec::default()
+        }
+    }
+
+    fn set_tag(
+        device_id: &str,
+        point_key: Option<&str>,
+        key: &str,
+        value: &str,
+    ) -> AppResult<Vec<TagData>> {
+        device_tag_set(
+            DeviceTagSetPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.to_string(),
+                point_key: point_key.map(ToString::to_string),
+                key: key.to_string(),
+                value: value.to_string(),
+            },
+            None,
+        )
+    }
+
+    fn tag(key: &str, value: &str) -> TagData {
+        TagData {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn select(selector: &str) -> AppResult<DeviceTagSelectData> {
+        device_tag_select(
+            DeviceTagSelectPayload {
+                operator_username: "admin".to_string(),
+                selector: selector.to_string(),
+                ..DeviceTagSelectPayload::default()
+            },
+            None,
+        )
+    }
+
+    fn selected(selector: &str) -> Vec<String> {
+        select(selector).expect("select devices").data.device_ids
+    }
+
+    #[test]
+    fn tags_on_devices_and_points_support_crud() {
+        ensure_test_db_ready();
+        let code = unique_code("tag_tpl");
+        let template = device_template_create(
+            DeviceTemplateCreatePayload {
+                operator_username: "admin".to_string(),
+                template: template_spec(&code, &["voltage", "power"]),
+            },
+            None,
+        )
+        .expect("create template")
+        .data;
+        let device_id = create_device("tag_crud", Some(template.template.id));
+
+        set_tag(&device_id, None, "Tenant", " T-01 ").expect("set tenant");
+        let tags = set_tag(&device_id, None, "critical", "")
+            .expect("set critical")
+            .data;
+        assert_eq!(tags, vec![tag("critical", ""), tag("tenant", "T-01")]);
+        let tags = set_tag(&device_id, None, "tenant", "T-02")
+            .expect("overwrite")
+            .data;
+        assert_eq!(tags[1], tag("tenant", "T-02"));
+        set_tag(&device_id, Some("voltage"), "billing", "main").expect("tag voltage");
+        set_tag(&device_id, Some("power"), "billing", "sub").expect("tag power");
+
+        let listed = device_tag_list(
+            DeviceTagListPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+            },
+            None,
+        )
+        .expect("list tags")
+        .data;
+        assert_eq!(listed.tags.len(), 2);
+        assert_eq!(listed.points.len(), 2);
+        assert_eq!(listed.points[0].point_key, "power");
+        assert_eq!(listed.points[1].tags, vec![tag("billing", "main")]);
+
+        assert_eq!(
+            set_tag(&device_id, None, "type", "meter").expect_err("reserved key"),
+            AppError::Validation("tag key is reserved: type".to_string())
+        );
+        assert_eq!(
+            set_tag(&device_id, None, "bad key", "").expect_err("invalid key"),
+            AppError::Validation(
+                "invalid tag key 'bad key': use letters, digits, '_', '-' or '.'".to_string()
+            )
+        );
+        assert_eq!(
+            set_tag(&device_id, Some("missing"), "billing", "").expect_err("missing point"),
+            AppError::Validation("point not found".to_string())
+        );
+
+        let delete = |key: &str| {
+            device_tag_delete(
+                DeviceTagDeletePayload {
+                    operator_username: "admin".to_string(),
+                    device_id: device_id.clone(),
+                    point_key: None,
+                    key: key.to_string(),
+                },
+                None,
+            )
+        };
+        assert!(delete("critical").expect("delete tag").data);
+        assert_eq!(
+            delete("critical").expect_err("already deleted"),
+            AppError::Validation("tag not found".to_string())
+        );
+
+        // 模板移除点位后同步，已移除点位上的标签随之清理
+        device_template_update(
+            DeviceTemplateUpdatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                template: template_spec(&code, &["voltage"]),
+            },
+            None,
+        )
+        .expect("update template");
+        device_template_propagate(
+            DeviceTemplatePropagatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                device_ids: Vec::new(),
+            },
+            None,
+        )
+        .expect("propagate template");
+        let listed = device_tag_list(
+            DeviceTagListPayload {
+                operator_username: "admin".to_string(),
+                device_id,
+            },
+            None,
+        )
+        .expect("list tags after propagate")
+        .data;
+        assert_eq!(listed.tags, vec![tag("tenant", "T-02")]);
+        assert_eq!(listed.points.len(), 1);
+        assert_eq!(listed.points[0].point_key, "voltage");
+    }
+
+    #[test]
+    fn bulk_tags_resolve_through_selectors() {
+        ensure_test_db_ready();
+        // 标签键按测试唯一，避免匹配到其他用例创建的设备
+        let group = unique_code("grp");
+        let flag = unique_code("flag");
+        let first = create_device("tag_bulk_a", None);
+        let second = create_device("tag_bulk_b", None);
+        let third = create_device("tag_bulk_c", None);
+
+        let bulk = |device_ids: Vec<String>, set: Vec<TagData>, remove: Vec<String>| {
+            device_tag_bulk_update(
+                DeviceTagBulkPayload {
+                    operator_username: "admin".to_string(),
+                    device_ids,
+                    point_key: None,
+                    set,
+                    remove,
+                },
+                None,
+            )
+        };
+        let data = bulk(
+            vec![first.clone(), second.clone(), "missing".to_string()],
+            vec![tag(&group, "x")],
+            Vec::new(),
+        )
+        .expect("bulk tag")
+        .data;
+        assert_eq!(data.succeeded, vec![first.clone(), second.clone()]);
+        assert_eq!(data.failed.len(), 1);
+        assert_eq!(data.failed[0].message, "device not found");
+        bulk(vec![third.clone()], vec![tag(&group, "y z")], Vec::new()).expect("tag third");
+        bulk(vec![first.clone()], vec![tag(&flag, "")], Vec::new()).expect("flag first");
+
+        assert_eq!(
+            selected(&format!("{group}=x")),
+            vec![first.clone(), second.clone()]
+        );
+        assert_eq!(
+            selected(&format!("type=meter AND {group} AND NOT {flag}")),
+            vec![second.clone(), third.clone()]
+        );
+        assert_eq!(
+            selected(&format!("{group}=\"y z\" or {flag}")),
+            vec![first.clone(), third.clone()]
+        );
+        assert_eq!(
+            selected(&format!("{group} AND {group}!=x")),
+            vec![third.clone()]
+        );
+
+        let data = select(&format!(
+            " {group} = x and not ( {flag} or lifecycle=in_service ) "
+        ))
+        .expect("normalize selector")
+        .data;
+        assert_eq!(
+            data.selector,
+            format!("{group}=x AND NOT ({flag} OR lifecycle=in_service)")
+        );
+        assert_eq!(data.device_ids, vec![second.clone()]);
+
+        let listed = device_list(
+            DeviceListPayload {
+                operator_username: "admin".to_string(),
+                selector: Some(format!("{group}=x")),
+                ..DeviceListPayload::default()
+            },
+            None,
+        )
+        .expect("list devices by selector")
+        .data;
+        assert_eq!(listed.total, 2);
+
+        bulk(vec![first.clone()], Vec::new(), vec![flag.clone()]).expect("remove flag");
+        assert!(selected(&flag).is_empty());
+        assert_eq!(
+            bulk(vec![first], vec![tag(&flag, "")], vec![flag.clone()]).expect_err("conflict"),
+            AppError::Validation(format!("tag key in both set and remove: {flag}"))
+        );
+    }
+
+    #[test]
+    fn location_selectors_match_subtrees() {
+        ensure_test_db_ready();
+        let group = unique_code("loc_grp");
+        let floor = create_location(None, "floor");
+        let room = create_location(Some(floor.id), "room");
+        let on_floor = create_device_with(
+            "tag_loc_floor",
+            DeviceCreatePayload {
+                location_id: Some(floor.id),
+                ..DeviceCreatePayload::default()
+            },
+        );
+        let in_room = create_device_with(
+            "tag_loc_room",
+            DeviceCreatePayload {
+                location_id: Some(room.id),
+                ..DeviceCreatePayload::default()
+            },
+        );
+        let unplaced = create_device("tag_loc_none", None);
+        device_tag_bulk_update(
+            DeviceTagBulkPayload {
+                operator_username: "admin".to_string(),
+                device_ids: vec![on_floor.clone(), in_room.clone(), unplaced.clone()],
+                set: vec![tag(&group, "")],
+                ..DeviceTagBulkPayload::default()
+            },
+            None,
+        )
+        .expect("bulk tag");
+
+        assert_eq!(
+            selected(&format!("{group} AND floor={}", floor.code)),
+            vec![on_floor.clone(), in_room.clone()]
+        );
+        assert_eq!(
+            selected(&format!("{group} AND location={}", room.code)),
+            vec![in_room]
+        );
+        assert!(selected(&format!("{group} AND area={}", floor.code)).is_empty());
+        assert_eq!(
+            selected(&format!("{group} AND NOT floor={}", floor.code)),
+            vec![unplaced]
+        );
+    }
+
+    #[test]
+    fn selector_syntax_errors_and_permissions() {
+        ensure_test_db_ready();
+        for (selector, message) in [
+            ("  ", "selector is required"),
+            (
+                "critical AND",
+                "invalid selector: expected tag key at column 13",
+            ),
+            ("(critical", "invalid selector: expected ')' at column 10"),
+            (
+                "critical floor=F03",
+                "invalid selector: expected AND or OR before 'floor' at column 10",
+            ),
+            (
+                "type",
+                "invalid selector: type requires a value at column 1",
+            ),
+            (
+                "lifecycle=retired",
+                "invalid selector: unknown lifecycle state 'retired' at column 1",
+            ),
+            (
+                "tenant=\"T-01",
+                "invalid selector: unterminated quoted value at column 8",
+            ),
+        ] {
+            assert_eq!(
+                select(selector).expect_err(selector),
+                AppError::Validation(message.to_string())
+            );
+        }
+
+        let device_id = create_device("tag_forbidden", None);
+        let err = device_tag_set(
+            DeviceTagSetPayload {
+                operator_username: "common".to_string(),
+                device_id,
+                key: "critical".to_string(),
+                ..DeviceTagSetPayload::default()
+            },
+            None,
+        )
+        .expect_err("forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device create required".to_string())
+        );
+    }
+}
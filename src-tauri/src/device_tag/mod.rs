@@ -0,0 +1,17 @@
+//! 设备标签模块入口
+//!
+//! 本模块维护设备与设备点位上的键值标签：
+//! - 用于不依赖位置树的临时分组（例如冷水机组、重要负荷、租户计费电表）
+//! - 标签的增删查与批量打标签
+//! - 选择器查询语言（例如 `type=meter AND floor=F03 AND critical`），解析为设备集合，供设备列表、报表、看板与告警规则使用
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 标签请求/响应结构
+pub mod models;
+// 公开选择器模块 - 选择器文本解析与规范化
+pub mod selector;
+// 公开服务模块 - 标签校验、选择器查询与设备范围
+pub mod services;
+// 公开仓储模块 - 标签与选择器条件的 SeaORM 读写
+pub mod repository;
@@ -0,0 +1,156 @@
+//! 设备标签模块数据模型
+//!
+//! 本模块定义设备与点位标签的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+/// 设备标签存储记录
+///
+/// 与 device_tags 表对应（`point_key` 为空字符串表示设备级标签）
+#[derive(Debug, Clone, Default)]
+pub struct TagRecord {
+    pub device_id: String, // 设备标识
+    pub point_key: String, // 点位标识（空字符串表示设备级标签）
+    pub key: String,       // 标签键
+    pub value: String,     // 标签值
+    pub updated_at: i64,   // 更新时间戳（毫秒）
+}
+
+// 标签键值（请求与响应共用）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TagData {
+    /// 标签键
+    pub key: String,
+    /// 标签值（空字符串表示仅标记）
+    pub value: String,
+}
+
+// 查询设备标签请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTagListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+}
+
+// 设置单个标签请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTagSetPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 点位标识（为空表示设备级标签）
+    pub point_key: Option<String>,
+    /// 标签键
+    pub key: String,
+    /// 标签值（可省略，表示仅标记）
+    pub value: String,
+}
+
+// 删除单个标签请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTagDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 点位标识（为空表示设备级标签）
+    pub point_key: Option<String>,
+    /// 标签键
+    pub key: String,
+}
+
+// 批量打标签请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTagBulkPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识列表（最多 500 个）
+    pub device_ids: Vec<String>,
+    /// 点位标识（为空表示设备级标签，否则作用于各设备的同名点位）
+    pub point_key: Option<String>,
+    /// 新增或覆盖的标签
+    pub set: Vec<TagData>,
+    /// 删除的标签键
+    pub remove: Vec<String>,
+}
+
+// 选择器查询请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTagSelectPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 选择器文本
+    pub selector: String,
+    /// 页码（从 1 开始）
+    pub page: Option<u32>,
+    /// 每页条数（默认 20，最大 200）
+    pub page_size: Option<u32>,
+}
+
+// 点位标签
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointTagsData {
+    /// 点位标识
+    pub point_key: String,
+    /// 按键排序的标签
+    pub tags: Vec<TagData>,
+}
+
+// 设备标签响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTagsData {
+    /// 设备标识
+    pub device_id: String,
+    /// 按键排序的设备级标签
+    pub tags: Vec<TagData>,
+    /// 按点位标识排序的点位标签（只包含有标签的点位）
+    pub points: Vec<PointTagsData>,
+}
+
+// 批量打标签中失败的设备
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTagFailure {
+    /// 设备标识
+    pub device_id: String,
+    /// 失败原因
+    pub message: String,
+}
+
+// 批量打标签结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTagBulkData {
+    /// 更新成功的设备
+    pub succeeded: Vec<String>,
+    /// 更新失败的设备
+    pub failed: Vec<DeviceTagFailure>,
+}
+
+// 选择器查询响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTagSelectData {
+    /// 规范化后的选择器文本
+    pub selector: String,
+    /// 匹配的设备总数
+    pub total: i64,
+    /// 当前页码
+    pub page: u32,
+    /// 每页条数
+    pub page_size: u32,
+    /// 当前页按设备标识排序的设备
+    pub device_ids: Vec<String>,
+}
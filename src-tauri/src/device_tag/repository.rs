@@ -0,0 +1,355 @@
+//! 设备标签模块数据仓储层
+//!
+//! 本模块负责 device_tags 表的读写与选择器查询：
+//! - 设备级标签与点位标签的查询、新增覆盖与删除
+//! - 将选择器表达式转换为 device_registry 上的查询条件（标签条件为 EXISTS 子查询，走标签键值部分索引；
+//!   位置条件为位置子树递归子查询）
+//! - 设备点位随模板同步重建时清理已不存在点位上的标签
+//!
+//! 标签读写与选择器查询按 `docs/database-access-policy.md` 规则 1 使用 SeaORM 实现，
+//! 仅位置子树条件按规则 2 使用原生 SQL 片段
+
+// 引入 SeaORM 查询构造器
+use sea_orm::sea_query::{Expr, OnConflict, Query, SimpleExpr};
+// 引入 SeaORM 核心 trait
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入实体模型
+use crate::db::entities::{device_registry, device_tags};
+// 引入设备标签模型
+use crate::device_tag::models::{TagData, TagRecord};
+// 引入选择器表达式
+use crate::device_tag::selector::{SelectorAttribute, SelectorExpr};
+
+/// 查询设备的全部标签（设备级与点位标签）
+///
+/// # 参数
+/// * `device_id` - 设备标识
+///
+/// # 返回
+/// * 按点位标识、标签键排序的标签记录（设备级标签在前）
+pub fn list_device_tags(device_id: &str) -> Result<Vec<TagRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = device_tags::Entity::find()
+            .filter(device_tags::Column::DeviceId.eq(device_id))
+            .order_by_asc(device_tags::Column::PointKey)
+            .order_by_asc(device_tags::Column::TagKey)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_model).collect())
+    })
+}
+
+/// 查询单个目标（设备或点位）上的标签
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `point_key` - 点位标识（空字符串表示设备级标签）
+///
+/// # 返回
+/// * 按标签键排序的标签记录
+pub fn list_target_tags(device_id: &str, point_key: &str) -> Result<Vec<TagRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = device_tags::Entity::find()
+            .filter(device_tags::Column::DeviceId.eq(device_id))
+            .filter(device_tags::Column::PointKey.eq(point_key))
+            .order_by_asc(device_tags::Column::TagKey)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_model).collect())
+    })
+}
+
+/// 新增覆盖与删除单个目标上的标签（单事务）
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `point_key` - 点位标识（空字符串表示设备级标签）
+/// * `set` - 新增或覆盖的标签
+/// * `remove` - 删除的标签键
+/// * `now_millis` - 当前时间戳（毫秒）
+pub fn apply_tags(
+    device_id: &str,
+    point_key: &str,
+    set: Vec<TagData>,
+    remove: Vec<String>,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        if !remove.is_empty() {
+            device_tags::Entity::delete_many()
+                .filter(device_tags::Column::DeviceId.eq(device_id))
+                .filter(device_tags::Column::PointKey.eq(point_key))
+                .filter(device_tags::Column::TagKey.is_in(remove))
+                .exec(&transaction)
+                .await
+                .map_err(map_db_error)?;
+        }
+        if !set.is_empty() {
+            let models = set.into_iter().map(|tag| device_tags::ActiveModel {
+                device_id: Set(device_id.to_string()),
+                point_key: Set(point_key.to_string()),
+                tag_key: Set(tag.key),
+                tag_value: Set(tag.value),
+                updated_at: Set(now_millis),
+            });
+            device_tags::Entity::insert_many(models)
+                .on_conflict(
+                    OnConflict::columns([
+                        device_tags::Column::DeviceId,
+                        device_tags::Column::PointKey,
+                        device_tags::Column::TagKey,
+                    ])
+                    .update_columns([
+                        device_tags::Column::TagValue,
+                        device_tags::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+                )
+                .exec(&transaction)
+                .await
+                .map_err(map_db_error)?;
+        }
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(())
+    })
+}
+
+/// 删除单个标签
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `point_key` - 点位标识（空字符串表示设备级标签）
+/// * `key` - 标签键
+///
+/// # 返回
+/// * 标签存在并已删除返回 true
+pub fn delete_tag(device_id: &str, point_key: &str, key: &str) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = device_tags::Entity::delete_many()
+            .filter(device_tags::Column::DeviceId.eq(device_id))
+            .filter(device_tags::Column::PointKey.eq(point_key))
+            .filter(device_tags::Column::TagKey.eq(key))
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected > 0)
+    })
+}
+
+/// 分页查询匹配选择器的设备
+///
+/// # 参数
+/// * `selector` - 选择器表达式
+/// * `device_ids` - 可访问设备范围（None 表示不限）
+/// * `limit` - 每页条数
+/// * `offset` - 偏移量
+///
+/// # 返回
+/// * (匹配的设备总数, 当前页按设备标识排序的设备标识)
+pub fn query_selected_devices(
+    selector: &SelectorExpr,
+    device_ids: Option<Vec<String>>,
+    limit: u64,
+    offset: u64,
+) -> Result<(i64, Vec<String>), AppError> {
+    let condition = scoped_condition(selector, device_ids);
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let total = device_registry::Entity::find()
+            .filter(condition.clone())
+            .count(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let items = device_registry::Entity::find()
+            .select_only()
+            .column(device_registry::Column::DeviceId)
+            .filter(condition)
+            .order_by_asc(device_registry::Column::DeviceId)
+            .limit(limit)
+            .offset(offset)
+            .into_tuple::<String>()
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let total = i64::try_from(total)
+            .map_err(|_| AppError::Database("device count out of range".to_string()))?;
+        Ok((total, items))
+    })
+}
+
+/// 查询匹配选择器的全部设备
+///
+/// # 参数
+/// * `selector` - 选择器表达式
+/// * `device_ids` - 可访问设备范围（None 表示不限）
+///
+/// # 返回
+/// * 按设备标识排序的设备标识
+pub fn list_selected_devices(
+    selector: &SelectorExpr,
+    device_ids: Option<Vec<String>>,
+) -> Result<Vec<String>, AppError> {
+    let condition = scoped_condition(selector, device_ids);
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        device_registry::Entity::find()
+            .select_only()
+            .column(device_registry::Column::DeviceId)
+            .filter(condition)
+            .order_by_asc(device_registry::Column::DeviceId)
+            .into_tuple::<String>()
+            .all(&connection)
+            .await
+            .map_err(map_db_error)
+    })
+}
+
+/// 将选择器表达式转换为 device_registry 上的查询条件
+///
+/// 设备管理模块的设备列表复用此条件
+///
+/// # 参数
+/// * `selector` - 选择器表达式
+///
+/// # 返回
+/// * 查询条件
+pub fn selector_condition(selector: &SelectorExpr) -> Condition {
+    match selector {
+        SelectorExpr::Tag { key, value } => {
+            Condition::all().add(tag_exists(false, key, value.as_deref()))
+        }
+        SelectorExpr::PointTag { key, value } => {
+            Condition::all().add(tag_exists(true, key, value.as_deref()))
+        }
+        SelectorExpr::Attribute { attribute, value } => Condition::all().add(match attribute {
+            SelectorAttribute::DeviceType => device_registry::Column::DeviceType.eq(value.as_str()),
+            SelectorAttribute::LifecycleState => {
+                device_registry::Column::LifecycleState.eq(value.as_str())
+            }
+            SelectorAttribute::Location => location_subtree(value, None),
+            SelectorAttribute::Area => location_subtree(value, Some("area")),
+            SelectorAttribute::Floor => location_subtree(value, Some("floor")),
+        }),
+        SelectorExpr::Not(inner) => selector_condition(inner).not(),
+        SelectorExpr::And(items) => items.iter().fold(Condition::all(), |condition, item| {
+            condition.add(selector_condition(item))
+        }),
+        SelectorExpr::Or(items) => items.iter().fold(Condition::any(), |condition, item| {
+            condition.add(selector_condition(item))
+        }),
+    }
+}
+
+/// 在给定连接（通常为事务）中清理设备已不存在点位上的标签
+///
+/// 设备模板模块重建设备生效点位时在同一事务中调用
+///
+/// # 参数
+/// * `connection` - 数据库连接或事务
+/// * `device_id` - 设备标识
+/// * `point_keys` - 重建后仍存在的点位标识
+pub async fn prune_point_tags<C: ConnectionTrait>(
+    connection: &C,
+    device_id: &str,
+    point_keys: Vec<String>,
+) -> Result<(), AppError> {
+    let mut delete = device_tags::Entity::delete_many()
+        .filter(device_tags::Column::DeviceId.eq(device_id))
+        .filter(device_tags::Column::PointKey.ne(""));
+    if !point_keys.is_empty() {
+        delete = delete.filter(device_tags::Column::PointKey.is_not_in(point_keys));
+    }
+    delete.exec(connection).await.map_err(map_db_error)?;
+    Ok(())
+}
+
+/// 组合选择器条件与可访问设备范围
+fn scoped_condition(selector: &SelectorExpr, device_ids: Option<Vec<String>>) -> Condition {
+    let mut condition = Condition::all().add(selector_condition(selector));
+    if let Some(device_ids) = device_ids {
+        condition = condition.add(device_registry::Column::DeviceId.is_in(device_ids));
+    }
+    condition
+}
+
+/// 标签存在条件：`EXISTS (SELECT 1 FROM device_tags WHERE device_id = 外层设备 AND ...)`
+fn tag_exists(point_level: bool, key: &str, value: Option<&str>) -> SimpleExpr {
+    let point_condition = if point_level {
+        Expr::col((device_tags::Entity, device_tags::Column::PointKey)).ne("")
+    } else {
+        Expr::col((device_tags::Entity, device_tags::Column::PointKey)).eq("")
+    };
+    let mut subquery = Query::select();
+    subquery
+        .expr(Expr::val(1))
+        .from(device_tags::Entity)
+        .and_where(
+            Expr::col((device_tags::Entity, device_tags::Column::DeviceId))
+                .equals((device_registry::Entity, device_registry::Column::DeviceId)),
+        )
+        .and_where(point_condition)
+        .and_where(Expr::col((device_tags::Entity, device_tags::Column::TagKey)).eq(key));
+    if let Some(value) = value {
+        subquery
+            .and_where(Expr::col((device_tags::Entity, device_tags::Column::TagValue)).eq(value));
+    }
+    Expr::exists(subquery)
+}
+
+/// 位置条件：设备位于给定编码的位置节点或其任意下级位置
+///
+/// 位置子树需要递归 CTE，SeaORM 无法表达，按规则 2 以带参数的原生 SQL 片段实现；
+/// 未设置位置的设备结果为 false（而非 NULL），`NOT` 条件才能匹配到这些设备
+fn location_subtree(code: &str, level: Option<&str>) -> SimpleExpr {
+    let level_condition = if level.is_some() {
+        " AND level = $2"
+    } else {
+        ""
+    };
+    let sql = format!(
+        r#"COALESCE("device_registry"."location_id" IN (
+          WITH RECURSIVE subtree AS (
+            SELECT id FROM location_nodes WHERE code = $1{level_condition}
+            UNION ALL
+            SELECT child.id FROM location_nodes child JOIN subtree ON child.parent_id = subtree.id
+          )
+          SELECT id FROM subtree
+        ), FALSE)"#
+    );
+    match level {
+        Some(level) => Expr::cust_with_values(sql, [code, level]),
+        None => Expr::cust_with_values(sql, [code]),
+    }
+}
+
+/// 将实体模型转换为标签记录
+fn map_model(model: device_tags::Model) -> TagRecord {
+    TagRecord {
+        device_id: model.device_id,
+        point_key: model.point_key,
+        key: model.tag_key,
+        value: model.tag_value,
+        updated_at: model.updated_at,
+    }
+}
+
+/// 将数据库错误映射为应用错误
+fn map_db_error(err: DbErr) -> AppError {
+    AppError::Database(err.to_string())
+}
@@ -0,0 +1,522 @@
+//! 设备选择器解析模块
+//!
+//! 本模块将选择器文本（例如 `type=meter AND floor=F03 AND critical`）解析为表达式树：
+//! - 条件：`key`（存在标签）、`key=value`、`key!=value`；`point:key` 形式匹配设备点位上的标签
+//! - 内置字段：`type`（设备类型）、`lifecycle`（生命周期状态）、`location` / `area` / `floor`（位置编码，含下级位置），必须带值
+//! - 组合：`NOT`、`AND`、`OR`（不区分大小写，优先级依次降低）与括号
+//! - 含空白或特殊字符的值使用双引号，引号内以 `\` 转义
+//!
+//! 表达式树由设备标签仓储转换为 SQL 条件，供设备列表、选择器查询以及报表、看板与告警规则复用
+
+// 引入格式化 trait（输出规范化的选择器文本）
+use std::fmt;
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入设备生命周期服务（校验生命周期状态）
+use crate::device_lifecycle::services as lifecycle_services;
+// 引入设备标签服务（校验标签键）
+use crate::device_tag::services;
+
+// 选择器文本最大长度（字符）
+const MAX_SELECTOR_LENGTH: usize = 1000;
+
+// 选择器条件数上限
+const MAX_SELECTOR_TERMS: usize = 64;
+
+// 括号与 NOT 嵌套层数上限
+const MAX_SELECTOR_DEPTH: usize = 16;
+
+// 点位标签条件前缀
+const POINT_PREFIX: &str = "point:";
+
+/// 选择器可引用的设备内置字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorAttribute {
+    DeviceType,     // 设备类型（type）
+    LifecycleState, // 生命周期状态（lifecycle）
+    Location,       // 任意层级的位置编码（location）
+    Area,           // 区域层级的位置编码（area）
+    Floor,          // 楼层层级的位置编码（floor）
+}
+
+impl SelectorAttribute {
+    /// 按选择器中的键名查找内置字段
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "type" => Some(Self::DeviceType),
+            "lifecycle" => Some(Self::LifecycleState),
+            "location" => Some(Self::Location),
+            "area" => Some(Self::Area),
+            "floor" => Some(Self::Floor),
+            _ => None,
+        }
+    }
+
+    /// 选择器中的键名
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::DeviceType => "type",
+            Self::LifecycleState => "lifecycle",
+            Self::Location => "location",
+            Self::Area => "area",
+            Self::Floor => "floor",
+        }
+    }
+}
+
+/// 选择器表达式树
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorExpr {
+    /// 设备级标签（`value` 为 None 时只要求存在该标签键）
+    Tag { key: String, value: Option<String> },
+    /// 设备任一点位上的标签
+    PointTag { key: String, value: Option<String> },
+    /// 设备内置字段等于给定值
+    Attribute {
+        attribute: SelectorAttribute,
+        value: String,
+    },
+    /// 取反
+    Not(Box<SelectorExpr>),
+    /// 全部满足
+    And(Vec<SelectorExpr>),
+    /// 任一满足
+    Or(Vec<SelectorExpr>),
+}
+
+/// 解析选择器文本
+///
+/// # 参数
+/// * `text` - 选择器文本
+///
+/// # 返回
+/// * 表达式树（语法错误返回 `invalid selector: ...`）
+pub fn parse_selector(text: &str) -> Result<SelectorExpr, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::Validation("selector is required".to_string()));
+    }
+    if text.chars().count() > MAX_SELECTOR_LENGTH {
+        return Err(AppError::Validation(
+            "selector must be at most 1000 characters".to_string(),
+        ));
+    }
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        terms: 0,
+        end_column: text.chars().count() + 1,
+    };
+    let expr = parser.parse_or(0)?;
+    if let Some(token) = parser.peek() {
+        return Err(invalid(format!(
+            "unexpected {} at column {}",
+            token.kind.describe(),
+            token.column
+        )));
+    }
+    Ok(expr)
+}
+
+impl fmt::Display for SelectorExpr {
+    /// 输出规范化的选择器文本（关键字大写，必要时加引号与括号）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag { key, value } => write_term(f, "", key, value.as_deref()),
+            Self::PointTag { key, value } => write_term(f, POINT_PREFIX, key, value.as_deref()),
+            Self::Attribute { attribute, value } => write_term(f, "", attribute.key(), Some(value)),
+            Self::Not(inner) => match inner.as_ref() {
+                Self::And(_) | Self::Or(_) => write!(f, "NOT ({inner})"),
+                _ => write!(f, "NOT {inner}"),
+            },
+            Self::And(items) => write_joined(f, items, " AND ", |item| matches!(item, Self::Or(_))),
+            Self::Or(items) => write_joined(f, items, " OR ", |_| false),
+        }
+    }
+}
+
+/// 输出单个条件
+fn write_term(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    key: &str,
+    value: Option<&str>,
+) -> fmt::Result {
+    write!(f, "{prefix}{key}")?;
+    let Some(value) = value else {
+        return Ok(());
+    };
+    if !value.is_empty() && value.chars().all(is_word_char) && Keyword::parse(value).is_none() {
+        write!(f, "={value}")
+    } else {
+        write!(
+            f,
+            "=\"{}\"",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    }
+}
+
+/// 以分隔符连接子表达式，`wrap` 为 true 的子表达式加括号
+fn write_joined(
+    f: &mut fmt::Formatter<'_>,
+    items: &[SelectorExpr],
+    separator: &str,
+    wrap: impl Fn(&SelectorExpr) -> bool,
+) -> fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        if wrap(item) {
+            write!(f, "({item})")?;
+        } else {
+            write!(f, "{item}")?;
+        }
+    }
+    Ok(())
+}
+
+// 选择器关键字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    And,
+    Or,
+    Not,
+}
+
+impl Keyword {
+    /// 识别关键字（不区分大小写）
+    fn parse(word: &str) -> Option<Self> {
+        if word.eq_ignore_ascii_case("and") {
+            Some(Self::And)
+        } else if word.eq_ignore_ascii_case("or") {
+            Some(Self::Or)
+        } else if word.eq_ignore_ascii_case("not") {
+            Some(Self::Not)
+        } else {
+            None
+        }
+    }
+}
+
+// 词法单元类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Word(String),   // 未加引号的词（键、值或关键字）
+    Quoted(String), // 双引号值
+    Equals,         // =
+    NotEquals,      // !=
+    LeftParen,      // (
+    RightParen,     // )
+}
+
+impl TokenKind {
+    /// 错误消息中的描述
+    fn describe(&self) -> String {
+        match self {
+            Self::Word(word) => format!("'{word}'"),
+            Self::Quoted(value) => format!("\"{value}\""),
+            Self::Equals => "'='".to_string(),
+            Self::NotEquals => "'!='".to_string(),
+            Self::LeftParen => "'('".to_string(),
+            Self::RightParen => "')'".to_string(),
+        }
+    }
+}
+
+// 词法单元
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind, // 类型
+    column: usize,   // 起始列（从 1 开始，按字符计）
+}
+
+/// 未加引号的词允许的字符
+fn is_word_char(ch: char) -> bool {
+    !ch.is_whitespace() && !ch.is_control() && !matches!(ch, '(' | ')' | '=' | '!' | '"')
+}
+
+/// 词法分析
+fn tokenize(text: &str) -> Result<Vec<Token>, AppError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let column = index + 1;
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        let kind = match ch {
+            '(' => {
+                index += 1;
+                TokenKind::LeftParen
+            }
+            ')' => {
+                index += 1;
+                TokenKind::RightParen
+            }
+            '=' => {
+                index += 1;
+                TokenKind::Equals
+            }
+            '!' if chars.get(index + 1) == Some(&'=') => {
+                index += 2;
+                TokenKind::NotEquals
+            }
+            '"' => {
+                let mut value = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        None => {
+                            return Err(invalid(format!(
+                                "unterminated quoted value at column {column}"
+                            )));
+                        }
+                        Some('"') => {
+                            index += 1;
+                            break;
+                        }
+                        Some('\\') if matches!(chars.get(index + 1), Some('"' | '\\')) => {
+                            value.push(chars[index + 1]);
+                            index += 2;
+                        }
+                        Some(&other) => {
+                            value.push(other);
+                            index += 1;
+                        }
+                    }
+                }
+                TokenKind::Quoted(value)
+            }
+            _ if is_word_char(ch) => {
+                let start = index;
+                while index < chars.len() && is_word_char(chars[index]) {
+                    index += 1;
+                }
+                TokenKind::Word(chars[start..index].iter().collect())
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "unexpected character '{ch}' at column {column}"
+                )));
+            }
+        };
+        tokens.push(Token { kind, column });
+    }
+    Ok(tokens)
+}
+
+// 递归下降语法分析器
+struct Parser {
+    tokens: Vec<Token>, // 词法单元
+    position: usize,    // 当前位置
+    terms: usize,       // 已解析的条件数
+    end_column: usize,  // 文本末尾的列号（用于缺少词法单元的错误）
+}
+
+impl Parser {
+    /// 查看当前词法单元
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// 当前词法单元是否为给定关键字
+    fn peek_keyword(&self, keyword: Keyword) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token { kind: TokenKind::Word(word), .. }) if Keyword::parse(word) == Some(keyword)
+        )
+    }
+
+    /// 取出当前词法单元
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// or := and (OR and)*
+    fn parse_or(&mut self, depth: usize) -> Result<SelectorExpr, AppError> {
+        let mut items = vec![self.parse_and(depth)?];
+        while self.peek_keyword(Keyword::Or) {
+            self.next();
+            items.push(self.parse_and(depth)?);
+        }
+        Ok(flatten(items, SelectorExpr::Or))
+    }
+
+    /// and := unary (AND unary)*
+    fn parse_and(&mut self, depth: usize) -> Result<SelectorExpr, AppError> {
+        let mut items = vec![self.parse_unary(depth)?];
+        while self.peek_keyword(Keyword::And) {
+            self.next();
+            items.push(self.parse_unary(depth)?);
+        }
+        if let Some(token) = self.peek()
+            && !self.peek_keyword(Keyword::Or)
+            && token.kind != TokenKind::RightParen
+        {
+            return Err(invalid(format!(
+                "expected AND or OR before {} at column {}",
+                token.kind.describe(),
+                token.column
+            )));
+        }
+        Ok(flatten(items, SelectorExpr::And))
+    }
+
+    /// unary := NOT unary | '(' or ')' | term
+    fn parse_unary(&mut self, depth: usize) -> Result<SelectorExpr, AppError> {
+        if depth >= MAX_SELECTOR_DEPTH {
+            return Err(invalid("nesting too deep".to_string()));
+        }
+        if self.peek_keyword(Keyword::Not) {
+            self.next();
+            let inner = self.parse_unary(depth + 1)?;
+            return Ok(match inner {
+                SelectorExpr::Not(inner) => *inner,
+                inner => SelectorExpr::Not(Box::new(inner)),
+            });
+        }
+        if matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::LeftParen,
+                ..
+            })
+        ) {
+            self.next();
+            let expr = self.parse_or(depth + 1)?;
+            return match self.next() {
+                Some(Token {
+                    kind: TokenKind::RightParen,
+                    ..
+                }) => Ok(expr),
+                Some(token) => Err(invalid(format!("expected ')' at column {}", token.column))),
+                None => Err(invalid(format!(
+                    "expected ')' at column {}",
+                    self.end_column
+                ))),
+            };
+        }
+        self.parse_term()
+    }
+
+    /// term := WORD [('=' | '!=') (WORD | QUOTED)]
+    fn parse_term(&mut self) -> Result<SelectorExpr, AppError> {
+        let (word, column) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(word),
+                column,
+            }) if Keyword::parse(&word).is_none() => (word, column),
+            Some(token) => {
+                return Err(invalid(format!(
+                    "expected tag key at column {}, found {}",
+                    token.column,
+                    token.kind.describe()
+                )));
+            }
+            None => {
+                return Err(invalid(format!(
+                    "expected tag key at column {}",
+                    self.end_column
+                )));
+            }
+        };
+        self.terms += 1;
+        if self.terms > MAX_SELECTOR_TERMS {
+            return Err(invalid("too many terms (at most 64)".to_string()));
+        }
+
+        let negated = match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Equals) => Some(false),
+            Some(TokenKind::NotEquals) => Some(true),
+            _ => None,
+        };
+        let value = match negated {
+            Some(_) => {
+                let operator = self.next().map_or(column, |token| token.column);
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::Word(value) | TokenKind::Quoted(value),
+                        ..
+                    }) => Some(value),
+                    _ => {
+                        return Err(invalid(format!(
+                            "expected value after operator at column {operator}"
+                        )));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let term = build_term(&word, value).map_err(|err| match err {
+            AppError::Validation(message) => invalid(format!("{message} at column {column}")),
+            other @ (AppError::Database(_) | AppError::Modbus(_)) => other,
+        })?;
+        Ok(if negated == Some(true) {
+            SelectorExpr::Not(Box::new(term))
+        } else {
+            term
+        })
+    }
+}
+
+/// 按键名构造条件（内置字段、点位标签或设备标签）
+fn build_term(word: &str, value: Option<String>) -> Result<SelectorExpr, AppError> {
+    let lowered = word.to_lowercase();
+    if let Some(attribute) = SelectorAttribute::from_key(&lowered) {
+        let Some(value) = value else {
+            return Err(AppError::Validation(format!("{lowered} requires a value")));
+        };
+        let value = match attribute {
+            SelectorAttribute::DeviceType => value.trim().to_lowercase(),
+            SelectorAttribute::LifecycleState => {
+                let state = value.trim().to_lowercase();
+                if !lifecycle_services::LIFECYCLE_STATES.contains(&state.as_str()) {
+                    return Err(AppError::Validation(format!(
+                        "unknown lifecycle state '{state}'"
+                    )));
+                }
+                state
+            }
+            SelectorAttribute::Location | SelectorAttribute::Area | SelectorAttribute::Floor => {
+                value.trim().to_string()
+            }
+        };
+        return Ok(SelectorExpr::Attribute { attribute, value });
+    }
+    let value = value.map(|value| value.trim().to_string());
+    if let Some(point_key) = lowered.strip_prefix(POINT_PREFIX) {
+        let key = services::normalize_tag_key(point_key)?;
+        return Ok(SelectorExpr::PointTag { key, value });
+    }
+    let key = services::normalize_tag_key(word)?;
+    Ok(SelectorExpr::Tag { key, value })
+}
+
+/// 单个子表达式直接返回，多个时组合
+fn flatten(
+    mut items: Vec<SelectorExpr>,
+    combine: fn(Vec<SelectorExpr>) -> SelectorExpr,
+) -> SelectorExpr {
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        combine(items)
+    }
+}
+
+/// 构造选择器语法错误
+fn invalid(message: String) -> AppError {
+    AppError::Validation(format!("invalid selector: {message}"))
+}
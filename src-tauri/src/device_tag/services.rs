@@ -0,0 +1,486 @@
+//! 设备标签模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 设备与点位标签的查询、设置、删除与批量打标签（标签键规范化为小写，每个设备或点位最多 50 个标签）
+//! - 选择器查询：解析选择器并返回操作员设备范围内匹配的设备
+//! - 供报表、看板与告警规则等模块按选择器解析设备集合（`resolve_selector`）
+//! - 权限校验：`device:view`（查询）、`device:create`（修改标签），并校验用户设备范围
+//! - 标签变更的审计记录（`targetType = "device"`）
+
+// 引入 JSON 构造宏与值类型
+use serde_json::{Map, Value, json};
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入设备范围判定
+use crate::auth::device_scope_services::{self, DeviceAccessFilter};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入设备服务（操作员校验与设备范围校验）
+use crate::device::services as device_services;
+// 引入设备标签模型
+use crate::device_tag::models::{
+    DeviceTagBulkData, DeviceTagBulkPayload, DeviceTagDeletePayload, DeviceTagFailure,
+    DeviceTagListPayload, DeviceTagSelectData, DeviceTagSelectPayload, DeviceTagSetPayload,
+    DeviceTagsData, PointTagsData, TagData, TagRecord,
+};
+// 引入设备标签仓储模块
+use crate::device_tag::repository;
+// 引入选择器解析
+use crate::device_tag::selector::{self, SelectorAttribute, SelectorExpr};
+// 引入设备模板仓储（校验设备点位存在）
+use crate::device_template::repository as template_repository;
+
+// 审计目标类型：设备
+const TARGET_TYPE_DEVICE: &str = "device";
+
+// 默认每页条数
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+// 每页条数上限
+const MAX_PAGE_SIZE: u32 = 200;
+
+// 批量打标签的设备数上限
+const MAX_BULK_DEVICES: usize = 500;
+
+// 单个设备或点位的标签数上限
+const MAX_TAGS_PER_TARGET: usize = 50;
+
+// 标签键最大长度
+const MAX_TAG_KEY_LENGTH: usize = 64;
+
+// 标签值最大长度（字符）
+const MAX_TAG_VALUE_LENGTH: usize = 128;
+
+/// 查询设备的标签
+///
+/// # 参数
+/// * `payload` - 操作员用户名与设备标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 设备级标签与点位标签
+pub fn list_tags(
+    payload: &DeviceTagListPayload,
+    now_millis: u64,
+) -> Result<DeviceTagsData, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let device = device_services::ensure_device_accessible(user_id, &payload.device_id, now)?;
+    let mut data = DeviceTagsData {
+        device_id: device.device_id,
+        tags: Vec::new(),
+        points: Vec::new(),
+    };
+    // 记录已按点位标识、标签键排序，相邻的同一点位标签归为一组
+    for record in repository::list_device_tags(&data.device_id)? {
+        let tag = map_tag(record.clone());
+        if record.point_key.is_empty() {
+            data.tags.push(tag);
+        } else if let Some(point) = data
+            .points
+            .last_mut()
+            .filter(|point| point.point_key == record.point_key)
+        {
+            point.tags.push(tag);
+        } else {
+            data.points.push(PointTagsData {
+                point_key: record.point_key,
+                tags: vec![tag],
+            });
+        }
+    }
+    Ok(data)
+}
+
+/// 设置设备或点位上的单个标签（已存在时覆盖标签值）
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识（可选）与标签键值
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 该设备或点位上的全部标签
+pub fn set_tag(payload: &DeviceTagSetPayload, now_millis: u64) -> Result<Vec<TagData>, AppError> {
+    let operator_username = payload.operator_username.trim();
+    let device_id = payload.device_id.trim();
+    let point_key = normalize_point_key(payload.point_key.as_deref());
+    let before = find_snapshot(device_id, &point_key);
+    let result = device_services::assert_operator_allowed(
+        operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )
+    .and_then(|(_, user_id, now)| {
+        let tag = normalize_tag(&payload.key, &payload.value)?;
+        update_target(user_id, device_id, &point_key, vec![tag], Vec::new(), now)
+    });
+    let after = result
+        .is_ok()
+        .then(|| find_snapshot(device_id, &point_key))
+        .flatten();
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_tag_set",
+            operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除设备或点位上的单个标签
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识（可选）与标签键
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_tag(payload: &DeviceTagDeletePayload, now_millis: u64) -> Result<bool, AppError> {
+    let operator_username = payload.operator_username.trim();
+    let device_id = payload.device_id.trim();
+    let point_key = normalize_point_key(payload.point_key.as_deref());
+    let before = find_snapshot(device_id, &point_key);
+    let result = device_services::assert_operator_allowed(
+        operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )
+    .and_then(|(_, user_id, now)| {
+        let device = device_services::ensure_device_accessible(user_id, device_id, now)?;
+        let key = payload.key.trim().to_lowercase();
+        if !repository::delete_tag(&device.device_id, &point_key, &key)? {
+            return Err(AppError::Validation("tag not found".to_string()));
+        }
+        Ok(true)
+    });
+    let after = result
+        .is_ok()
+        .then(|| find_snapshot(device_id, &point_key))
+        .flatten();
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_tag_delete",
+            operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 批量打标签
+///
+/// 标签先整体校验，再逐台设备独立更新并分别记录审计，单个设备失败不影响其他设备
+///
+/// # 参数
+/// * `payload` - 设备标识列表、点位标识（可选）、新增覆盖的标签与删除的标签键
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新成功与失败的设备
+pub fn bulk_update(
+    payload: DeviceTagBulkPayload,
+    now_millis: u64,
+) -> Result<DeviceTagBulkData, AppError> {
+    let (operator_username, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let point_key = normalize_point_key(payload.point_key.as_deref());
+
+    let mut set: Vec<TagData> = Vec::with_capacity(payload.set.len());
+    for tag in &payload.set {
+        let tag = normalize_tag(&tag.key, &tag.value)?;
+        if set.iter().any(|existing| existing.key == tag.key) {
+            return Err(AppError::Validation(format!(
+                "duplicate tag key: {}",
+                tag.key
+            )));
+        }
+        set.push(tag);
+    }
+    let mut remove: Vec<String> = Vec::with_capacity(payload.remove.len());
+    for key in &payload.remove {
+        let key = normalize_tag_key(key)?;
+        if set.iter().any(|tag| tag.key == key) {
+            return Err(AppError::Validation(format!(
+                "tag key in both set and remove: {key}"
+            )));
+        }
+        if !remove.contains(&key) {
+            remove.push(key);
+        }
+    }
+    if set.is_empty() && remove.is_empty() {
+        return Err(AppError::Validation(
+            "set or remove is required".to_string(),
+        ));
+    }
+
+    let mut device_ids: Vec<String> = Vec::with_capacity(payload.device_ids.len());
+    for device_id in payload.device_ids {
+        let device_id = device_id.trim().to_string();
+        if !device_id.is_empty() && !device_ids.contains(&device_id) {
+            device_ids.push(device_id);
+        }
+    }
+    if device_ids.is_empty() {
+        return Err(AppError::Validation("deviceIds is required".to_string()));
+    }
+    if device_ids.len() > MAX_BULK_DEVICES {
+        return Err(AppError::Validation(
+            "deviceIds must contain at most 500 devices".to_string(),
+        ));
+    }
+
+    let mut data = DeviceTagBulkData::default();
+    for device_id in device_ids {
+        let before = find_snapshot(&device_id, &point_key);
+        let result = update_target(
+            user_id,
+            &device_id,
+            &point_key,
+            set.clone(),
+            remove.clone(),
+            now,
+        );
+        let after = result
+            .is_ok()
+            .then(|| find_snapshot(&device_id, &point_key))
+            .flatten();
+        audit_services::record_command(
+            CommandAudit {
+                command: "device_tag_bulk_update",
+                operator_username: &operator_username,
+                target_type: TARGET_TYPE_DEVICE,
+                target_id: audit_services::target_id(&device_id),
+            },
+            (before, after),
+            &result,
+            now_millis,
+        );
+        match result {
+            Ok(_) => data.succeeded.push(device_id),
+            Err(err) => data.failed.push(DeviceTagFailure {
+                device_id,
+                message: err.to_string(),
+            }),
+        }
+    }
+    Ok(data)
+}
+
+/// 按选择器分页查询设备
+///
+/// 结果只包含操作员设备范围内的设备
+///
+/// # 参数
+/// * `payload` - 选择器文本与分页参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 规范化的选择器与匹配设备的分页结果
+pub fn select_devices(
+    payload: &DeviceTagSelectPayload,
+    now_millis: u64,
+) -> Result<DeviceTagSelectData, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let expr = selector::parse_selector(&payload.selector)?;
+
+    // 计算分页参数
+    let page = payload.page.unwrap_or(1).max(1);
+    let page_size = payload
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = u64::from(page - 1) * u64::from(page_size);
+
+    let (total, device_ids) = repository::query_selected_devices(
+        &expr,
+        accessible_device_ids(user_id, now)?,
+        u64::from(page_size),
+        offset,
+    )?;
+    Ok(DeviceTagSelectData {
+        selector: expr.to_string(),
+        total,
+        page,
+        page_size,
+        device_ids,
+    })
+}
+
+/// 解析用户设备范围内匹配选择器的全部设备
+///
+/// 供报表、看板与告警规则等模块使用：保存时以 `selector::parse_selector` 校验选择器文本，
+/// 执行时调用本函数得到当前匹配的设备（标签变更后自动生效）
+///
+/// # 参数
+/// * `user_id` - 用户 ID（按其设备范围过滤）
+/// * `selector` - 选择器表达式
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按设备标识排序的设备标识
+pub fn resolve_selector(
+    user_id: i64,
+    selector: &SelectorExpr,
+    now_millis: i64,
+) -> Result<Vec<String>, AppError> {
+    repository::list_selected_devices(selector, accessible_device_ids(user_id, now_millis)?)
+}
+
+/// 规范化并校验标签键
+///
+/// 标签键转为小写，1–64 个字符，只允许字母、数字、`_`、`-`、`.` 且以字母或数字开头；
+/// 选择器内置字段名不能作为标签键
+///
+/// # 参数
+/// * `key` - 标签键
+///
+/// # 返回
+/// * 规范化的标签键
+pub(crate) fn normalize_tag_key(key: &str) -> Result<String, AppError> {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() {
+        return Err(AppError::Validation("tag key is required".to_string()));
+    }
+    if key.len() > MAX_TAG_KEY_LENGTH {
+        return Err(AppError::Validation(
+            "tag key must be at most 64 characters".to_string(),
+        ));
+    }
+    let valid = key
+        .chars()
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '_' | '-' | '.'))
+        && key.starts_with(|ch: char| ch.is_ascii_alphanumeric());
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "invalid tag key '{key}': use letters, digits, '_', '-' or '.'"
+        )));
+    }
+    if SelectorAttribute::from_key(&key).is_some() {
+        return Err(AppError::Validation(format!("tag key is reserved: {key}")));
+    }
+    Ok(key)
+}
+
+/// 规范化并校验标签键值
+fn normalize_tag(key: &str, value: &str) -> Result<TagData, AppError> {
+    let key = normalize_tag_key(key)?;
+    let value = value.trim().to_string();
+    if value.chars().count() > MAX_TAG_VALUE_LENGTH {
+        return Err(AppError::Validation(
+            "tag value must be at most 128 characters".to_string(),
+        ));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(AppError::Validation(
+            "tag value must not contain control characters".to_string(),
+        ));
+    }
+    Ok(TagData { key, value })
+}
+
+/// 规范化点位标识（为空表示设备级标签，返回空字符串）
+fn normalize_point_key(point_key: Option<&str>) -> String {
+    point_key.map(str::trim).unwrap_or_default().to_string()
+}
+
+/// 校验设备范围、点位与标签数上限后更新单个目标上的标签
+fn update_target(
+    user_id: i64,
+    device_id: &str,
+    point_key: &str,
+    set: Vec<TagData>,
+    remove: Vec<String>,
+    now_millis: i64,
+) -> Result<Vec<TagData>, AppError> {
+    let device = device_services::ensure_device_accessible(user_id, device_id, now_millis)?;
+    if !point_key.is_empty()
+        && !template_repository::list_device_points(&device.device_id)?
+            .iter()
+            .any(|point| point.point.key == point_key)
+    {
+        return Err(AppError::Validation("point not found".to_string()));
+    }
+    let current = repository::list_target_tags(&device.device_id, point_key)?;
+    let mut keys: Vec<&str> = current
+        .iter()
+        .map(|record| record.key.as_str())
+        .filter(|key| !remove.iter().any(|removed| removed == key))
+        .collect();
+    for tag in &set {
+        if !keys.contains(&tag.key.as_str()) {
+            keys.push(&tag.key);
+        }
+    }
+    if keys.len() > MAX_TAGS_PER_TARGET {
+        return Err(AppError::Validation(
+            "at most 50 tags per device or point".to_string(),
+        ));
+    }
+    repository::apply_tags(&device.device_id, point_key, set, remove, now_millis)?;
+    Ok(repository::list_target_tags(&device.device_id, point_key)?
+        .into_iter()
+        .map(map_tag)
+        .collect())
+}
+
+/// 将操作员的设备范围转换为仓储查询的设备标识列表（None 表示不限）
+fn accessible_device_ids(user_id: i64, now_millis: i64) -> Result<Option<Vec<String>>, AppError> {
+    Ok(
+        match device_scope_services::resolve_accessible_devices(user_id, now_millis)? {
+            DeviceAccessFilter::All => None,
+            DeviceAccessFilter::Devices(device_ids) => Some(device_ids),
+        },
+    )
+}
+
+/// 将标签记录转换为响应格式
+fn map_tag(record: TagRecord) -> TagData {
+    TagData {
+        key: record.key,
+        value: record.value,
+    }
+}
+
+/// 查询设备或点位当前标签快照（审计内容，查询失败时不记录快照）
+fn find_snapshot(device_id: &str, point_key: &str) -> Option<Value> {
+    if device_id.is_empty() {
+        return None;
+    }
+    let records = repository::list_target_tags(device_id, point_key).ok()?;
+    let tags: Map<String, Value> = records
+        .into_iter()
+        .map(|record| (record.key, Value::String(record.value)))
+        .collect();
+    Some(json!({
+        "pointKey": (!point_key.is_empty()).then_some(point_key),
+        "tags": tags,
+    }))
+}
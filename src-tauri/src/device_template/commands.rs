@@ -0,0 +1,750 @@
+//! 设备模板模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的设备模板相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `device_template_list` | 查询模板列表 |
+//! | `device_template_get` | 查询模板详情（含点位表） |
+//! | `device_template_create` | 创建模板 |
+//! | `device_template_update` | 修改模板（版本加 1） |
+//! | `device_template_delete` | 删除没有关联设备的模板 |
+//! | `device_template_export` | 导出模板为 JSON 文档 |
+//! | `device_template_import` | 从 JSON 文档导入模板 |
+//! | `device_template_apply` | 为设备应用模板 |
+//! | `device_template_propagate` | 将模板变更同步到关联设备 |
+//! | `device_point_list` | 查询设备生效点位 |
+//! | `device_point_override` | 设置设备点位覆盖字段 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入设备模板数据模型
+use crate::device_template::models::{
+    DevicePointData, DevicePointListPayload, DevicePointOverridePayload,
+    DeviceTemplateApplyPayload, DeviceTemplateCreatePayload, DeviceTemplateData,
+    DeviceTemplateDeletePayload, DeviceTemplateDetailData, DeviceTemplateDocument,
+    DeviceTemplateExportPayload, DeviceTemplateGetPayload, DeviceTemplateImportData,
+    DeviceTemplateImportPayload, DeviceTemplateListPayload, DeviceTemplatePropagateData,
+    DeviceTemplatePropagatePayload, DeviceTemplateUpdatePayload,
+};
+// 引入设备模板服务层
+use crate::device_template::services;
+
+/// 查询模板列表
+///
+/// # 参数
+/// * `payload` - 操作员用户名与关键字
+///
+/// # 返回
+/// * 按编码排序的模板（含点位数与关联设备数）
+#[tauri::command]
+pub fn device_template_list(
+    payload: DeviceTemplateListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<DeviceTemplateData>> {
+    execute_traced_command("device_template_list", trace, || {
+        Ok(ApiResponse::ok(services::list_templates(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询模板详情
+///
+/// # 参数
+/// * `payload` - 操作员用户名与模板 ID
+///
+/// # 返回
+/// * 模板字段与点位表
+#[tauri::command]
+pub fn device_template_get(
+    payload: DeviceTemplateGetPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplateDetailData> {
+    execute_traced_command("device_template_get", trace, || {
+        Ok(ApiResponse::ok(services::get_template(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建模板
+///
+/// # 参数
+/// * `payload` - 模板编码、名称、厂商型号、设备类型、默认轮询参数与点位表
+///
+/// # 返回
+/// * 新建的模板详情
+#[tauri::command]
+pub fn device_template_create(
+    payload: DeviceTemplateCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplateDetailData> {
+    execute_traced_command("device_template_create", trace, || {
+        Ok(ApiResponse::ok(services::create_template(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 修改模板
+///
+/// # 参数
+/// * `payload` - 模板 ID 及整体替换的模板定义
+///
+/// # 返回
+/// * 修改后的模板详情
+#[tauri::command]
+pub fn device_template_update(
+    payload: DeviceTemplateUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplateDetailData> {
+    execute_traced_command("device_template_update", trace, || {
+        Ok(ApiResponse::ok(services::update_template(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除模板
+///
+/// # 参数
+/// * `payload` - 操作员用户名与模板 ID
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn device_template_delete(
+    payload: DeviceTemplateDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("device_template_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_template(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 导出模板
+///
+/// # 参数
+/// * `payload` - 操作员用户名与模板 ID 列表（为空时导出全部）
+///
+/// # 返回
+/// * 模板 JSON 文档
+#[tauri::command]
+pub fn device_template_export(
+    payload: DeviceTemplateExportPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplateDocument> {
+    execute_traced_command("device_template_export", trace, || {
+        Ok(ApiResponse::ok(services::export_templates(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 导入模板
+///
+/// # 参数
+/// * `payload` - 模板 JSON 文档与编码冲突时是否覆盖
+///
+/// # 返回
+/// * 新建、覆盖与跳过的模板编码
+#[tauri::command]
+pub fn device_template_import(
+    payload: DeviceTemplateImportPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplateImportData> {
+    execute_traced_command("device_template_import", trace, || {
+        Ok(ApiResponse::ok(services::import_templates(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 为设备应用模板
+///
+/// # 参数
+/// * `payload` - 设备标识与模板 ID
+///
+/// # 返回
+/// * 设备生效点位
+#[tauri::command]
+pub fn device_template_apply(
+    payload: DeviceTemplateApplyPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<DevicePointData>> {
+    execute_traced_command("device_template_apply", trace, || {
+        Ok(ApiResponse::ok(services::apply_template(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 将模板变更同步到关联设备
+///
+/// # 参数
+/// * `payload` - 模板 ID 与设备标识列表（为空时同步全部关联设备）
+///
+/// # 返回
+/// * 同步的模板版本与设备标识
+#[tauri::command]
+pub fn device_template_propagate(
+    payload: DeviceTemplatePropagatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DeviceTemplatePropagateData> {
+    execute_traced_command("device_template_propagate", trace, || {
+        Ok(ApiResponse::ok(services::propagate_template(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询设备生效点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与设备标识
+///
+/// # 返回
+/// * 按排序号排列的设备点位
+#[tauri::command]
+pub fn device_point_list(
+    payload: DevicePointListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<DevicePointData>> {
+    execute_traced_command("device_point_list", trace, || {
+        Ok(ApiResponse::ok(services::list_device_points(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 设置设备点位覆盖字段
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识与覆盖字段
+///
+/// # 返回
+/// * 更新后的设备点位
+#[tauri::command]
+pub fn device_point_override(
+    payload: DevicePointOverridePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<DevicePointData> {
+    execute_traced_command("device_point_override", trace, || {
+        Ok(ApiResponse::ok(services::override_device_point(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, json};
+
+    use super::*;
+    use crate::core::error::AppError;
+    use crate::db::test_support::{ensure_test_db_ready, unique_code};
+    use crate::device::commands::{device_create, device_delete, device_get};
+    use crate::device::models::{DeviceCreatePayload, DeviceDeletePayload, DeviceGetPayload};
+    use crate::device_template::models::{DeviceTemplateSpec, TemplatePointSpec};
+
+    fn point(key: &str, data_type: &str, register_type: &str, address: i64) -> TemplatePointSpec {
+        TemplatePointSpec {
+            key: key.to_string(),
+            name: format!("{key} 点位"),
+            data_type: data_type.to_string(),
+            register_type: register_type.to_string(),
+            address,
+            ..TemplatePointSpec::default()
+        }
+    }
+
+    // 辅助函数：电表模板定义（电压、功率、合闸线圈）
+    fn meter_spec(code: &str) -> DeviceTemplateSpec {
+        DeviceTemplateSpec {
+            code: code.to_string(),
+            name: "三相电表".to_string(),
+            manufacturer: Some("ACME".to_string()),
+            model: Some("PM-3".to_string()),
+            device_type: "meter".to_string(),
+            points: vec![
+                TemplatePointSpec {
+                    unit: Some("V".to_string()),
+                    scale: Some(0.1),
+                    ..point("voltage", "uint16", "input_register", 0)
+                },
+                TemplatePointSpec {
+                    unit: Some("kW".to_string()),
+                    byte_order: Some("cdab".to_string()),
+                    ..point("power", "float32", "holding_register", 10)
+                },
+                TemplatePointSpec {
+                    access: Some("read_write".to_string()),
+                    ..point("breaker", "bool", "coil", 0)
+                },
+            ],
+            ..DeviceTemplateSpec::default()
+        }
+    }
+
+    fn create_template(spec: DeviceTemplateSpec) -> DeviceTemplateDetailData {
+        device_template_create(
+            DeviceTemplateCreatePayload {
+                operator_username: "admin".to_string(),
+                template: spec,
+            },
+            None,
+        )
+        .expect("create template")
+        .data
+    }
+
+    fn create_device(device_id: &str, template_id: Option<i64>) {
+        device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.to_string(),
+                device_name: format!("{device_id} 名称"),
+                // 由模板创建时设备类型取模板的值
+                device_type: if template_id.is_some() { "" } else { "meter" }.to_string(),
+                template_id,
+                ..DeviceCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create device");
+    }
+
+    fn points(device_id: &str) -> Vec<DevicePointData> {
+        device_point_list(
+            DevicePointListPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.to_string(),
+            },
+            None,
+        )
+        .expect("list device points")
+        .data
+    }
+
+    fn overrides(value: serde_json::Value) -> Map<String, serde_json::Value> {
+        value.as_object().cloned().expect("override object")
+    }
+
+    #[test]
+    fn create_update_get_bumps_version_and_validates_points() {
+        ensure_test_db_ready();
+        let code = unique_code("tpl_crud");
+        let created = create_template(meter_spec(&code));
+        assert_eq!(created.template.version, 1);
+        assert_eq!(created.template.point_count, 3);
+        assert_eq!(created.template.polling.interval_ms, 1000);
+        assert_eq!(created.points[0].key, "voltage");
+        assert_eq!(created.points[0].byte_order, "abcd");
+        assert!((created.points[0].scale - 0.1).abs() < f64::EPSILON);
+        assert_eq!(created.points[2].access, "read_write");
+
+        let mut spec = meter_spec(&code);
+        spec.points.truncate(2);
+        spec.polling.interval_ms = 5000;
+        let updated = device_template_update(
+            DeviceTemplateUpdatePayload {
+                operator_username: "admin".to_string(),
+                template_id: created.template.id,
+                template: spec,
+            },
+            None,
+        )
+        .expect("update template")
+        .data;
+        assert_eq!(updated.template.version, 2);
+        assert_eq!(updated.template.point_count, 2);
+        assert_eq!(updated.template.polling.interval_ms, 5000);
+
+        let listed = device_template_list(
+            DeviceTemplateListPayload {
+                operator_username: "admin".to_string(),
+                keyword: Some(code.to_uppercase()),
+            },
+            None,
+        )
+        .expect("list templates")
+        .data;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, created.template.id);
+
+        let invalid_cases = [
+            (
+                point("flag", "bool", "holding_register", 0),
+                "point flag: bool points must use coil or discrete_input and vice versa",
+            ),
+            (
+                point("energy", "float64", "input_register", 65_533),
+                "point energy: address out of range 0..65535",
+            ),
+            (
+                TemplatePointSpec {
+                    access: Some("read_write".to_string()),
+                    ..point("temp", "int16", "input_register", 0)
+                },
+                "point temp: read_write access requires coil or holding_register",
+            ),
+            (
+                TemplatePointSpec {
+                    scale: Some(0.0),
+                    ..point("temp", "int16", "input_register", 0)
+                },
+                "point temp: scale must be a non-zero finite number",
+            ),
+        ];
+        for (invalid, message) in invalid_cases {
+            let err = device_template_create(
+                DeviceTemplateCreatePayload {
+                    operator_username: "admin".to_string(),
+                    template: DeviceTemplateSpec {
+                        points: vec![invalid],
+                        ..meter_spec(&unique_code("tpl_invalid"))
+                    },
+                },
+                None,
+            )
+            .expect_err("invalid point");
+            assert_eq!(err, AppError::Validation(message.to_string()));
+        }
+        let err = device_template_create(
+            DeviceTemplateCreatePayload {
+                operator_username: "admin".to_string(),
+                template: meter_spec(&code),
+            },
+            None,
+        )
+        .expect_err("duplicate code");
+        assert_eq!(
+            err,
+            AppError::Validation("template code already exists".to_string())
+        );
+    }
+
+    #[test]
+    fn device_created_from_template_inherits_points() {
+        ensure_test_db_ready();
+        let template = create_template(meter_spec(&unique_code("tpl_inherit")));
+        let device_id = unique_code("tpl_inherit_device");
+        create_device(&device_id, Some(template.template.id));
+
+        let device = device_get(
+            DeviceGetPayload {
+                operator_username: "admin".to_string(),
+                device_id: device_id.clone(),
+            },
+            None,
+        )
+        .expect("get device")
+        .data;
+        assert_eq!(device.template_id, Some(template.template.id));
+        assert_eq!(device.template_version, Some(1));
+        assert_eq!(device.device_type, "meter");
+        assert_eq!(device.manufacturer.as_deref(), Some("ACME"));
+        assert_eq!(device.model.as_deref(), Some("PM-3"));
+
+        let inherited = points(&device_id);
+        assert_eq!(inherited.len(), 3);
+        assert_eq!(inherited[0].point, template.points[0]);
+        assert!(inherited.iter().all(|point| point.overrides.is_empty()));
+
+        // 未关联模板的设备可以后续应用模板
+        let plain_id = unique_code("tpl_apply_device");
+        create_device(&plain_id, None);
+        assert!(points(&plain_id).is_empty());
+        let applied = device_template_apply(
+            DeviceTemplateApplyPayload {
+                operator_username: "admin".to_string(),
+                device_id: plain_id.clone(),
+                template_id: template.template.id,
+            },
+            None,
+        )
+        .expect("apply template")
+        .data;
+        assert_eq!(applied.len(), 3);
+
+        let err = device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: unique_code("tpl_missing"),
+                device_name: "missing".to_string(),
+                template_id: Some(-1),
+                ..DeviceCreatePayload::default()
+            },
+            None,
+        )
+        .expect_err("template not found");
+        assert_eq!(err, AppError::Validation("template not found".to_string()));
+    }
+
+    #[test]
+    fn device_point_override_applies_and_rejects_fixed_fields() {
+        ensure_test_db_ready();
+        let template = create_template(meter_spec(&unique_code("tpl_override")));
+        let first = unique_code("tpl_override_device");
+        create_device(&first, Some(template.template.id));
+
+        let overridden = device_point_override(
+            DevicePointOverridePayload {
+                operator_username: "admin".to_string(),
+                device_id: first.clone(),
+                point_key: "voltage".to_string(),
+                overrides: overrides(json!({ "address": 100, "name": "A 相电压" })),
+            },
+            None,
+        )
+        .expect("override point")
+        .data;
+        assert_eq!(overridden.point.address, 100);
+        assert_eq!(overridden.point.unit.as_deref(), Some("V"));
+
+        let err = device_point_override(
+            DevicePointOverridePayload {
+                operator_username: "admin".to_string(),
+                device_id: first.clone(),
+                point_key: "voltage".to_string(),
+                overrides: overrides(json!({ "dataType": "int32" })),
+            },
+            None,
+        )
+        .expect_err("data type not overridable");
+        assert_eq!(
+            err,
+            AppError::Validation("point voltage: field cannot be overridden: dataType".to_string())
+        );
+    }
+
+    #[test]
+    fn propagate_keeps_device_overrides() {
+        ensure_test_db_ready();
+        let code = unique_code("tpl_propagate");
+        let template = create_template(meter_spec(&code));
+        let first = unique_code("tpl_propagate_a");
+        let second = unique_code("tpl_propagate_b");
+        create_device(&first, Some(template.template.id));
+        create_device(&second, Some(template.template.id));
+
+        device_point_override(
+            DevicePointOverridePayload {
+                operator_username: "admin".to_string(),
+                device_id: first.clone(),
+                point_key: "voltage".to_string(),
+                overrides: overrides(json!({ "address": 100, "name": "A 相电压" })),
+            },
+            None,
+        )
+        .expect("override point");
+
+        let mut spec = meter_spec(&code);
+        spec.points[0].unit = Some("kV".to_string());
+        spec.points[0].scale = Some(0.001);
+        device_template_update(
+            DeviceTemplateUpdatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                template: spec,
+            },
+            None,
+        )
+        .expect("update template");
+
+        // 只同步第一台设备，第二台保留旧版本
+        let propagated = device_template_propagate(
+            DeviceTemplatePropagatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                device_ids: vec![first.clone()],
+            },
+            None,
+        )
+        .expect("propagate template")
+        .data;
+        assert_eq!(propagated.version, 2);
+        assert_eq!(propagated.device_ids, vec![first.clone()]);
+
+        let voltage = points(&first).remove(0);
+        assert_eq!(voltage.point.address, 100);
+        assert_eq!(voltage.point.name, "A 相电压");
+        assert_eq!(voltage.point.unit.as_deref(), Some("kV"));
+        assert_eq!(voltage.overrides.len(), 2);
+        assert_eq!(points(&second)[0].point.unit.as_deref(), Some("V"));
+
+        let err = device_point_override(
+            DevicePointOverridePayload {
+                operator_username: "admin".to_string(),
+                device_id: second.clone(),
+                point_key: "voltage".to_string(),
+                overrides: overrides(json!({ "address": 5 })),
+            },
+            None,
+        )
+        .expect_err("outdated device");
+        assert_eq!(
+            err,
+            AppError::Validation(
+                "device template version is outdated, propagate the template first".to_string()
+            )
+        );
+
+        let err = device_template_propagate(
+            DeviceTemplatePropagatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                device_ids: vec!["not-linked".to_string()],
+            },
+            None,
+        )
+        .expect_err("device not linked");
+        assert_eq!(
+            err,
+            AppError::Validation("device not linked to template: not-linked".to_string())
+        );
+
+        let all = device_template_propagate(
+            DeviceTemplatePropagatePayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+                device_ids: Vec::new(),
+            },
+            None,
+        )
+        .expect("propagate all")
+        .data;
+        assert_eq!(all.device_ids.len(), 2);
+        assert_eq!(points(&second)[0].point.unit.as_deref(), Some("kV"));
+    }
+
+    #[test]
+    fn export_import_round_trip_skips_or_overwrites() {
+        ensure_test_db_ready();
+        let code = unique_code("tpl_export");
+        let template = create_template(meter_spec(&code));
+        let document = device_template_export(
+            DeviceTemplateExportPayload {
+                operator_username: "admin".to_string(),
+                template_ids: vec![template.template.id],
+            },
+            None,
+        )
+        .expect("export templates")
+        .data;
+        assert_eq!(document.format, "device-templates");
+        assert_eq!(document.templates.len(), 1);
+        assert_eq!(document.templates[0].points.len(), 3);
+
+        // 文档经 JSON 往返后仍可导入
+        let mut document: DeviceTemplateDocument =
+            serde_json::from_value(serde_json::to_value(&document).expect("serialize document"))
+                .expect("deserialize document");
+        let imported_code = unique_code("tpl_import");
+        let mut copy = document.templates[0].clone();
+        copy.code.clone_from(&imported_code);
+        document.templates.push(copy);
+
+        let import = |document: DeviceTemplateDocument, overwrite: bool| {
+            device_template_import(
+                DeviceTemplateImportPayload {
+                    operator_username: "admin".to_string(),
+                    document,
+                    overwrite,
+                },
+                None,
+            )
+        };
+        let skipped = import(document.clone(), false).expect("import skip").data;
+        assert_eq!(skipped.created, vec![imported_code.clone()]);
+        assert_eq!(skipped.skipped, vec![code.clone()]);
+
+        let overwritten = import(document.clone(), true)
+            .expect("import overwrite")
+            .data;
+        assert_eq!(overwritten.updated, vec![code.clone(), imported_code]);
+        let reloaded = device_template_get(
+            DeviceTemplateGetPayload {
+                operator_username: "admin".to_string(),
+                template_id: template.template.id,
+            },
+            None,
+        )
+        .expect("get template")
+        .data;
+        assert_eq!(reloaded.template.version, 2);
+        assert_eq!(reloaded.points, template.points);
+
+        let mut duplicated = document.clone();
+        duplicated.templates.push(duplicated.templates[0].clone());
+        assert_eq!(
+            import(duplicated, true).expect_err("duplicate code"),
+            AppError::Validation(format!("duplicate template code: {code}"))
+        );
+        let mut invalid = document;
+        invalid.templates[0].points[0].register_type = "register".to_string();
+        assert_eq!(
+            import(invalid, true).expect_err("invalid point"),
+            AppError::Validation(format!(
+                "template {code}: point voltage: registerType must be one of coil, discrete_input, holding_register, input_register"
+            ))
+        );
+    }
+
+    #[test]
+    fn delete_is_blocked_by_linked_devices_and_requires_manage() {
+        ensure_test_db_ready();
+        let template = create_template(meter_spec(&unique_code("tpl_delete")));
+        let device_id = unique_code("tpl_delete_device");
+        create_device(&device_id, Some(template.template.id));
+        let delete = |operator_username: &str| {
+            device_template_delete(
+                DeviceTemplateDeletePayload {
+                    operator_username: operator_username.to_string(),
+                    template_id: template.template.id,
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            delete("common").expect_err("forbidden"),
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+        assert_eq!(
+            delete("admin").expect_err("linked devices"),
+            AppError::Validation("template has linked devices: 1".to_string())
+        );
+
+        device_delete(
+            DeviceDeletePayload {
+                operator_username: "admin".to_string(),
+                device_id,
+            },
+            None,
+        )
+        .expect("delete device");
+        assert!(delete("admin").expect("delete template").data);
+        assert_eq!(
+            delete("admin").expect_err("already deleted"),
+            AppError::Validation("template not found".to_string())
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! 设备模板模块入口
+//!
+//! 本模块维护同一厂商型号设备共享的设备模板：
+//! - 模板包含点位表（名称、单位、数据类型、寄存器映射、缩放）与默认轮询参数
+//! - 由模板创建或应用模板的设备继承点位表，设备可覆盖单个点位的部分字段
+//! - 模板修改后版本加 1，同步命令将新版本推送到关联设备并保留设备级覆盖
+//! - 模板可导出为 JSON 文档并在其他环境导入
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 模板、点位与导入导出文档结构
+pub mod models;
+// 公开服务模块 - 模板校验、应用与同步业务逻辑
+pub mod services;
+// 公开仓储模块 - 模板与设备点位的 SeaORM 读写
+pub mod repository;
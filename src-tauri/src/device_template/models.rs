@@ -0,0 +1,376 @@
+//! 设备模板模块数据模型
+//!
+//! 本模块定义设备模板、模板点位、设备生效点位的存储记录，
+//! 模板导入导出的 JSON 文档结构，以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+// 引入 JSON 对象与值类型
+use serde_json::{Map, Value};
+
+/// 设备模板存储记录
+///
+/// 与 device_templates 表对应，附带点位数与关联设备数
+#[derive(Debug, Clone, Default)]
+pub struct DeviceTemplateRecord {
+    pub id: i64,                      // 模板 ID
+    pub code: String,                 // 模板编码
+    pub name: String,                 // 模板名称
+    pub manufacturer: Option<String>, // 厂商
+    pub model: Option<String>,        // 型号
+    pub device_type: String,          // 设备类型
+    pub description: Option<String>,  // 说明
+    pub polling: PollingSettings,     // 默认轮询参数
+    pub version: i32,                 // 模板版本
+    pub created_at: i64,              // 创建时间戳（毫秒）
+    pub updated_at: i64,              // 更新时间戳（毫秒）
+    pub created_by: String,           // 创建人用户名
+    pub point_count: i64,             // 点位数
+    pub linked_device_count: i64,     // 关联设备数
+}
+
+/// 设备模板写入参数（已完成规范化）
+#[derive(Debug, Clone, Default)]
+pub struct DeviceTemplateInput {
+    pub code: String,                 // 模板编码
+    pub name: String,                 // 模板名称
+    pub manufacturer: Option<String>, // 厂商
+    pub model: Option<String>,        // 型号
+    pub device_type: String,          // 设备类型
+    pub description: Option<String>,  // 说明
+    pub polling: PollingSettings,     // 默认轮询参数
+    pub points: Vec<PointDefinition>, // 点位定义（按列表顺序排序）
+}
+
+/// 设备生效点位存储记录（模板点位合并设备级覆盖后的结果）
+#[derive(Debug, Clone, Default)]
+pub struct DevicePointRecord {
+    pub point: PointDefinition,        // 生效点位定义
+    pub sort_order: i32,               // 排序号
+    pub overrides: Map<String, Value>, // 设备级覆盖字段
+    pub updated_at: i64,               // 更新时间戳（毫秒）
+}
+
+/// 设备与模板的关联（创建设备或应用模板时写入）
+#[derive(Debug, Clone, Default)]
+pub struct TemplateBinding {
+    pub template_id: i64,               // 模板 ID
+    pub version: i32,                   // 模板版本
+    pub points: Vec<DevicePointRecord>, // 设备生效点位
+}
+
+/// 默认轮询参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PollingSettings {
+    /// 轮询周期（毫秒）
+    pub interval_ms: u32,
+    /// 请求超时（毫秒）
+    pub timeout_ms: u32,
+    /// 失败重试次数
+    pub retries: u32,
+}
+
+impl Default for PollingSettings {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            timeout_ms: 1000,
+            retries: 3,
+        }
+    }
+}
+
+/// 点位定义（已完成规范化）
+///
+/// 工程值 = 原始值 * scale + offset
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointDefinition {
+    /// 点位标识
+    pub key: String,
+    /// 点位名称
+    pub name: String,
+    /// 工程单位
+    pub unit: Option<String>,
+    /// 数据类型
+    pub data_type: String,
+    /// 寄存器类型
+    pub register_type: String,
+    /// 起始寄存器地址（0 起始）
+    pub address: i32,
+    /// 多寄存器字节序
+    pub byte_order: String,
+    /// 缩放系数
+    pub scale: f64,
+    /// 偏移量
+    pub offset: f64,
+    /// 访问方式
+    pub access: String,
+}
+
+// 点位定义（导入导出文档与创建/更新请求使用，可选字段取默认值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TemplatePointSpec {
+    /// 点位标识（模板内唯一）
+    pub key: String,
+    /// 点位名称
+    pub name: String,
+    /// 工程单位
+    pub unit: Option<String>,
+    /// 数据类型（bool / int16 / uint16 / int32 / uint32 / int64 / uint64 / float32 / float64）
+    pub data_type: String,
+    /// 寄存器类型（coil / discrete_input / holding_register / input_register）
+    pub register_type: String,
+    /// 起始寄存器地址（0 起始）
+    pub address: i64,
+    /// 多寄存器字节序（abcd / badc / cdab / dcba，默认 abcd）
+    pub byte_order: Option<String>,
+    /// 缩放系数（默认 1）
+    pub scale: Option<f64>,
+    /// 偏移量（默认 0）
+    pub offset: Option<f64>,
+    /// 访问方式（read / read_write，默认 read）
+    pub access: Option<String>,
+}
+
+// 设备模板定义（导入导出文档与创建/更新请求使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateSpec {
+    /// 模板编码（全局唯一）
+    pub code: String,
+    /// 模板名称
+    pub name: String,
+    /// 厂商
+    pub manufacturer: Option<String>,
+    /// 型号
+    pub model: Option<String>,
+    /// 设备类型（默认 generic）
+    pub device_type: String,
+    /// 说明
+    pub description: Option<String>,
+    /// 默认轮询参数
+    pub polling: PollingSettings,
+    /// 点位定义
+    pub points: Vec<TemplatePointSpec>,
+}
+
+// 设备模板导入导出文档
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateDocument {
+    /// 文档格式标识（固定为 device-templates）
+    pub format: String,
+    /// 文档格式版本
+    pub version: u32,
+    /// 导出时间戳（毫秒）
+    pub exported_at: i64,
+    /// 模板列表
+    pub templates: Vec<DeviceTemplateSpec>,
+}
+
+// 模板列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 关键字（匹配编码、名称、厂商、型号，不区分大小写）
+    pub keyword: Option<String>,
+}
+
+// 查询单个模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateGetPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板 ID
+    pub template_id: i64,
+}
+
+// 创建模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板定义
+    pub template: DeviceTemplateSpec,
+}
+
+// 更新模板请求体（整体替换模板字段与点位表）
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板 ID
+    pub template_id: i64,
+    /// 模板定义
+    pub template: DeviceTemplateSpec,
+}
+
+// 删除模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板 ID
+    pub template_id: i64,
+}
+
+// 导出模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateExportPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 导出的模板 ID（为空时导出全部模板）
+    pub template_ids: Vec<i64>,
+}
+
+// 导入模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateImportPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板文档
+    pub document: DeviceTemplateDocument,
+    /// 编码已存在时是否覆盖（false 时跳过）
+    pub overwrite: bool,
+}
+
+// 为设备应用模板请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplateApplyPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 模板 ID
+    pub template_id: i64,
+}
+
+// 同步模板变更到关联设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceTemplatePropagatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模板 ID
+    pub template_id: i64,
+    /// 同步的设备标识（为空时同步全部关联设备）
+    pub device_ids: Vec<String>,
+}
+
+// 设备点位列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DevicePointListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+}
+
+// 设置设备点位覆盖请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DevicePointOverridePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 设备标识
+    pub device_id: String,
+    /// 点位标识
+    pub point_key: String,
+    /// 覆盖字段（整体替换，空对象表示恢复模板定义）
+    pub overrides: Map<String, Value>,
+}
+
+// 模板响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTemplateData {
+    /// 模板 ID
+    pub id: i64,
+    /// 模板编码
+    pub code: String,
+    /// 模板名称
+    pub name: String,
+    /// 厂商
+    pub manufacturer: Option<String>,
+    /// 型号
+    pub model: Option<String>,
+    /// 设备类型
+    pub device_type: String,
+    /// 说明
+    pub description: Option<String>,
+    /// 默认轮询参数
+    pub polling: PollingSettings,
+    /// 模板版本
+    pub version: i32,
+    /// 点位数
+    pub point_count: i64,
+    /// 关联设备数
+    pub linked_device_count: i64,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+    /// 创建人用户名
+    pub created_by: String,
+}
+
+// 模板详情响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTemplateDetailData {
+    /// 模板字段
+    #[serde(flatten)]
+    pub template: DeviceTemplateData,
+    /// 点位定义（按排序号排列）
+    pub points: Vec<PointDefinition>,
+}
+
+// 模板导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTemplateImportData {
+    /// 新建的模板编码
+    pub created: Vec<String>,
+    /// 覆盖的模板编码
+    pub updated: Vec<String>,
+    /// 编码已存在而跳过的模板编码
+    pub skipped: Vec<String>,
+}
+
+// 模板同步结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTemplatePropagateData {
+    /// 模板 ID
+    pub template_id: i64,
+    /// 同步到设备的模板版本
+    pub version: i32,
+    /// 已同步的设备标识
+    pub device_ids: Vec<String>,
+}
+
+// 设备生效点位响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePointData {
+    /// 生效点位定义
+    #[serde(flatten)]
+    pub point: PointDefinition,
+    /// 设备级覆盖字段
+    pub overrides: Map<String, Value>,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+}
@@ -0,0 +1,666 @@
+//! 设备模板模块数据仓储层
+//!
+//! 本模块负责 device_templates、device_template_points、device_points 表的读写：
+//! - 模板及其点位表的增删改查（点位表整体替换，修改时模板版本加 1）
+//! - 模板批量导入（单事务，编码已存在时覆盖或跳过）
+//! - 设备与模板的关联及设备生效点位的写入（创建设备、应用模板、同步模板变更）
+//!
+//! 均为简单 CRUD，按 `docs/database-access-policy.md` 规则 1 使用 SeaORM 实现
+
+// 引入哈希映射（按模板汇总计数）
+use std::collections::HashMap;
+
+// 引入 SeaORM 查询表达式
+use sea_orm::sea_query::{Expr, Func, LikeExpr};
+// 引入 SeaORM 核心 trait
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DbErr,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入实体模型
+use crate::db::entities::{
+    device_points, device_registry, device_template_points, device_templates,
+};
+// 引入设备标签仓储（重建点位时清理点位标签）
+use crate::device_tag::repository as tag_repository;
+// 引入设备模板模型
+use crate::device_template::models::{
+    DevicePointRecord, DeviceTemplateImportData, DeviceTemplateInput, DeviceTemplateRecord,
+    PointDefinition, PollingSettings, TemplateBinding,
+};
+
+/// 查询模板列表
+///
+/// # 参数
+/// * `keyword` - 小写关键字（匹配编码、名称、厂商、型号）
+///
+/// # 返回
+/// * 按编码排序的模板记录
+pub fn list_templates(keyword: Option<&str>) -> Result<Vec<DeviceTemplateRecord>, AppError> {
+    let mut condition = Condition::all();
+    if let Some(keyword) = keyword {
+        let pattern = format!("%{}%", escape_like(keyword));
+        let mut keyword_condition = Condition::any();
+        for column in [
+            device_templates::Column::Code,
+            device_templates::Column::Name,
+            device_templates::Column::Manufacturer,
+            device_templates::Column::Model,
+        ] {
+            keyword_condition = keyword_condition.add(
+                Expr::expr(Func::lower(Expr::col(column)))
+                    .like(LikeExpr::new(pattern.clone()).escape('\\')),
+            );
+        }
+        condition = condition.add(keyword_condition);
+    }
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = device_templates::Entity::find()
+            .filter(condition)
+            .order_by_asc(device_templates::Column::Code)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        attach_counts(&connection, models).await
+    })
+}
+
+/// 查询模板及其点位表
+///
+/// # 参数
+/// * `template_ids` - 模板 ID 列表（None 表示全部模板）
+///
+/// # 返回
+/// * 按编码排序的 (模板记录, 按排序号排列的点位定义)
+pub fn list_templates_with_points(
+    template_ids: Option<Vec<i64>>,
+) -> Result<Vec<(DeviceTemplateRecord, Vec<PointDefinition>)>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let mut select = device_templates::Entity::find();
+        if let Some(template_ids) = template_ids {
+            select = select.filter(device_templates::Column::Id.is_in(template_ids));
+        }
+        let models = select
+            .order_by_asc(device_templates::Column::Code)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        let ids: Vec<i64> = models.iter().map(|model| model.id).collect();
+        let records = attach_counts(&connection, models).await?;
+        let mut points_by_template: HashMap<i64, Vec<PointDefinition>> = HashMap::new();
+        for point in device_template_points::Entity::find()
+            .filter(device_template_points::Column::TemplateId.is_in(ids))
+            .order_by_asc(device_template_points::Column::TemplateId)
+            .order_by_asc(device_template_points::Column::SortOrder)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?
+        {
+            points_by_template
+                .entry(point.template_id)
+                .or_default()
+                .push(map_template_point(point));
+        }
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let points = points_by_template.remove(&record.id).unwrap_or_default();
+                (record, points)
+            })
+            .collect())
+    })
+}
+
+/// 查询单个模板及其点位表
+///
+/// # 参数
+/// * `template_id` - 模板 ID
+///
+/// # 返回
+/// * (模板记录, 点位定义)，模板不存在时为 None
+pub fn find_template(
+    template_id: i64,
+) -> Result<Option<(DeviceTemplateRecord, Vec<PointDefinition>)>, AppError> {
+    Ok(list_templates_with_points(Some(vec![template_id]))?
+        .into_iter()
+        .next())
+}
+
+/// 新增模板及其点位表
+///
+/// # 参数
+/// * `input` - 模板写入参数
+/// * `created_by` - 创建人用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新模板 ID
+pub fn insert_template(
+    input: DeviceTemplateInput,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let template_id = insert_template_in(&transaction, input, created_by, now_millis).await?;
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(template_id)
+    })
+}
+
+/// 整体替换模板字段与点位表，模板版本加 1
+///
+/// # 参数
+/// * `template_id` - 模板 ID
+/// * `input` - 模板写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 模板存在返回 true
+pub fn update_template(
+    template_id: i64,
+    input: DeviceTemplateInput,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let Some(current) = device_templates::Entity::find_by_id(template_id)
+            .one(&transaction)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(false);
+        };
+        replace_template_in(&transaction, current, input, now_millis).await?;
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(true)
+    })
+}
+
+/// 删除模板（点位表级联删除）
+///
+/// 仍有关联设备时拒绝删除
+///
+/// # 参数
+/// * `template_id` - 模板 ID
+///
+/// # 返回
+/// * 删除的记录数（模板不存在时为 0）
+pub fn delete_template(template_id: i64) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let linked = device_registry::Entity::find()
+            .filter(device_registry::Column::TemplateId.eq(template_id))
+            .count(&transaction)
+            .await
+            .map_err(map_db_error)?;
+        if linked > 0 {
+            return Err(AppError::Validation(format!(
+                "template has linked devices: {linked}"
+            )));
+        }
+        let result = device_templates::Entity::delete_by_id(template_id)
+            .exec(&transaction)
+            .await
+            .map_err(map_template_mutation_error)?;
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(result.rows_affected)
+    })
+}
+
+/// 批量导入模板（单事务，任一模板失败则全部回滚）
+///
+/// # 参数
+/// * `inputs` - 已校验的模板写入参数
+/// * `overwrite` - 编码已存在时是否覆盖（覆盖时模板版本加 1）
+/// * `created_by` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建、覆盖与跳过的模板编码
+pub fn import_templates(
+    inputs: Vec<DeviceTemplateInput>,
+    overwrite: bool,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<DeviceTemplateImportData, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        let mut result = DeviceTemplateImportData::default();
+        for input in inputs {
+            let code = input.code.clone();
+            let current = device_templates::Entity::find()
+                .filter(device_templates::Column::Code.eq(code.as_str()))
+                .one(&transaction)
+                .await
+                .map_err(map_db_error)?;
+            match current {
+                Some(_) if !overwrite => result.skipped.push(code),
+                Some(current) => {
+                    replace_template_in(&transaction, current, input, now_millis).await?;
+                    result.updated.push(code);
+                }
+                None => {
+                    insert_template_in(&transaction, input, created_by, now_millis).await?;
+                    result.created.push(code);
+                }
+            }
+        }
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(result)
+    })
+}
+
+/// 查询关联到模板的设备标识
+///
+/// # 参数
+/// * `template_id` - 模板 ID
+///
+/// # 返回
+/// * 按设备标识排序的设备标识列表
+pub fn list_linked_device_ids(template_id: i64) -> Result<Vec<String>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        device_registry::Entity::find()
+            .select_only()
+            .column(device_registry::Column::DeviceId)
+            .filter(device_registry::Column::TemplateId.eq(template_id))
+            .order_by_asc(device_registry::Column::DeviceId)
+            .into_tuple::<String>()
+            .all(&connection)
+            .await
+            .map_err(map_db_error)
+    })
+}
+
+/// 查询设备的生效点位
+///
+/// # 参数
+/// * `device_id` - 设备标识
+///
+/// # 返回
+/// * 按排序号排列的设备点位
+pub fn list_device_points(device_id: &str) -> Result<Vec<DevicePointRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = device_points::Entity::find()
+            .filter(device_points::Column::DeviceId.eq(device_id))
+            .order_by_asc(device_points::Column::SortOrder)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_device_point).collect())
+    })
+}
+
+/// 批量写入设备与模板的关联及生效点位（单事务）
+///
+/// # 参数
+/// * `bindings` - (设备标识, 模板关联) 列表
+/// * `now_millis` - 当前时间戳（毫秒）
+pub fn bind_devices(
+    bindings: Vec<(String, TemplateBinding)>,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let transaction = connection.begin().await.map_err(map_db_error)?;
+        for (device_id, binding) in bindings {
+            write_device_binding(&transaction, &device_id, binding, now_millis).await?;
+        }
+        transaction.commit().await.map_err(map_db_error)?;
+        Ok(())
+    })
+}
+
+/// 更新单个设备点位的生效定义与覆盖字段
+///
+/// # 参数
+/// * `device_id` - 设备标识
+/// * `record` - 合并后的设备点位
+///
+/// # 返回
+/// * 点位存在返回 true
+pub fn update_device_point(device_id: &str, record: DevicePointRecord) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let Some(current) = device_points::Entity::find()
+            .filter(device_points::Column::DeviceId.eq(device_id))
+            .filter(device_points::Column::PointKey.eq(record.point.key.as_str()))
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(false);
+        };
+        let mut model: device_points::ActiveModel = current.into();
+        model.name = Set(record.point.name);
+        model.unit = Set(record.point.unit);
+        model.address = Set(record.point.address);
+        model.byte_order = Set(record.point.byte_order);
+        model.scale = Set(record.point.scale);
+        model.value_offset = Set(record.point.offset);
+        model.access = Set(record.point.access);
+        model.overrides = Set(Value::Object(record.overrides));
+        model.updated_at = Set(record.updated_at);
+        model.update(&connection).await.map_err(map_db_error)?;
+        Ok(true)
+    })
+}
+
+/// 在给定连接（通常为事务）中写入设备与模板的关联，并整体替换设备生效点位
+///
+/// 设备管理模块创建设备时在同一事务中调用；已移除点位上的标签同时清理
+///
+/// # 参数
+/// * `connection` - 数据库连接或事务
+/// * `device_id` - 设备标识
+/// * `binding` - 模板关联
+/// * `now_millis` - 当前时间戳（毫秒）
+pub async fn write_device_binding<C: ConnectionTrait>(
+    connection: &C,
+    device_id: &str,
+    binding: TemplateBinding,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    device_registry::Entity::update_many()
+        .col_expr(
+            device_registry::Column::TemplateId,
+            Expr::value(binding.template_id),
+        )
+        .col_expr(
+            device_registry::Column::TemplateVersion,
+            Expr::value(binding.version),
+        )
+        .col_expr(device_registry::Column::UpdatedAt, Expr::value(now_millis))
+        .filter(device_registry::Column::DeviceId.eq(device_id))
+        .exec(connection)
+        .await
+        .map_err(map_db_error)?;
+    device_points::Entity::delete_many()
+        .filter(device_points::Column::DeviceId.eq(device_id))
+        .exec(connection)
+        .await
+        .map_err(map_db_error)?;
+    // 保留仍存在点位上的标签，清理已移除点位上的标签
+    let point_keys = binding
+        .points
+        .iter()
+        .map(|record| record.point.key.clone())
+        .collect();
+    tag_repository::prune_point_tags(connection, device_id, point_keys).await?;
+    if binding.points.is_empty() {
+        return Ok(());
+    }
+    let models = binding
+        .points
+        .into_iter()
+        .map(|record| device_points::ActiveModel {
+            device_id: Set(device_id.to_string()),
+            point_key: Set(record.point.key),
+            name: Set(record.point.name),
+            unit: Set(record.point.unit),
+            data_type: Set(record.point.data_type),
+            register_type: Set(record.point.register_type),
+            address: Set(record.point.address),
+            byte_order: Set(record.point.byte_order),
+            scale: Set(record.point.scale),
+            value_offset: Set(record.point.offset),
+            access: Set(record.point.access),
+            sort_order: Set(record.sort_order),
+            overrides: Set(Value::Object(record.overrides)),
+            updated_at: Set(now_millis),
+            ..Default::default()
+        });
+    device_points::Entity::insert_many(models)
+        .exec(connection)
+        .await
+        .map_err(map_db_error)?;
+    Ok(())
+}
+
+/// 在事务中新增模板及其点位表
+async fn insert_template_in<C: ConnectionTrait>(
+    connection: &C,
+    input: DeviceTemplateInput,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    let model = device_templates::ActiveModel {
+        code: Set(input.code),
+        name: Set(input.name),
+        manufacturer: Set(input.manufacturer),
+        model: Set(input.model),
+        device_type: Set(input.device_type),
+        description: Set(input.description),
+        poll_interval_ms: Set(to_i32(input.polling.interval_ms)),
+        poll_timeout_ms: Set(to_i32(input.polling.timeout_ms)),
+        poll_retries: Set(to_i32(input.polling.retries)),
+        version: Set(1),
+        created_at: Set(now_millis),
+        updated_at: Set(now_millis),
+        created_by: Set(created_by.to_string()),
+        ..Default::default()
+    }
+    .insert(connection)
+    .await
+    .map_err(map_template_mutation_error)?;
+    insert_points(connection, model.id, input.points).await?;
+    Ok(model.id)
+}
+
+/// 在事务中整体替换模板字段与点位表，模板版本加 1
+async fn replace_template_in<C: ConnectionTrait>(
+    connection: &C,
+    current: device_templates::Model,
+    input: DeviceTemplateInput,
+    now_millis: i64,
+) -> Result<(), AppError> {
+    let template_id = current.id;
+    let version = current.version + 1;
+    let mut model: device_templates::ActiveModel = current.into();
+    model.code = Set(input.code);
+    model.name = Set(input.name);
+    model.manufacturer = Set(input.manufacturer);
+    model.model = Set(input.model);
+    model.device_type = Set(input.device_type);
+    model.description = Set(input.description);
+    model.poll_interval_ms = Set(to_i32(input.polling.interval_ms));
+    model.poll_timeout_ms = Set(to_i32(input.polling.timeout_ms));
+    model.poll_retries = Set(to_i32(input.polling.retries));
+    model.version = Set(version);
+    model.updated_at = Set(now_millis);
+    model
+        .update(connection)
+        .await
+        .map_err(map_template_mutation_error)?;
+    device_template_points::Entity::delete_many()
+        .filter(device_template_points::Column::TemplateId.eq(template_id))
+        .exec(connection)
+        .await
+        .map_err(map_db_error)?;
+    insert_points(connection, template_id, input.points).await
+}
+
+/// 写入模板点位（排序号取列表顺序）
+async fn insert_points<C: ConnectionTrait>(
+    connection: &C,
+    template_id: i64,
+    points: Vec<PointDefinition>,
+) -> Result<(), AppError> {
+    if points.is_empty() {
+        return Ok(());
+    }
+    let models = points.into_iter().zip(0..).map(|(point, sort_order)| {
+        device_template_points::ActiveModel {
+            template_id: Set(template_id),
+            point_key: Set(point.key),
+            name: Set(point.name),
+            unit: Set(point.unit),
+            data_type: Set(point.data_type),
+            register_type: Set(point.register_type),
+            address: Set(point.address),
+            byte_order: Set(point.byte_order),
+            scale: Set(point.scale),
+            value_offset: Set(point.offset),
+            access: Set(point.access),
+            sort_order: Set(sort_order),
+            ..Default::default()
+        }
+    });
+    device_template_points::Entity::insert_many(models)
+        .exec(connection)
+        .await
+        .map_err(map_db_error)?;
+    Ok(())
+}
+
+/// 为模板记录补充点位数与关联设备数
+async fn attach_counts<C: ConnectionTrait>(
+    connection: &C,
+    models: Vec<device_templates::Model>,
+) -> Result<Vec<DeviceTemplateRecord>, AppError> {
+    let ids: Vec<i64> = models.iter().map(|model| model.id).collect();
+    let point_counts: HashMap<i64, i64> = device_template_points::Entity::find()
+        .select_only()
+        .column(device_template_points::Column::TemplateId)
+        .column_as(
+            Expr::col(device_template_points::Column::Id).count(),
+            "count",
+        )
+        .filter(device_template_points::Column::TemplateId.is_in(ids.clone()))
+        .group_by(device_template_points::Column::TemplateId)
+        .into_tuple::<(i64, i64)>()
+        .all(connection)
+        .await
+        .map_err(map_db_error)?
+        .into_iter()
+        .collect();
+    let device_counts: HashMap<i64, i64> = device_registry::Entity::find()
+        .select_only()
+        .column(device_registry::Column::TemplateId)
+        .column_as(Expr::col(device_registry::Column::Id).count(), "count")
+        .filter(device_registry::Column::TemplateId.is_in(ids))
+        .group_by(device_registry::Column::TemplateId)
+        .into_tuple::<(i64, i64)>()
+        .all(connection)
+        .await
+        .map_err(map_db_error)?
+        .into_iter()
+        .collect();
+    Ok(models
+        .into_iter()
+        .map(|model| DeviceTemplateRecord {
+            point_count: point_counts.get(&model.id).copied().unwrap_or(0),
+            linked_device_count: device_counts.get(&model.id).copied().unwrap_or(0),
+            id: model.id,
+            code: model.code,
+            name: model.name,
+            manufacturer: model.manufacturer,
+            model: model.model,
+            device_type: model.device_type,
+            description: model.description,
+            polling: PollingSettings {
+                interval_ms: to_u32(model.poll_interval_ms),
+                timeout_ms: to_u32(model.poll_timeout_ms),
+                retries: to_u32(model.poll_retries),
+            },
+            version: model.version,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            created_by: model.created_by,
+        })
+        .collect())
+}
+
+/// 转义 LIKE 通配符（`%`、`_` 与转义符自身）
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// 将模板点位实体转换为点位定义
+fn map_template_point(model: device_template_points::Model) -> PointDefinition {
+    PointDefinition {
+        key: model.point_key,
+        name: model.name,
+        unit: model.unit,
+        data_type: model.data_type,
+        register_type: model.register_type,
+        address: model.address,
+        byte_order: model.byte_order,
+        scale: model.scale,
+        offset: model.value_offset,
+        access: model.access,
+    }
+}
+
+/// 将设备点位实体转换为设备点位记录
+fn map_device_point(model: device_points::Model) -> DevicePointRecord {
+    DevicePointRecord {
+        point: PointDefinition {
+            key: model.point_key,
+            name: model.name,
+            unit: model.unit,
+            data_type: model.data_type,
+            register_type: model.register_type,
+            address: model.address,
+            byte_order: model.byte_order,
+            scale: model.scale,
+            offset: model.value_offset,
+            access: model.access,
+        },
+        sort_order: model.sort_order,
+        overrides: match model.overrides {
+            Value::Object(overrides) => overrides,
+            _ => serde_json::Map::new(),
+        },
+        updated_at: model.updated_at,
+    }
+}
+
+/// 轮询参数写入数据库（已在服务层校验范围）
+fn to_i32(value: u32) -> i32 {
+    i32::try_from(value).unwrap_or(i32::MAX)
+}
+
+/// 从数据库读取轮询参数（负值视为 0）
+fn to_u32(value: i32) -> u32 {
+    u32::try_from(value).unwrap_or(0)
+}
+
+/// 将数据库错误映射为应用错误
+fn map_db_error(err: DbErr) -> AppError {
+    AppError::Database(err.to_string())
+}
+
+/// 将模板写入的数据库错误映射为应用错误（编码唯一约束与关联设备外键转为校验错误）
+fn map_template_mutation_error(err: DbErr) -> AppError {
+    let message = err.to_string();
+    if message.contains("device_templates_code_key") {
+        return AppError::Validation("template code already exists".to_string());
+    }
+    if message.contains("device_registry_template_id_fkey") {
+        return AppError::Validation("template has linked devices".to_string());
+    }
+    AppError::Database(message)
+}
@@ -0,0 +1,1037 @@
+//! 设备模板模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 设备模板（点位表与默认轮询参数）的查询与增删改，修改时模板版本加 1
+//! - 点位定义校验：数据类型、寄存器类型、地址范围、字节序、缩放与访问方式
+//! - 为设备应用模板、将模板变更同步到关联设备（保留设备级覆盖字段）
+//! - 设备点位的查询与设备级覆盖
+//! - 模板的 JSON 文档导入导出
+//! - 权限校验：`device:view`（查询与导出）、`device:manage`（模板增删改、导入与同步）、
+//!   `device:create`（应用模板与点位覆盖），涉及设备的操作同时校验用户设备范围
+//! - 审计记录（模板操作 `targetType = "device_template"`，设备操作 `targetType = "device"`）
+
+// 引入哈希映射与集合
+use std::collections::{HashMap, HashSet};
+
+// 引入 JSON 构造宏与值类型
+use serde_json::{Map, Value, json};
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入设备服务（操作员校验、设备范围校验与字段规范化）
+use crate::device::services as device_services;
+// 引入设备模板模型
+use crate::device_template::models::{
+    DevicePointData, DevicePointListPayload, DevicePointOverridePayload, DevicePointRecord,
+    DeviceTemplateApplyPayload, DeviceTemplateCreatePayload, DeviceTemplateData,
+    DeviceTemplateDeletePayload, DeviceTemplateDetailData, DeviceTemplateDocument,
+    DeviceTemplateExportPayload, DeviceTemplateGetPayload, DeviceTemplateImportData,
+    DeviceTemplateImportPayload, DeviceTemplateInput, DeviceTemplateListPayload,
+    DeviceTemplatePropagateData, DeviceTemplatePropagatePayload, DeviceTemplateRecord,
+    DeviceTemplateSpec, DeviceTemplateUpdatePayload, PointDefinition, PollingSettings,
+    TemplateBinding, TemplatePointSpec,
+};
+// 引入设备模板仓储模块
+use crate::device_template::repository;
+
+// 审计目标类型：设备模板
+const TARGET_TYPE_TEMPLATE: &str = "device_template";
+
+// 审计目标类型：设备
+const TARGET_TYPE_DEVICE: &str = "device";
+
+// 导入导出文档格式标识
+const DOCUMENT_FORMAT: &str = "device-templates";
+
+// 导入导出文档格式版本
+const DOCUMENT_VERSION: u32 = 1;
+
+// 未指定设备类型时的默认类型
+const DEFAULT_DEVICE_TYPE: &str = "generic";
+
+// 模板编码与点位标识最大长度
+const MAX_KEY_LENGTH: usize = 64;
+
+// 支持的数据类型
+const POINT_DATA_TYPES: [&str; 9] = [
+    "bool", "int16", "uint16", "int32", "uint32", "int64", "uint64", "float32", "float64",
+];
+
+// 支持的寄存器类型
+const REGISTER_TYPES: [&str; 4] = [
+    "coil",
+    "discrete_input",
+    "holding_register",
+    "input_register",
+];
+
+// 支持的多寄存器字节序
+const BYTE_ORDERS: [&str; 4] = ["abcd", "badc", "cdab", "dcba"];
+
+// 支持的访问方式
+const ACCESS_MODES: [&str; 2] = ["read", "read_write"];
+
+// 设备级允许覆盖的点位字段（数据类型与寄存器类型由模板决定）
+const OVERRIDABLE_FIELDS: [&str; 7] = [
+    "name",
+    "unit",
+    "address",
+    "byteOrder",
+    "scale",
+    "offset",
+    "access",
+];
+
+// 轮询周期范围（毫秒）
+const POLL_INTERVAL_RANGE: (u32, u32) = (100, 86_400_000);
+
+// 请求超时范围（毫秒）
+const POLL_TIMEOUT_RANGE: (u32, u32) = (100, 60_000);
+
+// 最大重试次数
+const MAX_POLL_RETRIES: u32 = 10;
+
+// 寄存器地址上限（0 起始）
+const MAX_REGISTER_ADDRESS: i64 = 65_535;
+
+/// 数据类型占用的寄存器（或线圈）数量
+///
+/// # 参数
+/// * `data_type` - 数据类型
+///
+/// # 返回
+/// * 占用数量（未知类型为 None）
+pub fn register_count(data_type: &str) -> Option<u16> {
+    match data_type {
+        "bool" | "int16" | "uint16" => Some(1),
+        "int32" | "uint32" | "float32" => Some(2),
+        "int64" | "uint64" | "float64" => Some(4),
+        _ => None,
+    }
+}
+
+/// 查询模板列表
+///
+/// # 参数
+/// * `payload` - 操作员用户名与关键字
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按编码排序的模板
+pub fn list_templates(
+    payload: DeviceTemplateListPayload,
+    now_millis: u64,
+) -> Result<Vec<DeviceTemplateData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let keyword = device_services::trim_optional(payload.keyword).map(|value| value.to_lowercase());
+    Ok(repository::list_templates(keyword.as_deref())?
+        .into_iter()
+        .map(map_template_record)
+        .collect())
+}
+
+/// 查询模板详情（含点位表）
+///
+/// # 参数
+/// * `payload` - 操作员用户名与模板 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 模板详情
+pub fn get_template(
+    payload: &DeviceTemplateGetPayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDetailData, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    find_detail(payload.template_id)
+}
+
+/// 创建模板
+///
+/// # 参数
+/// * `payload` - 创建请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的模板详情
+pub fn create_template(
+    payload: DeviceTemplateCreatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDetailData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let result = create_template_unaudited(payload, now_millis);
+    let target_id = result
+        .as_ref()
+        .ok()
+        .map(|detail| detail.template.id.to_string());
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_create",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_TEMPLATE,
+            target_id,
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 修改模板（整体替换模板字段与点位表，模板版本加 1）
+///
+/// 已关联的设备不会自动更新，需调用同步命令
+///
+/// # 参数
+/// * `payload` - 修改请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的模板详情
+pub fn update_template(
+    payload: DeviceTemplateUpdatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDetailData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let template_id = payload.template_id;
+    let before = find_snapshot(template_id);
+    let result = update_template_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_update",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_TEMPLATE,
+            target_id: Some(template_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除模板
+///
+/// 仍有关联设备的模板不能删除
+///
+/// # 参数
+/// * `payload` - 删除请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_template(
+    payload: &DeviceTemplateDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let before = find_snapshot(payload.template_id);
+    let result = delete_template_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_TEMPLATE,
+            target_id: Some(payload.template_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 导出模板为 JSON 文档
+///
+/// # 参数
+/// * `payload` - 操作员用户名与模板 ID 列表（为空时导出全部模板）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 模板文档
+pub fn export_templates(
+    payload: DeviceTemplateExportPayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDocument, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let requested: Vec<i64> = payload
+        .template_ids
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let filter = (!requested.is_empty()).then(|| requested.clone());
+    let templates = repository::list_templates_with_points(filter)?;
+    if let Some(missing) = requested
+        .iter()
+        .find(|id| !templates.iter().any(|(record, _)| record.id == **id))
+    {
+        return Err(AppError::Validation(format!(
+            "template not found: {missing}"
+        )));
+    }
+    Ok(DeviceTemplateDocument {
+        format: DOCUMENT_FORMAT.to_string(),
+        version: DOCUMENT_VERSION,
+        exported_at: now,
+        templates: templates
+            .into_iter()
+            .map(|(record, points)| to_spec(record, points))
+            .collect(),
+    })
+}
+
+/// 从 JSON 文档导入模板
+///
+/// 所有模板先完成校验再在单事务中写入；编码已存在时按 `overwrite` 覆盖（版本加 1）或跳过
+///
+/// # 参数
+/// * `payload` - 导入请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建、覆盖与跳过的模板编码
+pub fn import_templates(
+    payload: DeviceTemplateImportPayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateImportData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let result = import_templates_unaudited(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|data| serde_json::to_value(data).ok());
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_import",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_TEMPLATE,
+            target_id: None,
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 为设备应用模板
+///
+/// 设备点位按模板点位表整体重建；重新应用同一模板时保留设备级覆盖字段，更换模板时清空覆盖
+///
+/// # 参数
+/// * `payload` - 设备标识与模板 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 设备生效点位
+pub fn apply_template(
+    payload: &DeviceTemplateApplyPayload,
+    now_millis: u64,
+) -> Result<Vec<DevicePointData>, AppError> {
+    let device_id = payload.device_id.trim();
+    let before = find_binding_snapshot(device_id);
+    let result = apply_template_unaudited(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|_| find_binding_snapshot(device_id));
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_apply",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 将模板当前版本同步到关联设备
+///
+/// 设备点位按模板点位表重建并重新合并各设备的覆盖字段；覆盖字段对新模板不再有效时整体失败
+///
+/// # 参数
+/// * `payload` - 模板 ID 与设备标识列表（为空时同步全部关联设备）
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 同步的模板版本与设备标识
+pub fn propagate_template(
+    payload: DeviceTemplatePropagatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplatePropagateData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let template_id = payload.template_id;
+    let result = propagate_template_unaudited(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|data| serde_json::to_value(data).ok());
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_template_propagate",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_TEMPLATE,
+            target_id: Some(template_id.to_string()),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 查询设备生效点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与设备标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按排序号排列的设备点位
+pub fn list_device_points(
+    payload: &DevicePointListPayload,
+    now_millis: u64,
+) -> Result<Vec<DevicePointData>, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let device = device_services::ensure_device_accessible(user_id, &payload.device_id, now)?;
+    Ok(repository::list_device_points(&device.device_id)?
+        .into_iter()
+        .map(map_device_point)
+        .collect())
+}
+
+/// 设置设备点位的覆盖字段
+///
+/// 覆盖字段整体替换，生效定义 = 模板点位定义合并覆盖字段；空对象表示恢复模板定义。
+/// 设备已同步的模板版本落后时需先同步模板
+///
+/// # 参数
+/// * `payload` - 设备标识、点位标识与覆盖字段
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新后的设备点位
+pub fn override_device_point(
+    payload: DevicePointOverridePayload,
+    now_millis: u64,
+) -> Result<DevicePointData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let device_id = payload.device_id.trim().to_string();
+    let point_key = payload.point_key.trim().to_string();
+    let before = find_point_snapshot(&device_id, &point_key);
+    let result = override_device_point_unaudited(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(|data| serde_json::to_value(data).ok());
+    audit_services::record_command(
+        CommandAudit {
+            command: "device_point_override",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_DEVICE,
+            target_id: audit_services::target_id(&device_id),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 查询模板并生成设备关联（点位不含覆盖字段）
+///
+/// 设备管理模块由模板创建设备时调用
+///
+/// # 参数
+/// * `template_id` - 模板 ID
+///
+/// # 返回
+/// * (模板记录, 模板关联)
+pub(crate) fn resolve_template_binding(
+    template_id: i64,
+) -> Result<(DeviceTemplateRecord, TemplateBinding), AppError> {
+    let (record, points) = find_template(template_id)?;
+    let binding = build_binding(&record, &points, &HashMap::new())?;
+    Ok((record, binding))
+}
+
+// 创建模板（不含审计记录）
+fn create_template_unaudited(
+    payload: DeviceTemplateCreatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDetailData, AppError> {
+    let (operator_username, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let input = normalize_template(payload.template)?;
+    let template_id = repository::insert_template(input, &operator_username, now)?;
+    find_detail(template_id)
+}
+
+// 修改模板（不含审计记录）
+fn update_template_unaudited(
+    payload: DeviceTemplateUpdatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateDetailData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let input = normalize_template(payload.template)?;
+    if !repository::update_template(payload.template_id, input, now)? {
+        return Err(AppError::Validation("template not found".to_string()));
+    }
+    find_detail(payload.template_id)
+}
+
+// 删除模板（不含审计记录）
+fn delete_template_unaudited(
+    payload: &DeviceTemplateDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    if repository::delete_template(payload.template_id)? == 0 {
+        return Err(AppError::Validation("template not found".to_string()));
+    }
+    Ok(true)
+}
+
+// 导入模板（不含审计记录）
+fn import_templates_unaudited(
+    payload: DeviceTemplateImportPayload,
+    now_millis: u64,
+) -> Result<DeviceTemplateImportData, AppError> {
+    let (operator_username, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let document = payload.document;
+    if document.format != DOCUMENT_FORMAT {
+        return Err(AppError::Validation(format!(
+            "unsupported document format: {}",
+            document.format
+        )));
+    }
+    if document.version != DOCUMENT_VERSION {
+        return Err(AppError::Validation(format!(
+            "unsupported document version: {}",
+            document.version
+        )));
+    }
+    let mut codes = HashSet::new();
+    let mut inputs = Vec::with_capacity(document.templates.len());
+    for spec in document.templates {
+        let code = spec.code.trim().to_string();
+        let input = normalize_template(spec)
+            .map_err(|err| prefix_error(&format!("template {code}"), err))?;
+        if !codes.insert(input.code.clone()) {
+            return Err(AppError::Validation(format!(
+                "duplicate template code: {}",
+                input.code
+            )));
+        }
+        inputs.push(input);
+    }
+    repository::import_templates(inputs, payload.overwrite, &operator_username, now)
+}
+
+// 为设备应用模板（不含审计记录）
+fn apply_template_unaudited(
+    payload: &DeviceTemplateApplyPayload,
+    now_millis: u64,
+) -> Result<Vec<DevicePointData>, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let device = device_services::ensure_device_accessible(user_id, &payload.device_id, now)?;
+    let (record, points) = find_template(payload.template_id)?;
+    let overrides = if device.template_id == Some(record.id) {
+        current_overrides(&device.device_id)?
+    } else {
+        HashMap::new()
+    };
+    let binding = build_binding(&record, &points, &overrides)?;
+    repository::bind_devices(vec![(device.device_id.clone(), binding)], now)?;
+    Ok(repository::list_device_points(&device.device_id)?
+        .into_iter()
+        .map(map_device_point)
+        .collect())
+}
+
+// 同步模板到关联设备（不含审计记录）
+fn propagate_template_unaudited(
+    payload: DeviceTemplatePropagatePayload,
+    now_millis: u64,
+) -> Result<DeviceTemplatePropagateData, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let (record, points) = find_template(payload.template_id)?;
+    let linked = repository::list_linked_device_ids(record.id)?;
+    let device_ids = if payload.device_ids.is_empty() {
+        linked
+    } else {
+        let mut device_ids = Vec::with_capacity(payload.device_ids.len());
+        for device_id in payload.device_ids {
+            let device_id = device_id.trim().to_string();
+            if !linked.contains(&device_id) {
+                return Err(AppError::Validation(format!(
+                    "device not linked to template: {device_id}"
+                )));
+            }
+            if !device_ids.contains(&device_id) {
+                device_ids.push(device_id);
+            }
+        }
+        device_ids
+    };
+    let mut bindings = Vec::with_capacity(device_ids.len());
+    for device_id in &device_ids {
+        device_services::ensure_device_accessible(user_id, device_id, now)?;
+        let overrides = current_overrides(device_id)?;
+        let binding = build_binding(&record, &points, &overrides)
+            .map_err(|err| prefix_error(&format!("device {device_id}"), err))?;
+        bindings.push((device_id.clone(), binding));
+    }
+    repository::bind_devices(bindings, now)?;
+    Ok(DeviceTemplatePropagateData {
+        template_id: record.id,
+        version: record.version,
+        device_ids,
+    })
+}
+
+// 设置设备点位覆盖字段（不含审计记录）
+fn override_device_point_unaudited(
+    payload: DevicePointOverridePayload,
+    now_millis: u64,
+) -> Result<DevicePointData, AppError> {
+    let (_, user_id, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_CREATE,
+        "forbidden: device create required",
+        now_millis,
+    )?;
+    let device = device_services::ensure_device_accessible(user_id, &payload.device_id, now)?;
+    let template_id = device
+        .template_id
+        .ok_or_else(|| AppError::Validation("device has no template".to_string()))?;
+    let (record, points) = find_template(template_id)?;
+    if device.template_version != Some(record.version) {
+        return Err(AppError::Validation(
+            "device template version is outdated, propagate the template first".to_string(),
+        ));
+    }
+    let point_key = payload.point_key.trim();
+    let (sort_order, template_point) = points
+        .iter()
+        .zip(0..)
+        .find(|(point, _)| point.key == point_key)
+        .map(|(point, sort_order)| (sort_order, point))
+        .ok_or_else(|| AppError::Validation("point not found".to_string()))?;
+    let point = merge_point(template_point, &payload.overrides)?;
+    let updated = DevicePointRecord {
+        point,
+        sort_order,
+        overrides: payload.overrides,
+        updated_at: now,
+    };
+    if !repository::update_device_point(&device.device_id, updated.clone())? {
+        return Err(AppError::Validation("point not found".to_string()));
+    }
+    Ok(map_device_point(updated))
+}
+
+/// 查询模板及其点位表，模板不存在时返回校验错误
+fn find_template(
+    template_id: i64,
+) -> Result<(DeviceTemplateRecord, Vec<PointDefinition>), AppError> {
+    repository::find_template(template_id)?
+        .ok_or_else(|| AppError::Validation("template not found".to_string()))
+}
+
+/// 查询模板详情
+fn find_detail(template_id: i64) -> Result<DeviceTemplateDetailData, AppError> {
+    let (record, points) = find_template(template_id)?;
+    Ok(DeviceTemplateDetailData {
+        template: map_template_record(record),
+        points,
+    })
+}
+
+/// 读取设备当前各点位的覆盖字段
+fn current_overrides(device_id: &str) -> Result<HashMap<String, Map<String, Value>>, AppError> {
+    Ok(repository::list_device_points(device_id)?
+        .into_iter()
+        .filter(|record| !record.overrides.is_empty())
+        .map(|record| (record.point.key, record.overrides))
+        .collect())
+}
+
+/// 由模板点位表与覆盖字段生成设备关联
+///
+/// 模板中已删除的点位对应的覆盖字段随之丢弃
+fn build_binding(
+    record: &DeviceTemplateRecord,
+    points: &[PointDefinition],
+    overrides: &HashMap<String, Map<String, Value>>,
+) -> Result<TemplateBinding, AppError> {
+    let mut records = Vec::with_capacity(points.len());
+    for (point, sort_order) in points.iter().zip(0..) {
+        let point_overrides = overrides.get(&point.key).cloned().unwrap_or_default();
+        records.push(DevicePointRecord {
+            point: merge_point(point, &point_overrides)?,
+            sort_order,
+            overrides: point_overrides,
+            updated_at: 0,
+        });
+    }
+    Ok(TemplateBinding {
+        template_id: record.id,
+        version: record.version,
+        points: records,
+    })
+}
+
+/// 将覆盖字段合并到模板点位定义并重新校验
+fn merge_point(
+    point: &PointDefinition,
+    overrides: &Map<String, Value>,
+) -> Result<PointDefinition, AppError> {
+    if overrides.is_empty() {
+        return Ok(point.clone());
+    }
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(point) else {
+        return Err(AppError::Validation("invalid point definition".to_string()));
+    };
+    for (field, value) in overrides {
+        if !OVERRIDABLE_FIELDS.contains(&field.as_str()) {
+            return Err(AppError::Validation(format!(
+                "point {}: field cannot be overridden: {field}",
+                point.key
+            )));
+        }
+        merged.insert(field.clone(), value.clone());
+    }
+    let spec: TemplatePointSpec = serde_json::from_value(Value::Object(merged)).map_err(|err| {
+        AppError::Validation(format!("point {}: invalid override: {err}", point.key))
+    })?;
+    normalize_point(spec)
+}
+
+/// 校验并规范化模板定义
+fn normalize_template(spec: DeviceTemplateSpec) -> Result<DeviceTemplateInput, AppError> {
+    let code = normalize_key(&spec.code, "code")?;
+    let name = spec.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".to_string()));
+    }
+    let device_type = if spec.device_type.trim().is_empty() {
+        DEFAULT_DEVICE_TYPE.to_string()
+    } else {
+        device_services::normalize_device_type(&spec.device_type)?
+    };
+    let polling = normalize_polling(spec.polling)?;
+    let mut keys = HashSet::new();
+    let mut points = Vec::with_capacity(spec.points.len());
+    for point in spec.points {
+        let point = normalize_point(point)?;
+        if !keys.insert(point.key.clone()) {
+            return Err(AppError::Validation(format!(
+                "duplicate point key: {}",
+                point.key
+            )));
+        }
+        points.push(point);
+    }
+    Ok(DeviceTemplateInput {
+        code,
+        name,
+        manufacturer: device_services::trim_optional(spec.manufacturer),
+        model: device_services::trim_optional(spec.model),
+        device_type,
+        description: device_services::trim_optional(spec.description),
+        polling,
+        points,
+    })
+}
+
+/// 校验默认轮询参数范围
+fn normalize_polling(polling: PollingSettings) -> Result<PollingSettings, AppError> {
+    if !(POLL_INTERVAL_RANGE.0..=POLL_INTERVAL_RANGE.1).contains(&polling.interval_ms) {
+        return Err(AppError::Validation(
+            "polling.intervalMs must be between 100 and 86400000".to_string(),
+        ));
+    }
+    if !(POLL_TIMEOUT_RANGE.0..=POLL_TIMEOUT_RANGE.1).contains(&polling.timeout_ms) {
+        return Err(AppError::Validation(
+            "polling.timeoutMs must be between 100 and 60000".to_string(),
+        ));
+    }
+    if polling.retries > MAX_POLL_RETRIES {
+        return Err(AppError::Validation(
+            "polling.retries must be at most 10".to_string(),
+        ));
+    }
+    Ok(polling)
+}
+
+/// 校验并规范化点位定义
+///
+/// - 线圈与离散输入只能是 bool，bool 只能映射到线圈与离散输入
+/// - 起始地址加占用数量不能超出 0..=65535
+/// - 缩放系数必须是非零有限数，偏移量必须是有限数
+/// - read_write 只允许线圈与保持寄存器
+fn normalize_point(spec: TemplatePointSpec) -> Result<PointDefinition, AppError> {
+    let key = normalize_key(&spec.key, "point key")?;
+    let point_error = |message: &str| AppError::Validation(format!("point {key}: {message}"));
+    let name = spec.name.trim().to_string();
+    if name.is_empty() {
+        return Err(point_error("name is required"));
+    }
+    let data_type = spec.data_type.trim().to_lowercase();
+    let Some(count) = register_count(&data_type) else {
+        return Err(point_error(&format!(
+            "dataType must be one of {}",
+            POINT_DATA_TYPES.join(", ")
+        )));
+    };
+    let register_type = spec.register_type.trim().to_lowercase();
+    if !REGISTER_TYPES.contains(&register_type.as_str()) {
+        return Err(point_error(&format!(
+            "registerType must be one of {}",
+            REGISTER_TYPES.join(", ")
+        )));
+    }
+    let bit_register = matches!(register_type.as_str(), "coil" | "discrete_input");
+    if bit_register != (data_type == "bool") {
+        return Err(point_error(
+            "bool points must use coil or discrete_input and vice versa",
+        ));
+    }
+    if spec.address < 0 || spec.address + i64::from(count) - 1 > MAX_REGISTER_ADDRESS {
+        return Err(point_error("address out of range 0..65535"));
+    }
+    let byte_order = spec.byte_order.map_or_else(
+        || BYTE_ORDERS[0].to_string(),
+        |value| value.trim().to_lowercase(),
+    );
+    if !BYTE_ORDERS.contains(&byte_order.as_str()) {
+        return Err(point_error(&format!(
+            "byteOrder must be one of {}",
+            BYTE_ORDERS.join(", ")
+        )));
+    }
+    let scale = spec.scale.unwrap_or(1.0);
+    if !scale.is_finite() || scale == 0.0 {
+        return Err(point_error("scale must be a non-zero finite number"));
+    }
+    let offset = spec.offset.unwrap_or(0.0);
+    if !offset.is_finite() {
+        return Err(point_error("offset must be a finite number"));
+    }
+    let access = spec.access.map_or_else(
+        || ACCESS_MODES[0].to_string(),
+        |value| value.trim().to_lowercase(),
+    );
+    if !ACCESS_MODES.contains(&access.as_str()) {
+        return Err(point_error(&format!(
+            "access must be one of {}",
+            ACCESS_MODES.join(", ")
+        )));
+    }
+    if access == "read_write" && !matches!(register_type.as_str(), "coil" | "holding_register") {
+        return Err(point_error(
+            "read_write access requires coil or holding_register",
+        ));
+    }
+    Ok(PointDefinition {
+        address: i32::try_from(spec.address)
+            .map_err(|_| point_error("address out of range 0..65535"))?,
+        key,
+        name,
+        unit: device_services::trim_optional(spec.unit),
+        data_type,
+        register_type,
+        byte_order,
+        scale,
+        offset,
+        access,
+    })
+}
+
+/// 校验模板编码或点位标识
+///
+/// 只允许字母、数字、`_`、`-`、`.`，最长 64 个字符
+fn normalize_key(value: &str, field: &str) -> Result<String, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::Validation(format!("{field} is required")));
+    }
+    if value.len() > MAX_KEY_LENGTH
+        || !value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "{field} must be at most 64 letters, digits, '_', '-' or '.'"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// 为校验错误添加上下文前缀（数据库错误保持原样）
+fn prefix_error(context: &str, err: AppError) -> AppError {
+    match err {
+        AppError::Validation(message) => AppError::Validation(format!("{context}: {message}")),
+        other @ (AppError::Database(_) | AppError::Modbus(_)) => other,
+    }
+}
+
+/// 将模板记录与点位表转换为导出文档中的模板定义
+fn to_spec(record: DeviceTemplateRecord, points: Vec<PointDefinition>) -> DeviceTemplateSpec {
+    DeviceTemplateSpec {
+        code: record.code,
+        name: record.name,
+        manufacturer: record.manufacturer,
+        model: record.model,
+        device_type: record.device_type,
+        description: record.description,
+        polling: record.polling,
+        points: points
+            .into_iter()
+            .map(|point| TemplatePointSpec {
+                key: point.key,
+                name: point.name,
+                unit: point.unit,
+                data_type: point.data_type,
+                register_type: point.register_type,
+                address: i64::from(point.address),
+                byte_order: Some(point.byte_order),
+                scale: Some(point.scale),
+                offset: Some(point.offset),
+                access: Some(point.access),
+            })
+            .collect(),
+    }
+}
+
+/// 将模板记录转换为响应格式
+fn map_template_record(record: DeviceTemplateRecord) -> DeviceTemplateData {
+    DeviceTemplateData {
+        id: record.id,
+        code: record.code,
+        name: record.name,
+        manufacturer: record.manufacturer,
+        model: record.model,
+        device_type: record.device_type,
+        description: record.description,
+        polling: record.polling,
+        version: record.version,
+        point_count: record.point_count,
+        linked_device_count: record.linked_device_count,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        created_by: record.created_by,
+    }
+}
+
+/// 将设备点位记录转换为响应格式
+fn map_device_point(record: DevicePointRecord) -> DevicePointData {
+    DevicePointData {
+        point: record.point,
+        overrides: record.overrides,
+        updated_at: record.updated_at,
+    }
+}
+
+/// 查询模板当前快照（审计操作前内容，查询失败时不记录快照）
+fn find_snapshot(template_id: i64) -> Option<Value> {
+    find_detail(template_id).ok().as_ref().and_then(snapshot)
+}
+
+/// 将模板详情序列化为审计快照
+fn snapshot(data: &DeviceTemplateDetailData) -> Option<Value> {
+    serde_json::to_value(data).ok()
+}
+
+/// 查询设备的模板关联快照（关联模板、已同步版本与覆盖字段）
+fn find_binding_snapshot(device_id: &str) -> Option<Value> {
+    if device_id.is_empty() {
+        return None;
+    }
+    let device = crate::device::repository::find_device(device_id)
+        .ok()
+        .flatten()?;
+    let overrides: Map<String, Value> = current_overrides(device_id)
+        .ok()?
+        .into_iter()
+        .map(|(key, overrides)| (key, Value::Object(overrides)))
+        .collect();
+    Some(json!({
+        "templateId": device.template_id,
+        "templateVersion": device.template_version,
+        "overrides": overrides,
+    }))
+}
+
+/// 查询设备点位快照
+fn find_point_snapshot(device_id: &str, point_key: &str) -> Option<Value> {
+    if device_id.is_empty() {
+        return None;
+    }
+    repository::list_device_points(device_id)
+        .ok()?
+        .into_iter()
+        .find(|record| record.point.key == point_key)
+        .map(map_device_point)
+        .and_then(|data| serde_json::to_value(data).ok())
+}
@@ -0,0 +1,1885 @@
+//! 通信网关模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的通信网关相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `gateway_list` | 查询网关列表 |
+//! | `gateway_get` | 查询网关详情 |
+//! | `gateway_create` | 创建网关 |
+//! | `gateway_update` | 修改网关 |
+//! | `gateway_delete` | 删除网关 |
+//! | `gateway_test_connection` | 以独立的临时会话测试网关连接 |
+//! | `gateway_slave_list` | 查询网关下的从站 |
+//! | `gateway_slave_create` | 创建从站 |
+//! | `gateway_slave_update` | 修改从站 |
+//! | `gateway_slave_delete` | 删除从站（点位一并删除） |
+//! | `gateway_point_list` | 查询从站下的点位 |
+//! | `gateway_point_create` | 创建点位 |
+//! | `gateway_point_update` | 修改点位 |
+//! | `gateway_point_delete` | 删除点位 |
+//! | `gateway_point_read` | 点位读测试（读取并解码） |
+//! | `gateway_point_write` | 点位写测试（编码并写入） |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入通信网关数据模型
+use crate::gateway::models::{
+    GatewayCreatePayload, GatewayData, GatewayDeletePayload, GatewayGetPayload, GatewayListPayload,
+    GatewayPointCreatePayload, GatewayPointData, GatewayPointDeletePayload,
+    GatewayPointListPayload, GatewayPointReadData, GatewayPointReadPayload,
+    GatewayPointUpdatePayload, GatewayPointWriteData, GatewayPointWritePayload,
+    GatewaySlaveCreatePayload, GatewaySlaveData, GatewaySlaveDeletePayload,
+    GatewaySlaveListPayload, GatewaySlaveUpdatePayload, GatewayTestData, GatewayTestPayload,
+    GatewayUpdatePayload,
+};
+// 引入通信网关服务层
+use crate::gateway::services;
+
+/// 查询网关列表
+///
+/// # 参数
+/// * `payload` - 操作员用户名与关键字
+///
+/// # 返回
+/// * 按编码排序的网关
+#[tauri::command]
+pub fn gateway_list(
+    payload: GatewayListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<GatewayData>> {
+    execute_traced_command("gateway_list", trace, || {
+        Ok(ApiResponse::ok(services::list_gateways(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询网关详情
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+///
+/// # 返回
+/// * 网关配置
+#[tauri::command]
+pub fn gateway_get(
+    payload: GatewayGetPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayData> {
+    execute_traced_command("gateway_get", trace, || {
+        Ok(ApiResponse::ok(services::get_gateway(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建网关
+///
+/// # 参数
+/// * `payload` - 网关编码、名称、传输方式、地址端口或串口参数、帧格式与超时
+///
+/// # 返回
+/// * 新建的网关
+#[tauri::command]
+pub fn gateway_create(
+    payload: GatewayCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayData> {
+    execute_traced_command("gateway_create", trace, || {
+        Ok(ApiResponse::ok(services::create_gateway(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 修改网关
+///
+/// # 参数
+/// * `payload` - 网关 ID 及整体替换的网关定义
+///
+/// # 返回
+/// * 修改后的网关
+#[tauri::command]
+pub fn gateway_update(
+    payload: GatewayUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayData> {
+    execute_traced_command("gateway_update", trace, || {
+        Ok(ApiResponse::ok(services::update_gateway(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除网关
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn gateway_delete(
+    payload: GatewayDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("gateway_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_gateway(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 测试网关连接
+///
+/// # 参数
+/// * `payload` - 已保存的网关 ID 或网关定义，以及可选的探测读取
+///
+/// # 返回
+/// * 测试结果（建连与探测延迟、错误类别与错误信息）
+#[tauri::command]
+pub fn gateway_test_connection(
+    payload: GatewayTestPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayTestData> {
+    execute_traced_command("gateway_test_connection", trace, || {
+        Ok(ApiResponse::ok(services::test_connection(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询网关下的从站
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+///
+/// # 返回
+/// * 按单元号排序的从站
+#[tauri::command]
+pub fn gateway_slave_list(
+    payload: GatewaySlaveListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<GatewaySlaveData>> {
+    execute_traced_command("gateway_slave_list", trace, || {
+        Ok(ApiResponse::ok(services::list_slaves(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建从站
+///
+/// # 参数
+/// * `payload` - 网关 ID 与从站定义（单元号、名称与可选的地址范围）
+///
+/// # 返回
+/// * 新建的从站
+#[tauri::command]
+pub fn gateway_slave_create(
+    payload: GatewaySlaveCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewaySlaveData> {
+    execute_traced_command("gateway_slave_create", trace, || {
+        Ok(ApiResponse::ok(services::create_slave(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 修改从站
+///
+/// # 参数
+/// * `payload` - 从站 ID 及整体替换的从站定义
+///
+/// # 返回
+/// * 修改后的从站
+#[tauri::command]
+pub fn gateway_slave_update(
+    payload: GatewaySlaveUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewaySlaveData> {
+    execute_traced_command("gateway_slave_update", trace, || {
+        Ok(ApiResponse::ok(services::update_slave(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除从站（点位一并删除）
+///
+/// # 参数
+/// * `payload` - 操作员用户名与从站 ID
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn gateway_slave_delete(
+    payload: GatewaySlaveDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("gateway_slave_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_slave(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询从站下的点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与从站 ID
+///
+/// # 返回
+/// * 按排序号与起始地址排序的点位
+#[tauri::command]
+pub fn gateway_point_list(
+    payload: GatewayPointListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<GatewayPointData>> {
+    execute_traced_command("gateway_point_list", trace, || {
+        Ok(ApiResponse::ok(services::list_points(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建点位
+///
+/// # 参数
+/// * `payload` - 从站 ID 与点位定义（功能码、起始地址、数量、数据类型与字节序）
+///
+/// # 返回
+/// * 新建的点位
+#[tauri::command]
+pub fn gateway_point_create(
+    payload: GatewayPointCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayPointData> {
+    execute_traced_command("gateway_point_create", trace, || {
+        Ok(ApiResponse::ok(services::create_point(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 修改点位
+///
+/// # 参数
+/// * `payload` - 点位 ID 及整体替换的点位定义
+///
+/// # 返回
+/// * 修改后的点位
+#[tauri::command]
+pub fn gateway_point_update(
+    payload: GatewayPointUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayPointData> {
+    execute_traced_command("gateway_point_update", trace, || {
+        Ok(ApiResponse::ok(services::update_point(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与点位 ID
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn gateway_point_delete(
+    payload: GatewayPointDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("gateway_point_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_point(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 点位读测试
+///
+/// # 参数
+/// * `payload` - 操作员用户名与点位 ID
+///
+/// # 返回
+/// * 原始线圈或寄存器值与按数据类型、字节序解码后的值
+#[tauri::command]
+pub fn gateway_point_read(
+    payload: GatewayPointReadPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayPointReadData> {
+    execute_traced_command("gateway_point_read", trace, || {
+        Ok(ApiResponse::ok(services::read_point(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 点位写测试（需要 `control:issue`，写入审计事件）
+///
+/// # 参数
+/// * `payload` - 操作员用户名、点位 ID 与写入值
+///
+/// # 返回
+/// * 写入的功能码与编码后的寄存器值
+#[tauri::command]
+pub fn gateway_point_write(
+    payload: GatewayPointWritePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<GatewayPointWriteData> {
+    execute_traced_command("gateway_point_write", trace, || {
+        Ok(ApiResponse::ok(services::write_point(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use crate::db;
+    use crate::db::test_support::{ensure_test_db_ready, unique_code};
+    use crate::gateway::models::{
+        GatewayPointSpec, GatewayProbeSpec, GatewaySlaveSpec, GatewaySpec,
+    };
+    use crate::modbus::commands::{
+        modbus_capture_start, modbus_connection_list, modbus_diagnostics, modbus_disconnect,
+        modbus_tcp_connect,
+    };
+    use crate::modbus::models::{
+        ModbusCaptureStartPayload, ModbusConnectionListPayload, ModbusDiagnosticsPayload,
+        ModbusGatewayPayload, ModbusTcpConnectPayload,
+    };
+    use crate::modbus::simulator::{SlaveMemory, TcpSimulator};
+    use serde_json::json;
+
+    fn tcp_spec(code: &str, address: std::net::SocketAddr) -> GatewaySpec {
+        GatewaySpec {
+            code: code.to_string(),
+            name: "配电房网关".to_string(),
+            host: address.ip().to_string(),
+            port: Some(address.port()),
+            ..GatewaySpec::default()
+        }
+    }
+
+    fn create(spec: GatewaySpec) -> AppResult<GatewayData> {
+        gateway_create(
+            GatewayCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway: spec,
+            },
+            None,
+        )
+    }
+
+    fn test_connection(
+        gateway_id: Option<i64>,
+        gateway: Option<GatewaySpec>,
+        probe: Option<GatewayProbeSpec>,
+    ) -> GatewayTestData {
+        gateway_test_connection(
+            GatewayTestPayload {
+                operator_username: "admin".to_string(),
+                gateway_id,
+                gateway,
+                probe,
+            },
+            None,
+        )
+        .expect("test connection")
+        .data
+    }
+
+    fn holding_probe(address: u16, count: u16) -> GatewayProbeSpec {
+        GatewayProbeSpec {
+            register_type: "holding_register".to_string(),
+            address,
+            count: Some(count),
+            ..GatewayProbeSpec::default()
+        }
+    }
+
+    #[test]
+    fn gateway_crud_round_trips_tcp_and_serial() {
+        ensure_test_db_ready();
+        let code = unique_code("gw_crud");
+        let created = create(GatewaySpec {
+            remark: Some("  一号配电房  ".to_string()),
+            ..tcp_spec(&code, "192.168.1.100:502".parse().expect("address"))
+        })
+        .expect("create gateway")
+        .data;
+        assert_eq!(created.code, code);
+        assert_eq!(created.transport, "tcp");
+        assert_eq!(created.host.as_deref(), Some("192.168.1.100"));
+        assert_eq!(created.port, Some(502));
+        assert_eq!(created.framing, "mbap");
+        assert_eq!(
+            (created.connect_timeout_ms, created.request_timeout_ms),
+            (3000, 1000)
+        );
+        assert!(created.enabled);
+        assert_eq!(created.remark.as_deref(), Some("一号配电房"));
+        assert_eq!(created.created_by, "admin");
+
+        let listed = gateway_list(
+            GatewayListPayload {
+                operator_username: "admin".to_string(),
+                keyword: Some(code.to_uppercase()),
+            },
+            None,
+        )
+        .expect("list gateways")
+        .data;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, created.id);
+
+        // 改为串口网关：地址端口清空，串口参数规范化
+        let updated = gateway_update(
+            GatewayUpdatePayload {
+                operator_username: "admin".to_string(),
+                gateway_id: created.id,
+                gateway: GatewaySpec {
+                    code: code.clone(),
+                    name: "RS-485 总线".to_string(),
+                    transport: Some("Serial".to_string()),
+                    serial_port: "/dev/ttyUSB0".to_string(),
+                    baud_rate: Some(19_200),
+                    parity: Some("Even".to_string()),
+                    framing: Some("ascii".to_string()),
+                    request_timeout_ms: Some(500),
+                    enabled: Some(false),
+                    ..GatewaySpec::default()
+                },
+            },
+            None,
+        )
+        .expect("update gateway")
+        .data;
+        assert_eq!(updated.transport, "serial");
+        assert_eq!((updated.host, updated.port), (None, None));
+        assert_eq!(updated.serial_port.as_deref(), Some("/dev/ttyUSB0"));
+        assert_eq!(
+            (updated.baud_rate, updated.data_bits, updated.stop_bits),
+            (Some(19_200), Some(8), Some(1))
+        );
+        assert_eq!(updated.parity.as_deref(), Some("even"));
+        assert_eq!(updated.framing, "ascii");
+        assert_eq!(updated.request_timeout_ms, 500);
+        assert!(!updated.enabled);
+        assert_eq!(updated.remark, None);
+        let fetched = gateway_get(
+            GatewayGetPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: created.id,
+            },
+            None,
+        )
+        .expect("get gateway")
+        .data;
+        assert_eq!(fetched.name, "RS-485 总线");
+
+        let delete = |gateway_id| {
+            gateway_delete(
+                GatewayDeletePayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id,
+                },
+                None,
+            )
+        };
+        assert!(delete(created.id).expect("delete gateway").data);
+        assert_eq!(
+            delete(created.id).expect_err("deleted"),
+            AppError::Validation("gateway not found".to_string())
+        );
+    }
+
+    #[test]
+    fn gateway_delete_evicts_its_diagnostics() {
+        ensure_test_db_ready();
+        let code = unique_code("gw_diag");
+        let created = create(tcp_spec(&code, "127.0.0.1:502".parse().expect("address")))
+            .expect("create gateway")
+            .data;
+        let capture_start = || {
+            modbus_capture_start(
+                ModbusCaptureStartPayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id: code.clone(),
+                    capacity: None,
+                },
+                None,
+            )
+        };
+        let diagnostics = || {
+            modbus_diagnostics(
+                ModbusDiagnosticsPayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id: Some(code.clone()),
+                },
+                None,
+            )
+            .expect("diagnostics")
+            .data
+        };
+
+        // 已保存但尚未建立长连接的网关可以开启捕获；删除网关后诊断随之移除
+        assert!(capture_start().expect("start capture").data.capture.enabled);
+        assert_eq!(diagnostics().len(), 1);
+        gateway_delete(
+            GatewayDeletePayload {
+                operator_username: "admin".to_string(),
+                gateway_id: created.id,
+            },
+            None,
+        )
+        .expect("delete gateway");
+        assert!(diagnostics().is_empty());
+        assert_eq!(
+            capture_start().expect_err("deleted gateway"),
+            AppError::Validation("gateway not found".to_string())
+        );
+    }
+
+    #[test]
+    fn gateway_create_validates_transport_settings() {
+        ensure_test_db_ready();
+        let address = "10.0.0.1:502".parse().expect("address");
+        let code = unique_code("gw_validate");
+        create(tcp_spec(&code, address)).expect("create gateway");
+        let cases = [
+            (
+                tcp_spec(&code, address),
+                "gateway code already exists".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    code: " ".to_string(),
+                    ..tcp_spec(&code, address)
+                },
+                "code is required".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    host: String::new(),
+                    ..tcp_spec(&unique_code("gw_invalid"), address)
+                },
+                "host is required".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    transport: Some("udp".to_string()),
+                    ..tcp_spec(&unique_code("gw_invalid"), address)
+                },
+                "transport must be one of tcp, serial".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    transport: Some("serial".to_string()),
+                    ..tcp_spec(&unique_code("gw_invalid"), address)
+                },
+                "serialPort is required".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    transport: Some("serial".to_string()),
+                    serial_port: "/dev/ttyUSB1".to_string(),
+                    framing: Some("mbap".to_string()),
+                    ..tcp_spec(&unique_code("gw_invalid"), address)
+                },
+                "framing must be one of rtu, ascii".to_string(),
+            ),
+            (
+                GatewaySpec {
+                    connect_timeout_ms: Some(10),
+                    ..tcp_spec(&unique_code("gw_invalid"), address)
+                },
+                "connectTimeoutMs must be between 100 and 60000".to_string(),
+            ),
+        ];
+        for (spec, message) in cases {
+            assert_eq!(
+                create(spec).expect_err("invalid gateway"),
+                AppError::Validation(message)
+            );
+        }
+    }
+
+    #[test]
+    fn gateway_commands_require_device_permissions() {
+        ensure_test_db_ready();
+        let address = "10.0.0.1:502".parse().expect("address");
+        let err = gateway_list(
+            GatewayListPayload {
+                operator_username: "common".to_string(),
+                keyword: None,
+            },
+            None,
+        )
+        .expect_err("forbidden list");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+        let err = gateway_create(
+            GatewayCreatePayload {
+                operator_username: "common".to_string(),
+                gateway: tcp_spec(&unique_code("gw_forbidden"), address),
+            },
+            None,
+        )
+        .expect_err("forbidden create");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+    }
+
+    #[test]
+    fn test_connection_uses_an_isolated_session() {
+        ensure_test_db_ready();
+        let mut memory = SlaveMemory::new(10);
+        memory.holding_registers[..2].copy_from_slice(&[0x022B, 0x0064]);
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let code = unique_code("gw_test");
+        let gateway = create(tcp_spec(&code, simulator.local_addr()))
+            .expect("create gateway")
+            .data;
+
+        // 生产连接使用网关编码作为网关标识
+        let production = modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: code.clone(),
+                host: simulator.local_addr().ip().to_string(),
+                port: Some(simulator.local_addr().port()),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect("production connect")
+        .data;
+        assert!(production.connected);
+
+        let result = test_connection(Some(gateway.id), None, Some(holding_probe(0, 2)));
+        assert!(result.success);
+        assert_eq!(result.transport, "tcp");
+        assert_eq!(result.target, simulator.local_addr().to_string());
+        assert_eq!(result.registers, Some(vec![0x022B, 0x0064]));
+        assert!(result.connect_latency_ms.is_some() && result.probe_latency_ms.is_some());
+        assert_eq!(result.error_class, None);
+
+        // 从站异常响应
+        let result = test_connection(Some(gateway.id), None, Some(holding_probe(9, 5)));
+        assert!(!result.success);
+        assert_eq!(result.error_class.as_deref(), Some("exception"));
+        assert!(result.connect_latency_ms.is_some());
+
+        // 生产连接未受影响（未重连、未记录错误）
+        let connections = modbus_connection_list(
+            ModbusConnectionListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("connection list")
+        .data;
+        let production = connections
+            .iter()
+            .find(|connection| connection.gateway_id == code)
+            .expect("production connection");
+        assert!(production.connected);
+        assert_eq!(production.connect_count, 1);
+        assert_eq!(production.last_error, None);
+        modbus_disconnect(
+            ModbusGatewayPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: code,
+            },
+            None,
+        )
+        .expect("disconnect");
+    }
+
+    #[test]
+    fn test_connection_classifies_refused_and_timeout() {
+        ensure_test_db_ready();
+        // 尚未保存的网关定义：目标拒绝连接
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let closed_address = closed.local_addr().expect("address");
+        drop(closed);
+        let result = test_connection(None, Some(tcp_spec("", closed_address)), None);
+        assert!(!result.success);
+        assert_eq!(result.error_class.as_deref(), Some("refused"));
+        assert_eq!(result.connect_latency_ms, None);
+
+        // 从站不应答：探测读取按 3 秒超时返回
+        let silent = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let silent_address = silent.local_addr().expect("address");
+        let result = test_connection(
+            None,
+            Some(tcp_spec("", silent_address)),
+            Some(holding_probe(0, 1)),
+        );
+        assert_eq!(result.error_class.as_deref(), Some("timeout"));
+        assert_eq!(result.error.as_deref(), Some("timeout after 3000ms"));
+        assert!(result.latency_ms >= 3000);
+        drop(silent);
+    }
+
+    #[test]
+    fn test_connection_validates_payload_and_permissions() {
+        ensure_test_db_ready();
+        let gateway = create(tcp_spec(
+            &unique_code("gw_test_invalid"),
+            "192.168.1.100:502".parse().expect("address"),
+        ))
+        .expect("create gateway")
+        .data;
+        let invalid = |payload: GatewayTestPayload| {
+            gateway_test_connection(payload, None).expect_err("invalid test")
+        };
+        assert_eq!(
+            invalid(GatewayTestPayload {
+                operator_username: "admin".to_string(),
+                ..GatewayTestPayload::default()
+            }),
+            AppError::Validation("gatewayId or gateway is required".to_string())
+        );
+        assert_eq!(
+            invalid(GatewayTestPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: Some(gateway.id),
+                probe: Some(GatewayProbeSpec {
+                    register_type: "register".to_string(),
+                    ..GatewayProbeSpec::default()
+                }),
+                ..GatewayTestPayload::default()
+            }),
+            AppError::Validation(
+                "registerType must be one of coil, discrete_input, holding_register, input_register"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            invalid(GatewayTestPayload {
+                operator_username: "common".to_string(),
+                gateway_id: Some(gateway.id),
+                ..GatewayTestPayload::default()
+            }),
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+    }
+
+    fn slave_spec(
+        unit_id: u8,
+        address_min: Option<u16>,
+        address_max: Option<u16>,
+    ) -> GatewaySlaveSpec {
+        GatewaySlaveSpec {
+            unit_id,
+            name: "一号电表".to_string(),
+            address_min,
+            address_max,
+            ..GatewaySlaveSpec::default()
+        }
+    }
+
+    fn point_spec(
+        point_key: &str,
+        function_code: u8,
+        address: u16,
+        data_type: &str,
+    ) -> GatewayPointSpec {
+        GatewayPointSpec {
+            point_key: point_key.to_string(),
+            name: point_key.to_string(),
+            function_code,
+            address,
+            data_type: data_type.to_string(),
+            ..GatewayPointSpec::default()
+        }
+    }
+
+    fn create_point(slave_id: i64, point: GatewayPointSpec) -> AppResult<GatewayPointData> {
+        gateway_point_create(
+            GatewayPointCreatePayload {
+                operator_username: "admin".to_string(),
+                slave_id,
+                point,
+            },
+            None,
+        )
+    }
+
+    fn create_slave(gateway_id: i64, slave: GatewaySlaveSpec) -> AppResult<GatewaySlaveData> {
+        gateway_slave_create(
+            GatewaySlaveCreatePayload {
+                operator_username: "admin".to_string(),
+                gateway_id,
+                slave,
+            },
+            None,
+        )
+    }
+
+    /// 创建网关与地址范围为 100-199 的一号从站
+    fn bounded_slave(prefix: &str) -> (GatewayData, GatewaySlaveData) {
+        let gateway = create(tcp_spec(
+            &unique_code(prefix),
+            "192.168.1.100:502".parse().expect("address"),
+        ))
+        .expect("create gateway")
+        .data;
+        let slave = create_slave(gateway.id, slave_spec(1, Some(100), Some(199)))
+            .expect("create slave")
+            .data;
+        (gateway, slave)
+    }
+
+    #[test]
+    fn slave_table_validates_unit_ids_and_bounds() {
+        ensure_test_db_ready();
+        let (gateway, slave) = bounded_slave("gw_slaves");
+        assert_eq!(slave.gateway_id, gateway.id);
+        assert_eq!(
+            (slave.address_min, slave.address_max),
+            (Some(100), Some(199))
+        );
+        assert!(slave.enabled);
+
+        let slave_cases = [
+            (
+                slave_spec(1, None, None),
+                "unit id already exists on gateway",
+            ),
+            (
+                slave_spec(0, None, None),
+                "unitId must be between 1 and 247",
+            ),
+            (
+                slave_spec(248, None, None),
+                "unitId must be between 1 and 247",
+            ),
+            (
+                slave_spec(2, Some(10), Some(5)),
+                "addressMin must not exceed addressMax",
+            ),
+            (
+                GatewaySlaveSpec {
+                    name: " ".to_string(),
+                    ..slave_spec(2, None, None)
+                },
+                "name is required",
+            ),
+        ];
+        for (spec, message) in slave_cases {
+            assert_eq!(
+                create_slave(gateway.id, spec).expect_err("invalid slave"),
+                AppError::Validation(message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn point_table_derives_counts_and_orders_points() {
+        ensure_test_db_ready();
+        let (_gateway, slave) = bounded_slave("gw_points");
+        // 数量与字节序按数据类型推导，数据类型与字节序不区分大小写
+        let voltage = create_point(slave.id, point_spec("voltage", 3, 100, "F32"))
+            .expect("create f32 point")
+            .data;
+        assert_eq!((voltage.count, voltage.data_type.as_str()), (2, "f32"));
+        assert_eq!(
+            (voltage.byte_order.as_str(), voltage.access.as_str()),
+            ("abcd", "read")
+        );
+        let energy = create_point(
+            slave.id,
+            GatewayPointSpec {
+                byte_order: Some("CDAB".to_string()),
+                unit: Some(" kWh ".to_string()),
+                sort_order: Some(-1),
+                access: Some("read_write".to_string()),
+                ..point_spec("energy", 3, 102, "f64")
+            },
+        )
+        .expect("create f64 point")
+        .data;
+        assert_eq!((energy.count, energy.byte_order.as_str()), (4, "cdab"));
+        assert_eq!(energy.unit.as_deref(), Some("kWh"));
+        let serial = create_point(
+            slave.id,
+            GatewayPointSpec {
+                count: Some(8),
+                byte_order: Some("ba".to_string()),
+                ..point_spec("serial", 4, 190, "string")
+            },
+        )
+        .expect("create string point")
+        .data;
+        assert_eq!((serial.count, serial.byte_order.as_str()), (8, "ba"));
+        let relay = create_point(slave.id, point_spec("relay", 1, 150, "bool"))
+            .expect("create coil point")
+            .data;
+        assert_eq!((relay.count, relay.byte_order.as_str()), (1, "ab"));
+
+        let listed = gateway_point_list(
+            GatewayPointListPayload {
+                operator_username: "admin".to_string(),
+                slave_id: slave.id,
+            },
+            None,
+        )
+        .expect("list points")
+        .data;
+        let keys: Vec<_> = listed
+            .iter()
+            .map(|point| point.point_key.as_str())
+            .collect();
+        assert_eq!(keys, ["energy", "voltage", "relay", "serial"]);
+    }
+
+    #[test]
+    fn point_table_rejects_invalid_definitions() {
+        ensure_test_db_ready();
+        let (_gateway, slave) = bounded_slave("gw_point_invalid");
+        create_point(slave.id, point_spec("voltage", 3, 100, "f32")).expect("create point");
+        let point_cases = [
+            (
+                point_spec("voltage", 3, 110, "u16"),
+                "point key already exists on slave",
+            ),
+            (
+                point_spec("bad key", 3, 110, "u16"),
+                "pointKey must be at most 64 letters, digits, '_', '-' or '.'",
+            ),
+            (
+                point_spec("fc5", 5, 110, "u16"),
+                "functionCode must be one of 0, 1, 2, 3, 4",
+            ),
+            (
+                point_spec("int16", 3, 110, "int16"),
+                "dataType must be one of bool, u16, i16, u32, i32, f32, f64, string",
+            ),
+            (
+                point_spec("coil_u16", 1, 110, "u16"),
+                "dataType must be bool for functionCode 1 or 2",
+            ),
+            (
+                GatewayPointSpec {
+                    count: Some(1),
+                    ..point_spec("short", 3, 110, "u32")
+                },
+                "count 1 does not match data type u32",
+            ),
+            (
+                point_spec("text", 3, 110, "string"),
+                "count is required for dataType string",
+            ),
+            (
+                GatewayPointSpec {
+                    byte_order: Some("abcd".to_string()),
+                    ..point_spec("word", 3, 110, "i16")
+                },
+                "byte order abcd is not supported for data type i16",
+            ),
+            (
+                GatewayPointSpec {
+                    byte_order: Some("dcab".to_string()),
+                    ..point_spec("word", 3, 110, "i32")
+                },
+                "byteOrder must be one of ab, ba, abcd, cdab, badc, dcba",
+            ),
+            (
+                point_spec("low", 3, 99, "u16"),
+                "address range 99-99 is outside slave bounds 100-199",
+            ),
+            (
+                point_spec("tail", 3, 198, "f64"),
+                "address range 198-201 is outside slave bounds 100-199",
+            ),
+            (
+                GatewayPointSpec {
+                    access: Some("read_write".to_string()),
+                    ..point_spec("input", 4, 110, "u16")
+                },
+                "access read_write requires functionCode 1 or 3",
+            ),
+        ];
+        for (spec, message) in point_cases {
+            assert_eq!(
+                create_point(slave.id, spec).expect_err("invalid point"),
+                AppError::Validation(message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn slave_bounds_and_point_updates_keep_points_in_range() {
+        ensure_test_db_ready();
+        let (_gateway, slave) = bounded_slave("gw_point_bounds");
+        let voltage = create_point(slave.id, point_spec("voltage", 3, 100, "f32"))
+            .expect("create f32 point")
+            .data;
+        create_point(
+            slave.id,
+            GatewayPointSpec {
+                count: Some(8),
+                ..point_spec("serial", 4, 190, "string")
+            },
+        )
+        .expect("create string point");
+        // 收窄从站地址范围不得使已有点位越界
+        let update_slave = |slave_id, slave: GatewaySlaveSpec| {
+            gateway_slave_update(
+                GatewaySlaveUpdatePayload {
+                    operator_username: "admin".to_string(),
+                    slave_id,
+                    slave,
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            update_slave(slave.id, slave_spec(1, Some(100), Some(195))).expect_err("strand point"),
+            AppError::Validation(
+                "point serial: address range 190-197 is outside slave bounds 100-195".to_string()
+            )
+        );
+        let widened = update_slave(slave.id, slave_spec(1, None, Some(299)))
+            .expect("widen bounds")
+            .data;
+        assert_eq!(
+            (widened.address_min, widened.address_max),
+            (None, Some(299))
+        );
+
+        let updated = gateway_point_update(
+            GatewayPointUpdatePayload {
+                operator_username: "admin".to_string(),
+                point_id: voltage.id,
+                point: GatewayPointSpec {
+                    byte_order: Some("badc".to_string()),
+                    ..point_spec("voltage", 4, 0, "i32")
+                },
+            },
+            None,
+        )
+        .expect("update point")
+        .data;
+        assert_eq!(
+            (
+                updated.function_code,
+                updated.address,
+                updated.data_type.as_str()
+            ),
+            (4, 0, "i32")
+        );
+        assert_eq!(updated.byte_order, "badc");
+    }
+
+    #[test]
+    fn point_and_slave_deletes_cascade_and_require_permissions() {
+        ensure_test_db_ready();
+        let (gateway, slave) = bounded_slave("gw_point_delete");
+        let relay = create_point(slave.id, point_spec("relay", 1, 150, "bool"))
+            .expect("create coil point")
+            .data;
+        let err = gateway_slave_list(
+            GatewaySlaveListPayload {
+                operator_username: "common".to_string(),
+                gateway_id: gateway.id,
+            },
+            None,
+        )
+        .expect_err("forbidden list");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+        let err = gateway_point_delete(
+            GatewayPointDeletePayload {
+                operator_username: "common".to_string(),
+                point_id: relay.id,
+            },
+            None,
+        )
+        .expect_err("forbidden delete");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+
+        assert!(
+            gateway_point_delete(
+                GatewayPointDeletePayload {
+                    operator_username: "admin".to_string(),
+                    point_id: relay.id,
+                },
+                None,
+            )
+            .expect("delete point")
+            .data
+        );
+
+        // 删除从站时点位一并删除
+        assert!(
+            gateway_slave_delete(
+                GatewaySlaveDeletePayload {
+                    operator_username: "admin".to_string(),
+                    slave_id: slave.id,
+                },
+                None,
+            )
+            .expect("delete slave")
+            .data
+        );
+        assert_eq!(
+            create_point(slave.id, point_spec("orphan", 3, 0, "u16")).expect_err("slave deleted"),
+            AppError::Validation("slave not found".to_string())
+        );
+        let slaves = gateway_slave_list(
+            GatewaySlaveListPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway.id,
+            },
+            None,
+        )
+        .expect("list slaves")
+        .data;
+        assert!(slaves.is_empty());
+    }
+
+    /// 在模拟从站上建立网关与 power、offset、model、total、relay 五个点位
+    fn typed_points() -> (TcpSimulator, String, [GatewayPointData; 5]) {
+        let mut memory = SlaveMemory::new(20);
+        // F32 123.456（CDAB）、I16 -2、字符串 "AB-1"、U32 0x12345678（ABCD）
+        memory.holding_registers[..5].copy_from_slice(&[0xE979, 0x42F6, 0xFFFE, 0x4142, 0x2D31]);
+        memory.input_registers[..2].copy_from_slice(&[0x1234, 0x5678]);
+        memory.coils[4] = true;
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let code = unique_code("gw_point_test");
+        let gateway = create(tcp_spec(&code, simulator.local_addr()))
+            .expect("create gateway")
+            .data;
+        let slave = create_slave(gateway.id, slave_spec(1, None, None))
+            .expect("create slave")
+            .data;
+        let writable = |spec: GatewayPointSpec| GatewayPointSpec {
+            access: Some("read_write".to_string()),
+            ..spec
+        };
+        let power = create_point(
+            slave.id,
+            writable(GatewayPointSpec {
+                byte_order: Some("cdab".to_string()),
+                ..point_spec("power", 3, 0, "f32")
+            }),
+        )
+        .expect("create f32 point")
+        .data;
+        let offset = create_point(slave.id, writable(point_spec("offset", 3, 2, "i16")))
+            .expect("create i16 point")
+            .data;
+        let model = create_point(
+            slave.id,
+            GatewayPointSpec {
+                count: Some(2),
+                ..point_spec("model", 3, 3, "string")
+            },
+        )
+        .expect("create string point")
+        .data;
+        let total = create_point(slave.id, point_spec("total", 4, 0, "u32"))
+            .expect("create u32 point")
+            .data;
+        let relay = create_point(slave.id, writable(point_spec("relay", 1, 4, "bool")))
+            .expect("create coil point")
+            .data;
+        (simulator, code, [power, offset, model, total, relay])
+    }
+
+    fn read_point(point_id: i64) -> AppResult<GatewayPointReadData> {
+        gateway_point_read(
+            GatewayPointReadPayload {
+                operator_username: "admin".to_string(),
+                point_id,
+            },
+            None,
+        )
+    }
+
+    fn write_point(
+        operator_username: &str,
+        point_id: i64,
+        value: serde_json::Value,
+    ) -> AppResult<GatewayPointWriteData> {
+        gateway_point_write(
+            GatewayPointWritePayload {
+                operator_username: operator_username.to_string(),
+                point_id,
+                value,
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn point_read_tests_decode_values_over_a_temporary_session() {
+        ensure_test_db_ready();
+        let (simulator, _code, [power, offset, model, total, relay]) = typed_points();
+        // 网关未建立长连接：以保存的配置建立临时会话
+        let result = read_point(power.id).expect("read f32").data;
+        assert_eq!(result.session, "temporary");
+        assert_eq!((result.function_code, result.count), (3, 2));
+        assert_eq!(result.registers, Some(vec![0xE979, 0x42F6]));
+        let value = result.value.as_f64().expect("float value");
+        assert!((value - 123.456).abs() < 1e-4, "{value}");
+        assert_eq!(
+            read_point(offset.id).expect("read i16").data.value,
+            json!(-2)
+        );
+        assert_eq!(
+            read_point(model.id).expect("read string").data.value,
+            json!("AB-1")
+        );
+        let result = read_point(total.id).expect("read u32").data;
+        assert_eq!(
+            (result.function_code, result.value),
+            (4, json!(0x1234_5678))
+        );
+        let result = read_point(relay.id).expect("read coil").data;
+        assert_eq!((result.bits, result.registers), (Some(vec![true]), None));
+        assert_eq!(result.value, json!(true));
+        assert_eq!(
+            simulator.memory().lock().expect("memory").holding_registers[..2],
+            [0xE979, 0x42F6]
+        );
+    }
+
+    #[test]
+    fn point_write_tests_encode_values_over_the_shared_connection() {
+        ensure_test_db_ready();
+        let (simulator, code, [power, offset, _model, _total, relay]) = typed_points();
+        // 网关已建立长连接：复用长连接
+        modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: code.clone(),
+                host: simulator.local_addr().ip().to_string(),
+                port: Some(simulator.local_addr().port()),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect("production connect");
+        let result = write_point("admin", power.id, json!(-1.5))
+            .expect("write f32")
+            .data;
+        assert_eq!(result.session, "shared");
+        assert_eq!(result.function_code, 16);
+        assert_eq!(result.registers, Some(vec![0x0000, 0xBFC0]));
+        assert_eq!(
+            read_point(power.id).expect("read back").data.value,
+            json!(-1.5)
+        );
+        let result = write_point("admin", offset.id, json!(-300))
+            .expect("write i16")
+            .data;
+        assert_eq!(
+            (result.function_code, result.registers),
+            (6, Some(vec![0xFED4]))
+        );
+        let result = write_point("admin", relay.id, json!(false))
+            .expect("write coil")
+            .data;
+        assert_eq!((result.function_code, result.bits), (5, Some(vec![false])));
+        {
+            let memory = simulator.memory();
+            let memory = memory.lock().expect("memory");
+            assert_eq!(memory.holding_registers[..3], [0x0000, 0xBFC0, 0xFED4]);
+            assert!(!memory.coils[4]);
+        }
+        modbus_disconnect(
+            ModbusGatewayPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: code,
+            },
+            None,
+        )
+        .expect("disconnect");
+    }
+
+    #[test]
+    fn point_write_tests_reject_invalid_values_and_operators() {
+        ensure_test_db_ready();
+        let (_simulator, _code, [power, offset, _model, total, relay]) = typed_points();
+        let cases = [
+            ("admin", total.id, json!(1), "point is read-only"),
+            (
+                "admin",
+                offset.id,
+                json!(40_000),
+                "value out of range for data type i16",
+            ),
+            (
+                "admin",
+                power.id,
+                json!("1.5"),
+                "value does not match data type f32",
+            ),
+            (
+                "admin",
+                relay.id,
+                json!(1),
+                "value does not match data type bool",
+            ),
+            (
+                "admin",
+                power.id,
+                json!(null),
+                "value must be a boolean, number or string",
+            ),
+            ("admin", -1, json!(1), "point not found"),
+            (
+                "common",
+                power.id,
+                json!(1.0),
+                "forbidden: control issue required",
+            ),
+        ];
+        for (operator_username, point_id, value, message) in cases {
+            assert_eq!(
+                write_point(operator_username, point_id, value).expect_err("invalid write"),
+                AppError::Validation(message.to_string())
+            );
+        }
+        assert_eq!(
+            gateway_point_read(
+                GatewayPointReadPayload {
+                    operator_username: "common".to_string(),
+                    point_id: power.id,
+                },
+                None,
+            )
+            .expect_err("forbidden read"),
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+    }
+
+    fn with_transform(spec: GatewayPointSpec, transform: serde_json::Value) -> GatewayPointSpec {
+        GatewayPointSpec {
+            transform: Some(serde_json::from_value(transform).expect("transform")),
+            ..spec
+        }
+    }
+
+    /// 在模拟从站上建立带变换的 voltage、state、setpoint、valve 四个点位
+    fn transform_points() -> (TcpSimulator, [GatewayPointData; 4]) {
+        let mut memory = SlaveMemory::new(10);
+        // 电压 2301（0.1 V）、状态字 0x8011（bit 4–5 = 1）、温度设定 140
+        memory.holding_registers[..3].copy_from_slice(&[2301, 0x8011, 140]);
+        let simulator =
+            db::block_on(TcpSimulator::start("127.0.0.1:0", memory)).expect("start simulator");
+        let code = unique_code("gw_point_transform");
+        let gateway = create(tcp_spec(&code, simulator.local_addr()))
+            .expect("create gateway")
+            .data;
+        let slave = create_slave(gateway.id, slave_spec(1, None, None))
+            .expect("create slave")
+            .data;
+        let voltage = create_point(
+            slave.id,
+            with_transform(
+                GatewayPointSpec {
+                    unit: Some("V".to_string()),
+                    ..point_spec("voltage", 3, 0, "u16")
+                },
+                json!({ "scale": 0.1, "deadband": 0.5, "deadbandMode": " Percent ", "sourceUnit": "" }),
+            ),
+        )
+        .expect("create scaled point")
+        .data;
+        let writable = |spec: GatewayPointSpec| GatewayPointSpec {
+            access: Some("read_write".to_string()),
+            ..spec
+        };
+        let state = create_point(
+            slave.id,
+            writable(with_transform(
+                point_spec("state", 3, 1, "u16"),
+                json!({
+                    "bitOffset": 4,
+                    "bitLength": 2,
+                    "enumMap": { "0": "stopped", "1": "running", "2": "fault" }
+                }),
+            )),
+        )
+        .expect("create status point")
+        .data;
+        let setpoint = create_point(
+            slave.id,
+            writable(with_transform(
+                point_spec("setpoint", 3, 2, "u16"),
+                json!({ "scale": 0.5, "offset": -20, "clampMin": 0, "clampMax": 100 }),
+            )),
+        )
+        .expect("create setpoint")
+        .data;
+        let valve = create_point(
+            slave.id,
+            writable(with_transform(
+                point_spec("valve", 1, 0, "bool"),
+                json!({ "enumMap": { "0": "closed", "1": "open" } }),
+            )),
+        )
+        .expect("create valve")
+        .data;
+        (simulator, [voltage, state, setpoint, valve])
+    }
+
+    #[test]
+    fn point_transforms_reject_invalid_definitions() {
+        ensure_test_db_ready();
+        let gateway = create(tcp_spec(
+            &unique_code("gw_transform_invalid"),
+            "192.168.1.100:502".parse().expect("address"),
+        ))
+        .expect("create gateway")
+        .data;
+        let slave = create_slave(gateway.id, slave_spec(1, None, None))
+            .expect("create slave")
+            .data;
+
+        let cases = [
+            (
+                GatewayPointSpec {
+                    count: Some(4),
+                    ..point_spec("name", 3, 0, "string")
+                },
+                json!({ "scale": 2 }),
+                "transform: not supported for dataType string",
+            ),
+            (
+                point_spec("relay", 1, 0, "bool"),
+                json!({ "scale": 2 }),
+                "transform: only enumMap is supported for dataType bool",
+            ),
+            (
+                point_spec("power", 3, 0, "f32"),
+                json!({ "bitOffset": 1 }),
+                "transform: bitOffset requires an integer dataType",
+            ),
+            (
+                point_spec("status", 3, 0, "u16"),
+                json!({ "bitOffset": 12, "bitLength": 8 }),
+                "transform: bitLength must be between 1 and 4",
+            ),
+            (
+                point_spec("status", 3, 0, "u16"),
+                json!({ "enumMap": { "0": "stopped" }, "scale": 2 }),
+                "transform: enumMap cannot be combined with scale, offset, sourceUnit, clamp or deadband",
+            ),
+            (
+                point_spec("status", 3, 0, "u16"),
+                json!({ "enumMap": { "a": "stopped" } }),
+                "transform: enumMap key a must be an integer",
+            ),
+            (
+                point_spec("voltage", 3, 0, "u16"),
+                json!({ "scale": 0 }),
+                "transform: scale must be a non-zero number",
+            ),
+            (
+                point_spec("voltage", 3, 0, "u16"),
+                json!({ "sourceUnit": "°F" }),
+                "transform: unit is required for sourceUnit",
+            ),
+            (
+                GatewayPointSpec {
+                    unit: Some("°C".to_string()),
+                    ..point_spec("voltage", 3, 0, "u16")
+                },
+                json!({ "sourceUnit": "kWh" }),
+                "transform: cannot convert kWh to °C",
+            ),
+            (
+                point_spec("voltage", 3, 0, "u16"),
+                json!({ "clampMin": 10, "clampMax": 0 }),
+                "transform: clampMin must not exceed clampMax",
+            ),
+            (
+                point_spec("voltage", 3, 0, "u16"),
+                json!({ "deadband": 1, "deadbandMode": "relative" }),
+                "transform: deadbandMode must be one of absolute, percent",
+            ),
+        ];
+        for (spec, transform, message) in cases {
+            assert_eq!(
+                create_point(slave.id, with_transform(spec, transform))
+                    .expect_err("invalid transform"),
+                AppError::Validation(message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn point_transforms_convert_reads_to_engineering_values() {
+        ensure_test_db_ready();
+        let (_simulator, [voltage, state, setpoint, valve]) = transform_points();
+        assert_eq!(
+            serde_json::to_value(&voltage.transform).expect("serialize transform"),
+            json!({ "scale": 0.1, "deadband": 0.5, "deadbandMode": "percent" })
+        );
+
+        // 读测试返回原始值与工程值
+        let result = read_point(voltage.id).expect("read voltage").data;
+        assert_eq!(result.raw_value, json!(2301));
+        let volts = result.value.as_f64().expect("float value");
+        assert!((volts - 230.1).abs() < 1e-9, "{volts}");
+        let result = read_point(state.id).expect("read state").data;
+        assert_eq!(
+            (result.raw_value, result.value),
+            (json!(0x8011), json!("running"))
+        );
+        let result = read_point(setpoint.id).expect("read setpoint").data;
+        assert_eq!(result.value, json!(50.0));
+        assert_eq!(
+            read_point(valve.id).expect("read valve").data.value,
+            json!("closed")
+        );
+    }
+
+    #[test]
+    fn point_transforms_invert_writes_and_reject_invalid_values() {
+        ensure_test_db_ready();
+        let (simulator, [_voltage, state, setpoint, valve]) = transform_points();
+        // 写测试反变换：设定值按缩放与偏移还原，位域只替换对应位，枚举文本还原为原始值
+        let result = write_point("admin", setpoint.id, json!(62.5))
+            .expect("write setpoint")
+            .data;
+        assert_eq!(
+            (result.raw_value, result.registers),
+            (json!(165), Some(vec![165]))
+        );
+        let result = write_point("admin", state.id, json!("fault"))
+            .expect("write state")
+            .data;
+        assert_eq!(
+            (result.raw_value, result.registers),
+            (json!(2), Some(vec![0x8021]))
+        );
+        let result = write_point("admin", valve.id, json!("open"))
+            .expect("write valve")
+            .data;
+        assert_eq!(
+            (result.raw_value, result.bits),
+            (json!(true), Some(vec![true]))
+        );
+        {
+            let memory = simulator.memory();
+            let memory = memory.lock().expect("memory");
+            assert_eq!(memory.holding_registers[1..3], [0x8021, 165]);
+            assert!(memory.coils[0]);
+        }
+        assert_eq!(
+            read_point(state.id).expect("read back").data.value,
+            json!("fault")
+        );
+
+        let cases = [
+            (setpoint.id, json!(120), "value must be at most 100"),
+            (setpoint.id, json!(-1), "value must be at least 0"),
+            (setpoint.id, json!("high"), "value must be a number"),
+            (
+                state.id,
+                json!("idle"),
+                "value must be one of stopped, running, fault",
+            ),
+            (valve.id, json!(true), "value must be one of closed, open"),
+        ];
+        for (point_id, value, message) in cases {
+            assert_eq!(
+                write_point("admin", point_id, value).expect_err("invalid write"),
+                AppError::Validation(message.to_string())
+            );
+        }
+    }
+
+    fn virtual_spec(point_key: &str, expression: &str) -> GatewayPointSpec {
+        GatewayPointSpec {
+            expression: Some(expression.to_string()),
+            ..point_spec(point_key, 0, 0, "")
+        }
+    }
+
+    /// 建立电表（voltage、current、serial、state）与冷机（cooling）两个从站
+    fn virtual_sources() -> (GatewaySlaveData, GatewaySlaveData, GatewayPointData) {
+        let gateway = create(tcp_spec(
+            &unique_code("gw_virtual"),
+            "192.168.1.100:502".parse().expect("address"),
+        ))
+        .expect("create gateway")
+        .data;
+        let meter = create_slave(gateway.id, slave_spec(1, Some(100), Some(199)))
+            .expect("create meter")
+            .data;
+        let chiller = create_slave(gateway.id, slave_spec(2, Some(100), Some(199)))
+            .expect("create chiller")
+            .data;
+        let voltage = create_point(meter.id, point_spec("voltage", 3, 100, "u16"))
+            .expect("voltage")
+            .data;
+        create_point(meter.id, point_spec("current", 3, 101, "u16")).expect("current");
+        create_point(
+            meter.id,
+            GatewayPointSpec {
+                count: Some(4),
+                ..point_spec("serial", 3, 102, "string")
+            },
+        )
+        .expect("serial");
+        create_point(
+            meter.id,
+            GatewayPointSpec {
+                transform: Some(
+                    serde_json::from_value(json!({ "enumMap": { "0": "off", "1": "on" } }))
+                        .expect("transform"),
+                ),
+                ..point_spec("state", 3, 106, "u16")
+            },
+        )
+        .expect("state");
+        create_point(chiller.id, point_spec("cooling", 4, 100, "u16")).expect("cooling");
+        (meter, chiller, voltage)
+    }
+
+    #[test]
+    fn virtual_points_store_fixed_layout_and_cross_slave_references() {
+        ensure_test_db_ready();
+        let (meter, chiller, _voltage) = virtual_sources();
+        // 虚拟点位不受从站地址范围限制，起始地址、数量与字节序保存为固定值
+        let power = create_point(
+            meter.id,
+            GatewayPointSpec {
+                unit: Some("W".to_string()),
+                ..virtual_spec("power", "  voltage * [current] ")
+            },
+        )
+        .expect("create virtual point")
+        .data;
+        assert_eq!(
+            (
+                power.function_code,
+                power.address,
+                power.count,
+                power.data_type.as_str(),
+                power.byte_order.as_str(),
+                power.access.as_str(),
+                power.expression.as_deref()
+            ),
+            (0, 0, 1, "f64", "abcd", "read", Some("voltage * [current]"))
+        );
+        let cop = create_point(
+            chiller.id,
+            virtual_spec("cop", "cooling / max([1:power], 1)"),
+        )
+        .expect("cross-slave reference")
+        .data;
+        assert_eq!(cop.slave_id, chiller.id);
+    }
+
+    #[test]
+    fn virtual_points_reject_invalid_expressions() {
+        ensure_test_db_ready();
+        let (meter, _chiller, _voltage) = virtual_sources();
+        let cases = [
+            (
+                point_spec("calc", 0, 0, "f64"),
+                "expression is required for functionCode 0",
+            ),
+            (
+                GatewayPointSpec {
+                    expression: Some("voltage".to_string()),
+                    ..point_spec("calc", 3, 100, "u16")
+                },
+                "expression requires functionCode 0",
+            ),
+            (
+                point_spec("calc", 5, 100, "u16"),
+                "functionCode must be one of 0, 1, 2, 3, 4",
+            ),
+            (
+                virtual_spec("calc", "voltage *"),
+                "expression: unexpected end of expression",
+            ),
+            (
+                virtual_spec("calc", "sqrt(voltage, 2)"),
+                "expression: function sqrt expects 1 argument",
+            ),
+            (
+                virtual_spec("calc", "voltag + 1"),
+                "expression: unknown point [voltag]",
+            ),
+            (
+                virtual_spec("calc", "[2:voltage]"),
+                "expression: unknown point [2:voltage]",
+            ),
+            (
+                virtual_spec("calc", "serial + 1"),
+                "expression: point [serial] is not numeric",
+            ),
+            (
+                virtual_spec("calc", "state * 2"),
+                "expression: point [state] is not numeric",
+            ),
+            (
+                GatewayPointSpec {
+                    data_type: "u16".to_string(),
+                    ..virtual_spec("calc", "voltage")
+                },
+                "dataType must be f64 or bool for virtual points",
+            ),
+            (
+                GatewayPointSpec {
+                    access: Some("read_write".to_string()),
+                    ..virtual_spec("calc", "voltage")
+                },
+                "virtual points are read-only",
+            ),
+            (
+                virtual_spec("calc", "calc + 1"),
+                "expression: circular reference calc -> calc",
+            ),
+        ];
+        for (spec, message) in cases {
+            assert_eq!(
+                create_point(meter.id, spec).expect_err("invalid virtual point"),
+                AppError::Validation(message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn virtual_point_references_guard_updates_and_deletes() {
+        ensure_test_db_ready();
+        let (meter, chiller, voltage) = virtual_sources();
+        let power = create_point(meter.id, virtual_spec("power", "voltage * current"))
+            .expect("create virtual point")
+            .data;
+        let cop = create_point(
+            chiller.id,
+            virtual_spec("cop", "cooling / max([1:power], 1)"),
+        )
+        .expect("cross-slave reference")
+        .data;
+        let update = |point_id, point| {
+            gateway_point_update(
+                GatewayPointUpdatePayload {
+                    operator_username: "admin".to_string(),
+                    point_id,
+                    point,
+                },
+                None,
+            )
+        };
+        let delete = |point_id| {
+            gateway_point_delete(
+                GatewayPointDeletePayload {
+                    operator_username: "admin".to_string(),
+                    point_id,
+                },
+                None,
+            )
+        };
+
+        // 虚拟点位之间的循环引用
+        let doubled = create_point(meter.id, virtual_spec("doubled", "power * 2"))
+            .expect("virtual reference")
+            .data;
+        assert_eq!(
+            update(power.id, virtual_spec("power", "doubled + voltage")).expect_err("cycle"),
+            AppError::Validation(
+                "expression: circular reference power -> doubled -> power".to_string()
+            )
+        );
+
+        // 被引用的点位不能删除或改名，改为不可引用的类型同样拒绝
+        let referenced =
+            AppError::Validation("point is referenced by virtual point power".to_string());
+        assert_eq!(
+            delete(voltage.id).expect_err("referenced delete"),
+            referenced
+        );
+        assert_eq!(
+            update(voltage.id, point_spec("voltage_a", 3, 100, "u16")).expect_err("rename"),
+            referenced
+        );
+        assert_eq!(
+            update(
+                voltage.id,
+                GatewayPointSpec {
+                    count: Some(2),
+                    ..point_spec("voltage", 3, 100, "string")
+                }
+            )
+            .expect_err("non-numeric"),
+            referenced
+        );
+        assert_eq!(
+            delete(power.id).expect_err("virtual referenced by virtual"),
+            AppError::Validation("point is referenced by virtual point cop".to_string())
+        );
+
+        // 虚拟点位不能执行读测试
+        assert_eq!(
+            gateway_point_read(
+                GatewayPointReadPayload {
+                    operator_username: "admin".to_string(),
+                    point_id: power.id,
+                },
+                None,
+            )
+            .expect_err("virtual read"),
+            AppError::Validation("virtual point cannot be read from the device".to_string())
+        );
+
+        // 移除引用后可删除
+        assert!(delete(doubled.id).expect("delete doubled").data);
+        assert!(delete(cop.id).expect("delete cop").data);
+        assert!(delete(power.id).expect("delete power").data);
+        assert!(delete(voltage.id).expect("delete voltage").data);
+    }
+}
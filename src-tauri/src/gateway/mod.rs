@@ -0,0 +1,16 @@
+//! 通信网关模块入口
+//!
+//! 本模块维护 Modbus 网关的连接配置：
+//! - 网关编码、名称、传输方式（TCP 网关的地址与端口，串口网关的串口参数）、帧格式与超时
+//! - 网关的增删改查，网关编码作为 Modbus 长连接的网关标识
+//! - 连接测试：以独立的临时会话建连并可选探测读取，返回延迟与错误类别，不影响生产连接
+//! - 从站与点位表：网关下的从站（单元号与地址范围）及从站的寄存器点位（功能码、地址、数量、数据类型与字节序）
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 网关配置、连接测试与从站点位结构
+pub mod models;
+// 公开服务模块 - 网关校验、连接测试与从站点位校验业务逻辑
+pub mod services;
+// 公开仓储模块 - 网关配置与从站点位的 SeaORM 读写
+pub mod repository;
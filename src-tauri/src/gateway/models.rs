@@ -0,0 +1,628 @@
+//! 通信网关模块数据模型
+//!
+//! 本模块定义网关配置、从站与点位表（含点位值变换配置）的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入有序映射（枚举映射按原始值排序）
+use std::collections::BTreeMap;
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+/// 网关存储记录
+///
+/// 与 gateways 表对应；TCP 网关的串口字段为 None，串口网关的 host/port 为 None
+#[derive(Debug, Clone, Default)]
+pub struct GatewayRecord {
+    pub id: i64,             // 网关 ID
+    pub input: GatewayInput, // 可编辑字段
+    pub created_at: i64,     // 创建时间戳（毫秒）
+    pub updated_at: i64,     // 更新时间戳（毫秒）
+    pub created_by: String,  // 创建人用户名
+}
+
+/// 网关写入参数（创建与更新共用的可编辑字段，已完成规范化）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GatewayInput {
+    pub code: String,                  // 网关编码
+    pub name: String,                  // 网关名称
+    pub connection: GatewayConnection, // 连接参数
+    pub poll_interval_ms: u64,         // 轮询周期（毫秒）
+    pub poll_max_gap: u16,             // 合并读取允许跨越的最大空闲地址数
+    pub enabled: bool,                 // 是否启用
+    pub remark: Option<String>,        // 备注
+}
+
+/// 网关连接参数（已完成规范化）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GatewayConnection {
+    pub transport: String,           // 传输方式（tcp / serial）
+    pub host: Option<String>,        // IP 地址或主机名（TCP 网关）
+    pub port: Option<u16>,           // TCP 端口（TCP 网关）
+    pub serial_port: Option<String>, // 串口路径（串口网关）
+    pub baud_rate: Option<u32>,      // 波特率（串口网关）
+    pub data_bits: Option<u8>,       // 数据位（串口网关）
+    pub parity: Option<String>,      // 校验位（串口网关）
+    pub stop_bits: Option<u8>,       // 停止位（串口网关）
+    pub framing: String,             // 帧格式（mbap / rtu / ascii）
+    pub connect_timeout_ms: u64,     // 建连超时（毫秒）
+    pub request_timeout_ms: u64,     // 请求超时（毫秒）
+}
+
+// 网关定义（创建/更新请求与连接测试使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySpec {
+    /// 网关编码（全局唯一，作为长连接的网关标识）
+    pub code: String,
+    /// 网关名称
+    pub name: String,
+    /// 传输方式（tcp / serial，默认 tcp）
+    pub transport: Option<String>,
+    /// IP 地址或主机名（TCP 网关）
+    pub host: String,
+    /// TCP 端口（TCP 网关，默认 502）
+    pub port: Option<u16>,
+    /// 串口路径（串口网关）
+    pub serial_port: String,
+    /// 波特率（串口网关，默认 9600）
+    pub baud_rate: Option<u32>,
+    /// 数据位（串口网关，默认 8）
+    pub data_bits: Option<u8>,
+    /// 校验位（串口网关，none / even / odd，默认 none）
+    pub parity: Option<String>,
+    /// 停止位（串口网关，默认 1）
+    pub stop_bits: Option<u8>,
+    /// 帧格式（TCP 网关 mbap / rtu / ascii，默认 mbap；串口网关 rtu / ascii，默认 rtu）
+    pub framing: Option<String>,
+    /// 建连超时（毫秒，默认 3000）
+    pub connect_timeout_ms: Option<u64>,
+    /// 请求超时（毫秒，默认 1000）
+    pub request_timeout_ms: Option<u64>,
+    /// 轮询周期（毫秒，100–3600000，默认 1000；从站可单独配置）
+    pub poll_interval_ms: Option<u64>,
+    /// 合并读取允许跨越的最大空闲地址数（0–125，默认 0，只合并连续或重叠的地址）
+    pub poll_max_gap: Option<u16>,
+    /// 是否启用（默认启用）
+    pub enabled: Option<bool>,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 连接测试的探测读取
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayProbeSpec {
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 寄存器类型（coil / discrete_input / holding_register / input_register）
+    pub register_type: String,
+    /// 起始地址（0 起始）
+    pub address: u16,
+    /// 读取数量（默认 1）
+    pub count: Option<u16>,
+}
+
+// 网关列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 关键字（匹配编码、名称、地址与串口路径，不区分大小写）
+    pub keyword: Option<String>,
+}
+
+// 查询单个网关请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayGetPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+}
+
+// 创建网关请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关定义
+    pub gateway: GatewaySpec,
+}
+
+// 更新网关请求体（整体替换网关字段）
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+    /// 网关定义
+    pub gateway: GatewaySpec,
+}
+
+// 删除网关请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+}
+
+// 网关连接测试请求体（测试已保存的网关，或测试尚未保存的网关定义）
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayTestPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 已保存的网关 ID（与 gateway 二选一）
+    pub gateway_id: Option<i64>,
+    /// 网关定义（仅使用连接参数，编码与名称可为空）
+    pub gateway: Option<GatewaySpec>,
+    /// 建连成功后的探测读取（为空时只测试建连）
+    pub probe: Option<GatewayProbeSpec>,
+}
+
+// 网关响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayData {
+    /// 网关 ID
+    pub id: i64,
+    /// 网关编码
+    pub code: String,
+    /// 网关名称
+    pub name: String,
+    /// 传输方式（tcp / serial）
+    pub transport: String,
+    /// IP 地址或主机名
+    pub host: Option<String>,
+    /// TCP 端口
+    pub port: Option<u16>,
+    /// 串口路径
+    pub serial_port: Option<String>,
+    /// 波特率
+    pub baud_rate: Option<u32>,
+    /// 数据位
+    pub data_bits: Option<u8>,
+    /// 校验位
+    pub parity: Option<String>,
+    /// 停止位
+    pub stop_bits: Option<u8>,
+    /// 帧格式
+    pub framing: String,
+    /// 建连超时（毫秒）
+    pub connect_timeout_ms: u64,
+    /// 请求超时（毫秒）
+    pub request_timeout_ms: u64,
+    /// 轮询周期（毫秒）
+    pub poll_interval_ms: u64,
+    /// 合并读取允许跨越的最大空闲地址数
+    pub poll_max_gap: u16,
+    /// 是否启用
+    pub enabled: bool,
+    /// 备注
+    pub remark: Option<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+    /// 创建人
+    pub created_by: String,
+}
+
+// 网关连接测试响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayTestData {
+    /// 是否成功（建连成功且探测读取成功）
+    pub success: bool,
+    /// 传输方式（tcp / rtu_over_tcp / ascii_over_tcp / rtu / ascii）
+    pub transport: String,
+    /// 连接目标（host:port 或串口参数）
+    pub target: String,
+    /// 建连耗时（毫秒，建连失败时为 None）
+    pub connect_latency_ms: Option<u64>,
+    /// 探测读取耗时（毫秒，未探测或建连失败时为 None）
+    pub probe_latency_ms: Option<u64>,
+    /// 总耗时（毫秒）
+    pub latency_ms: u64,
+    /// 错误类别（refused / timeout / exception / connect / io / protocol）
+    pub error_class: Option<String>,
+    /// 错误信息
+    pub error: Option<String>,
+    /// 探测读取的线圈或离散输入值
+    pub bits: Option<Vec<bool>>,
+    /// 探测读取的寄存器值
+    pub registers: Option<Vec<u16>>,
+    /// 测试时间戳（毫秒）
+    pub tested_at: i64,
+}
+
+/// 从站存储记录
+#[derive(Debug, Clone, Default)]
+pub struct GatewaySlaveRecord {
+    pub id: i64,                  // 从站 ID
+    pub gateway_id: i64,          // 所属网关 ID
+    pub input: GatewaySlaveInput, // 可编辑字段
+    pub created_at: i64,          // 创建时间戳（毫秒）
+    pub updated_at: i64,          // 更新时间戳（毫秒）
+}
+
+/// 从站写入参数（已完成校验）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GatewaySlaveInput {
+    pub unit_id: u8,                   // 从站单元号（1–247）
+    pub name: String,                  // 从站名称
+    pub address_min: Option<u16>,      // 点位地址下限（含）
+    pub address_max: Option<u16>,      // 点位地址上限（含）
+    pub poll_interval_ms: Option<u64>, // 从站轮询周期（毫秒，为空沿用网关）
+    pub enabled: bool,                 // 是否启用
+    pub remark: Option<String>,        // 备注
+}
+
+/// 点位存储记录
+#[derive(Debug, Clone, Default)]
+pub struct GatewayPointRecord {
+    pub id: i64,                  // 点位 ID
+    pub slave_id: i64,            // 所属从站 ID
+    pub input: GatewayPointInput, // 可编辑字段
+    pub created_at: i64,          // 创建时间戳（毫秒）
+    pub updated_at: i64,          // 更新时间戳（毫秒）
+}
+
+/// 点位写入参数（已完成校验与规范化）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatewayPointInput {
+    pub point_key: String,             // 点位标识（从站内唯一）
+    pub name: String,                  // 点位名称
+    pub function_code: u8,             // 读取功能码（1 / 2 / 3 / 4，0 为虚拟点位）
+    pub address: u16,                  // 起始地址
+    pub count: u16,                    // 寄存器（或线圈）数量
+    pub data_type: String,             // 数据类型（小写）
+    pub byte_order: String,            // 字节序（小写）
+    pub access: String,                // 访问方式（read / read_write）
+    pub unit: Option<String>,          // 工程单位
+    pub sort_order: i32,               // 排序号
+    pub transform: PointTransformSpec, // 值变换配置
+    pub expression: Option<String>,    // 虚拟点位公式（物理点位为空）
+}
+
+// 点位值变换配置（全部为空表示不变换）
+//
+// 读取时依次执行：位域提取 → 枚举映射，或缩放与偏移 → 单位换算 → 限幅；写入时按相反顺序反变换
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PointTransformSpec {
+    /// 缩放系数（工程值 = 原始值 * scale + offset，默认 1，不能为 0）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    /// 偏移量（默认 0）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+    /// 位域起始位（0 为最低位，仅限整数类型；指定 bitLength 时默认 0）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bit_offset: Option<u8>,
+    /// 位域长度（指定 bitOffset 时默认 1）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bit_length: Option<u8>,
+    /// 枚举映射（原始整数值 → 文本，如 `{"0": "stopped", "1": "running"}`；不可与缩放、单位换算、限幅或死区同时使用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_map: Option<BTreeMap<String, String>>,
+    /// 缩放后的源单位（换算为点位的工程单位，两者须为同一物理量）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_unit: Option<String>,
+    /// 限幅下限（读取时截断，写入时超出范围拒绝）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clamp_min: Option<f64>,
+    /// 限幅上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clamp_max: Option<f64>,
+    /// 死区（与最近一次上报值之差不超过死区的变化不上报，仅作用于采集）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadband: Option<f64>,
+    /// 死区方式（absolute 绝对值 / percent 最近一次上报值的百分比，默认 absolute）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadband_mode: Option<String>,
+}
+
+// 从站定义（创建/更新请求使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySlaveSpec {
+    /// 从站单元号（1–247）
+    pub unit_id: u8,
+    /// 从站名称
+    pub name: String,
+    /// 点位地址下限（含，为空表示不限制）
+    pub address_min: Option<u16>,
+    /// 点位地址上限（含，为空表示不限制）
+    pub address_max: Option<u16>,
+    /// 从站轮询周期（毫秒，100–3600000，为空沿用网关的轮询周期）
+    pub poll_interval_ms: Option<u64>,
+    /// 是否启用（默认启用）
+    pub enabled: Option<bool>,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 点位定义（创建/更新请求使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointSpec {
+    /// 点位标识（从站内唯一，字母、数字、'_'、'-' 或 '.'）
+    pub point_key: String,
+    /// 点位名称
+    pub name: String,
+    /// 读取功能码（1 线圈 / 2 离散输入 / 3 保持寄存器 / 4 输入寄存器，0 为虚拟点位）
+    pub function_code: u8,
+    /// 起始地址（0 起始）
+    pub address: u16,
+    /// 寄存器（或线圈）数量（为空时按数据类型推导；字符串必填）
+    pub count: Option<u16>,
+    /// 数据类型（bool / u16 / i16 / u32 / i32 / f32 / f64 / string）
+    pub data_type: String,
+    /// 字节序（单寄存器与字符串 ab / ba，默认 ab；多寄存器 abcd / cdab / badc / dcba，默认 abcd）
+    pub byte_order: Option<String>,
+    /// 访问方式（read / read_write，默认 read；read_write 仅限功能码 1 与 3）
+    pub access: Option<String>,
+    /// 工程单位
+    pub unit: Option<String>,
+    /// 排序号（默认 0）
+    pub sort_order: Option<i32>,
+    /// 值变换配置（为空表示不变换）
+    pub transform: Option<PointTransformSpec>,
+    /// 虚拟点位公式（仅限功能码 0）
+    pub expression: Option<String>,
+}
+
+// 从站列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySlaveListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+}
+
+// 创建从站请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySlaveCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关 ID
+    pub gateway_id: i64,
+    /// 从站定义
+    pub slave: GatewaySlaveSpec,
+}
+
+// 更新从站请求体（整体替换从站字段）
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySlaveUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 从站 ID
+    pub slave_id: i64,
+    /// 从站定义
+    pub slave: GatewaySlaveSpec,
+}
+
+// 删除从站请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewaySlaveDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 从站 ID
+    pub slave_id: i64,
+}
+
+// 点位列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 从站 ID
+    pub slave_id: i64,
+}
+
+// 创建点位请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 从站 ID
+    pub slave_id: i64,
+    /// 点位定义
+    pub point: GatewayPointSpec,
+}
+
+// 更新点位请求体（整体替换点位字段）
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 点位 ID
+    pub point_id: i64,
+    /// 点位定义
+    pub point: GatewayPointSpec,
+}
+
+// 删除点位请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 点位 ID
+    pub point_id: i64,
+}
+
+// 从站响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewaySlaveData {
+    /// 从站 ID
+    pub id: i64,
+    /// 所属网关 ID
+    pub gateway_id: i64,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 从站名称
+    pub name: String,
+    /// 点位地址下限（含）
+    pub address_min: Option<u16>,
+    /// 点位地址上限（含）
+    pub address_max: Option<u16>,
+    /// 从站轮询周期（毫秒，为空沿用网关）
+    pub poll_interval_ms: Option<u64>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 备注
+    pub remark: Option<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+}
+
+// 点位响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPointData {
+    /// 点位 ID
+    pub id: i64,
+    /// 所属从站 ID
+    pub slave_id: i64,
+    /// 点位标识
+    pub point_key: String,
+    /// 点位名称
+    pub name: String,
+    /// 读取功能码
+    pub function_code: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 寄存器（或线圈）数量
+    pub count: u16,
+    /// 数据类型
+    pub data_type: String,
+    /// 字节序
+    pub byte_order: String,
+    /// 访问方式
+    pub access: String,
+    /// 工程单位
+    pub unit: Option<String>,
+    /// 排序号
+    pub sort_order: i32,
+    /// 值变换配置
+    pub transform: PointTransformSpec,
+    /// 虚拟点位公式（物理点位为空）
+    pub expression: Option<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+}
+
+// 点位读测试请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointReadPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 点位 ID
+    pub point_id: i64,
+}
+
+// 点位写测试请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayPointWritePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 点位 ID
+    pub point_id: i64,
+    /// 写入值（bool 为布尔，整数与浮点为数字，字符串为文本；配置了值变换时为工程值，枚举映射可为文本）
+    pub value: serde_json::Value,
+}
+
+// 点位读测试响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPointReadData {
+    /// 点位 ID
+    pub point_id: i64,
+    /// 点位标识
+    pub point_key: String,
+    /// 网关编码
+    pub gateway_code: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 读取功能码
+    pub function_code: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 寄存器（或线圈）数量
+    pub count: u16,
+    /// 数据类型
+    pub data_type: String,
+    /// 字节序
+    pub byte_order: String,
+    /// 会话（shared：网关长连接；temporary：以保存的配置建立的临时会话）
+    pub session: String,
+    /// 读取的线圈或离散输入原始值（功能码 1 / 2）
+    pub bits: Option<Vec<bool>>,
+    /// 读取的寄存器原始值（功能码 3 / 4）
+    pub registers: Option<Vec<u16>>,
+    /// 按数据类型与字节序解码后的原始值
+    pub raw_value: serde_json::Value,
+    /// 经过值变换后的工程值（未配置值变换时与原始值相同）
+    pub value: serde_json::Value,
+    /// 读取耗时（毫秒）
+    pub latency_ms: u64,
+    /// 读取时间戳（毫秒）
+    pub read_at: i64,
+}
+
+// 点位写测试响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPointWriteData {
+    /// 点位 ID
+    pub point_id: i64,
+    /// 点位标识
+    pub point_key: String,
+    /// 网关编码
+    pub gateway_code: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 写入功能码（5 / 6 / 16）
+    pub function_code: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 会话（shared / temporary）
+    pub session: String,
+    /// 写入的线圈值（功能码 5）
+    pub bits: Option<Vec<bool>>,
+    /// 编码后写入的寄存器值（功能码 6 / 16）
+    pub registers: Option<Vec<u16>>,
+    /// 写入的工程值
+    pub value: serde_json::Value,
+    /// 反变换后按数据类型编码的原始值（位域点位为位域值）
+    pub raw_value: serde_json::Value,
+    /// 写入耗时（毫秒）
+    pub latency_ms: u64,
+    /// 写入时间戳（毫秒）
+    pub written_at: i64,
+}
@@ -0,0 +1,600 @@
+//! 通信网关模块数据仓储层
+//!
+//! 本模块负责 gateways、gateway_slaves 与 gateway_points 表的读写：
+//! - 网关的增删改查（按关键字过滤，按编码排序）
+//! - 按网关编码查询（供按网关标识建立长连接的模块使用）
+//! - 网关下从站的增删改查（按单元号排序）与从站下点位的增删改查（按排序号与地址排序）
+//!
+//! 均为简单 CRUD，按 `docs/database-access-policy.md` 规则 1 使用 SeaORM 实现
+
+// 引入 SeaORM 查询表达式
+use sea_orm::sea_query::{Expr, Func, LikeExpr};
+// 引入 SeaORM 核心 trait
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入实体模型
+use crate::db::entities::{gateway_points, gateway_slaves, gateways};
+// 引入通信网关模型
+use crate::gateway::models::{
+    GatewayConnection, GatewayInput, GatewayPointInput, GatewayPointRecord, GatewayRecord,
+    GatewaySlaveInput, GatewaySlaveRecord,
+};
+
+/// 查询网关列表
+///
+/// # 参数
+/// * `keyword` - 小写关键字（匹配编码、名称、地址与串口路径）
+///
+/// # 返回
+/// * 按编码排序的网关记录
+pub fn list_gateways(keyword: Option<&str>) -> Result<Vec<GatewayRecord>, AppError> {
+    let mut condition = Condition::all();
+    if let Some(keyword) = keyword {
+        let pattern = format!("%{}%", escape_like(keyword));
+        let mut keyword_condition = Condition::any();
+        for column in [
+            gateways::Column::Code,
+            gateways::Column::Name,
+            gateways::Column::Host,
+            gateways::Column::SerialPort,
+        ] {
+            keyword_condition = keyword_condition.add(
+                Expr::expr(Func::lower(Expr::col(column)))
+                    .like(LikeExpr::new(pattern.clone()).escape('\\')),
+            );
+        }
+        condition = condition.add(keyword_condition);
+    }
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = gateways::Entity::find()
+            .filter(condition)
+            .order_by_asc(gateways::Column::Code)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_model).collect())
+    })
+}
+
+/// 按 ID 查询网关
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 网关记录（不存在时为 None）
+pub fn find_gateway(gateway_id: i64) -> Result<Option<GatewayRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let model = gateways::Entity::find_by_id(gateway_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(model.map(map_model))
+    })
+}
+
+/// 按编码查询网关
+///
+/// # 参数
+/// * `code` - 网关编码
+///
+/// # 返回
+/// * 网关记录（不存在时为 None）
+pub fn find_gateway_by_code(code: &str) -> Result<Option<GatewayRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let model = gateways::Entity::find()
+            .filter(gateways::Column::Code.eq(code))
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(model.map(map_model))
+    })
+}
+
+/// 新增网关
+///
+/// # 参数
+/// * `input` - 网关写入参数
+/// * `created_by` - 创建人用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新网关 ID
+pub fn insert_gateway(
+    input: GatewayInput,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let mut model = gateways::ActiveModel {
+            created_at: Set(now_millis),
+            created_by: Set(created_by.to_string()),
+            ..Default::default()
+        };
+        assign_input(&mut model, input, now_millis);
+        let model = model
+            .insert(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(model.id)
+    })
+}
+
+/// 整体替换网关字段
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+/// * `input` - 网关写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关存在返回 true
+pub fn update_gateway(
+    gateway_id: i64,
+    input: GatewayInput,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let Some(current) = gateways::Entity::find_by_id(gateway_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(false);
+        };
+        let mut model: gateways::ActiveModel = current.into();
+        assign_input(&mut model, input, now_millis);
+        model
+            .update(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(true)
+    })
+}
+
+/// 删除网关
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 删除的记录数（网关不存在时为 0）
+pub fn delete_gateway(gateway_id: i64) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = gateways::Entity::delete_by_id(gateway_id)
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected)
+    })
+}
+
+/// 查询网关下的从站
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+///
+/// # 返回
+/// * 按单元号排序的从站记录
+pub fn list_slaves(gateway_id: i64) -> Result<Vec<GatewaySlaveRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = gateway_slaves::Entity::find()
+            .filter(gateway_slaves::Column::GatewayId.eq(gateway_id))
+            .order_by_asc(gateway_slaves::Column::UnitId)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_slave_model).collect())
+    })
+}
+
+/// 按 ID 查询从站
+///
+/// # 参数
+/// * `slave_id` - 从站 ID
+///
+/// # 返回
+/// * 从站记录（不存在时为 None）
+pub fn find_slave(slave_id: i64) -> Result<Option<GatewaySlaveRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let model = gateway_slaves::Entity::find_by_id(slave_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(model.map(map_slave_model))
+    })
+}
+
+/// 新增从站
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+/// * `input` - 从站写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新从站 ID
+pub fn insert_slave(
+    gateway_id: i64,
+    input: GatewaySlaveInput,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let mut model = gateway_slaves::ActiveModel {
+            gateway_id: Set(gateway_id),
+            created_at: Set(now_millis),
+            ..Default::default()
+        };
+        assign_slave_input(&mut model, input, now_millis);
+        let model = model
+            .insert(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(model.id)
+    })
+}
+
+/// 整体替换从站字段
+///
+/// # 参数
+/// * `slave_id` - 从站 ID
+/// * `input` - 从站写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 从站存在返回 true
+pub fn update_slave(
+    slave_id: i64,
+    input: GatewaySlaveInput,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let Some(current) = gateway_slaves::Entity::find_by_id(slave_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(false);
+        };
+        let mut model: gateway_slaves::ActiveModel = current.into();
+        assign_slave_input(&mut model, input, now_millis);
+        model
+            .update(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(true)
+    })
+}
+
+/// 删除从站（点位随从站级联删除）
+///
+/// # 参数
+/// * `slave_id` - 从站 ID
+///
+/// # 返回
+/// * 删除的记录数（从站不存在时为 0）
+pub fn delete_slave(slave_id: i64) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = gateway_slaves::Entity::delete_by_id(slave_id)
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected)
+    })
+}
+
+/// 查询从站下的点位
+///
+/// # 参数
+/// * `slave_id` - 从站 ID
+///
+/// # 返回
+/// * 按排序号与起始地址排序的点位记录
+pub fn list_points(slave_id: i64) -> Result<Vec<GatewayPointRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let models = gateway_points::Entity::find()
+            .filter(gateway_points::Column::SlaveId.eq(slave_id))
+            .order_by_asc(gateway_points::Column::SortOrder)
+            .order_by_asc(gateway_points::Column::Address)
+            .order_by_asc(gateway_points::Column::Id)
+            .all(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(models.into_iter().map(map_point_model).collect())
+    })
+}
+
+/// 按 ID 查询点位
+///
+/// # 参数
+/// * `point_id` - 点位 ID
+///
+/// # 返回
+/// * 点位记录（不存在时为 None）
+pub fn find_point(point_id: i64) -> Result<Option<GatewayPointRecord>, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let model = gateway_points::Entity::find_by_id(point_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(model.map(map_point_model))
+    })
+}
+
+/// 新增点位
+///
+/// # 参数
+/// * `slave_id` - 从站 ID
+/// * `input` - 点位写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新点位 ID
+pub fn insert_point(
+    slave_id: i64,
+    input: GatewayPointInput,
+    now_millis: i64,
+) -> Result<i64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let mut model = gateway_points::ActiveModel {
+            slave_id: Set(slave_id),
+            created_at: Set(now_millis),
+            ..Default::default()
+        };
+        assign_point_input(&mut model, input, now_millis);
+        let model = model
+            .insert(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(model.id)
+    })
+}
+
+/// 整体替换点位字段
+///
+/// # 参数
+/// * `point_id` - 点位 ID
+/// * `input` - 点位写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 点位存在返回 true
+pub fn update_point(
+    point_id: i64,
+    input: GatewayPointInput,
+    now_millis: i64,
+) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let Some(current) = gateway_points::Entity::find_by_id(point_id)
+            .one(&connection)
+            .await
+            .map_err(map_db_error)?
+        else {
+            return Ok(false);
+        };
+        let mut model: gateway_points::ActiveModel = current.into();
+        assign_point_input(&mut model, input, now_millis);
+        model
+            .update(&connection)
+            .await
+            .map_err(map_gateway_mutation_error)?;
+        Ok(true)
+    })
+}
+
+/// 删除点位
+///
+/// # 参数
+/// * `point_id` - 点位 ID
+///
+/// # 返回
+/// * 删除的记录数（点位不存在时为 0）
+pub fn delete_point(point_id: i64) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let connection = db::connect_orm_async().await?;
+        let result = gateway_points::Entity::delete_by_id(point_id)
+            .exec(&connection)
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected)
+    })
+}
+
+/// 将写入参数赋值到 ActiveModel（同时刷新更新时间）
+fn assign_input(model: &mut gateways::ActiveModel, input: GatewayInput, now_millis: i64) {
+    let connection = input.connection;
+    model.code = Set(input.code);
+    model.name = Set(input.name);
+    model.transport = Set(connection.transport);
+    model.host = Set(connection.host);
+    model.port = Set(connection.port.map(i32::from));
+    model.serial_port = Set(connection.serial_port);
+    model.baud_rate = Set(connection.baud_rate.map(to_i32));
+    model.data_bits = Set(connection.data_bits.map(i32::from));
+    model.parity = Set(connection.parity);
+    model.stop_bits = Set(connection.stop_bits.map(i32::from));
+    model.framing = Set(connection.framing);
+    model.connect_timeout_ms = Set(to_i32(connection.connect_timeout_ms));
+    model.request_timeout_ms = Set(to_i32(connection.request_timeout_ms));
+    model.poll_interval_ms = Set(to_i32(input.poll_interval_ms));
+    model.poll_max_gap = Set(i32::from(input.poll_max_gap));
+    model.enabled = Set(input.enabled);
+    model.remark = Set(input.remark);
+    model.updated_at = Set(now_millis);
+}
+
+/// 将实体模型转换为网关记录
+fn map_model(model: gateways::Model) -> GatewayRecord {
+    GatewayRecord {
+        id: model.id,
+        input: GatewayInput {
+            code: model.code,
+            name: model.name,
+            connection: GatewayConnection {
+                transport: model.transport,
+                host: model.host,
+                port: model.port.and_then(|port| u16::try_from(port).ok()),
+                serial_port: model.serial_port,
+                baud_rate: model.baud_rate.and_then(|rate| u32::try_from(rate).ok()),
+                data_bits: model.data_bits.and_then(|bits| u8::try_from(bits).ok()),
+                parity: model.parity,
+                stop_bits: model.stop_bits.and_then(|bits| u8::try_from(bits).ok()),
+                framing: model.framing,
+                connect_timeout_ms: u64::try_from(model.connect_timeout_ms).unwrap_or_default(),
+                request_timeout_ms: u64::try_from(model.request_timeout_ms).unwrap_or_default(),
+            },
+            poll_interval_ms: u64::try_from(model.poll_interval_ms).unwrap_or_default(),
+            poll_max_gap: u16::try_from(model.poll_max_gap).unwrap_or_default(),
+            enabled: model.enabled,
+            remark: model.remark,
+        },
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+        created_by: model.created_by,
+    }
+}
+
+/// 将从站写入参数赋值到 ActiveModel（同时刷新更新时间）
+fn assign_slave_input(
+    model: &mut gateway_slaves::ActiveModel,
+    input: GatewaySlaveInput,
+    now_millis: i64,
+) {
+    model.unit_id = Set(i32::from(input.unit_id));
+    model.name = Set(input.name);
+    model.address_min = Set(input.address_min.map(i32::from));
+    model.address_max = Set(input.address_max.map(i32::from));
+    model.poll_interval_ms = Set(input.poll_interval_ms.map(to_i32));
+    model.enabled = Set(input.enabled);
+    model.remark = Set(input.remark);
+    model.updated_at = Set(now_millis);
+}
+
+/// 将点位写入参数赋值到 ActiveModel（同时刷新更新时间）
+fn assign_point_input(
+    model: &mut gateway_points::ActiveModel,
+    input: GatewayPointInput,
+    now_millis: i64,
+) {
+    model.point_key = Set(input.point_key);
+    model.name = Set(input.name);
+    model.function_code = Set(i32::from(input.function_code));
+    model.address = Set(i32::from(input.address));
+    model.count = Set(i32::from(input.count));
+    model.data_type = Set(input.data_type);
+    model.byte_order = Set(input.byte_order);
+    model.access = Set(input.access);
+    model.unit = Set(input.unit);
+    model.sort_order = Set(input.sort_order);
+    model.transform = Set(serde_json::to_value(&input.transform).unwrap_or_default());
+    model.expression = Set(input.expression);
+    model.updated_at = Set(now_millis);
+}
+
+/// 将从站实体模型转换为从站记录
+fn map_slave_model(model: gateway_slaves::Model) -> GatewaySlaveRecord {
+    GatewaySlaveRecord {
+        id: model.id,
+        gateway_id: model.gateway_id,
+        input: GatewaySlaveInput {
+            unit_id: u8::try_from(model.unit_id).unwrap_or_default(),
+            name: model.name,
+            address_min: model
+                .address_min
+                .and_then(|value| u16::try_from(value).ok()),
+            address_max: model
+                .address_max
+                .and_then(|value| u16::try_from(value).ok()),
+            poll_interval_ms: model
+                .poll_interval_ms
+                .and_then(|value| u64::try_from(value).ok()),
+            enabled: model.enabled,
+            remark: model.remark,
+        },
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+/// 将点位实体模型转换为点位记录
+fn map_point_model(model: gateway_points::Model) -> GatewayPointRecord {
+    GatewayPointRecord {
+        id: model.id,
+        slave_id: model.slave_id,
+        input: GatewayPointInput {
+            point_key: model.point_key,
+            name: model.name,
+            function_code: u8::try_from(model.function_code).unwrap_or_default(),
+            address: u16::try_from(model.address).unwrap_or_default(),
+            count: u16::try_from(model.count).unwrap_or_default(),
+            data_type: model.data_type,
+            byte_order: model.byte_order,
+            access: model.access,
+            unit: model.unit,
+            sort_order: model.sort_order,
+            transform: serde_json::from_value(model.transform).unwrap_or_default(),
+            expression: model.expression,
+        },
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+/// 转义 LIKE 模式中的通配符
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// 数值转换为 INTEGER 列（超出范围时取上限）
+fn to_i32<T: TryInto<i32>>(value: T) -> i32 {
+    value.try_into().unwrap_or(i32::MAX)
+}
+
+/// 将网关、从站与点位写入错误映射为应用错误（唯一约束冲突转为校验错误）
+fn map_gateway_mutation_error(err: DbErr) -> AppError {
+    let message = err.to_string();
+    if message.contains("gateways_code_key") {
+        return AppError::Validation("gateway code already exists".to_string());
+    }
+    if message.contains("gateway_slaves_unit_key") {
+        return AppError::Validation("unit id already exists on gateway".to_string());
+    }
+    if message.contains("gateway_points_key") {
+        return AppError::Validation("point key already exists on slave".to_string());
+    }
+    AppError::Database(message)
+}
+
+/// 将 SeaORM 错误映射为数据库错误
+fn map_db_error(err: DbErr) -> AppError {
+    AppError::Database(err.to_string())
+}
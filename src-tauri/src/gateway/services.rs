@@ -0,0 +1,1648 @@
+//! 通信网关模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 网关配置的查询与增删改：编码、名称、传输方式、地址端口或串口参数、帧格式与超时
+//! - 连接参数校验复用 Modbus 模块的传输配置校验，保存的配置可直接用于建立长连接
+//! - 连接测试：以独立的临时客户端建连（建连与请求超时固定 3 秒），可选执行一次探测读取，
+//!   返回延迟与错误类别；临时客户端不注册到全局连接表，不影响同一网关的生产连接
+//! - 从站与点位表：从站单元号 1–247 及可选的地址范围，点位的功能码、起始地址、数量、数据类型与字节序；
+//!   点位地址须落在从站地址范围内，数量须与数据类型匹配，字节序须适用于数据类型
+//! - 点位值变换：缩放与偏移、位域提取、枚举映射、单位换算、限幅与死区，保存前按数据类型与工程单位校验
+//! - 虚拟点位：功能码 0 的点位按公式由同一网关内的其他点位计算；保存前校验公式语法、引用的点位与循环引用，
+//!   删除或修改被引用的点位时同样校验
+//! - 点位读写测试：按点位的功能码与数量读取并按数据类型与字节序解码、变换为工程值，或将工程值反变换、
+//!   编码后写入（位域点位先读取寄存器当前值再替换对应位）；网关已建立长连接时复用长连接，否则以保存的配置建立临时会话
+//! - 采集参数：网关的轮询周期与合并间隙、从站可选的轮询周期；网关、从站与点位变更后通知采集引擎重新生成读取计划
+//! - 权限校验：`device:view`（查询与读测试）、`device:manage`（增删改与连接测试）、`control:issue`（写测试）
+//! - 审计记录（`targetType` 为 `gateway` / `gateway_slave` / `gateway_point`，增删改与写测试的成功与失败均记录）
+
+// 引入时间类型
+use std::time::{Duration, Instant};
+
+// 引入序列化 trait（审计快照）
+use serde::Serialize;
+// 引入 JSON 值类型
+use serde_json::{Value, json};
+
+// 引入采集引擎（配置变更后重新生成读取计划）
+use crate::acquisition::engine as acquisition_engine;
+// 引入虚拟点位公式
+use crate::acquisition::formula::{self, CatalogPoint, Formula, FormulaError};
+// 引入点位值变换
+use crate::acquisition::transform::{self, PointTransform};
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块（共享异步运行时）
+use crate::db;
+// 引入设备服务（操作员校验与字段规范化）
+use crate::device::services as device_services;
+// 引入通信网关模型
+use crate::gateway::models::{
+    GatewayConnection, GatewayCreatePayload, GatewayData, GatewayDeletePayload, GatewayGetPayload,
+    GatewayInput, GatewayListPayload, GatewayPointCreatePayload, GatewayPointData,
+    GatewayPointDeletePayload, GatewayPointInput, GatewayPointListPayload, GatewayPointReadData,
+    GatewayPointReadPayload, GatewayPointRecord, GatewayPointSpec, GatewayPointUpdatePayload,
+    GatewayPointWriteData, GatewayPointWritePayload, GatewayProbeSpec, GatewayRecord,
+    GatewaySlaveCreatePayload, GatewaySlaveData, GatewaySlaveDeletePayload, GatewaySlaveInput,
+    GatewaySlaveListPayload, GatewaySlaveRecord, GatewaySlaveSpec, GatewaySlaveUpdatePayload,
+    GatewaySpec, GatewayTestData, GatewayTestPayload, GatewayUpdatePayload, PointTransformSpec,
+};
+// 引入通信网关仓储模块
+use crate::gateway::repository;
+// 引入 Modbus 客户端
+use crate::modbus::client::{ClientConfig, ModbusClient};
+// 引入寄存器编解码（数据类型与字节序）
+use crate::modbus::codec::{self, ByteOrder, CodecError, DataType, PointValue};
+// 引入通信诊断（删除网关时移除其诊断）
+use crate::modbus::diagnostics as modbus_diagnostics;
+// 引入 Modbus 请求与响应类型
+use crate::modbus::protocol::{ModbusError, Request, Response};
+// 引入 Modbus 传输参数校验
+use crate::modbus::services::{self as modbus_services, SerialOptions, TimeoutOptions};
+// 引入传输配置
+use crate::modbus::transport::{TransportConfig, duration_millis};
+
+// 审计目标类型：通信网关
+const TARGET_TYPE_GATEWAY: &str = "gateway";
+
+// 审计目标类型：网关从站
+const TARGET_TYPE_SLAVE: &str = "gateway_slave";
+
+// 审计目标类型：网关点位
+const TARGET_TYPE_POINT: &str = "gateway_point";
+
+// 传输方式：TCP
+const TRANSPORT_TCP: &str = "tcp";
+
+// 传输方式：串口
+const TRANSPORT_SERIAL: &str = "serial";
+
+// 网关编码最大长度（与 Modbus 长连接的网关标识一致）
+const MAX_CODE_LENGTH: usize = 64;
+
+// 网关、从站与点位名称最大长度
+const MAX_NAME_LENGTH: usize = 128;
+
+// 点位标识最大长度
+const MAX_KEY_LENGTH: usize = 64;
+
+// 从站单元号上限（248–255 为保留地址）
+const MAX_UNIT_ID: u8 = 247;
+
+// 访问方式：只读
+const ACCESS_READ: &str = "read";
+
+// 功能码：虚拟点位（按公式计算，不读取设备）
+const FUNCTION_CODE_VIRTUAL: u8 = 0;
+
+// 访问方式：读写（写入使用 FC05/FC15 或 FC06/FC16）
+const ACCESS_READ_WRITE: &str = "read_write";
+
+// 点位读写会话：网关长连接
+const SESSION_SHARED: &str = "shared";
+
+// 点位读写会话：以保存的配置建立的临时会话
+const SESSION_TEMPORARY: &str = "temporary";
+
+// 默认轮询周期（毫秒）
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+
+// 轮询周期下限（毫秒）
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+// 轮询周期上限（毫秒，1 小时）
+const MAX_POLL_INTERVAL_MS: u64 = 3_600_000;
+
+// 合并读取允许跨越的最大空闲地址数（一次寄存器读取的上限）
+const MAX_POLL_GAP: u16 = 125;
+
+// 连接测试的建连与请求超时
+const TEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 探测读取的默认从站单元号
+const DEFAULT_PROBE_UNIT_ID: u8 = 1;
+
+/// 查询网关列表
+///
+/// # 参数
+/// * `payload` - 操作员用户名与关键字
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按编码排序的网关
+pub fn list_gateways(
+    payload: GatewayListPayload,
+    now_millis: u64,
+) -> Result<Vec<GatewayData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let keyword = device_services::trim_optional(payload.keyword).map(|value| value.to_lowercase());
+    Ok(repository::list_gateways(keyword.as_deref())?
+        .into_iter()
+        .map(map_gateway_record)
+        .collect())
+}
+
+/// 查询网关详情
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关配置
+pub fn get_gateway(payload: &GatewayGetPayload, now_millis: u64) -> Result<GatewayData, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    find_gateway(payload.gateway_id)
+}
+
+/// 创建网关
+///
+/// # 参数
+/// * `payload` - 网关定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的网关
+pub fn create_gateway(
+    payload: GatewayCreatePayload,
+    now_millis: u64,
+) -> Result<GatewayData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let result = create_gateway_unaudited(payload, now_millis);
+    let target_id = result.as_ref().ok().map(|gateway| gateway.id.to_string());
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_create",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_GATEWAY,
+            target_id,
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 修改网关（整体替换网关字段）
+///
+/// 已建立的长连接不会自动更新，需重新建连后生效
+///
+/// # 参数
+/// * `payload` - 网关 ID 与网关定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的网关
+pub fn update_gateway(
+    payload: GatewayUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewayData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let gateway_id = payload.gateway_id;
+    let before = find_snapshot(gateway_id);
+    let result = update_gateway_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_update",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_GATEWAY,
+            target_id: Some(gateway_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除网关
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_gateway(payload: &GatewayDeletePayload, now_millis: u64) -> Result<bool, AppError> {
+    let before = find_snapshot(payload.gateway_id);
+    let result = delete_gateway_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_GATEWAY,
+            target_id: Some(payload.gateway_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 测试网关连接
+///
+/// 以独立的临时客户端建连，建连与请求超时固定为 3 秒；提供探测读取时在建连成功后执行一次读取。
+/// 建连或探测失败不返回错误，而是在结果中给出错误类别（refused / timeout / exception / connect /
+/// io / protocol）与错误信息。串口网关与同一串口上的生产连接共用总线，测试事务与生产事务串行执行。
+///
+/// # 参数
+/// * `payload` - 已保存的网关 ID 或网关定义，以及可选的探测读取
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 测试结果（是否成功、延迟与错误类别）
+pub fn test_connection(
+    payload: &GatewayTestPayload,
+    now_millis: u64,
+) -> Result<GatewayTestData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let connection = match (payload.gateway_id, &payload.gateway) {
+        (Some(gateway_id), _) => {
+            repository::find_gateway(gateway_id)?
+                .ok_or_else(|| AppError::Validation("gateway not found".to_string()))?
+                .input
+                .connection
+        }
+        (None, Some(spec)) => normalize_connection(spec)?,
+        (None, None) => {
+            return Err(AppError::Validation(
+                "gatewayId or gateway is required".to_string(),
+            ));
+        }
+    };
+    let probe = payload.probe.as_ref().map(probe_request).transpose()?;
+    let mut config = ClientConfig::new(transport_config(&connection)?);
+    config.connect_timeout = TEST_TIMEOUT;
+    config.request_timeout = TEST_TIMEOUT;
+    let transport = config.transport.kind().to_string();
+    let target = config.transport.target();
+    let mut data = GatewayTestData {
+        success: false,
+        transport,
+        target,
+        connect_latency_ms: None,
+        probe_latency_ms: None,
+        latency_ms: 0,
+        error_class: None,
+        error: None,
+        bits: None,
+        registers: None,
+        tested_at: now,
+    };
+    db::block_on(async {
+        let started_at = Instant::now();
+        let mut client = ModbusClient::new(config);
+        let result = match client.connect().await {
+            Ok(()) => {
+                data.connect_latency_ms = Some(duration_millis(started_at.elapsed()));
+                match &probe {
+                    Some((unit_id, request)) => {
+                        let probe_started_at = Instant::now();
+                        let result = client.call(*unit_id, request).await;
+                        data.probe_latency_ms = Some(duration_millis(probe_started_at.elapsed()));
+                        result.map(Some)
+                    }
+                    None => Ok(None),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        client.disconnect();
+        data.latency_ms = duration_millis(started_at.elapsed());
+        match result {
+            Ok(response) => {
+                data.success = true;
+                match response {
+                    Some(Response::Bits(values)) => data.bits = Some(values),
+                    Some(Response::Registers(values)) => data.registers = Some(values),
+                    Some(Response::Written) | None => {}
+                }
+            }
+            Err(err) => {
+                data.error_class = Some(err.class().to_string());
+                data.error = Some(err.to_string());
+            }
+        }
+    });
+    Ok(data)
+}
+
+/// 由网关连接参数生成传输配置
+///
+/// # 参数
+/// * `connection` - 已保存（已规范化）的网关连接参数
+///
+/// # 返回
+/// * 传输配置
+pub fn transport_config(connection: &GatewayConnection) -> Result<TransportConfig, AppError> {
+    if connection.transport == TRANSPORT_SERIAL {
+        modbus_services::serial_transport(&SerialOptions {
+            port: connection.serial_port.as_deref().unwrap_or_default(),
+            baud_rate: connection.baud_rate,
+            data_bits: connection.data_bits,
+            parity: connection.parity.as_deref(),
+            stop_bits: connection.stop_bits,
+            inter_frame_delay_ms: None,
+            framing: Some(&connection.framing),
+        })
+    } else {
+        modbus_services::tcp_transport(
+            connection.host.as_deref().unwrap_or_default(),
+            connection.port,
+            Some(&connection.framing),
+        )
+    }
+}
+
+/// 由网关连接参数生成客户端配置（使用保存的建连与请求超时）
+///
+/// # 参数
+/// * `connection` - 已保存（已规范化）的网关连接参数
+///
+/// # 返回
+/// * 客户端配置
+pub fn client_config(connection: &GatewayConnection) -> Result<ClientConfig, AppError> {
+    let mut config = ClientConfig::new(transport_config(connection)?);
+    config.connect_timeout = Duration::from_millis(connection.connect_timeout_ms);
+    config.request_timeout = Duration::from_millis(connection.request_timeout_ms);
+    Ok(config)
+}
+
+/// 查询网关下的从站
+///
+/// # 参数
+/// * `payload` - 操作员用户名与网关 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按单元号排序的从站
+pub fn list_slaves(
+    payload: &GatewaySlaveListPayload,
+    now_millis: u64,
+) -> Result<Vec<GatewaySlaveData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    find_gateway(payload.gateway_id)?;
+    Ok(repository::list_slaves(payload.gateway_id)?
+        .into_iter()
+        .map(map_slave_record)
+        .collect())
+}
+
+/// 创建从站
+///
+/// # 参数
+/// * `payload` - 网关 ID 与从站定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的从站
+pub fn create_slave(
+    payload: &GatewaySlaveCreatePayload,
+    now_millis: u64,
+) -> Result<GatewaySlaveData, AppError> {
+    let result = create_slave_unaudited(payload, now_millis);
+    let target_id = result.as_ref().ok().map(|slave| slave.id.to_string());
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_slave_create",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_SLAVE,
+            target_id,
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 修改从站（整体替换从站字段）
+///
+/// 新的地址范围须包含从站下已有的全部点位
+///
+/// # 参数
+/// * `payload` - 从站 ID 与从站定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的从站
+pub fn update_slave(
+    payload: &GatewaySlaveUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewaySlaveData, AppError> {
+    let before = find_slave(payload.slave_id)
+        .ok()
+        .as_ref()
+        .and_then(snapshot);
+    let result = update_slave_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_slave_update",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_SLAVE,
+            target_id: Some(payload.slave_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除从站（从站下的点位一并删除）
+///
+/// # 参数
+/// * `payload` - 操作员用户名与从站 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_slave(
+    payload: &GatewaySlaveDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let before = find_slave(payload.slave_id)
+        .ok()
+        .as_ref()
+        .and_then(snapshot);
+    let result = delete_slave_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_slave_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_SLAVE,
+            target_id: Some(payload.slave_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 查询从站下的点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与从站 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按排序号与起始地址排序的点位
+pub fn list_points(
+    payload: &GatewayPointListPayload,
+    now_millis: u64,
+) -> Result<Vec<GatewayPointData>, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    find_slave(payload.slave_id)?;
+    Ok(repository::list_points(payload.slave_id)?
+        .into_iter()
+        .map(map_point_record)
+        .collect())
+}
+
+/// 创建点位
+///
+/// # 参数
+/// * `payload` - 从站 ID 与点位定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的点位
+pub fn create_point(
+    payload: &GatewayPointCreatePayload,
+    now_millis: u64,
+) -> Result<GatewayPointData, AppError> {
+    let result = create_point_unaudited(payload, now_millis);
+    let target_id = result.as_ref().ok().map(|point| point.id.to_string());
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_point_create",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_POINT,
+            target_id,
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 修改点位（整体替换点位字段）
+///
+/// # 参数
+/// * `payload` - 点位 ID 与点位定义
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 修改后的点位
+pub fn update_point(
+    payload: &GatewayPointUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewayPointData, AppError> {
+    let before = find_point(payload.point_id)
+        .ok()
+        .as_ref()
+        .and_then(snapshot);
+    let result = update_point_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_point_update",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_POINT,
+            target_id: Some(payload.point_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除点位
+///
+/// # 参数
+/// * `payload` - 操作员用户名与点位 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_point(
+    payload: &GatewayPointDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let before = find_point(payload.point_id)
+        .ok()
+        .as_ref()
+        .and_then(snapshot);
+    let result = delete_point_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_point_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_POINT,
+            target_id: Some(payload.point_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 点位读测试
+///
+/// 按点位的功能码、起始地址与数量读取，按数据类型与字节序解码后经过值变换得到工程值；
+/// 网关已建立长连接时复用长连接，否则以网关保存的配置建立临时会话（读取后断开）
+///
+/// # 参数
+/// * `payload` - 操作员用户名与点位 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 原始线圈或寄存器值、解码后的原始值与工程值
+pub fn read_point(
+    payload: &GatewayPointReadPayload,
+    now_millis: u64,
+) -> Result<GatewayPointReadData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let (point, slave, gateway) = find_point_target(payload.point_id)?;
+    if point.input.function_code == FUNCTION_CODE_VIRTUAL {
+        return Err(AppError::Validation(
+            "virtual point cannot be read from the device".to_string(),
+        ));
+    }
+    let (data_type, byte_order, transform) = point_codec(&point.input)?;
+    let GatewayPointInput { address, count, .. } = point.input;
+    let request = match point.input.function_code {
+        1 => Request::ReadCoils { address, count },
+        2 => Request::ReadDiscreteInputs { address, count },
+        3 => Request::ReadHoldingRegisters { address, count },
+        _ => Request::ReadInputRegisters { address, count },
+    };
+    let started_at = Instant::now();
+    let (session, response) = execute_on_gateway(&gateway, slave.input.unit_id, &request)?;
+    let latency_ms = duration_millis(started_at.elapsed());
+    let (bits, registers, raw_value) = match response {
+        Response::Bits(bits) => {
+            let value = PointValue::Bool(bits.first().copied().unwrap_or_default());
+            (Some(bits), None, value)
+        }
+        Response::Registers(registers) => {
+            let value = codec::decode(data_type, byte_order, &registers)?;
+            (None, Some(registers), value)
+        }
+        Response::Written => {
+            return Err(ModbusError::Protocol("expected read values".to_string()).into());
+        }
+    };
+    let value = transform.apply(raw_value.clone())?;
+    Ok(GatewayPointReadData {
+        point_id: point.id,
+        point_key: point.input.point_key,
+        gateway_code: gateway.input.code,
+        unit_id: slave.input.unit_id,
+        function_code: point.input.function_code,
+        address,
+        count,
+        data_type: point.input.data_type,
+        byte_order: point.input.byte_order,
+        session: session.to_string(),
+        bits,
+        registers,
+        raw_value: raw_value.to_json(),
+        value: value.to_json(),
+        latency_ms,
+        read_at: now,
+    })
+}
+
+/// 点位写测试
+///
+/// 仅限读写点位：写入值为工程值，先按点位的值变换反变换为原始值（超出限幅范围拒绝写入）。
+/// 线圈点位以 FC05 写入布尔值；寄存器点位按数据类型与字节序编码后，单个寄存器以 FC06 写入，
+/// 多个寄存器以 FC16 写入；位域点位先读取寄存器当前值，只替换位域对应的位。会话选择与读测试相同
+///
+/// # 参数
+/// * `payload` - 操作员用户名、点位 ID 与写入值
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 写入的功能码、反变换后的原始值与编码后的寄存器值
+pub fn write_point(
+    payload: &GatewayPointWritePayload,
+    now_millis: u64,
+) -> Result<GatewayPointWriteData, AppError> {
+    let result = write_point_unaudited(payload, now_millis);
+    let after = result
+        .as_ref()
+        .ok()
+        .and_then(snapshot)
+        .or_else(|| Some(json!({ "value": payload.value })));
+    audit_services::record_command(
+        CommandAudit {
+            command: "gateway_point_write",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_POINT,
+            target_id: Some(payload.point_id.to_string()),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 创建网关（不含审计记录）
+fn create_gateway_unaudited(
+    payload: GatewayCreatePayload,
+    now_millis: u64,
+) -> Result<GatewayData, AppError> {
+    let (operator_username, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let input = normalize_gateway(&payload.gateway)?;
+    let gateway_id = repository::insert_gateway(input, &operator_username, now)?;
+    acquisition_engine::invalidate_plans();
+    find_gateway(gateway_id)
+}
+
+// 修改网关（不含审计记录）
+fn update_gateway_unaudited(
+    payload: GatewayUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewayData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let input = normalize_gateway(&payload.gateway)?;
+    if !repository::update_gateway(payload.gateway_id, input, now)? {
+        return Err(AppError::Validation("gateway not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    find_gateway(payload.gateway_id)
+}
+
+// 删除网关（不含审计记录）
+fn delete_gateway_unaudited(
+    payload: &GatewayDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let record = find_gateway(payload.gateway_id)?;
+    if repository::delete_gateway(payload.gateway_id)? == 0 {
+        return Err(AppError::Validation("gateway not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    // 通信诊断按网关编码保存，随网关一并移除
+    modbus_diagnostics::remove(&record.code);
+    Ok(true)
+}
+
+// 创建从站（不含审计记录）
+fn create_slave_unaudited(
+    payload: &GatewaySlaveCreatePayload,
+    now_millis: u64,
+) -> Result<GatewaySlaveData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    find_gateway(payload.gateway_id)?;
+    let input = normalize_slave(&payload.slave)?;
+    let slave_id = repository::insert_slave(payload.gateway_id, input, now)?;
+    acquisition_engine::invalidate_plans();
+    find_slave(slave_id)
+}
+
+// 修改从站（不含审计记录）
+fn update_slave_unaudited(
+    payload: &GatewaySlaveUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewaySlaveData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let input = normalize_slave(&payload.slave)?;
+    for point in repository::list_points(payload.slave_id)? {
+        if point.input.function_code == FUNCTION_CODE_VIRTUAL {
+            continue;
+        }
+        check_slave_bounds(&input, point.input.address, point.input.count)
+            .map_err(|err| prefix_error(&format!("point {}", point.input.point_key), err))?;
+    }
+    if !repository::update_slave(payload.slave_id, input, now)? {
+        return Err(AppError::Validation("slave not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    find_slave(payload.slave_id)
+}
+
+// 删除从站（不含审计记录）
+fn delete_slave_unaudited(
+    payload: &GatewaySlaveDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    if repository::delete_slave(payload.slave_id)? == 0 {
+        return Err(AppError::Validation("slave not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    Ok(true)
+}
+
+// 创建点位（不含审计记录）
+fn create_point_unaudited(
+    payload: &GatewayPointCreatePayload,
+    now_millis: u64,
+) -> Result<GatewayPointData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let slave = repository::find_slave(payload.slave_id)?
+        .ok_or_else(|| AppError::Validation("slave not found".to_string()))?;
+    let input = normalize_point(&payload.point, &slave.input)?;
+    check_virtual_points(slave.gateway_id, None, slave.id, Some(&input))?;
+    let point_id = repository::insert_point(slave.id, input, now)?;
+    acquisition_engine::invalidate_plans();
+    find_point(point_id)
+}
+
+// 修改点位（不含审计记录）
+fn update_point_unaudited(
+    payload: &GatewayPointUpdatePayload,
+    now_millis: u64,
+) -> Result<GatewayPointData, AppError> {
+    let (_, _, now) = device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let point = repository::find_point(payload.point_id)?
+        .ok_or_else(|| AppError::Validation("point not found".to_string()))?;
+    let slave = repository::find_slave(point.slave_id)?
+        .ok_or_else(|| AppError::Validation("slave not found".to_string()))?;
+    let input = normalize_point(&payload.point, &slave.input)?;
+    check_virtual_points(slave.gateway_id, Some(point.id), slave.id, Some(&input))?;
+    if !repository::update_point(payload.point_id, input, now)? {
+        return Err(AppError::Validation("point not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    find_point(payload.point_id)
+}
+
+// 删除点位（不含审计记录）
+fn delete_point_unaudited(
+    payload: &GatewayPointDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    device_services::assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    if let Some(point) = repository::find_point(payload.point_id)? {
+        if let Some(slave) = repository::find_slave(point.slave_id)? {
+            check_virtual_points(slave.gateway_id, Some(point.id), slave.id, None)?;
+        }
+    }
+    if repository::delete_point(payload.point_id)? == 0 {
+        return Err(AppError::Validation("point not found".to_string()));
+    }
+    acquisition_engine::invalidate_plans();
+    Ok(true)
+}
+
+// 点位写测试（不含审计记录）
+fn write_point_unaudited(
+    payload: &GatewayPointWritePayload,
+    now_millis: u64,
+) -> Result<GatewayPointWriteData, AppError> {
+    let now = assert_control_allowed(&payload.operator_username, now_millis)?;
+    let (point, slave, gateway) = find_point_target(payload.point_id)?;
+    if point.input.access != ACCESS_READ_WRITE {
+        return Err(AppError::Validation("point is read-only".to_string()));
+    }
+    let (data_type, byte_order, transform) = point_codec(&point.input)?;
+    let value = PointValue::from_json(&payload.value).ok_or_else(|| {
+        AppError::Validation("value must be a boolean, number or string".to_string())
+    })?;
+    let raw_value = transform.inverse(data_type, value.clone())?;
+    let GatewayPointInput { address, count, .. } = point.input;
+    let request = if point.input.function_code == 1 {
+        let PointValue::Bool(value) = raw_value else {
+            return Err(CodecError::TypeMismatch(DataType::Bool).into());
+        };
+        Request::WriteSingleCoil { address, value }
+    } else {
+        let encoded = match (transform.bit_field(), &raw_value) {
+            (Some(bits), PointValue::Integer(field)) => {
+                let request = Request::ReadHoldingRegisters { address, count };
+                let (_, response) = execute_on_gateway(&gateway, slave.input.unit_id, &request)?;
+                let Response::Registers(current) = response else {
+                    return Err(ModbusError::Protocol("expected read values".to_string()).into());
+                };
+                let PointValue::Integer(current) = codec::decode(data_type, byte_order, &current)?
+                else {
+                    return Err(CodecError::TypeMismatch(data_type).into());
+                };
+                PointValue::Integer(bits.insert(current, *field))
+            }
+            _ => raw_value.clone(),
+        };
+        let mut registers = codec::encode(data_type, byte_order, count, &encoded)?;
+        if registers.len() == 1 {
+            Request::WriteSingleRegister {
+                address,
+                value: registers.remove(0),
+            }
+        } else {
+            Request::WriteMultipleRegisters {
+                address,
+                values: registers,
+            }
+        }
+    };
+    let started_at = Instant::now();
+    let (session, _) = execute_on_gateway(&gateway, slave.input.unit_id, &request)?;
+    let latency_ms = duration_millis(started_at.elapsed());
+    let (bits, registers) = match &request {
+        Request::WriteSingleCoil { value, .. } => (Some(vec![*value]), None),
+        Request::WriteSingleRegister { value, .. } => (None, Some(vec![*value])),
+        Request::WriteMultipleRegisters { values, .. } => (None, Some(values.clone())),
+        _ => (None, None),
+    };
+    Ok(GatewayPointWriteData {
+        point_id: point.id,
+        point_key: point.input.point_key,
+        gateway_code: gateway.input.code,
+        unit_id: slave.input.unit_id,
+        function_code: request.function().code(),
+        address,
+        session: session.to_string(),
+        bits,
+        registers,
+        value: value.to_json(),
+        raw_value: raw_value.to_json(),
+        latency_ms,
+        written_at: now,
+    })
+}
+
+/// 查询点位及其所属从站与网关
+fn find_point_target(
+    point_id: i64,
+) -> Result<(GatewayPointRecord, GatewaySlaveRecord, GatewayRecord), AppError> {
+    let point = repository::find_point(point_id)?
+        .ok_or_else(|| AppError::Validation("point not found".to_string()))?;
+    let slave = repository::find_slave(point.slave_id)?
+        .ok_or_else(|| AppError::Validation("slave not found".to_string()))?;
+    let gateway = repository::find_gateway(slave.gateway_id)?
+        .ok_or_else(|| AppError::Validation("gateway not found".to_string()))?;
+    Ok((point, slave, gateway))
+}
+
+/// 解析点位保存的数据类型、字节序与值变换
+fn point_codec(
+    point: &GatewayPointInput,
+) -> Result<(DataType, ByteOrder, PointTransform), AppError> {
+    let data_type = DataType::parse(&point.data_type)
+        .ok_or_else(|| AppError::Validation(format!("unsupported dataType {}", point.data_type)))?;
+    let byte_order = ByteOrder::parse(&point.byte_order).ok_or_else(|| {
+        AppError::Validation(format!("unsupported byteOrder {}", point.byte_order))
+    })?;
+    let transform = PointTransform::compile(&point.transform, data_type, point.unit.as_deref())
+        .map_err(|err| prefix_error("transform", err))?;
+    Ok((data_type, byte_order, transform))
+}
+
+/// 在网关上执行点位请求
+///
+/// 网关编码已建立长连接时在长连接上执行；否则以网关保存的连接参数与超时建立临时会话，
+/// 执行后断开（临时会话不注册到全局连接表）
+fn execute_on_gateway(
+    gateway: &GatewayRecord,
+    unit_id: u8,
+    request: &Request,
+) -> Result<(&'static str, Response), AppError> {
+    match modbus_services::execute(&gateway.input.code, unit_id, request) {
+        Err(ModbusError::NotConnected) => {}
+        result => return Ok((SESSION_SHARED, result?)),
+    }
+    let config = client_config(&gateway.input.connection)?;
+    let response = db::block_on(async {
+        let mut client = ModbusClient::new(config);
+        let result = client.call(unit_id, request).await;
+        client.disconnect();
+        result
+    })?;
+    Ok((SESSION_TEMPORARY, response))
+}
+
+/// 校验操作员的控制下发权限并返回当前时间戳
+fn assert_control_allowed(operator_username: &str, now_millis: u64) -> Result<i64, AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        rbac::RESOURCE_CONTROL,
+        rbac::ACTION_ISSUE,
+        now_millis,
+        "forbidden: control issue required",
+    )?;
+    Ok(now_millis)
+}
+
+/// 查询网关并转换为响应格式
+fn find_gateway(gateway_id: i64) -> Result<GatewayData, AppError> {
+    repository::find_gateway(gateway_id)?
+        .map(map_gateway_record)
+        .ok_or_else(|| AppError::Validation("gateway not found".to_string()))
+}
+
+/// 校验并规范化网关定义
+fn normalize_gateway(spec: &GatewaySpec) -> Result<GatewayInput, AppError> {
+    let code = spec.code.trim();
+    if code.is_empty() {
+        return Err(AppError::Validation("code is required".to_string()));
+    }
+    if code.chars().count() > MAX_CODE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "code must be at most {MAX_CODE_LENGTH} characters"
+        )));
+    }
+    Ok(GatewayInput {
+        code: code.to_string(),
+        name: normalize_name(&spec.name)?,
+        connection: normalize_connection(spec)?,
+        poll_interval_ms: normalize_poll_interval(spec.poll_interval_ms)?
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS),
+        poll_max_gap: match spec.poll_max_gap {
+            Some(gap) if gap > MAX_POLL_GAP => {
+                return Err(AppError::Validation(format!(
+                    "pollMaxGap must be between 0 and {MAX_POLL_GAP}"
+                )));
+            }
+            gap => gap.unwrap_or_default(),
+        },
+        enabled: spec.enabled.unwrap_or(true),
+        remark: device_services::trim_optional(spec.remark.clone()),
+    })
+}
+
+/// 查询从站并转换为响应格式
+fn find_slave(slave_id: i64) -> Result<GatewaySlaveData, AppError> {
+    repository::find_slave(slave_id)?
+        .map(map_slave_record)
+        .ok_or_else(|| AppError::Validation("slave not found".to_string()))
+}
+
+/// 查询点位并转换为响应格式
+fn find_point(point_id: i64) -> Result<GatewayPointData, AppError> {
+    repository::find_point(point_id)?
+        .map(map_point_record)
+        .ok_or_else(|| AppError::Validation("point not found".to_string()))
+}
+
+/// 校验从站定义（单元号、名称与地址范围）
+fn normalize_slave(spec: &GatewaySlaveSpec) -> Result<GatewaySlaveInput, AppError> {
+    if !(1..=MAX_UNIT_ID).contains(&spec.unit_id) {
+        return Err(AppError::Validation(format!(
+            "unitId must be between 1 and {MAX_UNIT_ID}"
+        )));
+    }
+    if let (Some(min), Some(max)) = (spec.address_min, spec.address_max) {
+        if min > max {
+            return Err(AppError::Validation(
+                "addressMin must not exceed addressMax".to_string(),
+            ));
+        }
+    }
+    Ok(GatewaySlaveInput {
+        unit_id: spec.unit_id,
+        name: normalize_name(&spec.name)?,
+        address_min: spec.address_min,
+        address_max: spec.address_max,
+        poll_interval_ms: normalize_poll_interval(spec.poll_interval_ms)?,
+        enabled: spec.enabled.unwrap_or(true),
+        remark: device_services::trim_optional(spec.remark.clone()),
+    })
+}
+
+/// 校验并规范化点位定义
+///
+/// 功能码 1、2 只支持 bool；数量为空时按数据类型推导（字符串必填）；字节序为空时取数据类型的默认字节序；
+/// 地址范围须落在从站的地址范围内；读写点位仅限功能码 1（线圈）与 3（保持寄存器）；
+/// 值变换按数据类型与工程单位校验后以规范化形式保存；功能码 0 为虚拟点位
+fn normalize_point(
+    spec: &GatewayPointSpec,
+    slave: &GatewaySlaveInput,
+) -> Result<GatewayPointInput, AppError> {
+    let point_key = normalize_key(&spec.point_key, "pointKey")?;
+    let name = normalize_name(&spec.name)?;
+    let function_code = spec.function_code;
+    if function_code > 4 {
+        return Err(AppError::Validation(
+            "functionCode must be one of 0, 1, 2, 3, 4".to_string(),
+        ));
+    }
+    let expression = device_services::trim_optional(spec.expression.clone());
+    if function_code == FUNCTION_CODE_VIRTUAL {
+        return normalize_virtual_point(spec, point_key, name, expression);
+    }
+    if expression.is_some() {
+        return Err(AppError::Validation(
+            "expression requires functionCode 0".to_string(),
+        ));
+    }
+    let data_type = DataType::parse(&spec.data_type).ok_or_else(|| {
+        AppError::Validation(
+            "dataType must be one of bool, u16, i16, u32, i32, f32, f64, string".to_string(),
+        )
+    })?;
+    if function_code <= 2 && data_type != DataType::Bool {
+        return Err(AppError::Validation(
+            "dataType must be bool for functionCode 1 or 2".to_string(),
+        ));
+    }
+    let count = match spec.count {
+        Some(count) => count,
+        None => data_type.register_count().ok_or_else(|| {
+            AppError::Validation("count is required for dataType string".to_string())
+        })?,
+    };
+    data_type.check_count(count)?;
+    let byte_order = match device_services::trim_optional(spec.byte_order.clone()) {
+        Some(value) => ByteOrder::parse(&value).ok_or_else(|| {
+            AppError::Validation(
+                "byteOrder must be one of ab, ba, abcd, cdab, badc, dcba".to_string(),
+            )
+        })?,
+        None => data_type.default_byte_order(),
+    };
+    codec::check_order(data_type, byte_order)?;
+    if u32::from(spec.address) + u32::from(count) > 0x1_0000 {
+        return Err(AppError::Validation(
+            "address range exceeds 65535".to_string(),
+        ));
+    }
+    check_slave_bounds(slave, spec.address, count)?;
+    let access = spec.access.as_deref().map_or(ACCESS_READ, str::trim);
+    if access != ACCESS_READ && access != ACCESS_READ_WRITE {
+        return Err(AppError::Validation(
+            "access must be one of read, read_write".to_string(),
+        ));
+    }
+    if access == ACCESS_READ_WRITE && !matches!(function_code, 1 | 3) {
+        return Err(AppError::Validation(
+            "access read_write requires functionCode 1 or 3".to_string(),
+        ));
+    }
+    let unit = device_services::trim_optional(spec.unit.clone());
+    let transform = normalize_transform(spec, data_type, unit.as_deref())?;
+    Ok(GatewayPointInput {
+        point_key,
+        name,
+        function_code,
+        address: spec.address,
+        count,
+        data_type: data_type.as_str().to_string(),
+        byte_order: byte_order.as_str().to_string(),
+        access: access.to_string(),
+        unit,
+        sort_order: spec.sort_order.unwrap_or_default(),
+        transform,
+        expression: None,
+    })
+}
+
+/// 校验并规范化虚拟点位定义
+///
+/// 虚拟点位须有公式，数据类型为 f64（默认）或 bool，只读；起始地址、数量与字节序不使用，保存为固定值。
+/// 公式引用的点位在保存前按网关内的点位解析（见 `check_virtual_points`）
+fn normalize_virtual_point(
+    spec: &GatewayPointSpec,
+    point_key: String,
+    name: String,
+    expression: Option<String>,
+) -> Result<GatewayPointInput, AppError> {
+    let expression = expression.ok_or_else(|| {
+        AppError::Validation("expression is required for functionCode 0".to_string())
+    })?;
+    Formula::parse(&expression).map_err(|err| prefix_error("expression", err.into()))?;
+    let data_type = match spec.data_type.trim() {
+        "" => DataType::F64,
+        value => DataType::parse(value)
+            .filter(|data_type| matches!(data_type, DataType::F64 | DataType::Bool))
+            .ok_or_else(|| {
+                AppError::Validation("dataType must be f64 or bool for virtual points".to_string())
+            })?,
+    };
+    if spec
+        .access
+        .as_deref()
+        .is_some_and(|access| access.trim() != ACCESS_READ)
+    {
+        return Err(AppError::Validation(
+            "virtual points are read-only".to_string(),
+        ));
+    }
+    let unit = device_services::trim_optional(spec.unit.clone());
+    let transform = normalize_transform(spec, data_type, unit.as_deref())?;
+    Ok(GatewayPointInput {
+        point_key,
+        name,
+        function_code: FUNCTION_CODE_VIRTUAL,
+        address: 0,
+        count: 1,
+        data_type: data_type.as_str().to_string(),
+        byte_order: data_type.default_byte_order().as_str().to_string(),
+        access: ACCESS_READ.to_string(),
+        unit,
+        sort_order: spec.sort_order.unwrap_or_default(),
+        transform,
+        expression: Some(expression),
+    })
+}
+
+/// 校验并规范化点位的值变换配置
+fn normalize_transform(
+    spec: &GatewayPointSpec,
+    data_type: DataType,
+    unit: Option<&str>,
+) -> Result<PointTransformSpec, AppError> {
+    let transform = spec
+        .transform
+        .as_ref()
+        .map(transform::normalize)
+        .unwrap_or_default();
+    PointTransform::compile(&transform, data_type, unit)
+        .map_err(|err| prefix_error("transform", err))?;
+    Ok(transform)
+}
+
+/// 将点位转换为公式引用目录中的点位（字符串与枚举映射点位不能被公式引用）
+///
+/// # 参数
+/// * `point_id` - 点位 ID
+/// * `input` - 点位定义
+/// * `unit_id` - 所属从站单元号
+pub fn catalog_point(point_id: i64, input: &GatewayPointInput, unit_id: u8) -> CatalogPoint {
+    CatalogPoint {
+        point_id,
+        point_key: input.point_key.clone(),
+        unit_id,
+        numeric: input.data_type != DataType::String.as_str() && input.transform.enum_map.is_none(),
+        expression: input.expression.clone(),
+    }
+}
+
+/// 校验点位变更后网关内的虚拟点位
+///
+/// 变更的虚拟点位引用的点位须存在且为数值点位；变更不能使原本有效的其他虚拟点位失效，也不能形成循环引用
+///
+/// # 参数
+/// * `gateway_id` - 网关 ID
+/// * `point_id` - 变更的点位 ID（新建时为 None）
+/// * `slave_id` - 变更的点位所属从站 ID
+/// * `input` - 变更后的点位定义（删除时为 None）
+fn check_virtual_points(
+    gateway_id: i64,
+    point_id: Option<i64>,
+    slave_id: i64,
+    input: Option<&GatewayPointInput>,
+) -> Result<(), AppError> {
+    // 新建的点位尚无 ID，以 0 代替
+    let changed_id = point_id.unwrap_or_default();
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for slave in repository::list_slaves(gateway_id)? {
+        for point in repository::list_points(slave.id)? {
+            let point = catalog_point(point.id, &point.input, slave.input.unit_id);
+            if point.point_id != changed_id {
+                after.push(point.clone());
+            }
+            before.push(point);
+        }
+        if let Some(input) = input.filter(|_| slave.id == slave_id) {
+            after.push(catalog_point(changed_id, input, slave.input.unit_id));
+        }
+    }
+    let point_key = |point_id: i64| {
+        after
+            .iter()
+            .find(|point| point.point_id == point_id)
+            .map(|point| point.point_key.clone())
+            .unwrap_or_default()
+    };
+    let resolved_before = formula::resolve(&before);
+    let resolved = formula::resolve(&after);
+    for (point_id, result) in &resolved {
+        let Err(err) = result else {
+            continue;
+        };
+        if *point_id == changed_id {
+            return Err(prefix_error("expression", err.clone().into()));
+        }
+        if resolved_before.get(point_id).is_some_and(Result::is_ok) {
+            return Err(AppError::Validation(format!(
+                "point is referenced by virtual point {}",
+                point_key(*point_id)
+            )));
+        }
+    }
+    let dependencies = resolved
+        .into_iter()
+        .filter_map(|(point_id, result)| result.ok().map(|resolved| (point_id, resolved.inputs)))
+        .collect();
+    if let Err(cycle) = formula::evaluation_order(&dependencies) {
+        let path: Vec<String> = cycle.into_iter().map(point_key).collect();
+        return Err(prefix_error(
+            "expression",
+            FormulaError::Cycle(path.join(" -> ")).into(),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验轮询周期（100–3600000 毫秒，为空时保持为空）
+fn normalize_poll_interval(value: Option<u64>) -> Result<Option<u64>, AppError> {
+    match value {
+        Some(value) if !(MIN_POLL_INTERVAL_MS..=MAX_POLL_INTERVAL_MS).contains(&value) => {
+            Err(AppError::Validation(format!(
+                "pollIntervalMs must be between {MIN_POLL_INTERVAL_MS} and {MAX_POLL_INTERVAL_MS}"
+            )))
+        }
+        value => Ok(value),
+    }
+}
+
+/// 校验点位地址范围落在从站地址范围内
+fn check_slave_bounds(slave: &GatewaySlaveInput, address: u16, count: u16) -> Result<(), AppError> {
+    let end = u32::from(address) + u32::from(count.max(1)) - 1;
+    let below = slave.address_min.is_some_and(|min| address < min);
+    let above = slave.address_max.is_some_and(|max| end > u32::from(max));
+    if below || above {
+        return Err(AppError::Validation(format!(
+            "address range {address}-{end} is outside slave bounds {}-{}",
+            slave.address_min.unwrap_or(0),
+            slave.address_max.unwrap_or(u16::MAX)
+        )));
+    }
+    Ok(())
+}
+
+/// 校验名称（必填，最多 128 个字符）
+fn normalize_name(value: &str) -> Result<String, AppError> {
+    let name = value.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::Validation(format!(
+            "name must be at most {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// 校验标识（必填，最多 64 个字母、数字、'_'、'-' 或 '.'）
+fn normalize_key(value: &str, field: &str) -> Result<String, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::Validation(format!("{field} is required")));
+    }
+    if value.len() > MAX_KEY_LENGTH
+        || !value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "{field} must be at most {MAX_KEY_LENGTH} letters, digits, '_', '-' or '.'"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// 为校验错误添加上下文前缀
+fn prefix_error(context: &str, err: AppError) -> AppError {
+    match err {
+        AppError::Validation(message) => AppError::Validation(format!("{context}: {message}")),
+        other => other,
+    }
+}
+
+/// 校验并规范化网关连接参数（传输方式、地址端口或串口参数、帧格式与超时）
+fn normalize_connection(spec: &GatewaySpec) -> Result<GatewayConnection, AppError> {
+    let transport = spec.transport.as_deref().map_or_else(
+        || TRANSPORT_TCP.to_string(),
+        |value| value.trim().to_ascii_lowercase(),
+    );
+    let transport = match transport.as_str() {
+        TRANSPORT_TCP => {
+            modbus_services::tcp_transport(&spec.host, spec.port, spec.framing.as_deref())?
+        }
+        TRANSPORT_SERIAL => {
+            if spec.serial_port.trim().is_empty() {
+                return Err(AppError::Validation("serialPort is required".to_string()));
+            }
+            modbus_services::serial_transport(&SerialOptions {
+                port: &spec.serial_port,
+                baud_rate: spec.baud_rate,
+                data_bits: spec.data_bits,
+                parity: spec.parity.as_deref(),
+                stop_bits: spec.stop_bits,
+                inter_frame_delay_ms: None,
+                framing: spec.framing.as_deref(),
+            })?
+        }
+        _ => {
+            return Err(AppError::Validation(
+                "transport must be one of tcp, serial".to_string(),
+            ));
+        }
+    };
+    let config = modbus_services::client_config(
+        transport,
+        TimeoutOptions {
+            connect_timeout: spec.connect_timeout_ms,
+            request_timeout: spec.request_timeout_ms,
+            ..TimeoutOptions::default()
+        },
+    )?;
+    let mut connection = GatewayConnection {
+        connect_timeout_ms: duration_millis(config.connect_timeout),
+        request_timeout_ms: duration_millis(config.request_timeout),
+        ..GatewayConnection::default()
+    };
+    match config.transport {
+        TransportConfig::Tcp { host, port } => {
+            connection.transport = TRANSPORT_TCP.to_string();
+            connection.host = Some(host);
+            connection.port = Some(port);
+            connection.framing = "mbap".to_string();
+        }
+        TransportConfig::TcpFramed {
+            host,
+            port,
+            framing,
+        } => {
+            connection.transport = TRANSPORT_TCP.to_string();
+            connection.host = Some(host);
+            connection.port = Some(port);
+            connection.framing = framing.as_str().to_string();
+        }
+        TransportConfig::Serial { config, framing } => {
+            connection.transport = TRANSPORT_SERIAL.to_string();
+            connection.serial_port = Some(config.port);
+            connection.baud_rate = Some(config.baud_rate);
+            connection.data_bits = Some(config.data_bits);
+            connection.parity = Some(config.parity.as_str().to_string());
+            connection.stop_bits = Some(config.stop_bits);
+            connection.framing = framing.as_str().to_string();
+        }
+    }
+    Ok(connection)
+}
+
+/// 校验探测读取并生成 (单元号, 读请求)
+fn probe_request(probe: &GatewayProbeSpec) -> Result<(u8, Request), AppError> {
+    let address = probe.address;
+    let count = probe.count.unwrap_or(1);
+    let request = match probe.register_type.trim() {
+        "coil" => Request::ReadCoils { address, count },
+        "discrete_input" => Request::ReadDiscreteInputs { address, count },
+        "holding_register" => Request::ReadHoldingRegisters { address, count },
+        "input_register" => Request::ReadInputRegisters { address, count },
+        _ => {
+            return Err(AppError::Validation(
+                "registerType must be one of coil, discrete_input, holding_register, input_register"
+                    .to_string(),
+            ));
+        }
+    };
+    request.validate()?;
+    Ok((probe.unit_id.unwrap_or(DEFAULT_PROBE_UNIT_ID), request))
+}
+
+/// 将网关记录转换为响应格式
+fn map_gateway_record(record: GatewayRecord) -> GatewayData {
+    let GatewayInput {
+        code,
+        name,
+        connection,
+        poll_interval_ms,
+        poll_max_gap,
+        enabled,
+        remark,
+    } = record.input;
+    GatewayData {
+        id: record.id,
+        code,
+        name,
+        transport: connection.transport,
+        host: connection.host,
+        port: connection.port,
+        serial_port: connection.serial_port,
+        baud_rate: connection.baud_rate,
+        data_bits: connection.data_bits,
+        parity: connection.parity,
+        stop_bits: connection.stop_bits,
+        framing: connection.framing,
+        connect_timeout_ms: connection.connect_timeout_ms,
+        request_timeout_ms: connection.request_timeout_ms,
+        poll_interval_ms,
+        poll_max_gap,
+        enabled,
+        remark,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        created_by: record.created_by,
+    }
+}
+
+/// 将从站记录转换为响应格式
+fn map_slave_record(record: GatewaySlaveRecord) -> GatewaySlaveData {
+    let GatewaySlaveInput {
+        unit_id,
+        name,
+        address_min,
+        address_max,
+        poll_interval_ms,
+        enabled,
+        remark,
+    } = record.input;
+    GatewaySlaveData {
+        id: record.id,
+        gateway_id: record.gateway_id,
+        unit_id,
+        name,
+        address_min,
+        address_max,
+        poll_interval_ms,
+        enabled,
+        remark,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+/// 将点位记录转换为响应格式
+fn map_point_record(record: GatewayPointRecord) -> GatewayPointData {
+    let GatewayPointInput {
+        point_key,
+        name,
+        function_code,
+        address,
+        count,
+        data_type,
+        byte_order,
+        access,
+        unit,
+        sort_order,
+        transform,
+        expression,
+    } = record.input;
+    GatewayPointData {
+        id: record.id,
+        slave_id: record.slave_id,
+        point_key,
+        name,
+        function_code,
+        address,
+        count,
+        data_type,
+        byte_order,
+        access,
+        unit,
+        sort_order,
+        transform,
+        expression,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+/// 查询网关审计快照
+fn find_snapshot(gateway_id: i64) -> Option<Value> {
+    find_gateway(gateway_id).ok().as_ref().and_then(snapshot)
+}
+
+/// 将网关、从站或点位序列化为审计快照
+fn snapshot<T: Serialize>(data: &T) -> Option<Value> {
+    serde_json::to_value(data).ok()
+}
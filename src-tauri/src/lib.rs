@@ -5,19 +5,30 @@
     clippy::doc_markdown, // 忽略文档 markdown 规范警告
     clippy::needless_pass_by_value // 忽略可传引用却按值传递的警告
 )] // clippy allow 列表结束
+pub mod acquisition; // 暴露数据采集模块
+pub mod audit; // 暴露审计日志模块
 pub mod auth; // 暴露认证相关模块
 pub mod core; // 暴露核心基础设施模块
 pub mod db; // 暴露业务数据库模块
+pub mod device; // 暴露设备管理模块
+pub mod device_lifecycle; // 暴露设备生命周期模块
+pub mod device_tag; // 暴露设备标签模块
+pub mod device_template; // 暴露设备模板模块
+pub mod gateway; // 暴露通信网关模块
+pub mod location; // 暴露空间位置模块
+pub mod modbus; // 暴露 Modbus 通信模块
 pub mod notice; // 暴露通知中心模块
+pub mod organization; // 暴露组织架构模块
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)] // 移动端使用 Tauri 的入口属性
+#[allow(clippy::too_many_lines)] // 命令注册列表随模块增长，不拆分入口函数
 pub fn run() { // 应用启动入口函数
     let runtime_config = core::config::runtime_config(); // 读取运行时配置
     core::tracing::init_tracing(runtime_config) // 初始化 tracing 日志
         .unwrap_or_else(|err| panic!("initialize tracing failed: {err}")); // 初始化失败则终止启动
 
     tauri::Builder::default() // 创建默认 Tauri 构建器
-        .setup(|_app| { // 配置应用启动前的初始化逻辑
+        .setup(|app| { // 配置应用启动前的初始化逻辑
             let database_url = runtime_config.database.url.clone(); // 复制数据库连接字符串
             tracing::info!("startup: configuring database url"); // 记录数据库配置日志
             db::set_database_url(database_url) // 设置数据库连接地址
@@ -37,20 +48,118 @@ pub fn run() { // 应用启动入口函数
             notice::init_notice_database().map_err(|err| { // 初始化通知数据库并映射错误
                 std::io::Error::other(format!("initialize notice db failed: {err}")) // 构造通知库错误
             })?; // 失败时直接返回错误
+            acquisition::telemetry::set_app_handle(app.handle().clone()); // 采集值变化通过 Tauri 事件推送前端
+            modbus::diagnostics::set_app_handle(app.handle().clone()); // 从站通信状态变化通过 Tauri 事件推送前端
             Ok(()) // setup 结束并返回成功
         }) // setup 闭包结束
         .invoke_handler(tauri::generate_handler![ // 注册前端可调用的 Tauri 命令
             auth::commands::auth_login, // 登录命令
             auth::commands::auth_refresh_token, // 刷新 token 命令
+            auth::commands::auth_change_own_password, // 修改本人密码命令（强制改密）
             auth::commands::auth_get_async_routes, // 获取异步路由命令
             auth::admin_commands::auth_admin_register_user, // 管理员注册用户
             auth::admin_commands::auth_admin_renew_user_account, // 管理员续期账号
             auth::admin_commands::auth_admin_list_users, // 管理员列出用户
             auth::admin_commands::auth_admin_update_user, // 管理员更新用户
             auth::admin_commands::auth_admin_delete_user, // 管理员删除用户
+            auth::admin_commands::auth_admin_restore_user, // 管理员恢复已删除用户
+            auth::admin_commands::auth_admin_purge_deleted_users, // 管理员清理已删除用户
             auth::admin_commands::auth_admin_change_user_password, // 管理员修改密码
+            auth::admin_commands::auth_admin_batch_renew_users, // 管理员批量续期账号
+            auth::admin_commands::auth_admin_batch_set_users_active, // 管理员批量启停用
+            auth::admin_commands::auth_admin_batch_add_user_roles, // 管理员批量增加角色
+            auth::admin_commands::auth_admin_batch_remove_user_roles, // 管理员批量移除角色
+            auth::admin_commands::auth_admin_batch_set_users_expiry, // 管理员批量设置到期时间
+            auth::admin_commands::auth_admin_set_user_delegation, // 管理员设置委派管理员
+            auth::admin_commands::auth_admin_remove_user_delegation, // 管理员撤销委派管理员
+            auth::admin_commands::auth_admin_list_user_delegations, // 管理员查询委派管理员
             auth::admin_commands::user_device_scope_get, // 获取用户设备权限
             auth::admin_commands::user_device_scope_upsert, // 更新用户设备权限
+            auth::admin_commands::user_device_scope_check, // 判定用户能否访问设备
+            audit::commands::audit_query, // 查询审计事件
+            audit::commands::audit_verify_chain, // 校验审计哈希链
+            organization::commands::organization_list, // 查询组织树
+            organization::commands::organization_create, // 创建组织
+            organization::commands::organization_update, // 更新组织
+            organization::commands::organization_delete, // 删除组织
+            organization::commands::organization_assign_users, // 设置用户所属组织
+            location::commands::location_list, // 查询位置树
+            location::commands::location_create, // 创建位置
+            location::commands::location_update, // 更新位置
+            location::commands::location_move, // 移动位置
+            location::commands::location_delete, // 删除位置子树
+            location::commands::location_list_devices, // 查询位置子树设备
+            device::commands::device_list, // 分页查询设备
+            device::commands::device_get, // 查询单个设备
+            device::commands::device_create, // 创建设备
+            device::commands::device_update, // 修改设备
+            device::commands::device_delete, // 删除设备
+            device_lifecycle::commands::device_lifecycle_states, // 查询设备生命周期状态定义
+            device_lifecycle::commands::device_lifecycle_transition, // 流转设备生命周期状态
+            device_lifecycle::commands::device_lifecycle_bulk_transition, // 批量流转设备生命周期状态
+            device_lifecycle::commands::device_lifecycle_history, // 查询设备生命周期流转历史
+            device_tag::commands::device_tag_list, // 查询设备标签
+            device_tag::commands::device_tag_set, // 设置设备标签
+            device_tag::commands::device_tag_delete, // 删除设备标签
+            device_tag::commands::device_tag_bulk_update, // 批量打标签
+            device_tag::commands::device_tag_select, // 按标签选择器查询设备
+            device_template::commands::device_template_list, // 查询设备模板列表
+            device_template::commands::device_template_get, // 查询设备模板详情
+            device_template::commands::device_template_create, // 创建设备模板
+            device_template::commands::device_template_update, // 修改设备模板
+            device_template::commands::device_template_delete, // 删除设备模板
+            device_template::commands::device_template_export, // 导出设备模板
+            device_template::commands::device_template_import, // 导入设备模板
+            device_template::commands::device_template_apply, // 为设备应用模板
+            device_template::commands::device_template_propagate, // 同步模板到关联设备
+            device_template::commands::device_point_list, // 查询设备点位
+            device_template::commands::device_point_override, // 设置设备点位覆盖
+            modbus::commands::modbus_tcp_connect, // 建立 Modbus TCP 网关连接
+            modbus::commands::modbus_rtu_connect, // 建立 Modbus RTU 串口网关连接
+            modbus::commands::modbus_serial_port_list, // 查询本机可用串口
+            modbus::commands::modbus_disconnect, // 断开 Modbus 网关连接
+            modbus::commands::modbus_connection_list, // 查询 Modbus 网关连接状态
+            modbus::commands::modbus_read_coils, // 读线圈
+            modbus::commands::modbus_read_discrete_inputs, // 读离散输入
+            modbus::commands::modbus_read_holding_registers, // 读保持寄存器
+            modbus::commands::modbus_read_input_registers, // 读输入寄存器
+            modbus::commands::modbus_write_single_coil, // 写单个线圈
+            modbus::commands::modbus_write_single_register, // 写单个寄存器
+            modbus::commands::modbus_write_multiple_coils, // 写多个线圈
+            modbus::commands::modbus_write_multiple_registers, // 写多个寄存器
+            modbus::commands::modbus_simulator_start, // 启动 Modbus 从站模拟器
+            modbus::commands::modbus_simulator_stop, // 停止 Modbus 从站模拟器
+            modbus::commands::modbus_simulator_list, // 查询 Modbus 从站模拟器状态
+            modbus::commands::modbus_simulator_set_faults, // 调整 Modbus 从站模拟器故障注入
+            modbus::commands::modbus_diagnostics, // 查询 Modbus 通信诊断
+            modbus::commands::modbus_diagnostics_reset, // 清零 Modbus 通信诊断
+            modbus::commands::modbus_capture_start, // 开启 Modbus 报文捕获
+            modbus::commands::modbus_capture_stop, // 停止 Modbus 报文捕获
+            modbus::commands::modbus_capture_export, // 导出 Modbus 捕获报文
+            gateway::commands::gateway_list, // 查询网关列表
+            gateway::commands::gateway_get, // 查询网关详情
+            gateway::commands::gateway_create, // 创建网关
+            gateway::commands::gateway_update, // 修改网关
+            gateway::commands::gateway_delete, // 删除网关
+            gateway::commands::gateway_test_connection, // 测试网关连接
+            gateway::commands::gateway_slave_list, // 查询网关下的从站
+            gateway::commands::gateway_slave_create, // 创建从站
+            gateway::commands::gateway_slave_update, // 修改从站
+            gateway::commands::gateway_slave_delete, // 删除从站（点位一并删除）
+            gateway::commands::gateway_point_list, // 查询从站下的点位
+            gateway::commands::gateway_point_create, // 创建点位
+            gateway::commands::gateway_point_update, // 修改点位
+            gateway::commands::gateway_point_delete, // 删除点位
+            gateway::commands::gateway_point_read, // 点位读测试
+            gateway::commands::gateway_point_write, // 点位写测试
+            acquisition::commands::acquisition_start, // 启动网关采集
+            acquisition::commands::acquisition_stop, // 停止网关采集
+            acquisition::commands::acquisition_status, // 查询采集状态
+            acquisition::commands::acquisition_values, // 查询最新点位值
+            acquisition::commands::acquisition_subscribe, // 订阅采集值变化事件
+            acquisition::commands::acquisition_unsubscribe, // 取消采集值订阅
+            acquisition::commands::acquisition_subscription_list, // 查询采集值订阅
+            acquisition::commands::acquisition_snapshot, // 查询采集值快照
             notice::commands::notice_get_unread_items, // 获取未读通知
             notice::commands::notice_get_read_items, // 获取已读通知
             notice::commands::notice_mark_read // 标记通知已读
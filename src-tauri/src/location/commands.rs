@@ -0,0 +1,401 @@
+//! 空间位置模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的空间位置相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `location_list` | 查询位置树或指定子树（含设备数） |
+//! | `location_create` | 创建位置 |
+//! | `location_update` | 更新位置编码、名称、排序号与备注 |
+//! | `location_move` | 移动位置到其他上级位置 |
+//! | `location_delete` | 删除不含设备的位置子树 |
+//! | `location_list_devices` | 查询位置子树内挂载的设备 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入位置数据模型
+use crate::location::models::{
+    LocationCreatePayload, LocationData, LocationDeletePayload, LocationDeviceData,
+    LocationDevicesPayload, LocationListPayload, LocationMovePayload, LocationNodeData,
+    LocationUpdatePayload,
+};
+// 引入位置服务层
+use crate::location::services;
+
+/// 查询位置树
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的子树根位置 ID
+///
+/// # 返回
+/// * 根位置列表，节点递归包含下级位置
+#[tauri::command]
+pub fn location_list(
+    payload: LocationListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<LocationNodeData>> {
+    execute_traced_command("location_list", trace, || {
+        Ok(ApiResponse::ok(services::list_location_tree(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建位置
+///
+/// # 参数
+/// * `payload` - 上级位置、层级、编码、名称、排序号与备注
+///
+/// # 返回
+/// * 新建的位置
+#[tauri::command]
+pub fn location_create(
+    payload: LocationCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<LocationData> {
+    execute_traced_command("location_create", trace, || {
+        Ok(ApiResponse::ok(services::create_location(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 更新位置
+///
+/// # 参数
+/// * `payload` - 位置 ID 及更新后的编码、名称、排序号与备注
+///
+/// # 返回
+/// * 更新后的位置
+#[tauri::command]
+pub fn location_update(
+    payload: LocationUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<LocationData> {
+    execute_traced_command("location_update", trace, || {
+        Ok(ApiResponse::ok(services::update_location(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 移动位置
+///
+/// # 参数
+/// * `payload` - 位置 ID、新的上级位置 ID（为空表示根层级）与可选排序号
+///
+/// # 返回
+/// * 移动后的位置
+#[tauri::command]
+pub fn location_move(
+    payload: LocationMovePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<LocationData> {
+    execute_traced_command("location_move", trace, || {
+        Ok(ApiResponse::ok(services::move_location(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除位置
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名与位置 ID 的请求体
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn location_delete(
+    payload: LocationDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("location_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_location(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询位置子树内的设备
+///
+/// # 参数
+/// * `payload` - 操作员用户名与子树根位置 ID
+///
+/// # 返回
+/// * 子树内挂载的设备列表
+#[tauri::command]
+pub fn location_list_devices(
+    payload: LocationDevicesPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<LocationDeviceData>> {
+    execute_traced_command("location_list_devices", trace, || {
+        Ok(ApiResponse::ok(services::list_location_devices(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use crate::db;
+    use crate::db::test_support::{ensure_test_db_ready, unique_code};
+
+    fn create(parent_id: Option<i64>, prefix: &str, level: &str) -> AppResult<LocationData> {
+        location_create(
+            LocationCreatePayload {
+                operator_username: "admin".to_string(),
+                parent_id,
+                level: level.to_string(),
+                code: unique_code(prefix),
+                name: format!("{prefix} 名称"),
+                remark: Some("  ".to_string()),
+                ..LocationCreatePayload::default()
+            },
+            None,
+        )
+    }
+
+    fn move_to(location_id: i64, parent_id: Option<i64>) -> AppResult<LocationData> {
+        location_move(
+            LocationMovePayload {
+                operator_username: "admin".to_string(),
+                location_id,
+                parent_id,
+                sort_order: None,
+            },
+            None,
+        )
+    }
+
+    // 辅助函数：写入一台挂载在指定位置的设备，返回设备标识
+    fn insert_device(location_id: i64) -> String {
+        let device_id = unique_code("location_device");
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query(
+                r"
+                INSERT INTO device_registry (device_id, device_name, owner_username, registered_at, location_id)
+                VALUES ($1, $1, 'admin', 1, $2)
+                ",
+            )
+            .bind(&device_id)
+            .bind(location_id)
+            .execute(&mut connection),
+        )
+        .expect("insert device");
+        device_id
+    }
+
+    fn remove_device(device_id: &str) {
+        let mut connection = db::connect().expect("open db");
+        db::block_on(
+            sqlx::query("DELETE FROM device_registry WHERE device_id = $1")
+                .bind(device_id)
+                .execute(&mut connection),
+        )
+        .expect("delete device");
+    }
+
+    #[test]
+    fn builds_subtree_with_device_counts() {
+        ensure_test_db_ready();
+        let site = create(None, "tree_site", "Site").expect("create site").data;
+        let building = create(Some(site.id), "tree_building", "building")
+            .expect("building")
+            .data;
+        let area = create(Some(building.id), "tree_area", "area")
+            .expect("area")
+            .data;
+        let floor = create(Some(area.id), "tree_floor", "floor")
+            .expect("floor")
+            .data;
+        let room = create(Some(floor.id), "tree_room", "room")
+            .expect("room")
+            .data;
+        let other_area = create(Some(building.id), "tree_other", "area")
+            .expect("other")
+            .data;
+        assert_eq!(site.level, "site");
+        assert!(site.remark.is_none());
+
+        let floor_device = insert_device(floor.id);
+        let room_device = insert_device(room.id);
+        insert_device(other_area.id);
+
+        let tree = location_list(
+            LocationListPayload {
+                operator_username: "admin".to_string(),
+                root_id: Some(building.id),
+            },
+            None,
+        )
+        .expect("list subtree")
+        .data;
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].location.id, building.id);
+        assert_eq!(tree[0].subtree_device_count, 3);
+        assert_eq!(tree[0].children.len(), 2);
+        let area_node = tree[0]
+            .children
+            .iter()
+            .find(|node| node.location.id == area.id)
+            .expect("area node");
+        assert_eq!(area_node.subtree_device_count, 2);
+        assert_eq!(area_node.children[0].location.device_count, 1);
+
+        let devices = location_list_devices(
+            LocationDevicesPayload {
+                operator_username: "admin".to_string(),
+                location_id: area.id,
+            },
+            None,
+        )
+        .expect("list devices")
+        .data;
+        let mut device_ids: Vec<String> = devices.into_iter().map(|item| item.device_id).collect();
+        device_ids.sort();
+        let mut expected = vec![floor_device, room_device];
+        expected.sort();
+        assert_eq!(device_ids, expected);
+    }
+
+    #[test]
+    fn create_and_move_enforce_level_order() {
+        ensure_test_db_ready();
+        let building = create(None, "order_building", "building")
+            .expect("building")
+            .data;
+        let floor = create(Some(building.id), "order_floor", "floor")
+            .expect("floor")
+            .data;
+        let area = create(None, "order_area", "area").expect("area").data;
+
+        assert_eq!(
+            create(Some(floor.id), "order_bad", "area").expect_err("area under floor"),
+            AppError::Validation("location level area cannot be placed under floor".to_string())
+        );
+        assert_eq!(
+            create(None, "order_unknown", "zone").expect_err("unknown level"),
+            AppError::Validation(
+                "level must be one of: site, building, area, floor, room".to_string()
+            )
+        );
+
+        // 区域移动到楼栋之下，楼层再移动到区域之下
+        let moved = move_to(area.id, Some(building.id)).expect("move area").data;
+        assert_eq!(moved.parent_id, Some(building.id));
+        move_to(floor.id, Some(area.id)).expect("move floor");
+
+        // 移动到自身下级会违反层级顺序
+        assert_eq!(
+            move_to(building.id, Some(floor.id)).expect_err("cycle rejected"),
+            AppError::Validation(
+                "location level building cannot be placed under floor".to_string()
+            )
+        );
+        let root = move_to(floor.id, None).expect("move to root").data;
+        assert!(root.parent_id.is_none());
+    }
+
+    #[test]
+    fn update_rejects_duplicate_code() {
+        ensure_test_db_ready();
+        let first = create(None, "dup_first", "site").expect("first").data;
+        let second = create(None, "dup_second", "site").expect("second").data;
+
+        let update = |code: &str| {
+            location_update(
+                LocationUpdatePayload {
+                    operator_username: "admin".to_string(),
+                    location_id: second.id,
+                    code: code.to_string(),
+                    name: " 新名称 ".to_string(),
+                    sort_order: 5,
+                    remark: Some("备注".to_string()),
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            update(&first.code).expect_err("duplicate code"),
+            AppError::Validation("location code already exists".to_string())
+        );
+        assert_eq!(
+            update("bad code").expect_err("invalid code"),
+            AppError::Validation(
+                "code must be at most 64 letters, digits, '_', '-' or '.'".to_string()
+            )
+        );
+
+        let code = unique_code("dup_renamed");
+        let updated = update(&code).expect("update").data;
+        assert_eq!(updated.code, code);
+        assert_eq!(updated.name, "新名称");
+        assert_eq!(updated.sort_order, 5);
+        assert_eq!(updated.level, "site");
+    }
+
+    #[test]
+    fn delete_rejects_subtree_with_devices() {
+        ensure_test_db_ready();
+        let site = create(None, "delete_site", "site").expect("site").data;
+        let floor = create(Some(site.id), "delete_floor", "floor")
+            .expect("floor")
+            .data;
+        let device_id = insert_device(floor.id);
+
+        let delete = |location_id: i64| {
+            location_delete(
+                LocationDeletePayload {
+                    operator_username: "admin".to_string(),
+                    location_id,
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            delete(site.id).expect_err("subtree has devices"),
+            AppError::Validation("location subtree has devices: 1".to_string())
+        );
+
+        remove_device(&device_id);
+        assert!(delete(site.id).expect("delete subtree").data);
+        assert_eq!(
+            delete(floor.id).expect_err("descendant deleted"),
+            AppError::Validation("location not found".to_string())
+        );
+    }
+
+    #[test]
+    fn non_admin_cannot_manage_locations() {
+        ensure_test_db_ready();
+        let err = location_create(
+            LocationCreatePayload {
+                operator_username: "common".to_string(),
+                level: "site".to_string(),
+                code: unique_code("forbidden"),
+                name: "禁止".to_string(),
+                ..LocationCreatePayload::default()
+            },
+            None,
+        )
+        .expect_err("expect forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: location manage required".to_string())
+        );
+    }
+}
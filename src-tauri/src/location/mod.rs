@@ -0,0 +1,15 @@
+//! 空间位置模块入口
+//!
+//! 本模块维护空间位置树（场所 → 楼栋 → 区域 → 楼层 → 房间）及设备所在位置：
+//! - 位置节点携带层级、全局唯一编码、名称与同级排序号，下级层级必须比上级更深
+//! - 设备通过 `device_registry.location_id` 挂载到位置节点，子树内仍有设备时禁止删除
+//! - 用户设备范围中的区域/楼层编码即 area / floor 层级的位置编码，按区域统计的报表以位置子树为范围
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 位置请求/响应结构
+pub mod models;
+// 公开服务模块 - 其他业务模块校验位置层级时使用
+pub mod services;
+// 公开仓储模块 - 其他业务模块通过 list_subtree / list_subtree_devices 解析位置范围
+pub mod repository;
@@ -0,0 +1,185 @@
+//! 空间位置模块数据模型
+//!
+//! 本模块定义位置树的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+/// 位置节点存储记录
+///
+/// 与 location_nodes 表对应，`device_count` 为直接挂载在该节点的设备数（查询时统计）
+#[derive(Debug, Clone, Default)]
+pub struct LocationRecord {
+    pub id: i64,                // 位置 ID
+    pub parent_id: Option<i64>, // 上级位置 ID（根节点为 None）
+    pub level: String,          // 位置层级（site / building / area / floor / room）
+    pub code: String,           // 位置编码（全局唯一）
+    pub name: String,           // 位置名称
+    pub sort_order: i32,        // 同级排序号
+    pub remark: Option<String>, // 备注
+    pub created_at: i64,        // 创建时间戳（毫秒）
+    pub updated_at: i64,        // 更新时间戳（毫秒）
+    pub created_by: String,     // 创建人
+    pub device_count: i64,      // 直接挂载的设备数
+}
+
+/// 位置写入参数（创建与更新共用的可编辑字段，已完成规范化）
+#[derive(Debug, Clone, Default)]
+pub struct LocationInput {
+    pub code: String,           // 位置编码
+    pub name: String,           // 位置名称
+    pub sort_order: i32,        // 同级排序号
+    pub remark: Option<String>, // 备注
+}
+
+/// 位置子树内的设备记录
+#[derive(Debug, Clone, Default)]
+pub struct LocationDeviceRecord {
+    pub device_id: String,      // 设备标识
+    pub device_name: String,    // 设备名称
+    pub owner_username: String, // 设备归属用户名
+    pub location_id: i64,       // 所在位置 ID
+    pub location_code: String,  // 所在位置编码
+    pub location_name: String,  // 所在位置名称
+}
+
+// 位置树查询请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 子树根位置 ID（为空表示查询整棵位置树）
+    pub root_id: Option<i64>,
+}
+
+// 创建位置请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 上级位置 ID（为空表示创建根位置）
+    pub parent_id: Option<i64>,
+    /// 位置层级（site / building / area / floor / room）
+    pub level: String,
+    /// 位置编码（全局唯一）
+    pub code: String,
+    /// 位置名称
+    pub name: String,
+    /// 同级排序号（升序）
+    pub sort_order: i32,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 更新位置请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 位置 ID
+    pub location_id: i64,
+    /// 位置编码
+    pub code: String,
+    /// 位置名称
+    pub name: String,
+    /// 同级排序号
+    pub sort_order: i32,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 移动位置请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationMovePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 位置 ID
+    pub location_id: i64,
+    /// 新的上级位置 ID（为空表示移动到根层级）
+    pub parent_id: Option<i64>,
+    /// 新的同级排序号（为空表示保持不变）
+    pub sort_order: Option<i32>,
+}
+
+// 删除位置请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 位置 ID（连同其下级位置一起删除）
+    pub location_id: i64,
+}
+
+// 查询位置子树设备请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocationDevicesPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 子树根位置 ID
+    pub location_id: i64,
+}
+
+// 位置响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationData {
+    /// 位置 ID
+    pub id: i64,
+    /// 上级位置 ID
+    pub parent_id: Option<i64>,
+    /// 位置层级
+    pub level: String,
+    /// 位置编码
+    pub code: String,
+    /// 位置名称
+    pub name: String,
+    /// 同级排序号
+    pub sort_order: i32,
+    /// 备注
+    pub remark: Option<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+    /// 创建人
+    pub created_by: String,
+    /// 直接挂载的设备数（不含下级位置）
+    pub device_count: i64,
+}
+
+// 位置树节点响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationNodeData {
+    /// 位置信息
+    #[serde(flatten)]
+    pub location: LocationData,
+    /// 子树设备总数（含自身及全部下级位置）
+    pub subtree_device_count: i64,
+    /// 下级位置（按排序号、编码排序）
+    pub children: Vec<LocationNodeData>,
+}
+
+// 位置子树设备响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationDeviceData {
+    /// 设备标识
+    pub device_id: String,
+    /// 设备名称
+    pub device_name: String,
+    /// 设备归属用户名
+    pub owner_username: String,
+    /// 所在位置 ID
+    pub location_id: i64,
+    /// 所在位置编码
+    pub location_code: String,
+    /// 所在位置名称
+    pub location_name: String,
+}
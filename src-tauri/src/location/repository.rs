@@ -0,0 +1,380 @@
+//! 空间位置模块数据仓储层
+//!
+//! 本模块负责 location_nodes 表的读写：
+//! - 位置节点的增删改查（含直接挂载的设备数统计）
+//! - 递归查询位置子树及子树内的设备
+//! - 移动位置节点、删除不含设备的位置子树（单事务）
+//!
+//! 位置树查询依赖递归 CTE，使用原生 SQL（SQLx）实现
+
+// 引入 SQLx 查询类型
+use sqlx::postgres::PgRow;
+use sqlx::{Row, query, query_scalar};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入位置模型
+use crate::location::models::{LocationDeviceRecord, LocationInput, LocationRecord};
+
+// 位置查询列（顺序与 map_location_row 对应）
+const LOCATION_COLUMNS: &str = "n.id, n.parent_id, n.level, n.code, n.name, n.sort_order, \
+     n.remark, n.created_at, n.updated_at, n.created_by, \
+     (SELECT COUNT(*) FROM device_registry d WHERE d.location_id = n.id) AS device_count";
+
+// 位置子树递归 CTE（$1 为子树根位置 ID，结果含根节点自身）
+const SUBTREE_CTE: &str = r"
+    WITH RECURSIVE subtree(id) AS (
+      SELECT id FROM location_nodes WHERE id = $1
+      UNION ALL
+      SELECT c.id FROM location_nodes c JOIN subtree s ON c.parent_id = s.id
+    )";
+
+/// 查询全部位置
+///
+/// # 返回
+/// * 按上级位置、排序号、编码排序的位置记录
+pub fn list_locations() -> Result<Vec<LocationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!(
+            "SELECT {LOCATION_COLUMNS} FROM location_nodes n \
+             ORDER BY n.parent_id NULLS FIRST, n.sort_order, n.code"
+        );
+        let rows = query(&sql)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_location_row).collect()
+    })
+}
+
+/// 查询位置子树（含自身）的全部位置
+///
+/// # 参数
+/// * `location_id` - 子树根位置 ID
+///
+/// # 返回
+/// * 子树内的位置记录（位置不存在时为空）
+pub fn list_subtree(location_id: i64) -> Result<Vec<LocationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!(
+            "{SUBTREE_CTE} SELECT {LOCATION_COLUMNS} FROM location_nodes n \
+             JOIN subtree s ON s.id = n.id \
+             ORDER BY n.parent_id NULLS FIRST, n.sort_order, n.code"
+        );
+        let rows = query(&sql)
+            .bind(location_id)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_location_row).collect()
+    })
+}
+
+/// 查询位置子树（含自身）内挂载的全部设备
+///
+/// # 参数
+/// * `location_id` - 子树根位置 ID
+///
+/// # 返回
+/// * 按设备标识排序的设备记录
+pub fn list_subtree_devices(location_id: i64) -> Result<Vec<LocationDeviceRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!(
+            "{SUBTREE_CTE} SELECT d.device_id, d.device_name, d.owner_username, n.id, n.code, n.name \
+             FROM device_registry d \
+             JOIN subtree s ON s.id = d.location_id \
+             JOIN location_nodes n ON n.id = d.location_id \
+             ORDER BY d.device_id"
+        );
+        let rows = query(&sql)
+            .bind(location_id)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_location_device_row).collect()
+    })
+}
+
+/// 按 ID 查询位置
+///
+/// # 参数
+/// * `location_id` - 位置 ID
+///
+/// # 返回
+/// * 位置记录（不存在时为 None）
+pub fn find_location(location_id: i64) -> Result<Option<LocationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!("SELECT {LOCATION_COLUMNS} FROM location_nodes n WHERE n.id = $1");
+        let row = query(&sql)
+            .bind(location_id)
+            .fetch_optional(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        row.as_ref().map(map_location_row).transpose()
+    })
+}
+
+/// 创建位置
+///
+/// # 参数
+/// * `parent_id` - 上级位置 ID（根节点为 None）
+/// * `level` - 位置层级
+/// * `input` - 位置写入参数
+/// * `created_by` - 创建人用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的位置记录
+pub fn insert_location(
+    parent_id: Option<i64>,
+    level: &str,
+    input: &LocationInput,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<LocationRecord, AppError> {
+    let location_id = db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query_scalar(
+            r"
+            INSERT INTO location_nodes (
+              parent_id, level, code, name, sort_order, remark, created_at, updated_at, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $7, $8)
+            RETURNING id
+            ",
+        )
+        .bind(parent_id)
+        .bind(level)
+        .bind(&input.code)
+        .bind(&input.name)
+        .bind(input.sort_order)
+        .bind(&input.remark)
+        .bind(now_millis)
+        .bind(created_by)
+        .fetch_one(&mut connection)
+        .await
+        .map_err(|err| map_location_mutation_error(&err))
+    })?;
+    find_location(location_id)?
+        .ok_or_else(|| AppError::Database("created location not found".to_string()))
+}
+
+/// 更新位置的编码、名称、排序号与备注
+///
+/// 编码变更会级联更新用户设备范围中引用该编码的明细
+///
+/// # 参数
+/// * `location_id` - 位置 ID
+/// * `input` - 位置写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新后的位置记录（位置不存在时为 None）
+pub fn update_location(
+    location_id: i64,
+    input: &LocationInput,
+    now_millis: i64,
+) -> Result<Option<LocationRecord>, AppError> {
+    let updated = db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query(
+            r"
+            UPDATE location_nodes
+            SET code = $2, name = $3, sort_order = $4, remark = $5, updated_at = $6
+            WHERE id = $1
+            ",
+        )
+        .bind(location_id)
+        .bind(&input.code)
+        .bind(&input.name)
+        .bind(input.sort_order)
+        .bind(&input.remark)
+        .bind(now_millis)
+        .execute(&mut connection)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(|err| map_location_mutation_error(&err))
+    })?;
+    if updated {
+        find_location(location_id)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 移动位置到新的上级位置
+///
+/// # 参数
+/// * `location_id` - 位置 ID
+/// * `parent_id` - 新的上级位置 ID（None 表示移动到根层级）
+/// * `sort_order` - 新的同级排序号
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 移动后的位置记录（位置不存在时为 None）
+pub fn move_location(
+    location_id: i64,
+    parent_id: Option<i64>,
+    sort_order: i32,
+    now_millis: i64,
+) -> Result<Option<LocationRecord>, AppError> {
+    let moved = db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query(
+            "UPDATE location_nodes SET parent_id = $2, sort_order = $3, updated_at = $4 WHERE id = $1",
+        )
+        .bind(location_id)
+        .bind(parent_id)
+        .bind(sort_order)
+        .bind(now_millis)
+        .execute(&mut connection)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(|err| map_location_mutation_error(&err))
+    })?;
+    if moved {
+        find_location(location_id)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 删除位置及其全部下级位置
+///
+/// 子树内仍挂载设备时拒绝删除；引用被删除位置编码的用户设备范围明细级联删除
+///
+/// # 参数
+/// * `location_id` - 子树根位置 ID
+///
+/// # 返回
+/// * 被删除的位置数（位置不存在时为 0）
+pub fn delete_location_subtree(location_id: i64) -> Result<u64, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        // 锁定整棵子树，防止并发挂载设备或下级位置
+        let locked: Vec<i64> = query_scalar(&format!(
+            "{SUBTREE_CTE} SELECT n.id FROM location_nodes n \
+             WHERE n.id IN (SELECT id FROM subtree) FOR UPDATE"
+        ))
+        .bind(location_id)
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+        if locked.is_empty() {
+            return Ok(0);
+        }
+
+        let device_count: i64 =
+            query_scalar("SELECT COUNT(*) FROM device_registry WHERE location_id = ANY($1)")
+                .bind(&locked)
+                .fetch_one(&mut *transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        if device_count > 0 {
+            return Err(AppError::Validation(format!(
+                "location subtree has devices: {device_count}"
+            )));
+        }
+
+        // 单条语句删除整棵子树，上下级外键在语句结束时统一校验
+        let deleted = query("DELETE FROM location_nodes WHERE id = ANY($1)")
+            .bind(&locked)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| map_location_mutation_error(&err))?
+            .rows_affected();
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(deleted)
+    })
+}
+
+/// 将位置查询的一行转换为位置记录
+fn map_location_row(row: &PgRow) -> Result<LocationRecord, AppError> {
+    Ok(LocationRecord {
+        id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        parent_id: row
+            .try_get(1)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        level: row
+            .try_get(2)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        code: row
+            .try_get(3)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        name: row
+            .try_get(4)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        sort_order: row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        remark: row
+            .try_get(6)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_at: row
+            .try_get(7)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_at: row
+            .try_get(8)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_by: row
+            .try_get(9)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        device_count: row
+            .try_get(10)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    })
+}
+
+/// 将位置子树设备查询的一行转换为设备记录
+fn map_location_device_row(row: &PgRow) -> Result<LocationDeviceRecord, AppError> {
+    Ok(LocationDeviceRecord {
+        device_id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        device_name: row
+            .try_get(1)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        owner_username: row
+            .try_get(2)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        location_id: row
+            .try_get(3)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        location_code: row
+            .try_get(4)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        location_name: row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    })
+}
+
+/// 将位置写入错误转换为业务错误（编码重复、上级位置不存在、仍有设备挂载）
+fn map_location_mutation_error(err: &sqlx::Error) -> AppError {
+    let message = err.to_string();
+    if message.contains("location_nodes_code_key") {
+        return AppError::Validation("location code already exists".to_string());
+    }
+    if message.contains("location_nodes_parent_id_fkey") {
+        return AppError::Validation("parent location not found".to_string());
+    }
+    if message.contains("device_registry_location_id_fkey") {
+        return AppError::Validation("location subtree has devices".to_string());
+    }
+    AppError::Database(message)
+}
@@ -0,0 +1,493 @@
+//! 空间位置模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 位置树的组装与增删改、移动（层级顺序、编码格式、子树含设备时禁止删除）
+//! - 位置子树内设备的查询
+//! - 位置管理操作的权限校验（`location:view` / `location:manage`）与审计记录
+
+// 引入哈希映射（按上级位置分组）
+use std::collections::HashMap;
+
+// 引入 JSON 值类型
+use serde_json::Value;
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入位置模型
+use crate::location::models::{
+    LocationCreatePayload, LocationData, LocationDeletePayload, LocationDeviceData,
+    LocationDevicesPayload, LocationInput, LocationListPayload, LocationMovePayload,
+    LocationNodeData, LocationRecord, LocationUpdatePayload,
+};
+// 引入位置仓储模块
+use crate::location::repository;
+
+// 审计目标类型：空间位置
+const TARGET_TYPE_LOCATION: &str = "location";
+
+/// 位置层级（由浅到深），下级位置的层级必须比上级位置更深
+pub const LOCATION_LEVELS: [&str; 5] = ["site", "building", "area", "floor", "room"];
+
+// 位置编码最大长度
+const MAX_CODE_LENGTH: usize = 64;
+
+/// 查询位置树
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的子树根位置 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 根位置列表（指定子树时仅包含该位置），每个节点递归包含下级位置
+pub fn list_location_tree(
+    payload: &LocationListPayload,
+    now_millis: u64,
+) -> Result<Vec<LocationNodeData>, AppError> {
+    assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: location view required",
+        now_millis,
+    )?;
+    let Some(root_id) = payload.root_id else {
+        return Ok(build_tree(repository::list_locations()?, None));
+    };
+    let records = repository::list_subtree(root_id)?;
+    let Some(root) = records.iter().find(|record| record.id == root_id) else {
+        return Err(AppError::Validation("location not found".to_string()));
+    };
+    let root_parent_id = root.parent_id;
+    Ok(build_tree(records, root_parent_id))
+}
+
+/// 查询位置子树（含自身）内挂载的设备
+///
+/// # 参数
+/// * `payload` - 操作员用户名与子树根位置 ID
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按设备标识排序的设备列表
+pub fn list_location_devices(
+    payload: &LocationDevicesPayload,
+    now_millis: u64,
+) -> Result<Vec<LocationDeviceData>, AppError> {
+    assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: location view required",
+        now_millis,
+    )?;
+    ensure_location_exists(payload.location_id)?;
+    Ok(repository::list_subtree_devices(payload.location_id)?
+        .into_iter()
+        .map(|record| LocationDeviceData {
+            device_id: record.device_id,
+            device_name: record.device_name,
+            owner_username: record.owner_username,
+            location_id: record.location_id,
+            location_code: record.location_code,
+            location_name: record.location_name,
+        })
+        .collect())
+}
+
+/// 创建位置
+///
+/// # 参数
+/// * `payload` - 创建请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的位置
+pub fn create_location(
+    payload: LocationCreatePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let result = create_location_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "location_create",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_LOCATION,
+            target_id: result.as_ref().ok().map(|data| data.id.to_string()),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 更新位置的编码、名称、排序号与备注
+///
+/// # 参数
+/// * `payload` - 更新请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新后的位置
+pub fn update_location(
+    payload: LocationUpdatePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let location_id = payload.location_id;
+    let before = find_snapshot(location_id);
+    let result = update_location_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "location_update",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_LOCATION,
+            target_id: Some(location_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 移动位置到新的上级位置
+///
+/// # 参数
+/// * `payload` - 移动请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 移动后的位置
+pub fn move_location(
+    payload: &LocationMovePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let before = find_snapshot(payload.location_id);
+    let result = move_location_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "location_move",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_LOCATION,
+            target_id: Some(payload.location_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除位置及其全部下级位置
+///
+/// 子树内仍挂载设备时拒绝删除
+///
+/// # 参数
+/// * `payload` - 删除请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_location(payload: &LocationDeletePayload, now_millis: u64) -> Result<bool, AppError> {
+    let before = find_snapshot(payload.location_id);
+    let result = delete_location_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "location_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_LOCATION,
+            target_id: Some(payload.location_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 创建位置（不含审计记录）
+fn create_location_unaudited(
+    payload: LocationCreatePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: location manage required",
+        now_millis,
+    )?;
+    let level = payload.level.trim().to_lowercase();
+    if level_rank(&level).is_none() {
+        return Err(AppError::Validation(format!(
+            "level must be one of: {}",
+            LOCATION_LEVELS.join(", ")
+        )));
+    }
+    let input = normalize_input(
+        &payload.code,
+        &payload.name,
+        payload.sort_order,
+        payload.remark,
+    )?;
+    if let Some(parent_id) = payload.parent_id {
+        ensure_parent_accepts(parent_id, &level)?;
+    }
+    let record = repository::insert_location(
+        payload.parent_id,
+        &level,
+        &input,
+        payload.operator_username.trim(),
+        now,
+    )?;
+    Ok(map_location_record(record))
+}
+
+// 更新位置（不含审计记录）
+fn update_location_unaudited(
+    payload: LocationUpdatePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: location manage required",
+        now_millis,
+    )?;
+    if payload.location_id <= 0 {
+        return Err(AppError::Validation("locationId is required".to_string()));
+    }
+    let input = normalize_input(
+        &payload.code,
+        &payload.name,
+        payload.sort_order,
+        payload.remark,
+    )?;
+    repository::update_location(payload.location_id, &input, now)?
+        .map(map_location_record)
+        .ok_or_else(|| AppError::Validation("location not found".to_string()))
+}
+
+// 移动位置（不含审计记录）
+fn move_location_unaudited(
+    payload: &LocationMovePayload,
+    now_millis: u64,
+) -> Result<LocationData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: location manage required",
+        now_millis,
+    )?;
+    let current = ensure_location_exists(payload.location_id)?;
+    // 下级位置的层级总是比上级更深，因此层级校验同时排除了移动到自身或下级位置之下形成的环
+    if let Some(parent_id) = payload.parent_id {
+        ensure_parent_accepts(parent_id, &current.level)?;
+    }
+    repository::move_location(
+        payload.location_id,
+        payload.parent_id,
+        payload.sort_order.unwrap_or(current.sort_order),
+        now,
+    )?
+    .map(map_location_record)
+    .ok_or_else(|| AppError::Validation("location not found".to_string()))
+}
+
+// 删除位置（不含审计记录）
+fn delete_location_unaudited(
+    payload: &LocationDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: location manage required",
+        now_millis,
+    )?;
+    if payload.location_id <= 0 {
+        return Err(AppError::Validation("locationId is required".to_string()));
+    }
+    if repository::delete_location_subtree(payload.location_id)? == 0 {
+        return Err(AppError::Validation("location not found".to_string()));
+    }
+    Ok(true)
+}
+
+/// 验证操作员是否具有位置权限
+///
+/// # 返回
+/// * 转换为 i64 的当前时间戳（毫秒）
+fn assert_operator_allowed(
+    operator_username: &str,
+    action: &str,
+    forbidden_message: &str,
+    now_millis: u64,
+) -> Result<i64, AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        rbac::RESOURCE_LOCATION,
+        action,
+        now_millis,
+        forbidden_message,
+    )?;
+    Ok(now_millis)
+}
+
+/// 查询位置，不存在时返回 "location not found"
+fn ensure_location_exists(location_id: i64) -> Result<LocationRecord, AppError> {
+    if location_id <= 0 {
+        return Err(AppError::Validation("locationId is required".to_string()));
+    }
+    repository::find_location(location_id)?
+        .ok_or_else(|| AppError::Validation("location not found".to_string()))
+}
+
+/// 校验上级位置存在且层级比指定层级更浅
+fn ensure_parent_accepts(parent_id: i64, level: &str) -> Result<(), AppError> {
+    if parent_id <= 0 {
+        return Err(AppError::Validation("invalid parentId".to_string()));
+    }
+    let parent = repository::find_location(parent_id)?
+        .ok_or_else(|| AppError::Validation("parent location not found".to_string()))?;
+    if level_rank(&parent.level) >= level_rank(level) {
+        return Err(AppError::Validation(format!(
+            "location level {level} cannot be placed under {}",
+            parent.level
+        )));
+    }
+    Ok(())
+}
+
+/// 返回位置层级的深度（未知层级为 None）
+fn level_rank(level: &str) -> Option<usize> {
+    LOCATION_LEVELS
+        .iter()
+        .position(|candidate| *candidate == level)
+}
+
+/// 校验并规范化位置写入参数
+///
+/// 编码只允许字母、数字、`_`、`-`、`.`；备注去除空白后空串视为未设置
+fn normalize_input(
+    code: &str,
+    name: &str,
+    sort_order: i32,
+    remark: Option<String>,
+) -> Result<LocationInput, AppError> {
+    let code = code.trim().to_string();
+    if code.is_empty() {
+        return Err(AppError::Validation("code is required".to_string()));
+    }
+    if code.len() > MAX_CODE_LENGTH
+        || !code
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(
+            "code must be at most 64 letters, digits, '_', '-' or '.'".to_string(),
+        ));
+    }
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".to_string()));
+    }
+    Ok(LocationInput {
+        code,
+        name,
+        sort_order,
+        remark: remark
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()),
+    })
+}
+
+/// 将扁平的位置记录组装为树（同级按排序号、编码排序），并累计子树设备数
+fn build_tree(records: Vec<LocationRecord>, root_parent_id: Option<i64>) -> Vec<LocationNodeData> {
+    let mut children_by_parent: HashMap<Option<i64>, Vec<LocationRecord>> = HashMap::new();
+    for record in records {
+        children_by_parent
+            .entry(record.parent_id)
+            .or_default()
+            .push(record);
+    }
+    attach_children(root_parent_id, &mut children_by_parent)
+}
+
+/// 递归取出指定上级位置的下级节点
+fn attach_children(
+    parent_id: Option<i64>,
+    children_by_parent: &mut HashMap<Option<i64>, Vec<LocationRecord>>,
+) -> Vec<LocationNodeData> {
+    let mut records = children_by_parent.remove(&parent_id).unwrap_or_default();
+    records.sort_by(|left, right| {
+        left.sort_order
+            .cmp(&right.sort_order)
+            .then_with(|| left.code.cmp(&right.code))
+    });
+    records
+        .into_iter()
+        .map(|record| {
+            let children = attach_children(Some(record.id), children_by_parent);
+            let subtree_device_count = record.device_count
+                + children
+                    .iter()
+                    .map(|child| child.subtree_device_count)
+                    .sum::<i64>();
+            LocationNodeData {
+                location: map_location_record(record),
+                subtree_device_count,
+                children,
+            }
+        })
+        .collect()
+}
+
+/// 将位置记录转换为响应格式
+fn map_location_record(record: LocationRecord) -> LocationData {
+    LocationData {
+        id: record.id,
+        parent_id: record.parent_id,
+        level: record.level,
+        code: record.code,
+        name: record.name,
+        sort_order: record.sort_order,
+        remark: record.remark,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        created_by: record.created_by,
+        device_count: record.device_count,
+    }
+}
+
+/// 查询位置当前快照（审计操作前内容，查询失败时不记录快照）
+fn find_snapshot(location_id: i64) -> Option<Value> {
+    if location_id <= 0 {
+        return None;
+    }
+    repository::find_location(location_id)
+        .ok()
+        .flatten()
+        .map(map_location_record)
+        .as_ref()
+        .and_then(snapshot)
+}
+
+/// 将位置响应数据序列化为审计快照
+fn snapshot(data: &LocationData) -> Option<Value> {
+    serde_json::to_value(data).ok()
+}
@@ -0,0 +1,158 @@
+//! Modbus ASCII 帧
+//!
+//! ASCII 帧格式为：`:` + 十六进制字符（单元号 + PDU + LRC）+ `\r\n`。
+//! 每个字节以两个十六进制字符表示，LRC 为单元号与 PDU 各字节之和的二进制补码。
+//! 帧以 `:` 开始、以换行结束，读取时跳过 `:` 之前的杂散字节。
+//!
+//! 本模块只处理成帧与校验，与具体的字节流（串口、TCP）无关。
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::modbus::protocol::ModbusError;
+
+// 帧起始字符
+const FRAME_START: u8 = b':';
+
+// 帧内十六进制字符数上限（单元号 1 + PDU 最大 253 + LRC 1，共 255 字节）
+const MAX_HEX_LENGTH: usize = 510;
+
+/// 计算 LRC（各字节之和的二进制补码）
+pub fn lrc(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0_u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg()
+}
+
+/// 组装 ASCII 帧（`:` + 单元号 + PDU + LRC 的十六进制 + CRLF）
+pub fn encode_frame(unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut content = Vec::with_capacity(pdu.len() + 2);
+    content.push(unit_id);
+    content.extend_from_slice(pdu);
+    content.push(lrc(&content));
+    let mut frame = Vec::with_capacity(content.len() * 2 + 3);
+    frame.push(FRAME_START);
+    for byte in content {
+        frame.extend_from_slice(format!("{byte:02X}").as_bytes());
+    }
+    frame.extend_from_slice(b"\r\n");
+    frame
+}
+
+/// 读取一个 ASCII 帧，返回单元号与 PDU（请求与响应格式相同）
+pub async fn read_frame<S>(stream: &mut S) -> Result<(u8, Vec<u8>), ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    while read_byte(stream).await? != FRAME_START {}
+    let mut hex = Vec::new();
+    loop {
+        match read_byte(stream).await? {
+            b'\n' => break,
+            // 帧中途出现新的起始字符时以新帧为准
+            FRAME_START => hex.clear(),
+            byte => {
+                if hex.len() > MAX_HEX_LENGTH {
+                    return Err(invalid_frame("frame too long"));
+                }
+                hex.push(byte);
+            }
+        }
+    }
+    if hex.pop() != Some(b'\r') {
+        return Err(invalid_frame("missing CR before LF"));
+    }
+    if hex.len() % 2 != 0 || hex.len() < 6 {
+        return Err(invalid_frame("unexpected length"));
+    }
+    let mut content = hex
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|text| u8::from_str_radix(text, 16).ok())
+                .ok_or_else(|| invalid_frame("non-hex character"))
+        })
+        .collect::<Result<Vec<u8>, ModbusError>>()?;
+    let received = content.pop().unwrap_or_default();
+    let expected = lrc(&content);
+    if expected != received {
+        return Err(ModbusError::Protocol(format!(
+            "lrc mismatch: expected 0x{expected:02X}, got 0x{received:02X}"
+        )));
+    }
+    Ok((content[0], content[1..].to_vec()))
+}
+
+// 帧格式错误
+fn invalid_frame(reason: &str) -> ModbusError {
+    ModbusError::Protocol(format!("invalid ascii frame: {reason}"))
+}
+
+// 读取一个字节
+async fn read_byte<S>(stream: &mut S) -> Result<u8, ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    stream.read_u8().await.map_err(|err| match err.kind() {
+        std::io::ErrorKind::UnexpectedEof => {
+            ModbusError::Io("connection closed by peer".to_string())
+        }
+        _ => ModbusError::Io(err.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[test]
+    fn ascii_frames_round_trip_with_lrc() {
+        // 规范示例：单元 0x11 读保持寄存器 0x006B 起 3 个 → LRC 0x7E
+        let frame = encode_frame(0x11, &[0x03, 0x00, 0x6B, 0x00, 0x03]);
+        assert_eq!(frame, b":1103006B00037E\r\n".to_vec());
+        assert_eq!(lrc(&[]), 0);
+
+        // 跳过起始符之前的杂散字节，十六进制不区分大小写
+        let mut stream: &[u8] = b"\x00\xFF:1103006b00037e\r\n:110302022BBD\r\n";
+        db::block_on(async {
+            assert_eq!(
+                read_frame(&mut stream).await.expect("request"),
+                (0x11, vec![0x03, 0x00, 0x6B, 0x00, 0x03])
+            );
+            assert_eq!(
+                read_frame(&mut stream).await.expect("response"),
+                (0x11, vec![0x03, 0x02, 0x02, 0x2B])
+            );
+        });
+
+        let cases: [(&[u8], ModbusError); 5] = [
+            (
+                b":1103006B000300\r\n",
+                ModbusError::Protocol("lrc mismatch: expected 0x7E, got 0x00".to_string()),
+            ),
+            (
+                b":1103006B00037E\n",
+                ModbusError::Protocol("invalid ascii frame: missing CR before LF".to_string()),
+            ),
+            (
+                b":1103\r\n",
+                ModbusError::Protocol("invalid ascii frame: unexpected length".to_string()),
+            ),
+            (
+                b":11030G6B00037E\r\n",
+                ModbusError::Protocol("invalid ascii frame: non-hex character".to_string()),
+            ),
+            (
+                b":1103006B",
+                ModbusError::Io("connection closed by peer".to_string()),
+            ),
+        ];
+        for (mut bytes, expected) in cases {
+            assert_eq!(
+                db::block_on(read_frame(&mut bytes)).expect_err("invalid frame"),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! Modbus 客户端
+//!
+//! 本模块在传输层之上提供：
+//! - FC01–FC06、FC15、FC16 的类型化读写方法
+//! - 长连接复用：连接层故障（超时、断开）后丢弃连接，下次请求时自动重连
+//! - 重连退避：连续建连失败时按指数退避（初始间隔翻倍直至上限），退避期内请求立即失败
+//! - 可配置的建连超时与请求超时
+//! - 通信诊断：长连接客户端的每个事务（含收发的原始字节）记录到所属网关的诊断中
+
+use std::time::{Duration, Instant};
+
+use crate::auth::services::now_millis;
+use crate::modbus::diagnostics::{self, SharedLink, Transaction};
+use crate::modbus::protocol::{ModbusError, Request, Response};
+use crate::modbus::transport::{ModbusTransport, TransportConfig, WireLog, duration_millis};
+
+// 默认建连超时
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 默认请求超时
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+// 默认初始重连间隔
+pub const DEFAULT_BACKOFF_INITIAL: Duration = Duration::from_millis(500);
+
+// 默认最大重连间隔
+pub const DEFAULT_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// 客户端配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    pub transport: TransportConfig, // 传输方式与连接参数
+    pub connect_timeout: Duration,  // 建连超时
+    pub request_timeout: Duration,  // 单次请求超时
+    pub backoff_initial: Duration,  // 初始重连间隔
+    pub backoff_max: Duration,      // 最大重连间隔
+}
+
+impl ClientConfig {
+    /// 使用默认超时与退避参数创建配置
+    pub fn new(transport: TransportConfig) -> Self {
+        Self {
+            transport,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            backoff_initial: DEFAULT_BACKOFF_INITIAL,
+            backoff_max: DEFAULT_BACKOFF_MAX,
+        }
+    }
+}
+
+/// Modbus 客户端（单个网关的长连接）
+pub struct ModbusClient {
+    config: ClientConfig,                        // 客户端配置
+    transport: Option<Box<dyn ModbusTransport>>, // 当前连接
+    backoff: Duration,                           // 下一次建连失败后的退避间隔
+    retry_at: Option<Instant>,                   // 退避结束时间
+    connect_count: u64,                          // 成功建连次数
+    last_error: Option<String>,                  // 最近一次错误
+    wire: WireLog,                               // 最近一次事务收发的原始字节
+    diagnostics: Option<SharedLink>,             // 通信诊断（临时会话为空）
+}
+
+impl ModbusClient {
+    /// 创建客户端（不立即建连）
+    pub fn new(config: ClientConfig) -> Self {
+        let backoff = config.backoff_initial;
+        Self {
+            config,
+            transport: None,
+            backoff,
+            retry_at: None,
+            connect_count: 0,
+            last_error: None,
+            wire: WireLog::default(),
+            diagnostics: None,
+        }
+    }
+
+    /// 将事务记录到网关的通信诊断
+    pub fn attach_diagnostics(&mut self, link: SharedLink) {
+        self.diagnostics = Some(link);
+    }
+
+    /// 客户端配置
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// 当前是否持有连接
+    pub fn is_connected(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// 成功建连次数（大于 1 表示发生过重连）
+    pub fn connect_count(&self) -> u64 {
+        self.connect_count
+    }
+
+    /// 最近一次错误
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// 距离退避结束的剩余时间（不在退避期返回 None）
+    pub fn retry_in(&self) -> Option<Duration> {
+        self.retry_at
+            .map(|retry_at| retry_at.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// 确保连接可用
+    ///
+    /// 已连接时直接返回；处于退避期时返回 `ModbusError::Offline`；
+    /// 否则尝试建连，失败后进入退避期并将下次退避间隔翻倍。
+    pub async fn connect(&mut self) -> Result<(), ModbusError> {
+        if self.transport.is_some() {
+            return Ok(());
+        }
+        if let Some(remaining) = self.retry_in() {
+            return Err(ModbusError::Offline(duration_millis(remaining)));
+        }
+        match self
+            .config
+            .transport
+            .connect(self.config.connect_timeout)
+            .await
+        {
+            Ok(transport) => {
+                self.transport = Some(transport);
+                self.backoff = self.config.backoff_initial;
+                self.retry_at = None;
+                self.connect_count += 1;
+                Ok(())
+            }
+            Err(err) => {
+                self.retry_at = Some(Instant::now() + self.backoff);
+                self.backoff = (self.backoff * 2).min(self.config.backoff_max);
+                self.last_error = Some(err.to_string());
+                Err(err)
+            }
+        }
+    }
+
+    /// 断开连接并清除退避状态
+    pub fn disconnect(&mut self) {
+        self.transport = None;
+        self.retry_at = None;
+        self.backoff = self.config.backoff_initial;
+    }
+
+    /// 执行一个请求
+    ///
+    /// 连接层故障时丢弃连接（下次请求自动重连）；异常响应不影响连接。
+    /// 参数合法的请求（含建连失败与退避期内的请求）都记录到通信诊断
+    pub async fn call(&mut self, unit_id: u8, request: &Request) -> Result<Response, ModbusError> {
+        request.validate()?;
+        let timestamp = || i64::try_from(now_millis()).unwrap_or(i64::MAX);
+        let started_at = self.diagnostics.as_ref().map(|_| timestamp());
+        self.wire.clear();
+        let (result, round_trip) = match self.connect().await {
+            Ok(()) => self.exchange(unit_id, request).await,
+            Err(err) => (Err(err), None),
+        };
+        if let (Some(link), Some(started_at)) = (&self.diagnostics, started_at) {
+            diagnostics::record(
+                link,
+                &Transaction {
+                    unit_id,
+                    function_code: request.function().code(),
+                    started_at,
+                    finished_at: timestamp(),
+                    round_trip,
+                    wire: &self.wire,
+                    error: result.as_ref().err(),
+                },
+            );
+        }
+        result
+    }
+
+    // 在已建立的连接上执行事务，返回结果与往返耗时
+    async fn exchange(
+        &mut self,
+        unit_id: u8,
+        request: &Request,
+    ) -> (Result<Response, ModbusError>, Option<Duration>) {
+        let pdu = request.encode();
+        let timeout = self.config.request_timeout;
+        let Some(transport) = self.transport.as_mut() else {
+            return (Err(ModbusError::NotConnected), None);
+        };
+        let sent_at = Instant::now();
+        let result = transport
+            .transact(unit_id, &pdu, timeout, &mut self.wire)
+            .await
+            .and_then(|response| Response::decode(request, &response));
+        let round_trip = sent_at.elapsed();
+        if let Err(err) = &result {
+            if err.is_connection_fault() {
+                self.transport = None;
+            }
+            self.last_error = Some(err.to_string());
+        }
+        (result, Some(round_trip))
+    }
+
+    /// 读线圈（FC01）
+    pub async fn read_coils(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        let response = self
+            .call(unit_id, &Request::ReadCoils { address, count })
+            .await?;
+        expect_bits(response)
+    }
+
+    /// 读离散输入（FC02）
+    pub async fn read_discrete_inputs(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        let response = self
+            .call(unit_id, &Request::ReadDiscreteInputs { address, count })
+            .await?;
+        expect_bits(response)
+    }
+
+    /// 读保持寄存器（FC03）
+    pub async fn read_holding_registers(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        let response = self
+            .call(unit_id, &Request::ReadHoldingRegisters { address, count })
+            .await?;
+        expect_registers(response)
+    }
+
+    /// 读输入寄存器（FC04）
+    pub async fn read_input_registers(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        let response = self
+            .call(unit_id, &Request::ReadInputRegisters { address, count })
+            .await?;
+        expect_registers(response)
+    }
+
+    /// 写单个线圈（FC05）
+    pub async fn write_single_coil(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        value: bool,
+    ) -> Result<(), ModbusError> {
+        self.call(unit_id, &Request::WriteSingleCoil { address, value })
+            .await
+            .map(|_| ())
+    }
+
+    /// 写单个寄存器（FC06）
+    pub async fn write_single_register(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        value: u16,
+    ) -> Result<(), ModbusError> {
+        self.call(unit_id, &Request::WriteSingleRegister { address, value })
+            .await
+            .map(|_| ())
+    }
+
+    /// 写多个线圈（FC15）
+    pub async fn write_multiple_coils(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        values: Vec<bool>,
+    ) -> Result<(), ModbusError> {
+        self.call(unit_id, &Request::WriteMultipleCoils { address, values })
+            .await
+            .map(|_| ())
+    }
+
+    /// 写多个寄存器（FC16）
+    pub async fn write_multiple_registers(
+        &mut self,
+        unit_id: u8,
+        address: u16,
+        values: Vec<u16>,
+    ) -> Result<(), ModbusError> {
+        self.call(
+            unit_id,
+            &Request::WriteMultipleRegisters { address, values },
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+// 取出位读取结果
+fn expect_bits(response: Response) -> Result<Vec<bool>, ModbusError> {
+    match response {
+        Response::Bits(values) => Ok(values),
+        _ => Err(ModbusError::Protocol("expected bit values".to_string())),
+    }
+}
+
+// 取出寄存器读取结果
+fn expect_registers(response: Response) -> Result<Vec<u16>, ModbusError> {
+    match response {
+        Response::Registers(values) => Ok(values),
+        _ => Err(ModbusError::Protocol(
+            "expected register values".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,639 @@
+//! 寄存器编解码
+//!
+//! 将寄存器数组按数据类型与字节序解码为点位值，或将点位值编码为寄存器数组：
+//! - 数据类型：Bool、U16、I16、U32、I32、F32、F64、String
+//! - 字节序：单寄存器类型与字符串为 AB / BA，多寄存器类型为 ABCD / CDAB / BADC / DCBA
+//!
+//! 字节序以报文中的字节排列描述（A 为最高字节）：`CDAB` 为字交换，`BADC` 为字内字节交换，
+//! `DCBA` 为全部字节倒序；F64 的四个寄存器按同样规则扩展（字交换即寄存器顺序整体倒序）。
+//! 每种字节序变换都是对合的，编码与解码使用同一变换。
+//!
+//! 本模块为纯函数，不涉及通信与存储。
+
+// 引入 JSON 值类型（点位值与前端交互的格式）
+use serde_json::{Number, Value};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+
+// 字符串点位的最大寄存器数（FC03/FC04 单次最多读取 125 个寄存器）
+pub const MAX_STRING_REGISTERS: u16 = 125;
+
+/// 点位数据类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Bool,   // 布尔（线圈、离散输入，或寄存器非零）
+    U16,    // 无符号 16 位整数
+    I16,    // 有符号 16 位整数
+    U32,    // 无符号 32 位整数
+    I32,    // 有符号 32 位整数
+    F32,    // 单精度浮点
+    F64,    // 双精度浮点
+    String, // 字符串（每个寄存器 2 个字节，末尾的 0 字节忽略）
+}
+
+impl DataType {
+    /// 全部数据类型
+    pub const ALL: [Self; 8] = [
+        Self::Bool,
+        Self::U16,
+        Self::I16,
+        Self::U32,
+        Self::I32,
+        Self::F32,
+        Self::F64,
+        Self::String,
+    ];
+
+    /// 由名称解析（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|data_type| data_type.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// 名称（小写）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::U16 => "u16",
+            Self::I16 => "i16",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::String => "string",
+        }
+    }
+
+    /// 固定占用的寄存器（或线圈）数量（字符串长度可变，为 None）
+    pub fn register_count(self) -> Option<u16> {
+        match self {
+            Self::Bool | Self::U16 | Self::I16 => Some(1),
+            Self::U32 | Self::I32 | Self::F32 => Some(2),
+            Self::F64 => Some(4),
+            Self::String => None,
+        }
+    }
+
+    /// 该类型可用的字节序
+    pub fn byte_orders(self) -> &'static [ByteOrder] {
+        match self {
+            Self::Bool | Self::U16 | Self::I16 | Self::String => &[ByteOrder::AB, ByteOrder::BA],
+            Self::U32 | Self::I32 | Self::F32 | Self::F64 => &[
+                ByteOrder::ABCD,
+                ByteOrder::CDAB,
+                ByteOrder::BADC,
+                ByteOrder::DCBA,
+            ],
+        }
+    }
+
+    /// 默认字节序（大端）
+    pub fn default_byte_order(self) -> ByteOrder {
+        self.byte_orders()[0]
+    }
+
+    /// 校验寄存器数量与数据类型是否匹配
+    ///
+    /// # 参数
+    /// * `count` - 寄存器（或线圈）数量
+    pub fn check_count(self, count: u16) -> Result<(), CodecError> {
+        let valid = match self.register_count() {
+            Some(expected) => count == expected,
+            None => (1..=MAX_STRING_REGISTERS).contains(&count),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(CodecError::CountMismatch {
+                data_type: self,
+                count,
+            })
+        }
+    }
+}
+
+/// 字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    AB,   // 大端（单寄存器）
+    BA,   // 字节交换（单寄存器）
+    ABCD, // 大端
+    CDAB, // 字交换（低字在前）
+    BADC, // 字内字节交换
+    DCBA, // 小端（全部字节倒序）
+}
+
+impl ByteOrder {
+    /// 全部字节序
+    pub const ALL: [Self; 6] = [
+        Self::AB,
+        Self::BA,
+        Self::ABCD,
+        Self::CDAB,
+        Self::BADC,
+        Self::DCBA,
+    ];
+
+    /// 由名称解析（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// 名称（小写）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AB => "ab",
+            Self::BA => "ba",
+            Self::ABCD => "abcd",
+            Self::CDAB => "cdab",
+            Self::BADC => "badc",
+            Self::DCBA => "dcba",
+        }
+    }
+
+    /// 将报文字节序的字节变换为大端（变换对合，也用于大端到报文字节序）
+    pub fn reorder(self, bytes: &mut [u8]) {
+        match self {
+            Self::AB | Self::ABCD => {}
+            Self::BA | Self::BADC => swap_bytes_in_words(bytes),
+            Self::CDAB => {
+                bytes.reverse();
+                swap_bytes_in_words(bytes);
+            }
+            Self::DCBA => bytes.reverse(),
+        }
+    }
+}
+
+/// 点位值
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointValue {
+    Bool(bool),   // 布尔
+    Integer(i64), // 整数（U16、I16、U32、I32）
+    Float(f64),   // 浮点（F32、F64）
+    Text(String), // 字符串
+}
+
+impl PointValue {
+    /// 转换为 JSON 值（非有限浮点数转换为 null）
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Bool(value) => Value::Bool(*value),
+            Self::Integer(value) => Value::Number((*value).into()),
+            Self::Float(value) => Number::from_f64(*value).map_or(Value::Null, Value::Number),
+            Self::Text(value) => Value::String(value.clone()),
+        }
+    }
+
+    /// 由 JSON 值解析（数字按是否为整数区分整数与浮点）
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(Self::Bool(*value)),
+            Value::Number(number) => number
+                .as_i64()
+                .map(Self::Integer)
+                .or_else(|| number.as_f64().map(Self::Float)),
+            Value::String(value) => Some(Self::Text(value.clone())),
+            _ => None,
+        }
+    }
+
+    // 按整数读取（整数值的浮点数视为整数）
+    fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(value) => Some(*value),
+            #[allow(clippy::cast_possible_truncation)]
+            Self::Float(value) if value.fract() == 0.0 && value.abs() < 9.0e15 => {
+                Some(*value as i64)
+            }
+            _ => None,
+        }
+    }
+
+    // 按浮点读取
+    #[allow(clippy::cast_precision_loss)]
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Integer(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// 编解码错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CodecError {
+    /// 寄存器数量与数据类型不匹配
+    #[error("count {count} does not match data type {}", data_type.as_str())]
+    CountMismatch { data_type: DataType, count: u16 },
+    /// 字节序不适用于数据类型
+    #[error("byte order {} is not supported for data type {}", order.as_str(), data_type.as_str())]
+    UnsupportedOrder {
+        data_type: DataType,
+        order: ByteOrder,
+    },
+    /// 值类型与数据类型不匹配
+    #[error("value does not match data type {}", .0.as_str())]
+    TypeMismatch(DataType),
+    /// 值超出数据类型范围
+    #[error("value out of range for data type {}", .0.as_str())]
+    OutOfRange(DataType),
+    /// 字符串不是合法的 UTF-8
+    #[error("string registers are not valid utf-8")]
+    InvalidText,
+}
+
+/// 转换为应用错误：编解码错误均为参数或配置问题，归为校验错误
+impl From<CodecError> for AppError {
+    fn from(err: CodecError) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+/// 按数据类型与字节序解码寄存器
+///
+/// # 参数
+/// * `data_type` - 数据类型
+/// * `order` - 字节序
+/// * `registers` - 寄存器值（报文顺序）
+///
+/// # 返回
+/// * 点位值
+pub fn decode(
+    data_type: DataType,
+    order: ByteOrder,
+    registers: &[u16],
+) -> Result<PointValue, CodecError> {
+    check_order(data_type, order)?;
+    data_type.check_count(u16::try_from(registers.len()).unwrap_or(u16::MAX))?;
+    let mut bytes: Vec<u8> = registers
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect();
+    order.reorder(&mut bytes);
+    Ok(match data_type {
+        DataType::Bool => PointValue::Bool(bytes.iter().any(|byte| *byte != 0)),
+        DataType::U16 => PointValue::Integer(i64::from(u16::from_be_bytes([bytes[0], bytes[1]]))),
+        DataType::I16 => PointValue::Integer(i64::from(i16::from_be_bytes([bytes[0], bytes[1]]))),
+        DataType::U32 => PointValue::Integer(i64::from(u32::from_be_bytes(array(&bytes)))),
+        DataType::I32 => PointValue::Integer(i64::from(i32::from_be_bytes(array(&bytes)))),
+        DataType::F32 => PointValue::Float(f64::from(f32::from_be_bytes(array(&bytes)))),
+        DataType::F64 => PointValue::Float(f64::from_be_bytes(array(&bytes))),
+        DataType::String => {
+            let end = bytes
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |index| index + 1);
+            bytes.truncate(end);
+            PointValue::Text(String::from_utf8(bytes).map_err(|_| CodecError::InvalidText)?)
+        }
+    })
+}
+
+/// 按数据类型与字节序编码点位值
+///
+/// # 参数
+/// * `data_type` - 数据类型
+/// * `order` - 字节序
+/// * `count` - 寄存器数量（须与数据类型匹配；字符串不足部分以 0 填充）
+/// * `value` - 点位值
+///
+/// # 返回
+/// * 寄存器值（报文顺序）
+pub fn encode(
+    data_type: DataType,
+    order: ByteOrder,
+    count: u16,
+    value: &PointValue,
+) -> Result<Vec<u16>, CodecError> {
+    check_order(data_type, order)?;
+    data_type.check_count(count)?;
+    let mut bytes = match data_type {
+        DataType::Bool => match value {
+            PointValue::Bool(value) => u16::from(*value).to_be_bytes().to_vec(),
+            _ => return Err(CodecError::TypeMismatch(data_type)),
+        },
+        DataType::U16 => integer::<u16>(data_type, value)?.to_be_bytes().to_vec(),
+        DataType::I16 => integer::<i16>(data_type, value)?.to_be_bytes().to_vec(),
+        DataType::U32 => integer::<u32>(data_type, value)?.to_be_bytes().to_vec(),
+        DataType::I32 => integer::<i32>(data_type, value)?.to_be_bytes().to_vec(),
+        DataType::F32 => {
+            let float = value
+                .as_float()
+                .ok_or(CodecError::TypeMismatch(data_type))?;
+            #[allow(clippy::cast_possible_truncation)]
+            let single = float as f32;
+            if float.is_finite() && !single.is_finite() {
+                return Err(CodecError::OutOfRange(data_type));
+            }
+            single.to_be_bytes().to_vec()
+        }
+        DataType::F64 => value
+            .as_float()
+            .ok_or(CodecError::TypeMismatch(data_type))?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::String => {
+            let PointValue::Text(text) = value else {
+                return Err(CodecError::TypeMismatch(data_type));
+            };
+            let capacity = usize::from(count) * 2;
+            if text.len() > capacity {
+                return Err(CodecError::OutOfRange(data_type));
+            }
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.resize(capacity, 0);
+            bytes
+        }
+    };
+    order.reorder(&mut bytes);
+    Ok(bytes
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+/// 校验字节序是否适用于数据类型
+///
+/// # 参数
+/// * `data_type` - 数据类型
+/// * `order` - 字节序
+pub fn check_order(data_type: DataType, order: ByteOrder) -> Result<(), CodecError> {
+    if data_type.byte_orders().contains(&order) {
+        Ok(())
+    } else {
+        Err(CodecError::UnsupportedOrder { data_type, order })
+    }
+}
+
+// 按目标整数类型读取整数值并检查范围
+fn integer<T: TryFrom<i64>>(data_type: DataType, value: &PointValue) -> Result<T, CodecError> {
+    let integer = value
+        .as_integer()
+        .ok_or(CodecError::TypeMismatch(data_type))?;
+    T::try_from(integer).map_err(|_| CodecError::OutOfRange(data_type))
+}
+
+// 交换每个字内的两个字节
+fn swap_bytes_in_words(bytes: &mut [u8]) {
+    for pair in bytes.chunks_mut(2) {
+        pair.swap(0, 1);
+    }
+}
+
+// 取定长字节数组（长度已由寄存器数量校验保证）
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0_u8; N];
+    array.copy_from_slice(&bytes[..N]);
+    array
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每种数据类型的样例值（含边界值）
+    fn samples(data_type: DataType) -> Vec<PointValue> {
+        match data_type {
+            DataType::Bool => vec![PointValue::Bool(true), PointValue::Bool(false)],
+            DataType::U16 => [0, 1, 0x1234, 65_535]
+                .into_iter()
+                .map(PointValue::Integer)
+                .collect(),
+            DataType::I16 => [-32_768, -2, 0, 0x1234, 32_767]
+                .into_iter()
+                .map(PointValue::Integer)
+                .collect(),
+            DataType::U32 => [0, 0x1234_5678, 4_294_967_295]
+                .into_iter()
+                .map(PointValue::Integer)
+                .collect(),
+            DataType::I32 => [-2_147_483_648, -123_456, 0, 2_147_483_647]
+                .into_iter()
+                .map(PointValue::Integer)
+                .collect(),
+            DataType::F32 => [0.0, -1.5, f64::from(123.456_f32), f64::from(f32::MAX)]
+                .into_iter()
+                .map(PointValue::Float)
+                .collect(),
+            DataType::F64 => [0.0, 1.0, -2.5e-300, std::f64::consts::PI]
+                .into_iter()
+                .map(PointValue::Float)
+                .collect(),
+            DataType::String => ["", "A", "AB-01", "电表"]
+                .into_iter()
+                .map(|text| PointValue::Text(text.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn every_type_and_order_round_trips() {
+        for data_type in DataType::ALL {
+            let count = data_type.register_count().unwrap_or(4);
+            for order in ByteOrder::ALL {
+                for value in samples(data_type) {
+                    let result = encode(data_type, order, count, &value);
+                    if !data_type.byte_orders().contains(&order) {
+                        assert_eq!(
+                            result,
+                            Err(CodecError::UnsupportedOrder { data_type, order })
+                        );
+                        continue;
+                    }
+                    let registers = result.expect("encode");
+                    assert_eq!(registers.len(), usize::from(count));
+                    assert_eq!(
+                        decode(data_type, order, &registers).expect("decode"),
+                        value,
+                        "{data_type:?} {order:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn byte_orders_match_reference_layouts() {
+        // U16 / I16：AB 为大端，BA 为字节交换
+        let cases: [(DataType, ByteOrder, i64, &[u16]); 4] = [
+            (DataType::U16, ByteOrder::AB, 0x1234, &[0x1234]),
+            (DataType::U16, ByteOrder::BA, 0x1234, &[0x3412]),
+            (DataType::I16, ByteOrder::AB, -2, &[0xFFFE]),
+            (DataType::I16, ByteOrder::BA, -2, &[0xFEFF]),
+        ];
+        for (data_type, order, value, registers) in cases {
+            assert_eq!(
+                encode(data_type, order, 1, &PointValue::Integer(value)).expect("encode"),
+                registers
+            );
+        }
+
+        // U32 0x12345678 / I32 -2（0xFFFFFFFE）
+        let orders: [(ByteOrder, [u16; 2], [u16; 2]); 4] = [
+            (ByteOrder::ABCD, [0x1234, 0x5678], [0xFFFF, 0xFFFE]),
+            (ByteOrder::CDAB, [0x5678, 0x1234], [0xFFFE, 0xFFFF]),
+            (ByteOrder::BADC, [0x3412, 0x7856], [0xFFFF, 0xFEFF]),
+            (ByteOrder::DCBA, [0x7856, 0x3412], [0xFEFF, 0xFFFF]),
+        ];
+        for (order, unsigned, signed) in orders {
+            assert_eq!(
+                decode(DataType::U32, order, &unsigned).expect("u32"),
+                PointValue::Integer(0x1234_5678)
+            );
+            assert_eq!(
+                decode(DataType::I32, order, &signed).expect("i32"),
+                PointValue::Integer(-2)
+            );
+        }
+
+        // F32 123.456（0x42F6E979）
+        let orders: [(ByteOrder, [u16; 2]); 4] = [
+            (ByteOrder::ABCD, [0x42F6, 0xE979]),
+            (ByteOrder::CDAB, [0xE979, 0x42F6]),
+            (ByteOrder::BADC, [0xF642, 0x79E9]),
+            (ByteOrder::DCBA, [0x79E9, 0xF642]),
+        ];
+        for (order, registers) in orders {
+            let PointValue::Float(value) = decode(DataType::F32, order, &registers).expect("f32")
+            else {
+                panic!("expected float");
+            };
+            assert!((value - 123.456).abs() < 1e-4, "{order:?} {value}");
+            assert_eq!(
+                encode(DataType::F32, order, 2, &PointValue::Float(123.456)).expect("encode"),
+                registers
+            );
+        }
+
+        // F64 1.0（0x3FF0000000000000）：字交换即四个寄存器整体倒序
+        let orders: [(ByteOrder, [u16; 4]); 4] = [
+            (ByteOrder::ABCD, [0x3FF0, 0, 0, 0]),
+            (ByteOrder::CDAB, [0, 0, 0, 0x3FF0]),
+            (ByteOrder::BADC, [0xF03F, 0, 0, 0]),
+            (ByteOrder::DCBA, [0, 0, 0, 0xF03F]),
+        ];
+        for (order, registers) in orders {
+            assert_eq!(
+                encode(DataType::F64, order, 4, &PointValue::Float(1.0)).expect("encode"),
+                registers
+            );
+        }
+        assert_eq!(
+            decode(
+                DataType::F64,
+                ByteOrder::CDAB,
+                &[0x5678, 0x1234, 0xDEF0, 0x9ABC]
+            )
+            .expect("f64"),
+            PointValue::Float(f64::from_bits(0x9ABC_DEF0_1234_5678))
+        );
+
+        // 字符串：AB 高字节在前，BA 低字节在前，末尾的 0 字节忽略
+        assert_eq!(
+            encode(
+                DataType::String,
+                ByteOrder::AB,
+                3,
+                &PointValue::Text("ABC".to_string())
+            )
+            .expect("encode"),
+            vec![0x4142, 0x4300, 0x0000]
+        );
+        assert_eq!(
+            decode(DataType::String, ByteOrder::BA, &[0x4241, 0x0043]).expect("decode"),
+            PointValue::Text("ABC".to_string())
+        );
+
+        // 寄存器非零即为 true
+        assert_eq!(
+            decode(DataType::Bool, ByteOrder::AB, &[0x0100]).expect("bool"),
+            PointValue::Bool(true)
+        );
+    }
+
+    #[test]
+    fn invalid_counts_values_and_text_are_rejected() {
+        for data_type in DataType::ALL {
+            let order = data_type.default_byte_order();
+            let valid = data_type.register_count().unwrap_or(1);
+            for count in [0, valid + 1, MAX_STRING_REGISTERS + 1] {
+                if data_type == DataType::String && count <= MAX_STRING_REGISTERS && count > 0 {
+                    continue;
+                }
+                assert_eq!(
+                    data_type.check_count(count),
+                    Err(CodecError::CountMismatch { data_type, count })
+                );
+                let registers = vec![0_u16; usize::from(count)];
+                assert_eq!(
+                    decode(data_type, order, &registers),
+                    Err(CodecError::CountMismatch { data_type, count })
+                );
+            }
+        }
+
+        let out_of_range: [(DataType, PointValue); 7] = [
+            (DataType::U16, PointValue::Integer(65_536)),
+            (DataType::U16, PointValue::Integer(-1)),
+            (DataType::I16, PointValue::Integer(32_768)),
+            (DataType::U32, PointValue::Integer(4_294_967_296)),
+            (DataType::I32, PointValue::Integer(-2_147_483_649)),
+            (DataType::F32, PointValue::Float(1.0e39)),
+            (DataType::String, PointValue::Text("ABCDE".to_string())),
+        ];
+        for (data_type, value) in out_of_range {
+            let count = data_type.register_count().unwrap_or(2);
+            assert_eq!(
+                encode(data_type, data_type.default_byte_order(), count, &value),
+                Err(CodecError::OutOfRange(data_type))
+            );
+        }
+
+        let mismatched: [(DataType, PointValue); 5] = [
+            (DataType::Bool, PointValue::Integer(1)),
+            (DataType::U16, PointValue::Float(1.5)),
+            (DataType::I32, PointValue::Text("1".to_string())),
+            (DataType::F64, PointValue::Bool(true)),
+            (DataType::String, PointValue::Integer(1)),
+        ];
+        for (data_type, value) in mismatched {
+            let count = data_type.register_count().unwrap_or(1);
+            assert_eq!(
+                encode(data_type, data_type.default_byte_order(), count, &value),
+                Err(CodecError::TypeMismatch(data_type))
+            );
+        }
+
+        // 整数值的浮点数可写入整数类型
+        assert_eq!(
+            encode(DataType::U16, ByteOrder::AB, 1, &PointValue::Float(7.0)).expect("encode"),
+            vec![7]
+        );
+        assert_eq!(
+            decode(DataType::String, ByteOrder::AB, &[0xFF00]),
+            Err(CodecError::InvalidText)
+        );
+        assert_eq!(
+            CodecError::UnsupportedOrder {
+                data_type: DataType::F32,
+                order: ByteOrder::AB
+            }
+            .to_string(),
+            "byte order ab is not supported for data type f32"
+        );
+        assert_eq!(DataType::parse(" F32 "), Some(DataType::F32));
+        assert_eq!(ByteOrder::parse("CDAB"), Some(ByteOrder::CDAB));
+        assert_eq!(DataType::parse("int16"), None);
+        assert_eq!(
+            PointValue::from_json(&serde_json::json!(12)),
+            Some(PointValue::Integer(12))
+        );
+        assert_eq!(PointValue::Float(f64::NAN).to_json(), Value::Null);
+    }
+}
@@ -0,0 +1,2234 @@
+//! Modbus 模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的 Modbus 连接管理与读写 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `modbus_tcp_connect` | 建立（或更新）网关的 Modbus TCP / RTU over TCP / ASCII over TCP 长连接 |
+//! | `modbus_rtu_connect` | 建立（或更新）网关的串口 Modbus RTU / ASCII 长连接 |
+//! | `modbus_serial_port_list` | 查询本机可用串口 |
+//! | `modbus_disconnect` | 断开并移除网关连接 |
+//! | `modbus_connection_list` | 查询全部网关连接状态 |
+//! | `modbus_read_coils` | 读线圈（FC01） |
+//! | `modbus_read_discrete_inputs` | 读离散输入（FC02） |
+//! | `modbus_read_holding_registers` | 读保持寄存器（FC03） |
+//! | `modbus_read_input_registers` | 读输入寄存器（FC04） |
+//! | `modbus_write_single_coil` | 写单个线圈（FC05） |
+//! | `modbus_write_single_register` | 写单个寄存器（FC06） |
+//! | `modbus_write_multiple_coils` | 写多个线圈（FC15） |
+//! | `modbus_write_multiple_registers` | 写多个寄存器（FC16） |
+//! | `modbus_simulator_start` | 启动进程内从站模拟器 |
+//! | `modbus_simulator_stop` | 停止模拟器 |
+//! | `modbus_simulator_list` | 查询全部模拟器状态 |
+//! | `modbus_simulator_set_faults` | 调整模拟器故障注入 |
+//! | `modbus_diagnostics` | 查询网关与从站的通信诊断 |
+//! | `modbus_diagnostics_reset` | 清零网关的通信诊断 |
+//! | `modbus_capture_start` | 开启网关的报文捕获 |
+//! | `modbus_capture_stop` | 停止网关的报文捕获 |
+//! | `modbus_capture_export` | 导出捕获的原始帧（十六进制） |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入 Modbus 数据模型
+use crate::modbus::models::{
+    ModbusBitsData, ModbusCaptureData, ModbusCaptureExportPayload, ModbusCaptureStartPayload,
+    ModbusConnectionData, ModbusConnectionListPayload, ModbusDiagnosticsData,
+    ModbusDiagnosticsPayload, ModbusGatewayPayload, ModbusReadPayload, ModbusRegistersData,
+    ModbusRtuConnectPayload, ModbusSerialPortData, ModbusSerialPortListPayload,
+    ModbusSimulatorData, ModbusSimulatorFaultsPayload, ModbusSimulatorListPayload,
+    ModbusSimulatorPayload, ModbusSimulatorStartPayload, ModbusTcpConnectPayload,
+    ModbusWriteCoilPayload, ModbusWriteCoilsPayload, ModbusWriteData, ModbusWriteRegisterPayload,
+    ModbusWriteRegistersPayload,
+};
+// 引入 Modbus 服务层
+use crate::modbus::services;
+
+/// 建立（或更新）网关的 Modbus TCP 长连接
+///
+/// # 参数
+/// * `payload` - 网关标识、目标地址、帧格式（mbap / rtu / ascii）与超时参数
+///
+/// # 返回
+/// * 网关连接状态（建连失败时 `connected = false` 并给出 `lastError`）
+#[tauri::command]
+pub fn modbus_tcp_connect(
+    payload: ModbusTcpConnectPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusConnectionData> {
+    execute_traced_command("modbus_tcp_connect", trace, || {
+        Ok(ApiResponse::ok(services::connect_tcp(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 建立（或更新）网关的串口 Modbus RTU / ASCII 长连接
+///
+/// # 参数
+/// * `payload` - 网关标识、串口参数、帧格式（rtu / ascii）与超时参数
+///
+/// # 返回
+/// * 网关连接状态（串口打开失败时 `connected = false` 并给出 `lastError`）
+#[tauri::command]
+pub fn modbus_rtu_connect(
+    payload: ModbusRtuConnectPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusConnectionData> {
+    execute_traced_command("modbus_rtu_connect", trace, || {
+        Ok(ApiResponse::ok(services::connect_rtu(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询本机可用串口
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+///
+/// # 返回
+/// * 按路径排序的串口列表
+#[tauri::command]
+pub fn modbus_serial_port_list(
+    payload: ModbusSerialPortListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<ModbusSerialPortData>> {
+    execute_traced_command("modbus_serial_port_list", trace, || {
+        Ok(ApiResponse::ok(services::list_serial_ports(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 断开并移除网关连接
+///
+/// # 参数
+/// * `payload` - 网关标识
+///
+/// # 返回
+/// * 断开成功返回 true
+#[tauri::command]
+pub fn modbus_disconnect(
+    payload: ModbusGatewayPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("modbus_disconnect", trace, || {
+        Ok(ApiResponse::ok(services::disconnect(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询全部网关连接状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+///
+/// # 返回
+/// * 按网关标识排序的连接状态
+#[tauri::command]
+pub fn modbus_connection_list(
+    payload: ModbusConnectionListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<ModbusConnectionData>> {
+    execute_traced_command("modbus_connection_list", trace, || {
+        Ok(ApiResponse::ok(services::list_connections(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 读线圈（FC01）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与数量（1–2000）
+///
+/// # 返回
+/// * 线圈值
+#[tauri::command]
+pub fn modbus_read_coils(
+    payload: ModbusReadPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusBitsData> {
+    execute_traced_command("modbus_read_coils", trace, || {
+        Ok(ApiResponse::ok(services::read_coils(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 读离散输入（FC02）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与数量（1–2000）
+///
+/// # 返回
+/// * 离散输入值
+#[tauri::command]
+pub fn modbus_read_discrete_inputs(
+    payload: ModbusReadPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusBitsData> {
+    execute_traced_command("modbus_read_discrete_inputs", trace, || {
+        Ok(ApiResponse::ok(services::read_discrete_inputs(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 读保持寄存器（FC03）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与数量（1–125）
+///
+/// # 返回
+/// * 原始寄存器值
+#[tauri::command]
+pub fn modbus_read_holding_registers(
+    payload: ModbusReadPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusRegistersData> {
+    execute_traced_command("modbus_read_holding_registers", trace, || {
+        Ok(ApiResponse::ok(services::read_holding_registers(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 读输入寄存器（FC04）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与数量（1–125）
+///
+/// # 返回
+/// * 原始寄存器值
+#[tauri::command]
+pub fn modbus_read_input_registers(
+    payload: ModbusReadPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusRegistersData> {
+    execute_traced_command("modbus_read_input_registers", trace, || {
+        Ok(ApiResponse::ok(services::read_input_registers(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 写单个线圈（FC05）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、地址与写入值
+///
+/// # 返回
+/// * 写入结果
+#[tauri::command]
+pub fn modbus_write_single_coil(
+    payload: ModbusWriteCoilPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusWriteData> {
+    execute_traced_command("modbus_write_single_coil", trace, || {
+        Ok(ApiResponse::ok(services::write_single_coil(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 写单个寄存器（FC06）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、地址与写入值
+///
+/// # 返回
+/// * 写入结果
+#[tauri::command]
+pub fn modbus_write_single_register(
+    payload: ModbusWriteRegisterPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusWriteData> {
+    execute_traced_command("modbus_write_single_register", trace, || {
+        Ok(ApiResponse::ok(services::write_single_register(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 写多个线圈（FC15）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与写入值（1–1968 个）
+///
+/// # 返回
+/// * 写入结果
+#[tauri::command]
+pub fn modbus_write_multiple_coils(
+    payload: ModbusWriteCoilsPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusWriteData> {
+    execute_traced_command("modbus_write_multiple_coils", trace, || {
+        Ok(ApiResponse::ok(services::write_multiple_coils(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 写多个寄存器（FC16）
+///
+/// # 参数
+/// * `payload` - 网关标识、单元号、起始地址与写入值（1–123 个）
+///
+/// # 返回
+/// * 写入结果
+#[tauri::command]
+pub fn modbus_write_multiple_registers(
+    payload: ModbusWriteRegistersPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusWriteData> {
+    execute_traced_command("modbus_write_multiple_registers", trace, || {
+        Ok(ApiResponse::ok(services::write_multiple_registers(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 启动进程内从站模拟器
+///
+/// # 参数
+/// * `payload` - 模拟器标识、监听地址、帧格式、设备模板、取值生成器与故障注入参数
+///
+/// # 返回
+/// * 模拟器状态（含实际监听地址与寄存器映射中的点位）
+#[tauri::command]
+pub fn modbus_simulator_start(
+    payload: ModbusSimulatorStartPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusSimulatorData> {
+    execute_traced_command("modbus_simulator_start", trace, || {
+        Ok(ApiResponse::ok(services::start_simulator(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 停止模拟器
+///
+/// # 参数
+/// * `payload` - 模拟器标识
+///
+/// # 返回
+/// * 停止成功返回 true
+#[tauri::command]
+pub fn modbus_simulator_stop(
+    payload: ModbusSimulatorPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("modbus_simulator_stop", trace, || {
+        Ok(ApiResponse::ok(services::stop_simulator(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询全部模拟器状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+///
+/// # 返回
+/// * 按模拟器标识排序的模拟器状态（含故障注入统计与点位最新取值）
+#[tauri::command]
+pub fn modbus_simulator_list(
+    payload: ModbusSimulatorListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<ModbusSimulatorData>> {
+    execute_traced_command("modbus_simulator_list", trace, || {
+        Ok(ApiResponse::ok(services::list_simulators(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 调整模拟器故障注入
+///
+/// # 参数
+/// * `payload` - 模拟器标识与故障注入参数（整体替换）
+///
+/// # 返回
+/// * 模拟器状态
+#[tauri::command]
+pub fn modbus_simulator_set_faults(
+    payload: ModbusSimulatorFaultsPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusSimulatorData> {
+    execute_traced_command("modbus_simulator_set_faults", trace, || {
+        Ok(ApiResponse::ok(services::set_simulator_faults(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 查询网关与从站的通信诊断
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的网关标识
+///
+/// # 返回
+/// * 按网关标识排序的计数、延迟分位数、最近错误与捕获状态
+#[tauri::command]
+pub fn modbus_diagnostics(
+    payload: ModbusDiagnosticsPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<ModbusDiagnosticsData>> {
+    execute_traced_command("modbus_diagnostics", trace, || {
+        Ok(ApiResponse::ok(services::list_diagnostics(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 清零网关的通信诊断
+///
+/// # 参数
+/// * `payload` - 网关标识
+///
+/// # 返回
+/// * 清零后的诊断
+#[tauri::command]
+pub fn modbus_diagnostics_reset(
+    payload: ModbusGatewayPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusDiagnosticsData> {
+    execute_traced_command("modbus_diagnostics_reset", trace, || {
+        Ok(ApiResponse::ok(services::reset_diagnostics(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 开启网关的报文捕获
+///
+/// # 参数
+/// * `payload` - 网关标识与环形缓冲区容量
+///
+/// # 返回
+/// * 网关诊断（含捕获状态）
+#[tauri::command]
+pub fn modbus_capture_start(
+    payload: ModbusCaptureStartPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusDiagnosticsData> {
+    execute_traced_command("modbus_capture_start", trace, || {
+        Ok(ApiResponse::ok(services::start_capture(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 停止网关的报文捕获
+///
+/// # 参数
+/// * `payload` - 网关标识
+///
+/// # 返回
+/// * 网关诊断（含捕获状态）
+#[tauri::command]
+pub fn modbus_capture_stop(
+    payload: ModbusGatewayPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusDiagnosticsData> {
+    execute_traced_command("modbus_capture_stop", trace, || {
+        Ok(ApiResponse::ok(services::stop_capture(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 导出捕获的原始帧
+///
+/// # 参数
+/// * `payload` - 网关标识与导出后是否清空
+///
+/// # 返回
+/// * 捕获的帧与十六进制文本
+#[tauri::command]
+pub fn modbus_capture_export(
+    payload: ModbusCaptureExportPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<ModbusCaptureData> {
+    execute_traced_command("modbus_capture_export", trace, || {
+        Ok(ApiResponse::ok(services::export_capture(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, Once};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::auth::admin_commands::user_device_scope_upsert;
+    use crate::auth::models::UserDeviceScopeUpsertPayload;
+    use crate::core::error::AppError;
+    use crate::db;
+    use crate::db::test_support::{ensure_test_db_ready, register_operator, unique_code};
+    use crate::device::commands::device_create;
+    use crate::device::models::DeviceCreatePayload;
+    use crate::device_template::commands::device_template_create;
+    use crate::device_template::models::{
+        DeviceTemplateCreatePayload, DeviceTemplateSpec, TemplatePointSpec,
+    };
+    use crate::modbus::diagnostics;
+    use crate::modbus::models::{
+        ModbusDiagnosticsEvent, ModbusSimulatorFaultSpec, ModbusSimulatorGeneratorSpec,
+    };
+    use crate::modbus::simulator::{FaultConfig, SlaveMemory, StreamSimulator, TcpSimulator};
+    use crate::modbus::transport::Framing;
+    use tokio_serial::{SerialPort, SerialStream};
+
+    fn start_simulator(bind: &str) -> TcpSimulator {
+        let mut memory = SlaveMemory::new(100);
+        memory.holding_registers[..3].copy_from_slice(&[0x022B, 0x0000, 0x0064]);
+        memory.input_registers[8] = 0x000A;
+        memory.discrete_inputs[1] = true;
+        db::block_on(TcpSimulator::start(bind, memory)).expect("start simulator")
+    }
+
+    fn connect(gateway_id: &str, address: std::net::SocketAddr) -> ModbusConnectionData {
+        modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.to_string(),
+                host: address.ip().to_string(),
+                port: Some(address.port()),
+                request_timeout_ms: Some(500),
+                backoff_initial_ms: Some(200),
+                backoff_max_ms: Some(400),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect("connect")
+        .data
+    }
+
+    fn read_holding(gateway_id: &str, address: u16, count: u16) -> AppResult<ModbusRegistersData> {
+        modbus_read_holding_registers(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.to_string(),
+                address,
+                count,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+    }
+
+    fn open_pty_slave(framing: Framing, unit_id: u8) -> (StreamSimulator, SerialStream, String) {
+        let (master, slave) = db::block_on(async { SerialStream::pair() }).expect("pty pair");
+        let port = slave.name().expect("pty name");
+        let mut memory = SlaveMemory::new(100);
+        memory.holding_registers[..2].copy_from_slice(&[0x1234, 0x5678]);
+        let simulator =
+            db::block_on(async { StreamSimulator::start(master, framing, unit_id, memory) });
+        (simulator, slave, port)
+    }
+
+    fn rtu_payload(gateway_id: &str, port: &str) -> ModbusRtuConnectPayload {
+        ModbusRtuConnectPayload {
+            operator_username: "admin".to_string(),
+            gateway_id: gateway_id.to_string(),
+            port: port.to_string(),
+            baud_rate: Some(19_200),
+            parity: Some("even".to_string()),
+            request_timeout_ms: Some(300),
+            ..ModbusRtuConnectPayload::default()
+        }
+    }
+
+    fn read_unit(
+        gateway_id: &str,
+        unit_id: u8,
+        address: u16,
+        count: u16,
+    ) -> AppResult<ModbusRegistersData> {
+        modbus_read_holding_registers(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.to_string(),
+                unit_id: Some(unit_id),
+                address,
+                count,
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn tcp_reads_all_tables_through_simulator() {
+        ensure_test_db_ready();
+        let simulator = start_simulator("127.0.0.1:0");
+        let gateway_id = unique_code("modbus_read");
+        let status = connect(&gateway_id, simulator.local_addr());
+        assert!(status.connected);
+        assert_eq!(status.transport, "tcp");
+        assert_eq!(status.target, simulator.local_addr().to_string());
+        assert_eq!(status.connect_count, 1);
+
+        let registers = read_holding(&gateway_id, 0, 3).expect("read holding").data;
+        assert_eq!(registers.values, vec![0x022B, 0x0000, 0x0064]);
+        assert_eq!(registers.unit_id, 1);
+        let inputs = modbus_read_input_registers(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                unit_id: Some(17),
+                address: 8,
+                count: 1,
+            },
+            None,
+        )
+        .expect("read input")
+        .data;
+        assert_eq!((inputs.unit_id, inputs.values), (17, vec![0x000A]));
+        let discrete = modbus_read_discrete_inputs(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 0,
+                count: 3,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+        .expect("read discrete")
+        .data;
+        assert_eq!(discrete.values, vec![false, true, false]);
+    }
+
+    #[test]
+    fn tcp_writes_round_trip_through_simulator() {
+        ensure_test_db_ready();
+        let simulator = start_simulator("127.0.0.1:0");
+        let gateway_id = unique_code("modbus_write");
+        connect(&gateway_id, simulator.local_addr());
+
+        modbus_write_single_coil(
+            ModbusWriteCoilPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 4,
+                value: true,
+                ..ModbusWriteCoilPayload::default()
+            },
+            None,
+        )
+        .expect("write coil");
+        let written = modbus_write_multiple_coils(
+            ModbusWriteCoilsPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 10,
+                values: vec![
+                    true, false, true, true, false, false, true, true, true, false,
+                ],
+                ..ModbusWriteCoilsPayload::default()
+            },
+            None,
+        )
+        .expect("write coils")
+        .data;
+        assert_eq!((written.address, written.count), (10, 10));
+        modbus_write_single_register(
+            ModbusWriteRegisterPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 20,
+                value: 0xBEEF,
+                ..ModbusWriteRegisterPayload::default()
+            },
+            None,
+        )
+        .expect("write register");
+        modbus_write_multiple_registers(
+            ModbusWriteRegistersPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 21,
+                values: vec![1, 2, 3],
+                ..ModbusWriteRegistersPayload::default()
+            },
+            None,
+        )
+        .expect("write registers");
+
+        let coils = modbus_read_coils(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 4,
+                count: 16,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+        .expect("read coils")
+        .data;
+        assert_eq!(
+            coils.values,
+            vec![
+                true, false, false, false, false, false, true, false, true, true, false, false,
+                true, true, true, false
+            ]
+        );
+        assert_eq!(
+            read_holding(&gateway_id, 20, 4)
+                .expect("read back")
+                .data
+                .values,
+            vec![0xBEEF, 1, 2, 3]
+        );
+        {
+            let memory = simulator.memory();
+            let memory = memory.lock().expect("memory");
+            assert_eq!(memory.holding_registers[20], 0xBEEF);
+            assert!(memory.coils[4]);
+        }
+    }
+
+    #[test]
+    fn tcp_exceptions_keep_the_connection_until_disconnect() {
+        ensure_test_db_ready();
+        let simulator = start_simulator("127.0.0.1:0");
+        let gateway_id = unique_code("modbus_exception");
+        connect(&gateway_id, simulator.local_addr());
+
+        // 异常响应映射为 Modbus 错误且不影响连接
+        let err = read_holding(&gateway_id, 99, 2).expect_err("illegal address");
+        assert_eq!(
+            err,
+            AppError::Modbus("exception 0x02 illegal data address (function 0x03)".to_string())
+        );
+        let err = read_holding(&gateway_id, 0, 126).expect_err("too many");
+        assert_eq!(
+            err,
+            AppError::Validation("count must be between 1 and 125".to_string())
+        );
+        let listed = modbus_connection_list(
+            ModbusConnectionListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list")
+        .data;
+        let status = listed
+            .iter()
+            .find(|item| item.gateway_id == gateway_id)
+            .expect("listed gateway");
+        assert!(status.connected);
+        assert_eq!(status.connect_count, 1);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("exception 0x02 illegal data address (function 0x03)")
+        );
+
+        assert!(
+            modbus_disconnect(
+                ModbusGatewayPayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id: gateway_id.clone(),
+                },
+                None,
+            )
+            .expect("disconnect")
+            .data
+        );
+        assert_eq!(
+            read_holding(&gateway_id, 0, 1).expect_err("disconnected"),
+            AppError::Modbus("not connected".to_string())
+        );
+    }
+
+    #[test]
+    fn dropped_connections_reconnect_with_backoff() {
+        ensure_test_db_ready();
+        let simulator = start_simulator("127.0.0.1:0");
+        let address = simulator.local_addr();
+        let gateway_id = unique_code("modbus_reconnect");
+        assert!(connect(&gateway_id, address).connected);
+        read_holding(&gateway_id, 0, 1).expect("initial read");
+
+        // 从站掉线：当前请求失败，下一次重连被拒绝后进入退避期
+        simulator.stop();
+        drop(simulator);
+        let err = read_holding(&gateway_id, 0, 1).expect_err("dropped");
+        assert!(matches!(err, AppError::Modbus(ref message) if message.starts_with("i/o error")));
+        let err = read_holding(&gateway_id, 0, 1).expect_err("refused");
+        assert!(
+            matches!(err, AppError::Modbus(ref message) if message.starts_with("connection refused"))
+        );
+        let err = read_holding(&gateway_id, 0, 1).expect_err("backoff");
+        assert!(
+            matches!(err, AppError::Modbus(ref message) if message.starts_with("gateway offline, next reconnect in"))
+        );
+
+        // 从站恢复：退避结束后自动重连
+        let simulator = start_simulator(&address.to_string());
+        thread::sleep(Duration::from_millis(250));
+        let registers = read_holding(&gateway_id, 0, 1).expect("reconnected").data;
+        assert_eq!(registers.values, vec![0x022B]);
+        let listed = modbus_connection_list(
+            ModbusConnectionListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list")
+        .data;
+        let status = listed
+            .iter()
+            .find(|item| item.gateway_id == gateway_id)
+            .expect("listed gateway");
+        assert!(status.connected);
+        assert_eq!(status.connect_count, 2);
+        assert_eq!(status.retry_in_ms, None);
+        drop(simulator);
+    }
+
+    #[test]
+    fn connection_failures_timeouts_and_permissions() {
+        ensure_test_db_ready();
+
+        // 建连被拒绝时返回状态而非错误
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let closed_address = closed.local_addr().expect("address");
+        drop(closed);
+        let gateway_id = unique_code("modbus_refused");
+        let status = connect(&gateway_id, closed_address);
+        assert!(!status.connected);
+        assert!(
+            status
+                .last_error
+                .as_deref()
+                .is_some_and(|message| message.starts_with("connection refused"))
+        );
+        assert!(status.retry_in_ms.is_some());
+
+        // 从站不应答时按请求超时返回
+        let silent = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let silent_address = silent.local_addr().expect("address");
+        let gateway_id = unique_code("modbus_silent");
+        assert!(connect(&gateway_id, silent_address).connected);
+        assert_eq!(
+            read_holding(&gateway_id, 0, 1).expect_err("timeout"),
+            AppError::Modbus("timeout after 500ms".to_string())
+        );
+        drop(silent);
+
+        let err = modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: unique_code("modbus_invalid"),
+                host: "127.0.0.1".to_string(),
+                request_timeout_ms: Some(10),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect_err("invalid timeout");
+        assert_eq!(
+            err,
+            AppError::Validation("requestTimeoutMs must be between 100 and 60000".to_string())
+        );
+        let err = modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: unique_code("modbus_invalid"),
+                host: " ".to_string(),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect_err("missing host");
+        assert_eq!(err, AppError::Validation("host is required".to_string()));
+
+        let err = modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "common".to_string(),
+                gateway_id: unique_code("modbus_forbidden"),
+                host: "127.0.0.1".to_string(),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect_err("forbidden connect");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+        let err = modbus_write_single_register(
+            ModbusWriteRegisterPayload {
+                operator_username: "common".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 0,
+                value: 1,
+                ..ModbusWriteRegisterPayload::default()
+            },
+            None,
+        )
+        .expect_err("forbidden write");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: control issue required".to_string())
+        );
+        let err = modbus_read_coils(
+            ModbusReadPayload {
+                operator_username: "common".to_string(),
+                gateway_id,
+                address: 0,
+                count: 1,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+        .expect_err("forbidden read");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+    }
+
+    #[test]
+    fn rtu_gateways_share_a_serial_bus_over_pty() {
+        ensure_test_db_ready();
+        let (simulator, pty_slave, port) = open_pty_slave(Framing::Rtu, 7);
+        let first = unique_code("modbus_rtu_first");
+        let second = unique_code("modbus_rtu_second");
+        let status = modbus_rtu_connect(rtu_payload(&first, &port), None)
+            .expect("connect first")
+            .data;
+        assert!(status.connected);
+        assert_eq!(status.transport, "rtu");
+        assert_eq!(status.target, format!("{port} 19200 8E1"));
+        assert!(
+            modbus_rtu_connect(rtu_payload(&second, &port), None)
+                .expect("connect second")
+                .data
+                .connected
+        );
+
+        // 两个网关并发请求同一总线：事务在总线锁内串行执行，帧不会交错
+        let readers: Vec<_> = [first.clone(), second.clone()]
+            .into_iter()
+            .map(|gateway_id| {
+                thread::spawn(move || {
+                    for _ in 0..20 {
+                        let registers = read_unit(&gateway_id, 7, 0, 2).expect("concurrent read");
+                        assert_eq!(registers.data.values, vec![0x1234, 0x5678]);
+                    }
+                })
+            })
+            .collect();
+        for reader in readers {
+            reader.join().expect("reader thread");
+        }
+
+        modbus_write_multiple_registers(
+            ModbusWriteRegistersPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: second.clone(),
+                unit_id: Some(7),
+                address: 10,
+                values: vec![1, 2, 3],
+            },
+            None,
+        )
+        .expect("write registers");
+        assert_eq!(
+            read_unit(&first, 7, 10, 3).expect("read back").data.values,
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            simulator.memory().lock().expect("memory").holding_registers[12],
+            3
+        );
+
+        // 总线上不存在的单元号不应答，按请求超时返回；总线仍可继续使用
+        assert_eq!(
+            read_unit(&first, 1, 0, 1).expect_err("absent unit"),
+            AppError::Modbus("timeout after 300ms".to_string())
+        );
+        assert_eq!(
+            read_unit(&first, 7, 0, 1)
+                .expect("after timeout")
+                .data
+                .values,
+            vec![0x1234]
+        );
+        assert_eq!(
+            read_unit(&first, 7, 99, 2).expect_err("illegal address"),
+            AppError::Modbus("exception 0x02 illegal data address (function 0x03)".to_string())
+        );
+
+        // 同一串口只能以一组参数打开
+        let mismatched = modbus_rtu_connect(
+            ModbusRtuConnectPayload {
+                baud_rate: Some(9600),
+                ..rtu_payload(&unique_code("modbus_rtu_mismatch"), &port)
+            },
+            None,
+        )
+        .expect("connect mismatched")
+        .data;
+        assert!(!mismatched.connected);
+        assert_eq!(
+            mismatched.last_error,
+            Some(format!(
+                "connect failed: {port}: already open as rtu {port} 19200 8E1"
+            ))
+        );
+        drop(pty_slave);
+    }
+
+    #[test]
+    fn rtu_inter_frame_delay_spaces_requests() {
+        ensure_test_db_ready();
+        let (_simulator, pty_slave, port) = open_pty_slave(Framing::Rtu, 1);
+        let gateway_id = unique_code("modbus_rtu_delay");
+        assert!(
+            modbus_rtu_connect(
+                ModbusRtuConnectPayload {
+                    inter_frame_delay_ms: Some(40),
+                    ..rtu_payload(&gateway_id, &port)
+                },
+                None,
+            )
+            .expect("connect")
+            .data
+            .connected
+        );
+        let started = std::time::Instant::now();
+        for _ in 0..5 {
+            read_unit(&gateway_id, 1, 0, 1).expect("read");
+        }
+        assert!(started.elapsed() >= Duration::from_millis(160));
+        drop(pty_slave);
+    }
+
+    #[test]
+    fn rtu_connect_validates_serial_settings() {
+        ensure_test_db_ready();
+        let port = "/dev/modbus-invalid";
+        let invalid = [
+            (
+                ModbusRtuConnectPayload {
+                    port: " ".to_string(),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "port is required",
+            ),
+            (
+                ModbusRtuConnectPayload {
+                    baud_rate: Some(100),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "baudRate must be between 300 and 921600",
+            ),
+            (
+                ModbusRtuConnectPayload {
+                    parity: Some("mark".to_string()),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "parity must be one of none, even, odd",
+            ),
+            (
+                ModbusRtuConnectPayload {
+                    data_bits: Some(9),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "dataBits must be between 5 and 8",
+            ),
+            (
+                ModbusRtuConnectPayload {
+                    stop_bits: Some(3),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "stopBits must be 1 or 2",
+            ),
+            (
+                ModbusRtuConnectPayload {
+                    inter_frame_delay_ms: Some(1001),
+                    ..rtu_payload("modbus_rtu_invalid", port)
+                },
+                "interFrameDelayMs must be between 0 and 1000",
+            ),
+        ];
+        for (payload, message) in invalid {
+            assert_eq!(
+                modbus_rtu_connect(payload, None).expect_err(message),
+                AppError::Validation(message.to_string())
+            );
+        }
+
+        // 串口不存在时返回状态而非错误
+        let missing = modbus_rtu_connect(
+            rtu_payload(&unique_code("modbus_rtu_missing"), "/dev/modbus-missing"),
+            None,
+        )
+        .expect("connect missing")
+        .data;
+        assert!(!missing.connected);
+        assert!(
+            missing
+                .last_error
+                .as_deref()
+                .is_some_and(|message| message.starts_with("connect failed: /dev/modbus-missing"))
+        );
+    }
+
+    #[test]
+    fn serial_port_listing_and_rtu_connect_require_permissions() {
+        ensure_test_db_ready();
+        let ports = modbus_serial_port_list(
+            ModbusSerialPortListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list ports")
+        .data;
+        assert!(ports.windows(2).all(|pair| pair[0].name <= pair[1].name));
+        let err = modbus_serial_port_list(
+            ModbusSerialPortListPayload {
+                operator_username: "common".to_string(),
+            },
+            None,
+        )
+        .expect_err("forbidden list");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+        let err = modbus_rtu_connect(
+            ModbusRtuConnectPayload {
+                operator_username: "common".to_string(),
+                ..rtu_payload(
+                    &unique_code("modbus_rtu_forbidden"),
+                    "/dev/modbus-forbidden",
+                )
+            },
+            None,
+        )
+        .expect_err("forbidden connect");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+    }
+
+    #[test]
+    fn rtu_and_ascii_over_tcp_share_the_client_api() {
+        ensure_test_db_ready();
+        let mut memory = SlaveMemory::new(100);
+        memory.holding_registers[..2].copy_from_slice(&[0x1234, 0x5678]);
+        for (framing, kind) in [
+            ("rtu", "rtu_over_tcp"),
+            ("ascii", "ascii_over_tcp"),
+            ("mbap", "tcp"),
+        ] {
+            let simulator = match Framing::parse(framing) {
+                Some(parsed) => db::block_on(TcpSimulator::start_framed(
+                    "127.0.0.1:0",
+                    parsed,
+                    5,
+                    memory.clone(),
+                )),
+                None => db::block_on(TcpSimulator::start("127.0.0.1:0", memory.clone())),
+            }
+            .expect("start simulator");
+            let gateway_id = unique_code("modbus_framed");
+            let status = modbus_tcp_connect(
+                ModbusTcpConnectPayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id: gateway_id.clone(),
+                    host: "127.0.0.1".to_string(),
+                    port: Some(simulator.local_addr().port()),
+                    framing: Some(framing.to_uppercase()),
+                    request_timeout_ms: Some(300),
+                    ..ModbusTcpConnectPayload::default()
+                },
+                None,
+            )
+            .expect("connect")
+            .data;
+            assert!(status.connected);
+            assert_eq!(status.transport, kind);
+            assert_eq!(status.target, simulator.local_addr().to_string());
+
+            assert_eq!(
+                read_unit(&gateway_id, 5, 0, 2).expect("read").data.values,
+                vec![0x1234, 0x5678]
+            );
+            modbus_write_single_coil(
+                ModbusWriteCoilPayload {
+                    operator_username: "admin".to_string(),
+                    gateway_id: gateway_id.clone(),
+                    unit_id: Some(5),
+                    address: 3,
+                    value: true,
+                },
+                None,
+            )
+            .expect("write coil");
+            assert!(simulator.memory().lock().expect("memory").coils[3]);
+            assert_eq!(
+                read_unit(&gateway_id, 5, 99, 2).expect_err("illegal address"),
+                AppError::Modbus("exception 0x02 illegal data address (function 0x03)".to_string())
+            );
+            if framing != "mbap" {
+                // 串口服务器后没有该单元号的从站：按请求超时返回，重连后继续可用
+                assert_eq!(
+                    read_unit(&gateway_id, 6, 0, 1).expect_err("absent unit"),
+                    AppError::Modbus("timeout after 300ms".to_string())
+                );
+                assert_eq!(
+                    read_unit(&gateway_id, 5, 1, 1)
+                        .expect("after timeout")
+                        .data
+                        .values,
+                    vec![0x5678]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_serial_round_trips_and_rejects_conflicting_framing() {
+        ensure_test_db_ready();
+        // 串口 ASCII（常见为 7E1）
+        let (simulator, pty_slave, port) = open_pty_slave(Framing::Ascii, 9);
+        let gateway_id = unique_code("modbus_ascii");
+        let status = modbus_rtu_connect(
+            ModbusRtuConnectPayload {
+                framing: Some("ascii".to_string()),
+                data_bits: Some(7),
+                ..rtu_payload(&gateway_id, &port)
+            },
+            None,
+        )
+        .expect("connect ascii")
+        .data;
+        assert!(status.connected);
+        assert_eq!(status.transport, "ascii");
+        assert_eq!(status.target, format!("{port} 19200 7E1"));
+        modbus_write_multiple_registers(
+            ModbusWriteRegistersPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                unit_id: Some(9),
+                address: 40,
+                values: vec![0xABCD, 0x0102],
+            },
+            None,
+        )
+        .expect("write registers");
+        assert_eq!(
+            read_unit(&gateway_id, 9, 40, 2)
+                .expect("read back")
+                .data
+                .values,
+            vec![0xABCD, 0x0102]
+        );
+        assert_eq!(
+            simulator.memory().lock().expect("memory").holding_registers[41],
+            0x0102
+        );
+
+        // 同一串口不能同时以 RTU 与 ASCII 打开
+        let conflict = modbus_rtu_connect(
+            ModbusRtuConnectPayload {
+                data_bits: Some(7),
+                ..rtu_payload(&unique_code("modbus_ascii_conflict"), &port)
+            },
+            None,
+        )
+        .expect("connect conflict")
+        .data;
+        assert_eq!(
+            conflict.last_error,
+            Some(format!(
+                "connect failed: {port}: already open as ascii {port} 19200 7E1"
+            ))
+        );
+        drop(pty_slave);
+    }
+
+    #[test]
+    fn connect_rejects_unknown_framing() {
+        ensure_test_db_ready();
+        let err = modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: unique_code("modbus_framing"),
+                host: "127.0.0.1".to_string(),
+                framing: Some("udp".to_string()),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect_err("invalid tcp framing");
+        assert_eq!(
+            err,
+            AppError::Validation("framing must be one of mbap, rtu, ascii".to_string())
+        );
+        let err = modbus_rtu_connect(
+            ModbusRtuConnectPayload {
+                framing: Some("mbap".to_string()),
+                ..rtu_payload("modbus_framing", "/dev/null")
+            },
+            None,
+        )
+        .expect_err("invalid serial framing");
+        assert_eq!(
+            err,
+            AppError::Validation("framing must be one of rtu, ascii".to_string())
+        );
+    }
+
+    fn simulator_template() -> i64 {
+        let point =
+            |key: &str, data_type: &str, register_type: &str, address: i64| TemplatePointSpec {
+                key: key.to_string(),
+                name: key.to_string(),
+                data_type: data_type.to_string(),
+                register_type: register_type.to_string(),
+                address,
+                ..TemplatePointSpec::default()
+            };
+        device_template_create(
+            DeviceTemplateCreatePayload {
+                operator_username: "admin".to_string(),
+                template: DeviceTemplateSpec {
+                    code: unique_code("tpl_sim"),
+                    name: "模拟电表".to_string(),
+                    device_type: "meter".to_string(),
+                    points: vec![
+                        TemplatePointSpec {
+                            scale: Some(0.1),
+                            ..point("voltage", "uint16", "input_register", 0)
+                        },
+                        TemplatePointSpec {
+                            byte_order: Some("cdab".to_string()),
+                            ..point("energy", "uint32", "holding_register", 10)
+                        },
+                        point("breaker", "bool", "coil", 2),
+                        point("temperature", "float32", "holding_register", 20),
+                    ],
+                    ..DeviceTemplateSpec::default()
+                },
+            },
+            None,
+        )
+        .expect("create template")
+        .data
+        .template
+        .id
+    }
+
+    fn generator(point_key: &str, kind: &str) -> ModbusSimulatorGeneratorSpec {
+        ModbusSimulatorGeneratorSpec {
+            point_key: point_key.to_string(),
+            kind: kind.to_string(),
+            ..ModbusSimulatorGeneratorSpec::default()
+        }
+    }
+
+    fn set_faults(simulator_id: &str, faults: ModbusSimulatorFaultSpec) -> ModbusSimulatorData {
+        modbus_simulator_set_faults(
+            ModbusSimulatorFaultsPayload {
+                operator_username: "admin".to_string(),
+                simulator_id: simulator_id.to_string(),
+                faults,
+            },
+            None,
+        )
+        .expect("set faults")
+        .data
+    }
+
+    // 重复读取直到成功（等待重连退避结束，最多 5 秒）
+    fn read_until_ok(gateway_id: &str, unit_id: u8, address: u16, count: u16) -> Vec<u16> {
+        for _ in 0..50 {
+            if let Ok(registers) = read_unit(gateway_id, unit_id, address, count) {
+                return registers.data.values;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        panic!("gateway {gateway_id} did not recover");
+    }
+
+    // 模拟电表：电压与合闸状态为常量，电能为计数器，温度无生成器
+    fn meter_payload(template_id: i64) -> ModbusSimulatorStartPayload {
+        ModbusSimulatorStartPayload {
+            operator_username: "admin".to_string(),
+            simulator_id: unique_code("sim"),
+            template_id: Some(template_id),
+            tick_ms: Some(50),
+            seed: Some(7),
+            generators: vec![
+                ModbusSimulatorGeneratorSpec {
+                    value: Some(230.4),
+                    ..generator("voltage", "constant")
+                },
+                ModbusSimulatorGeneratorSpec {
+                    start: Some(131_073.0),
+                    ..generator("energy", "counter")
+                },
+                ModbusSimulatorGeneratorSpec {
+                    value: Some(1.0),
+                    ..generator("breaker", "constant")
+                },
+            ],
+            ..ModbusSimulatorStartPayload::default()
+        }
+    }
+
+    fn launch_simulator(payload: ModbusSimulatorStartPayload) -> AppResult<ModbusSimulatorData> {
+        modbus_simulator_start(payload, None)
+    }
+
+    fn shutdown_simulator(operator_username: &str, simulator_id: &str) -> AppResult<bool> {
+        modbus_simulator_stop(
+            ModbusSimulatorPayload {
+                operator_username: operator_username.to_string(),
+                simulator_id: simulator_id.to_string(),
+            },
+            None,
+        )
+    }
+
+    // 启动模拟电表并建立连接，返回模拟器 ID 与连接 ID
+    fn start_meter() -> (String, String) {
+        ensure_test_db_ready();
+        let started = launch_simulator(meter_payload(simulator_template()))
+            .expect("start simulator")
+            .data;
+        let address =
+            std::net::SocketAddr::new(started.host.parse().expect("simulator host"), started.port);
+        let gateway_id = unique_code("modbus_sim");
+        connect(&gateway_id, address);
+        (started.simulator_id, gateway_id)
+    }
+
+    // 电能寄存器按 cdab 字节序组成的 32 位值
+    fn energy(registers: &[u16]) -> u32 {
+        (u32::from(registers[1]) << 16) | u32::from(registers[0])
+    }
+
+    fn read_energy(gateway_id: &str) -> u32 {
+        energy(&read_holding(gateway_id, 10, 2).expect("energy").data.values)
+    }
+
+    // 每组参数都以给定的校验错误被拒绝
+    fn assert_start_rejected(cases: Vec<(ModbusSimulatorStartPayload, String)>) {
+        for (payload, message) in cases {
+            assert_eq!(
+                launch_simulator(payload).expect_err(&message),
+                AppError::Validation(message.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn simulator_validates_transport_and_permissions() {
+        ensure_test_db_ready();
+        let meter = meter_payload(simulator_template());
+        assert_start_rejected(vec![
+            (
+                ModbusSimulatorStartPayload {
+                    template_id: None,
+                    ..meter.clone()
+                },
+                "templateId is required for generators".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    template_id: Some(-1),
+                    ..meter.clone()
+                },
+                "template not found".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    framing: Some("udp".to_string()),
+                    ..meter.clone()
+                },
+                "framing must be one of mbap, rtu, ascii".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    framing: Some("rtu".to_string()),
+                    unit_id: Some(0),
+                    ..meter.clone()
+                },
+                "unitId must be between 1 and 247".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    tick_ms: Some(5),
+                    ..meter.clone()
+                },
+                "tickMs must be between 10 and 60000".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    size: Some(21),
+                    ..meter.clone()
+                },
+                "size must be between 22 and 65536".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    operator_username: "common".to_string(),
+                    ..meter.clone()
+                },
+                "forbidden: device manage required".to_string(),
+            ),
+        ]);
+
+        // 同一 ID 只能运行一个模拟器；停止需要管理权限，停止后连接失效
+        let simulator_id = meter.simulator_id.clone();
+        let started = launch_simulator(meter.clone())
+            .expect("start simulator")
+            .data;
+        assert_eq!(
+            launch_simulator(meter).expect_err("duplicate simulator"),
+            AppError::Validation(format!("simulator {simulator_id} is already running"))
+        );
+        let gateway_id = unique_code("modbus_sim");
+        connect(
+            &gateway_id,
+            std::net::SocketAddr::new(started.host.parse().expect("simulator host"), started.port),
+        );
+        assert_eq!(
+            shutdown_simulator("common", &simulator_id).expect_err("forbidden stop"),
+            AppError::Validation("forbidden: device manage required".to_string())
+        );
+        assert!(
+            shutdown_simulator("admin", &simulator_id)
+                .expect("stop simulator")
+                .data
+        );
+        assert_eq!(
+            shutdown_simulator("admin", &simulator_id).expect_err("stopped simulator"),
+            AppError::Validation("simulator not found".to_string())
+        );
+        assert!(read_holding(&gateway_id, 10, 2).is_err());
+    }
+
+    #[test]
+    fn simulator_encodes_template_registers() {
+        let (simulator_id, gateway_id) = start_meter();
+
+        // 按模板生成寄存器映射：工程值反算原始值并按字节序编码
+        let input = modbus_read_input_registers(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 0,
+                count: 1,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+        .expect("read voltage")
+        .data;
+        assert_eq!(input.values, vec![2304]);
+        assert!(read_energy(&gateway_id) >= 131_073);
+        let breaker = modbus_read_coils(
+            ModbusReadPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                address: 2,
+                count: 1,
+                ..ModbusReadPayload::default()
+            },
+            None,
+        )
+        .expect("read breaker")
+        .data;
+        assert_eq!(breaker.values, vec![true]);
+
+        // 列表返回模板点位、生成器类型与当前工程值
+        let list = modbus_simulator_list(
+            ModbusSimulatorListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list simulators")
+        .data;
+        let listed = list
+            .iter()
+            .find(|simulator| simulator.simulator_id == simulator_id)
+            .expect("listed simulator");
+        let points: Vec<(&str, Option<&str>)> = listed
+            .points
+            .iter()
+            .map(|point| (point.point_key.as_str(), point.generator.as_deref()))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                ("voltage", Some("constant")),
+                ("energy", Some("counter")),
+                ("breaker", Some("constant")),
+                ("temperature", None),
+            ]
+        );
+        assert_eq!(listed.points[0].value, Some(230.4));
+        assert_eq!(listed.points[3].value, None);
+        assert!(
+            shutdown_simulator("admin", &simulator_id)
+                .expect("stop")
+                .data
+        );
+    }
+
+    #[test]
+    fn simulator_generators_validate_and_advance() {
+        ensure_test_db_ready();
+        let meter = meter_payload(simulator_template());
+        let with_generator = |spec: ModbusSimulatorGeneratorSpec| ModbusSimulatorStartPayload {
+            generators: vec![spec],
+            ..meter.clone()
+        };
+        assert_start_rejected(vec![
+            (
+                with_generator(generator("voltage", "square")),
+                "generator kind must be one of constant, ramp, sine, random_walk, counter"
+                    .to_string(),
+            ),
+            (
+                with_generator(generator("current", "constant")),
+                "generator point current not found in template".to_string(),
+            ),
+            (
+                ModbusSimulatorStartPayload {
+                    generators: vec![generator("voltage", "ramp"), generator("voltage", "sine")],
+                    ..meter.clone()
+                },
+                "duplicate generator for point voltage".to_string(),
+            ),
+            (
+                with_generator(ModbusSimulatorGeneratorSpec {
+                    min: Some(10.0),
+                    max: Some(5.0),
+                    ..generator("voltage", "random_walk")
+                }),
+                "generator voltage: min must not exceed max".to_string(),
+            ),
+            (
+                with_generator(ModbusSimulatorGeneratorSpec {
+                    increment: Some(-1.0),
+                    ..generator("energy", "counter")
+                }),
+                "generator energy: increment must not be negative".to_string(),
+            ),
+            (
+                with_generator(ModbusSimulatorGeneratorSpec {
+                    period_ms: Some(0),
+                    ..generator("voltage", "sine")
+                }),
+                "generator voltage: periodMs must be greater than 0".to_string(),
+            ),
+        ]);
+
+        // 计数器按刷新周期累加
+        let (simulator_id, gateway_id) = start_meter();
+        let first = read_energy(&gateway_id);
+        thread::sleep(Duration::from_millis(200));
+        assert!(read_energy(&gateway_id) > first);
+        assert!(
+            shutdown_simulator("admin", &simulator_id)
+                .expect("stop")
+                .data
+        );
+    }
+
+    #[test]
+    fn simulator_injects_faults_and_recovers() {
+        ensure_test_db_ready();
+        let meter = meter_payload(simulator_template());
+        let with_faults = |faults: ModbusSimulatorFaultSpec| ModbusSimulatorStartPayload {
+            faults,
+            ..meter.clone()
+        };
+        assert_start_rejected(vec![
+            (
+                with_faults(ModbusSimulatorFaultSpec {
+                    drop_rate: Some(1.5),
+                    ..ModbusSimulatorFaultSpec::default()
+                }),
+                "dropRate must be between 0 and 1".to_string(),
+            ),
+            (
+                with_faults(ModbusSimulatorFaultSpec {
+                    drop_rate: Some(0.6),
+                    exception_rate: Some(0.6),
+                    ..ModbusSimulatorFaultSpec::default()
+                }),
+                "fault rates must add up to at most 1".to_string(),
+            ),
+            (
+                with_faults(ModbusSimulatorFaultSpec {
+                    latency_ms: Some(60_001),
+                    ..ModbusSimulatorFaultSpec::default()
+                }),
+                "latencyMs must be between 0 and 60000".to_string(),
+            ),
+            (
+                with_faults(ModbusSimulatorFaultSpec {
+                    exception_code: Some(0),
+                    ..ModbusSimulatorFaultSpec::default()
+                }),
+                "exceptionCode must be between 1 and 255".to_string(),
+            ),
+        ]);
+
+        // 异常响应、损坏响应帧、断开连接与响应超时，清除后恢复
+        let (simulator_id, gateway_id) = start_meter();
+        let first = read_energy(&gateway_id);
+        let faults = set_faults(
+            &simulator_id,
+            ModbusSimulatorFaultSpec {
+                exception_rate: Some(1.0),
+                exception_code: Some(6),
+                ..ModbusSimulatorFaultSpec::default()
+            },
+        );
+        assert_eq!(faults.faults.exception_code, 6);
+        assert!((faults.faults.exception_rate - 1.0).abs() < f64::EPSILON);
+        assert_eq!(
+            read_holding(&gateway_id, 10, 2).expect_err("injected exception"),
+            AppError::Modbus("exception 0x06 server device busy (function 0x03)".to_string())
+        );
+        let only = |faults: ModbusSimulatorFaultSpec| set_faults(&simulator_id, faults);
+        only(ModbusSimulatorFaultSpec {
+            corrupt_rate: Some(1.0),
+            ..ModbusSimulatorFaultSpec::default()
+        });
+        assert_eq!(
+            read_holding(&gateway_id, 10, 2).expect_err("corrupted response"),
+            AppError::Modbus(
+                "invalid response: function code mismatch: expected 0x03, got 0x23".to_string()
+            )
+        );
+        only(ModbusSimulatorFaultSpec {
+            drop_rate: Some(1.0),
+            ..ModbusSimulatorFaultSpec::default()
+        });
+        assert!(matches!(
+            read_holding(&gateway_id, 10, 2).expect_err("dropped connection"),
+            AppError::Modbus(message) if message.starts_with("i/o error")
+        ));
+        only(ModbusSimulatorFaultSpec {
+            latency_ms: Some(800),
+            ..ModbusSimulatorFaultSpec::default()
+        });
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(
+            read_holding(&gateway_id, 10, 2).expect_err("delayed response"),
+            AppError::Modbus("timeout after 500ms".to_string())
+        );
+        let cleared = only(ModbusSimulatorFaultSpec::default());
+        assert_eq!(cleared.faults.latency_ms, 0);
+        assert!(energy(&read_until_ok(&gateway_id, 1, 10, 2)) > first);
+        let stats = &cleared.stats;
+        assert_eq!(
+            (
+                stats.exceptions,
+                stats.corrupted,
+                stats.dropped,
+                stats.delayed
+            ),
+            (1, 1, 1, 1)
+        );
+        assert!(
+            shutdown_simulator("admin", &simulator_id)
+                .expect("stop")
+                .data
+        );
+    }
+
+    #[test]
+    fn simulator_rtu_framing_rejects_corrupted_crc() {
+        ensure_test_db_ready();
+        let rtu_id = unique_code("sim_rtu");
+        let rtu = launch_simulator(ModbusSimulatorStartPayload {
+            operator_username: "admin".to_string(),
+            simulator_id: rtu_id.clone(),
+            framing: Some("rtu".to_string()),
+            unit_id: Some(3),
+            faults: ModbusSimulatorFaultSpec {
+                corrupt_rate: Some(1.0),
+                ..ModbusSimulatorFaultSpec::default()
+            },
+            ..ModbusSimulatorStartPayload::default()
+        })
+        .expect("start rtu simulator")
+        .data;
+        assert_eq!((rtu.unit_id, rtu.size, rtu.points.len()), (Some(3), 100, 0));
+        let rtu_gateway = unique_code("modbus_sim_rtu");
+        modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: rtu_gateway.clone(),
+                host: rtu.host.clone(),
+                port: Some(rtu.port),
+                framing: Some("rtu".to_string()),
+                request_timeout_ms: Some(500),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect("connect rtu");
+        let err = read_unit(&rtu_gateway, 3, 0, 2).expect_err("corrupted crc");
+        assert!(
+            matches!(&err, AppError::Modbus(message) if message.starts_with("invalid response: crc mismatch")),
+            "{err:?}"
+        );
+        set_faults(&rtu_id, ModbusSimulatorFaultSpec::default());
+        assert_eq!(read_until_ok(&rtu_gateway, 3, 0, 2), vec![0, 0]);
+        assert!(
+            shutdown_simulator("admin", &rtu_id)
+                .expect("stop rtu simulator")
+                .data
+        );
+    }
+
+    // 记录诊断事件的发送函数（测试进程内只注册一次）
+    fn recorded_diagnostics() -> &'static Mutex<Vec<ModbusDiagnosticsEvent>> {
+        static EVENTS: Mutex<Vec<ModbusDiagnosticsEvent>> = Mutex::new(Vec::new());
+        static SINK: Once = Once::new();
+        SINK.call_once(|| {
+            diagnostics::set_sink(Arc::new(|event| {
+                EVENTS.lock().expect("events").push(event.clone());
+            }));
+        });
+        &EVENTS
+    }
+
+    fn gateway_diagnostics(gateway_id: &str) -> ModbusDiagnosticsData {
+        let mut list = modbus_diagnostics(
+            ModbusDiagnosticsPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: Some(gateway_id.to_string()),
+            },
+            None,
+        )
+        .expect("diagnostics")
+        .data;
+        assert_eq!(list.len(), 1);
+        list.remove(0)
+    }
+
+    fn export(gateway_id: &str, clear: bool) -> AppResult<ModbusCaptureData> {
+        modbus_capture_export(
+            ModbusCaptureExportPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.to_string(),
+                clear,
+            },
+            None,
+        )
+    }
+
+    fn capture_start(gateway_id: &str, capacity: Option<u32>) -> AppResult<ModbusDiagnosticsData> {
+        modbus_capture_start(
+            ModbusCaptureStartPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.to_string(),
+                capacity,
+            },
+            None,
+        )
+    }
+
+    // RTU over TCP 模拟器（单元 5）与连接到它的网关，返回模拟器与连接 ID
+    fn diagnosed_gateway() -> (TcpSimulator, String) {
+        ensure_test_db_ready();
+        recorded_diagnostics();
+        let mut memory = SlaveMemory::new(10);
+        memory.holding_registers[..2].copy_from_slice(&[0x1234, 0x5678]);
+        let simulator = db::block_on(TcpSimulator::start_framed(
+            "127.0.0.1:0",
+            Framing::Rtu,
+            5,
+            memory,
+        ))
+        .expect("start simulator");
+        let gateway_id = unique_code("modbus_diag");
+        modbus_tcp_connect(
+            ModbusTcpConnectPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+                host: "127.0.0.1".to_string(),
+                port: Some(simulator.local_addr().port()),
+                framing: Some("rtu".to_string()),
+                request_timeout_ms: Some(300),
+                ..ModbusTcpConnectPayload::default()
+            },
+            None,
+        )
+        .expect("connect");
+        (simulator, gateway_id)
+    }
+
+    // 成功、异常响应、单元号不符导致超时、CRC 错误、恢复
+    fn exercise(simulator: &TcpSimulator, gateway_id: &str) {
+        assert_eq!(
+            read_unit(gateway_id, 5, 0, 2).expect("read").data.values,
+            vec![0x1234, 0x5678]
+        );
+        assert!(read_unit(gateway_id, 5, 9, 2).is_err());
+        assert!(read_unit(gateway_id, 6, 0, 1).is_err());
+        simulator.set_faults(FaultConfig {
+            corrupt_rate: 1.0,
+            ..FaultConfig::default()
+        });
+        assert!(read_unit(gateway_id, 5, 0, 2).is_err());
+        simulator.set_faults(FaultConfig::default());
+        assert_eq!(read_until_ok(gateway_id, 5, 0, 1), vec![0x1234]);
+    }
+
+    #[test]
+    fn diagnostics_count_outcomes_per_slave() {
+        let (simulator, gateway_id) = diagnosed_gateway();
+        exercise(&simulator, &gateway_id);
+        let data = gateway_diagnostics(&gateway_id);
+        assert_eq!(
+            (
+                data.stats.requests,
+                data.stats.successes,
+                data.stats.timeouts,
+                data.stats.crc_errors,
+                data.stats.connection_errors,
+            ),
+            (5, 2, 1, 1, 0)
+        );
+        assert_eq!(
+            data.stats
+                .exceptions
+                .iter()
+                .map(|exception| (exception.code, exception.count))
+                .collect::<Vec<_>>(),
+            vec![(0x02, 1)]
+        );
+        let last_error = data.stats.last_error.expect("last error");
+        assert_eq!((last_error.unit_id, last_error.function_code), (5, 0x03));
+        assert!(
+            last_error
+                .message
+                .starts_with("invalid response: crc mismatch"),
+            "{last_error:?}"
+        );
+        assert_eq!(
+            data.slaves
+                .iter()
+                .map(|slave| (slave.unit_id, slave.status.as_str(), slave.stats.requests))
+                .collect::<Vec<_>>(),
+            vec![(5, "ok", 4), (6, "failing", 1)]
+        );
+        assert_eq!(data.slaves[1].stats.timeouts, 1);
+        assert_eq!(
+            data.slaves[1]
+                .stats
+                .last_error
+                .as_ref()
+                .map(|error| error.class.as_str()),
+            Some("timeout")
+        );
+
+        // 清零计数；权限与未知网关
+        let reset = modbus_diagnostics_reset(
+            ModbusGatewayPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+            },
+            None,
+        )
+        .expect("reset")
+        .data;
+        assert_eq!((reset.stats.requests, reset.slaves.len()), (0, 0));
+        assert_eq!(
+            modbus_diagnostics(
+                ModbusDiagnosticsPayload {
+                    operator_username: "common".to_string(),
+                    gateway_id: None,
+                },
+                None,
+            )
+            .expect_err("forbidden diagnostics"),
+            AppError::Validation("forbidden: device view required".to_string())
+        );
+        assert_eq!(
+            export(&unique_code("modbus_diag_missing"), false).expect_err("missing"),
+            AppError::Validation("diagnostics not found".to_string())
+        );
+    }
+
+    #[test]
+    fn diagnostics_measure_latency_of_answered_requests() {
+        let (simulator, gateway_id) = diagnosed_gateway();
+        exercise(&simulator, &gateway_id);
+        // 超时与 CRC 错误之外的三次事务收到有效响应
+        let latency = gateway_diagnostics(&gateway_id).stats.latency;
+        assert_eq!(latency.samples, 3);
+        assert!(latency.p50_ms.is_some());
+        assert!(latency.p50_ms <= latency.max_ms);
+    }
+
+    #[test]
+    fn diagnostics_emit_slave_state_events() {
+        let (simulator, gateway_id) = diagnosed_gateway();
+        exercise(&simulator, &gateway_id);
+        // 单元 5 异常响应，单元 6 开始超时，单元 5 失败类别变为校验错误后恢复
+        let events = recorded_diagnostics()
+            .lock()
+            .expect("events")
+            .iter()
+            .filter(|event| event.gateway_id == gateway_id)
+            .map(|event| {
+                (
+                    event.unit_id,
+                    event.status.clone(),
+                    event.error.as_ref().map(|error| error.class.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let failing =
+            |unit_id: u8, class: &str| (unit_id, "failing".to_string(), Some(class.to_string()));
+        assert_eq!(
+            events,
+            vec![
+                failing(5, "exception"),
+                failing(6, "timeout"),
+                failing(5, "protocol"),
+                (5, "ok".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_capture_and_export_frames() {
+        let (simulator, gateway_id) = diagnosed_gateway();
+
+        // 开启捕获：网关须存在，容量校验与默认值
+        assert_eq!(
+            capture_start(&unique_code("modbus_diag_missing"), None).expect_err("missing gateway"),
+            AppError::Validation("gateway not found".to_string())
+        );
+        for capacity in [0, diagnostics::MAX_CAPTURE_CAPACITY + 1] {
+            assert_eq!(
+                capture_start(&gateway_id, Some(capacity)).expect_err("capacity"),
+                AppError::Validation("capacity must be between 1 and 10000".to_string())
+            );
+        }
+        let started = capture_start(&gateway_id, Some(10))
+            .expect("start capture")
+            .data;
+        assert_eq!(started.gateway_id, gateway_id);
+        assert!(started.capture.enabled);
+        assert_eq!(started.capture.capacity, 10);
+        exercise(&simulator, &gateway_id);
+
+        // 导出捕获：超时的请求只有发送帧，错误记录在事务最后一帧
+        let capture = export(&gateway_id, false).expect("export").data;
+        assert_eq!((capture.capacity, capture.dropped), (10, 0));
+        assert_eq!(
+            capture
+                .frames
+                .iter()
+                .map(|frame| (frame.direction.as_str(), frame.unit_id))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tx", 5),
+                ("rx", 5),
+                ("tx", 5),
+                ("rx", 5),
+                ("tx", 6),
+                ("tx", 5),
+                ("rx", 5),
+                ("tx", 5),
+                ("rx", 5),
+            ]
+        );
+        assert!(capture.frames[0].hex.starts_with("05 03 00 00 00 02 "));
+        assert!(capture.frames[3].hex.starts_with("05 83 02 "));
+        assert!(
+            capture.frames[4]
+                .error
+                .as_deref()
+                .is_some_and(|error| error.starts_with("timeout"))
+        );
+        let first_line = capture.text.lines().next().expect("first line");
+        assert!(
+            first_line.contains(" TX 05 03 00 00 00 02 "),
+            "{first_line}"
+        );
+        assert_eq!(capture.text.lines().count(), 9);
+
+        // 导出后清空；停止捕获后不再记录
+        assert_eq!(
+            export(&gateway_id, true).expect("export").data.frames.len(),
+            9
+        );
+        let frames = || export(&gateway_id, false).expect("export").data.frames;
+        assert!(frames().is_empty());
+        let stopped = modbus_capture_stop(
+            ModbusGatewayPayload {
+                operator_username: "admin".to_string(),
+                gateway_id: gateway_id.clone(),
+            },
+            None,
+        )
+        .expect("stop capture")
+        .data;
+        assert!(!stopped.capture.enabled);
+        read_unit(&gateway_id, 5, 0, 1).expect("read");
+        assert!(frames().is_empty());
+    }
+
+    // 创建绑定到网关从站的设备并设为操作员的设备范围
+    fn scope_operator_to(prefix: &str, comm_config_ref: String) -> String {
+        let (operator, user_id) = register_operator(prefix);
+        let device_id = device_create(
+            DeviceCreatePayload {
+                operator_username: "admin".to_string(),
+                device_id: unique_code("dev_modbus_diag"),
+                device_name: "诊断电表".to_string(),
+                device_type: "meter".to_string(),
+                comm_config_ref: Some(comm_config_ref),
+                ..DeviceCreatePayload::default()
+            },
+            None,
+        )
+        .expect("create device")
+        .data
+        .device_id;
+        user_device_scope_upsert(
+            UserDeviceScopeUpsertPayload {
+                operator_username: "admin".to_string(),
+                user_id,
+                devices: vec![device_id],
+                ..UserDeviceScopeUpsertPayload::default()
+            },
+            None,
+        )
+        .expect("upsert scope");
+        operator
+    }
+
+    #[test]
+    fn diagnostics_follow_the_operator_device_scope() {
+        let (simulator, gateway_id) = diagnosed_gateway();
+        capture_start(&gateway_id, Some(20)).expect("start capture");
+        exercise(&simulator, &gateway_id);
+        let list = |operator: &str| {
+            modbus_diagnostics(
+                ModbusDiagnosticsPayload {
+                    operator_username: operator.to_string(),
+                    gateway_id: None,
+                },
+                None,
+            )
+            .expect("diagnostics")
+            .data
+        };
+        let export_as = |operator: &str| {
+            modbus_capture_export(
+                ModbusCaptureExportPayload {
+                    operator_username: operator.to_string(),
+                    gateway_id: gateway_id.clone(),
+                    clear: false,
+                },
+                None,
+            )
+        };
+
+        // 范围内只有单元 6：网关合计、最近错误与导出的帧都只来自该从站，其他网关不返回
+        let operator = scope_operator_to("modbus_diag_scope", format!("modbus:{gateway_id}/6"));
+        let visible = list(&operator);
+        assert_eq!(
+            visible
+                .iter()
+                .map(|data| data.gateway_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![gateway_id.as_str()]
+        );
+        let data = &visible[0];
+        assert_eq!(
+            data.slaves
+                .iter()
+                .map(|slave| slave.unit_id)
+                .collect::<Vec<_>>(),
+            vec![6]
+        );
+        assert_eq!((data.stats.requests, data.stats.timeouts), (1, 1));
+        assert_eq!(
+            data.stats.last_error.as_ref().map(|error| error.unit_id),
+            Some(6)
+        );
+        assert_eq!(
+            export_as(&operator)
+                .expect("export")
+                .data
+                .frames
+                .iter()
+                .map(|frame| (frame.direction.as_str(), frame.unit_id))
+                .collect::<Vec<_>>(),
+            vec![("tx", 6)]
+        );
+
+        // 网关内没有可访问的从站时视为不存在
+        let outsider = scope_operator_to(
+            "modbus_diag_outsider",
+            format!("modbus:{}/1", unique_code("modbus_diag_other")),
+        );
+        assert!(list(&outsider).is_empty());
+        assert_eq!(
+            export_as(&outsider).expect_err("outside scope"),
+            AppError::Validation("diagnostics not found".to_string())
+        );
+    }
+}
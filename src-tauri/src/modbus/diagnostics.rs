@@ -0,0 +1,778 @@
+//! Modbus 通信诊断
+//!
+//! 按网关与从站单元号统计经由长连接（`state` 中注册的客户端）执行的事务，用于区分接线、单元号与超时等问题：
+//! - 计数：请求、成功、超时、CRC / LRC 校验错误、各异常码、连接错误（拒绝连接、断开、重连退避期）与其他报文错误
+//! - 延迟：最近 256 次收到响应（成功或异常响应）的往返时间，给出 p50 / p90 / p99 与最大值
+//! - 最近一次错误：时间、单元号、功能码、错误类别与信息
+//! - 状态事件：从站开始失败、失败类别变化或恢复正常时发送 `modbus:diagnostics` Tauri 事件
+//! - 报文捕获（按网关开启）：环形缓冲区保存最近的原始请求 / 响应帧与时间戳，可导出为十六进制文本
+//!
+//! 诊断按网关标识保存在内存中，连接断开或以新参数重建后继续累计，可手动清零，删除通信网关时移除；
+//! 临时会话不计入。
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::auth::services::now_millis;
+use crate::core::sync::lock;
+use crate::modbus::models::{
+    ModbusCaptureData, ModbusCaptureStatusData, ModbusDiagnosticErrorData, ModbusDiagnosticsData,
+    ModbusDiagnosticsEvent, ModbusDiagnosticsStatsData, ModbusExceptionCountData, ModbusFrameData,
+    ModbusLatencyData, ModbusSlaveDiagnosticsData,
+};
+use crate::modbus::protocol::{ExceptionCode, ModbusError};
+use crate::modbus::transport::WireLog;
+
+/// 从站通信状态变化事件名称
+pub const EVENT: &str = "modbus:diagnostics";
+
+/// 报文捕获默认容量（帧）
+pub const DEFAULT_CAPTURE_CAPACITY: u32 = 1000;
+
+/// 报文捕获容量上限（帧）
+pub const MAX_CAPTURE_CAPACITY: u32 = 10_000;
+
+// 延迟样本窗口（最近收到响应的事务数）
+const LATENCY_WINDOW: usize = 256;
+
+/// 共享的网关诊断
+pub type SharedLink = Arc<Mutex<Link>>;
+
+// 事件发送函数
+type Sink = Arc<dyn Fn(&ModbusDiagnosticsEvent) + Send + Sync>;
+
+/// 一次事务的结果
+pub struct Transaction<'a> {
+    pub unit_id: u8,                    // 从站单元号
+    pub function_code: u8,              // 请求功能码
+    pub started_at: i64,                // 开始时间戳（毫秒）
+    pub finished_at: i64,               // 结束时间戳（毫秒）
+    pub round_trip: Option<Duration>,   // 发出请求到结束的耗时（未发出请求时为空）
+    pub wire: &'a WireLog,              // 收发的原始字节
+    pub error: Option<&'a ModbusError>, // 失败时的错误
+}
+
+// 计数与延迟
+#[derive(Default)]
+struct Stats {
+    requests: u64,                                 // 请求数
+    successes: u64,                                // 成功数
+    timeouts: u64,                                 // 超时次数
+    crc_errors: u64,                               // 校验错误次数
+    exceptions: BTreeMap<u8, u64>,                 // 异常码 → 次数
+    connection_errors: u64,                        // 连接错误次数
+    protocol_errors: u64,                          // 其他报文错误次数
+    latencies: VecDeque<Duration>,                 // 最近的往返延迟
+    last_error: Option<ModbusDiagnosticErrorData>, // 最近一次错误
+}
+
+impl Stats {
+    // 记录一次事务
+    fn record(&mut self, transaction: &Transaction<'_>, error: Option<&ModbusDiagnosticErrorData>) {
+        self.requests += 1;
+        match transaction.error {
+            None => self.successes += 1,
+            Some(ModbusError::Timeout(_)) => self.timeouts += 1,
+            Some(ModbusError::Exception { code, .. }) => {
+                *self.exceptions.entry(code.code()).or_default() += 1;
+            }
+            Some(err) if err.is_checksum_mismatch() => self.crc_errors += 1,
+            Some(ModbusError::Protocol(_) | ModbusError::InvalidRequest(_)) => {
+                self.protocol_errors += 1;
+            }
+            Some(_) => self.connection_errors += 1,
+        }
+        // 收到响应（成功或异常响应）时记录往返延迟
+        let responded = matches!(
+            transaction.error,
+            None | Some(ModbusError::Exception { .. })
+        );
+        if let (true, Some(round_trip)) = (responded, transaction.round_trip) {
+            if self.latencies.len() == LATENCY_WINDOW {
+                self.latencies.pop_front();
+            }
+            self.latencies.push_back(round_trip);
+        }
+        if let Some(error) = error {
+            self.last_error = Some(error.clone());
+        }
+    }
+
+    // 合并另一份统计（延迟样本取两者最近的样本，最近一次错误取时间较晚者）
+    fn merge(&mut self, other: &Stats) {
+        self.requests += other.requests;
+        self.successes += other.successes;
+        self.timeouts += other.timeouts;
+        self.crc_errors += other.crc_errors;
+        for (code, count) in &other.exceptions {
+            *self.exceptions.entry(*code).or_default() += count;
+        }
+        self.connection_errors += other.connection_errors;
+        self.protocol_errors += other.protocol_errors;
+        self.latencies.extend(other.latencies.iter().copied());
+        let timestamp = |stats: &Stats| stats.last_error.as_ref().map(|error| error.timestamp);
+        if timestamp(other) > timestamp(self) {
+            self.last_error.clone_from(&other.last_error);
+        }
+    }
+
+    // 转换为响应数据
+    fn data(&self) -> ModbusDiagnosticsStatsData {
+        ModbusDiagnosticsStatsData {
+            requests: self.requests,
+            successes: self.successes,
+            timeouts: self.timeouts,
+            crc_errors: self.crc_errors,
+            exceptions: self
+                .exceptions
+                .iter()
+                .map(|(code, count)| ModbusExceptionCountData {
+                    code: *code,
+                    description: ExceptionCode::from_code(*code).description().to_string(),
+                    count: *count,
+                })
+                .collect(),
+            connection_errors: self.connection_errors,
+            protocol_errors: self.protocol_errors,
+            latency: latency_data(&self.latencies),
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+// 延迟分位数（最近秩法）
+fn latency_data(samples: &VecDeque<Duration>) -> ModbusLatencyData {
+    let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+    sorted.sort_unstable();
+    let percentile = |percent: usize| {
+        let rank = (sorted.len() * percent).div_ceil(100).max(1);
+        sorted.get(rank - 1).copied().map(millis)
+    };
+    ModbusLatencyData {
+        samples: u64::try_from(sorted.len()).unwrap_or(u64::MAX),
+        p50_ms: percentile(50),
+        p90_ms: percentile(90),
+        p99_ms: percentile(99),
+        max_ms: sorted.last().copied().map(millis),
+    }
+}
+
+// 毫秒数（保留到微秒）
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+// 从站诊断
+#[derive(Default)]
+struct SlaveLink {
+    stats: Stats,            // 计数与延迟
+    failing: Option<String>, // 失败中的错误类别（最近一次请求成功时为空）
+}
+
+impl SlaveLink {
+    // 通信状态
+    fn status(&self) -> String {
+        if self.failing.is_some() {
+            "failing".to_string()
+        } else {
+            "ok".to_string()
+        }
+    }
+}
+
+// 报文捕获
+struct Capture {
+    enabled: bool,                     // 是否正在捕获
+    capacity: u32,                     // 环形缓冲区容量（帧）
+    frames: VecDeque<ModbusFrameData>, // 捕获的帧
+    dropped: u64,                      // 缓冲区满后丢弃的帧数
+}
+
+impl Capture {
+    // 记录一次事务的请求帧与响应字节（没有收发字节时不记录）
+    fn record(&mut self, transaction: &Transaction<'_>, error: Option<&ModbusDiagnosticErrorData>) {
+        if !self.enabled {
+            return;
+        }
+        let frame = |direction: &str, timestamp: i64, bytes: &[u8]| ModbusFrameData {
+            timestamp,
+            direction: direction.to_string(),
+            unit_id: transaction.unit_id,
+            function_code: transaction.function_code,
+            hex: hex(bytes),
+            error: None,
+        };
+        let mut frames = Vec::with_capacity(2);
+        if !transaction.wire.sent.is_empty() {
+            frames.push(frame("tx", transaction.started_at, &transaction.wire.sent));
+        }
+        if !transaction.wire.received.is_empty() {
+            frames.push(frame(
+                "rx",
+                transaction.finished_at,
+                &transaction.wire.received,
+            ));
+        }
+        if let Some(last) = frames.last_mut() {
+            last.error = error.map(|error| error.message.clone());
+        }
+        for frame in frames {
+            self.frames.push_back(frame);
+            while self.frames.len() > self.capacity as usize {
+                self.frames.pop_front();
+                self.dropped += 1;
+            }
+        }
+    }
+
+    // 捕获状态
+    fn status(&self) -> ModbusCaptureStatusData {
+        ModbusCaptureStatusData {
+            enabled: self.enabled,
+            capacity: self.capacity,
+            frames: u64::try_from(self.frames.len()).unwrap_or(u64::MAX),
+            dropped: self.dropped,
+        }
+    }
+}
+
+// 空格分隔的大写十六进制
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// 网关的通信诊断
+pub struct Link {
+    gateway_id: String,              // 网关标识
+    since: i64,                      // 开始统计的时间戳（毫秒）
+    stats: Stats,                    // 网关合计
+    slaves: BTreeMap<u8, SlaveLink>, // 单元号 → 从站诊断
+    capture: Capture,                // 报文捕获
+}
+
+impl Link {
+    /// 创建网关诊断
+    pub fn new(gateway_id: &str, now: i64) -> Self {
+        Self {
+            gateway_id: gateway_id.to_string(),
+            since: now,
+            stats: Stats::default(),
+            slaves: BTreeMap::new(),
+            capture: Capture {
+                enabled: false,
+                capacity: DEFAULT_CAPTURE_CAPACITY,
+                frames: VecDeque::new(),
+                dropped: 0,
+            },
+        }
+    }
+
+    /// 记录一次事务
+    ///
+    /// # 返回
+    /// * 从站开始失败、失败类别变化或恢复正常时返回状态事件
+    pub fn record(&mut self, transaction: &Transaction<'_>) -> Option<ModbusDiagnosticsEvent> {
+        let error = transaction.error.map(|err| ModbusDiagnosticErrorData {
+            timestamp: transaction.finished_at,
+            unit_id: transaction.unit_id,
+            function_code: transaction.function_code,
+            class: err.class().to_string(),
+            message: err.to_string(),
+        });
+        self.stats.record(transaction, error.as_ref());
+        self.capture.record(transaction, error.as_ref());
+        let slave = self.slaves.entry(transaction.unit_id).or_default();
+        slave.stats.record(transaction, error.as_ref());
+        let failing = error.as_ref().map(|error| error.class.clone());
+        if slave.failing == failing {
+            return None;
+        }
+        slave.failing = failing;
+        Some(ModbusDiagnosticsEvent {
+            timestamp: transaction.finished_at,
+            gateway_id: self.gateway_id.clone(),
+            unit_id: transaction.unit_id,
+            status: slave.status(),
+            error,
+            stats: slave.stats.data(),
+        })
+    }
+
+    /// 网关标识
+    pub fn gateway_id(&self) -> &str {
+        &self.gateway_id
+    }
+
+    /// 诊断数据
+    pub fn data(&self) -> ModbusDiagnosticsData {
+        self.build_data(&self.stats, None)
+    }
+
+    /// 限定在指定从站内的诊断数据（网关合计只汇总这些从站）
+    ///
+    /// # 参数
+    /// * `units` - 可见的从站单元号
+    pub fn data_within(&self, units: &BTreeSet<u8>) -> ModbusDiagnosticsData {
+        let mut stats = Stats::default();
+        for (_, slave) in self
+            .slaves
+            .iter()
+            .filter(|(unit_id, _)| units.contains(unit_id))
+        {
+            stats.merge(&slave.stats);
+        }
+        self.build_data(&stats, Some(units))
+    }
+
+    // 生成诊断数据（units 为空时包含全部从站）
+    fn build_data(&self, stats: &Stats, units: Option<&BTreeSet<u8>>) -> ModbusDiagnosticsData {
+        ModbusDiagnosticsData {
+            gateway_id: self.gateway_id.clone(),
+            since: self.since,
+            stats: stats.data(),
+            slaves: self
+                .slaves
+                .iter()
+                .filter(|(unit_id, _)| units.is_none_or(|units| units.contains(unit_id)))
+                .map(|(unit_id, slave)| ModbusSlaveDiagnosticsData {
+                    unit_id: *unit_id,
+                    status: slave.status(),
+                    stats: slave.stats.data(),
+                })
+                .collect(),
+            capture: self.capture.status(),
+        }
+    }
+
+    /// 清零计数、延迟与最近错误（报文捕获不受影响）
+    pub fn reset(&mut self, now: i64) {
+        self.since = now;
+        self.stats = Stats::default();
+        self.slaves.clear();
+    }
+
+    /// 开启报文捕获（清空之前捕获的帧）
+    pub fn start_capture(&mut self, capacity: u32) {
+        self.capture.enabled = true;
+        self.capture.capacity = capacity;
+        self.capture.frames.clear();
+        self.capture.dropped = 0;
+    }
+
+    /// 停止报文捕获（保留已捕获的帧供导出）
+    pub fn stop_capture(&mut self) {
+        self.capture.enabled = false;
+    }
+
+    /// 导出捕获的帧
+    ///
+    /// # 参数
+    /// * `clear` - 导出后是否清空导出的帧
+    /// * `units` - 可见的从站单元号（为空时导出全部帧；限定时只导出并清空这些从站的帧）
+    pub fn export_capture(
+        &mut self,
+        clear: bool,
+        units: Option<&BTreeSet<u8>>,
+    ) -> ModbusCaptureData {
+        let visible =
+            |frame: &ModbusFrameData| units.is_none_or(|units| units.contains(&frame.unit_id));
+        let frames: Vec<ModbusFrameData> = self
+            .capture
+            .frames
+            .iter()
+            .filter(|frame| visible(frame))
+            .cloned()
+            .collect();
+        let text = frames
+            .iter()
+            .map(|frame| {
+                let mut line = format!(
+                    "{} {} {}",
+                    frame.timestamp,
+                    frame.direction.to_uppercase(),
+                    frame.hex
+                );
+                if let Some(error) = &frame.error {
+                    line.push_str(" # ");
+                    line.push_str(error);
+                }
+                line.push('\n');
+                line
+            })
+            .collect();
+        let data = ModbusCaptureData {
+            gateway_id: self.gateway_id.clone(),
+            enabled: self.capture.enabled,
+            capacity: self.capture.capacity,
+            dropped: self.capture.dropped,
+            frames,
+            text,
+        };
+        if clear {
+            self.capture.frames.retain(|frame| !visible(frame));
+            if units.is_none() {
+                self.capture.dropped = 0;
+            }
+        }
+        data
+    }
+}
+
+// 网关标识 → 诊断
+fn links() -> &'static Mutex<BTreeMap<String, SharedLink>> {
+    static LINKS: OnceLock<Mutex<BTreeMap<String, SharedLink>>> = OnceLock::new();
+    LINKS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+// 事件发送函数（应用启动时设置为 Tauri 事件发送）
+fn sink() -> &'static Mutex<Option<Sink>> {
+    static SINK: OnceLock<Mutex<Option<Sink>>> = OnceLock::new();
+    SINK.get_or_init(|| Mutex::new(None))
+}
+
+/// 获取网关诊断（不存在时创建）
+pub fn link(gateway_id: &str) -> SharedLink {
+    let mut links = lock(links());
+    Arc::clone(links.entry(gateway_id.to_string()).or_insert_with(|| {
+        Arc::new(Mutex::new(Link::new(
+            gateway_id,
+            i64::try_from(now_millis()).unwrap_or(i64::MAX),
+        )))
+    }))
+}
+
+/// 查找网关诊断
+pub fn get(gateway_id: &str) -> Option<SharedLink> {
+    lock(links()).get(gateway_id).cloned()
+}
+
+/// 移除网关诊断（网关删除时调用）
+pub fn remove(gateway_id: &str) -> Option<SharedLink> {
+    lock(links()).remove(gateway_id)
+}
+
+/// 全部网关诊断（按网关标识排序）
+pub fn all() -> Vec<SharedLink> {
+    lock(links()).values().cloned().collect()
+}
+
+/// 记录一次事务，从站通信状态变化时发送事件（不持有诊断锁）
+pub fn record(link: &SharedLink, transaction: &Transaction<'_>) {
+    let event = lock(link).record(transaction);
+    let Some(event) = event else {
+        return;
+    };
+    let Some(sink) = lock(sink()).clone() else {
+        return;
+    };
+    sink(&event);
+}
+
+/// 设置 Tauri 应用句柄，状态变化事件通过 Tauri 事件系统发送给前端
+pub fn set_app_handle(handle: AppHandle) {
+    set_sink(Arc::new(move |event| {
+        if let Err(err) = handle.emit(EVENT, event) {
+            tracing::warn!(error = %err, "emit modbus diagnostics event failed");
+        }
+    }));
+}
+
+/// 设置事件发送函数
+pub(crate) fn set_sink(sink_fn: Sink) {
+    *lock(sink()) = Some(sink_fn);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction<'a>(
+        unit_id: u8,
+        started_at: i64,
+        round_trip_ms: Option<u64>,
+        wire: &'a WireLog,
+        error: Option<&'a ModbusError>,
+    ) -> Transaction<'a> {
+        Transaction {
+            unit_id,
+            function_code: 0x03,
+            started_at,
+            finished_at: started_at + 5,
+            round_trip: round_trip_ms.map(Duration::from_millis),
+            wire,
+            error,
+        }
+    }
+
+    // 单元 1：100 次成功（延迟 1–100 毫秒）、两次超时、CRC 错误、异常响应后恢复；
+    // 单元 2：离线与单元号不符
+    fn outcomes() -> Vec<(u8, i64, Option<u64>, Option<ModbusError>)> {
+        let timeout = || Some(ModbusError::Timeout(500));
+        let mut outcomes: Vec<_> = (1..=100)
+            .map(|round_trip| (1, 0, Some(round_trip), None))
+            .collect();
+        outcomes.extend([
+            (1, 10, Some(500), timeout()),
+            (1, 20, Some(500), timeout()),
+            (
+                1,
+                30,
+                Some(3),
+                Some(ModbusError::Protocol(
+                    "crc mismatch: expected 0x840A, got 0x0000".to_string(),
+                )),
+            ),
+            (
+                1,
+                40,
+                Some(150),
+                Some(ModbusError::Exception {
+                    function: 0x03,
+                    code: ExceptionCode::IllegalDataAddress,
+                }),
+            ),
+            (1, 50, Some(4), None),
+            (2, 60, None, Some(ModbusError::Offline(200))),
+            (
+                2,
+                70,
+                Some(2),
+                Some(ModbusError::Protocol(
+                    "unit id mismatch: expected 1, got 2".to_string(),
+                )),
+            ),
+        ]);
+        outcomes
+    }
+
+    // 依次记录全部事务，返回产生的状态事件
+    fn record_outcomes(link: &mut Link) -> Vec<ModbusDiagnosticsEvent> {
+        let wire = WireLog::default();
+        outcomes()
+            .iter()
+            .filter_map(|(unit_id, started_at, round_trip, error)| {
+                link.record(&transaction(
+                    *unit_id,
+                    *started_at,
+                    *round_trip,
+                    &wire,
+                    error.as_ref(),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_outcomes_per_class_and_slave() {
+        let mut link = Link::new("gw", 1_000);
+        record_outcomes(&mut link);
+        let data = link.data();
+        let stats = &data.stats;
+        assert_eq!(
+            (
+                stats.requests,
+                stats.successes,
+                stats.timeouts,
+                stats.crc_errors,
+                stats.connection_errors,
+                stats.protocol_errors
+            ),
+            (107, 101, 2, 1, 1, 1)
+        );
+        assert_eq!(
+            stats.exceptions,
+            vec![ModbusExceptionCountData {
+                code: 2,
+                description: "illegal data address".to_string(),
+                count: 1,
+            }]
+        );
+        assert_eq!(
+            stats
+                .last_error
+                .as_ref()
+                .map(|error| (error.unit_id, error.timestamp)),
+            Some((2, 75))
+        );
+        let slaves: Vec<(u8, &str, u64)> = data
+            .slaves
+            .iter()
+            .map(|slave| (slave.unit_id, slave.status.as_str(), slave.stats.requests))
+            .collect();
+        assert_eq!(slaves, vec![(1, "ok", 105), (2, "failing", 2)]);
+
+        link.reset(2_000);
+        let data = link.data();
+        assert_eq!(
+            (data.since, data.stats.requests, data.slaves.len()),
+            (2_000, 0, 0)
+        );
+    }
+
+    #[test]
+    fn limits_data_to_visible_slaves() {
+        let mut link = Link::new("gw", 1_000);
+        record_outcomes(&mut link);
+        // 网关合计只汇总可见从站，最近一次错误同样取自可见从站
+        let data = link.data_within(&BTreeSet::from([1]));
+        assert_eq!(
+            data.slaves
+                .iter()
+                .map(|slave| slave.unit_id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(
+            (
+                data.stats.requests,
+                data.stats.connection_errors,
+                data.stats.latency.samples
+            ),
+            (105, 0, 102)
+        );
+        assert_eq!(
+            data.stats
+                .last_error
+                .as_ref()
+                .map(|error| (error.unit_id, error.timestamp)),
+            Some((1, 45))
+        );
+        let empty = link.data_within(&BTreeSet::new());
+        assert_eq!((empty.stats.requests, empty.slaves.len()), (0, 0));
+    }
+
+    #[test]
+    fn computes_latency_percentiles_from_responses() {
+        let mut link = Link::new("gw", 1_000);
+        record_outcomes(&mut link);
+        // 只统计收到响应的事务：1–100、150 与 4 毫秒
+        let latency = link.data().stats.latency;
+        assert_eq!(
+            (
+                latency.samples,
+                latency.p50_ms,
+                latency.p90_ms,
+                latency.p99_ms,
+                latency.max_ms
+            ),
+            (102, Some(50.0), Some(91.0), Some(100.0), Some(150.0))
+        );
+        link.reset(2_000);
+        assert_eq!(link.data().stats.latency.p50_ms, None);
+    }
+
+    #[test]
+    fn emits_events_only_when_the_slave_state_changes() {
+        let mut link = Link::new("gw", 1_000);
+        let events = record_outcomes(&mut link);
+        // 首次成功不产生事件；同一类别的失败不重复发送事件，类别变化时发送
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (
+                    event.unit_id,
+                    event.status.as_str(),
+                    event.error.as_ref().map(|error| error.class.as_str())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "failing", Some("timeout")),
+                (1, "failing", Some("protocol")),
+                (1, "failing", Some("exception")),
+                (1, "ok", None),
+                (2, "failing", Some("offline")),
+                (2, "failing", Some("protocol")),
+            ]
+        );
+        let failed = &events[0];
+        assert_eq!(failed.stats.timeouts, 1);
+        assert_eq!(
+            failed.error.as_ref().map(|error| error.message.as_str()),
+            Some("timeout after 500ms")
+        );
+    }
+
+    #[test]
+    fn captures_frames_in_a_ring_buffer_and_exports_hex() {
+        let mut link = Link::new("gw", 0);
+        let wire = WireLog {
+            sent: vec![0x01, 0x03, 0x00, 0x00, 0x00, 0x01, 0x84, 0x0A],
+            received: vec![0x01, 0x03, 0x02, 0x00, 0x2A, 0x38, 0x5B],
+        };
+        // 未开启时不捕获
+        link.record(&transaction(1, 100, Some(5), &wire, None));
+        assert_eq!(link.data().capture.frames, 0);
+
+        link.start_capture(3);
+        link.record(&transaction(1, 200, Some(5), &wire, None));
+        let timeout = ModbusError::Timeout(500);
+        let sent_only = WireLog {
+            sent: wire.sent.clone(),
+            received: Vec::new(),
+        };
+        link.record(&transaction(1, 300, None, &sent_only, Some(&timeout)));
+        assert_eq!(
+            link.data().capture,
+            ModbusCaptureStatusData {
+                enabled: true,
+                capacity: 3,
+                frames: 3,
+                dropped: 0,
+            }
+        );
+        link.record(&transaction(
+            1,
+            400,
+            None,
+            &WireLog::default(),
+            Some(&timeout),
+        ));
+        link.record(&transaction(1, 500, Some(5), &wire, None));
+        link.stop_capture();
+        link.record(&transaction(1, 600, Some(5), &wire, None));
+
+        let export = link.export_capture(true, None);
+        assert_eq!(
+            (export.enabled, export.dropped, export.frames.len()),
+            (false, 2, 3)
+        );
+        assert_eq!(
+            export.text,
+            "300 TX 01 03 00 00 00 01 84 0A # timeout after 500ms\n\
+             500 TX 01 03 00 00 00 01 84 0A\n\
+             505 RX 01 03 02 00 2A 38 5B\n"
+        );
+        assert_eq!(
+            export.frames[0].error.as_deref(),
+            Some("timeout after 500ms")
+        );
+        let cleared = link.export_capture(false, None);
+        assert_eq!(
+            (cleared.frames.len(), cleared.dropped, cleared.text.as_str()),
+            (0, 0, "")
+        );
+
+        // 限定从站时只导出并清空这些从站的帧
+        link.start_capture(10);
+        link.record(&transaction(1, 700, Some(5), &wire, None));
+        link.record(&transaction(2, 800, None, &sent_only, Some(&timeout)));
+        let units = BTreeSet::from([2]);
+        let scoped = link.export_capture(true, Some(&units));
+        assert_eq!(
+            scoped
+                .frames
+                .iter()
+                .map(|frame| (frame.unit_id, frame.timestamp))
+                .collect::<Vec<_>>(),
+            vec![(2, 800)]
+        );
+        assert_eq!(link.export_capture(false, None).frames.len(), 2);
+    }
+}
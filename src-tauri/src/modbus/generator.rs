@@ -0,0 +1,534 @@
+//! 模拟器取值生成器与寄存器映射
+//!
+//! 为进程内从站模拟器按设备模板生成寄存器数据：
+//! - [`Generator`]：常量、斜坡、正弦、随机游走与累加计数器（电能等累计量）
+//! - [`RegisterMap`]：由模板点位生成的寄存器映射，每次刷新按生成器计算工程值，
+//!   反算原始值（`(工程值 - 偏移量) / 缩放系数`）后按数据类型与字节序写入从站数据表
+//! - [`Rng`]：可指定种子的伪随机数（xorshift64*），同一种子的取值序列可复现
+//!
+//! 本模块为内存计算，不涉及通信。
+
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+// 引入模板点位定义
+use crate::device_template::models::PointDefinition;
+// 引入字节序（多寄存器类型的字节排列）
+use crate::modbus::codec::ByteOrder;
+// 引入从站数据表
+use crate::modbus::simulator::SlaveMemory;
+
+// 种子混淆常数（避免种子 0 使 xorshift 停在 0）
+const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// 伪随机数（xorshift64*）
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64, // 内部状态（非 0）
+}
+
+impl Rng {
+    /// 以种子创建（同一种子产生相同的序列）
+    pub fn new(seed: u64) -> Self {
+        let state = seed ^ SEED_MIX;
+        Self {
+            state: if state == 0 { SEED_MIX } else { state },
+        }
+    }
+
+    /// 下一个 64 位随机数
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// 下一个 [0, 1) 区间的随机数
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+/// 取值生成器（生成工程值）
+#[derive(Debug, Clone, PartialEq)]
+pub enum Generator {
+    /// 常量
+    Constant { value: f64 },
+    /// 斜坡：每次刷新增加 `step`，超过 `max` 回到 `min`（`step` 为负时低于 `min` 回到 `max`）
+    Ramp {
+        start: f64,
+        step: f64,
+        min: f64,
+        max: f64,
+    },
+    /// 正弦：`offset + amplitude * sin(2π * t / period)`，`t` 为模拟器启动后经过的时间
+    Sine {
+        offset: f64,
+        amplitude: f64,
+        period: Duration,
+    },
+    /// 随机游走：每次刷新在 `[-step, step]` 内随机变化，限制在 `[min, max]`
+    RandomWalk {
+        start: f64,
+        step: f64,
+        min: f64,
+        max: f64,
+    },
+    /// 累加计数器：每次刷新增加 `increment`（电能等只增不减的累计量）
+    Counter { start: f64, increment: f64 },
+}
+
+impl Generator {
+    /// 生成器类型名称
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Constant { .. } => "constant",
+            Self::Ramp { .. } => "ramp",
+            Self::Sine { .. } => "sine",
+            Self::RandomWalk { .. } => "random_walk",
+            Self::Counter { .. } => "counter",
+        }
+    }
+
+    /// 计算下一个值
+    ///
+    /// # 参数
+    /// * `current` - 当前值（首次刷新时为 None）
+    /// * `elapsed` - 模拟器启动后经过的时间
+    /// * `rng` - 随机数（随机游走使用）
+    pub fn next(&self, current: Option<f64>, elapsed: Duration, rng: &mut Rng) -> f64 {
+        match *self {
+            Self::Constant { value } => value,
+            Self::Ramp {
+                start,
+                step,
+                min,
+                max,
+            } => match current {
+                None => start.clamp(min, max),
+                Some(value) => {
+                    let next = value + step;
+                    if step >= 0.0 && next > max {
+                        min
+                    } else if step < 0.0 && next < min {
+                        max
+                    } else {
+                        next
+                    }
+                }
+            },
+            Self::Sine {
+                offset,
+                amplitude,
+                period,
+            } => {
+                let phase = elapsed.as_secs_f64() / period.as_secs_f64().max(f64::EPSILON);
+                offset + amplitude * (TAU * phase).sin()
+            }
+            Self::RandomWalk {
+                start,
+                step,
+                min,
+                max,
+            } => match current {
+                None => start.clamp(min, max),
+                Some(value) => (value + (rng.next_f64() * 2.0 - 1.0) * step).clamp(min, max),
+            },
+            Self::Counter { start, increment } => current.map_or(start, |value| value + increment),
+        }
+    }
+}
+
+/// 寄存器表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegisterTable {
+    Coil,            // 线圈
+    DiscreteInput,   // 离散输入
+    HoldingRegister, // 保持寄存器
+    InputRegister,   // 输入寄存器
+}
+
+impl RegisterTable {
+    /// 由模板寄存器类型解析（coil / discrete_input / holding_register / input_register）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "coil" => Some(Self::Coil),
+            "discrete_input" => Some(Self::DiscreteInput),
+            "holding_register" => Some(Self::HoldingRegister),
+            "input_register" => Some(Self::InputRegister),
+            _ => None,
+        }
+    }
+
+    /// 模板寄存器类型名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Coil => "coil",
+            Self::DiscreteInput => "discrete_input",
+            Self::HoldingRegister => "holding_register",
+            Self::InputRegister => "input_register",
+        }
+    }
+
+    /// 是否为位表（线圈与离散输入）
+    pub fn is_bits(self) -> bool {
+        matches!(self, Self::Coil | Self::DiscreteInput)
+    }
+}
+
+/// 映射中的点位
+#[derive(Debug, Clone)]
+pub struct SimPoint {
+    pub key: String,                  // 点位标识
+    pub table: RegisterTable,         // 寄存器表
+    pub address: u16,                 // 起始地址
+    pub count: u16,                   // 占用的寄存器（或线圈）数量
+    pub data_type: String,            // 模板数据类型
+    pub byte_order: ByteOrder,        // 多寄存器字节序
+    pub scale: f64,                   // 缩放系数
+    pub offset: f64,                  // 偏移量
+    pub generator: Option<Generator>, // 取值生成器（为空时不刷新，保持数据表中的值）
+    pub value: Option<f64>,           // 最近一次写入的工程值
+}
+
+impl SimPoint {
+    // 工程值编码为寄存器（报文顺序）
+    fn registers(&self, value: f64) -> Vec<u16> {
+        let raw = if self.scale == 0.0 {
+            value - self.offset
+        } else {
+            (value - self.offset) / self.scale
+        };
+        let mut bytes = raw_bytes(&self.data_type, raw);
+        if bytes.len() > 2 {
+            self.byte_order.reorder(&mut bytes);
+        }
+        bytes
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+}
+
+/// 模板寄存器映射
+#[derive(Debug, Clone)]
+pub struct RegisterMap {
+    points: Vec<SimPoint>, // 点位（按模板顺序）
+    rng: Rng,              // 随机数
+}
+
+impl RegisterMap {
+    /// 由模板点位生成寄存器映射（尚未绑定生成器）
+    ///
+    /// # 参数
+    /// * `definitions` - 模板点位定义
+    /// * `seed` - 随机种子
+    ///
+    /// # 返回
+    /// * 寄存器映射；数据类型、寄存器类型或地址无效时返回点位标识与原因
+    pub fn from_template(definitions: &[PointDefinition], seed: u64) -> Result<Self, String> {
+        let points = definitions
+            .iter()
+            .map(|definition| {
+                let invalid = |reason: &str| format!("point {}: {reason}", definition.key);
+                let count = register_count(&definition.data_type)
+                    .ok_or_else(|| invalid("unsupported data type"))?;
+                let table = RegisterTable::parse(&definition.register_type)
+                    .ok_or_else(|| invalid("unsupported register type"))?;
+                let address = u16::try_from(definition.address)
+                    .ok()
+                    .filter(|address| u32::from(*address) + u32::from(count) <= 0x1_0000)
+                    .ok_or_else(|| invalid("address out of range"))?;
+                Ok(SimPoint {
+                    key: definition.key.clone(),
+                    table,
+                    address,
+                    count: if table.is_bits() { 1 } else { count },
+                    data_type: definition.data_type.clone(),
+                    byte_order: ByteOrder::parse(&definition.byte_order).unwrap_or(ByteOrder::ABCD),
+                    scale: definition.scale,
+                    offset: definition.offset,
+                    generator: None,
+                    value: None,
+                })
+            })
+            .collect::<Result<Vec<SimPoint>, String>>()?;
+        Ok(Self {
+            points,
+            rng: Rng::new(seed),
+        })
+    }
+
+    /// 为点位绑定生成器
+    ///
+    /// # 返回
+    /// * 点位不存在时返回 false
+    pub fn set_generator(&mut self, key: &str, generator: Generator) -> bool {
+        match self.points.iter_mut().find(|point| point.key == key) {
+            Some(point) => {
+                point.generator = Some(generator);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 全部点位
+    pub fn points(&self) -> &[SimPoint] {
+        &self.points
+    }
+
+    /// 容纳全部点位所需的数据表大小
+    pub fn required_size(&self) -> usize {
+        self.points
+            .iter()
+            .map(|point| usize::from(point.address) + usize::from(point.count))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 刷新：按生成器计算全部点位的新值并写入数据表
+    ///
+    /// # 参数
+    /// * `memory` - 从站数据表（越出数据表的点位不写入）
+    /// * `elapsed` - 模拟器启动后经过的时间
+    pub fn tick(&mut self, memory: &mut SlaveMemory, elapsed: Duration) {
+        for point in &mut self.points {
+            let Some(generator) = &point.generator else {
+                continue;
+            };
+            let value = generator.next(point.value, elapsed, &mut self.rng);
+            point.value = Some(value);
+            let address = usize::from(point.address);
+            match point.table {
+                RegisterTable::Coil => set(&mut memory.coils, address, &[value != 0.0]),
+                RegisterTable::DiscreteInput => {
+                    set(&mut memory.discrete_inputs, address, &[value != 0.0]);
+                }
+                RegisterTable::HoldingRegister => {
+                    set(
+                        &mut memory.holding_registers,
+                        address,
+                        &point.registers(value),
+                    );
+                }
+                RegisterTable::InputRegister => {
+                    set(
+                        &mut memory.input_registers,
+                        address,
+                        &point.registers(value),
+                    );
+                }
+            }
+        }
+    }
+}
+
+// 模板数据类型占用的寄存器数量
+fn register_count(data_type: &str) -> Option<u16> {
+    match data_type {
+        "bool" | "int16" | "uint16" => Some(1),
+        "int32" | "uint32" | "float32" => Some(2),
+        "int64" | "uint64" | "float64" => Some(4),
+        _ => None,
+    }
+}
+
+// 原始值按数据类型编码为大端字节（整数四舍五入并截断到类型范围）
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn raw_bytes(data_type: &str, raw: f64) -> Vec<u8> {
+    let integer = raw.round();
+    match data_type {
+        "int16" => (integer as i16).to_be_bytes().to_vec(),
+        "uint16" => (integer as u16).to_be_bytes().to_vec(),
+        "int32" => (integer as i32).to_be_bytes().to_vec(),
+        "uint32" => (integer as u32).to_be_bytes().to_vec(),
+        "int64" => (integer as i64).to_be_bytes().to_vec(),
+        "uint64" => (integer as u64).to_be_bytes().to_vec(),
+        "float32" => (raw as f32).to_be_bytes().to_vec(),
+        "float64" => raw.to_be_bytes().to_vec(),
+        _ => u16::from(raw != 0.0).to_be_bytes().to_vec(),
+    }
+}
+
+// 写入数据表区间（越界时忽略）
+fn set<T: Copy>(table: &mut [T], address: usize, values: &[T]) {
+    if let Some(target) = table.get_mut(address..address + values.len()) {
+        target.copy_from_slice(values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(
+        key: &str,
+        data_type: &str,
+        register_type: &str,
+        address: i32,
+    ) -> PointDefinition {
+        PointDefinition {
+            key: key.to_string(),
+            name: key.to_string(),
+            unit: None,
+            data_type: data_type.to_string(),
+            register_type: register_type.to_string(),
+            address,
+            byte_order: "abcd".to_string(),
+            scale: 1.0,
+            offset: 0.0,
+            access: "read".to_string(),
+        }
+    }
+
+    // 连续刷新得到的值
+    fn sequence(generator: &Generator, ticks: u64, rng: &mut Rng) -> Vec<f64> {
+        let mut current = None;
+        (0..ticks)
+            .map(|tick| {
+                let value = generator.next(current, Duration::from_millis(tick * 250), rng);
+                current = Some(value);
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generators_produce_expected_sequences() {
+        let mut rng = Rng::new(7);
+        let ramp = Generator::Ramp {
+            start: 0.0,
+            step: 5.0,
+            min: 0.0,
+            max: 10.0,
+        };
+        assert_eq!(sequence(&ramp, 5, &mut rng), vec![0.0, 5.0, 10.0, 0.0, 5.0]);
+        let falling = Generator::Ramp {
+            start: 20.0,
+            step: -5.0,
+            min: 0.0,
+            max: 10.0,
+        };
+        assert_eq!(sequence(&falling, 4, &mut rng), vec![10.0, 5.0, 0.0, 10.0]);
+        let counter = Generator::Counter {
+            start: 100.0,
+            increment: 2.5,
+        };
+        assert_eq!(sequence(&counter, 3, &mut rng), vec![100.0, 102.5, 105.0]);
+        assert_eq!(
+            sequence(&Generator::Constant { value: 3.5 }, 2, &mut rng),
+            vec![3.5, 3.5]
+        );
+
+        // 正弦按经过时间取值：周期 1 秒，每 250 毫秒取四分之一周期
+        let sine = Generator::Sine {
+            offset: 220.0,
+            amplitude: 10.0,
+            period: Duration::from_secs(1),
+        };
+        let values = sequence(&sine, 4, &mut rng);
+        for (value, expected) in values.iter().zip([220.0, 230.0, 220.0, 210.0]) {
+            assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+        }
+
+        // 随机游走限制在区间内、单步不超过步长，同一种子可复现
+        let walk = Generator::RandomWalk {
+            start: 50.0,
+            step: 4.0,
+            min: 45.0,
+            max: 55.0,
+        };
+        let values = sequence(&walk, 200, &mut Rng::new(42));
+        assert!((values[0] - 50.0).abs() < 1e-9, "{} != 50", values[0]);
+        assert!(values.iter().all(|value| (45.0..=55.0).contains(value)));
+        assert!(
+            values
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0]).abs() <= 4.0)
+        );
+        assert!(
+            values
+                .windows(2)
+                .any(|pair| (pair[1] - pair[0]).abs() > 1e-9)
+        );
+        assert_eq!(values, sequence(&walk, 200, &mut Rng::new(42)));
+        assert_ne!(values, sequence(&walk, 200, &mut Rng::new(43)));
+    }
+
+    #[test]
+    fn register_map_encodes_template_points() {
+        let mut voltage = definition("voltage", "uint16", "input_register", 0);
+        voltage.scale = 0.1;
+        let mut energy = definition("energy", "uint32", "holding_register", 2);
+        energy.byte_order = "cdab".to_string();
+        let mut temperature = definition("temperature", "int16", "holding_register", 1);
+        temperature.offset = -40.0;
+        let definitions = vec![
+            voltage,
+            temperature,
+            energy,
+            definition("total", "int64", "holding_register", 4),
+            definition("power", "float32", "input_register", 1),
+            definition("running", "bool", "coil", 3),
+            definition("idle", "bool", "holding_register", 8),
+        ];
+        let mut map = RegisterMap::from_template(&definitions, 1).expect("register map");
+        assert_eq!(map.required_size(), 9);
+        assert_eq!(map.points()[0].table, RegisterTable::InputRegister);
+        for (key, generator) in [
+            ("voltage", Generator::Constant { value: 230.4 }),
+            ("temperature", Generator::Constant { value: -50.0 }),
+            (
+                "energy",
+                Generator::Counter {
+                    start: 131_073.0,
+                    increment: 1.0,
+                },
+            ),
+            ("total", Generator::Constant { value: -2.0 }),
+            ("power", Generator::Constant { value: 1.5 }),
+            ("running", Generator::Constant { value: 1.0 }),
+        ] {
+            assert!(map.set_generator(key, generator));
+        }
+        assert!(!map.set_generator("missing", Generator::Constant { value: 0.0 }));
+
+        let mut memory = SlaveMemory::new(10);
+        memory.holding_registers[8] = 7;
+        map.tick(&mut memory, Duration::ZERO);
+        // 原始值 = (工程值 - 偏移量) / 缩放系数，整数四舍五入
+        assert_eq!(memory.input_registers[..3], [2304, 0x3FC0, 0x0000]);
+        assert_eq!(
+            memory.holding_registers[..8],
+            [0, 0xFFF6, 0x0001, 0x0002, 0xFFFF, 0xFFFF, 0xFFFF, 0xFFFE]
+        );
+        assert!(memory.coils[3]);
+        // 未绑定生成器的点位保持数据表中的值
+        assert_eq!(memory.holding_registers[8], 7);
+        assert_eq!(map.points()[2].value, Some(131_073.0));
+
+        map.tick(&mut memory, Duration::from_secs(1));
+        assert_eq!(memory.holding_registers[2..4], [0x0002, 0x0002]);
+
+        // 数据表不足时越界的点位不写入
+        let mut small = SlaveMemory::new(2);
+        map.tick(&mut small, Duration::ZERO);
+        assert_eq!(small.input_registers, [2304, 0]);
+
+        let invalid =
+            RegisterMap::from_template(&[definition("text", "string", "holding_register", 0)], 1)
+                .expect_err("unsupported type");
+        assert_eq!(invalid, "point text: unsupported data type");
+        let invalid = RegisterMap::from_template(
+            &[definition("far", "float64", "holding_register", 65_533)],
+            1,
+        )
+        .expect_err("address out of range");
+        assert_eq!(invalid, "point far: address out of range");
+    }
+}
@@ -0,0 +1,38 @@
+//! Modbus 模块入口
+//!
+//! 本模块提供原生实现的 Modbus 通信能力：
+//! - 协议层：FC01–FC06、FC15、FC16 的 PDU 编解码与异常码映射
+//! - 传输层：Modbus TCP（MBAP）、RTU / ASCII over TCP 与串口 RTU / ASCII，各传输方式实现统一的传输接口
+//! - 寄存器编解码：按数据类型（Bool / 整数 / 浮点 / 字符串）与字节序解码、编码寄存器数组
+//! - 客户端：每个网关一条长连接，断线自动重连并指数退避，可配置建连与请求超时
+//! - 通信诊断：按网关与从站统计请求、超时、校验错误与异常码，延迟分位数、状态事件与原始报文捕获
+//! - 进程内从站模拟器，用于无设备联调与测试：按设备模板生成寄存器映射与取值，支持故障注入
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 连接与读写请求/响应结构
+pub mod models;
+// 公开协议模块 - PDU 编解码与错误类型
+pub mod protocol;
+// 公开编解码模块 - 数据类型、字节序与寄存器编解码
+pub mod codec;
+// 公开传输模块 - 传输接口、帧格式与 TCP 传输
+pub mod transport;
+// 公开 RTU 帧模块 - CRC 校验与按功能码成帧
+pub mod rtu;
+// 公开 ASCII 帧模块 - LRC 校验与十六进制字符成帧
+pub mod ascii;
+// 公开串口模块 - 串口参数、串口枚举与按串口共享的总线
+pub mod serial;
+// 公开客户端模块 - 长连接、重连退避与类型化读写
+pub mod client;
+// 公开状态模块 - 按网关保存的全局连接
+pub mod state;
+// 公开诊断模块 - 按网关与从站的通信计数、延迟、状态事件与报文捕获
+pub mod diagnostics;
+// 公开服务模块 - 权限校验、连接管理、读写与审计
+pub mod services;
+// 公开模拟器模块 - 进程内 Modbus 从站与故障注入
+pub mod simulator;
+// 公开生成器模块 - 模拟器取值生成器与模板寄存器映射
+pub mod generator;
@@ -0,0 +1,649 @@
+//! Modbus 模块数据模型
+//!
+//! 本模块定义 Modbus 连接管理与读写 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+// 建立 Modbus TCP 连接请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusTcpConnectPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识（同一网关重复连接时复用或替换已有连接）
+    pub gateway_id: String,
+    /// 目标主机（IPv4/IPv6 地址或主机名）
+    pub host: String,
+    /// 目标端口（默认 502）
+    pub port: Option<u16>,
+    /// 帧格式（mbap 默认；串口服务器透传时为 rtu 或 ascii）
+    pub framing: Option<String>,
+    /// 建连超时（毫秒，默认 3000）
+    pub connect_timeout_ms: Option<u64>,
+    /// 请求超时（毫秒，默认 1000）
+    pub request_timeout_ms: Option<u64>,
+    /// 初始重连间隔（毫秒，默认 500）
+    pub backoff_initial_ms: Option<u64>,
+    /// 最大重连间隔（毫秒，默认 30000）
+    pub backoff_max_ms: Option<u64>,
+}
+
+// 建立串口 Modbus RTU / ASCII 连接请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusRtuConnectPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识（同一串口可挂多个网关，共用一条总线）
+    pub gateway_id: String,
+    /// 串口路径（例如 /dev/ttyUSB0、COM3）
+    pub port: String,
+    /// 帧格式（rtu 默认，或 ascii）
+    pub framing: Option<String>,
+    /// 波特率（默认 9600）
+    pub baud_rate: Option<u32>,
+    /// 数据位（5–8，默认 8）
+    pub data_bits: Option<u8>,
+    /// 校验位（none / even / odd，默认 none）
+    pub parity: Option<String>,
+    /// 停止位（1 或 2，默认 1）
+    pub stop_bits: Option<u8>,
+    /// 帧间最小静默时间（毫秒，0–1000，默认按波特率取 3.5 个字符时间）
+    pub inter_frame_delay_ms: Option<u64>,
+    /// 建连超时（毫秒，默认 3000）
+    pub connect_timeout_ms: Option<u64>,
+    /// 请求超时（毫秒，默认 1000）
+    pub request_timeout_ms: Option<u64>,
+    /// 初始重连间隔（毫秒，默认 500）
+    pub backoff_initial_ms: Option<u64>,
+    /// 最大重连间隔（毫秒，默认 30000）
+    pub backoff_max_ms: Option<u64>,
+}
+
+// 指定网关的请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusGatewayPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+}
+
+// 查询连接列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusConnectionListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 查询本机串口请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSerialPortListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 读线圈/离散输入/寄存器请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusReadPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 起始地址（0-based 协议地址）
+    pub address: u16,
+    /// 读取数量（线圈/离散输入 1–2000，寄存器 1–125）
+    pub count: u16,
+}
+
+// 写单个线圈请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusWriteCoilPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 线圈地址
+    pub address: u16,
+    /// 写入值
+    pub value: bool,
+}
+
+// 写单个寄存器请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusWriteRegisterPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 寄存器地址
+    pub address: u16,
+    /// 写入值
+    pub value: u16,
+}
+
+// 写多个线圈请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusWriteCoilsPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 起始地址
+    pub address: u16,
+    /// 写入值（1–1968 个）
+    pub values: Vec<bool>,
+}
+
+// 写多个寄存器请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusWriteRegistersPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号（默认 1）
+    pub unit_id: Option<u8>,
+    /// 起始地址
+    pub address: u16,
+    /// 写入值（1–123 个）
+    pub values: Vec<u16>,
+}
+
+// 网关连接状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusConnectionData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 传输方式（tcp / rtu_over_tcp / ascii_over_tcp / rtu / ascii）
+    pub transport: String,
+    /// 连接目标（例如 192.168.1.100:502、/dev/ttyUSB0 9600 8N1）
+    pub target: String,
+    /// 当前是否已连接
+    pub connected: bool,
+    /// 成功建连次数（大于 1 表示发生过重连）
+    pub connect_count: u64,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+    /// 处于重连退避期时距下次重连的毫秒数
+    pub retry_in_ms: Option<u64>,
+    /// 建连超时（毫秒）
+    pub connect_timeout_ms: u64,
+    /// 请求超时（毫秒）
+    pub request_timeout_ms: u64,
+}
+
+// 本机串口
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSerialPortData {
+    /// 串口路径
+    pub name: String,
+    /// 连接方式（usb / pci / bluetooth / unknown）
+    pub port_type: String,
+    /// USB 厂商 ID
+    pub vid: Option<u16>,
+    /// USB 产品 ID
+    pub pid: Option<u16>,
+    /// USB 厂商名称
+    pub manufacturer: Option<String>,
+    /// USB 产品名称
+    pub product: Option<String>,
+    /// USB 序列号
+    pub serial_number: Option<String>,
+}
+
+// 线圈/离散输入读取结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusBitsData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 读取值
+    pub values: Vec<bool>,
+    /// 读取时间戳（毫秒）
+    pub read_at: i64,
+}
+
+// 寄存器读取结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusRegistersData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 读取值（原始 16 位寄存器）
+    pub values: Vec<u16>,
+    /// 读取时间戳（毫秒）
+    pub read_at: i64,
+}
+
+// 写入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusWriteData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 起始地址
+    pub address: u16,
+    /// 写入的线圈或寄存器数量
+    pub count: u16,
+    /// 写入时间戳（毫秒）
+    pub written_at: i64,
+}
+
+// 模拟器取值生成器
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorGeneratorSpec {
+    /// 模板点位标识
+    pub point_key: String,
+    /// 生成器类型（constant / ramp / sine / random_walk / counter）
+    pub kind: String,
+    /// 常量值（constant，默认 0）
+    pub value: Option<f64>,
+    /// 初值（ramp / random_walk 默认取下限，counter 默认 0）
+    pub start: Option<f64>,
+    /// 每次刷新的步长（ramp 可为负，random_walk 为最大变化量；默认 1）
+    pub step: Option<f64>,
+    /// 下限（ramp / random_walk，默认 0）
+    pub min: Option<f64>,
+    /// 上限（ramp / random_walk，默认 100）
+    pub max: Option<f64>,
+    /// 中心值（sine，默认 0）
+    pub offset: Option<f64>,
+    /// 振幅（sine，默认 1）
+    pub amplitude: Option<f64>,
+    /// 周期（毫秒，sine，默认 60000）
+    pub period_ms: Option<u64>,
+    /// 每次刷新的增量（counter，默认 1，不可为负）
+    pub increment: Option<f64>,
+}
+
+// 模拟器故障注入参数（省略的字段取 0，即不注入）
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorFaultSpec {
+    /// 响应延迟（毫秒，0–60000）
+    pub latency_ms: Option<u64>,
+    /// 随机增加的最大延迟（毫秒，0–60000）
+    pub jitter_ms: Option<u64>,
+    /// 返回异常响应的概率（0–1）
+    pub exception_rate: Option<f64>,
+    /// 异常码（默认 4，从站设备故障）
+    pub exception_code: Option<u8>,
+    /// 不响应并断开连接的概率（0–1）
+    pub drop_rate: Option<f64>,
+    /// 损坏响应帧的概率（0–1；RTU / ASCII 破坏 CRC / LRC，MBAP 破坏功能码）
+    pub corrupt_rate: Option<f64>,
+}
+
+// 启动模拟器请求体
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorStartPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模拟器标识
+    pub simulator_id: String,
+    /// 监听地址（默认 127.0.0.1:0，端口为 0 时由系统分配）
+    pub bind: Option<String>,
+    /// 帧格式（mbap 默认，响应任意单元号；rtu / ascii 模拟串口服务器后的单个从站）
+    pub framing: Option<String>,
+    /// 从站单元号（rtu / ascii，1–247，默认 1）
+    pub unit_id: Option<u8>,
+    /// 设备模板 ID（按模板点位生成寄存器映射）
+    pub template_id: Option<i64>,
+    /// 每张数据表的大小（默认取模板所需大小与 100 的较大值，最大 65536）
+    pub size: Option<u32>,
+    /// 生成器刷新周期（毫秒，10–60000，默认 1000）
+    pub tick_ms: Option<u64>,
+    /// 随机种子（随机游走与故障注入，默认取启动时间戳）
+    pub seed: Option<u64>,
+    /// 点位取值生成器（需指定模板）
+    pub generators: Vec<ModbusSimulatorGeneratorSpec>,
+    /// 故障注入参数
+    pub faults: ModbusSimulatorFaultSpec,
+}
+
+// 指定模拟器的请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模拟器标识
+    pub simulator_id: String,
+}
+
+// 查询模拟器列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 调整模拟器故障注入请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusSimulatorFaultsPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 模拟器标识
+    pub simulator_id: String,
+    /// 故障注入参数（整体替换）
+    pub faults: ModbusSimulatorFaultSpec,
+}
+
+// 模拟器故障注入配置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSimulatorFaultData {
+    /// 响应延迟（毫秒）
+    pub latency_ms: u64,
+    /// 随机增加的最大延迟（毫秒）
+    pub jitter_ms: u64,
+    /// 返回异常响应的概率
+    pub exception_rate: f64,
+    /// 异常码
+    pub exception_code: u8,
+    /// 断开连接的概率
+    pub drop_rate: f64,
+    /// 损坏响应帧的概率
+    pub corrupt_rate: f64,
+}
+
+// 模拟器请求统计
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSimulatorStatsData {
+    /// 收到的请求数
+    pub requests: u64,
+    /// 延迟响应的请求数
+    pub delayed: u64,
+    /// 注入异常响应的请求数
+    pub exceptions: u64,
+    /// 断开连接的请求数
+    pub dropped: u64,
+    /// 损坏响应帧的请求数
+    pub corrupted: u64,
+}
+
+// 模拟器寄存器映射中的点位
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSimulatorPointData {
+    /// 点位标识
+    pub point_key: String,
+    /// 寄存器类型
+    pub register_type: String,
+    /// 起始地址
+    pub address: u16,
+    /// 数据类型
+    pub data_type: String,
+    /// 生成器类型（未绑定时为空）
+    pub generator: Option<String>,
+    /// 最近一次写入的工程值
+    pub value: Option<f64>,
+}
+
+// 模拟器状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSimulatorData {
+    /// 模拟器标识
+    pub simulator_id: String,
+    /// 实际监听地址
+    pub host: String,
+    /// 实际监听端口
+    pub port: u16,
+    /// 帧格式
+    pub framing: String,
+    /// 从站单元号（mbap 响应任意单元号，为空）
+    pub unit_id: Option<u8>,
+    /// 设备模板 ID
+    pub template_id: Option<i64>,
+    /// 每张数据表的大小
+    pub size: u32,
+    /// 生成器刷新周期（毫秒）
+    pub tick_ms: u64,
+    /// 随机种子
+    pub seed: u64,
+    /// 启动时间戳（毫秒）
+    pub started_at: i64,
+    /// 故障注入配置
+    pub faults: ModbusSimulatorFaultData,
+    /// 请求统计
+    pub stats: ModbusSimulatorStatsData,
+    /// 寄存器映射中的点位（按模板顺序）
+    pub points: Vec<ModbusSimulatorPointData>,
+}
+
+// 查询通信诊断请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusDiagnosticsPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识（为空时查询全部网关）
+    pub gateway_id: Option<String>,
+}
+
+// 开启报文捕获请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusCaptureStartPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 环形缓冲区容量（帧，1–10000，默认 1000）
+    pub capacity: Option<u32>,
+}
+
+// 导出报文捕获请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModbusCaptureExportPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 导出后是否清空已捕获的帧
+    pub clear: bool,
+}
+
+// 按异常码的计数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusExceptionCountData {
+    /// 异常码
+    pub code: u8,
+    /// 异常码描述
+    pub description: String,
+    /// 次数
+    pub count: u64,
+}
+
+// 往返延迟分位数（毫秒，没有样本时为空）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusLatencyData {
+    /// 样本数（最近收到响应的事务，最多 256 个）
+    pub samples: u64,
+    /// 中位数
+    pub p50_ms: Option<f64>,
+    /// 90 分位数
+    pub p90_ms: Option<f64>,
+    /// 99 分位数
+    pub p99_ms: Option<f64>,
+    /// 最大值
+    pub max_ms: Option<f64>,
+}
+
+// 通信错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusDiagnosticErrorData {
+    /// 发生时间戳（毫秒）
+    pub timestamp: i64,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 请求功能码
+    pub function_code: u8,
+    /// 错误类别（timeout / exception / protocol / io / refused / connect / offline）
+    pub class: String,
+    /// 错误信息
+    pub message: String,
+}
+
+// 通信计数与延迟
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusDiagnosticsStatsData {
+    /// 请求数
+    pub requests: u64,
+    /// 成功数
+    pub successes: u64,
+    /// 超时次数
+    pub timeouts: u64,
+    /// CRC / LRC 校验错误次数
+    pub crc_errors: u64,
+    /// 异常响应次数（按异常码）
+    pub exceptions: Vec<ModbusExceptionCountData>,
+    /// 连接错误次数（拒绝连接、建连失败、连接断开、重连退避期）
+    pub connection_errors: u64,
+    /// 其他报文错误次数（单元号不符、报文格式错误等）
+    pub protocol_errors: u64,
+    /// 往返延迟
+    pub latency: ModbusLatencyData,
+    /// 最近一次错误
+    pub last_error: Option<ModbusDiagnosticErrorData>,
+}
+
+// 从站通信诊断
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusSlaveDiagnosticsData {
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 通信状态（ok：最近一次请求成功；failing：最近一次请求失败）
+    pub status: String,
+    /// 计数与延迟
+    #[serde(flatten)]
+    pub stats: ModbusDiagnosticsStatsData,
+}
+
+// 报文捕获状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusCaptureStatusData {
+    /// 是否正在捕获
+    pub enabled: bool,
+    /// 环形缓冲区容量（帧）
+    pub capacity: u32,
+    /// 已捕获的帧数
+    pub frames: u64,
+    /// 缓冲区满后丢弃的最早帧数
+    pub dropped: u64,
+}
+
+// 网关通信诊断
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusDiagnosticsData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 开始统计的时间戳（毫秒，清零后重新计算）
+    pub since: i64,
+    /// 网关合计的计数与延迟
+    #[serde(flatten)]
+    pub stats: ModbusDiagnosticsStatsData,
+    /// 各从站的诊断（按单元号排序）
+    pub slaves: Vec<ModbusSlaveDiagnosticsData>,
+    /// 报文捕获状态
+    pub capture: ModbusCaptureStatusData,
+}
+
+// 捕获的原始帧
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusFrameData {
+    /// 收发时间戳（毫秒）
+    pub timestamp: i64,
+    /// 方向（tx：发送的请求；rx：收到的响应字节）
+    pub direction: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 请求功能码
+    pub function_code: u8,
+    /// 帧内容（空格分隔的大写十六进制）
+    pub hex: String,
+    /// 事务失败时的错误信息（记录在该事务的最后一帧）
+    pub error: Option<String>,
+}
+
+// 报文捕获导出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusCaptureData {
+    /// 网关标识
+    pub gateway_id: String,
+    /// 是否正在捕获
+    pub enabled: bool,
+    /// 环形缓冲区容量（帧）
+    pub capacity: u32,
+    /// 缓冲区满后丢弃的最早帧数
+    pub dropped: u64,
+    /// 捕获的帧（按时间排序）
+    pub frames: Vec<ModbusFrameData>,
+    /// 十六进制文本（每帧一行：时间戳、方向、单元号与帧内容）
+    pub text: String,
+}
+
+// 从站通信状态变化事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusDiagnosticsEvent {
+    /// 事件时间戳（毫秒）
+    pub timestamp: i64,
+    /// 网关标识
+    pub gateway_id: String,
+    /// 从站单元号
+    pub unit_id: u8,
+    /// 通信状态（ok / failing）
+    pub status: String,
+    /// 失败时的错误
+    pub error: Option<ModbusDiagnosticErrorData>,
+    /// 从站的计数与延迟
+    #[serde(flatten)]
+    pub stats: ModbusDiagnosticsStatsData,
+}
@@ -0,0 +1,757 @@
+//! Modbus 协议数据单元（PDU）编解码
+//!
+//! 本模块与传输方式无关，负责：
+//! - 功能码 FC01–FC06、FC15、FC16 请求的校验与编码（客户端）/ 解码（模拟从站）
+//! - 响应的编码（模拟从站）/ 解码与回显校验（客户端）
+//! - 异常码的识别与描述
+//!
+//! 传输层（MBAP、RTU 等）只负责在 PDU 外层加帧，不解析 PDU 内容。
+
+use std::fmt;
+
+use crate::core::error::AppError;
+
+// 单次读取线圈/离散输入的数量上限
+pub const MAX_READ_BITS: u16 = 2000;
+
+// 单次读取寄存器的数量上限
+pub const MAX_READ_REGISTERS: u16 = 125;
+
+// 单次写入多个线圈的数量上限
+pub const MAX_WRITE_BITS: u16 = 1968;
+
+// 单次写入多个寄存器的数量上限
+pub const MAX_WRITE_REGISTERS: u16 = 123;
+
+// 异常响应的功能码标志位
+const EXCEPTION_FLAG: u8 = 0x80;
+
+/// Modbus 功能码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FunctionCode {
+    ReadCoils,              // FC01 读线圈
+    ReadDiscreteInputs,     // FC02 读离散输入
+    ReadHoldingRegisters,   // FC03 读保持寄存器
+    ReadInputRegisters,     // FC04 读输入寄存器
+    WriteSingleCoil,        // FC05 写单个线圈
+    WriteSingleRegister,    // FC06 写单个寄存器
+    WriteMultipleCoils,     // FC15 写多个线圈
+    WriteMultipleRegisters, // FC16 写多个寄存器
+}
+
+impl FunctionCode {
+    /// 功能码数值
+    pub fn code(self) -> u8 {
+        match self {
+            Self::ReadCoils => 0x01,
+            Self::ReadDiscreteInputs => 0x02,
+            Self::ReadHoldingRegisters => 0x03,
+            Self::ReadInputRegisters => 0x04,
+            Self::WriteSingleCoil => 0x05,
+            Self::WriteSingleRegister => 0x06,
+            Self::WriteMultipleCoils => 0x0F,
+            Self::WriteMultipleRegisters => 0x10,
+        }
+    }
+
+    /// 由功能码数值解析（不支持的功能码返回 None）
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x01 => Some(Self::ReadCoils),
+            0x02 => Some(Self::ReadDiscreteInputs),
+            0x03 => Some(Self::ReadHoldingRegisters),
+            0x04 => Some(Self::ReadInputRegisters),
+            0x05 => Some(Self::WriteSingleCoil),
+            0x06 => Some(Self::WriteSingleRegister),
+            0x0F => Some(Self::WriteMultipleCoils),
+            0x10 => Some(Self::WriteMultipleRegisters),
+            _ => None,
+        }
+    }
+}
+
+/// Modbus 异常码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExceptionCode {
+    IllegalFunction,              // 0x01 非法功能码
+    IllegalDataAddress,           // 0x02 非法数据地址
+    IllegalDataValue,             // 0x03 非法数据值
+    ServerDeviceFailure,          // 0x04 从站设备故障
+    Acknowledge,                  // 0x05 已确认（处理中）
+    ServerDeviceBusy,             // 0x06 从站设备忙
+    MemoryParityError,            // 0x08 存储奇偶校验错误
+    GatewayPathUnavailable,       // 0x0A 网关路径不可用
+    GatewayTargetFailedToRespond, // 0x0B 网关目标设备无响应
+    Other(u8),                    // 其他（非标准）异常码
+}
+
+impl ExceptionCode {
+    /// 异常码数值
+    pub fn code(self) -> u8 {
+        match self {
+            Self::IllegalFunction => 0x01,
+            Self::IllegalDataAddress => 0x02,
+            Self::IllegalDataValue => 0x03,
+            Self::ServerDeviceFailure => 0x04,
+            Self::Acknowledge => 0x05,
+            Self::ServerDeviceBusy => 0x06,
+            Self::MemoryParityError => 0x08,
+            Self::GatewayPathUnavailable => 0x0A,
+            Self::GatewayTargetFailedToRespond => 0x0B,
+            Self::Other(code) => code,
+        }
+    }
+
+    /// 由异常码数值解析
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x01 => Self::IllegalFunction,
+            0x02 => Self::IllegalDataAddress,
+            0x03 => Self::IllegalDataValue,
+            0x04 => Self::ServerDeviceFailure,
+            0x05 => Self::Acknowledge,
+            0x06 => Self::ServerDeviceBusy,
+            0x08 => Self::MemoryParityError,
+            0x0A => Self::GatewayPathUnavailable,
+            0x0B => Self::GatewayTargetFailedToRespond,
+            other => Self::Other(other),
+        }
+    }
+
+    /// 异常码的英文描述（用于错误信息）
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::IllegalFunction => "illegal function",
+            Self::IllegalDataAddress => "illegal data address",
+            Self::IllegalDataValue => "illegal data value",
+            Self::ServerDeviceFailure => "server device failure",
+            Self::Acknowledge => "acknowledge",
+            Self::ServerDeviceBusy => "server device busy",
+            Self::MemoryParityError => "memory parity error",
+            Self::GatewayPathUnavailable => "gateway path unavailable",
+            Self::GatewayTargetFailedToRespond => "gateway target device failed to respond",
+            Self::Other(_) => "unknown exception",
+        }
+    }
+}
+
+impl fmt::Display for ExceptionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X} {}", self.code(), self.description())
+    }
+}
+
+/// Modbus 通信错误
+///
+/// 由客户端与传输层返回，在服务层转换为 `AppError::Modbus`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ModbusError {
+    /// 请求参数不合法（数量越界、地址溢出等）
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// 目标拒绝连接
+    #[error("connection refused: {0}")]
+    ConnectionRefused(String),
+    /// 建立连接失败（地址无法解析、网络不可达等）
+    #[error("connect failed: {0}")]
+    Connect(String),
+    /// 连接或请求超时（毫秒）
+    #[error("timeout after {0}ms")]
+    Timeout(u64),
+    /// 连接读写失败或被对端关闭
+    #[error("i/o error: {0}")]
+    Io(String),
+    /// 从站返回异常响应
+    #[error("exception {code} (function 0x{function:02X})")]
+    Exception { function: u8, code: ExceptionCode },
+    /// 响应报文不合法
+    #[error("invalid response: {0}")]
+    Protocol(String),
+    /// 处于重连退避期，距下次重连的毫秒数
+    #[error("gateway offline, next reconnect in {0}ms")]
+    Offline(u64),
+    /// 网关未建立连接
+    #[error("not connected")]
+    NotConnected,
+}
+
+impl ModbusError {
+    /// 是否为连接层故障（需要丢弃当前连接并重连）
+    pub fn is_connection_fault(&self) -> bool {
+        matches!(
+            self,
+            Self::ConnectionRefused(_) | Self::Connect(_) | Self::Timeout(_) | Self::Io(_)
+        )
+    }
+
+    /// 是否为帧校验错误（RTU 的 CRC 或 ASCII 的 LRC 不符）
+    pub fn is_checksum_mismatch(&self) -> bool {
+        matches!(
+            self,
+            Self::Protocol(message)
+                if message.starts_with("crc mismatch") || message.starts_with("lrc mismatch")
+        )
+    }
+
+    /// 错误分类（refused / timeout / exception / connect / io / protocol / request / offline）
+    ///
+    /// 供连接测试与诊断按类别呈现失败原因
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::ConnectionRefused(_) => "refused",
+            Self::Timeout(_) => "timeout",
+            Self::Exception { .. } => "exception",
+            Self::Connect(_) => "connect",
+            Self::Io(_) => "io",
+            Self::Protocol(_) => "protocol",
+            Self::InvalidRequest(_) => "request",
+            Self::Offline(_) | Self::NotConnected => "offline",
+        }
+    }
+}
+
+/// 转换为应用错误：请求参数问题归为校验错误，其余归为 Modbus 错误
+impl From<ModbusError> for AppError {
+    fn from(err: ModbusError) -> Self {
+        match err {
+            ModbusError::InvalidRequest(message) => AppError::Validation(message),
+            other => AppError::Modbus(other.to_string()),
+        }
+    }
+}
+
+/// Modbus 请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    ReadCoils { address: u16, count: u16 },
+    ReadDiscreteInputs { address: u16, count: u16 },
+    ReadHoldingRegisters { address: u16, count: u16 },
+    ReadInputRegisters { address: u16, count: u16 },
+    WriteSingleCoil { address: u16, value: bool },
+    WriteSingleRegister { address: u16, value: u16 },
+    WriteMultipleCoils { address: u16, values: Vec<bool> },
+    WriteMultipleRegisters { address: u16, values: Vec<u16> },
+}
+
+impl Request {
+    /// 请求对应的功能码
+    pub fn function(&self) -> FunctionCode {
+        match self {
+            Self::ReadCoils { .. } => FunctionCode::ReadCoils,
+            Self::ReadDiscreteInputs { .. } => FunctionCode::ReadDiscreteInputs,
+            Self::ReadHoldingRegisters { .. } => FunctionCode::ReadHoldingRegisters,
+            Self::ReadInputRegisters { .. } => FunctionCode::ReadInputRegisters,
+            Self::WriteSingleCoil { .. } => FunctionCode::WriteSingleCoil,
+            Self::WriteSingleRegister { .. } => FunctionCode::WriteSingleRegister,
+            Self::WriteMultipleCoils { .. } => FunctionCode::WriteMultipleCoils,
+            Self::WriteMultipleRegisters { .. } => FunctionCode::WriteMultipleRegisters,
+        }
+    }
+
+    /// 起始地址
+    pub fn address(&self) -> u16 {
+        match self {
+            Self::ReadCoils { address, .. }
+            | Self::ReadDiscreteInputs { address, .. }
+            | Self::ReadHoldingRegisters { address, .. }
+            | Self::ReadInputRegisters { address, .. }
+            | Self::WriteSingleCoil { address, .. }
+            | Self::WriteSingleRegister { address, .. }
+            | Self::WriteMultipleCoils { address, .. }
+            | Self::WriteMultipleRegisters { address, .. } => *address,
+        }
+    }
+
+    /// 涉及的线圈或寄存器数量
+    pub fn count(&self) -> u16 {
+        match self {
+            Self::ReadCoils { count, .. }
+            | Self::ReadDiscreteInputs { count, .. }
+            | Self::ReadHoldingRegisters { count, .. }
+            | Self::ReadInputRegisters { count, .. } => *count,
+            Self::WriteSingleCoil { .. } | Self::WriteSingleRegister { .. } => 1,
+            Self::WriteMultipleCoils { values, .. } => saturating_len(values.len()),
+            Self::WriteMultipleRegisters { values, .. } => saturating_len(values.len()),
+        }
+    }
+
+    /// 校验数量范围与地址是否越过 0xFFFF
+    pub fn validate(&self) -> Result<(), ModbusError> {
+        let max = match self {
+            Self::ReadCoils { .. } | Self::ReadDiscreteInputs { .. } => MAX_READ_BITS,
+            Self::ReadHoldingRegisters { .. } | Self::ReadInputRegisters { .. } => {
+                MAX_READ_REGISTERS
+            }
+            Self::WriteSingleCoil { .. } | Self::WriteSingleRegister { .. } => 1,
+            Self::WriteMultipleCoils { .. } => MAX_WRITE_BITS,
+            Self::WriteMultipleRegisters { .. } => MAX_WRITE_REGISTERS,
+        };
+        let count = self.count();
+        if count == 0 || count > max {
+            return Err(ModbusError::InvalidRequest(format!(
+                "count must be between 1 and {max}"
+            )));
+        }
+        if u32::from(self.address()) + u32::from(count) > 0x1_0000 {
+            return Err(ModbusError::InvalidRequest(
+                "address range exceeds 0xFFFF".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 编码为请求 PDU（调用方需先通过 `validate`）
+    pub fn encode(&self) -> Vec<u8> {
+        let mut pdu = vec![self.function().code()];
+        push_u16(&mut pdu, self.address());
+        match self {
+            Self::ReadCoils { count, .. }
+            | Self::ReadDiscreteInputs { count, .. }
+            | Self::ReadHoldingRegisters { count, .. }
+            | Self::ReadInputRegisters { count, .. } => push_u16(&mut pdu, *count),
+            Self::WriteSingleCoil { value, .. } => {
+                push_u16(&mut pdu, if *value { 0xFF00 } else { 0x0000 });
+            }
+            Self::WriteSingleRegister { value, .. } => push_u16(&mut pdu, *value),
+            Self::WriteMultipleCoils { values, .. } => {
+                let packed = pack_bits(values);
+                push_u16(&mut pdu, self.count());
+                pdu.push(saturating_byte(packed.len()));
+                pdu.extend_from_slice(&packed);
+            }
+            Self::WriteMultipleRegisters { values, .. } => {
+                push_u16(&mut pdu, self.count());
+                pdu.push(saturating_byte(values.len() * 2));
+                for value in values {
+                    push_u16(&mut pdu, *value);
+                }
+            }
+        }
+        pdu
+    }
+
+    /// 解码请求 PDU（模拟从站使用）
+    ///
+    /// 解码失败时返回应回复的异常码
+    pub fn decode(pdu: &[u8]) -> Result<Self, ExceptionCode> {
+        let (&code, body) = pdu.split_first().ok_or(ExceptionCode::IllegalFunction)?;
+        let function = FunctionCode::from_code(code).ok_or(ExceptionCode::IllegalFunction)?;
+        if body.len() < 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let address = read_u16(body, 0);
+        let second = read_u16(body, 2);
+        let request = match function {
+            FunctionCode::ReadCoils => Self::ReadCoils {
+                address,
+                count: second,
+            },
+            FunctionCode::ReadDiscreteInputs => Self::ReadDiscreteInputs {
+                address,
+                count: second,
+            },
+            FunctionCode::ReadHoldingRegisters => Self::ReadHoldingRegisters {
+                address,
+                count: second,
+            },
+            FunctionCode::ReadInputRegisters => Self::ReadInputRegisters {
+                address,
+                count: second,
+            },
+            FunctionCode::WriteSingleCoil => Self::WriteSingleCoil {
+                address,
+                value: match second {
+                    0xFF00 => true,
+                    0x0000 => false,
+                    _ => return Err(ExceptionCode::IllegalDataValue),
+                },
+            },
+            FunctionCode::WriteSingleRegister => Self::WriteSingleRegister {
+                address,
+                value: second,
+            },
+            FunctionCode::WriteMultipleCoils => {
+                let data = multiple_write_data(body, usize::from(second).div_ceil(8))?;
+                Self::WriteMultipleCoils {
+                    address,
+                    values: unpack_bits(data, usize::from(second)),
+                }
+            }
+            FunctionCode::WriteMultipleRegisters => {
+                let data = multiple_write_data(body, usize::from(second) * 2)?;
+                Self::WriteMultipleRegisters {
+                    address,
+                    values: data.chunks_exact(2).map(|pair| read_u16(pair, 0)).collect(),
+                }
+            }
+        };
+        if request.validate().is_err() {
+            // 规范规定数量越界回复 0x03，地址溢出回复 0x02
+            let count = request.count();
+            let max = match function {
+                FunctionCode::ReadCoils | FunctionCode::ReadDiscreteInputs => MAX_READ_BITS,
+                FunctionCode::ReadHoldingRegisters | FunctionCode::ReadInputRegisters => {
+                    MAX_READ_REGISTERS
+                }
+                FunctionCode::WriteMultipleCoils => MAX_WRITE_BITS,
+                FunctionCode::WriteMultipleRegisters => MAX_WRITE_REGISTERS,
+                FunctionCode::WriteSingleCoil | FunctionCode::WriteSingleRegister => 1,
+            };
+            return Err(if count == 0 || count > max {
+                ExceptionCode::IllegalDataValue
+            } else {
+                ExceptionCode::IllegalDataAddress
+            });
+        }
+        Ok(request)
+    }
+}
+
+/// Modbus 响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// 线圈或离散输入（FC01/FC02）
+    Bits(Vec<bool>),
+    /// 保持或输入寄存器（FC03/FC04）
+    Registers(Vec<u16>),
+    /// 写入确认（FC05/FC06/FC15/FC16）
+    Written,
+}
+
+impl Response {
+    /// 编码为响应 PDU（模拟从站使用）
+    pub fn encode(&self, request: &Request) -> Vec<u8> {
+        let mut pdu = vec![request.function().code()];
+        match self {
+            Self::Bits(values) => {
+                let packed = pack_bits(values);
+                pdu.push(saturating_byte(packed.len()));
+                pdu.extend_from_slice(&packed);
+            }
+            Self::Registers(values) => {
+                pdu.push(saturating_byte(values.len() * 2));
+                for value in values {
+                    push_u16(&mut pdu, *value);
+                }
+            }
+            Self::Written => {
+                // 写入响应回显请求 PDU 的前 5 个字节
+                pdu = request.encode();
+                pdu.truncate(5);
+            }
+        }
+        pdu
+    }
+
+    /// 解码响应 PDU 并校验其与请求一致
+    pub fn decode(request: &Request, pdu: &[u8]) -> Result<Self, ModbusError> {
+        let function = request.function().code();
+        let (&code, body) = pdu
+            .split_first()
+            .ok_or_else(|| ModbusError::Protocol("empty response".to_string()))?;
+        if code == function | EXCEPTION_FLAG {
+            let exception = body
+                .first()
+                .ok_or_else(|| ModbusError::Protocol("missing exception code".to_string()))?;
+            return Err(ModbusError::Exception {
+                function,
+                code: ExceptionCode::from_code(*exception),
+            });
+        }
+        if code != function {
+            return Err(ModbusError::Protocol(format!(
+                "function code mismatch: expected 0x{function:02X}, got 0x{code:02X}"
+            )));
+        }
+        let count = usize::from(request.count());
+        match request {
+            Request::ReadCoils { .. } | Request::ReadDiscreteInputs { .. } => {
+                let data = counted_data(body, count.div_ceil(8))?;
+                Ok(Self::Bits(unpack_bits(data, count)))
+            }
+            Request::ReadHoldingRegisters { .. } | Request::ReadInputRegisters { .. } => {
+                let data = counted_data(body, count * 2)?;
+                Ok(Self::Registers(
+                    data.chunks_exact(2).map(|pair| read_u16(pair, 0)).collect(),
+                ))
+            }
+            Request::WriteSingleCoil { .. }
+            | Request::WriteSingleRegister { .. }
+            | Request::WriteMultipleCoils { .. }
+            | Request::WriteMultipleRegisters { .. } => {
+                let expected = request.encode();
+                if pdu.len() != 5 || pdu != &expected[..5] {
+                    return Err(ModbusError::Protocol("write echo mismatch".to_string()));
+                }
+                Ok(Self::Written)
+            }
+        }
+    }
+}
+
+/// 构造异常响应 PDU（模拟从站使用）
+pub fn exception_pdu(function: u8, code: ExceptionCode) -> Vec<u8> {
+    vec![function | EXCEPTION_FLAG, code.code()]
+}
+
+/// 按 LSB 优先将位序列打包为字节
+pub fn pack_bits(values: &[bool]) -> Vec<u8> {
+    let mut packed = vec![0_u8; values.len().div_ceil(8)];
+    for (index, value) in values.iter().enumerate() {
+        if *value {
+            packed[index / 8] |= 1 << (index % 8);
+        }
+    }
+    packed
+}
+
+/// 按 LSB 优先从字节中解出指定数量的位
+pub fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|index| bytes[index / 8] & (1 << (index % 8)) != 0)
+        .collect()
+}
+
+// 以大端序追加 16 位整数
+fn push_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+// 以大端序读取 16 位整数
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+// 长度转换为 u16（超出时取上限，由 validate 拦截）
+fn saturating_len(len: usize) -> u16 {
+    u16::try_from(len).unwrap_or(u16::MAX)
+}
+
+// 长度转换为字节计数（超出时取上限，由 validate 拦截）
+fn saturating_byte(len: usize) -> u8 {
+    u8::try_from(len).unwrap_or(u8::MAX)
+}
+
+// 读取响应中带字节计数前缀的数据段
+fn counted_data(body: &[u8], expected: usize) -> Result<&[u8], ModbusError> {
+    let (&byte_count, data) = body
+        .split_first()
+        .ok_or_else(|| ModbusError::Protocol("missing byte count".to_string()))?;
+    if usize::from(byte_count) != expected || data.len() != expected {
+        return Err(ModbusError::Protocol(format!(
+            "byte count mismatch: expected {expected}, got {}",
+            data.len()
+        )));
+    }
+    Ok(data)
+}
+
+// 读取 FC15/FC16 请求中的数据段
+fn multiple_write_data(body: &[u8], expected: usize) -> Result<&[u8], ExceptionCode> {
+    let byte_count = body
+        .get(4)
+        .copied()
+        .ok_or(ExceptionCode::IllegalDataValue)?;
+    let data = &body[5..];
+    if usize::from(byte_count) != expected || data.len() != expected {
+        return Err(ExceptionCode::IllegalDataValue);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_round_trip_through_pdu() {
+        let requests = [
+            Request::ReadCoils {
+                address: 0x0013,
+                count: 19,
+            },
+            Request::ReadDiscreteInputs {
+                address: 0x00C4,
+                count: 22,
+            },
+            Request::ReadHoldingRegisters {
+                address: 0x006B,
+                count: 3,
+            },
+            Request::ReadInputRegisters {
+                address: 0x0008,
+                count: 1,
+            },
+            Request::WriteSingleCoil {
+                address: 0x00AC,
+                value: true,
+            },
+            Request::WriteSingleRegister {
+                address: 0x0001,
+                value: 0x0003,
+            },
+            Request::WriteMultipleCoils {
+                address: 0x0013,
+                values: vec![
+                    true, false, true, true, false, false, true, true, true, false,
+                ],
+            },
+            Request::WriteMultipleRegisters {
+                address: 0x0001,
+                values: vec![0x000A, 0x0102],
+            },
+        ];
+        for request in requests {
+            assert_eq!(Request::decode(&request.encode()), Ok(request));
+        }
+
+        // 规范示例：FC15 写 10 个线圈，数据为 CD 01
+        let pdu = Request::WriteMultipleCoils {
+            address: 0x0013,
+            values: vec![
+                true, false, true, true, false, false, true, true, true, false,
+            ],
+        }
+        .encode();
+        assert_eq!(pdu, vec![0x0F, 0x00, 0x13, 0x00, 0x0A, 0x02, 0xCD, 0x01]);
+    }
+
+    #[test]
+    fn responses_are_decoded_and_checked_against_requests() {
+        let read = Request::ReadCoils {
+            address: 0x0013,
+            count: 19,
+        };
+        let bits = Response::decode(&read, &[0x01, 0x03, 0xCD, 0x6B, 0x05]).expect("bits");
+        let Response::Bits(values) = bits else {
+            panic!("expected bits");
+        };
+        assert_eq!(values.len(), 19);
+        assert_eq!(pack_bits(&values), vec![0xCD, 0x6B, 0x05]);
+
+        let registers = Request::ReadHoldingRegisters {
+            address: 0x006B,
+            count: 3,
+        };
+        assert_eq!(
+            Response::decode(
+                &registers,
+                &[0x03, 0x06, 0x02, 0x2B, 0x00, 0x00, 0x00, 0x64]
+            ),
+            Ok(Response::Registers(vec![0x022B, 0x0000, 0x0064]))
+        );
+        assert!(matches!(
+            Response::decode(&registers, &[0x03, 0x04, 0x02, 0x2B, 0x00, 0x00]),
+            Err(ModbusError::Protocol(_))
+        ));
+        assert!(matches!(
+            Response::decode(&registers, &[0x04, 0x02, 0x00, 0x01]),
+            Err(ModbusError::Protocol(_))
+        ));
+
+        let write = Request::WriteSingleRegister {
+            address: 1,
+            value: 3,
+        };
+        assert_eq!(
+            Response::decode(&write, &Response::Written.encode(&write)),
+            Ok(Response::Written)
+        );
+        assert!(matches!(
+            Response::decode(&write, &[0x06, 0x00, 0x01, 0x00, 0x04]),
+            Err(ModbusError::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn exceptions_map_to_codes_and_messages() {
+        let request = Request::ReadInputRegisters {
+            address: 0,
+            count: 1,
+        };
+        let err = Response::decode(
+            &request,
+            &exception_pdu(0x04, ExceptionCode::IllegalDataAddress),
+        )
+        .expect_err("exception");
+        assert_eq!(
+            err,
+            ModbusError::Exception {
+                function: 0x04,
+                code: ExceptionCode::IllegalDataAddress,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "exception 0x02 illegal data address (function 0x04)"
+        );
+        assert_eq!(ExceptionCode::from_code(0x0B).code(), 0x0B);
+        assert_eq!(ExceptionCode::from_code(0x42), ExceptionCode::Other(0x42));
+        assert!(!err.is_connection_fault());
+        assert!(ModbusError::Timeout(1000).is_connection_fault());
+        assert!(
+            ModbusError::Protocol("crc mismatch: expected 0x840A, got 0x0000".to_string())
+                .is_checksum_mismatch()
+        );
+        assert!(
+            !ModbusError::Protocol("unexpected protocol id 1".to_string()).is_checksum_mismatch()
+        );
+    }
+
+    #[test]
+    fn request_limits_are_enforced() {
+        let too_many = Request::ReadHoldingRegisters {
+            address: 0,
+            count: 126,
+        };
+        assert_eq!(
+            too_many.validate(),
+            Err(ModbusError::InvalidRequest(
+                "count must be between 1 and 125".to_string()
+            ))
+        );
+        assert_eq!(
+            Request::decode(&too_many.encode()),
+            Err(ExceptionCode::IllegalDataValue)
+        );
+
+        let overflow = Request::ReadCoils {
+            address: 0xFFFF,
+            count: 2,
+        };
+        assert_eq!(
+            overflow.validate(),
+            Err(ModbusError::InvalidRequest(
+                "address range exceeds 0xFFFF".to_string()
+            ))
+        );
+        assert_eq!(
+            Request::decode(&overflow.encode()),
+            Err(ExceptionCode::IllegalDataAddress)
+        );
+        assert!(
+            Request::ReadCoils {
+                address: 0xFFFF,
+                count: 1,
+            }
+            .validate()
+            .is_ok()
+        );
+        assert!(
+            Request::WriteMultipleRegisters {
+                address: 0,
+                values: vec![0; 124],
+            }
+            .validate()
+            .is_err()
+        );
+        assert_eq!(
+            Request::decode(&[0x05, 0x00, 0x01, 0x12, 0x34]),
+            Err(ExceptionCode::IllegalDataValue)
+        );
+        assert_eq!(
+            Request::decode(&[0x2B, 0x0E, 0x01, 0x00]),
+            Err(ExceptionCode::IllegalFunction)
+        );
+    }
+}
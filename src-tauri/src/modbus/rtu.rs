@@ -0,0 +1,226 @@
+//! Modbus RTU 帧
+//!
+//! RTU 帧格式为：单元号 1 字节 + PDU + CRC-16（低字节在前）。
+//! RTU 帧本身不带长度字段，接收方根据功能码与字节数推算帧长度：
+//! - 异常响应：功能码 + 异常码
+//! - FC01–FC04 响应：功能码 + 字节数 + 数据
+//! - FC05、FC06、FC15、FC16 响应：功能码 + 地址 + 数量/值（固定 4 字节）
+//!
+//! 本模块只处理成帧与校验，与具体的字节流（串口、TCP）无关。
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::modbus::protocol::{FunctionCode, ModbusError};
+
+// 异常响应的功能码标志位
+const EXCEPTION_FLAG: u8 = 0x80;
+
+/// 计算 Modbus CRC-16（多项式 0xA001，初值 0xFFFF）
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for byte in data {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 0x0001 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ 0xA001
+            };
+        }
+    }
+    crc
+}
+
+/// 组装 RTU 帧（单元号 + PDU + CRC）
+pub fn encode_frame(unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(pdu.len() + 3);
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    let crc = crc16(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+/// 读取一个 RTU 响应帧，返回单元号与响应 PDU
+pub async fn read_response<S>(stream: &mut S) -> Result<(u8, Vec<u8>), ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    read_frame(stream, response_body_length).await
+}
+
+/// 读取一个 RTU 请求帧，返回单元号与请求 PDU（供模拟从站使用）
+pub async fn read_request<S>(stream: &mut S) -> Result<(u8, Vec<u8>), ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    read_frame(stream, request_body_length).await
+}
+
+// 读取帧：先读单元号与功能码，再按功能码推算剩余长度，最后校验 CRC
+async fn read_frame<S>(
+    stream: &mut S,
+    body_length: fn(u8, &[u8]) -> Result<BodyLength, ModbusError>,
+) -> Result<(u8, Vec<u8>), ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    let mut frame = vec![0_u8; 2];
+    read_into(stream, &mut frame, 0).await?;
+    loop {
+        match body_length(frame[1], &frame[2..])? {
+            BodyLength::Exact(length) => {
+                let start = frame.len();
+                frame.resize(2 + length + 2, 0);
+                read_into(stream, &mut frame, start).await?;
+                break;
+            }
+            BodyLength::AtLeast(length) => {
+                let start = frame.len();
+                frame.resize(2 + length, 0);
+                read_into(stream, &mut frame, start).await?;
+            }
+        }
+    }
+    let (content, crc) = frame.split_at(frame.len() - 2);
+    let expected = crc16(content);
+    let received = u16::from_le_bytes([crc[0], crc[1]]);
+    if expected != received {
+        return Err(ModbusError::Protocol(format!(
+            "crc mismatch: expected 0x{expected:04X}, got 0x{received:04X}"
+        )));
+    }
+    Ok((content[0], content[1..].to_vec()))
+}
+
+// 功能码之后的帧体长度（不含 CRC）
+enum BodyLength {
+    Exact(usize),   // 已确定的长度
+    AtLeast(usize), // 至少需要读取的长度（读完后再次推算）
+}
+
+// 推算响应帧体长度
+fn response_body_length(function: u8, body: &[u8]) -> Result<BodyLength, ModbusError> {
+    if function & EXCEPTION_FLAG != 0 {
+        return Ok(BodyLength::Exact(1));
+    }
+    match FunctionCode::from_code(function) {
+        Some(
+            FunctionCode::ReadCoils
+            | FunctionCode::ReadDiscreteInputs
+            | FunctionCode::ReadHoldingRegisters
+            | FunctionCode::ReadInputRegisters,
+        ) => Ok(match body.first() {
+            None => BodyLength::AtLeast(1),
+            Some(byte_count) => BodyLength::Exact(1 + usize::from(*byte_count)),
+        }),
+        Some(_) => Ok(BodyLength::Exact(4)),
+        None => Err(unsupported_function(function)),
+    }
+}
+
+// 推算请求帧体长度
+fn request_body_length(function: u8, body: &[u8]) -> Result<BodyLength, ModbusError> {
+    match FunctionCode::from_code(function) {
+        Some(FunctionCode::WriteMultipleCoils | FunctionCode::WriteMultipleRegisters) => {
+            Ok(match body.get(4) {
+                None => BodyLength::AtLeast(5),
+                Some(byte_count) => BodyLength::Exact(5 + usize::from(*byte_count)),
+            })
+        }
+        Some(_) => Ok(BodyLength::Exact(4)),
+        None => Err(unsupported_function(function)),
+    }
+}
+
+// 无法推算长度的功能码
+fn unsupported_function(function: u8) -> ModbusError {
+    ModbusError::Protocol(format!("unsupported function code 0x{function:02X}"))
+}
+
+// 从流中读满 buffer[start..]
+async fn read_into<S>(stream: &mut S, buffer: &mut [u8], start: usize) -> Result<(), ModbusError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    stream
+        .read_exact(&mut buffer[start..])
+        .await
+        .map(|_| ())
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => {
+                ModbusError::Io("connection closed by peer".to_string())
+            }
+            _ => ModbusError::Io(err.to_string()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::modbus::protocol::{ExceptionCode, Request, exception_pdu};
+
+    #[test]
+    fn crc_matches_reference_frames() {
+        // 01 03 00 00 00 0A → CRC C5CD（低字节在前：C5 CD）
+        assert_eq!(
+            encode_frame(0x01, &[0x03, 0x00, 0x00, 0x00, 0x0A]),
+            vec![0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD]
+        );
+        // 11 06 00 01 00 03 → CRC 9A9B
+        assert_eq!(crc16(&[0x11, 0x06, 0x00, 0x01, 0x00, 0x03]), 0x9B9A);
+        assert_eq!(crc16(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn frames_are_delimited_by_function_code() {
+        let registers = encode_frame(0x07, &[0x03, 0x04, 0x02, 0x2B, 0x00, 0x64]);
+        let exception = encode_frame(
+            0x07,
+            &exception_pdu(0x03, ExceptionCode::IllegalDataAddress),
+        );
+        let write_request = encode_frame(
+            0x07,
+            &Request::WriteMultipleRegisters {
+                address: 10,
+                values: vec![1, 2],
+            }
+            .encode(),
+        );
+        let mut stream: Vec<u8> = [registers, exception, write_request.clone()].concat();
+        stream.extend_from_slice(&[0xAA, 0x2B]);
+        let mut reader = stream.as_slice();
+        db::block_on(async {
+            assert_eq!(
+                read_response(&mut reader).await.expect("read response"),
+                (0x07, vec![0x03, 0x04, 0x02, 0x2B, 0x00, 0x64])
+            );
+            assert_eq!(
+                read_response(&mut reader).await.expect("exception"),
+                (0x07, vec![0x83, 0x02])
+            );
+            assert_eq!(
+                read_request(&mut reader).await.expect("write request"),
+                (0x07, write_request[1..write_request.len() - 2].to_vec())
+            );
+            assert!(matches!(
+                read_response(&mut reader).await,
+                Err(ModbusError::Protocol(message)) if message == "unsupported function code 0x2B"
+            ));
+        });
+
+        let mut corrupted = encode_frame(0x01, &[0x06, 0x00, 0x01, 0x00, 0x03]);
+        corrupted[3] ^= 0xFF;
+        let mut reader = corrupted.as_slice();
+        let err = db::block_on(read_response(&mut reader)).expect_err("crc mismatch");
+        assert!(
+            matches!(err, ModbusError::Protocol(message) if message.starts_with("crc mismatch"))
+        );
+        let mut truncated: &[u8] = &[0x01, 0x03, 0x04, 0x00];
+        assert_eq!(
+            db::block_on(read_response(&mut truncated)).expect_err("truncated"),
+            ModbusError::Io("connection closed by peer".to_string())
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! 串口 Modbus RTU / ASCII 传输
+//!
+//! 本模块负责：
+//! - 串口参数（波特率、数据位、校验位、停止位、帧间隔）与本机串口枚举
+//! - 按串口路径共享的总线：同一条 RS-485 总线上同一时刻只能有一个事务，
+//!   挂在同一串口上的多个网关共用一个总线对象，事务在总线锁内串行执行
+//! - 帧间隔：两帧之间至少保持配置的静默时间（默认 3.5 个字符时间，波特率高于 19200 时固定 1.75ms）
+//!
+//! 串口以独占方式打开；同一串口只能以一组参数与一种帧格式打开，不一致的网关建连失败。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use tokio::io::AsyncWriteExt;
+use tokio_serial::{ClearBuffer, SerialPort, SerialPortBuilderExt, SerialStream};
+
+use crate::modbus::protocol::ModbusError;
+use crate::modbus::transport::{
+    Framing, ModbusTransport, Recorded, TransportFuture, WireLog, duration_millis,
+};
+
+// 默认波特率
+pub const DEFAULT_BAUD_RATE: u32 = 9600;
+
+// 高波特率下的固定帧间隔（Modbus 串行线路规范：波特率高于 19200 时取 1.75ms）
+const HIGH_SPEED_FRAME_DELAY: Duration = Duration::from_micros(1750);
+
+// 使用固定帧间隔的波特率阈值
+const HIGH_SPEED_BAUD_RATE: u32 = 19_200;
+
+/// 校验位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    None, // 无校验
+    Even, // 偶校验
+    Odd,  // 奇校验
+}
+
+impl Parity {
+    /// 由名称解析（none / even / odd）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Self::None),
+            "even" => Some(Self::Even),
+            "odd" => Some(Self::Odd),
+            _ => None,
+        }
+    }
+
+    /// 名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Even => "even",
+            Self::Odd => "odd",
+        }
+    }
+
+    // 简写字母（用于 8N1 形式的描述）
+    fn letter(self) -> char {
+        match self {
+            Self::None => 'N',
+            Self::Even => 'E',
+            Self::Odd => 'O',
+        }
+    }
+}
+
+/// 串口参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialConfig {
+    pub port: String,                // 串口路径（例如 /dev/ttyUSB0、COM3）
+    pub baud_rate: u32,              // 波特率
+    pub data_bits: u8,               // 数据位（5–8）
+    pub parity: Parity,              // 校验位
+    pub stop_bits: u8,               // 停止位（1 或 2）
+    pub inter_frame_delay: Duration, // 帧间最小静默时间
+}
+
+impl SerialConfig {
+    /// 以 8N1 与默认帧间隔创建串口参数
+    pub fn new(port: &str, baud_rate: u32) -> Self {
+        Self {
+            port: port.to_string(),
+            baud_rate,
+            data_bits: 8,
+            parity: Parity::None,
+            stop_bits: 1,
+            inter_frame_delay: default_inter_frame_delay(baud_rate),
+        }
+    }
+
+    /// 参数描述（例如 `/dev/ttyUSB0 9600 8N1`）
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} {}{}{}",
+            self.port,
+            self.baud_rate,
+            self.data_bits,
+            self.parity.letter(),
+            self.stop_bits
+        )
+    }
+}
+
+/// 默认帧间隔：3.5 个字符时间（每字符按 11 位计），波特率高于 19200 时固定 1.75ms
+pub fn default_inter_frame_delay(baud_rate: u32) -> Duration {
+    if baud_rate == 0 || baud_rate > HIGH_SPEED_BAUD_RATE {
+        return HIGH_SPEED_FRAME_DELAY;
+    }
+    Duration::from_micros(38_500_000 / u64::from(baud_rate))
+}
+
+/// 本机串口信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialPortEntry {
+    pub name: String,                  // 串口路径
+    pub port_type: &'static str,       // 连接方式（usb / pci / bluetooth / unknown）
+    pub vid: Option<u16>,              // USB 厂商 ID
+    pub pid: Option<u16>,              // USB 产品 ID
+    pub manufacturer: Option<String>,  // USB 厂商名称
+    pub product: Option<String>,       // USB 产品名称
+    pub serial_number: Option<String>, // USB 序列号
+}
+
+/// 枚举本机可用串口（按路径排序）
+pub fn available_ports() -> Result<Vec<SerialPortEntry>, ModbusError> {
+    let ports = tokio_serial::available_ports()
+        .map_err(|err| ModbusError::Io(format!("list serial ports: {err}")))?;
+    let mut entries: Vec<SerialPortEntry> = ports
+        .into_iter()
+        .map(|port| {
+            let mut entry = SerialPortEntry {
+                name: port.port_name,
+                port_type: "unknown",
+                vid: None,
+                pid: None,
+                manufacturer: None,
+                product: None,
+                serial_number: None,
+            };
+            match port.port_type {
+                tokio_serial::SerialPortType::UsbPort(usb) => {
+                    entry.port_type = "usb";
+                    entry.vid = Some(usb.vid);
+                    entry.pid = Some(usb.pid);
+                    entry.manufacturer = usb.manufacturer;
+                    entry.product = usb.product;
+                    entry.serial_number = usb.serial_number;
+                }
+                tokio_serial::SerialPortType::PciPort => entry.port_type = "pci",
+                tokio_serial::SerialPortType::BluetoothPort => entry.port_type = "bluetooth",
+                tokio_serial::SerialPortType::Unknown => {}
+            }
+            entry
+        })
+        .collect();
+    entries.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(entries)
+}
+
+// 共享的串口总线
+type SharedBus = Arc<tokio::sync::Mutex<SerialBus>>;
+
+// 串口路径 → 打开时的参数、帧格式与总线（总线在最后一个传输释放时关闭）
+type BusRegistry = HashMap<String, (SerialConfig, Framing, Weak<tokio::sync::Mutex<SerialBus>>)>;
+
+// 全局串口总线注册表
+fn buses() -> &'static Mutex<BusRegistry> {
+    static BUSES: OnceLock<Mutex<BusRegistry>> = OnceLock::new();
+    BUSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 打开（或复用）串口总线并创建串口传输
+///
+/// 串口已被其他网关以相同参数与帧格式打开时共用同一总线；不一致时返回建连错误
+pub fn open(config: &SerialConfig, framing: Framing) -> Result<SerialTransport, ModbusError> {
+    let mut buses = buses().lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((opened, opened_framing, bus)) = buses.get(&config.port) {
+        if let Some(bus) = bus.upgrade() {
+            if opened != config || *opened_framing != framing {
+                return Err(ModbusError::Connect(format!(
+                    "{}: already open as {} {}",
+                    config.port,
+                    opened_framing.as_str(),
+                    opened.describe()
+                )));
+            }
+            return Ok(SerialTransport { bus });
+        }
+    }
+    let stream = tokio_serial::new(&config.port, config.baud_rate)
+        .data_bits(match config.data_bits {
+            5 => tokio_serial::DataBits::Five,
+            6 => tokio_serial::DataBits::Six,
+            7 => tokio_serial::DataBits::Seven,
+            _ => tokio_serial::DataBits::Eight,
+        })
+        .parity(match config.parity {
+            Parity::None => tokio_serial::Parity::None,
+            Parity::Even => tokio_serial::Parity::Even,
+            Parity::Odd => tokio_serial::Parity::Odd,
+        })
+        .stop_bits(if config.stop_bits == 2 {
+            tokio_serial::StopBits::Two
+        } else {
+            tokio_serial::StopBits::One
+        })
+        .flow_control(tokio_serial::FlowControl::None)
+        .open_native_async()
+        .map_err(|err| ModbusError::Connect(format!("{}: {err}", config.port)))?;
+    let bus = Arc::new(tokio::sync::Mutex::new(SerialBus {
+        stream,
+        framing,
+        inter_frame_delay: config.inter_frame_delay,
+        last_frame_at: None,
+    }));
+    buses.retain(|_, (_, _, bus)| bus.strong_count() > 0);
+    buses.insert(
+        config.port.clone(),
+        (config.clone(), framing, Arc::downgrade(&bus)),
+    );
+    Ok(SerialTransport { bus })
+}
+
+// 串口总线
+struct SerialBus {
+    stream: SerialStream,           // 串口
+    framing: Framing,               // 帧格式
+    inter_frame_delay: Duration,    // 帧间最小静默时间
+    last_frame_at: Option<Instant>, // 最近一次收发帧的时间
+}
+
+impl SerialBus {
+    // 等待帧间隔后发送请求帧并读取响应
+    async fn exchange(
+        &mut self,
+        unit_id: u8,
+        pdu: &[u8],
+        wire: &mut WireLog,
+    ) -> Result<Vec<u8>, ModbusError> {
+        if let Some(last_frame_at) = self.last_frame_at {
+            tokio::time::sleep_until((last_frame_at + self.inter_frame_delay).into()).await;
+        }
+        // 丢弃上一事务超时后迟到的字节，避免与本次响应错位
+        self.stream
+            .clear(ClearBuffer::Input)
+            .map_err(|err| ModbusError::Io(err.to_string()))?;
+        let frame = self.framing.encode(unit_id, pdu);
+        wire.sent.extend_from_slice(&frame);
+        self.last_frame_at = Some(Instant::now());
+        self.stream
+            .write_all(&frame)
+            .await
+            .map_err(|err| ModbusError::Io(err.to_string()))?;
+        self.stream
+            .flush()
+            .await
+            .map_err(|err| ModbusError::Io(err.to_string()))?;
+        let (response_unit, response) = self
+            .framing
+            .read_response(&mut Recorded::new(&mut self.stream, &mut wire.received))
+            .await?;
+        self.last_frame_at = Some(Instant::now());
+        if response_unit != unit_id {
+            return Err(ModbusError::Protocol(format!(
+                "unit id mismatch: expected {unit_id}, got {response_unit}"
+            )));
+        }
+        Ok(response)
+    }
+}
+
+/// 串口传输（共享所在串口的总线）
+pub struct SerialTransport {
+    bus: SharedBus, // 串口总线
+}
+
+impl ModbusTransport for SerialTransport {
+    fn transact<'a>(
+        &'a mut self,
+        unit_id: u8,
+        pdu: &'a [u8],
+        timeout: Duration,
+        wire: &'a mut WireLog,
+    ) -> TransportFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            // 等待总线空闲的时间不计入请求超时
+            let mut bus = self.bus.lock().await;
+            tokio::time::timeout(timeout, bus.exchange(unit_id, pdu, wire))
+                .await
+                .map_err(|_| ModbusError::Timeout(duration_millis(timeout)))?
+        })
+    }
+}
@@ -0,0 +1,1422 @@
+//! Modbus 模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 网关长连接的建立、断开与状态查询（每个网关一个客户端，断线自动重连并指数退避）
+//! - 传输方式：Modbus TCP、RTU / ASCII over TCP（串口服务器透传）、串口 RTU / ASCII，以及本机串口枚举
+//! - 线圈、离散输入、保持寄存器、输入寄存器的读取（FC01–FC04）
+//! - 线圈与保持寄存器的写入（FC05、FC06、FC15、FC16）
+//! - 权限校验：`device:manage`（连接管理）、`device:view`（状态查询与读取）、`control:issue`（写入）
+//! - 写入操作的审计记录（`targetType = "modbus_gateway"`，成功与失败均记录）
+//! - 进程内从站模拟器的启动、停止、状态查询与故障注入调整（按模板生成寄存器映射与取值）
+//! - 通信诊断的查询与清零，报文捕获的开启、停止与十六进制导出
+//!
+//! 异步的 Modbus 事务在数据库模块的全局运行时上执行，连接在命令之间保持。
+
+// 引入集合、同步与时间类型
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+// 引入 JSON 构造宏与值类型
+use serde_json::{Value, json};
+
+// 引入采集服务（按操作员的设备范围解析可访问的从站）
+use crate::acquisition::services as acquisition_services;
+// 引入采集从站标识（网关编码与单元号）
+use crate::acquisition::telemetry::DeviceTarget;
+// 引入审计模型与服务
+use crate::audit::models::AuditEventInput;
+use crate::audit::services as audit_services;
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入锁辅助函数（锁中毒时继续使用内部数据）
+use crate::core::sync::lock;
+// 引入数据库模块（共享异步运行时）
+use crate::db;
+// 引入设备服务（校验设备权限并取得操作员 ID）
+use crate::device::services as device_services;
+// 引入设备模板数据访问（模拟器按模板点位生成寄存器映射）
+use crate::device_template::repository as template_repository;
+// 引入通信网关数据访问（报文捕获校验已保存的网关编码）
+use crate::gateway::repository as gateway_repository;
+// 引入 Modbus 客户端
+use crate::modbus::client::{
+    ClientConfig, DEFAULT_BACKOFF_INITIAL, DEFAULT_BACKOFF_MAX, DEFAULT_CONNECT_TIMEOUT,
+    DEFAULT_REQUEST_TIMEOUT, ModbusClient,
+};
+// 引入通信诊断
+use crate::modbus::diagnostics::{
+    self, DEFAULT_CAPTURE_CAPACITY, Link, MAX_CAPTURE_CAPACITY, SharedLink,
+};
+// 引入 Modbus 数据模型
+use crate::modbus::models::{
+    ModbusBitsData, ModbusCaptureData, ModbusCaptureExportPayload, ModbusCaptureStartPayload,
+    ModbusConnectionData, ModbusConnectionListPayload, ModbusDiagnosticsData,
+    ModbusDiagnosticsPayload, ModbusGatewayPayload, ModbusReadPayload, ModbusRegistersData,
+    ModbusRtuConnectPayload, ModbusSerialPortData, ModbusSerialPortListPayload,
+    ModbusSimulatorData, ModbusSimulatorFaultData, ModbusSimulatorFaultSpec,
+    ModbusSimulatorFaultsPayload, ModbusSimulatorGeneratorSpec, ModbusSimulatorListPayload,
+    ModbusSimulatorPayload, ModbusSimulatorPointData, ModbusSimulatorStartPayload,
+    ModbusSimulatorStatsData, ModbusTcpConnectPayload, ModbusWriteCoilPayload,
+    ModbusWriteCoilsPayload, ModbusWriteData, ModbusWriteRegisterPayload,
+    ModbusWriteRegistersPayload,
+};
+// 引入模拟器取值生成器与寄存器映射
+use crate::modbus::generator::{Generator, RegisterMap};
+// 引入协议错误、异常码与请求类型
+use crate::modbus::protocol::{ExceptionCode, ModbusError, Request, Response};
+// 引入串口参数与串口枚举
+use crate::modbus::serial::{self, DEFAULT_BAUD_RATE, Parity, SerialConfig};
+// 引入从站模拟器与故障注入配置
+use crate::modbus::simulator::{FaultConfig, SharedRegisterMap, SlaveMemory, TcpSimulator};
+// 引入全局连接状态
+use crate::modbus::state;
+// 引入传输配置
+use crate::modbus::transport::{Framing, TransportConfig, duration_millis};
+
+// 审计目标类型：Modbus 网关
+const TARGET_TYPE_GATEWAY: &str = "modbus_gateway";
+
+// Modbus TCP 默认端口
+const DEFAULT_TCP_PORT: u16 = 502;
+
+// 默认从站单元号
+const DEFAULT_UNIT_ID: u8 = 1;
+
+// 网关标识最大长度
+const MAX_GATEWAY_ID_LENGTH: usize = 64;
+
+// 超时与重连间隔的下限（毫秒）
+const MIN_TIMEOUT_MS: u64 = 100;
+
+// 超时与初始重连间隔的上限（毫秒）
+const MAX_TIMEOUT_MS: u64 = 60_000;
+
+// 最大重连间隔的上限（毫秒）
+const MAX_BACKOFF_MS: u64 = 600_000;
+
+// 波特率下限
+const MIN_BAUD_RATE: u32 = 300;
+
+// 波特率上限
+const MAX_BAUD_RATE: u32 = 921_600;
+
+// 帧间隔上限（毫秒）
+const MAX_INTER_FRAME_DELAY_MS: u64 = 1000;
+
+// 模拟器默认监听地址（端口由系统分配）
+const DEFAULT_SIMULATOR_BIND: &str = "127.0.0.1:0";
+
+// 模拟器数据表默认大小
+const DEFAULT_SIMULATOR_SIZE: usize = 100;
+
+// 模拟器数据表大小上限（地址 0–65535）
+const MAX_SIMULATOR_SIZE: usize = 65_536;
+
+// 模拟器默认刷新周期（毫秒）
+const DEFAULT_SIMULATOR_TICK_MS: u64 = 1000;
+
+// 模拟器刷新周期下限（毫秒）
+const MIN_SIMULATOR_TICK_MS: u64 = 10;
+
+// 斜坡与随机游走的默认上限
+const DEFAULT_GENERATOR_MAX: f64 = 100.0;
+
+// 正弦的默认周期（毫秒）
+const DEFAULT_SINE_PERIOD_MS: u64 = 60_000;
+
+/// 建立（或更新）网关的 Modbus TCP 长连接
+///
+/// `framing` 为 rtu / ascii 时按串口服务器透传方式收发 RTU / ASCII 帧（不带 MBAP 报文头）；
+/// 配置不变时复用已有连接；建连失败不返回错误，而是在状态中给出 `lastError`，
+/// 后续读写请求会在退避结束后自动重连
+///
+/// # 参数
+/// * `payload` - 网关标识、目标地址与超时参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关连接状态
+pub fn connect_tcp(
+    payload: &ModbusTcpConnectPayload,
+    now_millis: u64,
+) -> Result<ModbusConnectionData, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let transport = tcp_transport(&payload.host, payload.port, payload.framing.as_deref())?;
+    let config = client_config(
+        transport,
+        TimeoutOptions {
+            connect_timeout: payload.connect_timeout_ms,
+            request_timeout: payload.request_timeout_ms,
+            backoff_initial: payload.backoff_initial_ms,
+            backoff_max: payload.backoff_max_ms,
+        },
+    )?;
+    Ok(register_and_connect(gateway_id, config))
+}
+
+/// 建立（或更新）网关的串口 Modbus RTU / ASCII 长连接
+///
+/// 同一串口上可注册多个网关（例如同一 RS-485 总线上的多台仪表），它们共用一条总线、事务串行执行；
+/// 同一串口必须使用相同的串口参数
+///
+/// # 参数
+/// * `payload` - 网关标识、串口参数与超时参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关连接状态
+pub fn connect_rtu(
+    payload: &ModbusRtuConnectPayload,
+    now_millis: u64,
+) -> Result<ModbusConnectionData, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let transport = serial_transport(&SerialOptions {
+        port: &payload.port,
+        baud_rate: payload.baud_rate,
+        data_bits: payload.data_bits,
+        parity: payload.parity.as_deref(),
+        stop_bits: payload.stop_bits,
+        inter_frame_delay_ms: payload.inter_frame_delay_ms,
+        framing: payload.framing.as_deref(),
+    })?;
+    let config = client_config(
+        transport,
+        TimeoutOptions {
+            connect_timeout: payload.connect_timeout_ms,
+            request_timeout: payload.request_timeout_ms,
+            backoff_initial: payload.backoff_initial_ms,
+            backoff_max: payload.backoff_max_ms,
+        },
+    )?;
+    Ok(register_and_connect(gateway_id, config))
+}
+
+/// 校验 TCP 网关参数并生成传输配置
+///
+/// # 参数
+/// * `host` - IP 地址或主机名
+/// * `port` - TCP 端口（默认 502）
+/// * `framing` - 帧格式（mbap / rtu / ascii，默认 mbap）
+///
+/// # 返回
+/// * TCP 传输配置
+pub fn tcp_transport(
+    host: &str,
+    port: Option<u16>,
+    framing: Option<&str>,
+) -> Result<TransportConfig, AppError> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(AppError::Validation("host is required".to_string()));
+    }
+    let port = port.unwrap_or(DEFAULT_TCP_PORT);
+    if port == 0 {
+        return Err(AppError::Validation(
+            "port must be between 1 and 65535".to_string(),
+        ));
+    }
+    Ok(match framing.map(normalize_name).as_deref() {
+        None | Some("mbap") => TransportConfig::Tcp {
+            host: host.to_string(),
+            port,
+        },
+        Some(framing) => TransportConfig::TcpFramed {
+            host: host.to_string(),
+            port,
+            framing: Framing::parse(framing).ok_or_else(|| {
+                AppError::Validation("framing must be one of mbap, rtu, ascii".to_string())
+            })?,
+        },
+    })
+}
+
+/// 校验串口参数并生成串口传输配置
+///
+/// # 参数
+/// * `options` - 串口路径、串口参数与帧格式
+///
+/// # 返回
+/// * 串口传输配置
+pub fn serial_transport(options: &SerialOptions<'_>) -> Result<TransportConfig, AppError> {
+    let port = options.port.trim();
+    if port.is_empty() {
+        return Err(AppError::Validation("port is required".to_string()));
+    }
+    let baud_rate = options.baud_rate.unwrap_or(DEFAULT_BAUD_RATE);
+    if !(MIN_BAUD_RATE..=MAX_BAUD_RATE).contains(&baud_rate) {
+        return Err(AppError::Validation(format!(
+            "baudRate must be between {MIN_BAUD_RATE} and {MAX_BAUD_RATE}"
+        )));
+    }
+    let mut config = SerialConfig::new(port, baud_rate);
+    if let Some(data_bits) = options.data_bits {
+        if !(5..=8).contains(&data_bits) {
+            return Err(AppError::Validation(
+                "dataBits must be between 5 and 8".to_string(),
+            ));
+        }
+        config.data_bits = data_bits;
+    }
+    if let Some(parity) = options.parity {
+        config.parity = Parity::parse(&normalize_name(parity)).ok_or_else(|| {
+            AppError::Validation("parity must be one of none, even, odd".to_string())
+        })?;
+    }
+    if let Some(stop_bits) = options.stop_bits {
+        if !(1..=2).contains(&stop_bits) {
+            return Err(AppError::Validation("stopBits must be 1 or 2".to_string()));
+        }
+        config.stop_bits = stop_bits;
+    }
+    if let Some(delay) = options.inter_frame_delay_ms {
+        if delay > MAX_INTER_FRAME_DELAY_MS {
+            return Err(AppError::Validation(format!(
+                "interFrameDelayMs must be between 0 and {MAX_INTER_FRAME_DELAY_MS}"
+            )));
+        }
+        config.inter_frame_delay = Duration::from_millis(delay);
+    }
+    let framing = match options.framing.map(normalize_name) {
+        None => Framing::Rtu,
+        Some(framing) => Framing::parse(&framing)
+            .ok_or_else(|| AppError::Validation("framing must be one of rtu, ascii".to_string()))?,
+    };
+    Ok(TransportConfig::Serial { config, framing })
+}
+
+/// 查询本机可用串口
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按路径排序的串口列表
+pub fn list_serial_ports(
+    payload: &ModbusSerialPortListPayload,
+    now_millis: u64,
+) -> Result<Vec<ModbusSerialPortData>, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    Ok(serial::available_ports()?
+        .into_iter()
+        .map(|entry| ModbusSerialPortData {
+            name: entry.name,
+            port_type: entry.port_type.to_string(),
+            vid: entry.vid,
+            pid: entry.pid,
+            manufacturer: entry.manufacturer,
+            product: entry.product,
+            serial_number: entry.serial_number,
+        })
+        .collect())
+}
+
+/// 断开并移除网关连接
+///
+/// # 参数
+/// * `payload` - 网关标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 断开成功返回 true
+pub fn disconnect(payload: &ModbusGatewayPayload, now_millis: u64) -> Result<bool, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let client = state::remove(&gateway_id).ok_or(ModbusError::NotConnected)?;
+    db::block_on(async move {
+        client.lock().await.disconnect();
+    });
+    Ok(true)
+}
+
+/// 查询全部网关连接状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按网关标识排序的连接状态
+pub fn list_connections(
+    payload: &ModbusConnectionListPayload,
+    now_millis: u64,
+) -> Result<Vec<ModbusConnectionData>, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    Ok(db::block_on(async move {
+        let mut connections = Vec::new();
+        for (gateway_id, client) in state::all() {
+            let client = client.lock().await;
+            connections.push(connection_data(gateway_id, &client));
+        }
+        connections
+    }))
+}
+
+/// 读线圈（FC01）
+pub fn read_coils(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+) -> Result<ModbusBitsData, AppError> {
+    read_bits(payload, now_millis, |address, count| Request::ReadCoils {
+        address,
+        count,
+    })
+}
+
+/// 读离散输入（FC02）
+pub fn read_discrete_inputs(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+) -> Result<ModbusBitsData, AppError> {
+    read_bits(payload, now_millis, |address, count| {
+        Request::ReadDiscreteInputs { address, count }
+    })
+}
+
+/// 读保持寄存器（FC03）
+pub fn read_holding_registers(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+) -> Result<ModbusRegistersData, AppError> {
+    read_registers(payload, now_millis, |address, count| {
+        Request::ReadHoldingRegisters { address, count }
+    })
+}
+
+/// 读输入寄存器（FC04）
+pub fn read_input_registers(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+) -> Result<ModbusRegistersData, AppError> {
+    read_registers(payload, now_millis, |address, count| {
+        Request::ReadInputRegisters { address, count }
+    })
+}
+
+/// 写单个线圈（FC05）
+pub fn write_single_coil(
+    payload: &ModbusWriteCoilPayload,
+    now_millis: u64,
+) -> Result<ModbusWriteData, AppError> {
+    write(
+        "modbus_write_single_coil",
+        WriteTarget {
+            operator_username: &payload.operator_username,
+            gateway_id: &payload.gateway_id,
+            unit_id: payload.unit_id,
+        },
+        Request::WriteSingleCoil {
+            address: payload.address,
+            value: payload.value,
+        },
+        json!(payload.value),
+        now_millis,
+    )
+}
+
+/// 写单个寄存器（FC06）
+pub fn write_single_register(
+    payload: &ModbusWriteRegisterPayload,
+    now_millis: u64,
+) -> Result<ModbusWriteData, AppError> {
+    write(
+        "modbus_write_single_register",
+        WriteTarget {
+            operator_username: &payload.operator_username,
+            gateway_id: &payload.gateway_id,
+            unit_id: payload.unit_id,
+        },
+        Request::WriteSingleRegister {
+            address: payload.address,
+            value: payload.value,
+        },
+        json!(payload.value),
+        now_millis,
+    )
+}
+
+/// 写多个线圈（FC15）
+pub fn write_multiple_coils(
+    payload: &ModbusWriteCoilsPayload,
+    now_millis: u64,
+) -> Result<ModbusWriteData, AppError> {
+    write(
+        "modbus_write_multiple_coils",
+        WriteTarget {
+            operator_username: &payload.operator_username,
+            gateway_id: &payload.gateway_id,
+            unit_id: payload.unit_id,
+        },
+        Request::WriteMultipleCoils {
+            address: payload.address,
+            values: payload.values.clone(),
+        },
+        json!(payload.values),
+        now_millis,
+    )
+}
+
+/// 写多个寄存器（FC16）
+pub fn write_multiple_registers(
+    payload: &ModbusWriteRegistersPayload,
+    now_millis: u64,
+) -> Result<ModbusWriteData, AppError> {
+    write(
+        "modbus_write_multiple_registers",
+        WriteTarget {
+            operator_username: &payload.operator_username,
+            gateway_id: &payload.gateway_id,
+            unit_id: payload.unit_id,
+        },
+        Request::WriteMultipleRegisters {
+            address: payload.address,
+            values: payload.values.clone(),
+        },
+        json!(payload.values),
+        now_millis,
+    )
+}
+
+/// 在网关的长连接上执行一个请求
+///
+/// 供点表测试、轮询等模块复用；网关未连接时返回 `not connected`
+///
+/// # 参数
+/// * `gateway_id` - 网关标识
+/// * `unit_id` - 从站单元号
+/// * `request` - Modbus 请求
+pub fn execute(gateway_id: &str, unit_id: u8, request: &Request) -> Result<Response, ModbusError> {
+    let client = state::get(gateway_id).ok_or(ModbusError::NotConnected)?;
+    db::block_on(async move { client.lock().await.call(unit_id, request).await })
+}
+
+/// 启动进程内从站模拟器
+///
+/// 指定设备模板时按模板点位生成寄存器映射，并按刷新周期写入各点位生成器的取值
+/// （工程值按缩放系数与偏移量反算为原始值，按数据类型与字节序编码）
+///
+/// # 参数
+/// * `payload` - 模拟器标识、监听地址、帧格式、模板、生成器与故障注入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 模拟器状态（含实际监听地址）
+pub fn start_simulator(
+    payload: &ModbusSimulatorStartPayload,
+    now_millis: u64,
+) -> Result<ModbusSimulatorData, AppError> {
+    let now = assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let simulator_id = normalize_simulator_id(&payload.simulator_id)?;
+    if lock_simulators().contains_key(&simulator_id) {
+        return Err(AppError::Validation(format!(
+            "simulator {simulator_id} is already running"
+        )));
+    }
+    let (framing, unit_id) = simulator_framing(payload)?;
+    let tick_ms = payload.tick_ms.unwrap_or(DEFAULT_SIMULATOR_TICK_MS);
+    if !(MIN_SIMULATOR_TICK_MS..=MAX_TIMEOUT_MS).contains(&tick_ms) {
+        return Err(AppError::Validation(format!(
+            "tickMs must be between {MIN_SIMULATOR_TICK_MS} and {MAX_TIMEOUT_MS}"
+        )));
+    }
+    let seed = payload.seed.unwrap_or(now_millis);
+    let map = register_map(payload, seed)?;
+    let required = map.as_ref().map_or(0, RegisterMap::required_size);
+    let size = payload
+        .size
+        .map_or(required.max(DEFAULT_SIMULATOR_SIZE), |size| {
+            usize::try_from(size).unwrap_or(usize::MAX)
+        });
+    let min_size = required.max(1);
+    if !(min_size..=MAX_SIMULATOR_SIZE).contains(&size) {
+        return Err(AppError::Validation(format!(
+            "size must be between {min_size} and {MAX_SIMULATOR_SIZE}"
+        )));
+    }
+    let faults = fault_config(&payload.faults)?;
+    let bind = payload
+        .bind
+        .as_deref()
+        .map(str::trim)
+        .filter(|bind| !bind.is_empty())
+        .unwrap_or(DEFAULT_SIMULATOR_BIND)
+        .to_string();
+    let interval = Duration::from_millis(tick_ms);
+    let (simulator, map) = db::block_on(async move {
+        let memory = SlaveMemory::new(size);
+        let simulator = match (framing, unit_id) {
+            (Some(framing), Some(unit_id)) => {
+                TcpSimulator::start_framed(&bind, framing, unit_id, memory).await
+            }
+            _ => TcpSimulator::start(&bind, memory).await,
+        }
+        .map_err(|err| AppError::Modbus(format!("simulator bind {bind} failed: {err}")))?;
+        simulator.seed_faults(seed);
+        simulator.set_faults(faults);
+        let map = map.map(|map| simulator.drive(map, interval));
+        Ok::<_, AppError>((simulator, map))
+    })?;
+    let entry = SimulatorEntry {
+        simulator,
+        framing: framing.map_or("mbap", Framing::as_str),
+        unit_id,
+        template_id: payload.template_id,
+        size: u32::try_from(size).unwrap_or(u32::MAX),
+        tick_ms,
+        seed,
+        started_at: now,
+        map,
+    };
+    let data = simulator_data(&simulator_id, &entry);
+    let mut simulators = lock_simulators();
+    if simulators.contains_key(&simulator_id) {
+        return Err(AppError::Validation(format!(
+            "simulator {simulator_id} is already running"
+        )));
+    }
+    simulators.insert(simulator_id, entry);
+    Ok(data)
+}
+
+/// 停止模拟器（关闭监听、刷新与全部已建立的连接）
+///
+/// # 参数
+/// * `payload` - 模拟器标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 停止成功返回 true
+pub fn stop_simulator(payload: &ModbusSimulatorPayload, now_millis: u64) -> Result<bool, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let simulator_id = normalize_simulator_id(&payload.simulator_id)?;
+    let entry = lock_simulators()
+        .remove(&simulator_id)
+        .ok_or_else(|| AppError::Validation("simulator not found".to_string()))?;
+    entry.simulator.stop();
+    Ok(true)
+}
+
+/// 查询全部模拟器状态
+///
+/// # 参数
+/// * `payload` - 操作员用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按模拟器标识排序的模拟器状态
+pub fn list_simulators(
+    payload: &ModbusSimulatorListPayload,
+    now_millis: u64,
+) -> Result<Vec<ModbusSimulatorData>, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let simulators = lock_simulators();
+    let mut list: Vec<ModbusSimulatorData> = simulators
+        .iter()
+        .map(|(simulator_id, entry)| simulator_data(simulator_id, entry))
+        .collect();
+    list.sort_by(|left, right| left.simulator_id.cmp(&right.simulator_id));
+    Ok(list)
+}
+
+/// 调整模拟器的故障注入（整体替换，对已建立的连接立即生效）
+///
+/// # 参数
+/// * `payload` - 模拟器标识与故障注入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 模拟器状态
+pub fn set_simulator_faults(
+    payload: &ModbusSimulatorFaultsPayload,
+    now_millis: u64,
+) -> Result<ModbusSimulatorData, AppError> {
+    assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let simulator_id = normalize_simulator_id(&payload.simulator_id)?;
+    let faults = fault_config(&payload.faults)?;
+    let simulators = lock_simulators();
+    let entry = simulators
+        .get(&simulator_id)
+        .ok_or_else(|| AppError::Validation("simulator not found".to_string()))?;
+    entry.simulator.set_faults(faults);
+    Ok(simulator_data(&simulator_id, entry))
+}
+
+/// 查询通信诊断
+///
+/// 设定了设备范围的操作员只能看到范围内设备所绑定的从站：网关合计只汇总这些从站，
+/// 没有可访问从站的网关不返回
+///
+/// # 参数
+/// * `payload` - 操作员用户名与可选的网关标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 按网关标识排序的诊断（网关尚无诊断时为空列表）
+pub fn list_diagnostics(
+    payload: &ModbusDiagnosticsPayload,
+    now_millis: u64,
+) -> Result<Vec<ModbusDiagnosticsData>, AppError> {
+    let scope = resolve_device_scope(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let links = match payload.gateway_id.as_deref() {
+        Some(gateway_id) => diagnostics::get(&normalize_gateway_id(gateway_id)?)
+            .into_iter()
+            .collect(),
+        None => diagnostics::all(),
+    };
+    Ok(links
+        .iter()
+        .filter_map(|link| {
+            let link = lock(link);
+            match scope_units(scope.as_ref(), link.gateway_id()) {
+                None => Some(link.data()),
+                Some(units) if units.is_empty() => None,
+                Some(units) => Some(link.data_within(&units)),
+            }
+        })
+        .collect())
+}
+
+/// 清零网关的通信计数、延迟与最近错误
+///
+/// # 参数
+/// * `payload` - 网关标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 清零后的诊断
+pub fn reset_diagnostics(
+    payload: &ModbusGatewayPayload,
+    now_millis: u64,
+) -> Result<ModbusDiagnosticsData, AppError> {
+    let scope = resolve_device_scope(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let (link, units) = find_link(&payload.gateway_id, scope.as_ref())?;
+    let mut link = lock(&link);
+    link.reset(i64::try_from(now_millis).unwrap_or(i64::MAX));
+    Ok(scoped_data(&link, units.as_ref()))
+}
+
+/// 开启网关的报文捕获（清空之前捕获的帧）
+///
+/// 网关须已建立长连接或已保存为通信网关（按网关编码）；已保存但尚未建立长连接时同样可以开启，
+/// 建立连接后开始捕获。设定了设备范围的操作员只能对绑定了范围内设备的网关开启
+///
+/// # 参数
+/// * `payload` - 网关标识与环形缓冲区容量
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关诊断（含捕获状态）
+pub fn start_capture(
+    payload: &ModbusCaptureStartPayload,
+    now_millis: u64,
+) -> Result<ModbusDiagnosticsData, AppError> {
+    let scope = resolve_device_scope(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let capacity = payload.capacity.unwrap_or(DEFAULT_CAPTURE_CAPACITY);
+    if !(1..=MAX_CAPTURE_CAPACITY).contains(&capacity) {
+        return Err(AppError::Validation(format!(
+            "capacity must be between 1 and {MAX_CAPTURE_CAPACITY}"
+        )));
+    }
+    let units = scope_units(scope.as_ref(), &gateway_id);
+    if units.as_ref().is_some_and(BTreeSet::is_empty)
+        || (state::get(&gateway_id).is_none()
+            && gateway_repository::find_gateway_by_code(&gateway_id)?.is_none())
+    {
+        return Err(AppError::Validation("gateway not found".to_string()));
+    }
+    let link = diagnostics::link(&gateway_id);
+    let mut link = lock(&link);
+    link.start_capture(capacity);
+    Ok(scoped_data(&link, units.as_ref()))
+}
+
+/// 停止网关的报文捕获（已捕获的帧保留供导出）
+///
+/// # 参数
+/// * `payload` - 网关标识
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 网关诊断（含捕获状态）
+pub fn stop_capture(
+    payload: &ModbusGatewayPayload,
+    now_millis: u64,
+) -> Result<ModbusDiagnosticsData, AppError> {
+    let scope = resolve_device_scope(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: device manage required",
+        now_millis,
+    )?;
+    let (link, units) = find_link(&payload.gateway_id, scope.as_ref())?;
+    let mut link = lock(&link);
+    link.stop_capture();
+    Ok(scoped_data(&link, units.as_ref()))
+}
+
+/// 导出网关捕获的原始帧（十六进制）
+///
+/// 导出需要 `device:view`，同时清空已捕获的帧需要 `device:manage`；
+/// 设定了设备范围的操作员只导出（并清空）范围内设备所绑定从站的帧
+///
+/// # 参数
+/// * `payload` - 网关标识与是否清空
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 捕获的帧与十六进制文本
+pub fn export_capture(
+    payload: &ModbusCaptureExportPayload,
+    now_millis: u64,
+) -> Result<ModbusCaptureData, AppError> {
+    let (action, forbidden_message) = if payload.clear {
+        (rbac::ACTION_MANAGE, "forbidden: device manage required")
+    } else {
+        (rbac::ACTION_VIEW, "forbidden: device view required")
+    };
+    let scope = resolve_device_scope(
+        &payload.operator_username,
+        action,
+        forbidden_message,
+        now_millis,
+    )?;
+    let (link, units) = find_link(&payload.gateway_id, scope.as_ref())?;
+    Ok(lock(&link).export_capture(payload.clear, units.as_ref()))
+}
+
+// 校验设备权限并解析操作员可访问的从站（None 表示可访问全部设备）
+fn resolve_device_scope(
+    operator_username: &str,
+    action: &str,
+    forbidden_message: &str,
+    now_millis: u64,
+) -> Result<Option<BTreeSet<DeviceTarget>>, AppError> {
+    let (_, user_id, now_millis) = device_services::assert_operator_allowed(
+        operator_username,
+        action,
+        forbidden_message,
+        now_millis,
+    )?;
+    acquisition_services::resolve_scope(user_id, now_millis)
+}
+
+// 网关内操作员可访问的从站单元号（None 表示不限制）
+fn scope_units(scope: Option<&BTreeSet<DeviceTarget>>, gateway_id: &str) -> Option<BTreeSet<u8>> {
+    scope.map(|scope| {
+        scope
+            .iter()
+            .filter(|(gateway_code, _)| gateway_code == gateway_id)
+            .map(|(_, unit_id)| *unit_id)
+            .collect()
+    })
+}
+
+// 按操作员可访问的从站生成诊断数据
+fn scoped_data(link: &Link, units: Option<&BTreeSet<u8>>) -> ModbusDiagnosticsData {
+    units.map_or_else(|| link.data(), |units| link.data_within(units))
+}
+
+// 查找网关诊断与操作员可访问的从站（网关没有可访问的从站时视为不存在）
+fn find_link(
+    gateway_id: &str,
+    scope: Option<&BTreeSet<DeviceTarget>>,
+) -> Result<(SharedLink, Option<BTreeSet<u8>>), AppError> {
+    let gateway_id = normalize_gateway_id(gateway_id)?;
+    let units = scope_units(scope, &gateway_id);
+    if units.as_ref().is_some_and(BTreeSet::is_empty) {
+        return Err(diagnostics_not_found());
+    }
+    let link = diagnostics::get(&gateway_id).ok_or_else(diagnostics_not_found)?;
+    Ok((link, units))
+}
+
+// 诊断不存在（或不在操作员的设备范围内）
+fn diagnostics_not_found() -> AppError {
+    AppError::Validation("diagnostics not found".to_string())
+}
+
+// 写入请求的目标
+struct WriteTarget<'a> {
+    operator_username: &'a str, // 操作员用户名
+    gateway_id: &'a str,        // 网关标识
+    unit_id: Option<u8>,        // 从站单元号
+}
+
+/// 连接的超时与重连参数（毫秒，未提供时取默认值）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeoutOptions {
+    pub connect_timeout: Option<u64>, // 建连超时
+    pub request_timeout: Option<u64>, // 请求超时
+    pub backoff_initial: Option<u64>, // 初始重连间隔
+    pub backoff_max: Option<u64>,     // 最大重连间隔
+}
+
+/// 串口传输参数（未提供时取默认值）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerialOptions<'a> {
+    pub port: &'a str,                     // 串口路径
+    pub baud_rate: Option<u32>,            // 波特率（默认 9600）
+    pub data_bits: Option<u8>,             // 数据位（默认 8）
+    pub parity: Option<&'a str>,           // 校验位（默认 none）
+    pub stop_bits: Option<u8>,             // 停止位（默认 1）
+    pub inter_frame_delay_ms: Option<u64>, // 帧间隔（默认 3.5 个字符时间）
+    pub framing: Option<&'a str>,          // 帧格式（默认 rtu）
+}
+
+// 读取线圈或离散输入
+fn read_bits(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+    build: impl FnOnce(u16, u16) -> Request,
+) -> Result<ModbusBitsData, AppError> {
+    let now = assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let unit_id = payload.unit_id.unwrap_or(DEFAULT_UNIT_ID);
+    match execute(&gateway_id, unit_id, &build(payload.address, payload.count))? {
+        Response::Bits(values) => Ok(ModbusBitsData {
+            gateway_id,
+            unit_id,
+            address: payload.address,
+            values,
+            read_at: now,
+        }),
+        _ => Err(ModbusError::Protocol("expected bit values".to_string()).into()),
+    }
+}
+
+// 读取保持寄存器或输入寄存器
+fn read_registers(
+    payload: &ModbusReadPayload,
+    now_millis: u64,
+    build: impl FnOnce(u16, u16) -> Request,
+) -> Result<ModbusRegistersData, AppError> {
+    let now = assert_allowed(
+        &payload.operator_username,
+        rbac::RESOURCE_DEVICE,
+        rbac::ACTION_VIEW,
+        "forbidden: device view required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(&payload.gateway_id)?;
+    let unit_id = payload.unit_id.unwrap_or(DEFAULT_UNIT_ID);
+    match execute(&gateway_id, unit_id, &build(payload.address, payload.count))? {
+        Response::Registers(values) => Ok(ModbusRegistersData {
+            gateway_id,
+            unit_id,
+            address: payload.address,
+            values,
+            read_at: now,
+        }),
+        _ => Err(ModbusError::Protocol("expected register values".to_string()).into()),
+    }
+}
+
+// 执行写入并记录审计
+fn write(
+    command: &str,
+    target: WriteTarget<'_>,
+    request: Request,
+    values: Value,
+    now_millis: u64,
+) -> Result<ModbusWriteData, AppError> {
+    let now = assert_allowed(
+        target.operator_username,
+        rbac::RESOURCE_CONTROL,
+        rbac::ACTION_ISSUE,
+        "forbidden: control issue required",
+        now_millis,
+    )?;
+    let gateway_id = normalize_gateway_id(target.gateway_id)?;
+    let unit_id = target.unit_id.unwrap_or(DEFAULT_UNIT_ID);
+    request.validate()?;
+    let result = execute(&gateway_id, unit_id, &request);
+    audit_services::record_event_or_log(
+        AuditEventInput {
+            actor: target.operator_username.trim().to_string(),
+            command: command.to_string(),
+            target_type: TARGET_TYPE_GATEWAY.to_string(),
+            target_id: Some(gateway_id.clone()),
+            before: None,
+            after: Some(json!({
+                "unitId": unit_id,
+                "function": request.function().code(),
+                "address": request.address(),
+                "values": values,
+            })),
+            error_message: result.as_ref().err().map(ToString::to_string),
+        },
+        now_millis,
+    );
+    result?;
+    Ok(ModbusWriteData {
+        gateway_id,
+        unit_id,
+        address: request.address(),
+        count: request.count(),
+        written_at: now,
+    })
+}
+
+// 校验操作员权限并返回当前时间戳
+fn assert_allowed(
+    operator_username: &str,
+    resource: &str,
+    action: &str,
+    forbidden_message: &str,
+    now_millis: u64,
+) -> Result<i64, AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        resource,
+        action,
+        now_millis,
+        forbidden_message,
+    )?;
+    Ok(now_millis)
+}
+
+// 规范化网关标识
+fn normalize_gateway_id(gateway_id: &str) -> Result<String, AppError> {
+    let gateway_id = gateway_id.trim();
+    if gateway_id.is_empty() {
+        return Err(AppError::Validation("gatewayId is required".to_string()));
+    }
+    if gateway_id.chars().count() > MAX_GATEWAY_ID_LENGTH {
+        return Err(AppError::Validation(format!(
+            "gatewayId must be at most {MAX_GATEWAY_ID_LENGTH} characters"
+        )));
+    }
+    Ok(gateway_id.to_string())
+}
+
+/// 校验超时与重连参数并生成客户端配置
+///
+/// # 参数
+/// * `transport` - 传输配置
+/// * `options` - 超时与重连参数
+///
+/// # 返回
+/// * 客户端配置
+pub fn client_config(
+    transport: TransportConfig,
+    options: TimeoutOptions,
+) -> Result<ClientConfig, AppError> {
+    let connect_timeout = millis_option(
+        "connectTimeoutMs",
+        options.connect_timeout,
+        DEFAULT_CONNECT_TIMEOUT,
+        MAX_TIMEOUT_MS,
+    )?;
+    let request_timeout = millis_option(
+        "requestTimeoutMs",
+        options.request_timeout,
+        DEFAULT_REQUEST_TIMEOUT,
+        MAX_TIMEOUT_MS,
+    )?;
+    let backoff_initial = millis_option(
+        "backoffInitialMs",
+        options.backoff_initial,
+        DEFAULT_BACKOFF_INITIAL,
+        MAX_TIMEOUT_MS,
+    )?;
+    let backoff_max = millis_option(
+        "backoffMaxMs",
+        options.backoff_max,
+        DEFAULT_BACKOFF_MAX.max(backoff_initial),
+        MAX_BACKOFF_MS,
+    )?;
+    if backoff_max < backoff_initial {
+        return Err(AppError::Validation(
+            "backoffMaxMs must not be less than backoffInitialMs".to_string(),
+        ));
+    }
+    Ok(ClientConfig {
+        transport,
+        connect_timeout,
+        request_timeout,
+        backoff_initial,
+        backoff_max,
+    })
+}
+
+// 注册网关客户端并尝试建连
+fn register_and_connect(gateway_id: String, config: ClientConfig) -> ModbusConnectionData {
+    let client = state::register(&gateway_id, config);
+    db::block_on(async move {
+        let mut client = client.lock().await;
+        // 建连失败记录在客户端状态中，由返回的 lastError 呈现
+        let _ = client.connect().await;
+        connection_data(gateway_id, &client)
+    })
+}
+
+// 规范化枚举名称（去除首尾空白并转为小写）
+fn normalize_name(value: &str) -> String {
+    value.trim().to_ascii_lowercase()
+}
+
+// 解析可选的毫秒参数
+fn millis_option(
+    field: &str,
+    value: Option<u64>,
+    default: Duration,
+    max: u64,
+) -> Result<Duration, AppError> {
+    match value {
+        None => Ok(default),
+        Some(value) if (MIN_TIMEOUT_MS..=max).contains(&value) => Ok(Duration::from_millis(value)),
+        Some(_) => Err(AppError::Validation(format!(
+            "{field} must be between {MIN_TIMEOUT_MS} and {max}"
+        ))),
+    }
+}
+
+// 生成连接状态
+fn connection_data(gateway_id: String, client: &ModbusClient) -> ModbusConnectionData {
+    let config = client.config();
+    ModbusConnectionData {
+        gateway_id,
+        transport: config.transport.kind().to_string(),
+        target: config.transport.target(),
+        connected: client.is_connected(),
+        connect_count: client.connect_count(),
+        last_error: client.last_error().map(ToString::to_string),
+        retry_in_ms: client.retry_in().map(duration_millis),
+        connect_timeout_ms: duration_millis(config.connect_timeout),
+        request_timeout_ms: duration_millis(config.request_timeout),
+    }
+}
+
+// 运行中的模拟器
+struct SimulatorEntry {
+    simulator: TcpSimulator,        // 模拟器
+    framing: &'static str,          // 帧格式
+    unit_id: Option<u8>,            // 从站单元号（mbap 为空）
+    template_id: Option<i64>,       // 设备模板 ID
+    size: u32,                      // 每张数据表的大小
+    tick_ms: u64,                   // 生成器刷新周期（毫秒）
+    seed: u64,                      // 随机种子
+    started_at: i64,                // 启动时间戳（毫秒）
+    map: Option<SharedRegisterMap>, // 模板寄存器映射
+}
+
+// 获取模拟器注册表锁（模拟器标识 → 运行中的模拟器；锁中毒时继续使用内部数据）
+fn lock_simulators() -> MutexGuard<'static, HashMap<String, SimulatorEntry>> {
+    static SIMULATORS: OnceLock<Mutex<HashMap<String, SimulatorEntry>>> = OnceLock::new();
+    lock(SIMULATORS.get_or_init(|| Mutex::new(HashMap::new())))
+}
+
+// 规范化模拟器标识
+fn normalize_simulator_id(simulator_id: &str) -> Result<String, AppError> {
+    let simulator_id = simulator_id.trim();
+    if simulator_id.is_empty() {
+        return Err(AppError::Validation("simulatorId is required".to_string()));
+    }
+    if simulator_id.chars().count() > MAX_GATEWAY_ID_LENGTH {
+        return Err(AppError::Validation(format!(
+            "simulatorId must be at most {MAX_GATEWAY_ID_LENGTH} characters"
+        )));
+    }
+    Ok(simulator_id.to_string())
+}
+
+// 解析模拟器帧格式与单元号（mbap 响应任意单元号，不需要单元号）
+fn simulator_framing(
+    payload: &ModbusSimulatorStartPayload,
+) -> Result<(Option<Framing>, Option<u8>), AppError> {
+    let framing = match payload.framing.as_deref().map(normalize_name).as_deref() {
+        None | Some("mbap") => return Ok((None, None)),
+        Some(name) => Framing::parse(name).ok_or_else(|| {
+            AppError::Validation("framing must be one of mbap, rtu, ascii".to_string())
+        })?,
+    };
+    match payload.unit_id.unwrap_or(DEFAULT_UNIT_ID) {
+        unit_id @ 1..=247 => Ok((Some(framing), Some(unit_id))),
+        _ => Err(AppError::Validation(
+            "unitId must be between 1 and 247".to_string(),
+        )),
+    }
+}
+
+// 按模板生成寄存器映射并绑定生成器（未指定模板时为空）
+fn register_map(
+    payload: &ModbusSimulatorStartPayload,
+    seed: u64,
+) -> Result<Option<RegisterMap>, AppError> {
+    let Some(template_id) = payload.template_id else {
+        if payload.generators.is_empty() {
+            return Ok(None);
+        }
+        return Err(AppError::Validation(
+            "templateId is required for generators".to_string(),
+        ));
+    };
+    let (_, definitions) = template_repository::find_template(template_id)?
+        .ok_or_else(|| AppError::Validation("template not found".to_string()))?;
+    let mut map = RegisterMap::from_template(&definitions, seed).map_err(AppError::Validation)?;
+    let mut bound = HashSet::new();
+    for spec in &payload.generators {
+        let point_key = spec.point_key.trim();
+        if !bound.insert(point_key) {
+            return Err(AppError::Validation(format!(
+                "duplicate generator for point {point_key}"
+            )));
+        }
+        if !map.set_generator(point_key, generator(spec)?) {
+            return Err(AppError::Validation(format!(
+                "generator point {point_key} not found in template"
+            )));
+        }
+    }
+    Ok(Some(map))
+}
+
+// 校验生成器参数（省略的参数取默认值）
+fn generator(spec: &ModbusSimulatorGeneratorSpec) -> Result<Generator, AppError> {
+    let point_key = spec.point_key.trim();
+    let invalid = |reason: &str| AppError::Validation(format!("generator {point_key}: {reason}"));
+    let (min, max) = (
+        spec.min.unwrap_or(0.0),
+        spec.max.unwrap_or(DEFAULT_GENERATOR_MAX),
+    );
+    let step = spec.step.unwrap_or(1.0);
+    let kind = normalize_name(&spec.kind);
+    match kind.as_str() {
+        "constant" => Ok(Generator::Constant {
+            value: spec.value.unwrap_or(0.0),
+        }),
+        "ramp" | "random_walk" => {
+            if min > max {
+                return Err(invalid("min must not exceed max"));
+            }
+            let start = spec.start.unwrap_or(min);
+            if kind == "ramp" {
+                return Ok(Generator::Ramp {
+                    start,
+                    step,
+                    min,
+                    max,
+                });
+            }
+            if step < 0.0 {
+                return Err(invalid("step must not be negative"));
+            }
+            Ok(Generator::RandomWalk {
+                start,
+                step,
+                min,
+                max,
+            })
+        }
+        "sine" => {
+            let period_ms = spec.period_ms.unwrap_or(DEFAULT_SINE_PERIOD_MS);
+            if period_ms == 0 {
+                return Err(invalid("periodMs must be greater than 0"));
+            }
+            Ok(Generator::Sine {
+                offset: spec.offset.unwrap_or(0.0),
+                amplitude: spec.amplitude.unwrap_or(1.0),
+                period: Duration::from_millis(period_ms),
+            })
+        }
+        "counter" => {
+            let increment = spec.increment.unwrap_or(1.0);
+            if increment < 0.0 {
+                return Err(invalid("increment must not be negative"));
+            }
+            Ok(Generator::Counter {
+                start: spec.start.unwrap_or(0.0),
+                increment,
+            })
+        }
+        _ => Err(AppError::Validation(
+            "generator kind must be one of constant, ramp, sine, random_walk, counter".to_string(),
+        )),
+    }
+}
+
+// 校验故障注入参数（省略的字段取 0，异常码默认 4）
+fn fault_config(spec: &ModbusSimulatorFaultSpec) -> Result<FaultConfig, AppError> {
+    let millis = |field: &str, value: Option<u64>| match value.unwrap_or(0) {
+        value @ 0..=MAX_TIMEOUT_MS => Ok(Duration::from_millis(value)),
+        _ => Err(AppError::Validation(format!(
+            "{field} must be between 0 and {MAX_TIMEOUT_MS}"
+        ))),
+    };
+    let rate = |field: &str, value: Option<f64>| {
+        let value = value.unwrap_or(0.0);
+        if (0.0..=1.0).contains(&value) {
+            Ok(value)
+        } else {
+            Err(AppError::Validation(format!(
+                "{field} must be between 0 and 1"
+            )))
+        }
+    };
+    let exception_rate = rate("exceptionRate", spec.exception_rate)?;
+    let drop_rate = rate("dropRate", spec.drop_rate)?;
+    let corrupt_rate = rate("corruptRate", spec.corrupt_rate)?;
+    if exception_rate + drop_rate + corrupt_rate > 1.0 {
+        return Err(AppError::Validation(
+            "fault rates must add up to at most 1".to_string(),
+        ));
+    }
+    let exception_code = match spec.exception_code {
+        None => ExceptionCode::ServerDeviceFailure,
+        Some(0) => {
+            return Err(AppError::Validation(
+                "exceptionCode must be between 1 and 255".to_string(),
+            ));
+        }
+        Some(code) => ExceptionCode::from_code(code),
+    };
+    Ok(FaultConfig {
+        latency: millis("latencyMs", spec.latency_ms)?,
+        jitter: millis("jitterMs", spec.jitter_ms)?,
+        exception_rate,
+        exception_code,
+        drop_rate,
+        corrupt_rate,
+    })
+}
+
+// 生成模拟器状态
+fn simulator_data(simulator_id: &str, entry: &SimulatorEntry) -> ModbusSimulatorData {
+    let address = entry.simulator.local_addr();
+    let faults = entry.simulator.faults();
+    let stats = entry.simulator.fault_stats();
+    let points = entry.map.as_ref().map_or_else(Vec::new, |map| {
+        lock(map)
+            .points()
+            .iter()
+            .map(|point| ModbusSimulatorPointData {
+                point_key: point.key.clone(),
+                register_type: point.table.as_str().to_string(),
+                address: point.address,
+                data_type: point.data_type.clone(),
+                generator: point
+                    .generator
+                    .as_ref()
+                    .map(|generator| generator.kind().to_string()),
+                value: point.value,
+            })
+            .collect()
+    });
+    ModbusSimulatorData {
+        simulator_id: simulator_id.to_string(),
+        host: address.ip().to_string(),
+        port: address.port(),
+        framing: entry.framing.to_string(),
+        unit_id: entry.unit_id,
+        template_id: entry.template_id,
+        size: entry.size,
+        tick_ms: entry.tick_ms,
+        seed: entry.seed,
+        started_at: entry.started_at,
+        faults: ModbusSimulatorFaultData {
+            latency_ms: duration_millis(faults.latency),
+            jitter_ms: duration_millis(faults.jitter),
+            exception_rate: faults.exception_rate,
+            exception_code: faults.exception_code.code(),
+            drop_rate: faults.drop_rate,
+            corrupt_rate: faults.corrupt_rate,
+        },
+        stats: ModbusSimulatorStatsData {
+            requests: stats.requests,
+            delayed: stats.delayed,
+            exceptions: stats.exceptions,
+            dropped: stats.dropped,
+            corrupted: stats.corrupted,
+        },
+        points,
+    }
+}
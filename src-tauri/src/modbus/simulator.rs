@@ -0,0 +1,499 @@
+//! 进程内 Modbus 从站模拟器
+//!
+//! 用于在没有真实设备时联调与测试：
+//! - [`SlaveMemory`]：线圈、离散输入、保持寄存器、输入寄存器四张数据表，按请求读写
+//! - [`TcpSimulator`]：在本机端口上提供 Modbus TCP 从站服务（MBAP 帧响应任意单元号；
+//!   RTU / ASCII over TCP 模拟串口服务器后的单个从站，只响应自身单元号）
+//! - [`StreamSimulator`]：在字节流（例如 pty 主端）上提供 RTU / ASCII 从站服务，只响应自身单元号
+//!
+//! TCP 模拟器支持故障注入（[`FaultConfig`]，运行中可调整）：响应延迟与抖动、按概率返回异常响应、
+//! 断开连接与损坏响应帧；并可绑定模板寄存器映射（[`RegisterMap`]），按刷新周期写入生成器的取值。
+//!
+//! 模拟器运行在当前 tokio 运行时上，`stop` 后监听、刷新与全部已建立的连接都会关闭。
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::core::sync::lock;
+use crate::modbus::generator::{RegisterMap, Rng};
+use crate::modbus::protocol::{ExceptionCode, ModbusError, Request, Response, exception_pdu};
+use crate::modbus::transport::Framing;
+
+// 故障注入随机数的默认种子
+const DEFAULT_FAULT_SEED: u64 = 1;
+
+/// 故障注入配置（各概率之和不应超过 1）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultConfig {
+    pub latency: Duration,             // 响应前的固定延迟
+    pub jitter: Duration,              // 在固定延迟上随机增加的最大延迟
+    pub exception_rate: f64,           // 返回异常响应的概率（不读写数据表）
+    pub exception_code: ExceptionCode, // 异常码
+    pub drop_rate: f64,                // 不响应并断开连接的概率
+    pub corrupt_rate: f64,             // 损坏响应帧的概率
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            exception_rate: 0.0,
+            exception_code: ExceptionCode::ServerDeviceFailure,
+            drop_rate: 0.0,
+            corrupt_rate: 0.0,
+        }
+    }
+}
+
+/// 故障注入统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    pub requests: u64,   // 收到的请求数
+    pub delayed: u64,    // 延迟响应的请求数
+    pub exceptions: u64, // 注入异常响应的请求数
+    pub dropped: u64,    // 断开连接的请求数
+    pub corrupted: u64,  // 损坏响应帧的请求数
+}
+
+// 单个请求的注入结果
+enum Fault {
+    None,                     // 正常响应
+    Exception(ExceptionCode), // 异常响应
+    Drop,                     // 断开连接
+    Corrupt,                  // 损坏响应帧
+}
+
+// 故障注入状态（同一模拟器的全部连接共享）
+struct FaultState {
+    config: FaultConfig, // 当前配置
+    rng: Rng,            // 随机数
+    stats: FaultStats,   // 统计
+}
+
+impl FaultState {
+    // 为一个请求决定延迟与注入结果
+    fn decide(&mut self) -> (Duration, Fault) {
+        self.stats.requests += 1;
+        let config = self.config;
+        let mut delay = config.latency;
+        if !config.jitter.is_zero() {
+            delay += config.jitter.mul_f64(self.rng.next_f64());
+        }
+        if !delay.is_zero() {
+            self.stats.delayed += 1;
+        }
+        let sample = self.rng.next_f64();
+        let fault = if sample < config.drop_rate {
+            self.stats.dropped += 1;
+            Fault::Drop
+        } else if sample < config.drop_rate + config.exception_rate {
+            self.stats.exceptions += 1;
+            Fault::Exception(config.exception_code)
+        } else if sample < config.drop_rate + config.exception_rate + config.corrupt_rate {
+            self.stats.corrupted += 1;
+            Fault::Corrupt
+        } else {
+            Fault::None
+        };
+        (delay, fault)
+    }
+}
+
+// 共享的故障注入状态
+type SharedFaults = Arc<Mutex<FaultState>>;
+
+/// 共享的模板寄存器映射
+pub type SharedRegisterMap = Arc<Mutex<RegisterMap>>;
+
+/// 从站数据表
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlaveMemory {
+    pub coils: Vec<bool>,            // 线圈（FC01/05/15）
+    pub discrete_inputs: Vec<bool>,  // 离散输入（FC02）
+    pub holding_registers: Vec<u16>, // 保持寄存器（FC03/06/16）
+    pub input_registers: Vec<u16>,   // 输入寄存器（FC04）
+}
+
+impl SlaveMemory {
+    /// 创建每张数据表均为 `size` 个点的从站（初值为 0/false）
+    pub fn new(size: usize) -> Self {
+        Self {
+            coils: vec![false; size],
+            discrete_inputs: vec![false; size],
+            holding_registers: vec![0; size],
+            input_registers: vec![0; size],
+        }
+    }
+
+    /// 处理一个请求
+    ///
+    /// 地址越出数据表时返回 `IllegalDataAddress`
+    pub fn handle(&mut self, request: &Request) -> Result<Response, ExceptionCode> {
+        let address = usize::from(request.address());
+        let end = address + usize::from(request.count());
+        match request {
+            Request::ReadCoils { .. } => Ok(Response::Bits(read_range(&self.coils, address, end)?)),
+            Request::ReadDiscreteInputs { .. } => Ok(Response::Bits(read_range(
+                &self.discrete_inputs,
+                address,
+                end,
+            )?)),
+            Request::ReadHoldingRegisters { .. } => Ok(Response::Registers(read_range(
+                &self.holding_registers,
+                address,
+                end,
+            )?)),
+            Request::ReadInputRegisters { .. } => Ok(Response::Registers(read_range(
+                &self.input_registers,
+                address,
+                end,
+            )?)),
+            Request::WriteSingleCoil { value, .. } => {
+                write_range(&mut self.coils, address, &[*value])?;
+                Ok(Response::Written)
+            }
+            Request::WriteSingleRegister { value, .. } => {
+                write_range(&mut self.holding_registers, address, &[*value])?;
+                Ok(Response::Written)
+            }
+            Request::WriteMultipleCoils { values, .. } => {
+                write_range(&mut self.coils, address, values)?;
+                Ok(Response::Written)
+            }
+            Request::WriteMultipleRegisters { values, .. } => {
+                write_range(&mut self.holding_registers, address, values)?;
+                Ok(Response::Written)
+            }
+        }
+    }
+
+    /// 处理请求 PDU 并返回响应 PDU（含异常响应）
+    pub fn handle_pdu(&mut self, pdu: &[u8]) -> Vec<u8> {
+        let function = pdu.first().copied().unwrap_or_default();
+        match Request::decode(pdu) {
+            Ok(request) => match self.handle(&request) {
+                Ok(response) => response.encode(&request),
+                Err(code) => exception_pdu(function, code),
+            },
+            Err(code) => exception_pdu(function, code),
+        }
+    }
+}
+
+/// 共享的从站数据表
+pub type SharedMemory = Arc<Mutex<SlaveMemory>>;
+
+/// Modbus TCP 从站模拟器
+pub struct TcpSimulator {
+    address: SocketAddr,                    // 实际监听地址
+    memory: SharedMemory,                   // 从站数据表
+    faults: SharedFaults,                   // 故障注入
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>, // 监听、刷新与连接任务
+}
+
+impl TcpSimulator {
+    /// 在指定地址启动 Modbus TCP（MBAP）模拟器（端口为 0 时由系统分配）
+    pub async fn start(bind: &str, memory: SlaveMemory) -> io::Result<Self> {
+        Self::listen(bind, None, memory).await
+    }
+
+    /// 在指定地址启动 RTU / ASCII over TCP 模拟器（单元号为 `unit_id` 的单个从站）
+    pub async fn start_framed(
+        bind: &str,
+        framing: Framing,
+        unit_id: u8,
+        memory: SlaveMemory,
+    ) -> io::Result<Self> {
+        Self::listen(bind, Some((framing, unit_id)), memory).await
+    }
+
+    // 监听端口并为每个连接启动服务任务
+    async fn listen(
+        bind: &str,
+        framed: Option<(Framing, u8)>,
+        memory: SlaveMemory,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind).await?;
+        let address = listener.local_addr()?;
+        let memory = Arc::new(Mutex::new(memory));
+        let faults = Arc::new(Mutex::new(FaultState {
+            config: FaultConfig::default(),
+            rng: Rng::new(DEFAULT_FAULT_SEED),
+            stats: FaultStats::default(),
+        }));
+        let tasks = Arc::new(Mutex::new(Vec::<JoinHandle<()>>::new()));
+        let accept_memory = Arc::clone(&memory);
+        let accept_faults = Arc::clone(&faults);
+        let accept_tasks = Arc::clone(&tasks);
+        let accept = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let memory = Arc::clone(&accept_memory);
+                let faults = Some(Arc::clone(&accept_faults));
+                let connection = match framed {
+                    None => tokio::spawn(serve_connection(stream, memory, faults)),
+                    Some((framing, unit_id)) => {
+                        tokio::spawn(serve_framed(stream, framing, unit_id, memory, faults))
+                    }
+                };
+                // 清理已结束的连接任务（断开注入会频繁重建连接）
+                let mut tasks = lock(&accept_tasks);
+                tasks.retain(|task| !task.is_finished());
+                tasks.push(connection);
+            }
+        });
+        lock(&tasks).push(accept);
+        Ok(Self {
+            address,
+            memory,
+            faults,
+            tasks,
+        })
+    }
+
+    /// 实际监听地址
+    pub fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// 从站数据表（可在运行中读写）
+    pub fn memory(&self) -> SharedMemory {
+        Arc::clone(&self.memory)
+    }
+
+    /// 当前故障注入配置
+    pub fn faults(&self) -> FaultConfig {
+        lock(&self.faults).config
+    }
+
+    /// 调整故障注入配置（对已建立的连接立即生效，统计保留）
+    pub fn set_faults(&self, config: FaultConfig) {
+        lock(&self.faults).config = config;
+    }
+
+    /// 重置故障注入的随机种子（同一种子的注入序列可复现）
+    pub fn seed_faults(&self, seed: u64) {
+        lock(&self.faults).rng = Rng::new(seed);
+    }
+
+    /// 故障注入统计
+    pub fn fault_stats(&self) -> FaultStats {
+        lock(&self.faults).stats
+    }
+
+    /// 绑定模板寄存器映射，按刷新周期写入生成器的取值（需在 tokio 运行时内调用）
+    ///
+    /// 绑定时立即刷新一次，之后每隔 `interval` 刷新；请求写入的保持寄存器若绑定了生成器，会在下次刷新时被覆盖
+    ///
+    /// # 返回
+    /// * 共享的寄存器映射（可查询点位最近一次写入的工程值）
+    pub fn drive(&self, mut map: RegisterMap, interval: Duration) -> SharedRegisterMap {
+        let started = Instant::now();
+        map.tick(&mut lock(&self.memory), Duration::ZERO);
+        let map = Arc::new(Mutex::new(map));
+        let memory = Arc::clone(&self.memory);
+        let driven = Arc::clone(&map);
+        let task = tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                lock(&driven).tick(&mut lock(&memory), started.elapsed());
+            }
+        });
+        lock(&self.tasks).push(task);
+        map
+    }
+
+    /// 停止监听并断开全部连接
+    pub fn stop(&self) {
+        for task in lock(&self.tasks).drain(..) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for TcpSimulator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// RTU / ASCII 字节流从站模拟器
+pub struct StreamSimulator {
+    memory: SharedMemory, // 从站数据表
+    task: JoinHandle<()>, // 服务任务
+}
+
+impl StreamSimulator {
+    /// 在字节流上启动模拟器（需在 tokio 运行时内调用）
+    ///
+    /// 只响应 `unit_id` 的请求，发往其他单元号的帧被忽略（与多站总线上的从站行为一致）
+    pub fn start<S>(stream: S, framing: Framing, unit_id: u8, memory: SlaveMemory) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let memory = Arc::new(Mutex::new(memory));
+        let task = tokio::spawn(serve_framed(
+            stream,
+            framing,
+            unit_id,
+            Arc::clone(&memory),
+            None,
+        ));
+        Self { memory, task }
+    }
+
+    /// 从站数据表（可在运行中读写）
+    pub fn memory(&self) -> SharedMemory {
+        Arc::clone(&self.memory)
+    }
+
+    /// 停止服务
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for StreamSimulator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// 处理字节流上的 RTU / ASCII 请求（字节流关闭、读写失败或注入断开时结束）
+async fn serve_framed<S>(
+    mut stream: S,
+    framing: Framing,
+    unit_id: u8,
+    memory: SharedMemory,
+    faults: Option<SharedFaults>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let (request_unit, pdu) = match framing.read_request(&mut stream).await {
+            Ok(frame) => frame,
+            Err(ModbusError::Io(_)) => return,
+            // 校验失败或无法识别的帧直接丢弃，等待下一帧
+            Err(_) => continue,
+        };
+        if request_unit != unit_id {
+            continue;
+        }
+        let Some((response, corrupt)) = respond(&memory, faults.as_ref(), &pdu).await else {
+            return;
+        };
+        let mut frame = framing.encode(unit_id, &response);
+        if corrupt {
+            corrupt_checksum(framing, &mut frame);
+        }
+        if stream.write_all(&frame).await.is_err() || stream.flush().await.is_err() {
+            return;
+        }
+    }
+}
+
+// 处理单个 TCP 连接上的 MBAP 请求
+async fn serve_connection(
+    mut stream: TcpStream,
+    memory: SharedMemory,
+    faults: Option<SharedFaults>,
+) {
+    loop {
+        let mut header = [0_u8; 7];
+        if stream.read_exact(&mut header).await.is_err() {
+            return;
+        }
+        let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        if length < 2 {
+            return;
+        }
+        let mut pdu = vec![0_u8; length - 1];
+        if stream.read_exact(&mut pdu).await.is_err() {
+            return;
+        }
+        let Some((mut response, corrupt)) = respond(&memory, faults.as_ref(), &pdu).await else {
+            return;
+        };
+        // MBAP 没有校验字段，损坏响应的功能码（客户端按功能码不符拒绝）
+        if corrupt {
+            if let Some(function) = response.first_mut() {
+                *function ^= 0x20;
+            }
+        }
+        let Ok(response_length) = u16::try_from(response.len() + 1) else {
+            return;
+        };
+        let mut frame = header[..4].to_vec();
+        frame.extend_from_slice(&response_length.to_be_bytes());
+        frame.push(header[6]);
+        frame.extend_from_slice(&response);
+        if stream.write_all(&frame).await.is_err() {
+            return;
+        }
+    }
+}
+
+// 按故障注入处理请求 PDU，返回响应 PDU 与是否需要损坏响应帧（注入断开时返回 None）
+async fn respond(
+    memory: &SharedMemory,
+    faults: Option<&SharedFaults>,
+    pdu: &[u8],
+) -> Option<(Vec<u8>, bool)> {
+    let (delay, fault) = faults.map_or((Duration::ZERO, Fault::None), |faults| {
+        lock(faults).decide()
+    });
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    match fault {
+        Fault::None => Some((lock(memory).handle_pdu(pdu), false)),
+        Fault::Exception(code) => {
+            let function = pdu.first().copied().unwrap_or_default();
+            Some((exception_pdu(function, code), false))
+        }
+        Fault::Drop => None,
+        Fault::Corrupt => Some((lock(memory).handle_pdu(pdu), true)),
+    }
+}
+
+// 损坏 RTU 帧的 CRC 或 ASCII 帧的 LRC（帧其余部分保持完整，接收方读完整帧后校验失败）
+fn corrupt_checksum(framing: Framing, frame: &mut [u8]) {
+    let index = match framing {
+        Framing::Rtu => frame.len().checked_sub(1),
+        // ASCII 帧以 LRC 的两个十六进制字符与 CRLF 结尾，替换 LRC 的低位字符
+        Framing::Ascii => frame.len().checked_sub(3),
+    };
+    if let Some(byte) = index.and_then(|index| frame.get_mut(index)) {
+        *byte = match framing {
+            Framing::Rtu => *byte ^ 0xFF,
+            Framing::Ascii if *byte == b'0' => b'1',
+            Framing::Ascii => b'0',
+        };
+    }
+}
+
+// 读取数据表区间
+fn read_range<T: Copy>(table: &[T], start: usize, end: usize) -> Result<Vec<T>, ExceptionCode> {
+    table
+        .get(start..end)
+        .map(<[T]>::to_vec)
+        .ok_or(ExceptionCode::IllegalDataAddress)
+}
+
+// 写入数据表区间
+fn write_range<T: Copy>(table: &mut [T], start: usize, values: &[T]) -> Result<(), ExceptionCode> {
+    table
+        .get_mut(start..start + values.len())
+        .ok_or(ExceptionCode::IllegalDataAddress)?
+        .copy_from_slice(values);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Modbus 全局连接状态
+//!
+//! 以网关标识为键保存每个网关的长连接客户端：
+//! - 注册表本身使用同步锁，只在查找/替换客户端时短暂持有
+//! - 每个客户端包裹在 `tokio::sync::Mutex` 中，同一网关上的事务串行执行，不同网关互不阻塞
+//! - 注册的客户端将事务记录到同一网关标识的通信诊断（替换客户端后继续累计）
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::modbus::client::{ClientConfig, ModbusClient};
+use crate::modbus::diagnostics;
+
+/// 共享的网关客户端
+pub type SharedClient = Arc<tokio::sync::Mutex<ModbusClient>>;
+
+// 网关标识 → 客户端
+fn sessions() -> &'static Mutex<HashMap<String, SharedClient>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, SharedClient>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 获取注册表锁（锁中毒时继续使用内部数据）
+fn lock_sessions() -> std::sync::MutexGuard<'static, HashMap<String, SharedClient>> {
+    sessions()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// 注册网关客户端
+///
+/// 配置未变化时复用现有客户端（保留连接）；否则以新配置替换，旧连接在最后一个引用释放时关闭
+pub fn register(gateway_id: &str, config: ClientConfig) -> SharedClient {
+    let mut sessions = lock_sessions();
+    if let Some(existing) = sessions.get(gateway_id) {
+        let unchanged = existing
+            .try_lock()
+            .is_ok_and(|client| client.config() == &config);
+        if unchanged {
+            return Arc::clone(existing);
+        }
+    }
+    let mut client = ModbusClient::new(config);
+    client.attach_diagnostics(diagnostics::link(gateway_id));
+    let client = Arc::new(tokio::sync::Mutex::new(client));
+    sessions.insert(gateway_id.to_string(), Arc::clone(&client));
+    client
+}
+
+/// 查找网关客户端
+pub fn get(gateway_id: &str) -> Option<SharedClient> {
+    lock_sessions().get(gateway_id).cloned()
+}
+
+/// 移除网关客户端（返回被移除的客户端）
+pub fn remove(gateway_id: &str) -> Option<SharedClient> {
+    lock_sessions().remove(gateway_id)
+}
+
+/// 全部网关客户端（按网关标识排序）
+pub fn all() -> Vec<(String, SharedClient)> {
+    let mut entries: Vec<(String, SharedClient)> = lock_sessions()
+        .iter()
+        .map(|(gateway_id, client)| (gateway_id.clone(), Arc::clone(client)))
+        .collect();
+    entries.sort_by(|left, right| left.0.cmp(&right.0));
+    entries
+}
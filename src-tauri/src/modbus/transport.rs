@@ -0,0 +1,409 @@
+//! Modbus 传输层
+//!
+//! 本模块定义与帧格式无关的传输接口 [`ModbusTransport`]，以及各传输方式的连接参数：
+//! - Modbus TCP：MBAP 报文头（事务号、协议号、长度、单元号）+ PDU
+//! - RTU / ASCII over TCP：串口服务器透传的 RTU 帧（CRC）或 ASCII 帧（LRC），不带 MBAP 报文头
+//! - 串口 Modbus RTU / ASCII：见 `serial` 模块
+//!
+//! RTU 与 ASCII 帧的编解码见 `rtu`、`ascii` 模块，由 [`Framing`] 统一调度。
+//!
+//! 客户端只依赖 `ModbusTransport`，点表与轮询逻辑不关心底层使用哪种传输方式。
+//! 每次事务在链路上收发的原始字节记录在 [`WireLog`] 中，供通信诊断的报文捕获使用。
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpStream;
+
+use crate::modbus::protocol::ModbusError;
+use crate::modbus::serial::{self, SerialConfig};
+use crate::modbus::{ascii, rtu};
+
+// MBAP 报文头长度（事务号 2 + 协议号 2 + 长度 2 + 单元号 1）
+const MBAP_HEADER_LENGTH: usize = 7;
+
+// MBAP 长度字段上限（单元号 1 + PDU 最大 253）
+const MBAP_MAX_LENGTH: usize = 254;
+
+/// 传输层返回的异步结果
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ModbusError>> + Send + 'a>>;
+
+/// 一次事务在链路上收发的原始字节
+///
+/// `received` 包含本次事务读取到的全部字节（含被跳过的迟到响应与校验失败的帧）；
+/// 超时或读取失败时为失败前已收到的部分
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WireLog {
+    pub sent: Vec<u8>,     // 发送的请求帧
+    pub received: Vec<u8>, // 接收的字节
+}
+
+impl WireLog {
+    /// 清空记录（保留已分配的缓冲区）
+    pub fn clear(&mut self) {
+        self.sent.clear();
+        self.received.clear();
+    }
+}
+
+/// Modbus 传输接口
+///
+/// 一次调用完成一个事务：发送请求 PDU 并等待对应的响应 PDU。
+/// 返回连接层错误（见 `ModbusError::is_connection_fault`）后，调用方应丢弃该连接。
+pub trait ModbusTransport: Send {
+    /// 发送请求 PDU 并返回响应 PDU，收发的原始字节追加到 `wire`
+    fn transact<'a>(
+        &'a mut self,
+        unit_id: u8,
+        pdu: &'a [u8],
+        timeout: Duration,
+        wire: &'a mut WireLog,
+    ) -> TransportFuture<'a, Vec<u8>>;
+}
+
+/// 记录读取字节的流包装（读取到的字节按原样追加到记录中）
+pub(crate) struct Recorded<'a, S: ?Sized> {
+    stream: &'a mut S,       // 被读取的流
+    record: &'a mut Vec<u8>, // 读取记录
+}
+
+impl<'a, S: ?Sized> Recorded<'a, S> {
+    /// 包装流
+    pub(crate) fn new(stream: &'a mut S, record: &'a mut Vec<u8>) -> Self {
+        Self { stream, record }
+    }
+}
+
+impl<S> AsyncRead for Recorded<'_, S>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+        let poll = Pin::new(&mut *this.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            this.record.extend_from_slice(&buf.filled()[start..]);
+        }
+        poll
+    }
+}
+
+/// 串行链路帧格式（串口与串口服务器透传共用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Framing {
+    Rtu,   // RTU：二进制帧 + CRC-16
+    Ascii, // ASCII：十六进制字符帧 + LRC
+}
+
+impl Framing {
+    /// 由名称解析（rtu / ascii）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rtu" => Some(Self::Rtu),
+            "ascii" => Some(Self::Ascii),
+            _ => None,
+        }
+    }
+
+    /// 名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rtu => "rtu",
+            Self::Ascii => "ascii",
+        }
+    }
+
+    /// 组装请求或响应帧
+    pub fn encode(self, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Rtu => rtu::encode_frame(unit_id, pdu),
+            Self::Ascii => ascii::encode_frame(unit_id, pdu),
+        }
+    }
+
+    /// 读取一个响应帧，返回单元号与响应 PDU
+    pub async fn read_response<S>(self, stream: &mut S) -> Result<(u8, Vec<u8>), ModbusError>
+    where
+        S: AsyncRead + Unpin + ?Sized,
+    {
+        match self {
+            Self::Rtu => rtu::read_response(stream).await,
+            Self::Ascii => ascii::read_frame(stream).await,
+        }
+    }
+
+    /// 读取一个请求帧，返回单元号与请求 PDU（供模拟从站使用）
+    pub async fn read_request<S>(self, stream: &mut S) -> Result<(u8, Vec<u8>), ModbusError>
+    where
+        S: AsyncRead + Unpin + ?Sized,
+    {
+        match self {
+            Self::Rtu => rtu::read_request(stream).await,
+            Self::Ascii => ascii::read_frame(stream).await,
+        }
+    }
+}
+
+/// 传输方式与连接参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportConfig {
+    /// Modbus TCP（MBAP 帧）
+    Tcp { host: String, port: u16 },
+    /// RTU / ASCII over TCP（串口服务器透传）
+    TcpFramed {
+        host: String,
+        port: u16,
+        framing: Framing,
+    },
+    /// 串口 Modbus RTU / ASCII
+    Serial {
+        config: SerialConfig,
+        framing: Framing,
+    },
+}
+
+impl TransportConfig {
+    /// 传输方式名称（tcp / rtu_over_tcp / ascii_over_tcp / rtu / ascii）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Tcp { .. } => "tcp",
+            Self::TcpFramed {
+                framing: Framing::Rtu,
+                ..
+            } => "rtu_over_tcp",
+            Self::TcpFramed {
+                framing: Framing::Ascii,
+                ..
+            } => "ascii_over_tcp",
+            Self::Serial { framing, .. } => framing.as_str(),
+        }
+    }
+
+    /// 连接目标描述（例如 `192.168.1.100:502`、`/dev/ttyUSB0 9600 8N1`）
+    pub fn target(&self) -> String {
+        match self {
+            Self::Tcp { host, port } | Self::TcpFramed { host, port, .. } => {
+                format_host_port(host, *port)
+            }
+            Self::Serial { config, .. } => config.describe(),
+        }
+    }
+
+    /// 按配置建立连接
+    pub async fn connect(
+        &self,
+        timeout: Duration,
+    ) -> Result<Box<dyn ModbusTransport>, ModbusError> {
+        match self {
+            Self::Tcp { host, port } => {
+                let stream = connect_tcp(host, *port, timeout).await?;
+                Ok(Box::new(TcpTransport::new(stream)))
+            }
+            Self::TcpFramed {
+                host,
+                port,
+                framing,
+            } => {
+                let stream = connect_tcp(host, *port, timeout).await?;
+                Ok(Box::new(FramedTcpTransport {
+                    stream,
+                    framing: *framing,
+                }))
+            }
+            Self::Serial { config, framing } => Ok(Box::new(serial::open(config, *framing)?)),
+        }
+    }
+}
+
+/// 在超时时间内建立 TCP 连接
+pub(crate) async fn connect_tcp(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<TcpStream, ModbusError> {
+    let target = format_host_port(host, port);
+    let stream = tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| ModbusError::Timeout(duration_millis(timeout)))?
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::ConnectionRefused => ModbusError::ConnectionRefused(target.clone()),
+            _ => ModbusError::Connect(format!("{target}: {err}")),
+        })?;
+    // 关闭 Nagle 算法，避免短报文被延迟发送
+    stream
+        .set_nodelay(true)
+        .map_err(|err| ModbusError::Connect(format!("{target}: {err}")))?;
+    Ok(stream)
+}
+
+/// Modbus TCP 传输（MBAP 帧）
+pub struct TcpTransport {
+    stream: TcpStream,     // TCP 连接
+    next_transaction: u16, // 下一个事务号
+}
+
+impl TcpTransport {
+    /// 基于已建立的 TCP 连接创建传输
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            next_transaction: 1,
+        }
+    }
+
+    // 发送请求并读取事务号匹配的响应（跳过迟到的旧响应）
+    async fn exchange(
+        &mut self,
+        unit_id: u8,
+        pdu: &[u8],
+        wire: &mut WireLog,
+    ) -> Result<Vec<u8>, ModbusError> {
+        let transaction = self.next_transaction;
+        self.next_transaction = self.next_transaction.wrapping_add(1);
+
+        let length = u16::try_from(pdu.len() + 1)
+            .map_err(|_| ModbusError::InvalidRequest("pdu too long".to_string()))?;
+        let mut frame = Vec::with_capacity(MBAP_HEADER_LENGTH + pdu.len());
+        frame.extend_from_slice(&transaction.to_be_bytes());
+        frame.extend_from_slice(&0_u16.to_be_bytes());
+        frame.extend_from_slice(&length.to_be_bytes());
+        frame.push(unit_id);
+        frame.extend_from_slice(pdu);
+        wire.sent.extend_from_slice(&frame);
+        self.stream.write_all(&frame).await.map_err(io_error)?;
+
+        let mut stream = Recorded::new(&mut self.stream, &mut wire.received);
+        loop {
+            let mut header = [0_u8; MBAP_HEADER_LENGTH];
+            stream.read_exact(&mut header).await.map_err(io_error)?;
+            let response_transaction = u16::from_be_bytes([header[0], header[1]]);
+            let protocol = u16::from_be_bytes([header[2], header[3]]);
+            let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+            if protocol != 0 {
+                return Err(ModbusError::Protocol(format!(
+                    "unexpected protocol id {protocol}"
+                )));
+            }
+            if !(2..=MBAP_MAX_LENGTH).contains(&length) {
+                return Err(ModbusError::Protocol(format!(
+                    "invalid MBAP length {length}"
+                )));
+            }
+            let mut body = vec![0_u8; length - 1];
+            stream.read_exact(&mut body).await.map_err(io_error)?;
+            if response_transaction != transaction {
+                continue;
+            }
+            if header[6] != unit_id {
+                return Err(ModbusError::Protocol(format!(
+                    "unit id mismatch: expected {unit_id}, got {}",
+                    header[6]
+                )));
+            }
+            return Ok(body);
+        }
+    }
+}
+
+impl ModbusTransport for TcpTransport {
+    fn transact<'a>(
+        &'a mut self,
+        unit_id: u8,
+        pdu: &'a [u8],
+        timeout: Duration,
+        wire: &'a mut WireLog,
+    ) -> TransportFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            tokio::time::timeout(timeout, self.exchange(unit_id, pdu, wire))
+                .await
+                .map_err(|_| ModbusError::Timeout(duration_millis(timeout)))?
+        })
+    }
+}
+
+/// RTU / ASCII over TCP 传输（串口服务器透传，帧内不带事务号）
+pub struct FramedTcpTransport {
+    stream: TcpStream, // TCP 连接
+    framing: Framing,  // 帧格式
+}
+
+impl FramedTcpTransport {
+    // 发送请求帧并读取响应
+    async fn exchange(
+        &mut self,
+        unit_id: u8,
+        pdu: &[u8],
+        wire: &mut WireLog,
+    ) -> Result<Vec<u8>, ModbusError> {
+        // 丢弃上一事务残留的字节（超时后迟到的响应、校验失败帧的剩余部分），避免与本次响应错位
+        let mut stale = [0_u8; 256];
+        loop {
+            match self.stream.try_read(&mut stale) {
+                Ok(0) => return Err(ModbusError::Io("connection closed by peer".to_string())),
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(io_error(err)),
+            }
+        }
+        let frame = self.framing.encode(unit_id, pdu);
+        wire.sent.extend_from_slice(&frame);
+        self.stream.write_all(&frame).await.map_err(io_error)?;
+        let (response_unit, response) = self
+            .framing
+            .read_response(&mut Recorded::new(&mut self.stream, &mut wire.received))
+            .await?;
+        if response_unit != unit_id {
+            return Err(ModbusError::Protocol(format!(
+                "unit id mismatch: expected {unit_id}, got {response_unit}"
+            )));
+        }
+        Ok(response)
+    }
+}
+
+impl ModbusTransport for FramedTcpTransport {
+    fn transact<'a>(
+        &'a mut self,
+        unit_id: u8,
+        pdu: &'a [u8],
+        timeout: Duration,
+        wire: &'a mut WireLog,
+    ) -> TransportFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            tokio::time::timeout(timeout, self.exchange(unit_id, pdu, wire))
+                .await
+                .map_err(|_| ModbusError::Timeout(duration_millis(timeout)))?
+        })
+    }
+}
+
+/// 毫秒数（超出 u64 时取上限）
+pub(crate) fn duration_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+// 组合主机与端口（IPv6 地址加方括号）
+fn format_host_port(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+// 将读写错误转换为连接层错误
+fn io_error(err: io::Error) -> ModbusError {
+    match err.kind() {
+        io::ErrorKind::UnexpectedEof => ModbusError::Io("connection closed by peer".to_string()),
+        _ => ModbusError::Io(err.to_string()),
+    }
+}
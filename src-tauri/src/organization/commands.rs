@@ -0,0 +1,379 @@
+//! 组织架构模块 IPC 命令层
+//!
+//! 本模块定义前端可调用的组织架构相关 Tauri 命令接口
+//!
+//! | 命令名 | 功能说明 |
+//! |--------|----------|
+//! | `organization_list` | 查询组织树（含成员数） |
+//! | `organization_create` | 创建组织 |
+//! | `organization_update` | 更新组织信息或移动到其他上级组织 |
+//! | `organization_delete` | 删除没有下级组织和成员的组织 |
+//! | `organization_assign_users` | 批量设置用户所属组织 |
+
+// 引入时间工具函数
+use crate::auth::services::now_millis;
+// 引入核心错误类型
+use crate::core::error::{ApiResponse, AppResult};
+// 引入链路追踪相关类型
+use crate::core::tracing::{TraceContext, execute_traced_command};
+// 引入组织数据模型
+use crate::organization::models::{
+    OrganizationAssignUsersData, OrganizationAssignUsersPayload, OrganizationCreatePayload,
+    OrganizationData, OrganizationDeletePayload, OrganizationListPayload, OrganizationNodeData,
+    OrganizationUpdatePayload,
+};
+// 引入组织服务层
+use crate::organization::services;
+
+/// 查询组织树
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名的请求体
+///
+/// # 返回
+/// * 根组织列表，节点递归包含下级组织
+#[tauri::command]
+pub fn organization_list(
+    payload: OrganizationListPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<Vec<OrganizationNodeData>> {
+    execute_traced_command("organization_list", trace, || {
+        Ok(ApiResponse::ok(services::list_organization_tree(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 创建组织
+///
+/// # 参数
+/// * `payload` - 上级组织、名称、类型及联系人、合同编号等元数据
+///
+/// # 返回
+/// * 新建的组织
+#[tauri::command]
+pub fn organization_create(
+    payload: OrganizationCreatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<OrganizationData> {
+    execute_traced_command("organization_create", trace, || {
+        Ok(ApiResponse::ok(services::create_organization(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 更新组织
+///
+/// # 参数
+/// * `payload` - 组织 ID 及更新后的全部字段
+///
+/// # 返回
+/// * 更新后的组织
+#[tauri::command]
+pub fn organization_update(
+    payload: OrganizationUpdatePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<OrganizationData> {
+    execute_traced_command("organization_update", trace, || {
+        Ok(ApiResponse::ok(services::update_organization(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 删除组织
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名与组织 ID 的请求体
+///
+/// # 返回
+/// * 删除成功返回 true
+#[tauri::command]
+pub fn organization_delete(
+    payload: OrganizationDeletePayload,
+    trace: Option<TraceContext>,
+) -> AppResult<bool> {
+    execute_traced_command("organization_delete", trace, || {
+        Ok(ApiResponse::ok(services::delete_organization(
+            &payload,
+            now_millis(),
+        )?))
+    })
+}
+
+/// 批量设置用户所属组织
+///
+/// # 参数
+/// * `payload` - 目标组织 ID（为空表示移出组织）与用户 ID 列表
+///
+/// # 返回
+/// * 目标组织与已更新的用户 ID
+#[tauri::command]
+pub fn organization_assign_users(
+    payload: OrganizationAssignUsersPayload,
+    trace: Option<TraceContext>,
+) -> AppResult<OrganizationAssignUsersData> {
+    execute_traced_command("organization_assign_users", trace, || {
+        Ok(ApiResponse::ok(services::assign_users(
+            payload,
+            now_millis(),
+        )?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::auth::admin_commands::{auth_admin_list_users, auth_admin_register_user};
+    use crate::auth::models::{AdminListUsersPayload, AdminRegisterUserPayload};
+    use crate::core::error::AppError;
+    use crate::db;
+
+    fn unique_name(prefix: &str) -> String {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        format!("{prefix}_{counter}_{nanos}")
+    }
+
+    fn ensure_test_db_ready() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            db::set_database_url(db::test_database_url()).expect("configure database url");
+            db::init_database().expect("init database");
+        });
+    }
+
+    fn create(parent_id: Option<i64>, prefix: &str, org_type: &str) -> OrganizationData {
+        organization_create(
+            OrganizationCreatePayload {
+                operator_username: "admin".to_string(),
+                parent_id,
+                name: unique_name(prefix),
+                org_type: org_type.to_string(),
+                contact_name: Some("张工".to_string()),
+                contact_phone: Some("13800138000".to_string()),
+                contract_number: Some(" HT-2026-001 ".to_string()),
+                remark: Some("  ".to_string()),
+            },
+            None,
+        )
+        .expect("create organization")
+        .data
+    }
+
+    fn register_user(prefix: &str) -> i64 {
+        auth_admin_register_user(
+            AdminRegisterUserPayload {
+                operator_username: "admin".to_string(),
+                username: unique_name(prefix),
+                password: "admin123".to_string(),
+                nickname: "组织成员".to_string(),
+                phone: None,
+                roles: vec!["tenant".to_string()],
+                account_term_type: "permanent".to_string(),
+                account_valid_days: None,
+                account_start_at: None,
+                account_expire_at: None,
+                organization_id: None,
+            },
+            None,
+        )
+        .expect("register user")
+        .data
+        .user_id
+    }
+
+    fn list_member_ids(organization_id: i64) -> Vec<i64> {
+        auth_admin_list_users(
+            AdminListUsersPayload {
+                operator_username: "admin".to_string(),
+                include_deleted: false,
+                organization_id: Some(organization_id),
+            },
+            None,
+        )
+        .expect("list users")
+        .data
+        .into_iter()
+        .map(|user| user.user_id)
+        .collect()
+    }
+
+    fn assign(
+        organization_id: Option<i64>,
+        user_ids: Vec<i64>,
+    ) -> AppResult<OrganizationAssignUsersData> {
+        organization_assign_users(
+            OrganizationAssignUsersPayload {
+                operator_username: "admin".to_string(),
+                organization_id,
+                user_ids,
+            },
+            None,
+        )
+    }
+
+    fn find_node(nodes: &[OrganizationNodeData], id: i64) -> Option<&OrganizationNodeData> {
+        nodes.iter().find_map(|node| {
+            if node.organization.id == id {
+                Some(node)
+            } else {
+                find_node(&node.children, id)
+            }
+        })
+    }
+
+    #[test]
+    fn builds_tree_and_filters_users_by_subtree() {
+        ensure_test_db_ready();
+        let company = create(None, "company", "Company");
+        let site = create(Some(company.id), "site", "site");
+        let department = create(Some(site.id), "department", "department");
+        let other_site = create(Some(company.id), "other_site", "site");
+        assert_eq!(company.org_type, "company");
+        assert_eq!(company.contract_number.as_deref(), Some("HT-2026-001"));
+        assert!(company.remark.is_none());
+
+        let member = register_user("org_member");
+        let assigned = assign(Some(department.id), vec![member, member])
+            .expect("assign user")
+            .data;
+        assert_eq!(assigned.user_ids, vec![member]);
+
+        // 按子树过滤：上级组织包含下级组织的成员，兄弟组织不包含
+        assert!(list_member_ids(company.id).contains(&member));
+        assert!(list_member_ids(department.id).contains(&member));
+        assert!(!list_member_ids(other_site.id).contains(&member));
+
+        let tree = organization_list(
+            OrganizationListPayload {
+                operator_username: "admin".to_string(),
+            },
+            None,
+        )
+        .expect("list organizations")
+        .data;
+        let company_node = find_node(&tree, company.id).expect("company node");
+        assert!(company_node.organization.parent_id.is_none());
+        assert_eq!(company_node.children.len(), 2);
+        let department_node = find_node(&company_node.children, department.id).expect("department");
+        assert_eq!(department_node.organization.member_count, 1);
+    }
+
+    #[test]
+    fn update_rejects_moving_under_descendant() {
+        ensure_test_db_ready();
+        let company = create(None, "cycle_company", "company");
+        let site = create(Some(company.id), "cycle_site", "site");
+
+        let err = organization_update(
+            OrganizationUpdatePayload {
+                operator_username: "admin".to_string(),
+                organization_id: company.id,
+                parent_id: Some(site.id),
+                name: company.name.clone(),
+                org_type: "company".to_string(),
+                ..OrganizationUpdatePayload::default()
+            },
+            None,
+        )
+        .expect_err("expect cycle rejected");
+        assert_eq!(
+            err,
+            AppError::Validation(
+                "parentId cannot be the organization itself or its descendant".to_string()
+            )
+        );
+
+        // 移动到根层级并修改元数据
+        let moved = organization_update(
+            OrganizationUpdatePayload {
+                operator_username: "admin".to_string(),
+                organization_id: site.id,
+                parent_id: None,
+                name: site.name.clone(),
+                org_type: "site".to_string(),
+                contract_number: Some("HT-2026-002".to_string()),
+                ..OrganizationUpdatePayload::default()
+            },
+            None,
+        )
+        .expect("move to root")
+        .data;
+        assert!(moved.parent_id.is_none());
+        assert_eq!(moved.contract_number.as_deref(), Some("HT-2026-002"));
+    }
+
+    #[test]
+    fn delete_requires_empty_organization() {
+        ensure_test_db_ready();
+        let company = create(None, "delete_company", "company");
+        let site = create(Some(company.id), "delete_site", "site");
+        let member = register_user("delete_member");
+        assign(Some(site.id), vec![member]).expect("assign user");
+
+        let delete = |organization_id: i64| {
+            organization_delete(
+                OrganizationDeletePayload {
+                    operator_username: "admin".to_string(),
+                    organization_id,
+                },
+                None,
+            )
+        };
+        assert_eq!(
+            delete(company.id).expect_err("has children"),
+            AppError::Validation("organization has child organizations".to_string())
+        );
+        assert_eq!(
+            delete(site.id).expect_err("has members"),
+            AppError::Validation("organization has members".to_string())
+        );
+
+        assign(None, vec![member]).expect("remove user from organization");
+        assert!(delete(site.id).expect("delete site").data);
+        assert!(delete(company.id).expect("delete company").data);
+    }
+
+    #[test]
+    fn assign_rejects_unknown_users_atomically() {
+        ensure_test_db_ready();
+        let company = create(None, "assign_company", "company");
+        let member = register_user("assign_member");
+
+        let err = assign(Some(company.id), vec![member, i64::MAX]).expect_err("unknown user");
+        assert_eq!(err, AppError::Validation("user not found".to_string()));
+        assert!(list_member_ids(company.id).is_empty());
+    }
+
+    #[test]
+    fn non_admin_cannot_manage_organizations() {
+        ensure_test_db_ready();
+        let err = organization_create(
+            OrganizationCreatePayload {
+                operator_username: "common".to_string(),
+                name: unique_name("forbidden"),
+                org_type: "company".to_string(),
+                ..OrganizationCreatePayload::default()
+            },
+            None,
+        )
+        .expect_err("expect forbidden");
+        assert_eq!(
+            err,
+            AppError::Validation("forbidden: organization manage required".to_string())
+        );
+    }
+}
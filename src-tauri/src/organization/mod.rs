@@ -0,0 +1,15 @@
+//! 组织架构模块入口
+//!
+//! 本模块维护多级组织树（公司 → 场所 → 部门等）及用户归属：
+//! - 组织携带联系人、联系电话、合同编号等元数据
+//! - 用户通过 `users.organization_id` 归属到一个组织，管理员用户列表可按组织子树过滤
+//! - 设备范围、报表、委派管理等功能以组织 ID 作为范围键
+
+// 公开命令模块 - 暴露给前端调用的 Tauri 命令
+pub mod commands;
+// 公开模型模块 - 组织请求/响应结构
+pub mod models;
+// 公开服务模块 - 其他业务模块查询组织子树时使用
+pub mod services;
+// 公开仓储模块 - 其他业务模块通过 find_subtree_ids 解析组织范围
+pub mod repository;
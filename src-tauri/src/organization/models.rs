@@ -0,0 +1,164 @@
+//! 组织架构模块数据模型
+//!
+//! 本模块定义组织树的存储记录以及 IPC 命令的请求/响应结构
+
+// 引入序列化相关 trait
+use serde::{Deserialize, Serialize};
+
+/// 组织存储记录
+///
+/// 与 organizations 表对应，`member_count` 为未删除成员数（查询时统计）
+#[derive(Debug, Clone, Default)]
+pub struct OrganizationRecord {
+    pub id: i64,                         // 组织 ID
+    pub parent_id: Option<i64>,          // 上级组织 ID（根节点为 None）
+    pub name: String,                    // 组织名称
+    pub org_type: String,                // 组织类型
+    pub contact_name: Option<String>,    // 联系人
+    pub contact_phone: Option<String>,   // 联系电话
+    pub contract_number: Option<String>, // 合同编号
+    pub remark: Option<String>,          // 备注
+    pub created_at: i64,                 // 创建时间戳（毫秒）
+    pub updated_at: i64,                 // 更新时间戳（毫秒）
+    pub created_by: String,              // 创建人
+    pub member_count: i64,               // 未删除成员数
+}
+
+/// 组织写入参数（创建与更新共用，已完成规范化）
+#[derive(Debug, Clone, Default)]
+pub struct OrganizationInput {
+    pub parent_id: Option<i64>,          // 上级组织 ID
+    pub name: String,                    // 组织名称
+    pub org_type: String,                // 组织类型
+    pub contact_name: Option<String>,    // 联系人
+    pub contact_phone: Option<String>,   // 联系电话
+    pub contract_number: Option<String>, // 合同编号
+    pub remark: Option<String>,          // 备注
+}
+
+// 组织列表请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OrganizationListPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+}
+
+// 创建组织请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OrganizationCreatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 上级组织 ID（为空表示创建根组织）
+    pub parent_id: Option<i64>,
+    /// 组织名称
+    pub name: String,
+    /// 组织类型（如 company / site / department）
+    pub org_type: String,
+    /// 联系人
+    pub contact_name: Option<String>,
+    /// 联系电话
+    pub contact_phone: Option<String>,
+    /// 合同编号
+    pub contract_number: Option<String>,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 更新组织请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OrganizationUpdatePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 组织 ID
+    pub organization_id: i64,
+    /// 上级组织 ID（为空表示移动到根层级）
+    pub parent_id: Option<i64>,
+    /// 组织名称
+    pub name: String,
+    /// 组织类型
+    pub org_type: String,
+    /// 联系人
+    pub contact_name: Option<String>,
+    /// 联系电话
+    pub contact_phone: Option<String>,
+    /// 合同编号
+    pub contract_number: Option<String>,
+    /// 备注
+    pub remark: Option<String>,
+}
+
+// 删除组织请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OrganizationDeletePayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 组织 ID
+    pub organization_id: i64,
+}
+
+// 设置用户所属组织请求体
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OrganizationAssignUsersPayload {
+    /// 操作员用户名
+    pub operator_username: String,
+    /// 目标组织 ID（为空表示移出组织）
+    pub organization_id: Option<i64>,
+    /// 用户 ID 列表
+    pub user_ids: Vec<i64>,
+}
+
+// 组织响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationData {
+    /// 组织 ID
+    pub id: i64,
+    /// 上级组织 ID
+    pub parent_id: Option<i64>,
+    /// 组织名称
+    pub name: String,
+    /// 组织类型
+    pub org_type: String,
+    /// 联系人
+    pub contact_name: Option<String>,
+    /// 联系电话
+    pub contact_phone: Option<String>,
+    /// 合同编号
+    pub contract_number: Option<String>,
+    /// 备注
+    pub remark: Option<String>,
+    /// 创建时间戳（毫秒）
+    pub created_at: i64,
+    /// 更新时间戳（毫秒）
+    pub updated_at: i64,
+    /// 创建人
+    pub created_by: String,
+    /// 直属成员数（不含下级组织）
+    pub member_count: i64,
+}
+
+// 组织树节点响应数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationNodeData {
+    /// 组织信息
+    #[serde(flatten)]
+    pub organization: OrganizationData,
+    /// 下级组织（按名称排序）
+    pub children: Vec<OrganizationNodeData>,
+}
+
+// 设置用户所属组织响应体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationAssignUsersData {
+    /// 目标组织 ID（移出组织时为 null）
+    pub organization_id: Option<i64>,
+    /// 已更新的用户 ID（去重、升序）
+    pub user_ids: Vec<i64>,
+}
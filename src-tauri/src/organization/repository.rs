@@ -0,0 +1,342 @@
+//! 组织架构模块数据仓储层
+//!
+//! 本模块负责 organizations 表及 users.organization_id 的读写：
+//! - 组织的增删改查（含成员数统计）
+//! - 递归查询组织子树
+//! - 批量设置用户所属组织（单事务）
+//!
+//! 组织树查询依赖递归 CTE，使用原生 SQL（SQLx）实现
+
+// 引入 SQLx 查询类型
+use sqlx::postgres::PgRow;
+use sqlx::{Row, query, query_scalar};
+
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入数据库模块
+use crate::db;
+// 引入组织模型
+use crate::organization::models::{OrganizationInput, OrganizationRecord};
+
+// 组织查询列（顺序与 map_organization_row 对应）
+const ORGANIZATION_COLUMNS: &str = "o.id, o.parent_id, o.name, o.org_type, o.contact_name, \
+     o.contact_phone, o.contract_number, o.remark, o.created_at, o.updated_at, o.created_by, \
+     (SELECT COUNT(*) FROM users u WHERE u.organization_id = o.id AND u.deleted_at IS NULL) \
+     AS member_count";
+
+/// 查询全部组织
+///
+/// # 返回
+/// * 按上级组织、名称排序的组织记录
+pub fn list_organizations() -> Result<Vec<OrganizationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!(
+            "SELECT {ORGANIZATION_COLUMNS} FROM organizations o ORDER BY o.parent_id NULLS FIRST, o.name, o.id"
+        );
+        let rows = query(&sql)
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        rows.iter().map(map_organization_row).collect()
+    })
+}
+
+/// 按 ID 查询组织
+///
+/// # 参数
+/// * `organization_id` - 组织 ID
+///
+/// # 返回
+/// * 组织记录（不存在时为 None）
+pub fn find_organization(organization_id: i64) -> Result<Option<OrganizationRecord>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let sql = format!("SELECT {ORGANIZATION_COLUMNS} FROM organizations o WHERE o.id = $1");
+        let row = query(&sql)
+            .bind(organization_id)
+            .fetch_optional(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        row.as_ref().map(map_organization_row).transpose()
+    })
+}
+
+/// 查询组织子树（含自身）的全部组织 ID
+///
+/// # 参数
+/// * `organization_id` - 子树根组织 ID
+///
+/// # 返回
+/// * 子树内的组织 ID（组织不存在时为空）
+pub fn find_subtree_ids(organization_id: i64) -> Result<Vec<i64>, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query_scalar(
+            r"
+            WITH RECURSIVE subtree(id) AS (
+              SELECT id FROM organizations WHERE id = $1
+              UNION ALL
+              SELECT o.id FROM organizations o JOIN subtree s ON o.parent_id = s.id
+            )
+            SELECT id FROM subtree ORDER BY id
+            ",
+        )
+        .bind(organization_id)
+        .fetch_all(&mut connection)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+    })
+}
+
+/// 创建组织
+///
+/// # 参数
+/// * `input` - 组织写入参数
+/// * `created_by` - 创建人用户名
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的组织记录
+pub fn insert_organization(
+    input: &OrganizationInput,
+    created_by: &str,
+    now_millis: i64,
+) -> Result<OrganizationRecord, AppError> {
+    let organization_id = db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query_scalar(
+            r"
+            INSERT INTO organizations (
+              parent_id, name, org_type, contact_name, contact_phone, contract_number, remark,
+              created_at, updated_at, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8, $9)
+            RETURNING id
+            ",
+        )
+        .bind(input.parent_id)
+        .bind(&input.name)
+        .bind(&input.org_type)
+        .bind(&input.contact_name)
+        .bind(&input.contact_phone)
+        .bind(&input.contract_number)
+        .bind(&input.remark)
+        .bind(now_millis)
+        .bind(created_by)
+        .fetch_one(&mut connection)
+        .await
+        .map_err(|err| map_organization_mutation_error(&err))
+    })?;
+    find_organization(organization_id)?
+        .ok_or_else(|| AppError::Database("created organization not found".to_string()))
+}
+
+/// 更新组织
+///
+/// # 参数
+/// * `organization_id` - 组织 ID
+/// * `input` - 组织写入参数
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新后的组织记录（组织不存在时为 None）
+pub fn update_organization(
+    organization_id: i64,
+    input: &OrganizationInput,
+    now_millis: i64,
+) -> Result<Option<OrganizationRecord>, AppError> {
+    let updated = db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        query(
+            r"
+            UPDATE organizations
+            SET parent_id = $2, name = $3, org_type = $4, contact_name = $5, contact_phone = $6,
+                contract_number = $7, remark = $8, updated_at = $9
+            WHERE id = $1
+            ",
+        )
+        .bind(organization_id)
+        .bind(input.parent_id)
+        .bind(&input.name)
+        .bind(&input.org_type)
+        .bind(&input.contact_name)
+        .bind(&input.contact_phone)
+        .bind(&input.contract_number)
+        .bind(&input.remark)
+        .bind(now_millis)
+        .execute(&mut connection)
+        .await
+        .map(|result| result.rows_affected() > 0)
+        .map_err(|err| map_organization_mutation_error(&err))
+    })?;
+    if updated {
+        find_organization(organization_id)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 删除组织
+///
+/// 仅允许删除没有下级组织、也没有任何成员（含已软删除用户）的组织
+///
+/// # 参数
+/// * `organization_id` - 组织 ID
+///
+/// # 返回
+/// * 是否删除成功（组织不存在时为 false）
+pub fn delete_organization(organization_id: i64) -> Result<bool, AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        // 锁定目标组织，防止并发挂载下级组织或成员
+        let exists: Option<i64> =
+            query_scalar("SELECT id FROM organizations WHERE id = $1 FOR UPDATE")
+                .bind(organization_id)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+
+        let child_count: i64 =
+            query_scalar("SELECT COUNT(*) FROM organizations WHERE parent_id = $1")
+                .bind(organization_id)
+                .fetch_one(&mut *transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        if child_count > 0 {
+            return Err(AppError::Validation(
+                "organization has child organizations".to_string(),
+            ));
+        }
+
+        let member_count: i64 =
+            query_scalar("SELECT COUNT(*) FROM users WHERE organization_id = $1")
+                .bind(organization_id)
+                .fetch_one(&mut *transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        if member_count > 0 {
+            return Err(AppError::Validation("organization has members".to_string()));
+        }
+
+        query("DELETE FROM organizations WHERE id = $1")
+            .bind(organization_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(true)
+    })
+}
+
+/// 批量设置用户所属组织
+///
+/// 单事务执行：任一用户不存在或已软删除时整体回滚
+///
+/// # 参数
+/// * `organization_id` - 目标组织 ID（None 表示移出组织）
+/// * `user_ids` - 去重后的用户 ID 列表
+/// * `now_millis` - 当前时间戳（毫秒）
+pub fn assign_users(
+    organization_id: Option<i64>,
+    user_ids: &[i64],
+    now_millis: i64,
+) -> Result<(), AppError> {
+    db::block_on(async move {
+        let mut connection = db::connect_async().await?;
+        let mut transaction = sqlx::Connection::begin(&mut connection)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+
+        let updated = query(
+            r"
+            UPDATE users
+            SET organization_id = $1, updated_at = $2
+            WHERE id = ANY($3) AND deleted_at IS NULL
+            ",
+        )
+        .bind(organization_id)
+        .bind(now_millis)
+        .bind(user_ids)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| map_organization_mutation_error(&err))?
+        .rows_affected();
+        if usize::try_from(updated).unwrap_or(usize::MAX) != user_ids.len() {
+            // 未提交的事务在释放时自动回滚
+            return Err(AppError::Validation("user not found".to_string()));
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))
+    })
+}
+
+/// 将组织查询的一行转换为组织记录
+fn map_organization_row(row: &PgRow) -> Result<OrganizationRecord, AppError> {
+    Ok(OrganizationRecord {
+        id: row
+            .try_get(0)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        parent_id: row
+            .try_get(1)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        name: row
+            .try_get(2)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        org_type: row
+            .try_get(3)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        contact_name: row
+            .try_get(4)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        contact_phone: row
+            .try_get(5)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        contract_number: row
+            .try_get(6)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        remark: row
+            .try_get(7)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_at: row
+            .try_get(8)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        updated_at: row
+            .try_get(9)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        created_by: row
+            .try_get(10)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+        member_count: row
+            .try_get(11)
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    })
+}
+
+/// 将组织写入错误转换为业务错误（同级重名、上级组织不存在）
+fn map_organization_mutation_error(err: &sqlx::Error) -> AppError {
+    let message = err.to_string();
+    if message.contains("idx_organizations_parent_name") {
+        return AppError::Validation("organization name already exists".to_string());
+    }
+    if message.contains("organizations_parent_id_fkey") {
+        return AppError::Validation("parent organization not found".to_string());
+    }
+    if message.contains("users_organization_id_fkey") {
+        return AppError::Validation("organization not found".to_string());
+    }
+    AppError::Database(message)
+}
@@ -0,0 +1,456 @@
+//! 组织架构模块业务逻辑层
+//!
+//! 本模块负责：
+//! - 组织树的组装与增删改（同级重名、环路、非空删除校验）
+//! - 用户所属组织的批量设置
+//! - 组织管理操作的权限校验（`organization:view` / `organization:manage`）与审计记录
+
+// 引入哈希映射（按上级组织分组）
+use std::collections::HashMap;
+
+// 引入 JSON 值类型
+use serde_json::{Value, json};
+
+// 引入审计模型与服务
+use crate::audit::services::{self as audit_services, CommandAudit};
+// 引入权限模块
+use crate::auth::rbac;
+// 引入应用错误类型
+use crate::core::error::AppError;
+// 引入组织模型
+use crate::organization::models::{
+    OrganizationAssignUsersData, OrganizationAssignUsersPayload, OrganizationCreatePayload,
+    OrganizationData, OrganizationDeletePayload, OrganizationInput, OrganizationListPayload,
+    OrganizationNodeData, OrganizationRecord, OrganizationUpdatePayload,
+};
+// 引入组织仓储模块
+use crate::organization::repository;
+
+// 审计目标类型：组织
+const TARGET_TYPE_ORGANIZATION: &str = "organization";
+
+// 组织类型最大长度
+const MAX_ORG_TYPE_LENGTH: usize = 32;
+
+/// 查询组织树
+///
+/// # 参数
+/// * `payload` - 包含操作员用户名的请求体
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 根组织列表，每个节点递归包含下级组织
+pub fn list_organization_tree(
+    payload: &OrganizationListPayload,
+    now_millis: u64,
+) -> Result<Vec<OrganizationNodeData>, AppError> {
+    assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_VIEW,
+        "forbidden: organization view required",
+        now_millis,
+    )?;
+    Ok(build_tree(repository::list_organizations()?))
+}
+
+/// 创建组织
+///
+/// # 参数
+/// * `payload` - 创建请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 新建的组织
+pub fn create_organization(
+    payload: OrganizationCreatePayload,
+    now_millis: u64,
+) -> Result<OrganizationData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let result = create_organization_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "organization_create",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_ORGANIZATION,
+            target_id: result.as_ref().ok().map(|data| data.id.to_string()),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 更新组织（含移动到其他上级组织）
+///
+/// # 参数
+/// * `payload` - 更新请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 更新后的组织
+pub fn update_organization(
+    payload: OrganizationUpdatePayload,
+    now_millis: u64,
+) -> Result<OrganizationData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let organization_id = payload.organization_id;
+    let before = find_snapshot(organization_id);
+    let result = update_organization_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().and_then(snapshot);
+    audit_services::record_command(
+        CommandAudit {
+            command: "organization_update",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_ORGANIZATION,
+            target_id: Some(organization_id.to_string()),
+        },
+        (before, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 删除组织
+///
+/// 存在下级组织或成员（含已软删除用户）时拒绝删除
+///
+/// # 参数
+/// * `payload` - 删除请求
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 删除成功返回 true
+pub fn delete_organization(
+    payload: &OrganizationDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    let before = find_snapshot(payload.organization_id);
+    let result = delete_organization_unaudited(payload, now_millis);
+    audit_services::record_command(
+        CommandAudit {
+            command: "organization_delete",
+            operator_username: payload.operator_username.trim(),
+            target_type: TARGET_TYPE_ORGANIZATION,
+            target_id: Some(payload.organization_id.to_string()),
+        },
+        (before, None),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+/// 批量设置用户所属组织
+///
+/// # 参数
+/// * `payload` - 目标组织（为空表示移出组织）与用户 ID 列表
+/// * `now_millis` - 当前时间戳（毫秒）
+///
+/// # 返回
+/// * 目标组织与已更新的用户 ID
+pub fn assign_users(
+    payload: OrganizationAssignUsersPayload,
+    now_millis: u64,
+) -> Result<OrganizationAssignUsersData, AppError> {
+    let operator_username = payload.operator_username.trim().to_string();
+    let organization_id = payload.organization_id;
+    let result = assign_users_unaudited(payload, now_millis);
+    let after = result.as_ref().ok().map(|data| {
+        json!({
+            "organizationId": data.organization_id,
+            "userIds": data.user_ids,
+        })
+    });
+    audit_services::record_command(
+        CommandAudit {
+            command: "organization_assign_users",
+            operator_username: &operator_username,
+            target_type: TARGET_TYPE_ORGANIZATION,
+            target_id: organization_id.map(|organization_id| organization_id.to_string()),
+        },
+        (None, after),
+        &result,
+        now_millis,
+    );
+    result
+}
+
+// 创建组织（不含审计记录）
+fn create_organization_unaudited(
+    payload: OrganizationCreatePayload,
+    now_millis: u64,
+) -> Result<OrganizationData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: organization manage required",
+        now_millis,
+    )?;
+    let input = normalize_input(
+        payload.parent_id,
+        &payload.name,
+        &payload.org_type,
+        [
+            payload.contact_name,
+            payload.contact_phone,
+            payload.contract_number,
+            payload.remark,
+        ],
+    )?;
+    if let Some(parent_id) = input.parent_id
+        && repository::find_organization(parent_id)?.is_none()
+    {
+        return Err(AppError::Validation(
+            "parent organization not found".to_string(),
+        ));
+    }
+    let record = repository::insert_organization(&input, payload.operator_username.trim(), now)?;
+    Ok(map_organization_record(record))
+}
+
+// 更新组织（不含审计记录）
+fn update_organization_unaudited(
+    payload: OrganizationUpdatePayload,
+    now_millis: u64,
+) -> Result<OrganizationData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: organization manage required",
+        now_millis,
+    )?;
+    if payload.organization_id <= 0 {
+        return Err(AppError::Validation(
+            "organizationId is required".to_string(),
+        ));
+    }
+    let input = normalize_input(
+        payload.parent_id,
+        &payload.name,
+        &payload.org_type,
+        [
+            payload.contact_name,
+            payload.contact_phone,
+            payload.contract_number,
+            payload.remark,
+        ],
+    )?;
+
+    // 不能把组织移动到自身或其下级组织之下，否则会形成环
+    let subtree = repository::find_subtree_ids(payload.organization_id)?;
+    if subtree.is_empty() {
+        return Err(AppError::Validation("organization not found".to_string()));
+    }
+    if let Some(parent_id) = input.parent_id {
+        if subtree.contains(&parent_id) {
+            return Err(AppError::Validation(
+                "parentId cannot be the organization itself or its descendant".to_string(),
+            ));
+        }
+        if repository::find_organization(parent_id)?.is_none() {
+            return Err(AppError::Validation(
+                "parent organization not found".to_string(),
+            ));
+        }
+    }
+
+    repository::update_organization(payload.organization_id, &input, now)?
+        .map(map_organization_record)
+        .ok_or_else(|| AppError::Validation("organization not found".to_string()))
+}
+
+// 删除组织（不含审计记录）
+fn delete_organization_unaudited(
+    payload: &OrganizationDeletePayload,
+    now_millis: u64,
+) -> Result<bool, AppError> {
+    assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: organization manage required",
+        now_millis,
+    )?;
+    if payload.organization_id <= 0 {
+        return Err(AppError::Validation(
+            "organizationId is required".to_string(),
+        ));
+    }
+    if !repository::delete_organization(payload.organization_id)? {
+        return Err(AppError::Validation("organization not found".to_string()));
+    }
+    Ok(true)
+}
+
+// 批量设置用户所属组织（不含审计记录）
+fn assign_users_unaudited(
+    payload: OrganizationAssignUsersPayload,
+    now_millis: u64,
+) -> Result<OrganizationAssignUsersData, AppError> {
+    let now = assert_operator_allowed(
+        &payload.operator_username,
+        rbac::ACTION_MANAGE,
+        "forbidden: organization manage required",
+        now_millis,
+    )?;
+    let mut user_ids = payload.user_ids;
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    if user_ids.is_empty() || user_ids.iter().any(|user_id| *user_id <= 0) {
+        return Err(AppError::Validation("userIds is required".to_string()));
+    }
+    if let Some(organization_id) = payload.organization_id
+        && repository::find_organization(organization_id)?.is_none()
+    {
+        return Err(AppError::Validation("organization not found".to_string()));
+    }
+    repository::assign_users(payload.organization_id, &user_ids, now)?;
+    Ok(OrganizationAssignUsersData {
+        organization_id: payload.organization_id,
+        user_ids,
+    })
+}
+
+/// 验证操作员是否具有组织权限
+///
+/// # 返回
+/// * 转换为 i64 的当前时间戳（毫秒）
+fn assert_operator_allowed(
+    operator_username: &str,
+    action: &str,
+    forbidden_message: &str,
+    now_millis: u64,
+) -> Result<i64, AppError> {
+    let operator_username = operator_username.trim();
+    if operator_username.is_empty() {
+        return Err(AppError::Validation(
+            "operatorUsername is required".to_string(),
+        ));
+    }
+    let now_millis = i64::try_from(now_millis)
+        .map_err(|_| AppError::Validation("invalid current timestamp".to_string()))?;
+    rbac::ensure_user_allowed(
+        operator_username,
+        rbac::RESOURCE_ORGANIZATION,
+        action,
+        now_millis,
+        forbidden_message,
+    )?;
+    Ok(now_millis)
+}
+
+/// 校验并规范化组织写入参数
+///
+/// 可选字段依次为联系人、联系电话、合同编号、备注，去除空白后空串视为未设置
+fn normalize_input(
+    parent_id: Option<i64>,
+    name: &str,
+    org_type: &str,
+    [contact_name, contact_phone, contract_number, remark]: [Option<String>; 4],
+) -> Result<OrganizationInput, AppError> {
+    if parent_id.is_some_and(|parent_id| parent_id <= 0) {
+        return Err(AppError::Validation("invalid parentId".to_string()));
+    }
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".to_string()));
+    }
+    let org_type = org_type.trim().to_lowercase();
+    if org_type.is_empty() {
+        return Err(AppError::Validation("orgType is required".to_string()));
+    }
+    if org_type.len() > MAX_ORG_TYPE_LENGTH
+        || !org_type
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(AppError::Validation(
+            "orgType must be at most 32 letters, digits, '_' or '-'".to_string(),
+        ));
+    }
+    Ok(OrganizationInput {
+        parent_id,
+        name,
+        org_type,
+        contact_name: normalize_optional(contact_name),
+        contact_phone: normalize_optional(contact_phone),
+        contract_number: normalize_optional(contract_number),
+        remark: normalize_optional(remark),
+    })
+}
+
+/// 规范化可选文本（去除空白，空串视为未设置）
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 将扁平的组织记录组装为树（同级按名称排序）
+fn build_tree(records: Vec<OrganizationRecord>) -> Vec<OrganizationNodeData> {
+    let mut children_by_parent: HashMap<Option<i64>, Vec<OrganizationRecord>> = HashMap::new();
+    for record in records {
+        children_by_parent
+            .entry(record.parent_id)
+            .or_default()
+            .push(record);
+    }
+    attach_children(None, &mut children_by_parent)
+}
+
+/// 递归取出指定上级组织的下级节点
+fn attach_children(
+    parent_id: Option<i64>,
+    children_by_parent: &mut HashMap<Option<i64>, Vec<OrganizationRecord>>,
+) -> Vec<OrganizationNodeData> {
+    let mut records = children_by_parent.remove(&parent_id).unwrap_or_default();
+    records.sort_by(|left, right| left.name.cmp(&right.name).then(left.id.cmp(&right.id)));
+    records
+        .into_iter()
+        .map(|record| {
+            let children = attach_children(Some(record.id), children_by_parent);
+            OrganizationNodeData {
+                organization: map_organization_record(record),
+                children,
+            }
+        })
+        .collect()
+}
+
+/// 将组织记录转换为响应格式
+fn map_organization_record(record: OrganizationRecord) -> OrganizationData {
+    OrganizationData {
+        id: record.id,
+        parent_id: record.parent_id,
+        name: record.name,
+        org_type: record.org_type,
+        contact_name: record.contact_name,
+        contact_phone: record.contact_phone,
+        contract_number: record.contract_number,
+        remark: record.remark,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        created_by: record.created_by,
+        member_count: record.member_count,
+    }
+}
+
+/// 查询组织当前快照（审计操作前内容，查询失败时不记录快照）
+fn find_snapshot(organization_id: i64) -> Option<Value> {
+    if organization_id <= 0 {
+        return None;
+    }
+    repository::find_organization(organization_id)
+        .ok()
+        .flatten()
+        .map(map_organization_record)
+        .as_ref()
+        .and_then(snapshot)
+}
+
+/// 将组织响应数据序列化为审计快照
+fn snapshot(data: &OrganizationData) -> Option<Value> {
+    serde_json::to_value(data).ok()
+}
@@ -142,8 +142,9 @@ export type AdminChangeUserPasswordResult = {
 export type UserDeviceScopeGetResult = {
   success: boolean;
   data: {
-    implemented: boolean;
-    message: string;
+    userId: number;
+    updatedAt?: number;
+    updatedBy?: string;
     scope: {
       allAreas: boolean;
       allFloors: boolean;
@@ -235,17 +236,21 @@ export const adminChangeUserPassword = (
   );
 };
 
-export const getUserDeviceScope = (userId: number) => {
+export const getUserDeviceScope = (
+  operatorUsername: string,
+  userId: number
+) => {
   return invokeWithTrace<UserDeviceScopeGetResult>(
     "getUserDeviceScope",
     "user_device_scope_get",
     {
-      payload: { userId }
+      payload: { operatorUsername, userId }
     }
   );
 };
 
 export const upsertUserDeviceScope = (payload: {
+  operatorUsername: string;
   userId: number;
   allAreas: boolean;
   allFloors: boolean;
@@ -254,7 +259,11 @@ export const upsertUserDeviceScope = (payload: {
   floors: string[];
   devices: string[];
 }) => {
-  return invokeWithTrace("upsertUserDeviceScope", "user_device_scope_upsert", {
-    payload
-  });
+  return invokeWithTrace<UserDeviceScopeGetResult>(
+    "upsertUserDeviceScope",
+    "user_device_scope_upsert",
+    {
+      payload
+    }
+  );
 };